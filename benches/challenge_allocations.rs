@@ -0,0 +1,89 @@
+//! Counts the heap allocations of a Fiat-Shamir challenge and times it. Hashing over a digest streams the
+//! commitment into the transcript, so it allocates nothing; the hash functions chosen at runtime clone a boxed
+//! hasher and box its output, which allocates twice. Run it with `cargo bench --bench challenge_allocations`.
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    hint::black_box,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use criterion::{
+    criterion_group, criterion_main, measurement::WallTime, BenchmarkGroup, Criterion,
+};
+use rand::{rngs::StdRng, SeedableRng};
+use sigma_protocol_standard::{
+    protocols::{SchnorrDLOG, SchnorrInstance},
+    HashFunction, NIZK,
+};
+
+type G = ark_bls12_377::G1Projective;
+
+/// The runs over which the allocations are counted
+const RUNS: u32 = 1000;
+
+/// The system allocator, counting the allocations
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+/// The mean number of allocations of `f` over `RUNS` runs, after a warm-up run. They are counted apart from
+/// criterion, whose own allocations would be counted otherwise
+fn allocations<T>(mut f: impl FnMut() -> T) -> f64 {
+    black_box(f());
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    for _ in 0..RUNS {
+        black_box(f());
+    }
+    (ALLOCATIONS.load(Ordering::Relaxed) - before) as f64 / f64::from(RUNS)
+}
+
+/// Prints the allocations of `f` and times it in `benches`
+fn count_and_bench<T>(
+    benches: &mut BenchmarkGroup<WallTime>,
+    name: &str,
+    mut f: impl FnMut() -> T,
+) {
+    println!("{:<32} {:>6.2} allocations", name, allocations(&mut f));
+    benches.bench_function(name, |b| b.iter(&mut f));
+}
+
+fn bench_challenges(c: &mut Criterion) {
+    let rng = &mut StdRng::seed_from_u64(0);
+    let (instance, witness) = SchnorrInstance::<G>::generate(rng);
+    let nizk = NIZK::<SchnorrDLOG<G>, blake2::Blake2s>::new(&instance, b"bench");
+    let dyn_nizk =
+        NIZK::<SchnorrDLOG<G>, _>::with_hash_function(&instance, b"bench", HashFunction::SHA256)
+            .unwrap();
+    let proof = nizk.batchable_proof(&witness, None, rng).unwrap();
+    let dyn_proof = dyn_nizk.batchable_proof(&witness, None, rng).unwrap();
+
+    let mut benches = c.benchmark_group("challenge_allocations");
+    count_and_bench(&mut benches, "Blake2s, no message", || {
+        nizk.batchable_challenge(black_box(&proof), None)
+    });
+    count_and_bench(&mut benches, "Blake2s, message", || {
+        nizk.batchable_challenge(black_box(&proof), Some(b"message"))
+    });
+    count_and_bench(&mut benches, "SHA-256 at runtime, no message", || {
+        dyn_nizk.batchable_challenge(black_box(&dyn_proof), None)
+    });
+    benches.finish();
+}
+
+criterion_group!(benches, bench_challenges);
+criterion_main!(benches);
@@ -0,0 +1,34 @@
+//! Times the constant-time comparison of a recomputed challenge with the challenge of a short proof, for a
+//! mismatch in the first byte and in the last one. Only the comparison is timed, so that the scalar
+//! multiplications of a verification do not hide a difference. Run it with
+//! `cargo bench --bench challenge_comparison`.
+
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use sigma_protocol_standard::{Challenge, CHALLENGE_LENGTH};
+use subtle::ConstantTimeEq;
+
+fn bench_comparison(c: &mut Criterion) {
+    let challenge: Challenge = [0x5a; CHALLENGE_LENGTH];
+    let mismatch = |position: usize| {
+        let mut forged = challenge;
+        forged[position] ^= 1;
+        forged
+    };
+
+    let mut benches = c.benchmark_group("challenge_comparison");
+    for (name, other) in [
+        ("equal", challenge),
+        ("mismatch in the first byte", mismatch(0)),
+        ("mismatch in the last byte", mismatch(CHALLENGE_LENGTH - 1)),
+    ] {
+        benches.bench_function(name, |b| {
+            b.iter(|| bool::from(black_box(&challenge[..]).ct_eq(black_box(&other[..]))))
+        });
+    }
+    benches.finish();
+}
+
+criterion_group!(benches, bench_comparison);
+criterion_main!(benches);
@@ -0,0 +1,70 @@
+//! Compares proving and verifying many Schnorr proofs one after another with `NIZK::prove_all` and
+//! `NIZK::verify_all` on rayon thread pools of 1, 2, 4 and 8 threads. Run it with
+//! `cargo bench --bench parallel --features parallel`. Pools of more threads than cores measure the overhead of
+//! the pool rather than its scaling, so the scaling is only shown on 4 or more cores.
+
+use std::time::Duration;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use rand::{rngs::StdRng, SeedableRng};
+use rayon::ThreadPoolBuilder;
+use sigma_protocol_standard::{
+    protocols::{SchnorrDLOG, SchnorrInstance},
+    NIZK,
+};
+
+type G = ark_bls12_377::G1Projective;
+
+const PROOFS: usize = 1000;
+
+fn bench_parallel(c: &mut Criterion) {
+    let (instance, witness) = SchnorrInstance::<G>::generate(&mut StdRng::seed_from_u64(0));
+    let nizk = NIZK::<SchnorrDLOG<G>, blake2::Blake2s>::new(&instance, b"bench");
+    let witnesses_and_messages = vec![(witness, None); PROOFS];
+    let proofs: Vec<_> = nizk
+        .prove_all(&witnesses_and_messages, [0; 32])
+        .unwrap()
+        .into_iter()
+        .map(|proof| (proof, None))
+        .collect();
+
+    let mut benches = c.benchmark_group("parallel");
+    // A run proves or verifies all the proofs, which takes a few hundred milliseconds on one core
+    benches
+        .sample_size(10)
+        .measurement_time(Duration::from_secs(10))
+        .throughput(Throughput::Elements(PROOFS as u64));
+    benches.bench_function("sequential proving", |b| {
+        b.iter(|| {
+            let rng = &mut StdRng::seed_from_u64(0);
+            witnesses_and_messages
+                .iter()
+                .map(|(witness, message)| nizk.batchable_proof(witness, *message, rng).unwrap())
+                .collect::<Vec<_>>()
+        })
+    });
+    benches.bench_function("sequential verification", |b| {
+        b.iter(|| {
+            proofs
+                .iter()
+                .map(|(proof, message)| nizk.batchable_verify(proof, *message))
+                .collect::<Vec<_>>()
+        })
+    });
+    for threads in [1, 2, 4, 8] {
+        let pool = ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .build()
+            .unwrap();
+        benches.bench_with_input(BenchmarkId::new("prove_all", threads), &pool, |b, pool| {
+            b.iter(|| pool.install(|| nizk.prove_all(&witnesses_and_messages, [0; 32]).unwrap()))
+        });
+        benches.bench_with_input(BenchmarkId::new("verify_all", threads), &pool, |b, pool| {
+            b.iter(|| pool.install(|| nizk.verify_all(&proofs)))
+        });
+    }
+    benches.finish();
+}
+
+criterion_group!(benches, bench_parallel);
+criterion_main!(benches);
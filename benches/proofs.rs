@@ -0,0 +1,113 @@
+//! The baseline of the Schnorr NIZK that optimizations are measured against: proving and verifying batchable and
+//! short proofs, the challenge derivation alone, batch verification of 10, 100 and 1000 proofs and encoding round
+//! trips, over BLS12-377 and, with their features, ristretto255 and secp256k1. Every input is drawn from a fixed
+//! seed. Run it with `cargo bench --bench proofs --features ristretto,secp256k1`, and compare against a saved
+//! baseline with `-- --save-baseline before` and `-- --baseline before`.
+
+use std::hint::black_box;
+
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use rand::{rngs::StdRng, SeedableRng};
+use sigma_protocol_standard::{
+    protocols::{SchnorrDLOG, SchnorrInstance},
+    BatchVerifiable, BatchableProof, ShortProof, SigmaGroup, SigmaProtocol, NIZK,
+};
+
+type Hash = blake2::Blake2s;
+
+const SEED: u64 = 0;
+const CONTEXT: &[u8] = b"zkpstd/sigma/bench";
+const MESSAGE: &[u8] = b"message";
+const BATCH_SIZES: [usize; 3] = [10, 100, 1000];
+
+/// The canonical encoding of `value`
+fn encode<X: CanonicalSerialize>(value: &X) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    value.serialize(&mut bytes).unwrap();
+    bytes
+}
+
+fn bench_proofs<G: SigmaGroup>(c: &mut Criterion, group: &str) {
+    let rng = &mut StdRng::seed_from_u64(SEED);
+    let (instance, witness) = SchnorrInstance::<G>::generate(rng);
+    let nizk = NIZK::<SchnorrDLOG<G>, Hash>::new(&instance, CONTEXT);
+    let batchable = nizk.batchable_proof(&witness, Some(MESSAGE), rng).unwrap();
+    let short = nizk.short_proof(&witness, Some(MESSAGE), rng).unwrap();
+
+    let mut benches = c.benchmark_group(format!("schnorr/{}", group));
+    benches.throughput(Throughput::Elements(1));
+    benches.bench_function("batchable_proof", |b| {
+        b.iter(|| nizk.batchable_proof(&witness, Some(MESSAGE), rng))
+    });
+    benches.bench_function("short_proof", |b| {
+        b.iter(|| nizk.short_proof(&witness, Some(MESSAGE), rng))
+    });
+    benches.bench_function("batchable_verify", |b| {
+        b.iter(|| nizk.batchable_verify(black_box(&batchable), Some(MESSAGE)))
+    });
+    benches.bench_function("short_verify", |b| {
+        b.iter(|| nizk.short_verify(black_box(&short), Some(MESSAGE)))
+    });
+    benches.bench_function("batchable_challenge", |b| {
+        b.iter(|| nizk.batchable_challenge(black_box(&batchable), Some(MESSAGE)))
+    });
+
+    benches.throughput(Throughput::Bytes(encode(&batchable).len() as u64));
+    benches.bench_function("batchable round trip", |b| {
+        b.iter(|| BatchableProof::<SchnorrDLOG<G>>::deserialize(&encode(black_box(&batchable))[..]))
+    });
+    benches.throughput(Throughput::Bytes(encode(&short).len() as u64));
+    benches.bench_function("short round trip", |b| {
+        b.iter(|| ShortProof::<SchnorrDLOG<G>>::deserialize(&encode(black_box(&short))[..]))
+    });
+    benches.finish();
+}
+
+/// Batch verification, over the groups whose Schnorr proofs are batch verifiable
+fn bench_batches<G: SigmaGroup>(c: &mut Criterion, group: &str)
+where
+    SchnorrDLOG<G>:
+        BatchVerifiable + SigmaProtocol<Instance = SchnorrInstance<G>, Witness = G::Scalar>,
+{
+    let rng = &mut StdRng::seed_from_u64(SEED);
+    let (instance, witness) = SchnorrInstance::<G>::generate(rng);
+    let nizk = NIZK::<SchnorrDLOG<G>, Hash>::new(&instance, CONTEXT);
+
+    let mut benches = c.benchmark_group(format!("batch_verify/{}", group));
+    for size in BATCH_SIZES {
+        let proofs: Vec<_> = (0..size)
+            .map(|_| {
+                let proof = nizk.batchable_proof(&witness, Some(MESSAGE), rng).unwrap();
+                (proof, Some(MESSAGE))
+            })
+            .collect();
+        benches.throughput(Throughput::Elements(size as u64));
+        benches.bench_with_input(BenchmarkId::from_parameter(size), &proofs, |b, proofs| {
+            b.iter(|| nizk.batch_verify(proofs, rng))
+        });
+    }
+    benches.finish();
+}
+
+fn bench_groups(c: &mut Criterion) {
+    bench_proofs::<ark_bls12_377::G1Projective>(c, "BLS12-377 G1");
+    bench_batches::<ark_bls12_377::G1Projective>(c, "BLS12-377 G1");
+
+    #[cfg(feature = "ristretto")]
+    {
+        use sigma_protocol_standard::ristretto::RistrettoElement;
+        bench_proofs::<RistrettoElement>(c, "ristretto255");
+        bench_batches::<RistrettoElement>(c, "ristretto255");
+    }
+
+    #[cfg(feature = "secp256k1")]
+    {
+        use sigma_protocol_standard::secp256k1::Secp256k1Element;
+        bench_proofs::<Secp256k1Element>(c, "secp256k1");
+        bench_batches::<Secp256k1Element>(c, "secp256k1");
+    }
+}
+
+criterion_group!(benches, bench_groups);
+criterion_main!(benches);
@@ -0,0 +1,50 @@
+//! Compares proving and verifying over the same Schnorr instance with and without precomputed fixed-base tables.
+//! Run it with `cargo bench --bench schnorr_precomputation`.
+
+use std::{hint::black_box, sync::Arc};
+
+use ark_ec::ProjectiveCurve;
+use criterion::{criterion_group, criterion_main, Criterion};
+use rand::{rngs::StdRng, SeedableRng};
+use sigma_protocol_standard::{
+    protocols::{SchnorrDLOG, SchnorrInstance},
+    FixedBaseTable, SigmaProtocol, NIZK,
+};
+
+type G = ark_bls12_377::G1Projective;
+type Nizk = NIZK<SchnorrDLOG<G>, blake2::Blake2s>;
+
+fn bench_precomputation(c: &mut Criterion) {
+    let rng = &mut StdRng::seed_from_u64(0);
+    let generator = G::prime_subgroup_generator();
+    let (instance, witness) = SchnorrInstance::<G>::generate(rng);
+
+    let plain = Nizk::new(&instance, b"bench");
+    let precomputed =
+        Nizk::from_protocol(SchnorrDLOG::new(&instance).with_precomputation(), b"bench").unwrap();
+    let table = Arc::new(FixedBaseTable::new(generator));
+    let shared =
+        Nizk::from_protocol(SchnorrDLOG::new(&instance).with_base_table(table), b"bench").unwrap();
+    let proof = plain.batchable_proof(&witness, None, rng).unwrap();
+
+    let mut benches = c.benchmark_group("schnorr_precomputation");
+    for (name, nizk) in [
+        ("plain", &plain),
+        ("precomputed", &precomputed),
+        ("shared base table", &shared),
+    ] {
+        benches.bench_function(format!("prove, {}", name), |b| {
+            b.iter(|| nizk.batchable_proof(&witness, None, rng))
+        });
+        benches.bench_function(format!("verify, {}", name), |b| {
+            b.iter(|| nizk.batchable_verify(black_box(&proof), None))
+        });
+    }
+    benches.bench_function("build both tables", |b| {
+        b.iter(|| SchnorrDLOG::new(black_box(&instance)).with_precomputation())
+    });
+    benches.finish();
+}
+
+criterion_group!(benches, bench_precomputation);
+criterion_main!(benches);
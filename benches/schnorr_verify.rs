@@ -0,0 +1,82 @@
+//! Compares the two scalar multiplications the Schnorr verifier used to compute with the multi-scalar
+//! multiplication it computes now, times a whole verification, and times the batch check of 64 transcripts with
+//! 128-bit and full-width weights. Run it with `cargo bench --bench schnorr_verify`.
+
+use std::hint::black_box;
+
+use ark_ec::ProjectiveCurve;
+use ark_ff::{PrimeField, UniformRand};
+use criterion::{criterion_group, criterion_main, Criterion};
+use rand::{rngs::StdRng, SeedableRng};
+use sigma_protocol_standard::{
+    protocols::{SchnorrDLOG, SchnorrInstance},
+    BatchRandomizers, BatchVerifiable, ChallengeExpansion, CommitmentRecoverable, SigmaGroup,
+    SigmaProtocol, NIZK,
+};
+
+type G = ark_bls12_377::G1Projective;
+type F = ark_bls12_377::Fr;
+
+fn bench_verify(c: &mut Criterion) {
+    let rng = &mut StdRng::seed_from_u64(0);
+    let base = G::rand(rng);
+    let witness = F::rand(rng);
+    let claim = base.mul(witness.into_repr());
+    let (response, challenge) = (F::rand(rng), F::rand(rng));
+
+    let nizk =
+        NIZK::<SchnorrDLOG<G>, blake2::Blake2s>::new(&SchnorrInstance::new(base, claim), b"bench");
+    let proof = nizk.batchable_proof(&witness, None, rng).unwrap();
+
+    let mut benches = c.benchmark_group("schnorr_verify");
+    benches.bench_function("two scalar multiplications", |b| {
+        b.iter(|| {
+            black_box(base).mul(response.into_repr()) + black_box(claim).mul(challenge.into_repr())
+        })
+    });
+    benches.bench_function("multi-scalar multiplication", |b| {
+        b.iter(|| G::multi_scalar_mul(black_box(&[base, claim]), &[response, challenge]))
+    });
+    benches.bench_function("batchable_verify", |b| {
+        b.iter(|| nizk.batchable_verify(black_box(&proof), None))
+    });
+    benches.finish();
+}
+
+fn bench_batch_verifier(c: &mut Criterion) {
+    let rng = &mut StdRng::seed_from_u64(0);
+    let instance = SchnorrInstance::new(G::rand(rng), G::rand(rng));
+    let protocol = SchnorrDLOG::new(&instance);
+    let transcripts: Vec<_> = (0..64)
+        .map(|_| {
+            let (commitment, challenge, response) = protocol
+                .simulate_transcript(ChallengeExpansion::default(), rng)
+                .unwrap();
+            let challenge =
+                SchnorrDLOG::<G>::challenge_from_bytes(&challenge, ChallengeExpansion::default())
+                    .unwrap();
+            (commitment, challenge, response)
+        })
+        .collect();
+    let transcripts: Vec<_> = transcripts
+        .iter()
+        .map(|(commitment, challenge, response)| (commitment, *challenge, response))
+        .collect();
+
+    let mut benches = c.benchmark_group("schnorr_batch_verifier");
+    for bits in [BatchRandomizers::DEFAULT_BITS, F::size_in_bits() as u32] {
+        let protocol =
+            SchnorrDLOG::new(&instance).with_batch_randomizers(BatchRandomizers::with_bits(bits));
+        benches.bench_function(format!("{bits}-bit weights"), |b| {
+            b.iter(|| {
+                protocol
+                    .batch_verifier(black_box(&transcripts), rng)
+                    .unwrap()
+            })
+        });
+    }
+    benches.finish();
+}
+
+criterion_group!(benches, bench_verify, bench_batch_verifier);
+criterion_main!(benches);
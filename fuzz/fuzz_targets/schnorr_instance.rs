@@ -0,0 +1,11 @@
+//! Decodes arbitrary bytes as a Schnorr instance over BLS12-377 followed by a batchable proof, and verifies the
+//! proof under that instance, which must return an error rather than panic for every input
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use sigma_protocol_standard::testing::fuzz;
+
+fuzz_target!(|data: &[u8]| {
+    let _ = fuzz::schnorr_instance::<ark_bls12_377::G1Projective, blake2::Blake2s>(data);
+});
@@ -0,0 +1,11 @@
+//! Decodes arbitrary bytes as a short Schnorr proof over BLS12-377 and verifies it, which must return an
+//! error rather than panic for every input
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use sigma_protocol_standard::testing::fuzz;
+
+fuzz_target!(|data: &[u8]| {
+    let _ = fuzz::short_proof::<ark_bls12_377::G1Projective, blake2::Blake2s>(data);
+});
@@ -0,0 +1,166 @@
+/*
+ * The C ABI of sigma_protocol_standard, built with `cargo build --release --features ffi` into the cdylib
+ * target/release/libsigma_protocol_standard.so (.dylib, .dll). It verifies batchable Schnorr proofs over
+ * ristretto255 with Blake2s, see src/ffi.rs. Laid out as cbindgen outputs it with cbindgen.toml, and checked
+ * against the Rust declarations by the tests of the ffi feature.
+ */
+
+#ifndef SIGMA_PROTOCOL_STANDARD_H
+#define SIGMA_PROTOCOL_STANDARD_H
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/**
+ * The length of the canonical encoding of a witness, a ristretto255 scalar
+ */
+#define SIGMA_SCALAR_LENGTH 32
+
+/**
+ * The length of the canonical encoding of an instance, two compressed ristretto255 elements
+ */
+#define SIGMA_INSTANCE_LENGTH 64
+
+/**
+ * The length of the compressed encoding of a batchable proof, a commitment and a response
+ */
+#define SIGMA_PROOF_LENGTH 64
+
+/**
+ * The length of a Fiat-Shamir challenge
+ */
+#define SIGMA_CHALLENGE_LENGTH 32
+
+/**
+ * The length of the seeds the functions draw their randomness from
+ */
+#define SIGMA_SEED_LENGTH 32
+
+/**
+ * The result codes of the C functions: zero for success, and one code per `SigmaError` variant, followed by
+ * the failures of the boundary itself
+ */
+typedef enum SigmaErrorCode {
+  /**
+   * The call succeeded, or the proof is valid
+   */
+  SIGMA_OK = 0,
+  SIGMA_VERIFICATION_FAILED = 1,
+  SIGMA_CHALLENGE_CONVERSION_FAILURE = 2,
+  /**
+   * An encoding that does not decode
+   */
+  SIGMA_SERIALIZATION_ERROR = 3,
+  SIGMA_INVALID_INSTANCE = 4,
+  SIGMA_LENGTH_MISMATCH = 5,
+  SIGMA_COMMITMENT_NOT_RECOVERABLE = 6,
+  SIGMA_UNSUPPORTED_HASH_FUNCTION = 7,
+  SIGMA_DIGEST_TOO_SHORT = 8,
+  SIGMA_ENVELOPE_MISMATCH = 9,
+  /**
+   * A required pointer was null
+   */
+  SIGMA_NULL_POINTER = 10,
+  /**
+   * The call panicked, which is a bug of this library
+   */
+  SIGMA_PANIC = 11,
+  SIGMA_CHALLENGE_TOO_SHORT = 12,
+} SigmaErrorCode;
+
+/**
+ * A Schnorr NIZK over ristretto255 with Blake2s, opaque to C
+ */
+typedef struct SigmaNizk SigmaNizk;
+
+/**
+ * A Schnorr instance over ristretto255, opaque to C
+ */
+typedef struct SigmaSchnorrInstance SigmaSchnorrInstance;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * Samples a witness from the `SIGMA_SEED_LENGTH` bytes at `seed` and writes its canonical encoding, of
+ * `SIGMA_SCALAR_LENGTH` bytes, to `witness_out`, and the encoding of the instance over the standard generator, of
+ * `SIGMA_INSTANCE_LENGTH` bytes, to `instance_out`. The seed must be fresh randomness, as it determines the
+ * witness
+ */
+int32_t sigma_schnorr_keypair(const uint8_t *seed, uint8_t *witness_out, uint8_t *instance_out);
+
+/**
+ * Decodes a Schnorr instance from its canonical encoding, the compressed base followed by the compressed claim in
+ * 64 bytes, into `*out`, which must be released with `sigma_schnorr_instance_free`. Trailing bytes are rejected
+ * with `SIGMA_SERIALIZATION_ERROR`
+ */
+int32_t sigma_schnorr_instance_new(const uint8_t *bytes, size_t len, SigmaSchnorrInstance **out);
+
+/**
+ * Releases an instance of `sigma_schnorr_instance_new`. Null is ignored
+ */
+void sigma_schnorr_instance_free(SigmaSchnorrInstance *instance);
+
+/**
+ * Builds the NIZK for `instance` and the context `ctx` into `*out`, which must be released with
+ * `sigma_nizk_free`. An instance that proves nothing, as over the identity, is rejected with
+ * `SIGMA_INVALID_INSTANCE`
+ */
+int32_t sigma_nizk_new(const SigmaSchnorrInstance *instance,
+                       const uint8_t *ctx,
+                       size_t ctx_len,
+                       SigmaNizk **out);
+
+/**
+ * Releases a NIZK of `sigma_nizk_new`. Null is ignored
+ */
+void sigma_nizk_free(SigmaNizk *nizk);
+
+/**
+ * Verifies the compressed batchable proof of `proof_len` bytes at `proof` against the message of `msg_len` bytes
+ * at `msg`. A null `msg` stands for no message, which is not the same as an empty one. Returns `SIGMA_OK` for a
+ * valid proof and `SIGMA_VERIFICATION_FAILED` for an invalid one, and `SIGMA_SERIALIZATION_ERROR` for bytes
+ * that are not a proof
+ */
+int32_t sigma_batchable_verify(const SigmaNizk *nizk,
+                               const uint8_t *proof,
+                               size_t proof_len,
+                               const uint8_t *msg,
+                               size_t msg_len);
+
+/**
+ * Proves knowledge of the witness of `witness_len` bytes at `witness`, a canonical scalar, bound to the message
+ * at `msg` as in `sigma_batchable_verify`, and writes the compressed proof of `SIGMA_PROOF_LENGTH` bytes to
+ * `proof_out`. The commitment randomness is seeded with the `SIGMA_SEED_LENGTH` bytes at `seed`, which must be
+ * fresh randomness, together with the witness and the message. A witness that does not decode is rejected with
+ * `SIGMA_SERIALIZATION_ERROR`
+ */
+int32_t sigma_batchable_prove(const SigmaNizk *nizk,
+                              const uint8_t *witness,
+                              size_t witness_len,
+                              const uint8_t *msg,
+                              size_t msg_len,
+                              const uint8_t *seed,
+                              uint8_t *proof_out);
+
+/**
+ * Writes the Fiat-Shamir challenge of the compressed batchable proof at `proof` and the message at `msg`, of
+ * `SIGMA_CHALLENGE_LENGTH` bytes, to `challenge_out`, whether the proof is valid or not, for cross-checking
+ * transcripts with other implementations
+ */
+int32_t sigma_batchable_challenge(const SigmaNizk *nizk,
+                                  const uint8_t *proof,
+                                  size_t proof_len,
+                                  const uint8_t *msg,
+                                  size_t msg_len,
+                                  uint8_t *challenge_out);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* SIGMA_PROTOCOL_STANDARD_H */
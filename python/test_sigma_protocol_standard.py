@@ -0,0 +1,86 @@
+"""Tests of the pyo3 extension module, runnable with pytest or as a script once the module is importable.
+
+`cargo test --features python --test python_bindings` builds the module and runs them.
+"""
+
+from sigma_protocol_standard import CHALLENGE_LENGTH, INSTANCE_LENGTH, NIZK, PROOF_LENGTH, SchnorrInstance, SigmaError
+
+
+def test_prove_serialize_verify():
+    instance, witness = SchnorrInstance.generate()
+    proof = NIZK(instance, b"ctx").prove(witness, b"message")
+    assert isinstance(proof, bytes) and len(proof) == PROOF_LENGTH
+
+    # A verifier built from the serialized instance only
+    verifier = NIZK(SchnorrInstance(instance.to_bytes()), b"ctx")
+    assert verifier.verify(proof, b"message")
+    assert not verifier.verify(proof, b"another message")
+    assert not verifier.verify(proof, None)
+    assert not NIZK(instance, b"another ctx").verify(proof, b"message")
+
+
+def test_no_message_differs_from_empty_message():
+    instance, witness = SchnorrInstance.generate()
+    nizk = NIZK(instance, b"ctx")
+    proof = nizk.prove(witness)
+    assert nizk.verify(proof)
+    assert not nizk.verify(proof, b"")
+    assert nizk.challenge(proof) != nizk.challenge(proof, b"")
+
+
+def test_encodings_are_bytes():
+    instance, witness = SchnorrInstance.generate()
+    nizk = NIZK(instance, b"ctx")
+    proof = nizk.prove(witness, b"message")
+    challenge = nizk.challenge(proof, b"message")
+    assert isinstance(instance.to_bytes(), bytes) and len(instance.to_bytes()) == INSTANCE_LENGTH
+    assert isinstance(challenge, bytes) and len(challenge) == CHALLENGE_LENGTH
+    assert challenge != nizk.challenge(proof, b"another message")
+
+
+def test_seeded_instances_are_deterministic():
+    seed = bytes(range(32))
+    (first, first_witness), (second, second_witness) = SchnorrInstance.generate(seed), SchnorrInstance.generate(seed)
+    assert first.to_bytes() == second.to_bytes()
+    assert first_witness == second_witness
+
+    try:
+        SchnorrInstance.generate(seed[1:])
+    except ValueError:
+        pass
+    else:
+        raise AssertionError("expected a ValueError")
+
+
+def test_errors_raise_exceptions():
+    instance, witness = SchnorrInstance.generate()
+    nizk = NIZK(instance, b"ctx")
+    proof = nizk.prove(witness)
+
+    for call in [
+        lambda: nizk.verify(proof[1:]),
+        lambda: nizk.prove(witness + b"\0"),
+        lambda: SchnorrInstance(b"\xff" * 64),
+    ]:
+        try:
+            call()
+        except SigmaError as error:
+            assert isinstance(error, Exception)
+            assert error.name == "SERIALIZATION_ERROR"
+        else:
+            raise AssertionError("expected a SigmaError")
+
+    identity = bytes(32)
+    try:
+        NIZK(SchnorrInstance(identity + instance.to_bytes()[32:]), b"ctx")
+    except SigmaError as error:
+        assert error.name == "INVALID_INSTANCE"
+    else:
+        raise AssertionError("expected a SigmaError")
+
+
+if __name__ == "__main__":
+    for name, test in list(globals().items()):
+        if name.startswith("test_"):
+            test()
+            print(f"{name} ok")
@@ -0,0 +1,296 @@
+//! Command-line prover and verifier of Schnorr proofs over ristretto255, for testing implementations of the standard
+//! against each other through files.
+//!
+//! Every file holds a canonical byte encoding: a witness is a 32-byte scalar, an instance the compressed base and
+//! claim, and a proof the compressed batchable proof. Messages are raw bytes. The hash function is chosen by its name
+//! in the [`HashFunction`] registry, and must be the same for proving and verifying.
+//!
+//! The exit code is 0 for success and valid proofs, 1 for invalid proofs, 2 for usage errors, 3 for I/O errors and
+//! 4 for an instance or witness that does not decode or is rejected.
+
+use std::{
+    collections::HashMap,
+    env, fmt, fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    process::ExitCode,
+};
+
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use rand::thread_rng;
+use sigma_protocol_standard::{
+    protocols::{SchnorrDLOG, SchnorrInstance},
+    ristretto::{RistrettoElement, RistrettoScalar},
+    BatchableProof, DynTranscript, HashFunction, SigmaError, SigmaGroup, NIZK,
+};
+
+type Schnorr = SchnorrDLOG<RistrettoElement>;
+
+const USAGE: &str = "\
+usage:
+    sigma-cli keygen --witness <file> --instance <file>
+    sigma-cli prove --instance <file> --witness <file> --message <file> --out <file> [options]
+    sigma-cli verify --instance <file> --proof <file> --message <file> [options]
+    sigma-cli batch-verify --instance <file> --dir <directory> [options]
+
+options:
+    --hash <name>       blake2b, blake2s or sha256 (default blake2s)
+    --context <text>    the context the proofs are bound to (default empty)
+
+batch-verify checks every `<name>.proof` of the directory against the message in `<name>.msg`";
+
+/// The hash function of proofs made without `--hash`
+const DEFAULT_HASH: HashFunction = HashFunction::Blake2s;
+
+/// A failure of a command, which decides the exit code
+#[derive(Debug)]
+enum CliError {
+    Usage(String),
+    Io(PathBuf, io::Error),
+    Input(PathBuf, SigmaError),
+    Sigma(SigmaError),
+    Invalid,
+}
+
+impl CliError {
+    fn exit_code(&self) -> u8 {
+        match self {
+            CliError::Invalid => 1,
+            CliError::Usage(_) => 2,
+            CliError::Io(..) => 3,
+            CliError::Input(..) | CliError::Sigma(_) => 4,
+        }
+    }
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CliError::Usage(message) => write!(f, "{}\n\n{}", message, USAGE),
+            CliError::Io(path, error) => write!(f, "{}: {}", path.display(), error),
+            CliError::Input(path, error) => write!(f, "{}: {:?}", path.display(), error),
+            CliError::Sigma(error) => write!(f, "{:?}", error),
+            CliError::Invalid => write!(f, "invalid proof"),
+        }
+    }
+}
+
+/// The `--name value` options of a command, among those it accepts
+struct Options {
+    values: HashMap<String, String>,
+}
+
+impl Options {
+    fn parse(args: &[String], accepted: &[&str]) -> Result<Self, CliError> {
+        let mut values = HashMap::new();
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            let name = arg
+                .strip_prefix("--")
+                .filter(|name| accepted.contains(name))
+                .ok_or_else(|| CliError::Usage(format!("unexpected argument `{}`", arg)))?;
+            let value = args
+                .next()
+                .ok_or_else(|| CliError::Usage(format!("missing value of `--{}`", name)))?;
+            if values.insert(name.to_string(), value.clone()).is_some() {
+                return Err(CliError::Usage(format!("repeated option `--{}`", name)));
+            }
+        }
+        Ok(Self { values })
+    }
+
+    fn path(&self, name: &str) -> Result<PathBuf, CliError> {
+        self.values
+            .get(name)
+            .map(PathBuf::from)
+            .ok_or_else(|| CliError::Usage(format!("missing option `--{}`", name)))
+    }
+
+    /// The NIZK of `--hash` and `--context` for `instance`
+    fn nizk(&self, instance: &SchnorrInstance<RistrettoElement>) -> Result<Nizk, CliError> {
+        let hash = match self.values.get("hash") {
+            Some(name) => name
+                .parse()
+                .map_err(|_| CliError::Usage(format!("unknown hash function `{}`", name)))?,
+            None => DEFAULT_HASH,
+        };
+        let context = self.values.get("context").map_or("", String::as_str);
+
+        NIZK::with_hash_function(instance, context.as_bytes(), hash).map_err(|error| match error {
+            SigmaError::UnsupportedHashFunction => {
+                CliError::Usage(format!("`{}` is not supported by this build", hash.name()))
+            }
+            error => CliError::Sigma(error),
+        })
+    }
+}
+
+type Nizk = NIZK<Schnorr, DynTranscript>;
+
+fn read(path: &Path) -> Result<Vec<u8>, CliError> {
+    fs::read(path).map_err(|error| CliError::Io(path.to_path_buf(), error))
+}
+
+/// Decodes the canonical encoding in the file at `path`, rejecting trailing bytes
+fn read_value<X: CanonicalDeserialize>(path: &Path) -> Result<X, CliError> {
+    let bytes = read(path)?;
+    let mut reader = &bytes[..];
+    let value =
+        X::deserialize(&mut reader).map_err(|error| CliError::Input(path.into(), error.into()))?;
+    if reader.is_empty() {
+        Ok(value)
+    } else {
+        Err(CliError::Input(
+            path.into(),
+            SigmaError::SerializationError(ark_serialize::SerializationError::InvalidData),
+        ))
+    }
+}
+
+/// Writes `bytes` to the file at `path`, readable by its owner only when `secret`
+fn write(path: &Path, bytes: &[u8], secret: bool) -> Result<(), CliError> {
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    if secret {
+        std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    }
+    #[cfg(not(unix))]
+    let _ = secret;
+
+    options
+        .open(path)
+        .and_then(|mut file| file.write_all(bytes))
+        .map_err(|error| CliError::Io(path.to_path_buf(), error))
+}
+
+fn serialize<T: CanonicalSerialize>(value: &T) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    value
+        .serialize(&mut bytes)
+        .expect("serializing into a vector does not fail");
+    bytes
+}
+
+fn keygen(options: &Options) -> Result<(), CliError> {
+    let witness = RistrettoElement::random_scalar(&mut thread_rng());
+    let generator = RistrettoElement::generator();
+    let instance = SchnorrInstance::new(generator, generator.scalar_mul(&witness));
+
+    write(&options.path("witness")?, &serialize(&witness), true)?;
+    write(&options.path("instance")?, &serialize(&instance), false)
+}
+
+fn prove(options: &Options) -> Result<(), CliError> {
+    let instance_path = options.path("instance")?;
+    let instance = read_value(&instance_path)?;
+    let witness: RistrettoScalar = read_value(&options.path("witness")?)?;
+    let message = read(&options.path("message")?)?;
+    let out = options.path("out")?;
+
+    let proof = options
+        .nizk(&instance)?
+        .batchable_proof(&witness, Some(&message), &mut thread_rng())
+        .map_err(|error| CliError::Input(instance_path, error))?;
+    write(&out, &proof.to_bytes_compressed(), false)
+}
+
+fn verify(options: &Options) -> Result<(), CliError> {
+    let instance = read_value(&options.path("instance")?)?;
+    let proof = read(&options.path("proof")?)?;
+    let message = read(&options.path("message")?)?;
+
+    let nizk = options.nizk(&instance)?;
+
+    // A proof that does not decode is an invalid proof, like any other the verifier rejects
+    match BatchableProof::from_bytes_compressed(&proof)
+        .and_then(|proof| nizk.batchable_verify(&proof, Some(&message)))
+    {
+        Ok(()) => {
+            println!("valid");
+            Ok(())
+        }
+        Err(_) => Err(CliError::Invalid),
+    }
+}
+
+fn batch_verify(options: &Options) -> Result<(), CliError> {
+    let instance = read_value(&options.path("instance")?)?;
+    let nizk = options.nizk(&instance)?;
+    let dir = options.path("dir")?;
+
+    let mut names = Vec::new();
+    for entry in fs::read_dir(&dir).map_err(|error| CliError::Io(dir.clone(), error))? {
+        let path = entry
+            .map_err(|error| CliError::Io(dir.clone(), error))?
+            .path();
+        if path
+            .extension()
+            .is_some_and(|extension| extension == "proof")
+        {
+            names.push(path.with_extension(""));
+        }
+    }
+    names.sort();
+
+    // Every file is read before any proof is checked, so that an I/O error is never reported as an invalid proof
+    let mut files = Vec::new();
+    for name in names {
+        let proof = read(&name.with_extension("proof"))?;
+        let message = read(&name.with_extension("msg"))?;
+        files.push((name.with_extension("proof"), proof, message));
+    }
+
+    let mut all_valid = true;
+    for (path, proof, message) in files {
+        let valid = BatchableProof::from_bytes_compressed(&proof)
+            .and_then(|proof| nizk.batchable_verify(&proof, Some(&message)))
+            .is_ok();
+        all_valid &= valid;
+        println!(
+            "{}: {}",
+            path.display(),
+            if valid { "valid" } else { "invalid" }
+        );
+    }
+
+    if all_valid {
+        Ok(())
+    } else {
+        Err(CliError::Invalid)
+    }
+}
+
+fn run(args: &[String]) -> Result<(), CliError> {
+    let (command, args) = args
+        .split_first()
+        .ok_or_else(|| CliError::Usage("missing command".into()))?;
+    let options = |accepted: &[&str]| Options::parse(args, accepted);
+
+    match command.as_str() {
+        "keygen" => keygen(&options(&["witness", "instance"])?),
+        "prove" => prove(&options(&[
+            "instance", "witness", "message", "out", "hash", "context",
+        ])?),
+        "verify" => verify(&options(&[
+            "instance", "proof", "message", "hash", "context",
+        ])?),
+        "batch-verify" => batch_verify(&options(&["instance", "dir", "hash", "context"])?),
+        "help" | "--help" => {
+            println!("{}", USAGE);
+            Ok(())
+        }
+        _ => Err(CliError::Usage(format!("unknown command `{}`", command))),
+    }
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = env::args().skip(1).collect();
+    match run(&args) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("sigma-cli: {}", error);
+            ExitCode::from(error.exit_code())
+        }
+    }
+}
@@ -0,0 +1,407 @@
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, SerializationError};
+
+use crate::{
+    nizk_proofs::{proof_bytes, proof_from_bytes},
+    BatchableProof, Challenge, ProofEnvelope, ShortProof, SigmaError, SigmaProtocol,
+    CHALLENGE_LENGTH, LABEL_LENGTH,
+};
+
+/// The map keys of the encodings. A map holds the keys of its fields in ascending order, each at most once
+const VERSION: u64 = 0;
+const LABEL: u64 = 1;
+const HASH_ID: u64 = 2;
+const COMMITMENT: u64 = 3;
+const CHALLENGE: u64 = 4;
+const RESPONSE: u64 = 5;
+const INSTANCE: u64 = 6;
+
+/// The CBOR major types used by the encodings
+const UNSIGNED: u8 = 0;
+const BYTES: u8 = 2;
+const MAP: u8 = 5;
+
+/// The value of a map entry: an unsigned integer, or a byte string holding a compressed canonical encoding
+enum Value {
+    Unsigned(u64),
+    Bytes(Vec<u8>),
+}
+
+/// Writes the head of a data item in the shortest form of its argument
+fn write_head(buffer: &mut Vec<u8>, major: u8, argument: u64) {
+    let major = major << 5;
+    match argument {
+        0..=23 => buffer.push(major | argument as u8),
+        24..=0xff => buffer.extend([major | 24, argument as u8]),
+        0x100..=0xffff => {
+            buffer.push(major | 25);
+            buffer.extend((argument as u16).to_be_bytes());
+        }
+        0x1_0000..=0xffff_ffff => {
+            buffer.push(major | 26);
+            buffer.extend((argument as u32).to_be_bytes());
+        }
+        _ => {
+            buffer.push(major | 27);
+            buffer.extend(argument.to_be_bytes());
+        }
+    }
+}
+
+/// A definite-length map of `entries`, whose keys must be ascending
+fn encode_map(entries: Vec<(u64, Value)>) -> Vec<u8> {
+    let mut buffer = Vec::new();
+    write_head(&mut buffer, MAP, entries.len() as u64);
+    for (key, value) in entries {
+        write_head(&mut buffer, UNSIGNED, key);
+        match value {
+            Value::Unsigned(value) => write_head(&mut buffer, UNSIGNED, value),
+            Value::Bytes(bytes) => {
+                write_head(&mut buffer, BYTES, bytes.len() as u64);
+                buffer.extend(bytes);
+            }
+        }
+    }
+    buffer
+}
+
+fn invalid() -> SigmaError {
+    SerializationError::InvalidData.into()
+}
+
+/// A reader of a map with a fixed list of keys, which rejects any encoding other than the canonical one
+struct MapDecoder<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> MapDecoder<'a> {
+    /// Reads the head of a map of `entries` entries
+    fn new(bytes: &'a [u8], entries: u64) -> Result<Self, SigmaError> {
+        let mut decoder = Self { bytes };
+        if decoder.head(MAP)? != entries {
+            return Err(invalid());
+        }
+        Ok(decoder)
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8], SigmaError> {
+        if self.bytes.len() < len {
+            return Err(SerializationError::NotEnoughSpace.into());
+        }
+        let (taken, rest) = self.bytes.split_at(len);
+        self.bytes = rest;
+        Ok(taken)
+    }
+
+    /// Reads the head of a data item of type `major`, rejecting indefinite lengths and arguments that are not in
+    /// their shortest form
+    fn head(&mut self, major: u8) -> Result<u64, SigmaError> {
+        let initial = self.take(1)?[0];
+        if initial >> 5 != major {
+            return Err(invalid());
+        }
+
+        let (len, minimum) = match initial & 0x1f {
+            info @ 0..=23 => return Ok(info.into()),
+            24 => (1, 24),
+            25 => (2, 0x100),
+            26 => (4, 0x1_0000),
+            27 => (8, 0x1_0000_0000),
+            _ => return Err(invalid()),
+        };
+        let argument = self
+            .take(len)?
+            .iter()
+            .fold(0u64, |argument, byte| argument << 8 | u64::from(*byte));
+
+        if argument < minimum {
+            Err(invalid())
+        } else {
+            Ok(argument)
+        }
+    }
+
+    fn key(&mut self, key: u64) -> Result<(), SigmaError> {
+        if self.head(UNSIGNED)? == key {
+            Ok(())
+        } else {
+            Err(invalid())
+        }
+    }
+
+    fn unsigned(&mut self, key: u64) -> Result<u64, SigmaError> {
+        self.key(key)?;
+        self.head(UNSIGNED)
+    }
+
+    fn byte(&mut self, key: u64) -> Result<u8, SigmaError> {
+        self.unsigned(key)?.try_into().map_err(|_| invalid())
+    }
+
+    fn bytes(&mut self, key: u64) -> Result<&'a [u8], SigmaError> {
+        self.key(key)?;
+        let len = self.head(BYTES)?.try_into().map_err(|_| invalid())?;
+        self.take(len)
+    }
+
+    fn array<const N: usize>(&mut self, key: u64) -> Result<[u8; N], SigmaError> {
+        self.bytes(key)?.try_into().map_err(|_| invalid())
+    }
+
+    /// Decodes the compressed canonical encoding of a value, which must be the only encoding of that value
+    fn value<X: CanonicalSerialize + CanonicalDeserialize>(
+        &mut self,
+        key: u64,
+    ) -> Result<X, SigmaError> {
+        let bytes = self.bytes(key)?;
+        let value = proof_from_bytes(bytes, true)?;
+        if proof_bytes(&value, true) == bytes {
+            Ok(value)
+        } else {
+            Err(invalid())
+        }
+    }
+
+    fn finish(self) -> Result<(), SigmaError> {
+        if self.bytes.is_empty() {
+            Ok(())
+        } else {
+            Err(invalid())
+        }
+    }
+}
+
+impl<S: SigmaProtocol> BatchableProof<S> {
+    /// The canonical CBOR encoding of the proof: the map `{3: commitment, 5: response}`, whose values are byte
+    /// strings of the compressed canonical encodings
+    pub fn to_cbor(&self) -> Vec<u8> {
+        encode_map(vec![
+            (
+                COMMITMENT,
+                Value::Bytes(proof_bytes(self.commitment(), true)),
+            ),
+            (RESPONSE, Value::Bytes(proof_bytes(self.response(), true))),
+        ])
+    }
+
+    /// Decodes a proof from its canonical CBOR encoding. Unknown, missing or misordered keys, trailing bytes and
+    /// any non-canonical encoding are rejected with [`SigmaError::SerializationError`]
+    pub fn from_cbor(bytes: &[u8]) -> Result<Self, SigmaError> {
+        let mut decoder = MapDecoder::new(bytes, 2)?;
+        let commitment = decoder.value(COMMITMENT)?;
+        let response = decoder.value(RESPONSE)?;
+        decoder.finish()?;
+
+        Ok(Self::from_parts(commitment, response))
+    }
+}
+
+impl<S: SigmaProtocol> ShortProof<S> {
+    /// The canonical CBOR encoding of the proof: the map `{4: challenge, 5: response}`
+    pub fn to_cbor(&self) -> Vec<u8> {
+        encode_map(vec![
+            (CHALLENGE, Value::Bytes(self.challenge().to_vec())),
+            (RESPONSE, Value::Bytes(proof_bytes(self.response(), true))),
+        ])
+    }
+
+    /// Decodes a proof from its canonical CBOR encoding, rejecting any other encoding
+    pub fn from_cbor(bytes: &[u8]) -> Result<Self, SigmaError> {
+        let mut decoder = MapDecoder::new(bytes, 2)?;
+        let challenge: Challenge = decoder.array::<CHALLENGE_LENGTH>(CHALLENGE)?;
+        let response = decoder.value(RESPONSE)?;
+        decoder.finish()?;
+
+        Ok(Self::from_parts(challenge, response))
+    }
+}
+
+impl<S: SigmaProtocol> ProofEnvelope<BatchableProof<S>> {
+    /// The canonical CBOR encoding of the envelope: the map `{0: version, 1: label, 2: hash id, 3: commitment,
+    /// 5: response}`
+    pub fn to_cbor(&self) -> Vec<u8> {
+        let proof = self.proof();
+        encode_map(vec![
+            (VERSION, Value::Unsigned(self.version().into())),
+            (LABEL, Value::Bytes(self.label().to_vec())),
+            (HASH_ID, Value::Unsigned(self.hash_id().into())),
+            (
+                COMMITMENT,
+                Value::Bytes(proof_bytes(proof.commitment(), true)),
+            ),
+            (RESPONSE, Value::Bytes(proof_bytes(proof.response(), true))),
+        ])
+    }
+
+    /// Decodes an envelope from its canonical CBOR encoding, rejecting any other encoding. The header is not
+    /// checked against a protocol, which is left to [`NIZK::verify_enveloped`](crate::NIZK::verify_enveloped)
+    pub fn from_cbor(bytes: &[u8]) -> Result<Self, SigmaError> {
+        let mut decoder = MapDecoder::new(bytes, 5)?;
+        let version = decoder.byte(VERSION)?;
+        let label = decoder.array::<LABEL_LENGTH>(LABEL)?;
+        let hash_id = decoder.byte(HASH_ID)?;
+        let commitment = decoder.value(COMMITMENT)?;
+        let response = decoder.value(RESPONSE)?;
+        decoder.finish()?;
+
+        Ok(Self::from_parts(
+            version,
+            hash_id,
+            label,
+            BatchableProof::from_parts(commitment, response),
+        ))
+    }
+}
+
+/// The canonical CBOR encoding of an instance of `S`: the map `{1: label, 6: instance}`, with the label of the
+/// protocol over the instance and the compressed canonical encoding of the instance
+pub fn instance_to_cbor<S: SigmaProtocol>(instance: &S::Instance) -> Vec<u8>
+where
+    S::Instance: CanonicalSerialize,
+{
+    encode_map(vec![
+        (LABEL, Value::Bytes(S::new(instance).label().to_vec())),
+        (INSTANCE, Value::Bytes(proof_bytes(instance, true))),
+    ])
+}
+
+/// Decodes an instance of `S` from its canonical CBOR encoding, rejecting any other encoding with
+/// [`SigmaError::SerializationError`], and an instance whose protocol label is not the encoded label with
+/// [`SigmaError::InvalidInstance`]
+pub fn instance_from_cbor<S: SigmaProtocol>(bytes: &[u8]) -> Result<S::Instance, SigmaError>
+where
+    S::Instance: CanonicalSerialize + CanonicalDeserialize,
+{
+    let mut decoder = MapDecoder::new(bytes, 2)?;
+    let label = decoder.array::<LABEL_LENGTH>(LABEL)?;
+    let instance = decoder.value(INSTANCE)?;
+    decoder.finish()?;
+
+    if S::new(&instance).label() == label {
+        Ok(instance)
+    } else {
+        Err(SigmaError::InvalidInstance)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use rand::thread_rng;
+
+    use crate::{
+        protocols::{SchnorrDLOG, SchnorrInstance},
+        BatchableProof, HashFunction, ProofEnvelope, ShortProof, SigmaError, NIZK,
+    };
+
+    use super::{instance_from_cbor, instance_to_cbor, write_head};
+
+    type G = ark_bls12_377::G1Projective;
+    type F = ark_bls12_377::Fr;
+    type Schnorr = SchnorrDLOG<G>;
+
+    fn schnorr_setup() -> (SchnorrInstance<G>, F) {
+        SchnorrInstance::generate(&mut thread_rng())
+    }
+
+    fn invalid() -> Result<(), SigmaError> {
+        Err(SigmaError::SerializationError(
+            ark_serialize::SerializationError::InvalidData,
+        ))
+    }
+
+    #[test]
+    fn test_heads_are_shortest() {
+        let head = |argument| {
+            let mut buffer = Vec::new();
+            write_head(&mut buffer, 2, argument);
+            buffer
+        };
+
+        assert_eq!(head(23), [0x57]);
+        assert_eq!(head(24), [0x58, 24]);
+        assert_eq!(head(0x100), [0x59, 1, 0]);
+        assert_eq!(head(0x1_0000), [0x5a, 0, 1, 0, 0]);
+        assert_eq!(head(0x1_0000_0000), [0x5b, 0, 0, 0, 1, 0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn test_cbor_round_trips() {
+        let rng = &mut thread_rng();
+        let (instance, witness) = schnorr_setup();
+        let nizk = NIZK::<Schnorr, blake2::Blake2s>::new(&instance, b"ctx");
+        let dyn_nizk =
+            NIZK::<Schnorr, _>::with_hash_function(&instance, b"ctx", HashFunction::SHA256)
+                .unwrap();
+
+        let proof = nizk.batchable_proof(&witness, None, rng).unwrap();
+        let decoded = BatchableProof::<Schnorr>::from_cbor(&proof.to_cbor()).unwrap();
+        assert_eq!(decoded, proof);
+        assert!(nizk.batchable_verify(&decoded, None).is_ok());
+
+        let short_proof = nizk.short_proof(&witness, None, rng).unwrap();
+        let decoded = ShortProof::<Schnorr>::from_cbor(&short_proof.to_cbor()).unwrap();
+        assert_eq!(decoded, short_proof);
+
+        let envelope = dyn_nizk.prove_enveloped(&witness, None, rng).unwrap();
+        let decoded =
+            ProofEnvelope::<BatchableProof<Schnorr>>::from_cbor(&envelope.to_cbor()).unwrap();
+        assert!(dyn_nizk.verify_enveloped(&decoded, None).is_ok());
+
+        let decoded = instance_from_cbor::<Schnorr>(&instance_to_cbor::<Schnorr>(&instance));
+        assert_eq!(decoded.unwrap(), instance);
+    }
+
+    #[test]
+    fn test_cbor_rejects_other_encodings() {
+        let rng = &mut thread_rng();
+        let (instance, witness) = schnorr_setup();
+        let nizk = NIZK::<Schnorr, blake2::Blake2s>::new(&instance, b"ctx");
+        let bytes = nizk.short_proof(&witness, None, rng).unwrap().to_cbor();
+        let decode = |bytes: &[u8]| ShortProof::<Schnorr>::from_cbor(bytes).map(|_| ());
+        assert!(decode(&bytes).is_ok());
+
+        // The encoding is {4: h'<32 bytes>', 5: h'<32 bytes>'}
+        assert_eq!(bytes[..4], [0xa2, 0x04, 0x58, 0x20]);
+
+        let mut unknown_key = bytes.clone();
+        unknown_key[1] = 0x07;
+        let mut swapped_keys = bytes.clone();
+        swapped_keys[1] = 0x05;
+        let mut long_key = vec![0xa2, 0x18, 0x04];
+        long_key.extend(&bytes[2..]);
+        let mut indefinite_map = vec![0xbf];
+        indefinite_map.extend(&bytes[1..]);
+        indefinite_map.push(0xff);
+        let mut long_string = bytes[..2].to_vec();
+        long_string.extend([0x59, 0x00, 0x20]);
+        long_string.extend(&bytes[4..]);
+        let mut trailing = bytes.clone();
+        trailing.push(0);
+
+        for encoding in [
+            unknown_key,
+            swapped_keys,
+            long_key,
+            indefinite_map,
+            long_string,
+            trailing,
+        ] {
+            assert_eq!(decode(&encoding), invalid());
+        }
+        assert!(decode(&bytes[..bytes.len() - 1]).is_err());
+    }
+
+    #[test]
+    fn test_cbor_instance_under_another_label() {
+        let (instance, _) = schnorr_setup();
+        let mut bytes = instance_to_cbor::<Schnorr>(&instance);
+
+        // The encoding is {1: h'<label>', 6: h'<instance>'}
+        assert_eq!(bytes[..4], [0xa2, 0x01, 0x58, 0x20]);
+        bytes[4..36].copy_from_slice(&[0xff; 32]);
+        assert_eq!(
+            instance_from_cbor::<Schnorr>(&bytes),
+            Err(SigmaError::InvalidInstance)
+        );
+    }
+}
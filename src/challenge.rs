@@ -0,0 +1,343 @@
+use std::convert::Infallible;
+
+use digest::Digest;
+
+use crate::{trace, Challenge, HashedMessage, Transcript, CHALLENGE_LENGTH, LABEL_LENGTH};
+
+/// Absorbed before the hash of a [`TranscriptData`](crate::TranscriptData), so that structured data and raw
+/// messages never share a challenge. Longer than a hash of a message, which would otherwise have to start with it
+const TRANSCRIPT_DATA_DOMSEP: &[u8] = b"zkpstd/sigma/structured-transcript-data";
+
+/// The fields every challenge starts with, in the order they are absorbed: the hashes of the domain separator and
+/// of the context, the protocol label and, in the strong mode only, the hash of the instance
+fn prefix_fields<'a>(
+    hd: &'a Challenge,
+    hctx: &'a Challenge,
+    label: &'a [u8; LABEL_LENGTH],
+    hinst: Option<&'a Challenge>,
+) -> impl Iterator<Item = (&'static [u8], &'a [u8])> {
+    let fields: [(&'static [u8], &'a [u8]); 3] =
+        [(b"domain", hd), (b"context", hctx), (b"label", label)];
+    fields
+        .into_iter()
+        .chain(hinst.map(|hinst| (&b"instance"[..], &hinst[..])))
+}
+
+/// `transcript` once it has absorbed the fields every challenge starts with
+pub(crate) fn challenge_prefix<D: Transcript>(
+    mut transcript: D,
+    hd: &Challenge,
+    hctx: &Challenge,
+    label: &[u8; LABEL_LENGTH],
+    hinst: Option<&Challenge>,
+) -> D {
+    for (field, bytes) in prefix_fields(hd, hctx, label, hinst) {
+        transcript.append(field, bytes);
+        trace::absorbed(&transcript, field, bytes.len());
+    }
+    transcript
+}
+
+/// Absorbs the hash of the message of a challenge, after the domain separator of structured data if it is one
+pub(crate) fn absorb_message<D: Transcript>(transcript: &mut D, message: &HashedMessage<D>) {
+    if message.structured {
+        transcript.append(b"data", TRANSCRIPT_DATA_DOMSEP);
+        trace::absorbed(&*transcript, b"data", TRANSCRIPT_DATA_DOMSEP.len());
+    }
+    transcript.append(b"message", &message.hm);
+    trace::absorbed(&*transcript, b"message", message.hm.len());
+}
+
+/// The challenge over `transcript`, a transcript returned by [`challenge_prefix`]: absorbs the message, then the
+/// commitment with `absorb_commitment`, and keeps the first `L` bytes of what is squeezed
+pub(crate) fn challenge_over<D: Transcript, E, const L: usize>(
+    mut transcript: D,
+    message: Option<&HashedMessage<D>>,
+    absorb_commitment: impl FnOnce(&mut D) -> Result<(), E>,
+) -> Result<Challenge, E> {
+    if let Some(message) = message {
+        absorb_message(&mut transcript, message);
+    }
+    absorb_commitment(&mut transcript)?;
+    Ok(padded(&truncated::<L>(&transcript.challenge(b"challenge"))))
+}
+
+/// The first `L` bytes of a challenge, those a short proof of `L`-byte challenges carries
+pub(crate) fn truncated<const L: usize>(challenge: &Challenge) -> Challenge<L> {
+    let mut truncated = [0u8; L];
+    truncated.copy_from_slice(&challenge[..L]);
+    truncated
+}
+
+/// A challenge of `L` bytes as protocols read it, followed by zeros up to `CHALLENGE_LENGTH` bytes
+pub(crate) fn padded<const L: usize>(challenge: &Challenge<L>) -> Challenge {
+    let mut padded = [0u8; CHALLENGE_LENGTH];
+    padded[..L].copy_from_slice(challenge);
+    padded
+}
+
+/// The Fiat-Shamir challenge of a NIZK over the digest `D` with `L`-byte challenges, recomputed from its fields, as
+/// from a transcript dump. The challenge is the first `L` bytes, followed by zeros up to 32 bytes, of the last 32
+/// bytes of `D` over the concatenation, with no length prefix, of
+///
+/// 1. `hd`, the last 32 bytes of `D(DOMSEP)`, with the digest name appended to `DOMSEP` for the NIZKs over a
+///    [`HashFunction`](crate::HashFunction) and the mode byte `0x01` for [`FsMode::LegacyWeak`](crate::FsMode)
+/// 2. `hctx`, the last 32 bytes of `D(ctx)` for the context or the encoding of the
+///    [`SessionContext`](crate::SessionContext)
+/// 3. `label`, the 32-byte [`SigmaProtocol::label`](crate::SigmaProtocol::label)
+/// 4. `hinst`, the last 32 bytes of `D(instance_bytes)`, omitted in the weak mode
+/// 5. if the message is a [`TranscriptData`](crate::TranscriptData), the domain separator of structured data
+/// 6. if there is a message, its hash, [`HashedMessage::new`] or [`TranscriptData::hashed`](crate::TranscriptData::hashed)
+/// 7. `commitment_bytes`, the [`CanonicalAbsorb`](crate::CanonicalAbsorb) encoding of the commitment, compressed
+///    with every point in affine form
+///
+/// The NIZK derives its challenges with the same code, streaming the commitment rather than encoding it first
+pub fn derive_challenge<D: Digest + Clone + Default, const L: usize>(
+    hd: &Challenge,
+    hctx: &Challenge,
+    label: &[u8; LABEL_LENGTH],
+    hinst: Option<&Challenge>,
+    message: Option<&HashedMessage<D>>,
+    commitment_bytes: &[u8],
+) -> Challenge {
+    let transcript = challenge_prefix(D::default(), hd, hctx, label, hinst);
+    let Ok(challenge) = challenge_over::<D, Infallible, L>(transcript, message, |transcript| {
+        transcript.append(b"commitment", commitment_bytes);
+        trace::absorbed(&*transcript, b"commitment", commitment_bytes.len());
+        Ok(())
+    });
+    challenge
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_ec::ProjectiveCurve;
+    use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+    use rand::thread_rng;
+
+    use crate::{
+        encoding::encode_hex,
+        interactive_proofs::protocol_label,
+        protocols::{SchnorrDLOG, SchnorrInstance},
+        CanonicalAbsorb, ChallengeExpansion, CommitmentRecoverable, FsMode, HashedMessage,
+        SigmaGroup, SigmaProtocol, Transcript, TranscriptData, DOMSEP, NIZK,
+    };
+
+    use super::{derive_challenge, padded};
+
+    type G = ark_bls12_377::G1Projective;
+
+    #[test]
+    fn test_derive_challenge_known_answer() {
+        let (hd, hctx, label, hinst) = ([1; 32], [2; 32], [3; 32], [4; 32]);
+        let commitment = b"commitment";
+
+        // Other implementations compare against these vectors, so they must never change
+        let expected = [
+            (
+                derive_challenge::<blake2::Blake2s, 32>(
+                    &hd,
+                    &hctx,
+                    &label,
+                    Some(&hinst),
+                    Some(&HashedMessage::new(b"message")),
+                    commitment,
+                ),
+                "9a4d98f90cee223f9d5ff34c977665518af325642a4c3a6faf9b6268a87295e9",
+            ),
+            (
+                derive_challenge::<blake2::Blake2s, 32>(
+                    &hd,
+                    &hctx,
+                    &label,
+                    Some(&hinst),
+                    None,
+                    commitment,
+                ),
+                "e2199766422a0ea56af69cc8925268e2675091830c23a738e252c0e138db3076",
+            ),
+            (
+                derive_challenge::<blake2::Blake2s, 32>(&hd, &hctx, &label, None, None, commitment),
+                "439dbe3a1962e7c8bd57ccc6d86c1de1c23a47fc4a33a3fe1b1b452e11e5b8c3",
+            ),
+            (
+                derive_challenge::<sha2::Sha256, 32>(
+                    &hd,
+                    &hctx,
+                    &label,
+                    Some(&hinst),
+                    Some(&HashedMessage::new(b"message")),
+                    commitment,
+                ),
+                "bd529c3f8db93ffa23cb5edb9ddae39bcb817d3527c1991ae498ea06e1114260",
+            ),
+            (
+                derive_challenge::<blake2::Blake2s, 32>(
+                    &hd,
+                    &hctx,
+                    &label,
+                    Some(&hinst),
+                    Some(&TranscriptData::new().add_label("name", b"value").hashed()),
+                    commitment,
+                ),
+                "e612542605e1c61c9f03abc3986ab90681c3b988369e656fe70fcc55e486f9c3",
+            ),
+            (
+                derive_challenge::<blake2::Blake2s, 16>(
+                    &hd,
+                    &hctx,
+                    &label,
+                    Some(&hinst),
+                    Some(&HashedMessage::new(b"message")),
+                    commitment,
+                ),
+                "9a4d98f90cee223f9d5ff34c9776655100000000000000000000000000000000",
+            ),
+        ];
+        for (challenge, expected) in expected {
+            assert_eq!(encode_hex(&challenge), expected);
+        }
+    }
+
+    /// The absorbed encoding of the commitment `G + G`, a sum left in projective form, in hex, and the challenge
+    /// over it with the fields of `test_derive_challenge_known_answer`
+    fn absorbed_known_answer<G: SigmaGroup>() -> (String, String) {
+        let commitment = G::generator() + G::generator();
+        let mut absorbed = Vec::new();
+        commitment.absorb_canonical(&mut absorbed).unwrap();
+        assert_eq!(absorbed.len(), commitment.absorbed_size());
+        let challenge = derive_challenge::<blake2::Blake2s, 32>(
+            &[1; 32],
+            &[2; 32],
+            &[3; 32],
+            Some(&[4; 32]),
+            Some(&HashedMessage::new(b"message")),
+            &absorbed,
+        );
+
+        (encode_hex(&absorbed), encode_hex(&challenge))
+    }
+
+    #[test]
+    fn test_commitment_absorption_known_answer() {
+        // Other implementations compare against these vectors, so they must never change: the commitments are
+        // absorbed compressed and in affine form
+        assert_eq!(
+            absorbed_known_answer::<G>(),
+            (
+                "9063416a6ded7a8590dc816765610688551930a2c9970ee97e4b2addf3f7617eed52544b5adb6e05919e93413145ed00".to_string(),
+                "53c1891ef46123ea65df8a0746d8ee6de11d5ba6b3519f458ba492b871b54c8b".to_string()
+            )
+        );
+        #[cfg(feature = "ristretto")]
+        assert_eq!(
+            absorbed_known_answer::<crate::ristretto::RistrettoElement>(),
+            (
+                "6a493210f7499cd17fecb510ae0cea23a110e8d5b901f8acadd3095c73a3b919".to_string(),
+                "99cbbc902746e1477426db6c8da096aa192f500fed06004e46d2fea47f6d91b4".to_string()
+            )
+        );
+        #[cfg(feature = "secp256k1")]
+        assert_eq!(
+            absorbed_known_answer::<crate::secp256k1::Secp256k1Element>(),
+            (
+                "02c6047f9441ed7d6d3045406e95c07cd85c778e4b8cef3ca7abac09b95c709ee5".to_string(),
+                "424ce6cba812ef3281c6fe5eea7113426db5c73ec5eda9cb2c930e0b617b68f2".to_string()
+            )
+        );
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_derive_challenge_matches_nizk() {
+        type Hash = blake2::Blake2s;
+        let rng = &mut thread_rng();
+        let (instance, witness) = SchnorrInstance::<G>::generate(rng);
+        let protocol = SchnorrDLOG::new(&instance);
+        let hash = |bytes: &[u8]| Hash::default().hash(b"", bytes);
+        let hctx = hash(b"ctx");
+        // A batchable proof is encoded as its commitment followed by its response
+        let commitment_length = G::prime_subgroup_generator().serialized_size();
+
+        let strong = NIZK::<SchnorrDLOG<G>, Hash>::new(&instance, b"ctx");
+        let hinst = hash(&protocol.instance_bytes());
+        for message in [Some(&b"message"[..]), None] {
+            let proof = strong.batchable_proof(&witness, message, rng).unwrap();
+            assert_eq!(
+                strong.batchable_challenge(&proof, message).unwrap(),
+                derive_challenge::<Hash, 32>(
+                    &hash(DOMSEP),
+                    &hctx,
+                    &protocol.label(),
+                    Some(&hinst),
+                    message.map(HashedMessage::new).as_ref(),
+                    &proof.to_bytes_compressed()[..commitment_length]
+                )
+            );
+        }
+
+        let weak = NIZK::<SchnorrDLOG<G>, Hash>::builder(&instance)
+            .context(b"ctx")
+            .fiat_shamir(FsMode::LegacyWeak)
+            .build()
+            .unwrap();
+        let proof = weak.batchable_proof(&witness, None, rng).unwrap();
+        assert_eq!(
+            weak.batchable_challenge(&proof, None).unwrap(),
+            derive_challenge::<Hash, 32>(
+                &hash(&[DOMSEP, &[1]].concat()),
+                &hctx,
+                &protocol_label(SchnorrDLOG::<G>::PROTOCOL_ID, &[]),
+                None,
+                None,
+                &proof.to_bytes_compressed()[..commitment_length]
+            )
+        );
+    }
+
+    #[test]
+    fn test_derive_challenge_matches_short_nizk_with_data() {
+        type Hash = blake2::Blake2s;
+        const L: usize = 16;
+        let rng = &mut thread_rng();
+        let (instance, witness) = SchnorrInstance::<G>::generate(rng);
+        let protocol = SchnorrDLOG::new(&instance);
+        let hash = |bytes: &[u8]| Hash::default().hash(b"", bytes);
+        let data = TranscriptData::new().add_label("name", b"value");
+
+        // A short proof is encoded as the first `L` bytes of its challenge followed by its response, from which
+        // the commitment is recovered
+        let nizk = NIZK::<SchnorrDLOG<G>, Hash, L>::new(&instance, b"ctx");
+        let bytes = nizk
+            .short_proof_with_data(&witness, Some(&data), rng)
+            .unwrap()
+            .to_bytes_compressed();
+        let mut challenge = [0; L];
+        challenge.copy_from_slice(&bytes[..L]);
+        let response = CanonicalDeserialize::deserialize(&bytes[L..]).unwrap();
+        let commitment = protocol
+            .simulate_commitment(
+                &SchnorrDLOG::<G>::challenge_from_bytes(
+                    &padded(&challenge),
+                    ChallengeExpansion::default(),
+                )
+                .unwrap(),
+                &response,
+            )
+            .unwrap();
+        let mut commitment_bytes = Vec::new();
+        commitment.absorb_canonical(&mut commitment_bytes).unwrap();
+
+        assert_eq!(
+            padded(&challenge),
+            derive_challenge::<Hash, L>(
+                &hash(DOMSEP),
+                &hash(b"ctx"),
+                &protocol.label(),
+                Some(&hash(&protocol.instance_bytes())),
+                Some(&data.hashed()),
+                &commitment_bytes
+            )
+        );
+    }
+}
@@ -0,0 +1,316 @@
+use rand::Rng;
+
+use crate::{
+    interactive_proofs::canonical_bytes, interactive_proofs::protocol_label, Challenge,
+    ChallengeExpansion, CommitmentRecoverable, SigmaError, SigmaProtocol, LABEL_LENGTH,
+};
+
+/// AND composition of two Sigma protocols: the prover knows witnesses for both instances.
+///
+/// Both components run side by side and answer the same challenge, so the instance, witness, commitment, prover
+/// state, response and challenge are all pairs of those of the components.
+pub struct AndComposition<S1: SigmaProtocol, S2: SigmaProtocol> {
+    first: S1,
+    second: S2,
+}
+
+impl<S1: SigmaProtocol, S2: SigmaProtocol> AndComposition<S1, S2> {
+    /// The protocols of both statements
+    pub(crate) fn components(&self) -> (&S1, &S2) {
+        (&self.first, &self.second)
+    }
+}
+
+impl<S1: SigmaProtocol, S2: SigmaProtocol> SigmaProtocol for AndComposition<S1, S2> {
+    type Instance = (S1::Instance, S2::Instance);
+    type Commitment = (S1::Commitment, S2::Commitment);
+    type ProverState = (S1::ProverState, S2::ProverState);
+    type Witness = (S1::Witness, S2::Witness);
+    type Response = (S1::Response, S2::Response);
+    type Challenge = (S1::Challenge, S2::Challenge);
+
+    const PROTOCOL_ID: &'static [u8] = b"zkpstd/sigma/and";
+
+    /// The longer of the minimum challenge lengths of the components, which both see the challenge
+    const MIN_CHALLENGE_LENGTH: usize = if S1::MIN_CHALLENGE_LENGTH > S2::MIN_CHALLENGE_LENGTH {
+        S1::MIN_CHALLENGE_LENGTH
+    } else {
+        S2::MIN_CHALLENGE_LENGTH
+    };
+
+    fn label(&self) -> [u8; LABEL_LENGTH] {
+        protocol_label(
+            Self::PROTOCOL_ID,
+            &[self.first.label(), self.second.label()].concat(),
+        )
+    }
+
+    fn new(instance: &Self::Instance) -> Self {
+        Self {
+            first: S1::new(&instance.0),
+            second: S2::new(&instance.1),
+        }
+    }
+
+    /// The same bytes converted into the challenge spaces of both components
+    fn challenge_from_bytes(
+        challenge: &Challenge,
+        expansion: ChallengeExpansion,
+    ) -> Result<Self::Challenge, SigmaError> {
+        Ok((
+            S1::challenge_from_bytes(challenge, expansion)?,
+            S2::challenge_from_bytes(challenge, expansion)?,
+        ))
+    }
+
+    fn validate_instance(&self) -> Result<(), SigmaError> {
+        self.first.validate_instance()?;
+        self.second.validate_instance()
+    }
+
+    fn instance_bytes(&self) -> Vec<u8> {
+        canonical_bytes(&(self.first.instance_bytes(), self.second.instance_bytes()))
+    }
+
+    fn witness_bytes(&self, witness: &Self::Witness) -> Vec<u8> {
+        canonical_bytes(&(
+            self.first.witness_bytes(&witness.0),
+            self.second.witness_bytes(&witness.1),
+        ))
+    }
+
+    fn is_valid_witness(&self, witness: &Self::Witness) -> bool {
+        self.first.is_valid_witness(&witness.0) && self.second.is_valid_witness(&witness.1)
+    }
+
+    fn prover_commit<R: Rng>(
+        &self,
+        witness: &Self::Witness,
+        expansion: ChallengeExpansion,
+        rng: &mut R,
+    ) -> Result<(Self::Commitment, Self::ProverState), SigmaError> {
+        let (commitment1, state1) = self.first.prover_commit(&witness.0, expansion, rng)?;
+        let (commitment2, state2) = self.second.prover_commit(&witness.1, expansion, rng)?;
+
+        Ok(((commitment1, commitment2), (state1, state2)))
+    }
+
+    fn prover_response(
+        &self,
+        prover_state: &Self::ProverState,
+        challenge: &Self::Challenge,
+    ) -> Result<Self::Response, SigmaError> {
+        Ok((
+            self.first.prover_response(&prover_state.0, &challenge.0)?,
+            self.second.prover_response(&prover_state.1, &challenge.1)?,
+        ))
+    }
+
+    fn verifier(
+        &self,
+        commitment: &Self::Commitment,
+        challenge: &Self::Challenge,
+        response: &Self::Response,
+    ) -> Result<(), SigmaError> {
+        self.first
+            .verifier(&commitment.0, &challenge.0, &response.0)?;
+        self.second
+            .verifier(&commitment.1, &challenge.1, &response.1)
+    }
+
+    fn simulate_response<R: Rng>(&self, rng: &mut R) -> Self::Response {
+        (
+            self.first.simulate_response(rng),
+            self.second.simulate_response(rng),
+        )
+    }
+
+    fn zeroize_state(&self, prover_state: &mut Self::ProverState) {
+        self.first.zeroize_state(&mut prover_state.0);
+        self.second.zeroize_state(&mut prover_state.1);
+    }
+}
+
+impl<S1: CommitmentRecoverable, S2: CommitmentRecoverable> CommitmentRecoverable
+    for AndComposition<S1, S2>
+{
+    fn simulate_commitment(
+        &self,
+        challenge: &Self::Challenge,
+        response: &Self::Response,
+    ) -> Result<Self::Commitment, SigmaError> {
+        Ok((
+            self.first.simulate_commitment(&challenge.0, &response.0)?,
+            self.second.simulate_commitment(&challenge.1, &response.1)?,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_ec::ProjectiveCurve;
+    use ark_ff::{PrimeField, UniformRand, Zero};
+    use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+    use digest::Digest;
+    use rand::{thread_rng, Rng};
+
+    use crate::{
+        encoding::encode_hex,
+        nizk_proofs::tests::{run_nizk_batched, run_nizk_batched_at, run_nizk_short},
+        protocols::{Okamoto, OkamotoInstance, SchnorrDLOG, SchnorrInstance},
+        testing::interactive::run_interactive,
+        BatchableProof, SigmaError, SigmaProtocol, LABEL_LENGTH, NIZK,
+    };
+
+    use super::AndComposition;
+
+    type Hash = blake2::Blake2s;
+    type G = ark_bls12_377::G1Projective;
+    type F = ark_bls12_377::Fr;
+
+    type SchnorrAnd = AndComposition<SchnorrDLOG<G>, SchnorrDLOG<G>>;
+
+    fn schnorr_setup<R: Rng>(rng: &mut R) -> (SchnorrInstance<G>, F) {
+        let base = G::rand(rng);
+        let witness = F::rand(rng);
+
+        (
+            SchnorrInstance::new(base, base.mul(witness.into_repr())),
+            witness,
+        )
+    }
+
+    #[test]
+    fn test_and_accept_valid() {
+        let rng = &mut thread_rng();
+        let (instance1, witness1) = schnorr_setup(rng);
+        let (instance2, witness2) = schnorr_setup(rng);
+        let instance = (instance1, instance2);
+        let witness = (witness1, witness2);
+
+        let batched_result = run_nizk_batched::<Hash, SchnorrAnd, _>(&instance, &witness, rng);
+        let short_result = run_nizk_short::<Hash, SchnorrAnd, _>(&instance, &witness, rng);
+
+        assert!(batched_result.is_ok());
+        assert!(short_result.is_ok());
+    }
+
+    #[test]
+    fn test_and_needs_the_longer_challenges_of_its_components() {
+        let rng = &mut thread_rng();
+        let (instance1, witness1) = schnorr_setup(rng);
+        let (instance2, witness2) = schnorr_setup(rng);
+        let instance = (instance1, instance2);
+
+        let result =
+            run_nizk_batched_at::<Hash, SchnorrAnd, _, 16>(&instance, &(witness1, witness2), rng);
+        assert!(result.is_ok());
+
+        let okamoto = OkamotoInstance::new(G::rand(rng), G::rand(rng), G::rand(rng));
+        assert!(matches!(
+            NIZK::<AndComposition<SchnorrDLOG<G>, Okamoto<G>>, Hash, 16>::try_new(
+                &(instance1, okamoto),
+                b"ctx"
+            ),
+            Err(SigmaError::ChallengeTooShort { got: 16, need: 32 })
+        ));
+    }
+
+    #[test]
+    fn test_and_interactive_completeness() {
+        let rng = &mut thread_rng();
+        let (instance1, witness1) = schnorr_setup(rng);
+        let (instance2, witness2) = schnorr_setup(rng);
+
+        let result =
+            run_interactive::<SchnorrAnd, _>(&(instance1, instance2), &(witness1, witness2), rng);
+
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_and_reject_one_wrong_witness() {
+        let rng = &mut thread_rng();
+        let (instance1, witness1) = schnorr_setup(rng);
+        let (instance2, _) = schnorr_setup(rng);
+        let instance = (instance1, instance2);
+
+        for witness in [(witness1, F::rand(rng)), (F::rand(rng), witness1)] {
+            let test_result = run_nizk_batched::<Hash, SchnorrAnd, _>(&instance, &witness, rng);
+
+            assert_eq!(test_result, Err(SigmaError::VerificationFailed));
+            assert!(!SchnorrAnd::new(&instance).is_valid_witness(&witness));
+        }
+    }
+
+    #[test]
+    fn test_and_reject_invalid_component() {
+        let rng = &mut thread_rng();
+        let (valid, _) = schnorr_setup(rng);
+        let identity_base = SchnorrInstance::new(G::zero(), G::rand(rng));
+
+        for instance in [(valid, identity_base), (identity_base, valid)] {
+            assert_eq!(
+                SchnorrAnd::new(&instance).validate_instance(),
+                Err(SigmaError::InvalidInstance)
+            );
+            assert!(NIZK::<SchnorrAnd, Hash>::try_new(&instance, b"ctx").is_err());
+        }
+        assert!(SchnorrAnd::new(&(valid, valid)).validate_instance().is_ok());
+    }
+
+    #[test]
+    fn test_and_batchable_proof_round_trip() {
+        let rng = &mut thread_rng();
+        let (instance1, witness1) = schnorr_setup(rng);
+        let (instance2, witness2) = schnorr_setup(rng);
+        let instance = (instance1, instance2);
+        let nizk = NIZK::<SchnorrAnd, Hash>::new(&instance, b"ctx");
+
+        let proof = nizk
+            .batchable_proof(&(witness1, witness2), None, rng)
+            .unwrap();
+        let mut bytes = Vec::new();
+        proof.serialize(&mut bytes).unwrap();
+        let decoded = BatchableProof::<SchnorrAnd>::deserialize(&bytes[..]).unwrap();
+
+        assert!(nizk.batchable_verify(&decoded, None).is_ok());
+    }
+
+    #[test]
+    fn test_and_label_differs_from_components() {
+        let rng = &mut thread_rng();
+        let (instance1, _) = schnorr_setup(rng);
+        let (instance2, _) = schnorr_setup(rng);
+
+        let composed_label = SchnorrAnd::new(&(instance1, instance2)).label();
+
+        assert_ne!(composed_label, SchnorrDLOG::new(&instance1).label());
+    }
+
+    #[test]
+    fn test_and_label_known_answer() {
+        let generator = G::prime_subgroup_generator();
+        let instance = SchnorrInstance::new(generator, generator.double());
+        let component = SchnorrDLOG::new(&instance).label();
+        let id = SchnorrAnd::PROTOCOL_ID;
+        let digest = blake2::Blake2b::digest(
+            &[
+                &(id.len() as u64).to_le_bytes()[..],
+                id,
+                &component,
+                &component,
+            ]
+            .concat(),
+        );
+
+        // A composition frames its identifier as a protocol does, and hashes the labels of its components in place
+        // of an instance
+        let label = SchnorrAnd::new(&(instance, instance)).label();
+        assert_eq!(label[..], digest[64 - LABEL_LENGTH..]);
+        assert_eq!(
+            encode_hex(&label),
+            "8f73c39837df9cfda98896e835569a9e9eae98664a865454624d1b2bb44b3252"
+        );
+    }
+}
@@ -0,0 +1,284 @@
+use rand::Rng;
+
+use crate::{
+    interactive_proofs::canonical_bytes, interactive_proofs::protocol_label, Challenge,
+    ChallengeExpansion, CommitmentRecoverable, SigmaError, SigmaProtocol, LABEL_LENGTH,
+};
+
+/// AND composition of any number of instances of the same Sigma protocol: the prover knows a witness for every
+/// instance.
+///
+/// All components answer the same challenge. The commitment and response are vectors with one entry per instance,
+/// and a witness, commitment or response whose length differs from the number of instances is rejected with
+/// [`SigmaError::LengthMismatch`].
+pub struct AndMany<S: SigmaProtocol> {
+    protocols: Vec<S>,
+}
+
+impl<S: SigmaProtocol> AndMany<S> {
+    fn check_length(&self, length: usize) -> Result<(), SigmaError> {
+        if length == self.protocols.len() {
+            Ok(())
+        } else {
+            Err(SigmaError::LengthMismatch)
+        }
+    }
+}
+
+impl<S: SigmaProtocol> SigmaProtocol for AndMany<S> {
+    type Instance = Vec<S::Instance>;
+    type Commitment = Vec<S::Commitment>;
+    type ProverState = Vec<S::ProverState>;
+    type Witness = Vec<S::Witness>;
+    type Response = Vec<S::Response>;
+    type Challenge = S::Challenge;
+
+    const PROTOCOL_ID: &'static [u8] = b"zkpstd/sigma/and-many";
+
+    const MIN_CHALLENGE_LENGTH: usize = S::MIN_CHALLENGE_LENGTH;
+
+    /// The hash of the labels of all components, which binds the number of instances
+    fn label(&self) -> [u8; LABEL_LENGTH] {
+        let labels: Vec<u8> = self.protocols.iter().flat_map(S::label).collect();
+        protocol_label(Self::PROTOCOL_ID, &labels)
+    }
+
+    fn new(instance: &Self::Instance) -> Self {
+        Self {
+            protocols: instance.iter().map(S::new).collect(),
+        }
+    }
+
+    fn challenge_from_bytes(
+        challenge: &Challenge,
+        expansion: ChallengeExpansion,
+    ) -> Result<S::Challenge, SigmaError> {
+        S::challenge_from_bytes(challenge, expansion)
+    }
+
+    fn validate_instance(&self) -> Result<(), SigmaError> {
+        self.protocols.iter().try_for_each(S::validate_instance)
+    }
+
+    fn instance_bytes(&self) -> Vec<u8> {
+        canonical_bytes(
+            &self
+                .protocols
+                .iter()
+                .map(S::instance_bytes)
+                .collect::<Vec<_>>(),
+        )
+    }
+
+    fn witness_bytes(&self, witness: &Self::Witness) -> Vec<u8> {
+        canonical_bytes(
+            &self
+                .protocols
+                .iter()
+                .zip(witness)
+                .map(|(protocol, witness)| protocol.witness_bytes(witness))
+                .collect::<Vec<_>>(),
+        )
+    }
+
+    fn is_valid_witness(&self, witness: &Self::Witness) -> bool {
+        self.protocols.len() == witness.len()
+            && self
+                .protocols
+                .iter()
+                .zip(witness)
+                .all(|(protocol, witness)| protocol.is_valid_witness(witness))
+    }
+
+    fn prover_commit<R: Rng>(
+        &self,
+        witness: &Self::Witness,
+        expansion: ChallengeExpansion,
+        rng: &mut R,
+    ) -> Result<(Self::Commitment, Self::ProverState), SigmaError> {
+        self.check_length(witness.len())?;
+
+        self.protocols
+            .iter()
+            .zip(witness)
+            .map(|(protocol, witness)| protocol.prover_commit(witness, expansion, rng))
+            .collect::<Result<Vec<_>, _>>()
+            .map(|commitments_and_states| commitments_and_states.into_iter().unzip())
+    }
+
+    fn prover_response(
+        &self,
+        prover_state: &Self::ProverState,
+        challenge: &Self::Challenge,
+    ) -> Result<Self::Response, SigmaError> {
+        self.check_length(prover_state.len())?;
+
+        self.protocols
+            .iter()
+            .zip(prover_state)
+            .map(|(protocol, state)| protocol.prover_response(state, challenge))
+            .collect()
+    }
+
+    fn verifier(
+        &self,
+        commitment: &Self::Commitment,
+        challenge: &Self::Challenge,
+        response: &Self::Response,
+    ) -> Result<(), SigmaError> {
+        self.check_length(commitment.len())?;
+        self.check_length(response.len())?;
+
+        self.protocols
+            .iter()
+            .zip(commitment)
+            .zip(response)
+            .try_for_each(|((protocol, commitment), response)| {
+                protocol.verifier(commitment, challenge, response)
+            })
+    }
+
+    fn simulate_response<R: Rng>(&self, rng: &mut R) -> Self::Response {
+        self.protocols
+            .iter()
+            .map(|protocol| protocol.simulate_response(rng))
+            .collect()
+    }
+
+    fn zeroize_state(&self, prover_state: &mut Self::ProverState) {
+        for (protocol, state) in self.protocols.iter().zip(prover_state) {
+            protocol.zeroize_state(state);
+        }
+    }
+}
+
+impl<S: CommitmentRecoverable> CommitmentRecoverable for AndMany<S> {
+    fn simulate_commitment(
+        &self,
+        challenge: &Self::Challenge,
+        response: &Self::Response,
+    ) -> Result<Self::Commitment, SigmaError> {
+        self.check_length(response.len())?;
+
+        self.protocols
+            .iter()
+            .zip(response)
+            .map(|(protocol, response)| protocol.simulate_commitment(challenge, response))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_ff::UniformRand;
+    use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+    use rand::{thread_rng, Rng};
+
+    use crate::{
+        nizk_proofs::tests::{run_nizk_batched, run_nizk_short},
+        protocols::{Okamoto, OkamotoInstance, PedersenOpening, PedersenParams},
+        BatchableProof, SigmaError, SigmaProtocol, NIZK,
+    };
+
+    use super::AndMany;
+
+    type Hash = blake2::Blake2s;
+    type G = ark_bls12_377::G1Projective;
+    type F = ark_bls12_377::Fr;
+
+    type PedersenOpenings = AndMany<Okamoto<G>>;
+
+    const OPENINGS: usize = 30;
+
+    /// Knowledge of the openings of `count` Pedersen commitments, as Okamoto instances
+    fn pedersen_openings_setup<R: Rng>(
+        count: usize,
+        rng: &mut R,
+    ) -> (Vec<OkamotoInstance<G>>, Vec<(F, F)>) {
+        let params = PedersenParams::new(G::rand(rng), G::rand(rng));
+
+        (0..count)
+            .map(|_| {
+                let opening = PedersenOpening::new(F::rand(rng), F::rand(rng));
+                let instance = OkamotoInstance::new(
+                    params.generator(),
+                    params.blinding_base(),
+                    params.commit_opening(&opening),
+                );
+
+                (instance, (opening.message(), opening.blinding()))
+            })
+            .unzip()
+    }
+
+    #[test]
+    fn test_and_many_accept_valid() {
+        let rng = &mut thread_rng();
+        let (instance, witness) = pedersen_openings_setup(OPENINGS, rng);
+
+        let batched_result =
+            run_nizk_batched::<Hash, PedersenOpenings, _>(&instance, &witness, rng);
+        let short_result = run_nizk_short::<Hash, PedersenOpenings, _>(&instance, &witness, rng);
+
+        assert!(batched_result.is_ok());
+        assert!(short_result.is_ok());
+    }
+
+    #[test]
+    fn test_and_many_reject_one_wrong_witness() {
+        let rng = &mut thread_rng();
+        let (instance, mut witness) = pedersen_openings_setup(OPENINGS, rng);
+        witness[OPENINGS / 2].1 = F::rand(rng);
+
+        let test_result = run_nizk_batched::<Hash, PedersenOpenings, _>(&instance, &witness, rng);
+
+        assert_eq!(test_result, Err(SigmaError::VerificationFailed));
+    }
+
+    #[test]
+    fn test_and_many_reject_length_mismatch() {
+        let rng = &mut thread_rng();
+        let (instance, mut witness) = pedersen_openings_setup(OPENINGS, rng);
+        witness.pop();
+
+        let test_result = run_nizk_batched::<Hash, PedersenOpenings, _>(&instance, &witness, rng);
+
+        assert_eq!(test_result, Err(SigmaError::LengthMismatch));
+    }
+
+    #[test]
+    fn test_and_many_batchable_proof_round_trip() {
+        let rng = &mut thread_rng();
+        let (instance, witness) = pedersen_openings_setup(OPENINGS, rng);
+        let nizk = NIZK::<PedersenOpenings, Hash>::new(&instance, b"ctx");
+
+        let proof = nizk.batchable_proof(&witness, None, rng).unwrap();
+        let mut bytes = Vec::new();
+        proof.serialize(&mut bytes).unwrap();
+        let decoded = BatchableProof::<PedersenOpenings>::deserialize(&bytes[..]).unwrap();
+
+        assert!(nizk.batchable_verify(&decoded, None).is_ok());
+
+        // The decoded vectors keep their length, which no longer matches a shorter list of instances
+        let truncated_nizk = NIZK::<PedersenOpenings, Hash>::new(&instance[1..].to_vec(), b"ctx");
+        assert_eq!(
+            truncated_nizk.batchable_verify(&decoded, None),
+            Err(SigmaError::VerificationFailed)
+        );
+        assert_eq!(
+            truncated_nizk.batchable_verify_detailed(&decoded, None),
+            Err(SigmaError::LengthMismatch)
+        );
+    }
+
+    #[test]
+    fn test_and_many_label_binds_arity() {
+        let rng = &mut thread_rng();
+        let (instance, _) = pedersen_openings_setup(2, rng);
+
+        assert_ne!(
+            PedersenOpenings::new(&instance).label(),
+            PedersenOpenings::new(&instance[..1].to_vec()).label()
+        );
+    }
+}
@@ -0,0 +1,444 @@
+use std::{any::Any, rc::Rc};
+
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, SerializationError};
+use rand::{Rng, RngCore};
+
+use super::{AndMany, ChallengeBytes, OrMany};
+use crate::{
+    Challenge, ChallengeExpansion, CommitmentRecoverable, SigmaError, SigmaProtocol, LABEL_LENGTH,
+};
+
+/// An object-safe view of a Sigma protocol, bound to its instance, so that protocols of different types can be
+/// composed at runtime.
+///
+/// Commitments and responses are exchanged as their canonical encodings, and witnesses and prover states as
+/// [`Any`] values of the underlying protocol's types. Challenges are exchanged as their bytes, which the underlying
+/// protocol converts into its own challenge space with the expansion they carry. Every [`SigmaProtocol`] whose witness and prover state are
+/// `'static` implements it, so a protocol is made dynamic with `Rc::new(S::new(&instance))`. Undecodable
+/// commitments or responses are rejected with [`SigmaError::SerializationError`], and witnesses or prover states of
+/// the wrong type with [`SigmaError::LengthMismatch`], as they do not fit the instance.
+pub trait DynSigmaProtocol {
+    /// The label of the underlying protocol
+    fn label(&self) -> [u8; LABEL_LENGTH];
+
+    /// The canonical encoding of the underlying instance
+    fn instance_bytes(&self) -> Vec<u8>;
+
+    /// Checks the underlying instance, see [`SigmaProtocol::validate_instance`]
+    fn validate_instance(&self) -> Result<(), SigmaError>;
+
+    /// The canonical encoding of a witness of the underlying protocol's type, see
+    /// [`SigmaProtocol::witness_bytes`]. A witness of another type, which the prover rejects, encodes to no bytes
+    fn witness_bytes(&self, witness: &dyn Any) -> Vec<u8>;
+
+    /// Commits with a witness of the underlying protocol's type, returning the encoded commitment
+    fn prover_commit(
+        &self,
+        witness: &dyn Any,
+        expansion: ChallengeExpansion,
+        rng: &mut dyn RngCore,
+    ) -> Result<(Vec<u8>, Box<dyn Any>), SigmaError>;
+
+    /// Answers the challenge from a prover state returned by [`DynSigmaProtocol::prover_commit`]
+    fn prover_response(
+        &self,
+        prover_state: &dyn Any,
+        challenge: &ChallengeBytes,
+    ) -> Result<Vec<u8>, SigmaError>;
+
+    /// Verifies an encoded transcript
+    fn verifier(
+        &self,
+        commitment: &[u8],
+        challenge: &ChallengeBytes,
+        response: &[u8],
+    ) -> Result<(), SigmaError>;
+
+    /// Samples an encoded response for simulation
+    fn simulate_response(&self, rng: &mut dyn RngCore) -> Vec<u8>;
+
+    /// Recomputes the encoded commitment from a challenge and an encoded response
+    fn simulate_commitment(
+        &self,
+        challenge: &ChallengeBytes,
+        response: &[u8],
+    ) -> Result<Vec<u8>, SigmaError>;
+
+    /// Wipes a prover state returned by [`DynSigmaProtocol::prover_commit`], see
+    /// [`SigmaProtocol::zeroize_state`]. A state of another type is left as is
+    fn zeroize_state(&self, prover_state: &mut dyn Any);
+}
+
+fn encode<X: CanonicalSerialize>(value: &X) -> Result<Vec<u8>, SigmaError> {
+    let mut bytes = Vec::new();
+    value.serialize(&mut bytes)?;
+    Ok(bytes)
+}
+
+/// Decodes a value, rejecting trailing bytes so that every value has a single encoding
+fn decode<X: CanonicalDeserialize>(mut bytes: &[u8]) -> Result<X, SigmaError> {
+    let value = X::deserialize(&mut bytes)?;
+    if bytes.is_empty() {
+        Ok(value)
+    } else {
+        Err(SerializationError::InvalidData.into())
+    }
+}
+
+fn downcast<X: 'static>(value: &dyn Any) -> Result<&X, SigmaError> {
+    value.downcast_ref().ok_or(SigmaError::LengthMismatch)
+}
+
+impl<S: CommitmentRecoverable> DynSigmaProtocol for S
+where
+    S::Witness: 'static,
+    S::ProverState: 'static,
+{
+    fn label(&self) -> [u8; LABEL_LENGTH] {
+        SigmaProtocol::label(self)
+    }
+
+    fn instance_bytes(&self) -> Vec<u8> {
+        SigmaProtocol::instance_bytes(self)
+    }
+
+    fn validate_instance(&self) -> Result<(), SigmaError> {
+        SigmaProtocol::validate_instance(self)
+    }
+
+    fn witness_bytes(&self, witness: &dyn Any) -> Vec<u8> {
+        downcast::<S::Witness>(witness).map_or_else(
+            |_| Vec::new(),
+            |witness| SigmaProtocol::witness_bytes(self, witness),
+        )
+    }
+
+    fn prover_commit(
+        &self,
+        witness: &dyn Any,
+        expansion: ChallengeExpansion,
+        mut rng: &mut dyn RngCore,
+    ) -> Result<(Vec<u8>, Box<dyn Any>), SigmaError> {
+        let witness = downcast::<S::Witness>(witness)?;
+        let (commitment, state) = SigmaProtocol::prover_commit(self, witness, expansion, &mut rng)?;
+        Ok((encode(&commitment)?, Box::new(state)))
+    }
+
+    fn prover_response(
+        &self,
+        prover_state: &dyn Any,
+        challenge: &ChallengeBytes,
+    ) -> Result<Vec<u8>, SigmaError> {
+        let state = downcast::<S::ProverState>(prover_state)?;
+        encode(&SigmaProtocol::prover_response(
+            self,
+            state,
+            &S::challenge_from_bytes(&challenge.bytes, challenge.expansion)?,
+        )?)
+    }
+
+    fn verifier(
+        &self,
+        commitment: &[u8],
+        challenge: &ChallengeBytes,
+        response: &[u8],
+    ) -> Result<(), SigmaError> {
+        SigmaProtocol::verifier(
+            self,
+            &decode(commitment)?,
+            &S::challenge_from_bytes(&challenge.bytes, challenge.expansion)?,
+            &decode(response)?,
+        )
+    }
+
+    fn simulate_response(&self, mut rng: &mut dyn RngCore) -> Vec<u8> {
+        encode(&SigmaProtocol::simulate_response(self, &mut rng))
+            .expect("simulated responses have a canonical encoding")
+    }
+
+    fn simulate_commitment(
+        &self,
+        challenge: &ChallengeBytes,
+        response: &[u8],
+    ) -> Result<Vec<u8>, SigmaError> {
+        encode(&CommitmentRecoverable::simulate_commitment(
+            self,
+            &S::challenge_from_bytes(&challenge.bytes, challenge.expansion)?,
+            &decode(response)?,
+        )?)
+    }
+
+    fn zeroize_state(&self, prover_state: &mut dyn Any) {
+        if let Some(state) = prover_state.downcast_mut::<S::ProverState>() {
+            SigmaProtocol::zeroize_state(self, state);
+        }
+    }
+}
+
+/// A protocol assembled at runtime, shared between the composition and the caller
+pub type DynInstance = Rc<dyn DynSigmaProtocol>;
+
+/// The [`SigmaProtocol`] running a [`DynSigmaProtocol`], whose instance is the dynamic protocol itself.
+///
+/// Its commitment and response are the encodings produced by the dynamic protocol, and its label is the label of
+/// the underlying protocol, so that a composition of dynamic protocols hashes each sub-protocol's label and
+/// encoded commitment in order.
+pub struct DynProtocol {
+    protocol: DynInstance,
+}
+
+impl SigmaProtocol for DynProtocol {
+    type Instance = DynInstance;
+    type Commitment = Vec<u8>;
+    type ProverState = Box<dyn Any>;
+    type Witness = Box<dyn Any>;
+    type Response = Vec<u8>;
+    type Challenge = ChallengeBytes;
+
+    const PROTOCOL_ID: &'static [u8] = b"zkpstd/sigma/dyn";
+
+    fn label(&self) -> [u8; LABEL_LENGTH] {
+        self.protocol.label()
+    }
+
+    fn new(instance: &Self::Instance) -> Self {
+        Self {
+            protocol: Rc::clone(instance),
+        }
+    }
+
+    fn challenge_from_bytes(
+        challenge: &Challenge,
+        expansion: ChallengeExpansion,
+    ) -> Result<ChallengeBytes, SigmaError> {
+        Ok(ChallengeBytes {
+            bytes: *challenge,
+            expansion,
+        })
+    }
+
+    fn instance_bytes(&self) -> Vec<u8> {
+        self.protocol.instance_bytes()
+    }
+
+    fn validate_instance(&self) -> Result<(), SigmaError> {
+        self.protocol.validate_instance()
+    }
+
+    fn witness_bytes(&self, witness: &Self::Witness) -> Vec<u8> {
+        self.protocol.witness_bytes(&**witness)
+    }
+
+    fn prover_commit<R: Rng>(
+        &self,
+        witness: &Self::Witness,
+        expansion: ChallengeExpansion,
+        rng: &mut R,
+    ) -> Result<(Self::Commitment, Self::ProverState), SigmaError> {
+        self.protocol
+            .prover_commit(witness.as_ref(), expansion, rng)
+    }
+
+    fn prover_response(
+        &self,
+        prover_state: &Self::ProverState,
+        challenge: &ChallengeBytes,
+    ) -> Result<Self::Response, SigmaError> {
+        self.protocol
+            .prover_response(prover_state.as_ref(), challenge)
+    }
+
+    fn verifier(
+        &self,
+        commitment: &Self::Commitment,
+        challenge: &ChallengeBytes,
+        response: &Self::Response,
+    ) -> Result<(), SigmaError> {
+        self.protocol.verifier(commitment, challenge, response)
+    }
+
+    fn simulate_response<R: Rng>(&self, rng: &mut R) -> Self::Response {
+        self.protocol.simulate_response(rng)
+    }
+
+    fn zeroize_state(&self, prover_state: &mut Self::ProverState) {
+        self.protocol.zeroize_state(prover_state.as_mut())
+    }
+}
+
+impl CommitmentRecoverable for DynProtocol {
+    fn simulate_commitment(
+        &self,
+        challenge: &ChallengeBytes,
+        response: &Self::Response,
+    ) -> Result<Self::Commitment, SigmaError> {
+        self.protocol.simulate_commitment(challenge, response)
+    }
+}
+
+/// AND composition of protocols of different types assembled at runtime. The witness holds one boxed witness per
+/// protocol, of that protocol's witness type
+pub type AndDyn = AndMany<DynProtocol>;
+
+/// OR composition of protocols of different types assembled at runtime. The witness is the index of the known
+/// protocol and its boxed witness
+pub type OrDyn = OrMany<DynProtocol>;
+
+#[cfg(test)]
+mod tests {
+    use std::{any::Any, io, rc::Rc};
+
+    use ark_ec::ProjectiveCurve;
+    use ark_ff::{PrimeField, UniformRand};
+    use ark_serialize::SerializationError;
+    use rand::{thread_rng, Rng};
+
+    use crate::{
+        nizk_proofs::tests::{run_nizk_batched, run_nizk_short},
+        protocols::{
+            Okamoto, OkamotoInstance, PedersenOpening, PedersenParams, SchnorrDLOG, SchnorrInstance,
+        },
+        ChallengeExpansion, SigmaError, SigmaProtocol,
+    };
+
+    use super::{AndDyn, ChallengeBytes, DynInstance, OrDyn};
+
+    type Hash = blake2::Blake2s;
+    type G = ark_bls12_377::G1Projective;
+    type F = ark_bls12_377::Fr;
+
+    /// A Schnorr key and a Pedersen opening as dynamic protocols, with their witnesses
+    fn key_and_opening_setup<R: Rng>(rng: &mut R) -> (Vec<DynInstance>, Vec<Box<dyn Any>>) {
+        let generator = G::prime_subgroup_generator();
+        let secret = F::rand(rng);
+        let key = SchnorrInstance::new(generator, generator.mul(secret.into_repr()));
+
+        let params = PedersenParams::new(G::rand(rng), G::rand(rng));
+        let opening = PedersenOpening::new(F::rand(rng), F::rand(rng));
+        let commitment = OkamotoInstance::new(
+            params.generator(),
+            params.blinding_base(),
+            params.commit_opening(&opening),
+        );
+
+        let instance: Vec<DynInstance> = vec![
+            Rc::new(SchnorrDLOG::new(&key)),
+            Rc::new(Okamoto::new(&commitment)),
+        ];
+        let witness: Vec<Box<dyn Any>> = vec![
+            Box::new(secret),
+            Box::new((opening.message(), opening.blinding())),
+        ];
+
+        (instance, witness)
+    }
+
+    #[test]
+    fn test_and_dyn_accept_valid() {
+        let rng = &mut thread_rng();
+        let (instance, witness) = key_and_opening_setup(rng);
+
+        let batched_result = run_nizk_batched::<Hash, AndDyn, _>(&instance, &witness, rng);
+        let short_result = run_nizk_short::<Hash, AndDyn, _>(&instance, &witness, rng);
+
+        assert!(batched_result.is_ok());
+        assert!(short_result.is_ok());
+    }
+
+    #[test]
+    fn test_and_dyn_reject_wrong_witness() {
+        let rng = &mut thread_rng();
+        let (instance, mut witness) = key_and_opening_setup(rng);
+        witness[0] = Box::new(F::rand(rng));
+
+        let test_result = run_nizk_batched::<Hash, AndDyn, _>(&instance, &witness, rng);
+
+        assert_eq!(test_result, Err(SigmaError::VerificationFailed));
+    }
+
+    #[test]
+    fn test_and_dyn_reject_witness_of_wrong_type() {
+        let rng = &mut thread_rng();
+        let (instance, mut witness) = key_and_opening_setup(rng);
+        witness.swap(0, 1);
+
+        let test_result = run_nizk_batched::<Hash, AndDyn, _>(&instance, &witness, rng);
+
+        assert_eq!(test_result, Err(SigmaError::LengthMismatch));
+    }
+
+    #[test]
+    fn test_dyn_reject_undecodable_transcript() {
+        let rng = &mut thread_rng();
+        let (instance, witness) = key_and_opening_setup(rng);
+        let schnorr = &instance[0];
+
+        let expansion = ChallengeExpansion::default();
+        let challenge = ChallengeBytes {
+            bytes: [1u8; 32],
+            expansion,
+        };
+        let (commitment, state) = schnorr
+            .prover_commit(witness[0].as_ref(), expansion, rng)
+            .unwrap();
+        let response = schnorr.prover_response(state.as_ref(), &challenge).unwrap();
+        let mut padded_response = response.clone();
+        padded_response.push(0);
+
+        assert_eq!(
+            schnorr.verifier(&commitment[1..], &challenge, &response),
+            Err(SerializationError::IoError(io::ErrorKind::UnexpectedEof.into()).into())
+        );
+        assert_eq!(
+            schnorr.verifier(&commitment, &challenge, &padded_response),
+            Err(SerializationError::InvalidData.into())
+        );
+    }
+
+    #[test]
+    fn test_or_dyn_accept_either_branch() {
+        let rng = &mut thread_rng();
+        let (instance, witnesses) = key_and_opening_setup(rng);
+
+        for (index, witness) in witnesses.into_iter().enumerate() {
+            let witness = (index, witness);
+            let test_result = run_nizk_batched::<Hash, OrDyn, _>(&instance, &witness, rng);
+
+            assert!(test_result.is_ok());
+        }
+    }
+
+    #[test]
+    fn test_nested_dyn_compositions() {
+        let rng = &mut thread_rng();
+        let (instance, witness) = key_and_opening_setup(rng);
+        let (other_instance, _) = key_and_opening_setup(rng);
+
+        // (knows the key AND the opening) OR knows another key, assembled at runtime
+        let instance: Vec<DynInstance> = vec![
+            Rc::new(AndDyn::new(&instance)),
+            Rc::clone(&other_instance[0]),
+        ];
+        let witness: (usize, Box<dyn Any>) = (0, Box::new(witness));
+
+        let test_result = run_nizk_short::<Hash, OrDyn, _>(&instance, &witness, rng);
+
+        assert!(test_result.is_ok());
+    }
+
+    #[test]
+    fn test_dyn_label_follows_components() {
+        let rng = &mut thread_rng();
+        let (instance, _) = key_and_opening_setup(rng);
+        let reversed: Vec<_> = instance.iter().rev().cloned().collect();
+
+        assert_ne!(
+            AndDyn::new(&instance).label(),
+            AndDyn::new(&reversed).label()
+        );
+        assert_ne!(
+            AndDyn::new(&instance).label(),
+            OrDyn::new(&instance).label()
+        );
+    }
+}
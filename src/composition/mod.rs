@@ -0,0 +1,45 @@
+use crate::{Challenge, ChallengeExpansion};
+
+mod and;
+pub use and::AndComposition;
+
+mod or;
+pub use or::{Either, OrComposition, OrResponse};
+
+mod and_many;
+pub use and_many::AndMany;
+
+mod or_many;
+pub use or_many::{OrMany, OrManyResponse, OrManyWitness};
+
+mod threshold;
+pub use threshold::{Threshold, ThresholdInstance, ThresholdResponse, ThresholdWitness};
+
+mod repeated;
+pub use repeated::{Repeated, Repetitions};
+
+mod shared_witness;
+pub use shared_witness::{SharedWitnessAnd, SharedWitnessInstance};
+
+mod dynamic;
+pub use dynamic::{AndDyn, DynInstance, DynProtocol, DynSigmaProtocol, OrDyn};
+
+/// The challenge of the compositions that split it before their components read it, as the OR compositions do:
+/// the bytes, and the expansion the components convert their shares with
+#[derive(Debug, Clone, Copy)]
+pub struct ChallengeBytes {
+    /// The bytes of the challenge
+    pub bytes: Challenge,
+    /// The expansion of the transcript the challenge was derived with
+    pub expansion: ChallengeExpansion,
+}
+
+/// Combines two sub-challenges of an OR composition, which must XOR to the challenge of the composition
+pub(crate) fn xor_challenges(left: &Challenge, right: &Challenge) -> Challenge {
+    let mut challenge = *left;
+    challenge
+        .iter_mut()
+        .zip(right)
+        .for_each(|(byte, other)| *byte ^= other);
+    challenge
+}
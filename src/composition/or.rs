@@ -0,0 +1,475 @@
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Read, SerializationError, Write};
+use rand::Rng;
+
+use super::{xor_challenges, ChallengeBytes};
+use crate::{
+    interactive_proofs::canonical_bytes, interactive_proofs::protocol_label, Challenge,
+    ChallengeExpansion, CommitmentRecoverable, SigmaError, SigmaProtocol, CHALLENGE_LENGTH,
+    LABEL_LENGTH,
+};
+
+/// A witness for one of two statements, indicating which one the prover knows
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Either<L, R> {
+    /// A witness for the first statement
+    Left(L),
+    /// A witness for the second statement
+    Right(R),
+}
+
+/// OR composition of two Sigma protocols, following Cramer, Damgård and Schoenmakers: the prover knows a witness
+/// for at least one of the two instances.
+///
+/// The prover simulates the branch it has no witness for with a random challenge, and answers the other branch
+/// honestly with the challenge `c1 = c XOR c2`. The response carries both sub-challenges and both sub-responses,
+/// and the verifier checks that the sub-challenges recombine into the challenge.
+pub struct OrComposition<S1: SigmaProtocol, S2: SigmaProtocol> {
+    first: S1,
+    second: S2,
+}
+
+/// The response of an OR composition: the challenge and response of each branch
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OrResponse<R1, R2> {
+    pub(super) challenge1: Challenge,
+    pub(super) challenge2: Challenge,
+    pub(super) response1: R1,
+    pub(super) response2: R2,
+}
+
+impl<R1: CanonicalSerialize, R2: CanonicalSerialize> CanonicalSerialize for OrResponse<R1, R2> {
+    fn serialize<W: Write>(&self, mut writer: W) -> Result<(), SerializationError> {
+        writer.write_all(&self.challenge1)?;
+        writer.write_all(&self.challenge2)?;
+        self.response1.serialize(&mut writer)?;
+        self.response2.serialize(&mut writer)
+    }
+
+    fn serialized_size(&self) -> usize {
+        2 * CHALLENGE_LENGTH + self.response1.serialized_size() + self.response2.serialized_size()
+    }
+}
+
+impl<R1: CanonicalDeserialize, R2: CanonicalDeserialize> CanonicalDeserialize
+    for OrResponse<R1, R2>
+{
+    fn deserialize<R: Read>(mut reader: R) -> Result<Self, SerializationError> {
+        let mut challenge1 = [0u8; CHALLENGE_LENGTH];
+        let mut challenge2 = [0u8; CHALLENGE_LENGTH];
+        reader.read_exact(&mut challenge1)?;
+        reader.read_exact(&mut challenge2)?;
+
+        Ok(Self {
+            challenge1,
+            challenge2,
+            response1: R1::deserialize(&mut reader)?,
+            response2: R2::deserialize(&mut reader)?,
+        })
+    }
+}
+
+/// The prover state of an OR composition: the state of the real branch, and the transcript of the simulated one
+pub enum ProverState<S1: SigmaProtocol, S2: SigmaProtocol> {
+    Left {
+        state: S1::ProverState,
+        simulated_challenge: Challenge,
+        simulated_response: S2::Response,
+    },
+    Right {
+        state: S2::ProverState,
+        simulated_challenge: Challenge,
+        simulated_response: S1::Response,
+    },
+}
+
+impl<S1: CommitmentRecoverable, S2: CommitmentRecoverable> SigmaProtocol for OrComposition<S1, S2>
+where
+    S1::Response: Clone,
+    S2::Response: Clone,
+{
+    type Instance = (S1::Instance, S2::Instance);
+    type Commitment = (S1::Commitment, S2::Commitment);
+    type ProverState = ProverState<S1, S2>;
+    type Witness = Either<S1::Witness, S2::Witness>;
+    type Response = OrResponse<S1::Response, S2::Response>;
+    /// The bytes, which split into the sub-challenges before each branch converts its own
+    type Challenge = ChallengeBytes;
+
+    const PROTOCOL_ID: &'static [u8] = b"zkpstd/sigma/or";
+
+    /// The longer of the minimum challenge lengths of the components, which both see the challenge
+    const MIN_CHALLENGE_LENGTH: usize = if S1::MIN_CHALLENGE_LENGTH > S2::MIN_CHALLENGE_LENGTH {
+        S1::MIN_CHALLENGE_LENGTH
+    } else {
+        S2::MIN_CHALLENGE_LENGTH
+    };
+
+    fn label(&self) -> [u8; LABEL_LENGTH] {
+        protocol_label(
+            Self::PROTOCOL_ID,
+            &[self.first.label(), self.second.label()].concat(),
+        )
+    }
+
+    fn new(instance: &Self::Instance) -> Self {
+        Self {
+            first: S1::new(&instance.0),
+            second: S2::new(&instance.1),
+        }
+    }
+
+    fn challenge_from_bytes(
+        challenge: &Challenge,
+        expansion: ChallengeExpansion,
+    ) -> Result<ChallengeBytes, SigmaError> {
+        Ok(ChallengeBytes {
+            bytes: *challenge,
+            expansion,
+        })
+    }
+
+    fn validate_instance(&self) -> Result<(), SigmaError> {
+        self.first.validate_instance()?;
+        self.second.validate_instance()
+    }
+
+    fn instance_bytes(&self) -> Vec<u8> {
+        canonical_bytes(&(self.first.instance_bytes(), self.second.instance_bytes()))
+    }
+
+    /// The side of the known witness, then its encoding
+    fn witness_bytes(&self, witness: &Self::Witness) -> Vec<u8> {
+        match witness {
+            Either::Left(witness) => canonical_bytes(&(0u8, self.first.witness_bytes(witness))),
+            Either::Right(witness) => canonical_bytes(&(1u8, self.second.witness_bytes(witness))),
+        }
+    }
+
+    fn is_valid_witness(&self, witness: &Self::Witness) -> bool {
+        match witness {
+            Either::Left(witness) => self.first.is_valid_witness(witness),
+            Either::Right(witness) => self.second.is_valid_witness(witness),
+        }
+    }
+
+    fn prover_commit<R: Rng>(
+        &self,
+        witness: &Self::Witness,
+        expansion: ChallengeExpansion,
+        rng: &mut R,
+    ) -> Result<(Self::Commitment, Self::ProverState), SigmaError> {
+        match witness {
+            Either::Left(witness) => {
+                let (simulated_commitment, simulated_challenge, simulated_response) =
+                    self.second.simulate_transcript(expansion, rng)?;
+                let (commitment, state) = self.first.prover_commit(witness, expansion, rng)?;
+                let state = ProverState::Left {
+                    state,
+                    simulated_challenge,
+                    simulated_response,
+                };
+
+                Ok(((commitment, simulated_commitment), state))
+            }
+            Either::Right(witness) => {
+                let (simulated_commitment, simulated_challenge, simulated_response) =
+                    self.first.simulate_transcript(expansion, rng)?;
+                let (commitment, state) = self.second.prover_commit(witness, expansion, rng)?;
+                let state = ProverState::Right {
+                    state,
+                    simulated_challenge,
+                    simulated_response,
+                };
+
+                Ok(((simulated_commitment, commitment), state))
+            }
+        }
+    }
+
+    fn prover_response(
+        &self,
+        prover_state: &Self::ProverState,
+        challenge: &ChallengeBytes,
+    ) -> Result<Self::Response, SigmaError> {
+        match prover_state {
+            ProverState::Left {
+                state,
+                simulated_challenge,
+                simulated_response,
+            } => {
+                let challenge1 = xor_challenges(&challenge.bytes, simulated_challenge);
+
+                Ok(OrResponse {
+                    challenge1,
+                    challenge2: *simulated_challenge,
+                    response1: self.first.prover_response(
+                        state,
+                        &S1::challenge_from_bytes(&challenge1, challenge.expansion)?,
+                    )?,
+                    response2: simulated_response.clone(),
+                })
+            }
+            ProverState::Right {
+                state,
+                simulated_challenge,
+                simulated_response,
+            } => {
+                let challenge2 = xor_challenges(&challenge.bytes, simulated_challenge);
+
+                Ok(OrResponse {
+                    challenge1: *simulated_challenge,
+                    challenge2,
+                    response1: simulated_response.clone(),
+                    response2: self.second.prover_response(
+                        state,
+                        &S2::challenge_from_bytes(&challenge2, challenge.expansion)?,
+                    )?,
+                })
+            }
+        }
+    }
+
+    fn verifier(
+        &self,
+        commitment: &Self::Commitment,
+        challenge: &ChallengeBytes,
+        response: &Self::Response,
+    ) -> Result<(), SigmaError> {
+        if xor_challenges(&response.challenge1, &response.challenge2) != challenge.bytes {
+            return Err(SigmaError::VerificationFailed);
+        }
+
+        self.first.verifier(
+            &commitment.0,
+            &S1::challenge_from_bytes(&response.challenge1, challenge.expansion)?,
+            &response.response1,
+        )?;
+        self.second.verifier(
+            &commitment.1,
+            &S2::challenge_from_bytes(&response.challenge2, challenge.expansion)?,
+            &response.response2,
+        )
+    }
+
+    fn simulate_response<R: Rng>(&self, rng: &mut R) -> Self::Response {
+        OrResponse {
+            challenge1: rng.gen(),
+            challenge2: rng.gen(),
+            response1: self.first.simulate_response(rng),
+            response2: self.second.simulate_response(rng),
+        }
+    }
+
+    fn zeroize_state(&self, prover_state: &mut Self::ProverState) {
+        match prover_state {
+            ProverState::Left { state, .. } => self.first.zeroize_state(state),
+            ProverState::Right { state, .. } => self.second.zeroize_state(state),
+        }
+    }
+}
+
+impl<S1: CommitmentRecoverable, S2: CommitmentRecoverable> CommitmentRecoverable
+    for OrComposition<S1, S2>
+where
+    S1::Response: Clone,
+    S2::Response: Clone,
+{
+    fn simulate_commitment(
+        &self,
+        challenge: &ChallengeBytes,
+        response: &Self::Response,
+    ) -> Result<Self::Commitment, SigmaError> {
+        if xor_challenges(&response.challenge1, &response.challenge2) != challenge.bytes {
+            return Err(SigmaError::VerificationFailed);
+        }
+
+        Ok((
+            self.first.simulate_commitment(
+                &S1::challenge_from_bytes(&response.challenge1, challenge.expansion)?,
+                &response.response1,
+            )?,
+            self.second.simulate_commitment(
+                &S2::challenge_from_bytes(&response.challenge2, challenge.expansion)?,
+                &response.response2,
+            )?,
+        ))
+    }
+
+    /// Simulates both branches and combines their challenges, as a response of `simulate_response` only fits the
+    /// challenge its sub-challenges XOR to
+    fn simulate_transcript<R: Rng>(
+        &self,
+        expansion: ChallengeExpansion,
+        rng: &mut R,
+    ) -> Result<(Self::Commitment, Challenge, Self::Response), SigmaError> {
+        let (commitment1, challenge1, response1) =
+            self.first.simulate_transcript(expansion, rng)?;
+        let (commitment2, challenge2, response2) =
+            self.second.simulate_transcript(expansion, rng)?;
+
+        Ok((
+            (commitment1, commitment2),
+            xor_challenges(&challenge1, &challenge2),
+            OrResponse {
+                challenge1,
+                challenge2,
+                response1,
+                response2,
+            },
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_ec::ProjectiveCurve;
+    use ark_ff::{PrimeField, UniformRand};
+    use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+    use rand::{thread_rng, Rng};
+
+    use crate::{
+        composition::xor_challenges,
+        nizk_proofs::tests::{run_nizk_batched, run_nizk_short},
+        protocols::{SchnorrDLOG, SchnorrInstance},
+        testing::interactive::run_interactive,
+        BatchableProof, ChallengeExpansion, SigmaError, SigmaProtocol, NIZK,
+    };
+
+    use super::{Either, OrComposition};
+
+    type Hash = blake2::Blake2s;
+    type G = ark_bls12_377::G1Projective;
+    type F = ark_bls12_377::Fr;
+
+    type SchnorrOr = OrComposition<SchnorrDLOG<G>, SchnorrDLOG<G>>;
+
+    /// Two Schnorr instances and the witness of each
+    fn schnorr_or_setup<R: Rng>(rng: &mut R) -> ((SchnorrInstance<G>, SchnorrInstance<G>), F, F) {
+        let base = G::rand(rng);
+        let (witness1, witness2) = (F::rand(rng), F::rand(rng));
+        let instance = (
+            SchnorrInstance::new(base, base.mul(witness1.into_repr())),
+            SchnorrInstance::new(base, base.mul(witness2.into_repr())),
+        );
+
+        (instance, witness1, witness2)
+    }
+
+    fn batchable_proof<R: Rng>(
+        nizk: &NIZK<SchnorrOr, Hash>,
+        witness: &Either<F, F>,
+        rng: &mut R,
+    ) -> BatchableProof<SchnorrOr> {
+        nizk.batchable_proof(witness, None, rng).unwrap()
+    }
+
+    #[test]
+    fn test_or_accept_either_branch() {
+        let rng = &mut thread_rng();
+        let (instance, witness1, witness2) = schnorr_or_setup(rng);
+
+        for witness in [Either::Left(witness1), Either::Right(witness2)] {
+            let batched_result = run_nizk_batched::<Hash, SchnorrOr, _>(&instance, &witness, rng);
+            let short_result = run_nizk_short::<Hash, SchnorrOr, _>(&instance, &witness, rng);
+
+            assert!(batched_result.is_ok());
+            assert!(short_result.is_ok());
+        }
+    }
+
+    #[test]
+    fn test_or_interactive_completeness() {
+        let rng = &mut thread_rng();
+        let (instance, witness1, witness2) = schnorr_or_setup(rng);
+
+        for witness in [Either::Left(witness1), Either::Right(witness2)] {
+            assert!(run_interactive::<SchnorrOr, _>(&instance, &witness, rng).is_ok());
+        }
+    }
+
+    #[test]
+    fn test_nested_or_accept_any_branch() {
+        let rng = &mut thread_rng();
+        let (inner, witness1, witness2) = schnorr_or_setup(rng);
+        let (outer, witness3, _) = schnorr_or_setup(rng);
+        let instance = (inner, outer.0);
+
+        // The simulated inner OR must fit the challenge left over by the outer one
+        for witness in [
+            Either::Left(Either::Left(witness1)),
+            Either::Left(Either::Right(witness2)),
+            Either::Right(witness3),
+        ] {
+            let test_result = run_nizk_batched::<Hash, OrComposition<SchnorrOr, SchnorrDLOG<G>>, _>(
+                &instance, &witness, rng,
+            );
+
+            assert!(test_result.is_ok());
+        }
+    }
+
+    #[test]
+    fn test_or_reject_neither_witness() {
+        let rng = &mut thread_rng();
+        let (instance, _, _) = schnorr_or_setup(rng);
+
+        for witness in [Either::Left(F::rand(rng)), Either::Right(F::rand(rng))] {
+            let batched_result = run_nizk_batched::<Hash, SchnorrOr, _>(&instance, &witness, rng);
+            let short_result = run_nizk_short::<Hash, SchnorrOr, _>(&instance, &witness, rng);
+
+            assert_eq!(batched_result, Err(SigmaError::VerificationFailed));
+            assert_eq!(short_result, Err(SigmaError::VerificationFailed));
+        }
+    }
+
+    #[test]
+    fn test_or_branches_indistinguishable_encoding() {
+        let rng = &mut thread_rng();
+        let (instance, witness1, witness2) = schnorr_or_setup(rng);
+        let nizk = NIZK::<SchnorrOr, Hash>::new(&instance, b"ctx");
+
+        // Both branches produce the same proof type with the same encoded length
+        let left_proof = batchable_proof(&nizk, &Either::Left(witness1), rng);
+        let right_proof = batchable_proof(&nizk, &Either::Right(witness2), rng);
+        let (mut left_bytes, mut right_bytes) = (Vec::new(), Vec::new());
+        left_proof.serialize(&mut left_bytes).unwrap();
+        right_proof.serialize(&mut right_bytes).unwrap();
+
+        assert_eq!(left_bytes.len(), right_bytes.len());
+        for bytes in [left_bytes, right_bytes] {
+            let decoded = BatchableProof::<SchnorrOr>::deserialize(&bytes[..]).unwrap();
+            assert!(nizk.batchable_verify(&decoded, None).is_ok());
+        }
+    }
+
+    #[test]
+    fn test_or_reject_challenges_not_recombining() {
+        let rng = &mut thread_rng();
+        let (instance, witness1, _) = schnorr_or_setup(rng);
+        let protocol = SchnorrOr::new(&instance);
+
+        let expansion = ChallengeExpansion::default();
+        let (commitment, state) = protocol
+            .prover_commit(&Either::Left(witness1), expansion, rng)
+            .unwrap();
+        let (challenge, mut response) = loop {
+            let challenge = SchnorrOr::challenge_from_bytes(&rng.gen(), expansion).unwrap();
+            if let Ok(response) = protocol.prover_response(&state, &challenge) {
+                break (challenge, response);
+            }
+        };
+        assert!(protocol
+            .verifier(&commitment, &challenge, &response)
+            .is_ok());
+
+        response.challenge2[0] ^= 1;
+        assert_ne!(
+            xor_challenges(&response.challenge1, &response.challenge2),
+            challenge.bytes
+        );
+        assert_eq!(
+            protocol.verifier(&commitment, &challenge, &response),
+            Err(SigmaError::VerificationFailed)
+        );
+    }
+}
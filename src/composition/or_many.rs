@@ -0,0 +1,529 @@
+use std::fmt;
+
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Read, SerializationError, Write};
+use rand::Rng;
+
+use super::{xor_challenges, ChallengeBytes};
+use crate::{
+    group::wipe, interactive_proofs::canonical_bytes, interactive_proofs::protocol_label,
+    secret::Redacted, Challenge, ChallengeExpansion, CommitmentRecoverable, SigmaError,
+    SigmaProtocol, CHALLENGE_LENGTH, LABEL_LENGTH,
+};
+
+/// OR composition of any number of instances of the same Sigma protocol: the prover knows a witness for at least
+/// one instance, without revealing which.
+///
+/// This generalises [`OrComposition`](super::OrComposition): every branch but the known one is simulated with a
+/// random challenge, and the known branch answers the challenge that makes the XOR of all sub-challenges equal to
+/// the challenge. XOR over the challenge bytes is used, rather than addition, so that the sub-challenges do not
+/// depend on the challenge space of the protocol.
+pub struct OrMany<S: SigmaProtocol> {
+    protocols: Vec<S>,
+}
+
+/// A wrapper type for an OR composition witness: the index of the known instance and its witness
+pub type OrManyWitness<S> = (usize, <S as SigmaProtocol>::Witness);
+
+/// The response of an OR composition over many instances: the challenge and response of each branch. It is
+/// encoded as the number of branches `k`, followed by the `k` sub-challenges and the `k` sub-responses
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OrManyResponse<R> {
+    challenges: Vec<Challenge>,
+    responses: Vec<R>,
+}
+
+impl<R: CanonicalSerialize> CanonicalSerialize for OrManyResponse<R> {
+    fn serialize<W: Write>(&self, mut writer: W) -> Result<(), SerializationError> {
+        if self.challenges.len() != self.responses.len() {
+            return Err(SerializationError::InvalidData);
+        }
+
+        (self.challenges.len() as u64).serialize(&mut writer)?;
+        for challenge in &self.challenges {
+            writer.write_all(challenge)?;
+        }
+        for response in &self.responses {
+            response.serialize(&mut writer)?;
+        }
+
+        Ok(())
+    }
+
+    fn serialized_size(&self) -> usize {
+        0u64.serialized_size()
+            + self.challenges.len() * CHALLENGE_LENGTH
+            + self
+                .responses
+                .iter()
+                .map(CanonicalSerialize::serialized_size)
+                .sum::<usize>()
+    }
+}
+
+impl<R: CanonicalDeserialize> CanonicalDeserialize for OrManyResponse<R> {
+    fn deserialize<Re: Read>(mut reader: Re) -> Result<Self, SerializationError> {
+        let branches = u64::deserialize(&mut reader)? as usize;
+
+        let mut challenges = Vec::new();
+        for _ in 0..branches {
+            let mut challenge = [0u8; CHALLENGE_LENGTH];
+            reader.read_exact(&mut challenge)?;
+            challenges.push(challenge);
+        }
+        let responses = (0..branches)
+            .map(|_| R::deserialize(&mut reader))
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self {
+            challenges,
+            responses,
+        })
+    }
+}
+
+pub struct ProverState<S: SigmaProtocol> {
+    index: usize,
+    state: S::ProverState,
+    simulated_challenges: Vec<Challenge>,
+    simulated_responses: Vec<S::Response>,
+}
+
+impl<S: SigmaProtocol> fmt::Debug for ProverState<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ProverState")
+            .field("index", &Redacted)
+            .field("state", &Redacted)
+            .field("simulated_challenges", &Redacted)
+            .field("simulated_responses", &Redacted)
+            .finish()
+    }
+}
+
+impl<S: SigmaProtocol> OrMany<S> {
+    /// Checks that a commitment and response have one entry per branch, and that the sub-challenges recombine into
+    /// the challenge
+    fn check_response(
+        &self,
+        commitments: Option<usize>,
+        challenge: &Challenge,
+        response: &OrManyResponse<S::Response>,
+    ) -> Result<(), SigmaError> {
+        let branches = self.protocols.len();
+        if response.challenges.len() != branches
+            || response.responses.len() != branches
+            || commitments.is_some_and(|length| length != branches)
+        {
+            return Err(SigmaError::LengthMismatch);
+        }
+
+        if combine_challenges(&response.challenges) == *challenge {
+            Ok(())
+        } else {
+            Err(SigmaError::VerificationFailed)
+        }
+    }
+}
+
+/// The XOR of all sub-challenges
+fn combine_challenges<'a>(challenges: impl IntoIterator<Item = &'a Challenge>) -> Challenge {
+    challenges
+        .into_iter()
+        .fold([0u8; CHALLENGE_LENGTH], |combined, challenge| {
+            xor_challenges(&combined, challenge)
+        })
+}
+
+impl<S: CommitmentRecoverable> SigmaProtocol for OrMany<S>
+where
+    S::Response: Clone,
+{
+    type Instance = Vec<S::Instance>;
+    type Commitment = Vec<S::Commitment>;
+    type ProverState = ProverState<S>;
+    type Witness = OrManyWitness<S>;
+    type Response = OrManyResponse<S::Response>;
+    /// The bytes, which split into the sub-challenges before each branch converts its own
+    type Challenge = ChallengeBytes;
+
+    const PROTOCOL_ID: &'static [u8] = b"zkpstd/sigma/or-many";
+
+    const MIN_CHALLENGE_LENGTH: usize = S::MIN_CHALLENGE_LENGTH;
+
+    fn label(&self) -> [u8; LABEL_LENGTH] {
+        let labels: Vec<u8> = self.protocols.iter().flat_map(S::label).collect();
+        protocol_label(Self::PROTOCOL_ID, &labels)
+    }
+
+    fn new(instance: &Self::Instance) -> Self {
+        Self {
+            protocols: instance.iter().map(S::new).collect(),
+        }
+    }
+
+    fn challenge_from_bytes(
+        challenge: &Challenge,
+        expansion: ChallengeExpansion,
+    ) -> Result<ChallengeBytes, SigmaError> {
+        Ok(ChallengeBytes {
+            bytes: *challenge,
+            expansion,
+        })
+    }
+
+    fn validate_instance(&self) -> Result<(), SigmaError> {
+        self.protocols.iter().try_for_each(S::validate_instance)
+    }
+
+    fn instance_bytes(&self) -> Vec<u8> {
+        canonical_bytes(
+            &self
+                .protocols
+                .iter()
+                .map(S::instance_bytes)
+                .collect::<Vec<_>>(),
+        )
+    }
+
+    /// The index of the known branch and the encoding of its witness. An index out of range, which the prover
+    /// rejects, encodes no witness
+    fn witness_bytes(&self, (index, witness): &Self::Witness) -> Vec<u8> {
+        let bytes = self
+            .protocols
+            .get(*index)
+            .map(|protocol| protocol.witness_bytes(witness));
+        canonical_bytes(&(*index, bytes.unwrap_or_default()))
+    }
+
+    fn prover_commit<R: Rng>(
+        &self,
+        witness: &Self::Witness,
+        expansion: ChallengeExpansion,
+        rng: &mut R,
+    ) -> Result<(Self::Commitment, Self::ProverState), SigmaError> {
+        let (index, witness) = witness;
+        if *index >= self.protocols.len() {
+            return Err(SigmaError::LengthMismatch);
+        }
+
+        let mut commitments = Vec::with_capacity(self.protocols.len());
+        let mut simulated_challenges = Vec::with_capacity(self.protocols.len() - 1);
+        let mut simulated_responses = Vec::with_capacity(self.protocols.len() - 1);
+        for (_, protocol) in self
+            .protocols
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| i != index)
+        {
+            let (commitment, challenge, response) = protocol.simulate_transcript(expansion, rng)?;
+            commitments.push(commitment);
+            simulated_challenges.push(challenge);
+            simulated_responses.push(response);
+        }
+
+        let (commitment, state) = self.protocols[*index].prover_commit(witness, expansion, rng)?;
+        commitments.insert(*index, commitment);
+
+        let state = ProverState {
+            index: *index,
+            state,
+            simulated_challenges,
+            simulated_responses,
+        };
+
+        Ok((commitments, state))
+    }
+
+    fn prover_response(
+        &self,
+        prover_state: &Self::ProverState,
+        challenge: &ChallengeBytes,
+    ) -> Result<Self::Response, SigmaError> {
+        let index = prover_state.index;
+        let real_challenge = xor_challenges(
+            &challenge.bytes,
+            &combine_challenges(&prover_state.simulated_challenges),
+        );
+        let real_response = self.protocols[index].prover_response(
+            &prover_state.state,
+            &S::challenge_from_bytes(&real_challenge, challenge.expansion)?,
+        )?;
+
+        let mut challenges = prover_state.simulated_challenges.clone();
+        let mut responses = prover_state.simulated_responses.clone();
+        challenges.insert(index, real_challenge);
+        responses.insert(index, real_response);
+
+        Ok(OrManyResponse {
+            challenges,
+            responses,
+        })
+    }
+
+    fn verifier(
+        &self,
+        commitment: &Self::Commitment,
+        challenge: &ChallengeBytes,
+        response: &Self::Response,
+    ) -> Result<(), SigmaError> {
+        self.check_response(Some(commitment.len()), &challenge.bytes, response)?;
+
+        let expansion = challenge.expansion;
+        self.protocols
+            .iter()
+            .zip(commitment)
+            .zip(response.challenges.iter().zip(&response.responses))
+            .try_for_each(|((protocol, commitment), (challenge, response))| {
+                let challenge = S::challenge_from_bytes(challenge, expansion)?;
+                protocol.verifier(commitment, &challenge, response)
+            })
+    }
+
+    fn simulate_response<R: Rng>(&self, rng: &mut R) -> Self::Response {
+        OrManyResponse {
+            challenges: self.protocols.iter().map(|_| rng.gen()).collect(),
+            responses: self
+                .protocols
+                .iter()
+                .map(|protocol| protocol.simulate_response(rng))
+                .collect(),
+        }
+    }
+
+    fn zeroize_state(&self, prover_state: &mut Self::ProverState) {
+        self.protocols[prover_state.index].zeroize_state(&mut prover_state.state);
+        wipe(&mut prover_state.index);
+    }
+}
+
+impl<S: CommitmentRecoverable> CommitmentRecoverable for OrMany<S>
+where
+    S::Response: Clone,
+{
+    fn simulate_commitment(
+        &self,
+        challenge: &ChallengeBytes,
+        response: &Self::Response,
+    ) -> Result<Self::Commitment, SigmaError> {
+        self.check_response(None, &challenge.bytes, response)?;
+
+        let expansion = challenge.expansion;
+        self.protocols
+            .iter()
+            .zip(response.challenges.iter().zip(&response.responses))
+            .map(|(protocol, (challenge, response))| {
+                let challenge = S::challenge_from_bytes(challenge, expansion)?;
+                protocol.simulate_commitment(&challenge, response)
+            })
+            .collect()
+    }
+
+    /// Simulates every branch and combines their challenges, as a response of `simulate_response` only fits the
+    /// challenge its sub-challenges XOR to
+    fn simulate_transcript<R: Rng>(
+        &self,
+        expansion: ChallengeExpansion,
+        rng: &mut R,
+    ) -> Result<(Self::Commitment, Challenge, Self::Response), SigmaError> {
+        let mut commitments = Vec::with_capacity(self.protocols.len());
+        let mut challenges = Vec::with_capacity(self.protocols.len());
+        let mut responses = Vec::with_capacity(self.protocols.len());
+        for protocol in &self.protocols {
+            let (commitment, challenge, response) = protocol.simulate_transcript(expansion, rng)?;
+            commitments.push(commitment);
+            challenges.push(challenge);
+            responses.push(response);
+        }
+
+        let challenge = combine_challenges(&challenges);
+        Ok((
+            commitments,
+            challenge,
+            OrManyResponse {
+                challenges,
+                responses,
+            },
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_ec::ProjectiveCurve;
+    use ark_ff::{PrimeField, UniformRand};
+    use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+    use rand::{rngs::StdRng, thread_rng, Rng, SeedableRng};
+
+    use crate::{
+        composition::{ChallengeBytes, Either, OrComposition},
+        nizk_proofs::tests::{run_nizk_batched, run_nizk_short},
+        protocols::{SchnorrDLOG, SchnorrInstance},
+        testing::interactive::run_interactive,
+        BatchableProof, ChallengeExpansion, CommitmentRecoverable, SigmaError, SigmaProtocol, NIZK,
+    };
+
+    use super::OrMany;
+
+    type Hash = blake2::Blake2s;
+    type G = ark_bls12_377::G1Projective;
+    type F = ark_bls12_377::Fr;
+
+    type SchnorrOrMany = OrMany<SchnorrDLOG<G>>;
+
+    /// `count` Schnorr instances over the same base, and the witness of each
+    fn schnorr_setup<R: Rng>(count: usize, rng: &mut R) -> (Vec<SchnorrInstance<G>>, Vec<F>) {
+        let base = G::rand(rng);
+
+        (0..count)
+            .map(|_| {
+                let witness = F::rand(rng);
+                (
+                    SchnorrInstance::new(base, base.mul(witness.into_repr())),
+                    witness,
+                )
+            })
+            .unzip()
+    }
+
+    /// Runs the interactive protocol on a challenge that the sub-protocols can convert
+    fn interactive_run<S: SigmaProtocol<Challenge = ChallengeBytes>>(
+        protocol: &S,
+        witness: &S::Witness,
+        seed: u64,
+    ) -> (S::Commitment, ChallengeBytes, S::Response) {
+        let rng = &mut StdRng::seed_from_u64(seed);
+        let expansion = ChallengeExpansion::default();
+        let (commitment, state) = protocol.prover_commit(witness, expansion, rng).unwrap();
+        loop {
+            let challenge = S::challenge_from_bytes(&rng.gen(), expansion).unwrap();
+            if let Ok(response) = protocol.prover_response(&state, &challenge) {
+                break (commitment, challenge, response);
+            }
+        }
+    }
+
+    #[test]
+    fn test_or_many_agrees_with_binary_or() {
+        let rng = &mut thread_rng();
+        let (instances, witnesses) = schnorr_setup(2, rng);
+        let or_many = SchnorrOrMany::new(&instances);
+        let or =
+            OrComposition::<SchnorrDLOG<G>, SchnorrDLOG<G>>::new(&(instances[0], instances[1]));
+
+        for index in 0..2 {
+            let either = if index == 0 {
+                Either::Left(witnesses[0])
+            } else {
+                Either::Right(witnesses[1])
+            };
+            let (many_commitment, many_challenge, many_response) =
+                interactive_run(&or_many, &(index, witnesses[index]), 7);
+            let (or_commitment, or_challenge, or_response) = interactive_run(&or, &either, 7);
+
+            assert_eq!(many_challenge.bytes, or_challenge.bytes);
+            assert_eq!(many_commitment, vec![or_commitment.0, or_commitment.1]);
+            assert_eq!(
+                many_response.challenges,
+                vec![or_response.challenge1, or_response.challenge2]
+            );
+            assert_eq!(
+                many_response.responses,
+                vec![or_response.response1, or_response.response2]
+            );
+            assert!(or_many
+                .verifier(&many_commitment, &many_challenge, &many_response)
+                .is_ok());
+        }
+    }
+
+    #[test]
+    fn test_or_many_accept_any_branch() {
+        let rng = &mut thread_rng();
+        let (instances, witnesses) = schnorr_setup(10, rng);
+
+        for index in [0, 4, 9] {
+            let witness = (index, witnesses[index]);
+            let batched_result =
+                run_nizk_batched::<Hash, SchnorrOrMany, _>(&instances, &witness, rng);
+            let short_result = run_nizk_short::<Hash, SchnorrOrMany, _>(&instances, &witness, rng);
+
+            assert!(batched_result.is_ok());
+            assert!(short_result.is_ok());
+        }
+    }
+
+    #[test]
+    fn test_or_many_interactive_completeness() {
+        let rng = &mut thread_rng();
+        let (instances, witnesses) = schnorr_setup(5, rng);
+
+        for index in [0, 4] {
+            let witness = (index, witnesses[index]);
+            assert!(run_interactive::<SchnorrOrMany, _>(&instances, &witness, rng).is_ok());
+        }
+    }
+
+    #[test]
+    fn test_or_many_reject_wrong_witness() {
+        let rng = &mut thread_rng();
+        let (instances, _) = schnorr_setup(10, rng);
+
+        let wrong_witness = (3, F::rand(rng));
+        let test_result =
+            run_nizk_batched::<Hash, SchnorrOrMany, _>(&instances, &wrong_witness, rng);
+        let out_of_range = (10, F::rand(rng));
+        let out_of_range_result =
+            run_nizk_batched::<Hash, SchnorrOrMany, _>(&instances, &out_of_range, rng);
+
+        assert_eq!(test_result, Err(SigmaError::VerificationFailed));
+        assert_eq!(out_of_range_result, Err(SigmaError::LengthMismatch));
+    }
+
+    #[test]
+    fn test_or_many_reject_malformed_challenges() {
+        let rng = &mut thread_rng();
+        let (instances, witnesses) = schnorr_setup(10, rng);
+        let protocol = SchnorrOrMany::new(&instances);
+
+        let (commitment, challenge, mut response) =
+            interactive_run(&protocol, &(6, witnesses[6]), rng.gen());
+        assert!(protocol
+            .verifier(&commitment, &challenge, &response)
+            .is_ok());
+
+        // The simulated sub-challenge of another branch no longer recombines into the challenge
+        response.challenges[2][31] ^= 0x80;
+        assert_eq!(
+            protocol.verifier(&commitment, &challenge, &response),
+            Err(SigmaError::VerificationFailed)
+        );
+        assert_eq!(
+            protocol.simulate_commitment(&challenge, &response),
+            Err(SigmaError::VerificationFailed)
+        );
+    }
+
+    #[test]
+    fn test_or_many_batchable_proof_round_trip() {
+        let rng = &mut thread_rng();
+        let (instances, witnesses) = schnorr_setup(10, rng);
+        let nizk = NIZK::<SchnorrOrMany, Hash>::new(&instances, b"ctx");
+
+        let proof = nizk.batchable_proof(&(1, witnesses[1]), None, rng).unwrap();
+        let mut bytes = Vec::new();
+        proof.serialize(&mut bytes).unwrap();
+        let decoded = BatchableProof::<SchnorrOrMany>::deserialize(&bytes[..]).unwrap();
+
+        assert!(nizk.batchable_verify(&decoded, None).is_ok());
+
+        // The encoded number of branches no longer matches a shorter list of instances
+        let truncated_nizk = NIZK::<SchnorrOrMany, Hash>::new(&instances[1..].to_vec(), b"ctx");
+        assert_eq!(
+            truncated_nizk.batchable_verify(&decoded, None),
+            Err(SigmaError::VerificationFailed)
+        );
+        assert_eq!(
+            truncated_nizk.batchable_verify_detailed(&decoded, None),
+            Err(SigmaError::LengthMismatch)
+        );
+    }
+}
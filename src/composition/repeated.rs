@@ -0,0 +1,324 @@
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Read, SerializationError, Write};
+use rand::Rng;
+
+use crate::{
+    interactive_proofs::protocol_label, protocols::challenge_bits, Challenge, ChallengeExpansion,
+    CommitmentRecoverable, SigmaError, SigmaProtocol, CHALLENGE_LENGTH, LABEL_LENGTH,
+};
+
+/// Parallel repetition of a Sigma protocol with a small challenge space: `T` independent copies run on the same
+/// instance and witness, and the verifier accepts only if every copy does.
+///
+/// The bits of the challenge are split into `T` disjoint chunks of `8 * CHALLENGE_LENGTH / T` bits, and copy `i`
+/// receives chunk `i` as the low bits of its own challenge, the other bits being zero. The inner protocol must only
+/// read that many low bits, as for instance [`SquareRootIdentification`](crate::protocols::SquareRootIdentification)
+/// with a single round. `T` must be between 1 and `8 * CHALLENGE_LENGTH`.
+pub struct Repeated<S: SigmaProtocol, const T: usize> {
+    protocol: S,
+}
+
+/// One value per repetition, encoded as the `T` encodings one after the other
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Repetitions<X, const T: usize>(pub [X; T]);
+
+impl<X: CanonicalSerialize, const T: usize> CanonicalSerialize for Repetitions<X, T> {
+    fn serialize<W: Write>(&self, mut writer: W) -> Result<(), SerializationError> {
+        self.0.iter().try_for_each(|x| x.serialize(&mut writer))
+    }
+
+    fn serialized_size(&self) -> usize {
+        self.0.iter().map(CanonicalSerialize::serialized_size).sum()
+    }
+}
+
+impl<X: CanonicalDeserialize, const T: usize> CanonicalDeserialize for Repetitions<X, T> {
+    fn deserialize<R: Read>(mut reader: R) -> Result<Self, SerializationError> {
+        let values = (0..T)
+            .map(|_| X::deserialize(&mut reader))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        values
+            .try_into()
+            .map(Self)
+            .map_err(|_| SerializationError::InvalidData)
+    }
+}
+
+impl<S: SigmaProtocol, const T: usize> Repeated<S, T> {
+    /// The number of challenge bits given to each copy
+    const WIDTH: usize = 8 * CHALLENGE_LENGTH / if T == 0 { 1 } else { T };
+
+    fn check_repetitions() -> Result<(), SigmaError> {
+        if T == 0 || T > 8 * CHALLENGE_LENGTH {
+            Err(SigmaError::LengthMismatch)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// The challenge of copy `index`: its chunk of the challenge bits, least significant bit of each byte first
+    fn sub_challenge(challenge: &Challenge, index: usize) -> Challenge {
+        let mut sub_challenge = [0u8; CHALLENGE_LENGTH];
+        challenge_bits(challenge, 8 * CHALLENGE_LENGTH)
+            .skip(index * Self::WIDTH)
+            .take(Self::WIDTH)
+            .enumerate()
+            .filter(|(_, bit)| *bit)
+            .for_each(|(i, _)| sub_challenge[i / 8] |= 1 << (i % 8));
+        sub_challenge
+    }
+
+    /// Runs `f` on the index of every copy, collecting the results into an array
+    fn for_each_copy<X, F: FnMut(usize) -> Result<X, SigmaError>>(
+        f: F,
+    ) -> Result<[X; T], SigmaError> {
+        Self::check_repetitions()?;
+
+        let values = (0..T).map(f).collect::<Result<Vec<_>, _>>()?;
+
+        Ok(values
+            .try_into()
+            .unwrap_or_else(|_| unreachable!("one value per copy")))
+    }
+}
+
+impl<S: SigmaProtocol, const T: usize> SigmaProtocol for Repeated<S, T> {
+    type Instance = S::Instance;
+    type Commitment = Repetitions<S::Commitment, T>;
+    type ProverState = Vec<S::ProverState>;
+    type Witness = S::Witness;
+    type Response = Repetitions<S::Response, T>;
+    /// The sub-challenge of every copy, in the challenge space of the inner protocol
+    type Challenge = [S::Challenge; T];
+
+    const PROTOCOL_ID: &'static [u8] = b"zkpstd/sigma/repeated";
+
+    /// The hash of the number of repetitions and the label of the inner protocol
+    fn label(&self) -> [u8; LABEL_LENGTH] {
+        let data = [&(T as u64).to_le_bytes()[..], &self.protocol.label()].concat();
+        protocol_label(Self::PROTOCOL_ID, &data)
+    }
+
+    fn new(instance: &Self::Instance) -> Self {
+        Self {
+            protocol: S::new(instance),
+        }
+    }
+
+    fn challenge_from_bytes(
+        challenge: &Challenge,
+        expansion: ChallengeExpansion,
+    ) -> Result<Self::Challenge, SigmaError> {
+        Self::for_each_copy(|i| {
+            S::challenge_from_bytes(&Self::sub_challenge(challenge, i), expansion)
+        })
+    }
+
+    fn validate_instance(&self) -> Result<(), SigmaError> {
+        self.protocol.validate_instance()
+    }
+
+    fn instance_bytes(&self) -> Vec<u8> {
+        self.protocol.instance_bytes()
+    }
+
+    fn witness_bytes(&self, witness: &Self::Witness) -> Vec<u8> {
+        self.protocol.witness_bytes(witness)
+    }
+
+    fn is_valid_witness(&self, witness: &Self::Witness) -> bool {
+        self.protocol.is_valid_witness(witness)
+    }
+
+    fn prover_commit<R: Rng>(
+        &self,
+        witness: &Self::Witness,
+        expansion: ChallengeExpansion,
+        rng: &mut R,
+    ) -> Result<(Self::Commitment, Self::ProverState), SigmaError> {
+        Self::check_repetitions()?;
+
+        let (commitments, states): (Vec<_>, Vec<_>) = (0..T)
+            .map(|_| self.protocol.prover_commit(witness, expansion, rng))
+            .collect::<Result<Vec<_>, _>>()?
+            .into_iter()
+            .unzip();
+
+        let commitments = commitments
+            .try_into()
+            .unwrap_or_else(|_| unreachable!("one commitment per copy"));
+
+        Ok((Repetitions(commitments), states))
+    }
+
+    fn prover_response(
+        &self,
+        prover_state: &Self::ProverState,
+        challenge: &Self::Challenge,
+    ) -> Result<Self::Response, SigmaError> {
+        if prover_state.len() != T {
+            return Err(SigmaError::LengthMismatch);
+        }
+
+        Self::for_each_copy(|i| {
+            self.protocol
+                .prover_response(&prover_state[i], &challenge[i])
+        })
+        .map(Repetitions)
+    }
+
+    fn verifier(
+        &self,
+        commitment: &Self::Commitment,
+        challenge: &Self::Challenge,
+        response: &Self::Response,
+    ) -> Result<(), SigmaError> {
+        Self::for_each_copy(|i| {
+            self.protocol
+                .verifier(&commitment.0[i], &challenge[i], &response.0[i])
+        })
+        .map(|_| ())
+    }
+
+    fn simulate_response<R: Rng>(&self, rng: &mut R) -> Self::Response {
+        Repetitions(std::array::from_fn(|_| {
+            self.protocol.simulate_response(rng)
+        }))
+    }
+
+    fn zeroize_state(&self, prover_state: &mut Self::ProverState) {
+        for state in prover_state {
+            self.protocol.zeroize_state(state);
+        }
+    }
+}
+
+impl<S: CommitmentRecoverable, const T: usize> CommitmentRecoverable for Repeated<S, T> {
+    fn simulate_commitment(
+        &self,
+        challenge: &Self::Challenge,
+        response: &Self::Response,
+    ) -> Result<Self::Commitment, SigmaError> {
+        Self::for_each_copy(|i| {
+            self.protocol
+                .simulate_commitment(&challenge[i], &response.0[i])
+        })
+        .map(Repetitions)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use num_bigint::{BigUint, RandBigInt};
+    use rand::{thread_rng, Rng};
+
+    use crate::{
+        nizk_proofs::tests::{run_nizk_batched, run_nizk_short},
+        protocols::{SquareRootIdentification, SquareRootInstance},
+        ChallengeExpansion, CommitmentRecoverable, SigmaError, SigmaProtocol,
+    };
+
+    use super::{Repeated, Repetitions};
+
+    type Hash = blake2::Blake2s;
+
+    const REPETITIONS: usize = 40;
+
+    /// The product of the Mersenne primes `2^127 - 1` and `2^89 - 1`, both congruent to 3 modulo 4
+    fn blum_integer() -> BigUint {
+        let one = BigUint::from(1u8);
+        ((&one << 127) - &one) * ((&one << 89) - &one)
+    }
+
+    /// A single round of the square root identification, whose challenge is a single bit
+    fn single_round_setup<R: Rng>(rng: &mut R) -> (SquareRootInstance, BigUint) {
+        let modulus = blum_integer();
+        let root = rng.gen_biguint_below(&modulus);
+        let square = &root * &root % &modulus;
+
+        (SquareRootInstance::new(modulus, square, 1), root)
+    }
+
+    /// A prover without witness that guesses the challenge of every copy and answers with simulated transcripts.
+    /// Each copy is accepted only if its challenge bit was guessed right
+    fn cheat<S: CommitmentRecoverable, const T: usize, R: Rng>(
+        instance: &S::Instance,
+        rng: &mut R,
+    ) -> Result<(), SigmaError> {
+        let protocol = S::new(instance);
+        let guesses: Vec<_> = (0..T)
+            .map(|_| {
+                protocol
+                    .simulate_transcript(ChallengeExpansion::default(), rng)
+                    .unwrap()
+            })
+            .collect();
+        let (commitments, responses): (Vec<_>, Vec<_>) = guesses
+            .into_iter()
+            .map(|(commitment, _, response)| (commitment, response))
+            .unzip();
+        let commitment = Repetitions(commitments.try_into().unwrap_or_else(|_| unreachable!()));
+        let response = Repetitions(responses.try_into().unwrap_or_else(|_| unreachable!()));
+
+        let challenge =
+            Repeated::<S, T>::challenge_from_bytes(&rng.gen(), ChallengeExpansion::default())?;
+        Repeated::<S, T>::new(instance).verifier(&commitment, &challenge, &response)
+    }
+
+    #[test]
+    fn test_repeated_accept_valid() {
+        let rng = &mut thread_rng();
+        let (instance, witness) = single_round_setup(rng);
+
+        let batched_result =
+            run_nizk_batched::<Hash, Repeated<SquareRootIdentification, REPETITIONS>, _>(
+                &instance, &witness, rng,
+            );
+        let short_result = run_nizk_short::<Hash, Repeated<SquareRootIdentification, REPETITIONS>, _>(
+            &instance, &witness, rng,
+        );
+
+        assert!(batched_result.is_ok());
+        assert!(short_result.is_ok());
+    }
+
+    #[test]
+    fn test_repeated_soundness_amplification() {
+        let rng = &mut thread_rng();
+        let (instance, _) = single_round_setup(rng);
+        const TRIALS: usize = 200;
+
+        // A single round is fooled about half of the time
+        let single_successes = (0..TRIALS)
+            .filter(|_| cheat::<SquareRootIdentification, 1, _>(&instance, rng).is_ok())
+            .count();
+        assert!((TRIALS / 4..3 * TRIALS / 4).contains(&single_successes));
+
+        // Fooling 40 rounds at once happens with probability 2^-40
+        for _ in 0..TRIALS {
+            assert_eq!(
+                cheat::<SquareRootIdentification, REPETITIONS, _>(&instance, rng),
+                Err(SigmaError::VerificationFailed)
+            );
+        }
+    }
+
+    #[test]
+    fn test_repeated_sub_challenges_are_disjoint() {
+        let mut challenge = [0u8; 32];
+        // Bit 6 is the first bit of the second copy, as each of the 40 copies gets 6 bits
+        challenge[0] = 1 << 6;
+
+        let sub_challenges: Vec<_> = (0..REPETITIONS)
+            .map(|i| {
+                Repeated::<SquareRootIdentification, REPETITIONS>::sub_challenge(&challenge, i)
+            })
+            .collect();
+
+        assert_eq!(sub_challenges[1][0], 1);
+        assert!(sub_challenges
+            .iter()
+            .enumerate()
+            .all(|(i, sub_challenge)| i == 1 || sub_challenge == &[0u8; 32]));
+    }
+}
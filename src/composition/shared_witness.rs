@@ -0,0 +1,290 @@
+use ark_ec::ProjectiveCurve;
+use rand::Rng;
+
+use crate::{
+    interactive_proofs::protocol_label,
+    protocols::{
+        LinearMapCommitment, LinearMapInstance, LinearMapProof, LinearMapResponse, LinearMapWitness,
+    },
+    Challenge, ChallengeExpansion, CommitmentRecoverable, SigmaError, SigmaProtocol, LABEL_LENGTH,
+};
+
+/// AND composition of linear statements whose witnesses may be shared: the prover knows global witnesses
+/// `w_0, ..., w_{n-1}` such that every statement holds for the witnesses its slots are mapped to.
+///
+/// Unlike an [`AndComposition`](super::AndComposition) of two Schnorr proofs, which accepts two unrelated
+/// witnesses, a shared witness gets a single commitment randomness and a single response scalar, so that
+/// `A = x * G` and `B = x * H` can only be proven with the same `x`. The statements are merged into one
+/// [`LinearMapProof`] over the global witnesses.
+pub struct SharedWitnessAnd<G: ProjectiveCurve> {
+    protocol: LinearMapProof<G>,
+}
+
+/// The instance for the shared witness AND, assembled statement by statement
+#[derive(Debug, Clone)]
+pub struct SharedWitnessInstance<G: ProjectiveCurve> {
+    witnesses: usize,
+    statements: Vec<LinearMapInstance<G>>,
+    slots: Vec<Vec<usize>>,
+}
+
+impl<G: ProjectiveCurve> SharedWitnessInstance<G> {
+    /// Create an instance without statements over `witnesses` global witnesses
+    pub fn new(witnesses: usize) -> Self {
+        Self {
+            witnesses,
+            statements: Vec::new(),
+            slots: Vec::new(),
+        }
+    }
+
+    /// Adds a statement whose `j`-th witness is the global witness `slots[j]`. Returns
+    /// [`SigmaError::LengthMismatch`] if the statement does not have one witness per slot, or if a slot is not a
+    /// global witness index
+    pub fn statement(
+        mut self,
+        statement: LinearMapInstance<G>,
+        slots: Vec<usize>,
+    ) -> Result<Self, SigmaError> {
+        statement.check_lengths(slots.len())?;
+        if slots.iter().any(|slot| *slot >= self.witnesses) {
+            return Err(SigmaError::LengthMismatch);
+        }
+
+        self.statements.push(statement);
+        self.slots.push(slots);
+        Ok(self)
+    }
+
+    /// The linear map over the global witnesses: each row of a statement, with its coefficients moved to the
+    /// columns of the global witnesses they apply to
+    fn merged(&self) -> LinearMapInstance<G> {
+        let (matrix, image) = self
+            .statements
+            .iter()
+            .zip(&self.slots)
+            .flat_map(|(statement, slots)| {
+                statement.rows().map(move |(row, image)| {
+                    let mut merged_row = vec![G::zero(); self.witnesses];
+                    row.iter()
+                        .zip(slots)
+                        .for_each(|(coefficient, slot)| merged_row[*slot] += coefficient);
+                    (merged_row, *image)
+                })
+            })
+            .unzip();
+
+        LinearMapInstance::new(matrix, image)
+    }
+}
+
+impl<G: ProjectiveCurve> SigmaProtocol for SharedWitnessAnd<G> {
+    type Instance = SharedWitnessInstance<G>;
+    type Commitment = LinearMapCommitment<G>;
+    type ProverState = <LinearMapProof<G> as SigmaProtocol>::ProverState;
+    type Witness = LinearMapWitness<G>;
+    type Response = LinearMapResponse<G>;
+    type Challenge = <LinearMapProof<G> as SigmaProtocol>::Challenge;
+
+    const PROTOCOL_ID: &'static [u8] = b"zkpstd/sigma/shared-witness-and";
+
+    fn label(&self) -> [u8; LABEL_LENGTH] {
+        protocol_label(Self::PROTOCOL_ID, &self.protocol.label())
+    }
+
+    fn new(instance: &Self::Instance) -> Self {
+        Self {
+            protocol: LinearMapProof::new(&instance.merged()),
+        }
+    }
+
+    fn challenge_from_bytes(
+        challenge: &Challenge,
+        expansion: ChallengeExpansion,
+    ) -> Result<Self::Challenge, SigmaError> {
+        LinearMapProof::<G>::challenge_from_bytes(challenge, expansion)
+    }
+
+    fn instance_bytes(&self) -> Vec<u8> {
+        self.protocol.instance_bytes()
+    }
+
+    fn witness_bytes(&self, witness: &Self::Witness) -> Vec<u8> {
+        self.protocol.witness_bytes(witness)
+    }
+
+    fn prover_commit<R: Rng>(
+        &self,
+        witness: &Self::Witness,
+        expansion: ChallengeExpansion,
+        rng: &mut R,
+    ) -> Result<(Self::Commitment, Self::ProverState), SigmaError> {
+        self.protocol.prover_commit(witness, expansion, rng)
+    }
+
+    fn prover_response(
+        &self,
+        prover_state: &Self::ProverState,
+        challenge: &Self::Challenge,
+    ) -> Result<Self::Response, SigmaError> {
+        self.protocol.prover_response(prover_state, challenge)
+    }
+
+    fn verifier(
+        &self,
+        commitment: &Self::Commitment,
+        challenge: &Self::Challenge,
+        response: &Self::Response,
+    ) -> Result<(), SigmaError> {
+        self.protocol.verifier(commitment, challenge, response)
+    }
+
+    fn simulate_response<R: Rng>(&self, rng: &mut R) -> Self::Response {
+        self.protocol.simulate_response(rng)
+    }
+
+    fn zeroize_state(&self, prover_state: &mut Self::ProverState) {
+        self.protocol.zeroize_state(prover_state);
+    }
+}
+
+impl<G: ProjectiveCurve> CommitmentRecoverable for SharedWitnessAnd<G> {
+    fn simulate_commitment(
+        &self,
+        challenge: &Self::Challenge,
+        response: &Self::Response,
+    ) -> Result<Self::Commitment, SigmaError> {
+        self.protocol.simulate_commitment(challenge, response)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_ec::ProjectiveCurve;
+    use ark_ff::{PrimeField, UniformRand};
+    use rand::{thread_rng, Rng};
+
+    use crate::{
+        composition::AndComposition,
+        nizk_proofs::tests::{run_nizk_batched, run_nizk_short},
+        protocols::{
+            LinearMapInstance, PedersenOpening, PedersenParams, SchnorrDLOG, SchnorrInstance,
+        },
+        SigmaError,
+    };
+
+    use super::{SharedWitnessAnd, SharedWitnessInstance};
+
+    type Hash = blake2::Blake2s;
+    type G = ark_bls12_377::G1Projective;
+    type F = ark_bls12_377::Fr;
+
+    /// `A = x1 * G` and `B = x2 * H`, which share a witness iff `x1 = x2`
+    fn two_keys_setup<R: Rng>(x1: F, x2: F, rng: &mut R) -> ((G, G), (G, G)) {
+        let (base1, base2) = (G::rand(rng), G::rand(rng));
+
+        (
+            (base1, base1.mul(x1.into_repr())),
+            (base2, base2.mul(x2.into_repr())),
+        )
+    }
+
+    fn shared_key_instance(
+        (base1, claim1): (G, G),
+        (base2, claim2): (G, G),
+    ) -> SharedWitnessInstance<G> {
+        SharedWitnessInstance::new(1)
+            .statement(LinearMapInstance::schnorr(base1, claim1), vec![0])
+            .and_then(|instance| {
+                instance.statement(LinearMapInstance::schnorr(base2, claim2), vec![0])
+            })
+            .unwrap()
+    }
+
+    #[test]
+    fn test_shared_witness_accept_equal_witnesses() {
+        let rng = &mut thread_rng();
+        let x = F::rand(rng);
+        let (key1, key2) = two_keys_setup(x, x, rng);
+        let instance = shared_key_instance(key1, key2);
+
+        let batched_result =
+            run_nizk_batched::<Hash, SharedWitnessAnd<G>, _>(&instance, &vec![x], rng);
+        let short_result = run_nizk_short::<Hash, SharedWitnessAnd<G>, _>(&instance, &vec![x], rng);
+
+        assert!(batched_result.is_ok());
+        assert!(short_result.is_ok());
+    }
+
+    #[test]
+    fn test_shared_witness_reject_different_witnesses() {
+        let rng = &mut thread_rng();
+        let (x1, x2) = (F::rand(rng), F::rand(rng));
+        let (key1, key2) = two_keys_setup(x1, x2, rng);
+
+        // A plain AND accepts the two different witnesses
+        let and_instance = (
+            SchnorrInstance::new(key1.0, key1.1),
+            SchnorrInstance::new(key2.0, key2.1),
+        );
+        let and_result = run_nizk_batched::<Hash, AndComposition<SchnorrDLOG<G>, SchnorrDLOG<G>>, _>(
+            &and_instance,
+            &(x1, x2),
+            rng,
+        );
+        assert!(and_result.is_ok());
+
+        // Sharing the witness, neither of them satisfies both statements
+        let instance = shared_key_instance(key1, key2);
+        for x in [x1, x2] {
+            let test_result =
+                run_nizk_batched::<Hash, SharedWitnessAnd<G>, _>(&instance, &vec![x], rng);
+            assert_eq!(test_result, Err(SigmaError::VerificationFailed));
+        }
+    }
+
+    #[test]
+    fn test_shared_witness_pedersen_message_and_key() {
+        let rng = &mut thread_rng();
+        let params = PedersenParams::new(G::rand(rng), G::rand(rng));
+        let opening = PedersenOpening::new(F::rand(rng), F::rand(rng));
+        let commitment = params.commit_opening(&opening);
+        let base = G::rand(rng);
+        let key = base.mul(opening.message().into_repr());
+
+        // C = m * G + r * H and K = m * B, with global witnesses (m, r)
+        let instance = SharedWitnessInstance::new(2)
+            .statement(
+                LinearMapInstance::pedersen_opening(params, commitment),
+                vec![0, 1],
+            )
+            .and_then(|instance| instance.statement(LinearMapInstance::schnorr(base, key), vec![0]))
+            .unwrap();
+
+        let valid_result = run_nizk_batched::<Hash, SharedWitnessAnd<G>, _>(
+            &instance,
+            &vec![opening.message(), opening.blinding()],
+            rng,
+        );
+        let wrong_message_result = run_nizk_batched::<Hash, SharedWitnessAnd<G>, _>(
+            &instance,
+            &vec![F::rand(rng), opening.blinding()],
+            rng,
+        );
+
+        assert!(valid_result.is_ok());
+        assert_eq!(wrong_message_result, Err(SigmaError::VerificationFailed));
+    }
+
+    #[test]
+    fn test_shared_witness_reject_invalid_slots() {
+        let rng = &mut thread_rng();
+        let statement = LinearMapInstance::schnorr(G::rand(rng), G::rand(rng));
+
+        let out_of_range = SharedWitnessInstance::new(1).statement(statement.clone(), vec![1]);
+        let too_many_slots = SharedWitnessInstance::new(2).statement(statement, vec![0, 1]);
+
+        assert_eq!(out_of_range.err(), Some(SigmaError::LengthMismatch));
+        assert_eq!(too_many_slots.err(), Some(SigmaError::LengthMismatch));
+    }
+}
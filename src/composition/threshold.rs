@@ -0,0 +1,535 @@
+use std::{fmt, marker::PhantomData};
+
+use ark_ff::PrimeField;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Read, SerializationError, Write};
+use rand::Rng;
+
+use crate::{
+    group::wipe, interactive_proofs::canonical_bytes, interactive_proofs::protocol_label,
+    protocols::challenge_scalar, secret::Redacted, Challenge, ChallengeExpansion,
+    CommitmentRecoverable, SigmaError, SigmaProtocol, LABEL_LENGTH,
+};
+
+/// Threshold composition of `n` instances of the same Sigma protocol, following Cramer, Damgård and Schoenmakers:
+/// the prover knows witnesses for at least `k` of them, without revealing which.
+///
+/// The challenge `c` is read as a scalar of `F` and Shamir-shared with a polynomial `P` of degree `n - k` such that
+/// `P(0) = c`; branch `i` answers the share `P(i + 1)`. The prover picks random shares for the `n - k` simulated
+/// branches, which together with `c` determine `P`, and interpolates the shares of the branches it knows. The
+/// verifier checks that all `n` shares lie on a polynomial of degree `n - k` through `c`.
+///
+/// `F` is the field the inner protocol reads its challenges in, so that each share is passed to the inner protocol
+/// as its challenge.
+pub struct Threshold<S: SigmaProtocol, F: PrimeField> {
+    threshold: usize,
+    protocols: Vec<S>,
+    _field: PhantomData<F>,
+}
+
+/// The instance for the threshold composition: the threshold `k` and the `n` instances
+pub struct ThresholdInstance<S: SigmaProtocol> {
+    threshold: usize,
+    instances: Vec<S::Instance>,
+}
+
+impl<S: SigmaProtocol> ThresholdInstance<S> {
+    /// Create a new threshold instance, claiming witnesses for `threshold` of `instances`
+    pub fn new(threshold: usize, instances: Vec<S::Instance>) -> Self {
+        Self {
+            threshold,
+            instances,
+        }
+    }
+}
+
+/// A wrapper type for a threshold witness: exactly `k` pairs of a distinct instance index and its witness
+pub type ThresholdWitness<S> = Vec<(usize, <S as SigmaProtocol>::Witness)>;
+
+/// The response of a threshold composition: the challenge share and response of each of the `n` branches
+#[derive(Debug, Clone, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct ThresholdResponse<F: PrimeField, Resp: CanonicalSerialize + CanonicalDeserialize> {
+    shares: Vec<F>,
+    responses: Vec<Resp>,
+}
+
+pub struct ProverState<S: SigmaProtocol, F: PrimeField> {
+    real: Vec<(usize, S::ProverState)>,
+    simulated: Vec<(usize, F, S::Response)>,
+}
+
+impl<S: SigmaProtocol, F: PrimeField> fmt::Debug for ProverState<S, F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ProverState")
+            .field("real", &Redacted)
+            .field("simulated", &Redacted)
+            .finish()
+    }
+}
+
+/// Evaluates at `x` the polynomial of degree `points.len() - 1` through `points`, whose abscissas must be distinct
+pub(crate) fn interpolate<F: PrimeField>(points: &[(F, F)], x: F) -> F {
+    points
+        .iter()
+        .enumerate()
+        .map(|(i, (x_i, y_i))| {
+            let (numerator, denominator) = points.iter().enumerate().filter(|(j, _)| *j != i).fold(
+                (F::one(), F::one()),
+                |(numerator, denominator), (_, (x_j, _))| {
+                    (numerator * (x - x_j), denominator * (*x_i - x_j))
+                },
+            );
+
+            *y_i * numerator * denominator.inverse().unwrap()
+        })
+        .sum()
+}
+
+/// The abscissa of the share of branch `index`, as `0` is reserved for the challenge
+fn share_point<F: PrimeField>(index: usize) -> F {
+    F::from(index as u64 + 1)
+}
+
+impl<S: SigmaProtocol, F: PrimeField> Threshold<S, F> {
+    fn check_threshold(&self) -> Result<(), SigmaError> {
+        if self.threshold == 0 || self.threshold > self.protocols.len() {
+            Err(SigmaError::LengthMismatch)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Checks that a response has one entry per branch, and that its shares lie on a polynomial of degree `n - k`
+    /// whose value at `0` is the challenge
+    fn check_shares(
+        &self,
+        challenge: &F,
+        response: &ThresholdResponse<F, S::Response>,
+    ) -> Result<(), SigmaError> {
+        self.check_threshold()?;
+        let branches = self.protocols.len();
+        if response.shares.len() != branches || response.responses.len() != branches {
+            return Err(SigmaError::LengthMismatch);
+        }
+
+        let degree = branches - self.threshold;
+        let points: Vec<_> = std::iter::once((F::zero(), *challenge))
+            .chain(
+                response.shares[..degree]
+                    .iter()
+                    .enumerate()
+                    .map(|(i, share)| (share_point(i), *share)),
+            )
+            .collect();
+
+        if response.shares[degree..]
+            .iter()
+            .enumerate()
+            .all(|(i, share)| interpolate(&points, share_point(degree + i)) == *share)
+        {
+            Ok(())
+        } else {
+            Err(SigmaError::VerificationFailed)
+        }
+    }
+}
+
+impl<S: CommitmentRecoverable<Challenge = F>, F: PrimeField> SigmaProtocol for Threshold<S, F>
+where
+    S::Response: Clone,
+{
+    type Instance = ThresholdInstance<S>;
+    type Commitment = Vec<S::Commitment>;
+    type ProverState = ProverState<S, F>;
+    type Witness = ThresholdWitness<S>;
+    type Response = ThresholdResponse<F, S::Response>;
+    type Challenge = F;
+
+    const PROTOCOL_ID: &'static [u8] = b"zkpstd/sigma/threshold";
+
+    /// The hash of the threshold and the labels of all components
+    fn label(&self) -> [u8; LABEL_LENGTH] {
+        let data: Vec<u8> = (self.threshold as u64)
+            .to_le_bytes()
+            .into_iter()
+            .chain(self.protocols.iter().flat_map(S::label))
+            .collect();
+        protocol_label(Self::PROTOCOL_ID, &data)
+    }
+
+    fn new(instance: &Self::Instance) -> Self {
+        Self {
+            threshold: instance.threshold,
+            protocols: instance.instances.iter().map(S::new).collect(),
+            _field: PhantomData,
+        }
+    }
+
+    fn challenge_from_bytes(
+        challenge: &Challenge,
+        expansion: ChallengeExpansion,
+    ) -> Result<F, SigmaError> {
+        challenge_scalar(challenge, expansion)
+    }
+
+    fn validate_instance(&self) -> Result<(), SigmaError> {
+        self.protocols.iter().try_for_each(S::validate_instance)
+    }
+
+    fn instance_bytes(&self) -> Vec<u8> {
+        let instances: Vec<_> = self.protocols.iter().map(S::instance_bytes).collect();
+        canonical_bytes(&(self.threshold, instances))
+    }
+
+    /// The index and the encoding of every known witness. An index out of range, which the prover rejects,
+    /// encodes no witness
+    fn witness_bytes(&self, witness: &Self::Witness) -> Vec<u8> {
+        let witnesses: Vec<_> = witness
+            .iter()
+            .map(|(index, witness)| {
+                let bytes = self
+                    .protocols
+                    .get(*index)
+                    .map(|protocol| protocol.witness_bytes(witness));
+                (*index, bytes.unwrap_or_default())
+            })
+            .collect();
+        canonical_bytes(&witnesses)
+    }
+
+    fn prover_commit<R: Rng>(
+        &self,
+        witness: &Self::Witness,
+        expansion: ChallengeExpansion,
+        rng: &mut R,
+    ) -> Result<(Self::Commitment, Self::ProverState), SigmaError> {
+        self.check_threshold()?;
+        let mut known = vec![None; self.protocols.len()];
+        for (index, witness) in witness {
+            match known.get_mut(*index) {
+                Some(slot @ None) => *slot = Some(witness),
+                _ => return Err(SigmaError::LengthMismatch),
+            }
+        }
+        if witness.len() != self.threshold {
+            return Err(SigmaError::LengthMismatch);
+        }
+
+        let mut commitments = Vec::with_capacity(self.protocols.len());
+        let mut real = Vec::with_capacity(self.threshold);
+        let mut simulated = Vec::with_capacity(self.protocols.len() - self.threshold);
+        for (index, (protocol, witness)) in self.protocols.iter().zip(known).enumerate() {
+            match witness {
+                Some(witness) => {
+                    let (commitment, state) = protocol.prover_commit(witness, expansion, rng)?;
+                    commitments.push(commitment);
+                    real.push((index, state));
+                }
+                None => {
+                    let share = F::rand(rng);
+                    let response = protocol.simulate_response(rng);
+                    commitments.push(protocol.simulate_commitment(&share, &response)?);
+                    simulated.push((index, share, response));
+                }
+            }
+        }
+
+        Ok((commitments, ProverState { real, simulated }))
+    }
+
+    fn prover_response(
+        &self,
+        prover_state: &Self::ProverState,
+        challenge: &Self::Challenge,
+    ) -> Result<Self::Response, SigmaError> {
+        let points: Vec<_> = std::iter::once((F::zero(), *challenge))
+            .chain(
+                prover_state
+                    .simulated
+                    .iter()
+                    .map(|(index, share, _)| (share_point(*index), *share)),
+            )
+            .collect();
+
+        let mut shares = vec![F::zero(); self.protocols.len()];
+        let mut responses = vec![None; self.protocols.len()];
+        for (index, share, response) in &prover_state.simulated {
+            shares[*index] = *share;
+            responses[*index] = Some(response.clone());
+        }
+        for (index, state) in &prover_state.real {
+            let share = interpolate(&points, share_point(*index));
+            shares[*index] = share;
+            responses[*index] = Some(self.protocols[*index].prover_response(state, &share)?);
+        }
+
+        Ok(ThresholdResponse {
+            shares,
+            responses: responses.into_iter().map(Option::unwrap).collect(),
+        })
+    }
+
+    fn verifier(
+        &self,
+        commitment: &Self::Commitment,
+        challenge: &Self::Challenge,
+        response: &Self::Response,
+    ) -> Result<(), SigmaError> {
+        self.check_shares(challenge, response)?;
+        if commitment.len() != self.protocols.len() {
+            return Err(SigmaError::LengthMismatch);
+        }
+
+        self.protocols
+            .iter()
+            .zip(commitment)
+            .zip(response.shares.iter().zip(&response.responses))
+            .try_for_each(|((protocol, commitment), (share, response))| {
+                protocol.verifier(commitment, share, response)
+            })
+    }
+
+    fn simulate_response<R: Rng>(&self, rng: &mut R) -> Self::Response {
+        ThresholdResponse {
+            shares: self.protocols.iter().map(|_| F::rand(rng)).collect(),
+            responses: self
+                .protocols
+                .iter()
+                .map(|protocol| protocol.simulate_response(rng))
+                .collect(),
+        }
+    }
+
+    fn zeroize_state(&self, prover_state: &mut Self::ProverState) {
+        for (index, state) in &mut prover_state.real {
+            self.protocols[*index].zeroize_state(state);
+            wipe(index);
+        }
+        for (index, _, _) in &mut prover_state.simulated {
+            wipe(index);
+        }
+    }
+}
+
+impl<S: CommitmentRecoverable<Challenge = F>, F: PrimeField> CommitmentRecoverable
+    for Threshold<S, F>
+where
+    S::Response: Clone,
+{
+    fn simulate_commitment(
+        &self,
+        challenge: &Self::Challenge,
+        response: &Self::Response,
+    ) -> Result<Self::Commitment, SigmaError> {
+        self.check_shares(challenge, response)?;
+
+        self.protocols
+            .iter()
+            .zip(response.shares.iter().zip(&response.responses))
+            .map(|(protocol, (share, response))| protocol.simulate_commitment(share, response))
+            .collect()
+    }
+
+    /// Samples the challenge and the first `n - k` shares, interpolates the other shares, then simulates every
+    /// branch for its share, as a response of `simulate_response` only fits the challenge its shares interpolate to
+    fn simulate_transcript<R: Rng>(
+        &self,
+        expansion: ChallengeExpansion,
+        rng: &mut R,
+    ) -> Result<(Self::Commitment, Challenge, Self::Response), SigmaError> {
+        self.check_threshold()?;
+        let branches = self.protocols.len();
+        let degree = branches - self.threshold;
+
+        let challenge: Challenge = rng.gen();
+        let mut points = vec![(
+            F::zero(),
+            Self::challenge_from_bytes(&challenge, expansion)?,
+        )];
+        points.extend((0..degree).map(|i| (share_point(i), F::rand(rng))));
+        let shares: Vec<F> = (0..branches)
+            .map(|i| match points.get(i + 1) {
+                Some((_, share)) => *share,
+                None => interpolate(&points, share_point(i)),
+            })
+            .collect();
+
+        let responses: Vec<_> = self
+            .protocols
+            .iter()
+            .map(|protocol| protocol.simulate_response(rng))
+            .collect();
+        let commitments = self
+            .protocols
+            .iter()
+            .zip(shares.iter().zip(&responses))
+            .map(|(protocol, (share, response))| protocol.simulate_commitment(share, response))
+            .collect::<Result<_, SigmaError>>()?;
+
+        Ok((
+            commitments,
+            challenge,
+            ThresholdResponse { shares, responses },
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_ec::ProjectiveCurve;
+    use ark_ff::{PrimeField, UniformRand};
+    use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+    use rand::{thread_rng, Rng};
+
+    use crate::{
+        nizk_proofs::tests::{run_nizk_batched, run_nizk_short},
+        protocols::{SchnorrDLOG, SchnorrInstance},
+        testing::interactive::run_interactive,
+        BatchableProof, ChallengeExpansion, SigmaError, SigmaProtocol, NIZK,
+    };
+
+    use super::{interpolate, Threshold, ThresholdInstance};
+
+    type Hash = blake2::Blake2s;
+    type G = ark_bls12_377::G1Projective;
+    type F = ark_bls12_377::Fr;
+
+    type SchnorrThreshold = Threshold<SchnorrDLOG<G>, F>;
+
+    /// A `threshold`-of-`count` instance, and the witnesses of all its Schnorr instances
+    fn threshold_setup<R: Rng>(
+        threshold: usize,
+        count: usize,
+        rng: &mut R,
+    ) -> (ThresholdInstance<SchnorrDLOG<G>>, Vec<F>) {
+        let base = G::rand(rng);
+        let (instances, witnesses) = (0..count)
+            .map(|_| {
+                let witness = F::rand(rng);
+                (
+                    SchnorrInstance::new(base, base.mul(witness.into_repr())),
+                    witness,
+                )
+            })
+            .unzip();
+
+        (ThresholdInstance::new(threshold, instances), witnesses)
+    }
+
+    fn known_witnesses(witnesses: &[F], indices: &[usize]) -> Vec<(usize, F)> {
+        indices.iter().map(|i| (*i, witnesses[*i])).collect()
+    }
+
+    fn check_accept(threshold: usize, count: usize, indices: &[usize]) {
+        let rng = &mut thread_rng();
+        let (instance, witnesses) = threshold_setup(threshold, count, rng);
+        let witness = known_witnesses(&witnesses, indices);
+
+        let batched_result =
+            run_nizk_batched::<Hash, SchnorrThreshold, _>(&instance, &witness, rng);
+        let short_result = run_nizk_short::<Hash, SchnorrThreshold, _>(&instance, &witness, rng);
+
+        assert!(batched_result.is_ok());
+        assert!(short_result.is_ok());
+    }
+
+    #[test]
+    fn test_interpolate() {
+        let rng = &mut thread_rng();
+        // 3 + 2x + x^2
+        let polynomial = |x: F| F::from(3u64) + F::from(2u64) * x + x * x;
+        let points: Vec<_> = [1u64, 5, 9]
+            .iter()
+            .map(|x| (F::from(*x), polynomial(F::from(*x))))
+            .collect();
+
+        let x = F::rand(rng);
+        assert_eq!(interpolate(&points, x), polynomial(x));
+    }
+
+    #[test]
+    fn test_threshold_all_of_n() {
+        check_accept(3, 3, &[0, 1, 2]);
+
+        // With no simulated branch, the polynomial is constant and every branch answers the challenge itself
+        let rng = &mut thread_rng();
+        let (instance, witnesses) = threshold_setup(3, 3, rng);
+        let protocol = SchnorrThreshold::new(&instance);
+        let (_, state) = protocol
+            .prover_commit(
+                &known_witnesses(&witnesses, &[2, 0, 1]),
+                ChallengeExpansion::default(),
+                rng,
+            )
+            .unwrap();
+        let challenge = F::rand(rng);
+        let response = protocol.prover_response(&state, &challenge).unwrap();
+        assert!(response.shares.iter().all(|share| *share == challenge));
+    }
+
+    #[test]
+    fn test_threshold_one_of_n() {
+        check_accept(1, 4, &[2]);
+    }
+
+    #[test]
+    fn test_threshold_three_of_five() {
+        check_accept(3, 5, &[0, 2, 4]);
+        check_accept(3, 5, &[3, 1, 2]);
+    }
+
+    #[test]
+    fn test_threshold_interactive_completeness() {
+        let rng = &mut thread_rng();
+        let (instance, witnesses) = threshold_setup(3, 5, rng);
+        let witness = known_witnesses(&witnesses, &[0, 2, 4]);
+
+        assert!(run_interactive::<SchnorrThreshold, _>(&instance, &witness, rng).is_ok());
+    }
+
+    #[test]
+    fn test_threshold_reject_too_few_real_witnesses() {
+        let rng = &mut thread_rng();
+        let (instance, witnesses) = threshold_setup(3, 5, rng);
+
+        // Only two of the three claimed witnesses are real
+        let mut witness = known_witnesses(&witnesses, &[0, 2, 4]);
+        witness[1].1 = F::rand(rng);
+        let wrong_result = run_nizk_batched::<Hash, SchnorrThreshold, _>(&instance, &witness, rng);
+        // Two witnesses are not enough to even run the prover
+        let short_witness = known_witnesses(&witnesses, &[0, 2]);
+        let short_result =
+            run_nizk_batched::<Hash, SchnorrThreshold, _>(&instance, &short_witness, rng);
+        let repeated_witness = known_witnesses(&witnesses, &[0, 2, 2]);
+        let repeated_result =
+            run_nizk_batched::<Hash, SchnorrThreshold, _>(&instance, &repeated_witness, rng);
+
+        assert_eq!(wrong_result, Err(SigmaError::VerificationFailed));
+        assert_eq!(short_result, Err(SigmaError::LengthMismatch));
+        assert_eq!(repeated_result, Err(SigmaError::LengthMismatch));
+    }
+
+    #[test]
+    fn test_threshold_reject_inconsistent_shares() {
+        let rng = &mut thread_rng();
+        let (instance, witnesses) = threshold_setup(3, 5, rng);
+        let nizk = NIZK::<SchnorrThreshold, Hash>::new(&instance, b"ctx");
+
+        let proof = nizk
+            .batchable_proof(&known_witnesses(&witnesses, &[1, 2, 3]), None, rng)
+            .unwrap();
+        let mut bytes = Vec::new();
+        proof.serialize(&mut bytes).unwrap();
+        assert!(nizk
+            .batchable_verify(&BatchableProof::deserialize(&bytes[..]).unwrap(), None)
+            .is_ok());
+
+        // Bump the last share: it no longer lies on the polynomial through the challenge
+        let last_share = bytes.len() - 5 * 32 - 8 - 32;
+        bytes[last_share] ^= 1;
+        let tampered = BatchableProof::<SchnorrThreshold>::deserialize(&bytes[..]).unwrap();
+        assert_eq!(
+            nizk.batchable_verify(&tampered, None),
+            Err(SigmaError::VerificationFailed)
+        );
+    }
+}
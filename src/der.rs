@@ -0,0 +1,276 @@
+use ::der::{
+    asn1::{BitStringRef, ObjectIdentifier, OctetStringRef},
+    Decode, DecodeValue, Encode, EncodeValue, Header, Length, Reader, Sequence, Writer,
+};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, SerializationError};
+
+use crate::{
+    nizk_proofs::{proof_bytes, proof_from_bytes},
+    signature::{Signature, VerifyingKey},
+    BatchableProof, SigmaError, SigmaGroup, SigmaProtocol,
+};
+
+/// The placeholder OID of batchable proofs. The arc is not allocated, and will be replaced by the one the working
+/// group registers
+pub const BATCHABLE_PROOF_OID: ObjectIdentifier =
+    ObjectIdentifier::new_unwrap("1.3.6.1.4.1.99999.1.1");
+
+/// The placeholder OID of Schnorr signatures, as for [`BATCHABLE_PROOF_OID`]
+pub const SIGNATURE_OID: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.3.6.1.4.1.99999.1.2");
+
+/// The SEQUENCE of an algorithm OID, a public key as a BIT STRING without unused bits and a proof as an OCTET
+/// STRING
+struct ProofOfPossession<'a> {
+    algorithm: ObjectIdentifier,
+    public_key: BitStringRef<'a>,
+    proof: OctetStringRef<'a>,
+}
+
+impl<'a> DecodeValue<'a> for ProofOfPossession<'a> {
+    fn decode_value<R: Reader<'a>>(reader: &mut R, header: Header) -> ::der::Result<Self> {
+        reader.read_nested(header.length, |reader| {
+            Ok(Self {
+                algorithm: reader.decode()?,
+                public_key: reader.decode()?,
+                proof: reader.decode()?,
+            })
+        })
+    }
+}
+
+impl EncodeValue for ProofOfPossession<'_> {
+    fn value_len(&self) -> ::der::Result<Length> {
+        self.algorithm.encoded_len()? + self.public_key.encoded_len()? + self.proof.encoded_len()?
+    }
+
+    fn encode_value(&self, writer: &mut impl Writer) -> ::der::Result<()> {
+        self.algorithm.encode(writer)?;
+        self.public_key.encode(writer)?;
+        self.proof.encode(writer)
+    }
+}
+
+impl<'a> Sequence<'a> for ProofOfPossession<'a> {}
+
+fn invalid() -> SigmaError {
+    SerializationError::InvalidData.into()
+}
+
+/// The DER encoding of the SEQUENCE of `oid`, `public_key` and `proof`
+fn encode_sequence(oid: ObjectIdentifier, public_key: &[u8], proof: &[u8]) -> Vec<u8> {
+    ProofOfPossession {
+        algorithm: oid,
+        public_key: BitStringRef::from_bytes(public_key).expect("a byte string is a bit string"),
+        proof: OctetStringRef::new(proof)
+            .expect("the proof is shorter than the longest DER length"),
+    }
+    .to_der()
+    .expect("the encoding is shorter than the longest DER length")
+}
+
+/// Decodes the SEQUENCE of [`encode_sequence`] into its public key and proof, for the OID `oid`. The strict DER
+/// decoder of the `der` crate rejects indefinite and non-minimal lengths and trailing bytes
+fn decode_sequence(bytes: &[u8], oid: ObjectIdentifier) -> Result<(&[u8], &[u8]), SigmaError> {
+    let sequence = ProofOfPossession::from_der(bytes).map_err(|_| invalid())?;
+    if sequence.algorithm != oid {
+        return Err(invalid());
+    }
+    let public_key = sequence.public_key.as_bytes().ok_or_else(invalid)?;
+
+    Ok((public_key, sequence.proof.as_bytes()))
+}
+
+/// Decodes the compressed canonical encoding of a value, which must be the only encoding of that value
+fn value<X: CanonicalSerialize + CanonicalDeserialize>(bytes: &[u8]) -> Result<X, SigmaError> {
+    let value = proof_from_bytes(bytes, true)?;
+    if proof_bytes(&value, true) == bytes {
+        Ok(value)
+    } else {
+        Err(invalid())
+    }
+}
+
+impl<S: SigmaProtocol> BatchableProof<S> {
+    /// The DER encoding of the proof and the instance it is made for, as a proof of possession:
+    ///
+    /// ```text
+    /// SEQUENCE {
+    ///     algorithm OBJECT IDENTIFIER,  -- BATCHABLE_PROOF_OID
+    ///     publicKey BIT STRING,         -- the compressed canonical encoding of the instance
+    ///     proof     OCTET STRING        -- the compressed canonical encoding of the proof
+    /// }
+    /// ```
+    pub fn to_der(&self, instance: &S::Instance) -> Vec<u8>
+    where
+        S::Instance: CanonicalSerialize,
+    {
+        encode_sequence(
+            BATCHABLE_PROOF_OID,
+            &proof_bytes(instance, true),
+            &proof_bytes(self, true),
+        )
+    }
+
+    /// Decodes an instance and a proof from their DER encoding. Another OID, BER encodings such as indefinite
+    /// lengths, trailing bytes and non-canonical values are rejected with [`SigmaError::SerializationError`]
+    pub fn from_der(bytes: &[u8]) -> Result<(S::Instance, Self), SigmaError>
+    where
+        S::Instance: CanonicalSerialize + CanonicalDeserialize,
+    {
+        let (instance, proof) = decode_sequence(bytes, BATCHABLE_PROOF_OID)?;
+        Ok((value(instance)?, value(proof)?))
+    }
+}
+
+impl<G: SigmaGroup> Signature<G> {
+    /// The DER encoding of the signature and its verifying key, the SEQUENCE of [`BatchableProof::to_der`] under
+    /// [`SIGNATURE_OID`] with the canonical encodings of the key and the signature
+    pub fn to_der(&self, verifying_key: &VerifyingKey<G>) -> Vec<u8> {
+        encode_sequence(
+            SIGNATURE_OID,
+            &proof_bytes(verifying_key, true),
+            &proof_bytes(self, true),
+        )
+    }
+
+    /// Decodes a verifying key and a signature from their DER encoding, rejecting any other encoding as
+    /// [`BatchableProof::from_der`] does
+    pub fn from_der(bytes: &[u8]) -> Result<(VerifyingKey<G>, Self), SigmaError> {
+        let (verifying_key, signature) = decode_sequence(bytes, SIGNATURE_OID)?;
+        Ok((value(verifying_key)?, value(signature)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use ark_serialize::SerializationError;
+    use rand::thread_rng;
+
+    use crate::{
+        protocols::{SchnorrDLOG, SchnorrInstance},
+        signature::{Signature, SigningKey},
+        BatchableProof, SigmaError, NIZK,
+    };
+
+    use super::{BATCHABLE_PROOF_OID, SIGNATURE_OID};
+
+    type G = ark_bls12_377::G1Projective;
+    type F = ark_bls12_377::Fr;
+    type Schnorr = SchnorrDLOG<G>;
+
+    fn schnorr_setup() -> (SchnorrInstance<G>, F) {
+        SchnorrInstance::generate(&mut thread_rng())
+    }
+
+    fn invalid<T>() -> Result<T, SigmaError> {
+        Err(SigmaError::SerializationError(
+            SerializationError::InvalidData,
+        ))
+    }
+
+    /// The content of the OID of `arcs`, each written in base 128 with the continuation bit
+    fn oid(arcs: &[u64]) -> Vec<u8> {
+        let mut content = vec![(arcs[0] * 40 + arcs[1]) as u8];
+        for arc in &arcs[2..] {
+            let digits = (0..10).rev().map(|digit| (arc >> (7 * digit)) as u8 & 0x7f);
+            let digits: Vec<_> = digits.skip_while(|digit| *digit == 0).collect();
+            let last = digits.len().saturating_sub(1);
+            content.extend(digits.iter().enumerate().map(|(i, digit)| match i {
+                i if i == last => *digit,
+                _ => digit | 0x80,
+            }));
+        }
+        content
+    }
+
+    #[test]
+    fn test_oids() {
+        assert_eq!(
+            BATCHABLE_PROOF_OID.as_bytes(),
+            oid(&[1, 3, 6, 1, 4, 1, 99999, 1, 1])
+        );
+        assert_eq!(
+            SIGNATURE_OID.as_bytes(),
+            oid(&[1, 3, 6, 1, 4, 1, 99999, 1, 2])
+        );
+    }
+
+    #[test]
+    fn test_der_round_trips() {
+        let rng = &mut thread_rng();
+        let (instance, witness) = schnorr_setup();
+        let nizk = NIZK::<Schnorr, blake2::Blake2s>::new(&instance, b"ctx");
+        let proof = nizk.batchable_proof(&witness, None, rng).unwrap();
+
+        let (decoded_instance, decoded) =
+            BatchableProof::<Schnorr>::from_der(&proof.to_der(&instance)).unwrap();
+        assert_eq!(decoded_instance, instance);
+        assert_eq!(decoded, proof);
+        assert!(
+            NIZK::<Schnorr, blake2::Blake2s>::new(&decoded_instance, b"ctx")
+                .batchable_verify(&decoded, None)
+                .is_ok()
+        );
+
+        let signing_key = SigningKey::<G>::generate(rng);
+        let signature = signing_key.sign(b"csr", rng).unwrap();
+        let (verifying_key, decoded) =
+            Signature::<G>::from_der(&signature.to_der(signing_key.verifying_key())).unwrap();
+        assert_eq!(&verifying_key, signing_key.verifying_key());
+        assert!(verifying_key.verify(b"csr", &decoded).is_ok());
+
+        // The OIDs keep proofs and signatures apart
+        assert_eq!(
+            Signature::<G>::from_der(&proof.to_der(&instance)).map(|_| ()),
+            invalid()
+        );
+    }
+
+    #[test]
+    fn test_der_rejects_ber_encodings() {
+        let rng = &mut thread_rng();
+        let (instance, witness) = schnorr_setup();
+        let nizk = NIZK::<Schnorr, blake2::Blake2s>::new(&instance, b"ctx");
+        let bytes = nizk
+            .batchable_proof(&witness, None, rng)
+            .unwrap()
+            .to_der(&instance);
+        let decode = |bytes: &[u8]| BatchableProof::<Schnorr>::from_der(bytes).map(|_| ());
+        assert!(decode(&bytes).is_ok());
+
+        // The encoding is SEQUENCE { OID, BIT STRING, OCTET STRING }, whose content is longer than 127 bytes
+        assert_eq!(bytes[..5], [0x30, 0x81, bytes.len() as u8 - 3, 0x06, 0x0a]);
+        let content = &bytes[3..];
+
+        let mut indefinite = vec![0x30, 0x80];
+        indefinite.extend(content);
+        indefinite.extend([0, 0]);
+        let mut long_length = vec![0x30, 0x82, 0x00, content.len() as u8];
+        long_length.extend(content);
+        let mut other_oid = bytes.clone();
+        other_oid[14] = 0x03;
+        let mut unused_bits = bytes.clone();
+        unused_bits[17] = 0x01;
+        let mut other_tag = bytes.clone();
+        other_tag[15] = 0x04;
+        let mut trailing = bytes.clone();
+        trailing.push(0);
+        let mut trailing_sequence = vec![0x30, 0x81, content.len() as u8 + 1];
+        trailing_sequence.extend(content);
+        trailing_sequence.push(0);
+
+        for encoding in [
+            indefinite,
+            long_length,
+            other_oid,
+            unused_bits,
+            other_tag,
+            trailing,
+            trailing_sequence,
+        ] {
+            assert_eq!(decode(&encoding), invalid());
+        }
+        assert!(decode(&bytes[..bytes.len() - 1]).is_err());
+    }
+}
@@ -0,0 +1,141 @@
+use ark_serialize::SerializationError;
+
+use crate::SigmaError;
+
+const HEX_DIGITS: &[u8; 16] = b"0123456789abcdef";
+
+/// The URL-safe base64 alphabet of RFC 4648, section 5
+const BASE64URL_DIGITS: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+fn invalid() -> SigmaError {
+    SerializationError::InvalidData.into()
+}
+
+/// Lowercase hex encoding of `bytes`
+pub(crate) fn encode_hex(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .flat_map(|byte| [byte >> 4, byte & 0xf])
+        .map(|digit| char::from(HEX_DIGITS[usize::from(digit)]))
+        .collect()
+}
+
+fn hex_digit(digit: u8) -> Result<u8, SigmaError> {
+    match digit {
+        b'0'..=b'9' => Ok(digit - b'0'),
+        b'a'..=b'f' => Ok(digit - b'a' + 10),
+        b'A'..=b'F' => Ok(digit - b'A' + 10),
+        _ => Err(invalid()),
+    }
+}
+
+/// Decodes hex in either case. An odd number of digits, whitespace, a `0x` prefix or any other character is
+/// rejected with [`SerializationError::InvalidData`]
+pub(crate) fn decode_hex(hex: &str) -> Result<Vec<u8>, SigmaError> {
+    let hex = hex.as_bytes();
+    if !hex.len().is_multiple_of(2) {
+        return Err(invalid());
+    }
+
+    hex.chunks(2)
+        .map(|pair| Ok(hex_digit(pair[0])? << 4 | hex_digit(pair[1])?))
+        .collect()
+}
+
+/// Unpadded base64url encoding of `bytes`
+pub(crate) fn encode_base64url(bytes: &[u8]) -> String {
+    bytes
+        .chunks(3)
+        .flat_map(|chunk| {
+            let block = chunk.iter().enumerate().fold(0u32, |block, (i, byte)| {
+                block | u32::from(*byte) << (16 - 8 * i)
+            });
+            (0..=chunk.len())
+                .map(move |i| BASE64URL_DIGITS[(block >> (18 - 6 * i) & 0x3f) as usize])
+        })
+        .map(char::from)
+        .collect()
+}
+
+fn base64url_digit(digit: u8) -> Result<u32, SigmaError> {
+    BASE64URL_DIGITS
+        .iter()
+        .position(|candidate| *candidate == digit)
+        .map(|value| value as u32)
+        .ok_or_else(invalid)
+}
+
+/// Decodes unpadded base64url. Padding, whitespace, characters of the standard alphabet (`+` and `/`), a length
+/// that no encoding has, and unused bits that are not zero are rejected with [`SerializationError::InvalidData`],
+/// so that every byte string has a single encoding
+pub(crate) fn decode_base64url(base64: &str) -> Result<Vec<u8>, SigmaError> {
+    let base64 = base64.as_bytes();
+    if base64.len() % 4 == 1 {
+        return Err(invalid());
+    }
+
+    let mut bytes = Vec::with_capacity(base64.len() / 4 * 3 + 2);
+    for chunk in base64.chunks(4) {
+        let block = chunk
+            .iter()
+            .enumerate()
+            .try_fold(0u32, |block, (i, digit)| {
+                Ok::<_, SigmaError>(block | base64url_digit(*digit)? << (18 - 6 * i))
+            })?;
+        let len = chunk.len() - 1;
+        if block & (0xff_ffff >> (8 * len)) != 0 {
+            return Err(invalid());
+        }
+        bytes.extend(&block.to_be_bytes()[1..=len]);
+    }
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_serialize::SerializationError;
+
+    use crate::SigmaError;
+
+    use super::{decode_base64url, decode_hex, encode_base64url, encode_hex};
+
+    const INVALID: SigmaError = SigmaError::SerializationError(SerializationError::InvalidData);
+
+    #[test]
+    fn test_rfc4648_vectors() {
+        let vectors = [
+            ("", "", ""),
+            ("f", "66", "Zg"),
+            ("fo", "666f", "Zm8"),
+            ("foo", "666f6f", "Zm9v"),
+            ("foob", "666f6f62", "Zm9vYg"),
+            ("fooba", "666f6f6261", "Zm9vYmE"),
+            ("foobar", "666f6f626172", "Zm9vYmFy"),
+        ];
+
+        for (bytes, hex, base64) in vectors {
+            assert_eq!(encode_hex(bytes.as_bytes()), hex);
+            assert_eq!(encode_base64url(bytes.as_bytes()), base64);
+            assert_eq!(decode_hex(hex).unwrap(), bytes.as_bytes());
+            assert_eq!(decode_base64url(base64).unwrap(), bytes.as_bytes());
+        }
+
+        // The URL-safe alphabet
+        assert_eq!(encode_base64url(&[0xfb, 0xff]), "-_8");
+        assert_eq!(decode_hex("0aFf").unwrap(), [0x0a, 0xff]);
+    }
+
+    #[test]
+    fn test_strict_parsing() {
+        for hex in ["abc", "0x00", "00 ", " 00", "0g", "é0"] {
+            assert_eq!(decode_hex(hex), Err(INVALID));
+        }
+
+        for base64 in [
+            "Zg==", "Zm8=", "Zm9v ", "Zm9\nv", "+/8", "Z", "Zm9vY", "Zh", "Zm9", "Zm9vYmF=",
+        ] {
+            assert_eq!(decode_base64url(base64), Err(INVALID));
+        }
+    }
+}
@@ -0,0 +1,616 @@
+use std::{
+    panic::{catch_unwind, AssertUnwindSafe},
+    ptr, slice,
+};
+
+use rand::{rngs::StdRng, SeedableRng};
+
+use crate::{
+    nizk_proofs::{proof_bytes, proof_from_bytes},
+    protocols::{SchnorrDLOG, SchnorrInstance},
+    ristretto::RistrettoElement,
+    BatchableProof, SigmaError, SigmaGroup, CHALLENGE_LENGTH, NIZK,
+};
+
+type Schnorr = SchnorrDLOG<RistrettoElement>;
+
+/// The length of the canonical encoding of a witness, a ristretto255 scalar
+pub const SIGMA_SCALAR_LENGTH: usize = 32;
+
+/// The length of the canonical encoding of an instance, two compressed ristretto255 elements
+pub const SIGMA_INSTANCE_LENGTH: usize = 64;
+
+/// The length of the compressed encoding of a batchable proof, a commitment and a response
+pub const SIGMA_PROOF_LENGTH: usize = 64;
+
+/// The length of a Fiat-Shamir challenge
+pub const SIGMA_CHALLENGE_LENGTH: usize = CHALLENGE_LENGTH;
+
+/// The length of the seeds the functions draw their randomness from
+pub const SIGMA_SEED_LENGTH: usize = 32;
+
+/// The result codes of the C functions: zero for success, and one code per [`SigmaError`] variant, followed by
+/// the failures of the boundary itself
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SigmaErrorCode {
+    /// The call succeeded, or the proof is valid
+    Ok = 0,
+    /// See [`SigmaError::VerificationFailed`]
+    VerificationFailed = 1,
+    /// See [`SigmaError::ChallengeConversionFailure`]
+    ChallengeConversionFailure = 2,
+    /// See [`SigmaError::SerializationError`], as for an encoding that does not decode
+    SerializationError = 3,
+    /// See [`SigmaError::InvalidInstance`]
+    InvalidInstance = 4,
+    /// See [`SigmaError::LengthMismatch`]
+    LengthMismatch = 5,
+    /// See [`SigmaError::CommitmentNotRecoverable`]
+    CommitmentNotRecoverable = 6,
+    /// See [`SigmaError::UnsupportedHashFunction`]
+    UnsupportedHashFunction = 7,
+    /// See [`SigmaError::DigestTooShort`]
+    DigestTooShort = 8,
+    /// See [`SigmaError::EnvelopeMismatch`]
+    EnvelopeMismatch = 9,
+    /// A required pointer was null
+    NullPointer = 10,
+    /// The call panicked, which is a bug of this library
+    Panic = 11,
+    /// See [`SigmaError::ChallengeTooShort`]
+    ChallengeTooShort = 12,
+}
+
+impl From<&SigmaError> for SigmaErrorCode {
+    fn from(error: &SigmaError) -> Self {
+        match error {
+            SigmaError::VerificationFailed => Self::VerificationFailed,
+            SigmaError::ChallengeConversionFailure => Self::ChallengeConversionFailure,
+            SigmaError::SerializationError(_) => Self::SerializationError,
+            SigmaError::InvalidInstance => Self::InvalidInstance,
+            SigmaError::LengthMismatch => Self::LengthMismatch,
+            SigmaError::CommitmentNotRecoverable => Self::CommitmentNotRecoverable,
+            SigmaError::UnsupportedHashFunction => Self::UnsupportedHashFunction,
+            SigmaError::DigestTooShort { .. } => Self::DigestTooShort,
+            SigmaError::EnvelopeMismatch => Self::EnvelopeMismatch,
+            SigmaError::ChallengeTooShort { .. } => Self::ChallengeTooShort,
+        }
+    }
+}
+
+/// A Schnorr instance over ristretto255, opaque to C
+pub struct SigmaSchnorrInstance(SchnorrInstance<RistrettoElement>);
+
+/// A Schnorr NIZK over ristretto255 with Blake2s, opaque to C. It verifies the proofs of
+/// `NIZK::<SchnorrDLOG<RistrettoElement>, blake2::Blake2s>` for the same instance and context
+pub struct SigmaNizk(NIZK<Schnorr, blake2::Blake2s>);
+
+/// Runs `f` and turns its result and any panic into a code
+fn guard(f: impl FnOnce() -> Result<(), SigmaErrorCode>) -> i32 {
+    let code = catch_unwind(AssertUnwindSafe(f))
+        .unwrap_or(Err(SigmaErrorCode::Panic))
+        .err()
+        .unwrap_or(SigmaErrorCode::Ok);
+    code as i32
+}
+
+/// The `len` bytes at `bytes`, which may be null when `len` is zero
+///
+/// # Safety
+/// A non-null `bytes` must point to `len` readable bytes that outlive the slice
+unsafe fn bytes<'a>(bytes: *const u8, len: usize) -> Result<&'a [u8], SigmaErrorCode> {
+    match (bytes.is_null(), len) {
+        (true, 0) => Ok(&[]),
+        (true, _) => Err(SigmaErrorCode::NullPointer),
+        (false, _) => Ok(slice::from_raw_parts(bytes, len)),
+    }
+}
+
+/// The message at `msg`, or no message for a null `msg`
+///
+/// # Safety
+/// A non-null `msg` must point to `msg_len` readable bytes that outlive the slice
+unsafe fn message<'a>(msg: *const u8, msg_len: usize) -> Result<Option<&'a [u8]>, SigmaErrorCode> {
+    if msg.is_null() {
+        Ok(None)
+    } else {
+        bytes(msg, msg_len).map(Some)
+    }
+}
+
+/// The rng seeded with the `SIGMA_SEED_LENGTH` bytes at `seed`
+///
+/// # Safety
+/// `seed` must point to `SIGMA_SEED_LENGTH` readable bytes
+unsafe fn seeded_rng(seed: *const u8) -> Result<StdRng, SigmaErrorCode> {
+    let mut bytes = [0u8; SIGMA_SEED_LENGTH];
+    bytes.copy_from_slice(self::bytes(seed, SIGMA_SEED_LENGTH)?);
+    Ok(StdRng::from_seed(bytes))
+}
+
+/// Writes `bytes` to `out`
+///
+/// # Safety
+/// `out` must point to `bytes.len()` writable bytes
+unsafe fn write_out(bytes: &[u8], out: *mut u8) -> Result<(), SigmaErrorCode> {
+    if out.is_null() {
+        return Err(SigmaErrorCode::NullPointer);
+    }
+    slice::from_raw_parts_mut(out, bytes.len()).copy_from_slice(bytes);
+    Ok(())
+}
+
+fn code(error: SigmaError) -> SigmaErrorCode {
+    SigmaErrorCode::from(&error)
+}
+
+/// Samples a witness from the `SIGMA_SEED_LENGTH` bytes at `seed` and writes its canonical encoding, of
+/// `SIGMA_SCALAR_LENGTH` bytes, to `witness_out`, and the encoding of the instance over the standard generator, of
+/// `SIGMA_INSTANCE_LENGTH` bytes, to `instance_out`. The seed must be fresh randomness, as it determines the
+/// witness
+///
+/// # Safety
+/// `seed` must point to `SIGMA_SEED_LENGTH` readable bytes, `witness_out` to `SIGMA_SCALAR_LENGTH` writable bytes
+/// and `instance_out` to `SIGMA_INSTANCE_LENGTH` writable bytes
+#[no_mangle]
+pub unsafe extern "C" fn sigma_schnorr_keypair(
+    seed: *const u8,
+    witness_out: *mut u8,
+    instance_out: *mut u8,
+) -> i32 {
+    guard(|| {
+        let witness = RistrettoElement::random_scalar(&mut seeded_rng(seed)?);
+        let generator = RistrettoElement::generator();
+        let instance = SchnorrInstance::new(generator, generator.scalar_mul(&witness));
+
+        write_out(&proof_bytes(&witness, true), witness_out)?;
+        write_out(&proof_bytes(&instance, true), instance_out)
+    })
+}
+
+/// Decodes a Schnorr instance from its canonical encoding, the compressed base followed by the compressed claim in
+/// 64 bytes, into `*out`, which must be released with [`sigma_schnorr_instance_free`]. Trailing bytes are rejected
+/// with [`SigmaErrorCode::SerializationError`]
+///
+/// # Safety
+/// `bytes` must point to `len` readable bytes, and `out` to a writable pointer
+#[no_mangle]
+pub unsafe extern "C" fn sigma_schnorr_instance_new(
+    bytes: *const u8,
+    len: usize,
+    out: *mut *mut SigmaSchnorrInstance,
+) -> i32 {
+    guard(|| {
+        if out.is_null() {
+            return Err(SigmaErrorCode::NullPointer);
+        }
+        *out = ptr::null_mut();
+
+        let instance = proof_from_bytes(self::bytes(bytes, len)?, true).map_err(code)?;
+        *out = Box::into_raw(Box::new(SigmaSchnorrInstance(instance)));
+        Ok(())
+    })
+}
+
+/// Releases an instance of [`sigma_schnorr_instance_new`]. Null is ignored
+///
+/// # Safety
+/// `instance` must be null or a pointer returned by [`sigma_schnorr_instance_new`] not released yet
+#[no_mangle]
+pub unsafe extern "C" fn sigma_schnorr_instance_free(instance: *mut SigmaSchnorrInstance) {
+    guard(|| {
+        if !instance.is_null() {
+            drop(Box::from_raw(instance));
+        }
+        Ok(())
+    });
+}
+
+/// Builds the NIZK for `instance` and the context `ctx` into `*out`, which must be released with
+/// [`sigma_nizk_free`]. An instance that proves nothing, as over the identity, is rejected with
+/// [`SigmaErrorCode::InvalidInstance`]
+///
+/// # Safety
+/// `instance` must be a live instance, `ctx` must point to `ctx_len` readable bytes or be null with a zero length,
+/// and `out` must point to a writable pointer
+#[no_mangle]
+pub unsafe extern "C" fn sigma_nizk_new(
+    instance: *const SigmaSchnorrInstance,
+    ctx: *const u8,
+    ctx_len: usize,
+    out: *mut *mut SigmaNizk,
+) -> i32 {
+    guard(|| {
+        if out.is_null() || instance.is_null() {
+            return Err(SigmaErrorCode::NullPointer);
+        }
+        *out = ptr::null_mut();
+
+        let nizk = NIZK::try_new(&(*instance).0, bytes(ctx, ctx_len)?).map_err(code)?;
+        *out = Box::into_raw(Box::new(SigmaNizk(nizk)));
+        Ok(())
+    })
+}
+
+/// Releases a NIZK of [`sigma_nizk_new`]. Null is ignored
+///
+/// # Safety
+/// `nizk` must be null or a pointer returned by [`sigma_nizk_new`] not released yet
+#[no_mangle]
+pub unsafe extern "C" fn sigma_nizk_free(nizk: *mut SigmaNizk) {
+    guard(|| {
+        if !nizk.is_null() {
+            drop(Box::from_raw(nizk));
+        }
+        Ok(())
+    });
+}
+
+/// Verifies the compressed batchable proof of `proof_len` bytes at `proof` against the message of `msg_len` bytes
+/// at `msg`. A null `msg` stands for no message, which is not the same as an empty one. Returns
+/// [`SigmaErrorCode::Ok`] for a valid proof and [`SigmaErrorCode::VerificationFailed`] for an invalid one, and
+/// [`SigmaErrorCode::SerializationError`] for bytes that are not a proof
+///
+/// # Safety
+/// `nizk` must be a live NIZK, `proof` must point to `proof_len` readable bytes, and a non-null `msg` to `msg_len`
+/// readable bytes
+#[no_mangle]
+pub unsafe extern "C" fn sigma_batchable_verify(
+    nizk: *const SigmaNizk,
+    proof: *const u8,
+    proof_len: usize,
+    msg: *const u8,
+    msg_len: usize,
+) -> i32 {
+    guard(|| {
+        if nizk.is_null() || proof.is_null() {
+            return Err(SigmaErrorCode::NullPointer);
+        }
+
+        let (proof, message) = (bytes(proof, proof_len)?, message(msg, msg_len)?);
+        BatchableProof::from_bytes_compressed(proof)
+            .and_then(|proof| (*nizk).0.batchable_verify(&proof, message))
+            .map_err(code)
+    })
+}
+
+/// Proves knowledge of the witness of `witness_len` bytes at `witness`, a canonical scalar, bound to the message
+/// at `msg` as in [`sigma_batchable_verify`], and writes the compressed proof of `SIGMA_PROOF_LENGTH` bytes to
+/// `proof_out`. The commitment randomness is seeded with the `SIGMA_SEED_LENGTH` bytes at `seed`, which must be
+/// fresh randomness, together with the witness and the message. A witness that does not decode is rejected with
+/// [`SigmaErrorCode::SerializationError`]
+///
+/// # Safety
+/// `nizk` must be a live NIZK, `witness` must point to `witness_len` readable bytes, a non-null `msg` to `msg_len`
+/// readable bytes, `seed` to `SIGMA_SEED_LENGTH` readable bytes and `proof_out` to `SIGMA_PROOF_LENGTH` writable
+/// bytes
+#[no_mangle]
+pub unsafe extern "C" fn sigma_batchable_prove(
+    nizk: *const SigmaNizk,
+    witness: *const u8,
+    witness_len: usize,
+    msg: *const u8,
+    msg_len: usize,
+    seed: *const u8,
+    proof_out: *mut u8,
+) -> i32 {
+    guard(|| {
+        if nizk.is_null() || witness.is_null() {
+            return Err(SigmaErrorCode::NullPointer);
+        }
+
+        let witness = proof_from_bytes(bytes(witness, witness_len)?, true).map_err(code)?;
+        let proof = (*nizk)
+            .0
+            .batchable_proof(&witness, message(msg, msg_len)?, &mut seeded_rng(seed)?)
+            .map_err(code)?;
+        write_out(&proof.to_bytes_compressed(), proof_out)
+    })
+}
+
+/// Writes the Fiat-Shamir challenge of the compressed batchable proof at `proof` and the message at `msg`, of
+/// `SIGMA_CHALLENGE_LENGTH` bytes, to `challenge_out`, whether the proof is valid or not, for cross-checking
+/// transcripts with other implementations
+///
+/// # Safety
+/// `nizk` must be a live NIZK, `proof` must point to `proof_len` readable bytes, a non-null `msg` to `msg_len`
+/// readable bytes and `challenge_out` to `SIGMA_CHALLENGE_LENGTH` writable bytes
+#[no_mangle]
+pub unsafe extern "C" fn sigma_batchable_challenge(
+    nizk: *const SigmaNizk,
+    proof: *const u8,
+    proof_len: usize,
+    msg: *const u8,
+    msg_len: usize,
+    challenge_out: *mut u8,
+) -> i32 {
+    guard(|| {
+        if nizk.is_null() || proof.is_null() {
+            return Err(SigmaErrorCode::NullPointer);
+        }
+
+        let (proof, message) = (bytes(proof, proof_len)?, message(msg, msg_len)?);
+        let challenge = BatchableProof::from_bytes_compressed(proof)
+            .and_then(|proof| (*nizk).0.batchable_challenge(&proof, message))
+            .map_err(code)?;
+        write_out(&challenge, challenge_out)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ptr;
+
+    use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+    use rand::thread_rng;
+
+    use crate::{
+        protocols::{SchnorrDLOG, SchnorrInstance},
+        ristretto::RistrettoElement,
+        BatchableProof, SigmaGroup, NIZK,
+    };
+
+    use super::{
+        sigma_batchable_challenge, sigma_batchable_prove, sigma_batchable_verify, sigma_nizk_free,
+        sigma_nizk_new, sigma_schnorr_instance_free, sigma_schnorr_instance_new,
+        sigma_schnorr_keypair, SigmaErrorCode, SigmaNizk, SigmaSchnorrInstance,
+        SIGMA_CHALLENGE_LENGTH, SIGMA_INSTANCE_LENGTH, SIGMA_PROOF_LENGTH, SIGMA_SCALAR_LENGTH,
+        SIGMA_SEED_LENGTH,
+    };
+
+    type Hash = blake2::Blake2s;
+
+    const HEADER: &str = include_str!("../include/sigma_protocol_standard.h");
+
+    const OK: i32 = SigmaErrorCode::Ok as i32;
+
+    fn encode(instance: &SchnorrInstance<RistrettoElement>) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        instance.serialize(&mut bytes).unwrap();
+        bytes
+    }
+
+    /// The NIZK for the instance encoded in `bytes`, through the C functions
+    unsafe fn nizk(bytes: &[u8], ctx: &[u8]) -> Result<*mut SigmaNizk, i32> {
+        let mut instance: *mut SigmaSchnorrInstance = ptr::null_mut();
+        let code = sigma_schnorr_instance_new(bytes.as_ptr(), bytes.len(), &mut instance);
+        if code != OK {
+            assert!(instance.is_null());
+            return Err(code);
+        }
+
+        let mut nizk = ptr::null_mut();
+        let code = sigma_nizk_new(instance, ctx.as_ptr(), ctx.len(), &mut nizk);
+        sigma_schnorr_instance_free(instance);
+        if code == OK {
+            Ok(nizk)
+        } else {
+            assert!(nizk.is_null());
+            Err(code)
+        }
+    }
+
+    #[test]
+    fn test_verify_through_c_abi() {
+        let rng = &mut thread_rng();
+        let generator = RistrettoElement::generator();
+        let witness = RistrettoElement::random_scalar(rng);
+        let instance = SchnorrInstance::new(generator, generator.scalar_mul(&witness));
+        let prover = NIZK::<SchnorrDLOG<_>, Hash>::new(&instance, b"ctx");
+        let proof = prover
+            .batchable_proof(&witness, Some(b"message"), rng)
+            .unwrap()
+            .to_bytes_compressed();
+        let unbound = prover
+            .batchable_proof(&witness, None, rng)
+            .unwrap()
+            .to_bytes_compressed();
+
+        unsafe {
+            let nizk = nizk(&encode(&instance), b"ctx").unwrap();
+            let verify = |proof: &[u8], message: Option<&[u8]>| {
+                let (msg, msg_len) = message.map_or((ptr::null(), 0), |message| {
+                    (message.as_ptr(), message.len())
+                });
+                sigma_batchable_verify(nizk, proof.as_ptr(), proof.len(), msg, msg_len)
+            };
+
+            assert_eq!(verify(&proof, Some(b"message")), OK);
+            assert_eq!(verify(&unbound, None), OK);
+            let failed = SigmaErrorCode::VerificationFailed as i32;
+            assert_eq!(verify(&proof, Some(b"another message")), failed);
+            assert_eq!(verify(&proof, None), failed);
+            assert_eq!(verify(&unbound, Some(b"")), failed);
+            let malformed = SigmaErrorCode::SerializationError as i32;
+            assert_eq!(verify(&proof[1..], Some(b"message")), malformed);
+            assert_eq!(verify(&[proof.clone(), vec![0]].concat(), None), malformed);
+
+            let null = SigmaErrorCode::NullPointer as i32;
+            assert_eq!(
+                sigma_batchable_verify(ptr::null(), proof.as_ptr(), proof.len(), ptr::null(), 0),
+                null
+            );
+            assert_eq!(
+                sigma_batchable_verify(nizk, ptr::null(), 0, ptr::null(), 0),
+                null
+            );
+
+            sigma_nizk_free(nizk);
+            sigma_nizk_free(ptr::null_mut());
+        }
+    }
+
+    #[test]
+    fn test_prove_through_c_abi() {
+        let seed = [7u8; SIGMA_SEED_LENGTH];
+        let mut witness = [0u8; SIGMA_SCALAR_LENGTH];
+        let mut instance = [0u8; SIGMA_INSTANCE_LENGTH];
+        let mut proof = [0u8; SIGMA_PROOF_LENGTH];
+        let mut challenge = [0u8; SIGMA_CHALLENGE_LENGTH];
+
+        unsafe {
+            assert_eq!(
+                sigma_schnorr_keypair(seed.as_ptr(), witness.as_mut_ptr(), instance.as_mut_ptr()),
+                OK
+            );
+            let nizk = nizk(&instance, b"ctx").unwrap();
+            let prove = |witness: &[u8], proof: &mut [u8]| {
+                sigma_batchable_prove(
+                    nizk,
+                    witness.as_ptr(),
+                    witness.len(),
+                    b"message".as_ptr(),
+                    7,
+                    seed.as_ptr(),
+                    proof.as_mut_ptr(),
+                )
+            };
+            assert_eq!(prove(&witness, &mut proof), OK);
+            let code = sigma_batchable_challenge(
+                nizk,
+                proof.as_ptr(),
+                proof.len(),
+                b"message".as_ptr(),
+                7,
+                challenge.as_mut_ptr(),
+            );
+            assert_eq!(code, OK);
+
+            // The proof verifies natively, with the challenge reported through the C ABI
+            let native = NIZK::<SchnorrDLOG<_>, Hash>::new(
+                &SchnorrInstance::<RistrettoElement>::deserialize(&instance[..]).unwrap(),
+                b"ctx",
+            );
+            let decoded = BatchableProof::from_bytes_compressed(&proof).unwrap();
+            assert!(native.batchable_verify(&decoded, Some(b"message")).is_ok());
+            assert_eq!(
+                native
+                    .batchable_challenge(&decoded, Some(b"message"))
+                    .unwrap(),
+                challenge
+            );
+
+            // The seeds make both functions deterministic
+            let mut other_witness = [0u8; SIGMA_SCALAR_LENGTH];
+            let mut other_proof = [0u8; SIGMA_PROOF_LENGTH];
+            assert_eq!(
+                sigma_schnorr_keypair(
+                    seed.as_ptr(),
+                    other_witness.as_mut_ptr(),
+                    instance.as_mut_ptr()
+                ),
+                OK
+            );
+            assert_eq!(other_witness, witness);
+            assert_eq!(prove(&witness, &mut other_proof), OK);
+            assert_eq!(other_proof, proof);
+
+            assert_eq!(
+                prove(&[0xff; SIGMA_SCALAR_LENGTH], &mut other_proof),
+                SigmaErrorCode::SerializationError as i32
+            );
+            assert_eq!(
+                prove(&witness[1..], &mut other_proof),
+                SigmaErrorCode::SerializationError as i32
+            );
+            assert_eq!(
+                sigma_schnorr_keypair(ptr::null(), witness.as_mut_ptr(), instance.as_mut_ptr()),
+                SigmaErrorCode::NullPointer as i32
+            );
+            sigma_nizk_free(nizk);
+        }
+    }
+
+    #[test]
+    fn test_reject_bad_instances_through_c_abi() {
+        let generator = RistrettoElement::generator();
+        let identity = generator - generator;
+
+        unsafe {
+            assert_eq!(
+                nizk(&encode(&SchnorrInstance::new(identity, generator)), b"ctx"),
+                Err(SigmaErrorCode::InvalidInstance as i32)
+            );
+
+            let bytes = encode(&SchnorrInstance::new(generator, generator));
+            for malformed in [&bytes[1..], &[bytes.clone(), vec![0]].concat(), &[0xff; 64]] {
+                assert_eq!(
+                    nizk(malformed, b"ctx"),
+                    Err(SigmaErrorCode::SerializationError as i32)
+                );
+            }
+
+            assert_eq!(
+                sigma_schnorr_instance_new(bytes.as_ptr(), bytes.len(), ptr::null_mut()),
+                SigmaErrorCode::NullPointer as i32
+            );
+            sigma_schnorr_instance_free(ptr::null_mut());
+        }
+    }
+
+    #[test]
+    fn test_header_matches_abi() {
+        let codes = [
+            ("SIGMA_OK", SigmaErrorCode::Ok),
+            (
+                "SIGMA_VERIFICATION_FAILED",
+                SigmaErrorCode::VerificationFailed,
+            ),
+            (
+                "SIGMA_CHALLENGE_CONVERSION_FAILURE",
+                SigmaErrorCode::ChallengeConversionFailure,
+            ),
+            (
+                "SIGMA_SERIALIZATION_ERROR",
+                SigmaErrorCode::SerializationError,
+            ),
+            ("SIGMA_INVALID_INSTANCE", SigmaErrorCode::InvalidInstance),
+            ("SIGMA_LENGTH_MISMATCH", SigmaErrorCode::LengthMismatch),
+            (
+                "SIGMA_COMMITMENT_NOT_RECOVERABLE",
+                SigmaErrorCode::CommitmentNotRecoverable,
+            ),
+            (
+                "SIGMA_UNSUPPORTED_HASH_FUNCTION",
+                SigmaErrorCode::UnsupportedHashFunction,
+            ),
+            ("SIGMA_DIGEST_TOO_SHORT", SigmaErrorCode::DigestTooShort),
+            ("SIGMA_ENVELOPE_MISMATCH", SigmaErrorCode::EnvelopeMismatch),
+            ("SIGMA_NULL_POINTER", SigmaErrorCode::NullPointer),
+            ("SIGMA_PANIC", SigmaErrorCode::Panic),
+            (
+                "SIGMA_CHALLENGE_TOO_SHORT",
+                SigmaErrorCode::ChallengeTooShort,
+            ),
+        ];
+        for (name, code) in codes {
+            let declaration = format!("  {} = {},", name, code as i32);
+            assert!(HEADER.contains(&declaration), "missing `{}`", declaration);
+        }
+
+        for function in [
+            "int32_t sigma_schnorr_instance_new(",
+            "void sigma_schnorr_instance_free(",
+            "int32_t sigma_nizk_new(",
+            "void sigma_nizk_free(",
+            "int32_t sigma_batchable_verify(",
+            "int32_t sigma_schnorr_keypair(",
+            "int32_t sigma_batchable_prove(",
+            "int32_t sigma_batchable_challenge(",
+        ] {
+            assert!(HEADER.contains(function), "missing `{}`", function);
+        }
+
+        for (name, value) in [
+            ("SIGMA_SCALAR_LENGTH", SIGMA_SCALAR_LENGTH),
+            ("SIGMA_INSTANCE_LENGTH", SIGMA_INSTANCE_LENGTH),
+            ("SIGMA_PROOF_LENGTH", SIGMA_PROOF_LENGTH),
+            ("SIGMA_CHALLENGE_LENGTH", SIGMA_CHALLENGE_LENGTH),
+            ("SIGMA_SEED_LENGTH", SIGMA_SEED_LENGTH),
+        ] {
+            let definition = format!("#define {} {}\n", name, value);
+            assert!(HEADER.contains(&definition), "missing `{}`", definition);
+        }
+    }
+}
@@ -0,0 +1,327 @@
+use std::fmt::Debug;
+use std::ops::{Add, Mul, Sub};
+
+use ark_ec::{
+    msm::{FixedBaseMSM, VariableBaseMSM},
+    ProjectiveCurve,
+};
+use ark_ff::{BigInteger, FpParameters, PrimeField};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::UniformRand;
+use rand::Rng;
+
+use crate::{protocols::challenge_scalar, Challenge, ChallengeExpansion, SigmaError};
+
+/// A value that can be wiped from memory. With the `zeroize` feature this is [`zeroize::Zeroize`], which every
+/// scalar of a [`SigmaGroup`] must implement so that witnesses and nonces are wiped on drop. Without it every type
+/// is `Zeroizable` and nothing is wiped
+#[cfg(feature = "zeroize")]
+pub trait Zeroizable: zeroize::Zeroize {}
+
+#[cfg(feature = "zeroize")]
+impl<T: zeroize::Zeroize> Zeroizable for T {}
+
+/// A value that can be wiped from memory. With the `zeroize` feature this is `zeroize::Zeroize`, which every
+/// scalar of a [`SigmaGroup`] must implement so that witnesses and nonces are wiped on drop. Without it every type
+/// is `Zeroizable` and nothing is wiped
+#[cfg(not(feature = "zeroize"))]
+pub trait Zeroizable {}
+
+#[cfg(not(feature = "zeroize"))]
+impl<T> Zeroizable for T {}
+
+/// Wipes `value` with the `zeroize` feature, and does nothing without it
+pub(crate) fn wipe<T: Zeroizable>(value: &mut T) {
+    #[cfg(feature = "zeroize")]
+    value.zeroize();
+    #[cfg(not(feature = "zeroize"))]
+    let _ = value;
+}
+
+/// The operations a prime-order group must provide for the protocols of this crate.
+///
+/// Every arkworks curve is a `SigmaGroup` through a blanket implementation; other group libraries can be
+/// supported by implementing this trait for a wrapper type.
+pub trait SigmaGroup:
+    Copy
+    + Eq
+    + Debug
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + CanonicalSerialize
+    + CanonicalDeserialize
+{
+    /// The scalar field of the group
+    type Scalar: Copy
+        + Eq
+        + Debug
+        + Add<Output = Self::Scalar>
+        + Sub<Output = Self::Scalar>
+        + Mul<Output = Self::Scalar>
+        + CanonicalSerialize
+        + CanonicalDeserialize
+        + Zeroizable;
+
+    /// The standard generator of the group
+    fn generator() -> Self;
+
+    /// Whether the element is the identity of the group
+    fn is_identity(&self) -> bool;
+
+    /// Whether the element lies in the prime-order subgroup the scalars act on. Groups of prime order, which
+    /// contain no other element, keep the default
+    fn is_in_prime_subgroup(&self) -> bool {
+        true
+    }
+
+    /// Multiply the group element by a scalar
+    fn scalar_mul(&self, scalar: &Self::Scalar) -> Self;
+
+    /// The linear combination `sum(scalars[i] * bases[i])`, as computed by verifiers. It may run in variable time,
+    /// so the scalars must be public. Defaults to a scalar multiplication per base; backends with a multi-scalar
+    /// multiplication override it. Panics if the lengths differ
+    fn multi_scalar_mul(bases: &[Self], scalars: &[Self::Scalar]) -> Self {
+        assert_eq!(bases.len(), scalars.len(), "one scalar per base");
+        let identity = Self::generator() - Self::generator();
+
+        bases
+            .iter()
+            .zip(scalars)
+            .fold(identity, |sum, (base, scalar)| {
+                sum + base.scalar_mul(scalar)
+            })
+    }
+
+    /// Puts the elements in the normal form of their encoding, such as affine coordinates with a single inversion
+    /// for all of them. Defaults to nothing, for groups whose elements need no normalization
+    fn batch_normalize(_elements: &mut [Self]) {}
+
+    /// Sample a uniformly random scalar
+    fn random_scalar<R: Rng>(rng: &mut R) -> Self::Scalar;
+
+    /// Map the challenge bytes to a scalar. Implementations should reduce the challenge expanded with `expansion`
+    /// to at least 128 bits more than the group order, as arkworks curves do, so that the conversion never fails
+    fn challenge_scalar(
+        challenge: &Challenge,
+        expansion: ChallengeExpansion,
+    ) -> Result<Self::Scalar, SigmaError>;
+}
+
+impl<G: ProjectiveCurve> SigmaGroup for G {
+    type Scalar = G::ScalarField;
+
+    fn generator() -> Self {
+        G::prime_subgroup_generator()
+    }
+
+    fn is_identity(&self) -> bool {
+        self.is_zero()
+    }
+
+    /// Curves with a cofactor have points outside the prime-order subgroup, which multiplying by the group order
+    /// does not send to the identity
+    fn is_in_prime_subgroup(&self) -> bool {
+        self.mul(<G::ScalarField as PrimeField>::Params::MODULUS)
+            .is_zero()
+    }
+
+    fn scalar_mul(&self, scalar: &Self::Scalar) -> Self {
+        self.mul(scalar.into_repr())
+    }
+
+    fn batch_normalize(elements: &mut [Self]) {
+        G::batch_normalization(elements);
+    }
+
+    /// A Pippenger multi-scalar multiplication over the bases normalized with a single inversion
+    fn multi_scalar_mul(bases: &[Self], scalars: &[Self::Scalar]) -> Self {
+        assert_eq!(bases.len(), scalars.len(), "one scalar per base");
+        let scalars: Vec<_> = scalars.iter().map(|scalar| scalar.into_repr()).collect();
+
+        VariableBaseMSM::multi_scalar_mul(&G::batch_normalization_into_affine(bases), &scalars)
+    }
+
+    fn random_scalar<R: Rng>(rng: &mut R) -> Self::Scalar {
+        G::ScalarField::rand(rng)
+    }
+
+    fn challenge_scalar(
+        challenge: &Challenge,
+        expansion: ChallengeExpansion,
+    ) -> Result<Self::Scalar, SigmaError> {
+        challenge_scalar(challenge, expansion)
+    }
+}
+
+/// The Pippenger multi-scalar multiplication of arkworks over scalars below `2^bits`, which walks the windows of
+/// `bits` bits only instead of those of the modulus: over 128-bit scalars of a 253-bit field it runs half the
+/// windows, and so about half the doublings and bucket sums. Panics if the lengths differ
+pub(crate) fn short_multi_scalar_mul<G: ProjectiveCurve>(
+    bases: &[G::Affine],
+    scalars: &[<G::ScalarField as PrimeField>::BigInt],
+    bits: u32,
+) -> G {
+    assert_eq!(bases.len(), scalars.len(), "one scalar per base");
+    let bits = bits.min(<G::ScalarField as PrimeField>::Params::MODULUS_BITS) as usize;
+    // The window width of arkworks
+    let window = match bases.len() {
+        0..=31 => 3,
+        len => (ark_std::log2(len) * 69 / 100) as usize + 2,
+    };
+
+    let mut sum = G::zero();
+    for start in (0..bits).step_by(window).rev() {
+        for _ in 0..window {
+            sum.double_in_place();
+        }
+
+        let mut buckets = vec![G::zero(); (1 << window) - 1];
+        for (base, scalar) in bases.iter().zip(scalars) {
+            let mut scalar = *scalar;
+            scalar.divn(start as u32);
+            let digit = scalar.as_ref()[0] % (1 << window);
+            if digit != 0 {
+                buckets[digit as usize - 1].add_assign_mixed(base);
+            }
+        }
+
+        // The sum of `digit * bucket` over the digits, as the sum of the suffix sums of the buckets
+        let mut suffix = G::zero();
+        for bucket in buckets.iter().rev() {
+            suffix += bucket;
+            sum += &suffix;
+        }
+    }
+    sum
+}
+
+/// The window of fixed-base tables in bits: a table holds `2^FIXED_BASE_WINDOW` multiples of the base for every
+/// window of the scalar
+const FIXED_BASE_WINDOW: usize = 6;
+
+/// Precomputed multiples of a fixed point of an arkworks curve. Multiplying the point by a scalar then takes one
+/// mixed addition per 6-bit window of the scalar, instead of a double-and-add over all of its bits. Building the
+/// table costs about as much as twenty scalar multiplications, so it pays off for a point multiplied many times,
+/// such as the base of a Schnorr instance proven over and over. As the scalar multiplication of arkworks, the table
+/// lookups are not constant-time
+pub struct FixedBaseTable<G: ProjectiveCurve> {
+    base: G,
+    windows: usize,
+    multiples: Vec<Vec<G::Affine>>,
+}
+
+impl<G: ProjectiveCurve> FixedBaseTable<G> {
+    /// Precompute the multiples of `base`
+    pub fn new(base: G) -> Self {
+        let scalar_bits = <G::ScalarField as PrimeField>::Params::MODULUS_BITS as usize;
+
+        Self {
+            base,
+            windows: scalar_bits.div_ceil(FIXED_BASE_WINDOW),
+            multiples: FixedBaseMSM::get_window_table(scalar_bits, FIXED_BASE_WINDOW, base),
+        }
+    }
+
+    /// The point the table is for
+    pub fn base(&self) -> G {
+        self.base
+    }
+
+    /// `scalar * base`
+    pub fn mul(&self, scalar: &G::ScalarField) -> G {
+        FixedBaseMSM::windowed_mul(self.windows, FIXED_BASE_WINDOW, &self.multiples, scalar)
+    }
+}
+
+/// The multiplication by a fixed point, through which protocols generic over [`SigmaGroup`] use the tables of
+/// the groups that have them
+pub(crate) trait FixedBaseMul<G: SigmaGroup>: Send + Sync {
+    fn mul(&self, scalar: &G::Scalar) -> G;
+}
+
+impl<G: ProjectiveCurve> FixedBaseMul<G> for FixedBaseTable<G> {
+    fn mul(&self, scalar: &G::ScalarField) -> G {
+        FixedBaseTable::mul(self, scalar)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_ec::ProjectiveCurve;
+    use ark_ff::{One, PrimeField, UniformRand};
+    use rand::thread_rng;
+
+    use crate::BatchRandomizers;
+
+    use super::{short_multi_scalar_mul, FixedBaseTable, SigmaGroup};
+
+    type G = ark_bls12_377::G1Projective;
+    type F = ark_bls12_377::Fr;
+
+    #[test]
+    fn test_multi_scalar_mul_matches_scalar_muls() {
+        let rng = &mut thread_rng();
+        let bases: Vec<_> = (0..3).map(|_| G::rand(rng)).collect();
+        let scalars: Vec<_> = (0..3).map(|_| F::rand(rng)).collect();
+
+        assert_eq!(
+            G::multi_scalar_mul(&bases, &scalars),
+            bases[0].scalar_mul(&scalars[0])
+                + bases[1].scalar_mul(&scalars[1])
+                + bases[2].scalar_mul(&scalars[2])
+        );
+        assert!(G::multi_scalar_mul(&[], &[]).is_identity());
+    }
+
+    #[test]
+    fn test_short_multi_scalar_mul_matches_multi_scalar_mul() {
+        let rng = &mut thread_rng();
+        // Below and above the 32 bases from which the window widens, and over widths up to past the modulus
+        for len in [0, 3, 40] {
+            let bases: Vec<_> = (0..len).map(|_| G::rand(rng)).collect();
+            for bits in [1, 64, 128, 253, 300] {
+                let scalars: Vec<F> = (0..len)
+                    .map(|_| BatchRandomizers::with_bits(bits).sample(rng))
+                    .collect();
+                let reprs: Vec<_> = scalars.iter().map(|scalar| scalar.into_repr()).collect();
+
+                assert_eq!(
+                    short_multi_scalar_mul::<G>(
+                        &G::batch_normalization_into_affine(&bases),
+                        &reprs,
+                        bits
+                    ),
+                    G::multi_scalar_mul(&bases, &scalars)
+                );
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "one scalar per base")]
+    fn test_multi_scalar_mul_rejects_length_mismatch() {
+        let _ = G::multi_scalar_mul(&[G::rand(&mut thread_rng())], &[]);
+    }
+
+    #[test]
+    fn test_fixed_base_table_matches_scalar_mul() {
+        let rng = &mut thread_rng();
+        let base = G::rand(rng);
+        let table = FixedBaseTable::new(base);
+
+        for scalar in [F::from(0u64), F::from(1u64), -F::from(1u64), F::rand(rng)] {
+            assert_eq!(table.mul(&scalar), base.scalar_mul(&scalar));
+        }
+    }
+
+    #[test]
+    fn test_batch_normalize_keeps_points() {
+        let rng = &mut thread_rng();
+        let points: Vec<_> = (0..4).map(|_| G::rand(rng).double()).collect();
+        let mut normalized = points.clone();
+
+        G::batch_normalize(&mut normalized);
+        assert_eq!(normalized, points);
+        assert!(normalized.iter().all(|point| point.z.is_one()));
+    }
+}
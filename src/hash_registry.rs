@@ -1,7 +1,29 @@
+use std::str::FromStr;
+
+use blake2::{Blake2b, Blake2s};
+use digest::{
+    consts::U64, DynDigest, ExtendableOutput, FixedOutput, Output, Reset, Update, XofReader,
+};
+use sha2::Sha256;
+use sha3::{Sha3_256, Sha3_512, Shake256};
+
+use crate::SigmaError;
+
 /// All supported hash function
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum HashFunction {
+    /// BLAKE2b with a 64-byte output
     Blake2b,
+    /// BLAKE2s with a 32-byte output
+    Blake2s,
+    /// SHA-256
+    SHA256,
+    /// SHA3 with a 32-byte output
     SHA3_256,
+    /// SHA3 with a 64-byte output
+    SHA3_512,
+    /// SHAKE256 squeezed to 64 bytes
+    SHAKE256,
 }
 
 impl HashFunction {
@@ -9,7 +31,11 @@ impl HashFunction {
     pub fn block_len(&self) -> usize {
         match self {
             HashFunction::Blake2b => 128,
+            HashFunction::Blake2s => 64,
+            HashFunction::SHA256 => 64,
             HashFunction::SHA3_256 => 136,
+            HashFunction::SHA3_512 => 72,
+            HashFunction::SHAKE256 => 136,
         }
     }
 
@@ -17,7 +43,153 @@ impl HashFunction {
     pub fn digest_len(&self) -> usize {
         match self {
             HashFunction::Blake2b => 64,
+            HashFunction::Blake2s => 32,
+            HashFunction::SHA256 => 32,
             HashFunction::SHA3_256 => 32,
+            HashFunction::SHA3_512 => 64,
+            HashFunction::SHAKE256 => 64,
+        }
+    }
+
+    /// Returns the name identifying the hash function, as parsed by [`FromStr`] and absorbed into the domain
+    /// separator of proofs made with it.
+    pub fn name(&self) -> &'static str {
+        match self {
+            HashFunction::Blake2b => "blake2b",
+            HashFunction::Blake2s => "blake2s",
+            HashFunction::SHA256 => "sha256",
+            HashFunction::SHA3_256 => "sha3-256",
+            HashFunction::SHA3_512 => "sha3-512",
+            HashFunction::SHAKE256 => "shake256",
+        }
+    }
+
+    /// Returns the identifier of the hash function in the registry, as recorded in proof envelopes.
+    pub fn id(&self) -> u8 {
+        match self {
+            HashFunction::Blake2b => 1,
+            HashFunction::Blake2s => 2,
+            HashFunction::SHA256 => 3,
+            HashFunction::SHA3_256 => 4,
+            HashFunction::SHA3_512 => 5,
+            HashFunction::SHAKE256 => 6,
         }
     }
+
+    /// Returns a fresh hasher, or [`SigmaError::UnsupportedHashFunction`] if this build has no implementation of
+    /// the hash function.
+    pub fn hasher(&self) -> Result<Box<dyn DynDigest>, SigmaError> {
+        match self {
+            HashFunction::Blake2b => Ok(Box::new(Blake2b::default())),
+            HashFunction::Blake2s => Ok(Box::new(Blake2s::default())),
+            HashFunction::SHA256 => Ok(Box::new(Sha256::default())),
+            HashFunction::SHA3_256 => Ok(Box::new(Sha3_256::default())),
+            HashFunction::SHA3_512 => Ok(Box::new(Sha3_512::default())),
+            HashFunction::SHAKE256 => Ok(Box::new(FixedShake256::default())),
+        }
+    }
+}
+
+/// SHAKE256 squeezed to the 64 bytes of the registry, as a fixed-output digest
+#[derive(Clone, Default)]
+struct FixedShake256(Shake256);
+
+impl Update for FixedShake256 {
+    fn update(&mut self, data: impl AsRef<[u8]>) {
+        self.0.update(data);
+    }
+}
+
+impl FixedOutput for FixedShake256 {
+    type OutputSize = U64;
+
+    fn finalize_into(self, out: &mut Output<Self>) {
+        self.0.finalize_xof().read(out);
+    }
+
+    fn finalize_into_reset(&mut self, out: &mut Output<Self>) {
+        self.0.finalize_xof_reset().read(out);
+    }
+}
+
+impl Reset for FixedShake256 {
+    fn reset(&mut self) {
+        self.0.reset();
+    }
+}
+
+impl FromStr for HashFunction {
+    type Err = SigmaError;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        [
+            HashFunction::Blake2b,
+            HashFunction::Blake2s,
+            HashFunction::SHA256,
+            HashFunction::SHA3_256,
+            HashFunction::SHA3_512,
+            HashFunction::SHAKE256,
+        ]
+        .into_iter()
+        .find(|hash| hash.name() == name)
+        .ok_or(SigmaError::UnsupportedHashFunction)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::HashFunction;
+    use crate::{encoding::encode_hex, SigmaError};
+
+    const HASHES: [HashFunction; 6] = [
+        HashFunction::Blake2b,
+        HashFunction::Blake2s,
+        HashFunction::SHA256,
+        HashFunction::SHA3_256,
+        HashFunction::SHA3_512,
+        HashFunction::SHAKE256,
+    ];
+
+    #[test]
+    fn test_hashers_have_the_registered_length() {
+        for hash in HASHES {
+            assert_eq!(hash.hasher().unwrap().output_size(), hash.digest_len());
+            assert_eq!(hash.name().parse(), Ok(hash));
+        }
+
+        assert_eq!(
+            "md5".parse::<HashFunction>(),
+            Err(SigmaError::UnsupportedHashFunction)
+        );
+    }
+
+    #[test]
+    fn test_sha3_hashers_known_answer() {
+        // The digests of "abc" from FIPS 202, SHAKE256 squeezed to 64 bytes, and the same across a reset
+        let digest = |hash: HashFunction| {
+            let mut hasher = hash.hasher().unwrap();
+            hasher.update(b"discarded");
+            hasher.reset();
+            hasher.update(b"ab");
+            hasher.update(b"c");
+            let first = hasher.finalize_reset();
+            hasher.update(b"abc");
+            assert_eq!(hasher.finalize(), first);
+            encode_hex(&first)
+        };
+        assert_eq!(
+            digest(HashFunction::SHA3_256),
+            "3a985da74fe225b2045c172d6bd390bd855f086e3e9d525b46bfe24511431532"
+        );
+        assert_eq!(
+            digest(HashFunction::SHA3_512),
+            "b751850b1a57168a5693cd924b6b096e08f621827444f70d884f5d0240d2712e\
+             10e116e9192af3c91a7ec57647e3934057340b4cf408d5a56592f8274eec53f0"
+        );
+        assert_eq!(
+            digest(HashFunction::SHAKE256),
+            "483366601360a8771c6863080cc4114d8db44530f8f1e1ee4f94ea37e78b5739\
+             d5a15bef186a5386c75744c0527e1faa9f8726e462a12a4feb06bd8801e751e4"
+        );
+    }
 }
@@ -1,45 +1,525 @@
+use std::sync::Arc;
+
+use ark_ff::PrimeField;
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
-use rand::Rng;
+use blake2::{Blake2b, Digest};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use subtle::{Choice, ConstantTimeEq};
+
+use crate::{
+    transcript::compress_digest, CanonicalAbsorb, Challenge, ChallengeExpansion, SigmaError,
+    CHALLENGE_LENGTH, LABEL_LENGTH,
+};
 
-use crate::{Challenge, SigmaError, CHALLENGE_LENGTH};
+/// Domain separator of the seed of [`SigmaProtocol::is_valid_witness`]
+const WITNESS_CHECK_DOMSEP: &[u8] = b"zkpstd/sigma/witness-check";
 
 #[allow(missing_docs)]
 /// Interface for a Sigma protocol. **WARNING**: As explained in the standard, Sigma protocols are *not* to be used interactively
 pub trait SigmaProtocol {
     type Instance;
     type Witness;
-    type Commitment: CanonicalSerialize + CanonicalDeserialize;
+    /// Absorbed into the challenge in its [`CanonicalAbsorb`] encoding
+    type Commitment: CanonicalSerialize + CanonicalDeserialize + CanonicalAbsorb;
     type ProverState;
     type Response: CanonicalSerialize + CanonicalDeserialize;
 
-    fn label(&self) -> [u8; CHALLENGE_LENGTH];
+    /// The challenge as the protocol reads it, such as a scalar, into which
+    /// [`SigmaProtocol::challenge_from_bytes`] converts the bytes of the Fiat-Shamir challenge. Protocols without a
+    /// challenge space of their own keep the bytes, as the compositions do to hand them on to their components
+    type Challenge: Clone;
+
+    /// The identifier of the protocol, such as `b"zkpstd/sigma/dlog"`
+    const PROTOCOL_ID: &'static [u8];
+
+    /// The shortest challenges, in bytes, the protocol is sound with. Protocols reviewed for shorter challenges
+    /// state it; the others only support the full `CHALLENGE_LENGTH`. Shorter challenges reach protocols followed
+    /// by zeros, so that they still read `CHALLENGE_LENGTH` bytes
+    const MIN_CHALLENGE_LENGTH: usize = CHALLENGE_LENGTH;
+
+    /// The last `LABEL_LENGTH` bytes of the Blake2b hash of the length-prefixed protocol identifier and the instance
+    /// encoding. Compositions hash their parameters and the labels of their components in place of an instance
+    fn label(&self) -> [u8; LABEL_LENGTH] {
+        protocol_label(Self::PROTOCOL_ID, &self.instance_bytes())
+    }
 
     fn new(instance: &Self::Instance) -> Self;
 
+    /// The protocol for an instance shared with its other users, as the prover and the verifier of
+    /// [`NIZK::from_shared`](crate::NIZK::from_shared). Defaults to copying the instance as [`SigmaProtocol::new`]
+    /// does; the protocols whose instances grow with the statement, as
+    /// [`Representation`](crate::protocols::Representation), hold the shared instance instead
+    fn from_shared(instance: Arc<Self::Instance>) -> Self
+    where
+        Self: Sized,
+    {
+        Self::new(&instance)
+    }
+
+    /// Converts the bytes of a challenge into the challenge space, or fails with
+    /// [`SigmaError::ChallengeConversionFailure`] for bytes outside of it. The NIZK converts every challenge it
+    /// derives before the protocol reads it, with the [`ChallengeExpansion`] of its transcript; the protocols over
+    /// a [`SigmaGroup`](crate::SigmaGroup) reduce the bytes expanded with `expansion` into a scalar, which never
+    /// fails
+    fn challenge_from_bytes(
+        challenge: &Challenge,
+        expansion: ChallengeExpansion,
+    ) -> Result<Self::Challenge, SigmaError>;
+
+    /// Rejects an instance for which proofs prove nothing, such as a Schnorr instance over the identity, with
+    /// [`SigmaError::InvalidInstance`]. The NIZK checks it when it is built. Defaults to accepting every instance;
+    /// compositions check each of their components
+    fn validate_instance(&self) -> Result<(), SigmaError> {
+        Ok(())
+    }
+
+    /// The canonical encoding of the instance, absorbed into the Fiat-Shamir challenge
+    fn instance_bytes(&self) -> Vec<u8>;
+
+    /// The canonical encoding of the witness, absorbed with the instance, the message and fresh randomness into the
+    /// seed of the commitment randomness of the NIZK. It must encode the whole witness: with a stuck rng, two
+    /// witnesses of the same statement sharing their encoding get the same nonces for the same message, and the
+    /// responses to two challenges then reveal their difference. Compositions encode the witness of every component
+    fn witness_bytes(&self, witness: &Self::Witness) -> Vec<u8>;
+
+    /// Whether `witness` is a witness for the instance, so that a witness from an external source can be checked
+    /// before proving. Defaults to running the protocol once with a challenge and commitment randomness derived
+    /// from the instance and the witness encoding, which a wrong witness fails with overwhelming probability
+    /// unless it was crafted for that challenge, in which case its proofs fail to verify anyway. Protocols with a
+    /// direct check override it. The run converts its challenge with the default [`ChallengeExpansion`], as an
+    /// honest run accepts under every expansion
+    fn is_valid_witness(&self, witness: &Self::Witness) -> bool {
+        // Seeded rather than drawn from `thread_rng`, so that the crate needs no OS randomness
+        let mut hasher = Blake2b::new();
+        hasher.update(WITNESS_CHECK_DOMSEP);
+        hasher.update(self.instance_bytes());
+        hasher.update(self.witness_bytes(witness));
+        let rng = &mut StdRng::from_seed(compress_digest(&hasher.finalize()));
+        let expansion = ChallengeExpansion::default();
+        let challenge = Self::challenge_from_bytes(&rng.gen(), expansion);
+        challenge
+            .and_then(|challenge| {
+                let (commitment, mut state) = self.prover_commit(witness, expansion, rng)?;
+                let response = self.prover_response(&state, &challenge);
+                self.zeroize_state(&mut state);
+                self.verifier(&commitment, &challenge, &response?)
+            })
+            .is_ok()
+    }
+
+    /// The commitment and the state answering the challenge. Compositions simulating a component, as the OR
+    /// compositions do, convert the challenge of the simulation with `expansion`, which must be the expansion the
+    /// challenge will be converted with; the other protocols ignore it
     fn prover_commit<R: Rng>(
         &self,
         witness: &Self::Witness,
+        expansion: ChallengeExpansion,
         rng: &mut R,
-    ) -> (Self::Commitment, Self::ProverState);
+    ) -> Result<(Self::Commitment, Self::ProverState), SigmaError>;
 
     fn prover_response(
         &self,
         prover_state: &Self::ProverState,
-        challenge: &Challenge,
+        challenge: &Self::Challenge,
     ) -> Result<Self::Response, SigmaError>;
 
     fn verifier(
         &self,
         commitment: &Self::Commitment,
-        challenge: &Challenge,
+        challenge: &Self::Challenge,
         response: &Self::Response,
     ) -> Result<(), SigmaError>;
 
     fn simulate_response<R: Rng>(&self, rng: &mut R) -> Self::Response;
 
+    /// Puts commitments in the normal form of their encoding, as affine coordinates for projective points, with a
+    /// single field inversion for all of them. The NIZK normalizes the commitments it hashes, so that neither the
+    /// challenges nor the encoding of the proofs invert each commitment on its own. Defaults to leaving them as
+    /// they are, which only costs those inversions
+    fn normalize_commitments(&self, _commitments: &mut [Self::Commitment]) {}
+
+    /// Wipes the witness and the randomness `prover_state` holds, with the `zeroize` feature. The NIZK wipes every
+    /// state it commits with once the response is computed, or proving failed. Defaults to nothing, for the states
+    /// holding no secret; every protocol and composition of the crate wipes its state, compositions through the
+    /// states of their components
+    fn zeroize_state(&self, _prover_state: &mut Self::ProverState) {}
+}
+
+/// A Sigma protocol whose commitment is determined by the challenge and the response, so that it can be recomputed
+/// from them. Short proofs, which carry the challenge in place of the commitment, and the simulators of the OR
+/// compositions need it; [`NIZK::short_proof`](crate::NIZK::short_proof) and the other short-proof methods are
+/// only available for protocols implementing it, so that a protocol which cannot recompute its commitment, as
+/// [`RangeProof`](crate::protocols::RangeProof), does not offer short proofs at all:
+///
+/// ```compile_fail
+/// use sigma_protocol_standard::{
+///     protocols::{RangeProof, RangeWitness},
+///     NIZK,
+/// };
+///
+/// type G = ark_bls12_377::G1Projective;
+///
+/// fn prove(nizk: &NIZK<RangeProof<G>, blake2::Blake2s>, witness: &RangeWitness<G>) {
+///     let _ = nizk.short_proof(witness, None, &mut rand::thread_rng());
+/// }
+/// ```
+pub trait CommitmentRecoverable: SigmaProtocol {
+    /// The only commitment for which `(commitment, challenge, response)` is an accepting transcript
     fn simulate_commitment(
         &self,
-        challenge: &Challenge,
+        challenge: &Self::Challenge,
         response: &Self::Response,
     ) -> Result<Self::Commitment, SigmaError>;
+
+    /// Simulates an accepting transcript for a random challenge, as the honest-verifier zero-knowledge simulator
+    /// of the standard: a random challenge and a simulated response, from which the commitment is recomputed.
+    /// Challenges that cannot be converted into the protocol's challenge space with `expansion` are resampled. The
+    /// challenge is returned as its bytes, which compositions combine
+    fn simulate_transcript<R: Rng>(
+        &self,
+        expansion: ChallengeExpansion,
+        rng: &mut R,
+    ) -> Result<(Self::Commitment, Challenge, Self::Response), SigmaError> {
+        loop {
+            let challenge = rng.gen();
+            let response = self.simulate_response(rng);
+            match Self::challenge_from_bytes(&challenge, expansion)
+                .and_then(|converted| self.simulate_commitment(&converted, &response))
+            {
+                Err(SigmaError::ChallengeConversionFailure) => continue,
+                commitment => break Ok((commitment?, challenge, response)),
+            }
+        }
+    }
+}
+
+/// A Sigma protocol whose verification can be batched over many transcripts for the same instance, as done by
+/// [`NizkVerifier::batch_verify`](crate::NizkVerifier::batch_verify). The default implementation verifies the
+/// transcripts one after the other, so a protocol without a faster check can opt in with an empty implementation.
+pub trait BatchVerifiable: SigmaProtocol {
+    /// Accepts only if every `(commitment, challenge, response)` transcript would be accepted by
+    /// [`SigmaProtocol::verifier`]. A randomized check may accept an invalid transcript with negligible probability
+    fn batch_verifier<R: Rng>(
+        &self,
+        transcripts: &[(&Self::Commitment, Self::Challenge, &Self::Response)],
+        _rng: &mut R,
+    ) -> Result<(), SigmaError> {
+        transcripts
+            .iter()
+            .try_for_each(|(commitment, challenge, response)| {
+                self.verifier(commitment, challenge, response)
+            })
+    }
+
+    /// The commitments [`CommitmentRecoverable::simulate_commitment`] computes for `(challenge, response)` pairs,
+    /// as recomputed by [`NizkVerifier::batch_verify_short`](crate::NizkVerifier::batch_verify_short). The default
+    /// simulates them one after the other
+    fn batch_simulate_commitments(
+        &self,
+        transcripts: &[(Self::Challenge, &Self::Response)],
+    ) -> Result<Vec<Self::Commitment>, SigmaError>
+    where
+        Self: CommitmentRecoverable,
+    {
+        transcripts
+            .iter()
+            .map(|(challenge, response)| self.simulate_commitment(challenge, response))
+            .collect()
+    }
+}
+
+/// A public-coin protocol of more than three moves, as the composed or amortized protocols in the scope of the
+/// standard: the prover sends [`MultiRoundSigmaProtocol::ROUNDS`] messages, each answered by a challenge of the
+/// verifier, and then its response. The NIZK derives the challenge of each round from the transcript so far, see
+/// [`NIZK::multi_round_proof`](crate::NIZK::multi_round_proof).
+///
+/// Every [`SigmaProtocol`] is the multi-round protocol of a single round, whose message is its commitment, so
+/// that the multi-round proofs of a three-move protocol have the challenges of its batchable proofs
+pub trait MultiRoundSigmaProtocol {
+    /// The statement
+    type Instance;
+    /// What the prover knows about the statement
+    type Witness;
+    /// Absorbed into the challenge answering it in its [`CanonicalAbsorb`] encoding
+    type Message: CanonicalSerialize + CanonicalDeserialize + CanonicalAbsorb;
+    /// What the prover keeps between its messages, as its randomness
+    type ProverState;
+    /// The last message of the prover
+    type Response: CanonicalSerialize + CanonicalDeserialize;
+    /// See [`SigmaProtocol::Challenge`]
+    type Challenge: Clone;
+
+    /// See [`SigmaProtocol::PROTOCOL_ID`]
+    const PROTOCOL_ID: &'static [u8];
+
+    /// See [`SigmaProtocol::MIN_CHALLENGE_LENGTH`]
+    const MIN_CHALLENGE_LENGTH: usize = CHALLENGE_LENGTH;
+
+    /// The number of messages of the prover before its response, and of challenges, at least one
+    const ROUNDS: usize;
+
+    /// See [`SigmaProtocol::label`]
+    fn label(&self) -> [u8; LABEL_LENGTH] {
+        protocol_label(Self::PROTOCOL_ID, &self.instance_bytes())
+    }
+
+    /// The protocol for a given instance
+    fn new(instance: &Self::Instance) -> Self;
+
+    /// See [`SigmaProtocol::from_shared`]
+    fn from_shared(instance: Arc<Self::Instance>) -> Self
+    where
+        Self: Sized,
+    {
+        Self::new(&instance)
+    }
+
+    /// See [`SigmaProtocol::challenge_from_bytes`]
+    fn challenge_from_bytes(
+        challenge: &Challenge,
+        expansion: ChallengeExpansion,
+    ) -> Result<Self::Challenge, SigmaError>;
+
+    /// See [`SigmaProtocol::validate_instance`]
+    fn validate_instance(&self) -> Result<(), SigmaError> {
+        Ok(())
+    }
+
+    /// See [`SigmaProtocol::instance_bytes`]
+    fn instance_bytes(&self) -> Vec<u8>;
+
+    /// See [`SigmaProtocol::witness_bytes`]
+    fn witness_bytes(&self, witness: &Self::Witness) -> Vec<u8>;
+
+    /// The first message of the prover, answered by the challenge of round 0. See
+    /// [`SigmaProtocol::prover_commit`] for `expansion`
+    fn prover_commit<R: Rng>(
+        &self,
+        witness: &Self::Witness,
+        expansion: ChallengeExpansion,
+        rng: &mut R,
+    ) -> Result<(Self::Message, Self::ProverState), SigmaError>;
+
+    /// The message answering the challenge of `round`, for every round but the last, which the response answers
+    fn prover_message<R: Rng>(
+        &self,
+        prover_state: &mut Self::ProverState,
+        round: usize,
+        challenge: &Self::Challenge,
+        rng: &mut R,
+    ) -> Result<Self::Message, SigmaError>;
+
+    /// The response to the challenge of the last round
+    fn prover_response(
+        &self,
+        prover_state: &Self::ProverState,
+        challenge: &Self::Challenge,
+    ) -> Result<Self::Response, SigmaError>;
+
+    /// Accepts the transcript of the messages, the challenges answering them in the same order and the response.
+    /// Rejects any other number of messages or challenges than [`MultiRoundSigmaProtocol::ROUNDS`] with
+    /// [`SigmaError::LengthMismatch`]
+    fn verifier(
+        &self,
+        messages: &[Self::Message],
+        challenges: &[Self::Challenge],
+        response: &Self::Response,
+    ) -> Result<(), SigmaError>;
+
+    /// See [`SigmaProtocol::zeroize_state`]
+    fn zeroize_state(&self, _prover_state: &mut Self::ProverState) {}
+}
+
+impl<S: SigmaProtocol> MultiRoundSigmaProtocol for S {
+    type Instance = S::Instance;
+    type Witness = S::Witness;
+    type Message = S::Commitment;
+    type ProverState = S::ProverState;
+    type Response = S::Response;
+    type Challenge = S::Challenge;
+
+    const PROTOCOL_ID: &'static [u8] = S::PROTOCOL_ID;
+    const MIN_CHALLENGE_LENGTH: usize = S::MIN_CHALLENGE_LENGTH;
+    const ROUNDS: usize = 1;
+
+    fn label(&self) -> [u8; LABEL_LENGTH] {
+        SigmaProtocol::label(self)
+    }
+
+    fn new(instance: &S::Instance) -> Self {
+        SigmaProtocol::new(instance)
+    }
+
+    fn from_shared(instance: Arc<S::Instance>) -> Self {
+        SigmaProtocol::from_shared(instance)
+    }
+
+    fn challenge_from_bytes(
+        challenge: &Challenge,
+        expansion: ChallengeExpansion,
+    ) -> Result<S::Challenge, SigmaError> {
+        <S as SigmaProtocol>::challenge_from_bytes(challenge, expansion)
+    }
+
+    fn validate_instance(&self) -> Result<(), SigmaError> {
+        SigmaProtocol::validate_instance(self)
+    }
+
+    fn instance_bytes(&self) -> Vec<u8> {
+        SigmaProtocol::instance_bytes(self)
+    }
+
+    fn witness_bytes(&self, witness: &S::Witness) -> Vec<u8> {
+        SigmaProtocol::witness_bytes(self, witness)
+    }
+
+    fn prover_commit<R: Rng>(
+        &self,
+        witness: &S::Witness,
+        expansion: ChallengeExpansion,
+        rng: &mut R,
+    ) -> Result<(S::Commitment, S::ProverState), SigmaError> {
+        SigmaProtocol::prover_commit(self, witness, expansion, rng)
+    }
+
+    /// A three-move protocol answers its only challenge with its response
+    fn prover_message<R: Rng>(
+        &self,
+        _prover_state: &mut S::ProverState,
+        _round: usize,
+        _challenge: &S::Challenge,
+        _rng: &mut R,
+    ) -> Result<S::Commitment, SigmaError> {
+        Err(SigmaError::LengthMismatch)
+    }
+
+    fn prover_response(
+        &self,
+        prover_state: &S::ProverState,
+        challenge: &S::Challenge,
+    ) -> Result<S::Response, SigmaError> {
+        SigmaProtocol::prover_response(self, prover_state, challenge)
+    }
+
+    fn verifier(
+        &self,
+        messages: &[S::Commitment],
+        challenges: &[S::Challenge],
+        response: &S::Response,
+    ) -> Result<(), SigmaError> {
+        match (messages, challenges) {
+            ([commitment], [challenge]) => {
+                SigmaProtocol::verifier(self, commitment, challenge, response)
+            }
+            _ => Err(SigmaError::LengthMismatch),
+        }
+    }
+
+    fn zeroize_state(&self, prover_state: &mut S::ProverState) {
+        SigmaProtocol::zeroize_state(self, prover_state)
+    }
+}
+
+/// The random weights with which a batch verifier combines the verification equations of many transcripts into
+/// one, as in [`BatchVerifiable::batch_verifier`]. Each weight is an integer of [`BatchRandomizers::bits`] bits,
+/// [`BatchRandomizers::DEFAULT_BITS`] unless set with [`BatchRandomizers::with_bits`].
+///
+/// Over arkworks curves, the batch verifier of [`SchnorrDLOG`](crate::protocols::SchnorrDLOG) weights the
+/// commitments in a multi-scalar multiplication over the width of the weights, which 128-bit weights make about half
+/// as long as full-width ones; `cargo bench --bench schnorr_verify` compares the two. The multi-scalar
+/// multiplications of ristretto255 and secp256k1 walk every bit of the scalar field whatever the width, so narrow
+/// weights do not shorten them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BatchRandomizers {
+    bits: u32,
+}
+
+impl BatchRandomizers {
+    /// The default width of the weights. A batch containing an invalid transcript has a nonzero error `e` in the
+    /// equation of that transcript, and the combined equation only holds if its weight `w` cancels `w * e`
+    /// against the other terms. Given the other weights, at most one value of `w` does, so a batch with an
+    /// invalid transcript is accepted with probability at most `2^-bits`. 128 bits match the security level of
+    /// the challenges, and the verifier samples the weights after the proofs are fixed, so they cannot be ground
+    pub const DEFAULT_BITS: u32 = 128;
+
+    /// Weights of `bits` bits, accepting a batch with an invalid transcript with probability at most `2^-bits`.
+    /// Widths at least as large as the scalar field give uniform scalars, and so do widths over 128 bits for
+    /// ristretto255 and secp256k1. Panics if `bits` is zero
+    pub fn with_bits(bits: u32) -> Self {
+        assert!(bits > 0, "the weights need at least one bit");
+        Self { bits }
+    }
+
+    /// The width of the weights in bits
+    pub fn bits(&self) -> u32 {
+        self.bits
+    }
+
+    /// Sample a weight, uniform among the integers of `bits` bits
+    pub fn sample<F: PrimeField, R: Rng>(&self, rng: &mut R) -> F {
+        let bits = self.bits as usize;
+        if bits >= F::size_in_bits() {
+            return F::rand(rng);
+        }
+
+        let mut repr = F::BigInt::default();
+        for (i, limb) in repr.as_mut().iter_mut().enumerate() {
+            let remaining = bits.saturating_sub(64 * i);
+            *limb = match remaining {
+                0 => 0,
+                1..=63 => rng.gen::<u64>() & ((1 << remaining) - 1),
+                _ => rng.gen(),
+            };
+        }
+        // Below 2^(size_in_bits - 1), hence below the modulus
+        F::from_repr(repr).expect("the weight is below the modulus")
+    }
+
+    /// Sample a weight in a group whose scalars convert from integers, uniform among the integers of `bits` bits
+    /// up to 128 bits and among the scalars for wider weights, which is at least as sound
+    #[cfg(any(feature = "ristretto", feature = "secp256k1"))]
+    pub(crate) fn sample_scalar<G: crate::SigmaGroup, R: Rng>(&self, rng: &mut R) -> G::Scalar
+    where
+        G::Scalar: From<u128>,
+    {
+        match self.bits {
+            bits @ 1..=127 => G::Scalar::from(rng.gen::<u128>() & ((1 << bits) - 1)),
+            128 => G::Scalar::from(rng.gen::<u128>()),
+            _ => G::random_scalar(rng),
+        }
+    }
+}
+
+impl Default for BatchRandomizers {
+    fn default() -> Self {
+        Self::with_bits(Self::DEFAULT_BITS)
+    }
+}
+
+/// The canonical encoding of a value. Serializing into a vector cannot run out of space, so this does not fail
+pub(crate) fn canonical_bytes<X: CanonicalSerialize>(value: &X) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(value.serialized_size());
+    value
+        .serialize(&mut bytes)
+        .unwrap_or_else(|_| unreachable!("serializing into a vector does not fail"));
+    bytes
+}
+
+/// Compares the canonical encodings of two values in constant time. Encoding normalizes group elements, so that
+/// different representations of the same point, as projective coordinates, compare equal
+pub(crate) fn canonical_ct_eq<X: CanonicalSerialize>(a: &X, b: &X) -> Choice {
+    canonical_bytes(a).ct_eq(&canonical_bytes(b))
+}
+
+/// The label of a protocol, the one derivation of every label: the last `LABEL_LENGTH` bytes of
+/// `Blake2b(len(id) || id || data)`, compressed as every digest of the standard is, for the length of the protocol
+/// identifier as a little-endian `u64`. The data of a protocol is its instance encoding; the data of a composition
+/// is its parameters, such as a threshold, followed by the labels of its components, which have a fixed length
+pub(crate) fn protocol_label(id: &[u8], data: &[u8]) -> [u8; LABEL_LENGTH] {
+    let mut hasher = Blake2b::new();
+    hasher.update((id.len() as u64).to_le_bytes());
+    hasher.update(id);
+    hasher.update(data);
+
+    compress_digest(&hasher.finalize())
 }
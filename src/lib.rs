@@ -4,20 +4,59 @@
 #![warn(missing_docs, unreachable_pub)]
 #![deny(unused_must_use, rust_2018_idioms)]
 
+use std::{fmt, mem};
+
+use ark_serialize::SerializationError;
+
 /// Length of a challenge in bytes
 pub const CHALLENGE_LENGTH: usize = 32;
 
+/// Length of a protocol label in bytes
+pub const LABEL_LENGTH: usize = 32;
+
 /// Domain separator for the hash functions
 pub const DOMSEP: &[u8] = b"zkpstd/sigma/0.1";
 
-/// Type alias for a challenge
-pub type Challenge = [u8; CHALLENGE_LENGTH];
+/// Type alias for a challenge, of `CHALLENGE_LENGTH` bytes unless a NIZK is built for shorter challenges
+pub type Challenge<const L: usize = CHALLENGE_LENGTH> = [u8; L];
+
+mod group;
+pub use group::{FixedBaseTable, SigmaGroup, Zeroizable};
 
 mod interactive_proofs;
-pub use interactive_proofs::SigmaProtocol;
+pub use interactive_proofs::{
+    BatchRandomizers, BatchVerifiable, CommitmentRecoverable, MultiRoundSigmaProtocol,
+    SigmaProtocol,
+};
 
 mod nizk_proofs;
-pub use nizk_proofs::{BatchableProof, ShortProof, NIZK};
+pub use nizk_proofs::{
+    BatchableProof, FsMode, HashedMessage, MessageHasher, MultiRoundProof, NizkBuilder, NizkProver,
+    NizkVerifier, ProofEnvelope, ProverStateBytes, SessionContext, ShortProof, TranscriptData,
+    NIZK,
+};
+
+mod proof_stream;
+pub use proof_stream::ProofStream;
+
+/// The Fiat-Shamir challenge recomputed from its fields, outside proving and verifying
+mod challenge;
+pub use challenge::derive_challenge;
+
+/// Secret scalars and the redacted debug output of secret values
+mod secret;
+pub use secret::SecretScalar;
+
+/// Hex and base64url text encodings of byte strings
+mod encoding;
+
+mod transcript;
+pub use transcript::{
+    CanonicalAbsorb, ChallengeExpansion, DynTranscript, SufficientOutput, Transcript, XofTranscript,
+};
+
+/// Events of the Fiat-Shamir transcripts, proofs and verifications, with the `tracing` feature
+mod trace;
 
 /// Module defining the set of all supported hash functions
 mod hash_registry;
@@ -26,17 +65,293 @@ pub use hash_registry::HashFunction;
 /// Concrete implementations of known Sigma protocols.
 pub mod protocols;
 
-// pub enum ComposedSigmaProtocol {
-//     ANDComposition,
-//     ORComposition,
-// }
+/// Adapter for the ristretto255 group of `curve25519-dalek`.
+#[cfg(feature = "ristretto")]
+pub mod ristretto;
+
+/// Adapter for the secp256k1 group of `k256`.
+#[cfg(feature = "secp256k1")]
+pub mod secp256k1;
+
+/// Canonical CBOR encodings of proofs and instances, for exchanging them with other implementations of the
+/// standard.
+#[cfg(feature = "cbor")]
+pub mod cbor;
+
+/// Strict DER encodings of proofs and signatures with the public key they are made for, as proofs of possession in
+/// certificate workflows.
+#[cfg(feature = "der")]
+pub mod der;
+
+/// Proving and verifying many independent proofs across threads.
+#[cfg(feature = "parallel")]
+mod parallel;
+
+/// A C ABI proving and verifying batchable Schnorr proofs over ristretto255 with Blake2s, for services not written in Rust.
+/// Inputs are canonical byte encodings, every function returns a [`ffi::SigmaErrorCode`] but the `_free` ones, and
+/// panics are caught at the boundary. The declarations are in `include/sigma_protocol_standard.h`.
+#[cfg(feature = "ffi")]
+pub mod ffi;
+
+/// `wasm_bindgen` functions proving and verifying batchable Schnorr proofs over ristretto255 with Blake2s from
+/// JavaScript, over the canonical byte encodings, so that proofs made in a browser verify on a Rust server.
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+/// The pyo3 extension module `sigma_protocol_standard`, proving and verifying batchable Schnorr proofs over
+/// ristretto255 with Blake2s from Python, with every error raised as a `SigmaError` exception.
+#[cfg(feature = "python")]
+mod python;
+
+/// Schnorr signatures built on the NIZK, which always bind the message.
+pub mod signature;
+
+/// Combinators building Sigma protocols out of other Sigma protocols. As every combinator is itself a Sigma
+/// protocol, they nest into arbitrary AND/OR policy trees, whose labels reflect the whole tree.
+pub mod composition;
+
+/// The Schnorr NIZK of RFC 8235, whose challenge hashes the base, commitment, public key and user identifier as
+/// the RFC specifies, for interoperating with other implementations such as those of J-PAKE.
+pub mod rfc8235;
+
+/// The Merlin transcript of the dalek `zkp` crate, over the `merlin` crate, which is also a transcript
+/// backend of the NIZK.
+#[cfg(feature = "merlin")]
+mod merlin;
+#[cfg(feature = "merlin")]
+pub use self::merlin::MerlinTranscript;
+
+/// `Serialize` and `Deserialize` for proofs and instances through their compressed canonical encodings: hex
+/// strings in human-readable formats such as JSON, byte strings in the others such as bincode.
+#[cfg(feature = "serde")]
+mod serde;
+
+/// Proofs interoperating with the Schnorr DLOG statements of the dalek `zkp` crate over ristretto255: its Merlin
+/// transcript, the labels its `define_proof!` macro appends and the `bincode` layout of its `CompactProof`.
+#[cfg(feature = "zkp-compat")]
+pub mod zkp_compat;
+
+/// Loader and generator of the JSON test vectors of the working group, which replays full NIZK transcripts
+/// against verification and deterministic proving.
+#[cfg(any(test, feature = "test-vectors"))]
+pub mod test_vectors;
+
+/// Re-exports for the expansion of [`define_relation!`], which is not part of the public API
+#[doc(hidden)]
+pub mod __private {
+    pub use ark_ec::ProjectiveCurve;
+    pub use ark_ff::{PrimeField, Zero};
+    pub use ark_serialize::{
+        CanonicalDeserialize, CanonicalSerialize, Read, SerializationError, Write,
+    };
+    pub use rand::Rng;
+
+    pub use crate::{
+        protocols::{LinearMapInstance, LinearMapProof},
+        secret::Redacted,
+        Challenge, ChallengeExpansion, CommitmentRecoverable, SigmaError, SigmaProtocol,
+    };
+}
+
+/// Test support for checking the defining properties of Sigma protocols, such as the extractors of their special
+/// soundness. It is not meant for production use.
+#[doc(hidden)]
+pub mod testing;
 
-#[derive(Debug, PartialEq, Eq)]
 /// An error type for failures in sigma protocols
+#[derive(Debug)]
+#[non_exhaustive]
 pub enum SigmaError {
     /// An error to signify that verification has failed
     VerificationFailed,
 
     /// An error to signify that a challenge could not be derived from the provided vector of bytes
     ChallengeConversionFailure,
+
+    /// An error to signify that a commitment, response or proof could not be serialized or deserialized
+    SerializationError(SerializationError),
+
+    /// An error to signify that the instance is not a valid statement for the protocol
+    InvalidInstance,
+
+    /// An error to signify that a witness or response does not have the length required by the instance
+    LengthMismatch,
+
+    /// An error to signify that the protocol cannot recompute its commitment from a challenge and response. The
+    /// protocols of the crate that never can do not implement [`CommitmentRecoverable`], and offer no short proofs
+    CommitmentNotRecoverable,
+
+    /// An error to signify that a hash function is not in the registry or has no implementation in this build
+    UnsupportedHashFunction,
+
+    /// An error to signify that the transcript outputs `got` bytes, fewer than the `need` bytes of a challenge
+    DigestTooShort {
+        /// The output length of the transcript
+        got: usize,
+        /// The length of a challenge
+        need: usize,
+    },
+
+    /// An error to signify that the header of a proof envelope does not match the configuration of the verifier
+    EnvelopeMismatch,
+
+    /// An error to signify that a NIZK is built for challenges of `got` bytes, fewer than the `need` bytes the
+    /// protocol supports
+    ChallengeTooShort {
+        /// The challenge length of the NIZK
+        got: usize,
+        /// The minimum challenge length of the protocol
+        need: usize,
+    },
+}
+
+impl fmt::Display for SigmaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::VerificationFailed => write!(f, "verification failed"),
+            Self::ChallengeConversionFailure => {
+                write!(
+                    f,
+                    "the challenge could not be converted into the challenge space"
+                )
+            }
+            Self::SerializationError(error) => write!(f, "serialization failed: {}", error),
+            Self::InvalidInstance => write!(f, "the instance is not valid for the protocol"),
+            Self::LengthMismatch => write!(f, "the length does not match the instance"),
+            Self::CommitmentNotRecoverable => {
+                write!(f, "the commitment cannot be recomputed from the response")
+            }
+            Self::UnsupportedHashFunction => write!(f, "the hash function is not supported"),
+            Self::DigestTooShort { got, need } => {
+                write!(
+                    f,
+                    "the digest outputs {} bytes, a challenge needs {}",
+                    got, need
+                )
+            }
+            Self::EnvelopeMismatch => {
+                write!(f, "the proof envelope does not match the verifier")
+            }
+            Self::ChallengeTooShort { got, need } => {
+                write!(
+                    f,
+                    "challenges of {} bytes are too short, the protocol needs {}",
+                    got, need
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for SigmaError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::SerializationError(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<SerializationError> for SigmaError {
+    fn from(error: SerializationError) -> Self {
+        Self::SerializationError(error)
+    }
+}
+
+/// Serialization errors are compared by kind, as I/O errors cannot be compared, and the other variants by
+/// their fields
+impl PartialEq for SigmaError {
+    fn eq(&self, other: &Self) -> bool {
+        use SerializationError::*;
+
+        match (self, other) {
+            (Self::SerializationError(error), Self::SerializationError(other)) => {
+                match (error, other) {
+                    (IoError(error), IoError(other)) => error.kind() == other.kind(),
+                    _ => mem::discriminant(error) == mem::discriminant(other),
+                }
+            }
+            (
+                Self::DigestTooShort { got, need },
+                Self::DigestTooShort {
+                    got: other_got,
+                    need: other_need,
+                },
+            )
+            | (
+                Self::ChallengeTooShort { got, need },
+                Self::ChallengeTooShort {
+                    got: other_got,
+                    need: other_need,
+                },
+            ) => got == other_got && need == other_need,
+            // The remaining variants carry no fields
+            _ => mem::discriminant(self) == mem::discriminant(other),
+        }
+    }
+}
+
+impl Eq for SigmaError {}
+
+#[cfg(test)]
+mod tests {
+    use std::{error::Error, io};
+
+    use ark_serialize::SerializationError;
+
+    use crate::SigmaError;
+
+    #[test]
+    fn test_serialization_errors_compare_by_kind() {
+        let io_error = |kind| SigmaError::from(SerializationError::IoError(io::Error::from(kind)));
+
+        assert_eq!(
+            io_error(io::ErrorKind::UnexpectedEof),
+            io_error(io::ErrorKind::UnexpectedEof)
+        );
+        assert_ne!(
+            io_error(io::ErrorKind::UnexpectedEof),
+            io_error(io::ErrorKind::InvalidData)
+        );
+        assert_ne!(
+            SigmaError::from(SerializationError::InvalidData),
+            SigmaError::from(SerializationError::NotEnoughSpace)
+        );
+        assert_ne!(
+            SigmaError::from(SerializationError::InvalidData),
+            SigmaError::VerificationFailed
+        );
+    }
+
+    #[test]
+    fn test_length_errors_compare_by_fields() {
+        assert_eq!(
+            SigmaError::DigestTooShort { got: 16, need: 32 },
+            SigmaError::DigestTooShort { got: 16, need: 32 }
+        );
+        assert_ne!(
+            SigmaError::DigestTooShort { got: 16, need: 32 },
+            SigmaError::DigestTooShort { got: 20, need: 32 }
+        );
+        assert_ne!(
+            SigmaError::ChallengeTooShort { got: 8, need: 16 },
+            SigmaError::ChallengeTooShort { got: 8, need: 32 }
+        );
+        assert_ne!(
+            SigmaError::ChallengeTooShort { got: 16, need: 32 },
+            SigmaError::DigestTooShort { got: 16, need: 32 }
+        );
+    }
+
+    #[test]
+    fn test_serialization_error_is_the_source() {
+        let error = SigmaError::from(SerializationError::InvalidData);
+
+        assert!(error.source().is_some());
+        assert!(SigmaError::VerificationFailed.source().is_none());
+        assert_eq!(
+            error.to_string(),
+            "serialization failed: the input buffer contained invalid data"
+        );
+    }
 }
@@ -0,0 +1,192 @@
+use crate::{Challenge, SufficientOutput, Transcript, CHALLENGE_LENGTH, DOMSEP};
+
+/// A transcript of the `merlin` crate, over which the proofs of the dalek `zkp` crate are made. Only the operations
+/// those proofs use are provided.
+///
+/// It is also a [`Transcript`] of the NIZK, which unlike a digest binds the label of every field: each field is one
+/// Merlin message under its label, and the challenge is `CHALLENGE_LENGTH` bytes of challenge under its label.
+/// [`Default`] starts the transcript for the application protocol [`DOMSEP`]
+#[derive(Clone)]
+pub struct MerlinTranscript {
+    transcript: merlin::Transcript,
+    /// The field being appended through [`Transcript::append`], buffered until another field starts, as a Merlin
+    /// message is prefixed with its length
+    field: Option<(&'static [u8], Vec<u8>)>,
+}
+
+impl MerlinTranscript {
+    /// A transcript for the application protocol `label`, as `merlin::Transcript::new`
+    pub fn new(label: &'static [u8]) -> Self {
+        Self {
+            transcript: merlin::Transcript::new(label),
+            field: None,
+        }
+    }
+
+    /// Appends `message` under `label`, as `merlin::Transcript::append_message`
+    pub fn append_message(&mut self, label: &'static [u8], message: &[u8]) {
+        self.end_field();
+        self.transcript.append_message(label, message);
+    }
+
+    /// Fills `dest` with challenge bytes bound to everything appended so far, as
+    /// `merlin::Transcript::challenge_bytes`
+    pub fn challenge_bytes(&mut self, label: &'static [u8], dest: &mut [u8]) {
+        self.end_field();
+        self.transcript.challenge_bytes(label, dest);
+    }
+
+    /// Appends the buffered field, if any, as one message
+    fn end_field(&mut self) {
+        if let Some((label, bytes)) = self.field.take() {
+            self.transcript.append_message(label, &bytes);
+        }
+    }
+}
+
+impl Default for MerlinTranscript {
+    fn default() -> Self {
+        Self::new(DOMSEP)
+    }
+}
+
+impl Transcript for MerlinTranscript {
+    fn append(&mut self, label: &'static [u8], bytes: &[u8]) {
+        match &mut self.field {
+            Some((field, buffer)) if *field == label => buffer.extend_from_slice(bytes),
+            _ => {
+                self.end_field();
+                self.field = Some((label, bytes.to_vec()));
+            }
+        }
+    }
+
+    fn challenge(mut self, label: &'static [u8]) -> Challenge {
+        let mut challenge = [0u8; CHALLENGE_LENGTH];
+        self.challenge_bytes(label, &mut challenge);
+        challenge
+    }
+
+    fn output_len(&self) -> usize {
+        CHALLENGE_LENGTH
+    }
+}
+
+impl SufficientOutput for MerlinTranscript {}
+
+#[cfg(test)]
+mod tests {
+    use rand::thread_rng;
+
+    use crate::{
+        encoding::encode_hex,
+        protocols::{SchnorrDLOG, SchnorrInstance},
+        SigmaError, Transcript, CHALLENGE_LENGTH, NIZK,
+    };
+
+    use super::MerlinTranscript;
+
+    type G = ark_bls12_377::G1Projective;
+
+    #[test]
+    fn test_merlin_known_answer() {
+        // The `equivalence_simple` vector of the `merlin` crate
+        let mut transcript = MerlinTranscript::new(b"test protocol");
+        transcript.append_message(b"some label", b"some data");
+        let mut challenge = [0u8; 32];
+        transcript.challenge_bytes(b"challenge", &mut challenge);
+        assert_eq!(
+            encode_hex(&challenge),
+            "d5a21972d0d5fe320c0d263fac7fffb8145aa640af6e9bca177c03c7efcf0615"
+        );
+    }
+
+    #[test]
+    fn test_merlin_long_messages_cross_the_rate() {
+        let mut short = MerlinTranscript::new(b"test protocol");
+        let mut long = short.clone();
+        short.append_message(b"data", &[99; 165]);
+        long.append_message(b"data", &[99; 1024]);
+
+        let (mut first, mut second) = ([0u8; 200], [0u8; 200]);
+        short.challenge_bytes(b"challenge", &mut first);
+        long.challenge_bytes(b"challenge", &mut second);
+        assert_ne!(first, second);
+        assert_ne!(first[..100], first[100..]);
+    }
+
+    #[test]
+    fn test_transcript_fields_are_merlin_messages() {
+        // A field appended in pieces is one message under its label, and the challenge is squeezed under its own
+        let mut streamed = MerlinTranscript::new(b"test protocol");
+        Transcript::append(&mut streamed, b"some label", b"some ");
+        Transcript::append(&mut streamed, b"some label", b"data");
+        assert_eq!(
+            encode_hex(&Transcript::challenge(streamed, b"challenge")),
+            "d5a21972d0d5fe320c0d263fac7fffb8145aa640af6e9bca177c03c7efcf0615"
+        );
+
+        // Unlike a digest, the transcript binds the labels
+        let transcript = MerlinTranscript::default();
+        assert_ne!(
+            transcript.hash(b"first", b"data"),
+            transcript.hash(b"second", b"data")
+        );
+    }
+
+    #[test]
+    fn test_transcript_matches_merlin() {
+        // Fields appended in pieces and longer than a block of STROBE, and several challenges, as the `merlin` crate
+        let long = [7u8; 500];
+        let mut ours = MerlinTranscript::new(b"test protocol");
+        let mut theirs = merlin::Transcript::new(b"test protocol");
+        for piece in long.chunks(97) {
+            Transcript::append(&mut ours, b"long", piece);
+        }
+        Transcript::append(&mut ours, b"short", b"data");
+        theirs.append_message(b"long", &long);
+        theirs.append_message(b"short", b"data");
+
+        for label in [&b"first"[..], b"second"] {
+            let (mut expected, mut challenge) = ([0u8; 200], [0u8; 200]);
+            theirs.challenge_bytes(label, &mut expected);
+            ours.challenge_bytes(label, &mut challenge);
+            assert_eq!(challenge, expected);
+        }
+        ours.append_message(b"after", b"the challenges");
+        theirs.append_message(b"after", b"the challenges");
+        let mut expected = [0u8; CHALLENGE_LENGTH];
+        theirs.challenge_bytes(b"challenge", &mut expected);
+        assert_eq!(Transcript::challenge(ours, b"challenge"), expected);
+    }
+
+    #[test]
+    fn test_nizk_over_merlin_transcript() {
+        let rng = &mut thread_rng();
+        let (instance, witness) = SchnorrInstance::<G>::generate(rng);
+        let nizk = NIZK::<SchnorrDLOG<G>, MerlinTranscript>::new(&instance, b"ctx");
+        let digest_nizk = NIZK::<SchnorrDLOG<G>, blake2::Blake2s>::new(&instance, b"ctx");
+
+        let proof = nizk
+            .batchable_proof(&witness, Some(b"message"), rng)
+            .unwrap();
+        let short_proof = nizk.short_proof(&witness, None, rng).unwrap();
+
+        assert!(nizk.batchable_verify(&proof, Some(b"message")).is_ok());
+        assert!(nizk.short_verify(&short_proof, None).is_ok());
+        assert_eq!(
+            nizk.batchable_verify(&proof, Some(b"other")),
+            Err(SigmaError::VerificationFailed)
+        );
+        assert_eq!(
+            NIZK::<SchnorrDLOG<G>, MerlinTranscript>::new(&instance, b"other")
+                .short_verify(&short_proof, None),
+            Err(SigmaError::VerificationFailed)
+        );
+        // The transcripts differ, so the backends do not cross-verify
+        assert_eq!(
+            digest_nizk.batchable_verify(&proof, Some(b"message")),
+            Err(SigmaError::VerificationFailed)
+        );
+    }
+}
@@ -1,196 +1,3912 @@
+use std::{fmt, io, marker::PhantomData, ops::Range, slice, str::FromStr, sync::Arc};
+
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Read, SerializationError, Write};
 use digest::Digest;
-use rand::Rng;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use subtle::{Choice, ConstantTimeEq};
+
+use crate::{
+    challenge::{absorb_message, challenge_over, challenge_prefix, padded, truncated},
+    encoding::{decode_base64url, decode_hex, encode_base64url, encode_hex},
+    interactive_proofs::protocol_label,
+    trace,
+    transcript::compress_digest,
+    BatchVerifiable, CanonicalAbsorb, Challenge, ChallengeExpansion, CommitmentRecoverable,
+    DynTranscript, HashFunction, MultiRoundSigmaProtocol, SigmaError, SigmaProtocol,
+    SufficientOutput, Transcript, CHALLENGE_LENGTH, DOMSEP, LABEL_LENGTH,
+};
 
-use crate::{Challenge, SigmaError, SigmaProtocol, CHALLENGE_LENGTH, DOMSEP};
+/// Separates the derivation of the commitment randomness from the challenges
+const NONCE_DOMSEP: &[u8] = b"zkpstd/sigma/nonce";
 
-/// A non-interactive zk (NIZK) proof derived from applying the Fiat-Shamir transformation to a Sigma protocol
-pub struct NIZK<S: SigmaProtocol, D: Digest> {
+/// The first field of the encoding of every [`SessionContext`] built from its fields
+const SESSION_DOMSEP: &[u8] = b"zkpstd/sigma/session";
+
+/// The Fiat-Shamir transformation of a Sigma protocol for a given instance and context, shared by the prover and the
+/// verifier. Every challenge is computed with a fresh transcript, so it only needs to be borrowed
+struct FiatShamir<S: MultiRoundSigmaProtocol, D: Transcript, const L: usize> {
     interactive_protocol: S,
-    hasher: D,
+    transcript: D,
     hd: [u8; CHALLENGE_LENGTH],
-    ha: [u8; CHALLENGE_LENGTH],
+    ha: [u8; LABEL_LENGTH],
     hctx: [u8; CHALLENGE_LENGTH],
+    hinst: [u8; CHALLENGE_LENGTH],
+    mode: FsMode,
+}
+
+/// Whether the Fiat-Shamir challenge binds the instance
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FsMode {
+    /// Strong Fiat-Shamir, the challenge hashes the instance: a proof cannot be reused for a statement chosen after
+    /// its challenge
+    #[default]
+    Strong,
+    /// Weak Fiat-Shamir, the challenge omits the instance and labels the protocol by its identifier only, for
+    /// interoperating with deployed systems. A prover choosing the instance after the challenge can forge proofs
+    #[deprecated(
+        note = "weak Fiat-Shamir lets the prover choose the instance after the challenge, use `Strong`"
+    )]
+    LegacyWeak,
+}
+
+/// Builds a [`NIZK`], or only its prover or verifier, from the instance and the options that default to an empty
+/// context and [`FsMode::Strong`]
+#[derive(Clone)]
+pub struct NizkBuilder<S: MultiRoundSigmaProtocol, D: Transcript, const L: usize = CHALLENGE_LENGTH>
+{
+    protocol: S,
+    session: SessionContext,
+    mode: FsMode,
+    transcript: PhantomData<D>,
+}
+
+/// The prover side of a NIZK proof derived from applying the Fiat-Shamir transformation to a Sigma protocol
+pub struct NizkProver<S: MultiRoundSigmaProtocol, D: Transcript, const L: usize = CHALLENGE_LENGTH>
+{
+    fiat_shamir: FiatShamir<S, D, L>,
+}
+
+/// The verifier side of a NIZK proof derived from applying the Fiat-Shamir transformation to a Sigma protocol
+pub struct NizkVerifier<
+    S: MultiRoundSigmaProtocol,
+    D: Transcript,
+    const L: usize = CHALLENGE_LENGTH,
+> {
+    fiat_shamir: FiatShamir<S, D, L>,
+}
+
+/// A non-interactive zk (NIZK) proof derived from applying the Fiat-Shamir transformation to a Sigma protocol,
+/// both proving and verifying. See [`NizkProver`] and [`NizkVerifier`] for a single side.
+///
+/// Proving and verifying only borrow the NIZK, so a single verifier can be shared between threads.
+///
+/// The prover and the verifier only share the instance and the context:
+///
+/// ```
+/// use rand::thread_rng;
+/// use sigma_protocol_standard::{
+///     protocols::{SchnorrDLOG, SchnorrInstance},
+///     NizkProver, NizkVerifier, NIZK,
+/// };
+///
+/// type Hash = blake2::Blake2s;
+/// type G = ark_bls12_377::G1Projective;
+///
+/// let rng = &mut thread_rng();
+/// let ctx = b"example";
+/// let (instance, witness) = SchnorrInstance::<G>::generate(rng);
+///
+/// let prover = NizkProver::<SchnorrDLOG<G>, Hash>::new(&instance, ctx);
+/// let proof = prover.batchable_proof(&witness, None, rng).unwrap();
+///
+/// let verifier = NizkVerifier::<SchnorrDLOG<G>, Hash>::new(&instance, ctx);
+/// assert!(verifier.batchable_verify(&proof, None).is_ok());
+///
+/// // The combined NIZK accepts the same proofs
+/// let nizk = NIZK::<SchnorrDLOG<G>, Hash>::new(&instance, ctx);
+/// assert!(nizk.batchable_verify(&proof, None).is_ok());
+/// ```
+pub struct NIZK<S: MultiRoundSigmaProtocol, D: Transcript, const L: usize = CHALLENGE_LENGTH> {
+    prover: NizkProver<S, D, L>,
+    verifier: NizkVerifier<S, D, L>,
+}
+
+/// Incrementally hashes a message that is too large to be held in memory, for the `_hashed` variants of proving and
+/// verifying. Absorbing the bytes of a message in any number of pieces gives the same challenge as passing the whole
+/// message at once. Implements [`std::io::Write`], so that a reader can be copied into it
+pub struct MessageHasher<D: Transcript> {
+    transcript: D,
+}
+
+/// A prover state, wiped with [`MultiRoundSigmaProtocol::zeroize_state`] when it is dropped, so that proving wipes
+/// the witness and the nonces it holds when it fails as when it succeeds
+struct WipedOnDrop<'a, S: MultiRoundSigmaProtocol> {
+    protocol: &'a S,
+    state: S::ProverState,
+}
+
+/// Absorbs everything written into one field of a transcript, so that values are serialized straight into the hash
+struct TranscriptWriter<'a, D> {
+    transcript: &'a mut D,
+    label: &'static [u8],
+}
+
+/// The hash of a message, to which a proof is bound exactly as to the message itself
+pub struct HashedMessage<D: Transcript> {
+    pub(crate) hm: [u8; CHALLENGE_LENGTH],
+    /// Whether this is the hash of a [`TranscriptData`] rather than of a raw message
+    pub(crate) structured: bool,
+    _transcript: PhantomData<D>,
+}
+
+/// Associated data made of labeled fields, to which a proof is bound in place of an opaque message. Each label and
+/// value is prefixed with its length as a `u64` in little endian, so that splitting the same bytes differently
+/// between the fields gives different challenges
+#[derive(Debug, Clone, Default)]
+pub struct TranscriptData {
+    encoding: Vec<u8>,
+}
+
+/// The session a proof is bound to: the version of the application protocol, the identifier of the application,
+/// the identity of the prover, the nonce of the session and an optional timestamp. The NIZK absorbs the fields in
+/// place of an opaque context, framed as those of [`TranscriptData`] after a domain separator, so that no two
+/// sessions share their challenges: a proof made in one session is rejected in a session with another nonce, even
+/// for the same instance and message.
+///
+/// [`SessionContext::from_raw_context`] keeps the opaque contexts of the constructors taking `ctx: &[u8]`, for
+/// deployed applications whose proofs must keep verifying
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SessionContext {
+    encoding: Vec<u8>,
 }
 
 /// A batchable proof. The canonical form of proofs.
-#[derive(Debug, CanonicalSerialize, CanonicalDeserialize)]
+#[derive(CanonicalSerialize, CanonicalDeserialize)]
 pub struct BatchableProof<S: SigmaProtocol> {
     commitment: S::Commitment,
     response: S::Response,
 }
 
 /// A shorter proof. The commitment can be deterministically computed from the challenge and the response.
-#[derive(Debug)]
-pub struct ShortProof<S: SigmaProtocol> {
-    challenge: Challenge,
+pub struct ShortProof<S: SigmaProtocol, const L: usize = CHALLENGE_LENGTH> {
+    challenge: Challenge<L>,
+    response: S::Response,
+}
+
+/// A proof of a [`MultiRoundSigmaProtocol`]: the message of every round, from which the verifier recomputes the
+/// challenges, and the response
+pub struct MultiRoundProof<S: MultiRoundSigmaProtocol> {
+    messages: Vec<S::Message>,
     response: S::Response,
 }
 
-impl<S: SigmaProtocol, D: Digest> NIZK<S, D> {
-    /// initialise the NIZK for a given Sigma protocol.
-    pub fn new(instance: &S::Instance, ctx: &[u8]) -> Self {
-        let protocol = S::new(instance);
-        let mut hasher = D::new();
+/// A proof with a header recording how it was made, for proofs stored beyond the lifetime of their configuration:
+/// the version of the standard, the registry identifier of the hash function and the label of the protocol.
+/// Encoded as the version byte, the hash identifier byte, the label and the encoding of the proof
+#[derive(Debug)]
+pub struct ProofEnvelope<P> {
+    version: u8,
+    hash_id: u8,
+    label: [u8; LABEL_LENGTH],
+    proof: P,
+}
+
+/// The encoded prover state of a proof split between [`NizkProver::commit_phase`] and
+/// [`NizkProver::response_phase`], for provers whose commitment and response are computed in different processes.
+///
+/// **The state holds the witness and the commitment randomness**: anyone who learns it learns the witness, and
+/// answering two challenges with it also reveals the witness. It must only be moved over a confidential channel,
+/// and passed to a single response phase, which consumes it. Its debug output omits the bytes, which are wiped on
+/// drop with the `zeroize` feature
+pub struct ProverStateBytes {
+    bytes: Vec<u8>,
+}
+
+impl<D: SufficientOutput + Default> MessageHasher<D> {
+    /// Start hashing a message over the default transcript
+    pub fn new() -> Self {
+        Self {
+            transcript: D::default(),
+        }
+    }
+}
+
+impl<D: Transcript> MessageHasher<D> {
+    /// Absorb the next bytes of the message
+    pub fn update(&mut self, bytes: &[u8]) {
+        self.transcript.append(b"message", bytes);
+    }
+
+    /// The hash of all the bytes absorbed
+    pub fn finalize(self) -> HashedMessage<D> {
+        HashedMessage {
+            hm: self.transcript.challenge(b"message"),
+            structured: false,
+            _transcript: PhantomData,
+        }
+    }
+}
+
+impl<D: SufficientOutput + Default> Default for MessageHasher<D> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<D: Transcript> io::Write for MessageHasher<D> {
+    fn write(&mut self, bytes: &[u8]) -> io::Result<usize> {
+        self.update(bytes);
+        Ok(bytes.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl<S: MultiRoundSigmaProtocol> Drop for WipedOnDrop<'_, S> {
+    fn drop(&mut self) {
+        self.protocol.zeroize_state(&mut self.state);
+    }
+}
+
+impl<D: Transcript> io::Write for TranscriptWriter<'_, D> {
+    fn write(&mut self, bytes: &[u8]) -> io::Result<usize> {
+        self.transcript.append(self.label, bytes);
+        Ok(bytes.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl<D: SufficientOutput + Default> HashedMessage<D> {
+    /// The hash of a message held in memory
+    pub fn new(message: &[u8]) -> Self {
+        let mut hasher = MessageHasher::new();
+        hasher.update(message);
+        hasher.finalize()
+    }
+}
+
+impl TranscriptData {
+    /// Associated data without fields
+    pub fn new() -> Self {
+        Self::default()
+    }
 
-        hasher.update(DOMSEP);
-        let hd_long = hasher.finalize_reset();
-        let mut hd = [0u8; CHALLENGE_LENGTH];
-        hd.copy_from_slice(&hd_long[..CHALLENGE_LENGTH]); // TODO use last 32 bytes instead
+    /// Appends the field `label` with the given value
+    pub fn add_label(mut self, label: &str, value: &[u8]) -> Self {
+        for bytes in [label.as_bytes(), value] {
+            self.encoding
+                .extend_from_slice(&(bytes.len() as u64).to_le_bytes());
+            self.encoding.extend_from_slice(bytes);
+        }
+        self
+    }
 
-        let ha = protocol.label();
+    /// The hash of the framed fields, which stands for the message in the challenge. The challenge absorbs a
+    /// domain separator before it, so that a proof bound to the fields is not bound to a raw message equal to their
+    /// encoding
+    pub fn hashed<D: SufficientOutput + Default>(&self) -> HashedMessage<D> {
+        HashedMessage {
+            structured: true,
+            ..HashedMessage::new(&self.encoding)
+        }
+    }
+}
 
-        hasher.update(ctx);
-        let hctx_long = hasher.finalize_reset();
-        let mut hctx = [0u8; CHALLENGE_LENGTH];
-        hctx.copy_from_slice(&hctx_long[..CHALLENGE_LENGTH]);
+impl SessionContext {
+    /// The session of the prover `prover_identity` in the application `application_id` at version `version`,
+    /// under the fresh `session_nonce` of the verifier and, if any, a timestamp such as the seconds since the Unix
+    /// epoch
+    pub fn new(
+        version: u32,
+        application_id: &[u8],
+        prover_identity: &[u8],
+        session_nonce: &[u8],
+        timestamp: Option<u64>,
+    ) -> Self {
+        let mut data = TranscriptData::new()
+            .add_label("domain", SESSION_DOMSEP)
+            .add_label("version", &version.to_le_bytes())
+            .add_label("application", application_id)
+            .add_label("prover", prover_identity)
+            .add_label("nonce", session_nonce);
+        if let Some(timestamp) = timestamp {
+            data = data.add_label("timestamp", &timestamp.to_le_bytes());
+        }
+        Self {
+            encoding: data.encoding,
+        }
+    }
 
+    /// An opaque context, absorbed as is: the NIZK built with it is the one the constructors taking `ctx: &[u8]`
+    /// build. It only shares its challenges with a session if it is crafted as the encoding of that session
+    pub fn from_raw_context(ctx: &[u8]) -> Self {
         Self {
-            interactive_protocol: protocol,
-            hasher,
-            hd,
-            ha,
-            hctx,
+            encoding: ctx.to_vec(),
         }
     }
+}
 
-    fn challenge(&mut self, message: Option<&[u8]>, commitment: &S::Commitment) -> Challenge {
-        let mut challenge: Challenge = [0; CHALLENGE_LENGTH];
+impl FsMode {
+    /// The byte appended to the domain separator, where 0 means no suffix: the strong mode appends nothing
+    #[allow(deprecated)]
+    fn byte(self) -> u8 {
+        match self {
+            FsMode::Strong => 0,
+            FsMode::LegacyWeak => 1,
+        }
+    }
+}
 
-        let mut commitment_bytes = Vec::new();
-        commitment.serialize(&mut commitment_bytes).unwrap();
+impl<S: MultiRoundSigmaProtocol, D: Transcript, const L: usize> NizkBuilder<S, D, L> {
+    /// Start building for a given Sigma protocol instance
+    pub fn new(instance: &S::Instance) -> Self {
+        Self::from_protocol(S::new(instance))
+    }
 
-        let hashed = match message {
-            Some(msg) => {
-                self.hasher.update(msg);
-                let hm_long = self.hasher.finalize_reset();
-                let hm = &hm_long[..CHALLENGE_LENGTH];
+    /// Start building for an already built protocol, such as one carrying precomputed tables
+    pub fn from_protocol(protocol: S) -> Self {
+        Self {
+            protocol,
+            session: SessionContext::from_raw_context(&[]),
+            mode: FsMode::Strong,
+            transcript: PhantomData,
+        }
+    }
 
-                self.hasher.update(&self.hd);
-                self.hasher.update(&self.hctx);
-                self.hasher.update(&self.ha);
-                self.hasher.update(hm);
-                self.hasher.update(&commitment_bytes);
-                self.hasher.finalize_reset()
-            }
-            None => {
-                self.hasher.update(&self.hd);
-                self.hasher.update(&self.hctx);
-                self.hasher.update(&self.ha);
-                self.hasher.update(&commitment_bytes);
-                self.hasher.finalize_reset()
-            }
-        };
+    /// Bind the proofs to an opaque context, as the constructors taking `ctx: &[u8]` do
+    pub fn context(self, ctx: &[u8]) -> Self {
+        self.session(SessionContext::from_raw_context(ctx))
+    }
 
-        challenge.copy_from_slice(&hashed[..CHALLENGE_LENGTH]);
+    /// Bind the proofs to a session, see [`SessionContext`]
+    pub fn session(mut self, session: SessionContext) -> Self {
+        self.session = session;
+        self
+    }
 
-        challenge
+    /// Choose whether the challenges bind the instance, see [`FsMode`]
+    pub fn fiat_shamir(mut self, mode: FsMode) -> Self {
+        self.mode = mode;
+        self
     }
 
-    /// Produce a batchable proof for the instance using the provided witness
-    pub fn batchable_proof<R: Rng>(
-        &mut self,
-        witness: &S::Witness,
-        message: Option<&[u8]>,
-        rng: &mut R,
-    ) -> Result<BatchableProof<S>, SigmaError> {
-        let (commitment, prover_state) = self.interactive_protocol.prover_commit(witness, rng);
-        let challenge = self.challenge(message, &commitment);
-        let response = self
-            .interactive_protocol
-            .prover_response(&prover_state, &challenge)?;
+    /// Build the NIZK, see [`NizkProver::try_new`] for the errors
+    pub fn build(self) -> Result<NIZK<S, D, L>, SigmaError>
+    where
+        S: Clone,
+        D: Default,
+    {
+        Ok(NIZK {
+            prover: self.clone().build_prover()?,
+            verifier: self.build_verifier()?,
+        })
+    }
 
-        Ok(BatchableProof {
-            commitment,
-            response,
+    /// Build only the prover, see [`NizkProver::try_new`] for the errors
+    pub fn build_prover(self) -> Result<NizkProver<S, D, L>, SigmaError>
+    where
+        D: Default,
+    {
+        Ok(NizkProver {
+            fiat_shamir: self.transform()?,
         })
     }
 
-    /// Verify a batchable proof
-    pub fn batchable_verify(
-        &mut self,
-        proof: &BatchableProof<S>,
-        message: Option<&[u8]>,
-    ) -> Result<(), SigmaError> {
-        let challenge = self.challenge(message, &proof.commitment);
-        self.interactive_protocol
-            .verifier(&proof.commitment, &challenge, &proof.response)
+    /// Build only the verifier, see [`NizkProver::try_new`] for the errors
+    pub fn build_verifier(self) -> Result<NizkVerifier<S, D, L>, SigmaError>
+    where
+        D: Default,
+    {
+        Ok(NizkVerifier {
+            fiat_shamir: self.transform()?,
+        })
     }
 
-    /// Produce a short proof for the instance using the provided witness
-    pub fn short_proof<R: Rng>(
-        &mut self,
-        witness: &S::Witness,
-        message: Option<&[u8]>,
-        rng: &mut R,
-    ) -> Result<ShortProof<S>, SigmaError> {
-        let (commitment, prover_state) = self.interactive_protocol.prover_commit(witness, rng);
-        let challenge = self.challenge(message, &commitment);
-        let response = self
-            .interactive_protocol
-            .prover_response(&prover_state, &challenge)?;
+    fn transform(self) -> Result<FiatShamir<S, D, L>, SigmaError>
+    where
+        D: Default,
+    {
+        FiatShamir::new(
+            self.protocol,
+            &self.session,
+            D::default(),
+            DOMSEP,
+            self.mode,
+        )
+    }
+}
 
-        Ok(ShortProof {
+/// Appended to the domain separator for the version byte of proof envelopes since challenges are the last bytes of
+/// the digests rather than the first, so that envelopes of proofs over 64-byte digests made before are rejected
+const DIGEST_SUFFIX_REVISION: &[u8] = b"/digest-suffix";
+
+/// The version byte of proof envelopes, the first byte of the compressed `Blake2b(DOMSEP || DIGEST_SUFFIX_REVISION)`,
+/// so that it changes with the version of the standard named in the domain separator and with the revisions of the
+/// transcript
+fn envelope_version() -> u8 {
+    compress_digest(&blake2::Blake2b::digest(
+        &[DOMSEP, DIGEST_SUFFIX_REVISION].concat(),
+    ))[0]
+}
+
+impl<P> ProofEnvelope<P> {
+    #[cfg(feature = "cbor")]
+    pub(crate) fn from_parts(
+        version: u8,
+        hash_id: u8,
+        label: [u8; LABEL_LENGTH],
+        proof: P,
+    ) -> Self {
+        Self {
+            version,
+            hash_id,
+            label,
+            proof,
+        }
+    }
+
+    /// The version of the standard the proof was made under
+    pub fn version(&self) -> u8 {
+        self.version
+    }
+
+    /// The registry identifier of the hash function the proof was made with, see [`HashFunction::id`]
+    pub fn hash_id(&self) -> u8 {
+        self.hash_id
+    }
+
+    /// The label of the protocol the proof was made for
+    pub fn label(&self) -> &[u8; LABEL_LENGTH] {
+        &self.label
+    }
+
+    /// A reference to the proof, without checking the header
+    pub fn proof(&self) -> &P {
+        &self.proof
+    }
+
+    /// The proof, without checking the header
+    pub fn into_proof(self) -> P {
+        self.proof
+    }
+}
+
+impl ProverStateBytes {
+    /// Wraps an encoded prover state received from the commit phase
+    pub fn from_bytes(bytes: Vec<u8>) -> Self {
+        Self { bytes }
+    }
+
+    /// The encoded prover state, to be sent to the response phase
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+}
+
+impl fmt::Debug for ProverStateBytes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ProverStateBytes").finish_non_exhaustive()
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl zeroize::Zeroize for ProverStateBytes {
+    fn zeroize(&mut self) {
+        self.bytes.zeroize();
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl Drop for ProverStateBytes {
+    fn drop(&mut self) {
+        zeroize::Zeroize::zeroize(self);
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl zeroize::ZeroizeOnDrop for ProverStateBytes {}
+
+impl<P: CanonicalSerialize> CanonicalSerialize for ProofEnvelope<P> {
+    fn serialize<W: Write>(&self, mut writer: W) -> Result<(), SerializationError> {
+        self.version.serialize(&mut writer)?;
+        self.hash_id.serialize(&mut writer)?;
+        writer.write_all(&self.label)?;
+        self.proof.serialize(&mut writer)
+    }
+
+    fn serialized_size(&self) -> usize {
+        2 + LABEL_LENGTH + self.proof.serialized_size()
+    }
+}
+
+impl<P: CanonicalDeserialize> CanonicalDeserialize for ProofEnvelope<P> {
+    fn deserialize<R: Read>(mut reader: R) -> Result<Self, SerializationError> {
+        let version = u8::deserialize(&mut reader)?;
+        let hash_id = u8::deserialize(&mut reader)?;
+        let mut label = [0u8; LABEL_LENGTH];
+        reader.read_exact(&mut label)?;
+
+        Ok(Self {
+            version,
+            hash_id,
+            label,
+            proof: P::deserialize(&mut reader)?,
+        })
+    }
+}
+
+// Proofs are cloned, compared and printed field by field, as deriving would require the protocol itself to be
+// `Clone`, `PartialEq` and `Debug`
+impl<S: SigmaProtocol> fmt::Debug for BatchableProof<S>
+where
+    S::Commitment: fmt::Debug,
+    S::Response: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BatchableProof")
+            .field("commitment", &self.commitment)
+            .field("response", &self.response)
+            .finish()
+    }
+}
+
+impl<S: SigmaProtocol, const L: usize> fmt::Debug for ShortProof<S, L>
+where
+    S::Response: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ShortProof")
+            .field("challenge", &self.challenge)
+            .field("response", &self.response)
+            .finish()
+    }
+}
+
+impl<S: SigmaProtocol> Clone for BatchableProof<S>
+where
+    S::Commitment: Clone,
+    S::Response: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            commitment: self.commitment.clone(),
+            response: self.response.clone(),
+        }
+    }
+}
+
+impl<S: SigmaProtocol> PartialEq for BatchableProof<S>
+where
+    S::Commitment: PartialEq,
+    S::Response: PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.commitment == other.commitment && self.response == other.response
+    }
+}
+
+impl<S: SigmaProtocol, const L: usize> Clone for ShortProof<S, L>
+where
+    S::Response: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            challenge: self.challenge,
+            response: self.response.clone(),
+        }
+    }
+}
+
+impl<S: SigmaProtocol, const L: usize> PartialEq for ShortProof<S, L>
+where
+    S::Response: PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.challenge == other.challenge && self.response == other.response
+    }
+}
+
+/// Encoded as the challenge bytes followed by the encoding of the response
+impl<S: SigmaProtocol, const L: usize> CanonicalSerialize for ShortProof<S, L> {
+    fn serialize<W: Write>(&self, mut writer: W) -> Result<(), SerializationError> {
+        writer.write_all(&self.challenge)?;
+        self.response.serialize(&mut writer)
+    }
+
+    fn serialized_size(&self) -> usize {
+        L + self.response.serialized_size()
+    }
+
+    fn serialize_uncompressed<W: Write>(&self, mut writer: W) -> Result<(), SerializationError> {
+        writer.write_all(&self.challenge)?;
+        self.response.serialize_uncompressed(&mut writer)
+    }
+
+    fn serialize_unchecked<W: Write>(&self, mut writer: W) -> Result<(), SerializationError> {
+        writer.write_all(&self.challenge)?;
+        self.response.serialize_unchecked(&mut writer)
+    }
+
+    fn uncompressed_size(&self) -> usize {
+        L + self.response.uncompressed_size()
+    }
+}
+
+impl<S: SigmaProtocol, const L: usize> CanonicalDeserialize for ShortProof<S, L> {
+    fn deserialize<R: Read>(mut reader: R) -> Result<Self, SerializationError> {
+        let mut challenge = [0u8; L];
+        reader.read_exact(&mut challenge)?;
+        let response = S::Response::deserialize(&mut reader)?;
+        Ok(Self {
             challenge,
             response,
         })
     }
 
-    /// Verify a short proof
-    pub fn short_verify(
-        &mut self,
-        proof: &ShortProof<S>,
-        message: Option<&[u8]>,
-    ) -> Result<(), SigmaError> {
-        let commitment = self
-            .interactive_protocol
-            .simulate_commitment(&proof.challenge, &proof.response)?;
-        let challenge = self.challenge(message, &commitment);
+    fn deserialize_uncompressed<R: Read>(mut reader: R) -> Result<Self, SerializationError> {
+        let mut challenge = [0u8; L];
+        reader.read_exact(&mut challenge)?;
+        let response = S::Response::deserialize_uncompressed(&mut reader)?;
+        Ok(Self {
+            challenge,
+            response,
+        })
+    }
 
-        if challenge == proof.challenge {
-            return Ok(());
-        } else {
-            return Err(SigmaError::VerificationFailed);
+    fn deserialize_unchecked<R: Read>(mut reader: R) -> Result<Self, SerializationError> {
+        let mut challenge = [0u8; L];
+        reader.read_exact(&mut challenge)?;
+        let response = S::Response::deserialize_unchecked(&mut reader)?;
+        Ok(Self {
+            challenge,
+            response,
+        })
+    }
+}
+
+impl<S: MultiRoundSigmaProtocol> fmt::Debug for MultiRoundProof<S>
+where
+    S::Message: fmt::Debug,
+    S::Response: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MultiRoundProof")
+            .field("messages", &self.messages)
+            .field("response", &self.response)
+            .finish()
+    }
+}
+
+impl<S: MultiRoundSigmaProtocol> Clone for MultiRoundProof<S>
+where
+    S::Message: Clone,
+    S::Response: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            messages: self.messages.clone(),
+            response: self.response.clone(),
         }
     }
 }
 
-#[cfg(test)]
-pub(crate) mod tests {
-    use digest::Digest;
-    use rand::Rng;
+/// Encoded as the encodings of the messages followed by that of the response, with no length prefix as the
+/// protocol fixes the number of rounds
+impl<S: MultiRoundSigmaProtocol> CanonicalSerialize for MultiRoundProof<S> {
+    fn serialize<W: Write>(&self, mut writer: W) -> Result<(), SerializationError> {
+        for message in &self.messages {
+            message.serialize(&mut writer)?;
+        }
+        self.response.serialize(&mut writer)
+    }
 
-    use crate::{SigmaError, SigmaProtocol, NIZK};
+    fn serialized_size(&self) -> usize {
+        let messages: usize = self.messages.iter().map(|m| m.serialized_size()).sum();
+        messages + self.response.serialized_size()
+    }
 
-    /// Generates a batched proof using the provided witness and instance and returns the verifier output
-    pub(crate) fn run_nizk_batched<D: Digest, S: SigmaProtocol, R: Rng>(
-        instance: &S::Instance,
-        witness: &S::Witness,
-        rng: &mut R,
-    ) -> Result<(), SigmaError> {
-        let ctx = b"this is a test";
-        let message = b"this is a message";
+    fn serialize_uncompressed<W: Write>(&self, mut writer: W) -> Result<(), SerializationError> {
+        for message in &self.messages {
+            message.serialize_uncompressed(&mut writer)?;
+        }
+        self.response.serialize_uncompressed(&mut writer)
+    }
 
-        let mut nizk = NIZK::<S, D>::new(instance, ctx);
+    fn serialize_unchecked<W: Write>(&self, mut writer: W) -> Result<(), SerializationError> {
+        for message in &self.messages {
+            message.serialize_unchecked(&mut writer)?;
+        }
+        self.response.serialize_unchecked(&mut writer)
+    }
 
-        let proof = nizk.batchable_proof(witness, Some(message), rng)?;
+    fn uncompressed_size(&self) -> usize {
+        let messages: usize = self.messages.iter().map(|m| m.uncompressed_size()).sum();
+        messages + self.response.uncompressed_size()
+    }
+}
 
-        nizk.batchable_verify(&proof, Some(message))
+impl<S: MultiRoundSigmaProtocol> CanonicalDeserialize for MultiRoundProof<S> {
+    fn deserialize<R: Read>(mut reader: R) -> Result<Self, SerializationError> {
+        let messages = (0..S::ROUNDS)
+            .map(|_| S::Message::deserialize(&mut reader))
+            .collect::<Result<_, _>>()?;
+        let response = S::Response::deserialize(&mut reader)?;
+        Ok(Self { messages, response })
     }
 
-    /// Generates a batched proof using the provided witness and instance and returns the verifier output
-    pub(crate) fn run_nizk_short<D: Digest, S: SigmaProtocol, R: Rng>(
-        instance: &S::Instance,
-        witness: &S::Witness,
-        rng: &mut R,
-    ) -> Result<(), SigmaError> {
-        let ctx = b"this is a test";
-        let message = b"this is a message";
+    fn deserialize_uncompressed<R: Read>(mut reader: R) -> Result<Self, SerializationError> {
+        let messages = (0..S::ROUNDS)
+            .map(|_| S::Message::deserialize_uncompressed(&mut reader))
+            .collect::<Result<_, _>>()?;
+        let response = S::Response::deserialize_uncompressed(&mut reader)?;
+        Ok(Self { messages, response })
+    }
+
+    fn deserialize_unchecked<R: Read>(mut reader: R) -> Result<Self, SerializationError> {
+        let messages = (0..S::ROUNDS)
+            .map(|_| S::Message::deserialize_unchecked(&mut reader))
+            .collect::<Result<_, _>>()?;
+        let response = S::Response::deserialize_unchecked(&mut reader)?;
+        Ok(Self { messages, response })
+    }
+}
+
+/// The encoding of a proof. Serializing into a vector cannot run out of space, so this does not fail
+pub(crate) fn proof_bytes<X: CanonicalSerialize>(proof: &X, compressed: bool) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(proof_size(proof, compressed));
+    if compressed {
+        proof.serialize(&mut bytes)
+    } else {
+        proof.serialize_uncompressed(&mut bytes)
+    }
+    .unwrap_or_else(|_| unreachable!("serializing into a vector does not fail"));
+    bytes
+}
+
+fn proof_size<X: CanonicalSerialize>(proof: &X, compressed: bool) -> usize {
+    if compressed {
+        proof.serialized_size()
+    } else {
+        proof.uncompressed_size()
+    }
+}
+
+/// Decodes a proof, rejecting trailing bytes so that every proof has a single encoding
+pub(crate) fn proof_from_bytes<X: CanonicalDeserialize>(
+    mut bytes: &[u8],
+    compressed: bool,
+) -> Result<X, SigmaError> {
+    let proof = if compressed {
+        X::deserialize(&mut bytes)?
+    } else {
+        X::deserialize_uncompressed(&mut bytes)?
+    };
+
+    if bytes.is_empty() {
+        Ok(proof)
+    } else {
+        Err(SerializationError::InvalidData.into())
+    }
+}
+
+impl<S: SigmaProtocol> fmt::Display for BatchableProof<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_hex())
+    }
+}
+
+impl<S: SigmaProtocol> FromStr for BatchableProof<S> {
+    type Err = SigmaError;
+
+    fn from_str(hex: &str) -> Result<Self, Self::Err> {
+        Self::from_hex(hex)
+    }
+}
+
+impl<S: SigmaProtocol> BatchableProof<S> {
+    #[cfg(feature = "cbor")]
+    pub(crate) fn from_parts(commitment: S::Commitment, response: S::Response) -> Self {
+        Self {
+            commitment,
+            response,
+        }
+    }
+
+    pub(crate) fn commitment(&self) -> &S::Commitment {
+        &self.commitment
+    }
+
+    pub(crate) fn response(&self) -> &S::Response {
+        &self.response
+    }
+
+    /// The compressed encoding of the proof, as produced by [`CanonicalSerialize::serialize`]
+    pub fn to_bytes_compressed(&self) -> Vec<u8> {
+        proof_bytes(self, true)
+    }
+
+    /// The uncompressed encoding of the proof, larger but faster to decode
+    pub fn to_bytes_uncompressed(&self) -> Vec<u8> {
+        proof_bytes(self, false)
+    }
+
+    /// Decodes a compressed batchable proof, rejecting trailing bytes
+    pub fn from_bytes_compressed(bytes: &[u8]) -> Result<Self, SigmaError> {
+        proof_from_bytes(bytes, true)
+    }
+
+    /// Writes the compressed encoding of the proof into `writer`, as [`BatchableProof::to_bytes_compressed`]
+    /// without the intermediate vector
+    pub fn write_into<W: io::Write>(&self, writer: W) -> Result<(), SigmaError> {
+        Ok(self.serialize(writer)?)
+    }
+
+    /// Reads a compressed batchable proof from `reader`, which is left after the last byte of the proof. See
+    /// [`ProofStream`](crate::ProofStream) for reading many proofs from one stream
+    pub fn read_from<R: io::Read>(reader: R) -> Result<Self, SigmaError> {
+        Ok(Self::deserialize(reader)?)
+    }
+
+    /// Decodes an uncompressed batchable proof, rejecting trailing bytes
+    pub fn from_bytes_uncompressed(bytes: &[u8]) -> Result<Self, SigmaError> {
+        proof_from_bytes(bytes, false)
+    }
+
+    /// The lowercase hex of the compressed encoding, which is also how the proof is displayed
+    pub fn to_hex(&self) -> String {
+        encode_hex(&self.to_bytes_compressed())
+    }
+
+    /// Decodes a batchable proof from the hex of its compressed encoding, in either case. Odd lengths, whitespace
+    /// and any other character are rejected, as is done when parsing the proof with [`str::parse`]
+    pub fn from_hex(hex: &str) -> Result<Self, SigmaError> {
+        Self::from_bytes_compressed(&decode_hex(hex)?)
+    }
+
+    /// The unpadded base64url encoding of the compressed encoding, for embedding the proof in URLs or tokens
+    pub fn to_base64url(&self) -> String {
+        encode_base64url(&self.to_bytes_compressed())
+    }
+
+    /// Decodes a batchable proof from the unpadded base64url encoding of its compressed encoding. Padding, whitespace,
+    /// the standard base64 alphabet and non-zero unused bits are rejected
+    pub fn from_base64url(base64: &str) -> Result<Self, SigmaError> {
+        Self::from_bytes_compressed(&decode_base64url(base64)?)
+    }
+
+    /// The length of the compressed or uncompressed encoding of the proof
+    pub fn size_in_bytes(&self, compressed: bool) -> usize {
+        proof_size(self, compressed)
+    }
+}
+
+impl<S: SigmaProtocol, const L: usize> fmt::Display for ShortProof<S, L> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_hex())
+    }
+}
+
+impl<S: SigmaProtocol, const L: usize> FromStr for ShortProof<S, L> {
+    type Err = SigmaError;
+
+    fn from_str(hex: &str) -> Result<Self, Self::Err> {
+        Self::from_hex(hex)
+    }
+}
+
+impl<S: SigmaProtocol, const L: usize> ShortProof<S, L> {
+    #[cfg(feature = "cbor")]
+    pub(crate) fn from_parts(challenge: Challenge<L>, response: S::Response) -> Self {
+        Self {
+            challenge,
+            response,
+        }
+    }
+
+    #[cfg(feature = "cbor")]
+    pub(crate) fn challenge(&self) -> &Challenge<L> {
+        &self.challenge
+    }
+
+    #[cfg(feature = "cbor")]
+    pub(crate) fn response(&self) -> &S::Response {
+        &self.response
+    }
+
+    /// The compressed encoding of the proof, as produced by [`CanonicalSerialize::serialize`]
+    pub fn to_bytes_compressed(&self) -> Vec<u8> {
+        proof_bytes(self, true)
+    }
+
+    /// The uncompressed encoding of the proof, larger but faster to decode
+    pub fn to_bytes_uncompressed(&self) -> Vec<u8> {
+        proof_bytes(self, false)
+    }
+
+    /// Decodes a compressed short proof, rejecting trailing bytes
+    pub fn from_bytes_compressed(bytes: &[u8]) -> Result<Self, SigmaError> {
+        proof_from_bytes(bytes, true)
+    }
+
+    /// Decodes an uncompressed short proof, rejecting trailing bytes
+    pub fn from_bytes_uncompressed(bytes: &[u8]) -> Result<Self, SigmaError> {
+        proof_from_bytes(bytes, false)
+    }
+
+    /// The lowercase hex of the compressed encoding, which is also how the proof is displayed
+    pub fn to_hex(&self) -> String {
+        encode_hex(&self.to_bytes_compressed())
+    }
+
+    /// Decodes a short proof from the hex of its compressed encoding, in either case. Odd lengths, whitespace
+    /// and any other character are rejected, as is done when parsing the proof with [`str::parse`]
+    pub fn from_hex(hex: &str) -> Result<Self, SigmaError> {
+        Self::from_bytes_compressed(&decode_hex(hex)?)
+    }
+
+    /// The unpadded base64url encoding of the compressed encoding, for embedding the proof in URLs or tokens
+    pub fn to_base64url(&self) -> String {
+        encode_base64url(&self.to_bytes_compressed())
+    }
+
+    /// Decodes a short proof from the unpadded base64url encoding of its compressed encoding. Padding, whitespace,
+    /// the standard base64 alphabet and non-zero unused bits are rejected
+    pub fn from_base64url(base64: &str) -> Result<Self, SigmaError> {
+        Self::from_bytes_compressed(&decode_base64url(base64)?)
+    }
+
+    /// The length of the compressed or uncompressed encoding of the proof
+    pub fn size_in_bytes(&self, compressed: bool) -> usize {
+        proof_size(self, compressed)
+    }
+}
+
+/// The error of every rejected proof. The public verifiers report [`SigmaError::VerificationFailed`] whatever the
+/// cause, so that a malformed proof cannot be told apart from a proof with a wrong challenge; the `_detailed`
+/// verifiers keep the cause, for debugging
+fn rejected(_cause: SigmaError) -> SigmaError {
+    SigmaError::VerificationFailed
+}
+
+/// The result of every item of a batch of `len` items, localizing failures by binary splitting: `check` is run
+/// on the whole batch, and every failing range is split in halves until the failing items are isolated. With few
+/// failures this runs `check` about `2 * failures * log2(len)` times instead of once per item
+fn localize_failures<F: FnMut(Range<usize>) -> Result<(), SigmaError>>(
+    len: usize,
+    mut check: F,
+) -> Vec<Result<(), SigmaError>> {
+    fn split<F: FnMut(Range<usize>) -> Result<(), SigmaError>>(
+        range: Range<usize>,
+        check: &mut F,
+        results: &mut [Result<(), SigmaError>],
+    ) {
+        if range.is_empty() {
+            return;
+        }
+
+        match check(range.clone()) {
+            Ok(()) => {}
+            Err(error) if range.len() == 1 => results[range.start] = Err(error),
+            Err(_) => {
+                let middle = range.start + range.len() / 2;
+                split(range.start..middle, check, results);
+                split(middle..range.end, check, results);
+            }
+        }
+    }
+
+    let mut results: Vec<_> = (0..len).map(|_| Ok(())).collect();
+    split(0..len, &mut check, &mut results);
+    results
+}
+
+impl<S: MultiRoundSigmaProtocol, D: Transcript, const L: usize> FiatShamir<S, D, L> {
+    /// Returns [`SigmaError::DigestTooShort`] if the transcript cannot output a whole challenge,
+    /// [`SigmaError::ChallengeTooShort`] if the protocol needs challenges longer than `L` bytes, and the error of
+    /// [`SigmaProtocol::validate_instance`] if the instance is rejected. Challenges longer than `CHALLENGE_LENGTH`
+    /// do not compile
+    fn new(
+        protocol: S,
+        ctx: &SessionContext,
+        transcript: D,
+        domain: &[u8],
+        mode: FsMode,
+    ) -> Result<Self, SigmaError> {
+        if transcript.output_len() < CHALLENGE_LENGTH {
+            return Err(SigmaError::DigestTooShort {
+                got: transcript.output_len(),
+                need: CHALLENGE_LENGTH,
+            });
+        }
+
+        const { assert!(L <= CHALLENGE_LENGTH, "challenges are at most 32 bytes") };
+        const { assert!(S::ROUNDS > 0, "protocols have at least one round") };
+        if L < S::MIN_CHALLENGE_LENGTH {
+            return Err(SigmaError::ChallengeTooShort {
+                got: L,
+                need: S::MIN_CHALLENGE_LENGTH,
+            });
+        }
+
+        protocol.validate_instance()?;
+
+        // The strong mode keeps the domain separator of the releases before the modes, so that its proofs still
+        // verify, and every other mode appends its byte, so that proofs of different modes never cross-verify
+        let hd = match mode.byte() {
+            0 => transcript.hash(b"domain", domain),
+            byte => transcript.hash(b"domain", &[domain, &[byte]].concat()),
+        };
+        // The label of a protocol is derived from its instance, so the weak mode derives it from the protocol
+        // identifier alone
+        let ha = match mode {
+            FsMode::Strong => protocol.label(),
+            _ => protocol_label(S::PROTOCOL_ID, &[]),
+        };
+        let hctx = transcript.hash(b"context", &ctx.encoding);
+        // Only absorbed by the challenges of the strong mode, and by the nonces of every mode
+        let hinst = transcript.hash(b"instance", &protocol.instance_bytes());
+
+        Ok(Self {
+            interactive_protocol: protocol,
+            transcript,
+            hd,
+            ha,
+            hctx,
+            hinst,
+            mode,
+        })
+    }
+
+    /// The expansion of the transcript, with which every challenge converts into those of the protocol
+    fn expansion(&self) -> ChallengeExpansion {
+        self.transcript.challenge_expansion()
+    }
+
+    fn message_hasher(&self) -> MessageHasher<D> {
+        MessageHasher {
+            transcript: self.transcript.clone(),
+        }
+    }
+
+    fn hash_message(&self, message: Option<&[u8]>) -> Option<HashedMessage<D>> {
+        message.map(|message| {
+            let mut hasher = self.message_hasher();
+            hasher.update(message);
+            hasher.finalize()
+        })
+    }
+
+    fn hash_data(&self, data: Option<&TranscriptData>) -> Option<HashedMessage<D>> {
+        let hashed = self.hash_message(data.map(|data| &data.encoding[..]))?;
+        Some(HashedMessage {
+            structured: true,
+            ..hashed
+        })
+    }
+
+    /// The rng the commitment randomness is drawn from, seeded with the hash of the instance, the message, 32 bytes
+    /// of `rng` and the witness. The nonces stay unpredictable if either the rng fails or the witness is guessable,
+    /// and a stuck rng cannot repeat a nonce across messages
+    fn nonce_rng<R: Rng>(
+        &self,
+        witness_bytes: &[u8],
+        message: Option<&HashedMessage<D>>,
+        rng: &mut R,
+    ) -> StdRng {
+        let mut transcript = self.transcript.clone();
+
+        transcript.append(b"nonce", NONCE_DOMSEP);
+        transcript.append(b"domain", &self.hd);
+        transcript.append(b"context", &self.hctx);
+        transcript.append(b"label", &self.ha);
+        transcript.append(b"instance", &self.hinst);
+        match message {
+            Some(message) => {
+                transcript.append(b"message", &[if message.structured { 2 } else { 1 }]);
+                transcript.append(b"message", &message.hm);
+            }
+            None => transcript.append(b"message", &[0]),
+        }
+        transcript.append(b"randomness", &rng.gen::<[u8; 32]>());
+        // Last, as the only field of variable length
+        transcript.append(b"witness", witness_bytes);
+
+        StdRng::from_seed(transcript.challenge(b"nonce"))
+    }
+
+    /// The transcript once it has absorbed the fields every challenge starts with, which proving many times
+    /// clones instead of absorbing them again
+    fn challenge_prefix(&self) -> D {
+        let hinst = (self.mode == FsMode::Strong).then_some(&self.hinst);
+        challenge_prefix(
+            self.transcript.clone(),
+            &self.hd,
+            &self.hctx,
+            &self.ha,
+            hinst,
+        )
+    }
+
+    /// The transcript of a multi-round proof once it has absorbed the fields every challenge starts with and the
+    /// message
+    fn round_transcript(&self, message: Option<&HashedMessage<D>>) -> D {
+        let mut transcript = self.challenge_prefix();
+        if let Some(message) = message {
+            absorb_message(&mut transcript, message);
+        }
+        transcript
+    }
+
+    /// Absorbs the message of a round into `transcript` and squeezes the challenge answering it, which is absorbed
+    /// in turn so that the challenges of the later rounds depend on it. The challenge of the first round is the
+    /// one [`FiatShamir::challenge_from_prefix`] derives over the same commitment
+    fn round_challenge(
+        &self,
+        transcript: &mut D,
+        message: &S::Message,
+    ) -> Result<Challenge, SigmaError> {
+        message.absorb_canonical(TranscriptWriter {
+            transcript: &mut *transcript,
+            label: b"commitment",
+        })?;
+        trace::absorbed(&*transcript, b"commitment", message.absorbed_size());
+
+        let challenge = padded(&truncated::<L>(&transcript.clone().challenge(b"challenge")));
+        transcript.append(b"challenge", &challenge);
+        Ok(challenge)
+    }
+}
+
+impl<S: SigmaProtocol, D: Transcript, const L: usize> FiatShamir<S, D, L> {
+    fn challenge(
+        &self,
+        message: Option<&[u8]>,
+        commitment: &S::Commitment,
+    ) -> Result<Challenge, SigmaError> {
+        self.hashed_challenge(self.hash_message(message).as_ref(), commitment)
+    }
+
+    fn hashed_challenge(
+        &self,
+        message: Option<&HashedMessage<D>>,
+        commitment: &S::Commitment,
+    ) -> Result<Challenge, SigmaError> {
+        let _span = trace::challenge_span(&self.ha, &self.hctx);
+        self.challenge_from_prefix(self.challenge_prefix(), message, commitment)
+    }
+
+    /// The challenge over `prefix`, a transcript returned by [`FiatShamir::challenge_prefix`]
+    fn challenge_from_prefix(
+        &self,
+        transcript: D,
+        message: Option<&HashedMessage<D>>,
+        commitment: &S::Commitment,
+    ) -> Result<Challenge, SigmaError> {
+        challenge_over::<D, SigmaError, L>(transcript, message, |transcript| {
+            // Streamed rather than buffered, so that no challenge allocates over a digest
+            commitment.absorb_canonical(TranscriptWriter {
+                transcript: &mut *transcript,
+                label: b"commitment",
+            })?;
+            trace::absorbed(&*transcript, b"commitment", commitment.absorbed_size());
+            Ok(())
+        })
+    }
+}
+
+impl<S: SigmaProtocol, const L: usize> FiatShamir<S, DynTranscript, L> {
+    fn envelope<P>(&self, proof: P) -> ProofEnvelope<P> {
+        ProofEnvelope {
+            version: envelope_version(),
+            hash_id: self.transcript.hash_function().id(),
+            label: self.ha,
+            proof,
+        }
+    }
+
+    fn check_envelope<P>(&self, envelope: &ProofEnvelope<P>) -> Result<(), SigmaError> {
+        if envelope.version == envelope_version()
+            && envelope.hash_id == self.transcript.hash_function().id()
+            && envelope.label == self.ha
+        {
+            Ok(())
+        } else {
+            Err(SigmaError::EnvelopeMismatch)
+        }
+    }
+}
+
+impl<S: SigmaProtocol> NizkProver<S, DynTranscript> {
+    /// Initialise the prover over a hash function chosen at runtime. The name of the hash is absorbed into the domain
+    /// separator, so that proofs made with different hashes never cross-verify
+    pub fn with_hash_function(
+        instance: &S::Instance,
+        ctx: &[u8],
+        hash: HashFunction,
+    ) -> Result<Self, SigmaError> {
+        Ok(Self {
+            fiat_shamir: FiatShamir::new(
+                S::new(instance),
+                &SessionContext::from_raw_context(ctx),
+                DynTranscript::new(hash)?,
+                &[DOMSEP, hash.name().as_bytes()].concat(),
+                FsMode::Strong,
+            )?,
+        })
+    }
+
+    /// Produce a batchable proof wrapped in an envelope recording the version, hash function and protocol
+    pub fn prove_enveloped<R: Rng>(
+        &self,
+        witness: &S::Witness,
+        message: Option<&[u8]>,
+        rng: &mut R,
+    ) -> Result<ProofEnvelope<BatchableProof<S>>, SigmaError> {
+        let proof = self.batchable_proof(witness, message, rng)?;
+        Ok(self.fiat_shamir.envelope(proof))
+    }
+}
+
+impl<S: MultiRoundSigmaProtocol, D: Transcript, const L: usize> NizkProver<S, D, L> {
+    /// Initialise the prover for a given Sigma protocol instance and context. Panics if
+    /// [`SigmaProtocol::validate_instance`] rejects the instance, which [`NizkProver::try_new`] reports instead
+    pub fn new(instance: &S::Instance, ctx: &[u8]) -> Self
+    where
+        D: SufficientOutput + Default,
+    {
+        Self::try_new(instance, ctx).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Initialise the prover over a transcript whose output length is only known at runtime. Returns
+    /// [`SigmaError::DigestTooShort`] if it outputs fewer than `CHALLENGE_LENGTH` bytes, and
+    /// [`SigmaError::InvalidInstance`] if [`SigmaProtocol::validate_instance`] rejects the instance
+    pub fn try_new(instance: &S::Instance, ctx: &[u8]) -> Result<Self, SigmaError>
+    where
+        D: Default,
+    {
+        Self::from_protocol(S::new(instance), ctx)
+    }
+
+    /// Initialise the prover for an instance shared with the caller, which the protocols overriding
+    /// [`SigmaProtocol::from_shared`] hold rather than copy. See [`NizkProver::try_new`] for the errors
+    pub fn from_shared(instance: Arc<S::Instance>, ctx: &[u8]) -> Result<Self, SigmaError>
+    where
+        D: Default,
+    {
+        Self::from_protocol(S::from_shared(instance), ctx)
+    }
+
+    /// Initialise the prover for an already built protocol, such as one carrying precomputed tables, see
+    /// [`NizkProver::try_new`]
+    pub fn from_protocol(protocol: S, ctx: &[u8]) -> Result<Self, SigmaError>
+    where
+        D: Default,
+    {
+        Self::from_protocol_with_session(protocol, &SessionContext::from_raw_context(ctx))
+    }
+
+    /// Initialise the prover for the proofs of a session, see [`SessionContext`] and [`NizkProver::try_new`] for the
+    /// errors
+    pub fn with_session(
+        instance: &S::Instance,
+        session: &SessionContext,
+    ) -> Result<Self, SigmaError>
+    where
+        D: Default,
+    {
+        Self::from_protocol_with_session(S::new(instance), session)
+    }
+
+    /// Initialise the prover for an already built protocol and the proofs of a session, see
+    /// [`NizkProver::with_session`]
+    pub fn from_protocol_with_session(
+        protocol: S,
+        session: &SessionContext,
+    ) -> Result<Self, SigmaError>
+    where
+        D: Default,
+    {
+        Ok(Self {
+            fiat_shamir: FiatShamir::new(protocol, session, D::default(), DOMSEP, FsMode::Strong)?,
+        })
+    }
+
+    /// Start hashing a message over the transcript of this prover, for the `_hashed` variants
+    pub fn message_hasher(&self) -> MessageHasher<D> {
+        self.fiat_shamir.message_hasher()
+    }
+
+    fn hash_data(&self, data: Option<&TranscriptData>) -> Option<HashedMessage<D>> {
+        self.fiat_shamir.hash_data(data)
+    }
+
+    /// Produce a proof of a multi-round protocol. The challenge of each round is squeezed from a transcript
+    /// absorbing the fields every challenge starts with, the message, and then in turn the message of each round
+    /// of the prover and the challenge answering it, so that every challenge depends on the transcript so far
+    pub fn multi_round_proof<R: Rng>(
+        &self,
+        witness: &S::Witness,
+        message: Option<&[u8]>,
+        rng: &mut R,
+    ) -> Result<MultiRoundProof<S>, SigmaError> {
+        let expansion = self.fiat_shamir.expansion();
+        let fiat_shamir = &self.fiat_shamir;
+        let protocol = &fiat_shamir.interactive_protocol;
+        let hashed = fiat_shamir.hash_message(message);
+        let nonce_rng =
+            &mut fiat_shamir.nonce_rng(&protocol.witness_bytes(witness), hashed.as_ref(), rng);
+        let mut transcript = fiat_shamir.round_transcript(hashed.as_ref());
+
+        let (first, state) = protocol.prover_commit(witness, expansion, nonce_rng)?;
+        let mut prover_state = WipedOnDrop { protocol, state };
+        let mut messages = Vec::with_capacity(S::ROUNDS);
+        messages.push(first);
+        let response = loop {
+            let round = messages.len() - 1;
+            let challenge = fiat_shamir.round_challenge(&mut transcript, &messages[round])?;
+            let challenge = S::challenge_from_bytes(&challenge, expansion)?;
+            if messages.len() == S::ROUNDS {
+                break protocol.prover_response(&prover_state.state, &challenge)?;
+            }
+            messages.push(protocol.prover_message(
+                &mut prover_state.state,
+                round,
+                &challenge,
+                nonce_rng,
+            )?);
+        };
+        // The state holds the witness and the nonces, which are wiped as it is dropped
+        drop(prover_state);
+
+        Ok(MultiRoundProof { messages, response })
+    }
+}
+
+impl<S: SigmaProtocol, D: Transcript, const L: usize> NizkProver<S, D, L> {
+    /// Produce a batchable proof for the instance using the provided witness
+    pub fn batchable_proof<R: Rng>(
+        &self,
+        witness: &S::Witness,
+        message: Option<&[u8]>,
+        rng: &mut R,
+    ) -> Result<BatchableProof<S>, SigmaError> {
+        self.batchable_proof_hashed(
+            witness,
+            self.fiat_shamir.hash_message(message).as_ref(),
+            rng,
+        )
+    }
+
+    /// Produce a batchable proof bound to labeled associated data
+    pub fn batchable_proof_with_data<R: Rng>(
+        &self,
+        witness: &S::Witness,
+        data: Option<&TranscriptData>,
+        rng: &mut R,
+    ) -> Result<BatchableProof<S>, SigmaError> {
+        self.batchable_proof_hashed(witness, self.hash_data(data).as_ref(), rng)
+    }
+
+    /// Produce a batchable proof bound to a message hashed with a [`MessageHasher`]
+    pub fn batchable_proof_hashed<R: Rng>(
+        &self,
+        witness: &S::Witness,
+        message: Option<&HashedMessage<D>>,
+        rng: &mut R,
+    ) -> Result<BatchableProof<S>, SigmaError> {
+        let (commitment, _, response) = self.prove(witness, message, rng)?;
+        trace::proved(&self.fiat_shamir.ha, "batchable");
+
+        Ok(BatchableProof {
+            commitment,
+            response,
+        })
+    }
+
+    /// Produce a batchable proof for each pair of a witness and a message, in their order, as for signing many
+    /// messages at once. The proofs are those of [`NizkProver::batchable_proof`] called in a loop with the same
+    /// rng, made faster: the commitments are normalized together before they are hashed, with a single inversion
+    /// for all of them, and the fields every challenge starts with are absorbed once
+    pub fn batchable_proofs<R: Rng>(
+        &self,
+        jobs: &[(S::Witness, Option<&[u8]>)],
+        rng: &mut R,
+    ) -> Result<Vec<BatchableProof<S>>, SigmaError> {
+        let expansion = self.fiat_shamir.expansion();
+        let protocol = &self.fiat_shamir.interactive_protocol;
+        let messages: Vec<_> = jobs
+            .iter()
+            .map(|(_, message)| self.fiat_shamir.hash_message(*message))
+            .collect();
+
+        let (mut commitments, prover_states): (Vec<_>, Vec<_>) = jobs
+            .iter()
+            .zip(&messages)
+            .map(|((witness, _), message)| {
+                let witness_bytes = protocol.witness_bytes(witness);
+                let nonce_rng =
+                    &mut self
+                        .fiat_shamir
+                        .nonce_rng(&witness_bytes, message.as_ref(), rng);
+                let (commitment, state) = protocol.prover_commit(witness, expansion, nonce_rng)?;
+                Ok((commitment, WipedOnDrop { protocol, state }))
+            })
+            .collect::<Result<Vec<_>, SigmaError>>()?
+            .into_iter()
+            .unzip();
+        protocol.normalize_commitments(&mut commitments);
+
+        let _span = trace::challenge_span(&self.fiat_shamir.ha, &self.fiat_shamir.hctx);
+        let prefix = self.fiat_shamir.challenge_prefix();
+        let responses = messages
+            .iter()
+            .zip(&commitments)
+            .zip(&prover_states)
+            .map(|((message, commitment), prover_state)| {
+                let challenge = self.fiat_shamir.challenge_from_prefix(
+                    prefix.clone(),
+                    message.as_ref(),
+                    commitment,
+                )?;
+                protocol.prover_response(
+                    &prover_state.state,
+                    &S::challenge_from_bytes(&challenge, expansion)?,
+                )
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        // The states hold the witnesses and the nonces, which are wiped as they are dropped
+        drop(prover_states);
+        for _ in &responses {
+            trace::proved(&self.fiat_shamir.ha, "batchable");
+        }
+
+        Ok(commitments
+            .into_iter()
+            .zip(responses)
+            .map(|(commitment, response)| BatchableProof {
+                commitment,
+                response,
+            })
+            .collect())
+    }
+
+    /// Produce a short proof for the instance using the provided witness
+    pub fn short_proof<R: Rng>(
+        &self,
+        witness: &S::Witness,
+        message: Option<&[u8]>,
+        rng: &mut R,
+    ) -> Result<ShortProof<S, L>, SigmaError>
+    where
+        S: CommitmentRecoverable,
+    {
+        self.short_proof_hashed(
+            witness,
+            self.fiat_shamir.hash_message(message).as_ref(),
+            rng,
+        )
+    }
+
+    /// Produce a short proof bound to labeled associated data
+    pub fn short_proof_with_data<R: Rng>(
+        &self,
+        witness: &S::Witness,
+        data: Option<&TranscriptData>,
+        rng: &mut R,
+    ) -> Result<ShortProof<S, L>, SigmaError>
+    where
+        S: CommitmentRecoverable,
+    {
+        self.short_proof_hashed(witness, self.hash_data(data).as_ref(), rng)
+    }
+
+    /// Produce a short proof bound to a message hashed with a [`MessageHasher`]
+    pub fn short_proof_hashed<R: Rng>(
+        &self,
+        witness: &S::Witness,
+        message: Option<&HashedMessage<D>>,
+        rng: &mut R,
+    ) -> Result<ShortProof<S, L>, SigmaError>
+    where
+        S: CommitmentRecoverable,
+    {
+        let (_, challenge, response) = self.prove(witness, message, rng)?;
+        trace::proved(&self.fiat_shamir.ha, "short");
+
+        Ok(ShortProof {
+            challenge: truncated(&challenge),
+            response,
+        })
+    }
+
+    /// The first half of a batchable proof split across processes: the commitment, and the encoded prover state
+    /// to be passed to [`NizkProver::response_phase`] with the same message. See [`ProverStateBytes`] for how the
+    /// state must be handled
+    pub fn commit_phase<R: Rng>(
+        &self,
+        witness: &S::Witness,
+        message: Option<&[u8]>,
+        rng: &mut R,
+    ) -> Result<(S::Commitment, ProverStateBytes), SigmaError>
+    where
+        S::ProverState: CanonicalSerialize,
+    {
+        let protocol = &self.fiat_shamir.interactive_protocol;
+        let message = self.fiat_shamir.hash_message(message);
+        let nonce_rng = &mut self.fiat_shamir.nonce_rng(
+            &protocol.witness_bytes(witness),
+            message.as_ref(),
+            rng,
+        );
+        let expansion = self.fiat_shamir.expansion();
+        let (mut commitment, state) = protocol.prover_commit(witness, expansion, nonce_rng)?;
+        let prover_state = WipedOnDrop { protocol, state };
+        protocol.normalize_commitments(slice::from_mut(&mut commitment));
+        // Allocated at its final size, so that growing the vector leaves no copy of the state behind
+        let mut bytes = Vec::with_capacity(prover_state.state.serialized_size());
+        prover_state.state.serialize(&mut bytes)?;
+
+        Ok((commitment, ProverStateBytes { bytes }))
+    }
+
+    /// The second half of a batchable proof split across processes: answers the Fiat-Shamir challenge of
+    /// `commitment` and `message` with the state of [`NizkProver::commit_phase`], which is consumed. Returns
+    /// [`SigmaError::SerializationError`] if the state cannot be decoded
+    pub fn response_phase(
+        &self,
+        commitment: S::Commitment,
+        prover_state: ProverStateBytes,
+        message: Option<&[u8]>,
+    ) -> Result<BatchableProof<S>, SigmaError>
+    where
+        S::ProverState: CanonicalDeserialize,
+    {
+        let expansion = self.fiat_shamir.expansion();
+        let protocol = &self.fiat_shamir.interactive_protocol;
+        let state = WipedOnDrop {
+            protocol,
+            state: proof_from_bytes(prover_state.as_bytes(), true)?,
+        };
+        drop(prover_state);
+        let challenge = self.fiat_shamir.challenge(message, &commitment)?;
+        let response = protocol.prover_response(
+            &state.state,
+            &S::challenge_from_bytes(&challenge, expansion)?,
+        )?;
+        drop(state);
+        trace::proved(&self.fiat_shamir.ha, "batchable");
+
+        Ok(BatchableProof {
+            commitment,
+            response,
+        })
+    }
+
+    fn prove<R: Rng>(
+        &self,
+        witness: &S::Witness,
+        message: Option<&HashedMessage<D>>,
+        rng: &mut R,
+    ) -> Result<(S::Commitment, Challenge, S::Response), SigmaError> {
+        let expansion = self.fiat_shamir.expansion();
+        let protocol = &self.fiat_shamir.interactive_protocol;
+        let nonce_rng =
+            &mut self
+                .fiat_shamir
+                .nonce_rng(&protocol.witness_bytes(witness), message, rng);
+        let (mut commitment, state) = protocol.prover_commit(witness, expansion, nonce_rng)?;
+        let prover_state = WipedOnDrop { protocol, state };
+        // Normalized once, so that hashing the commitment and encoding the proof do not each invert it
+        protocol.normalize_commitments(slice::from_mut(&mut commitment));
+        let challenge = self.fiat_shamir.hashed_challenge(message, &commitment)?;
+        let response = protocol.prover_response(
+            &prover_state.state,
+            &S::challenge_from_bytes(&challenge, expansion)?,
+        )?;
+        // The state holds the witness and the nonce, which are wiped as it is dropped
+        drop(prover_state);
+
+        Ok((commitment, challenge, response))
+    }
+}
+
+impl<S: SigmaProtocol> NizkVerifier<S, DynTranscript> {
+    /// Initialise the verifier over a hash function chosen at runtime. The name of the hash is absorbed into the domain
+    /// separator, so that proofs made with different hashes never cross-verify
+    pub fn with_hash_function(
+        instance: &S::Instance,
+        ctx: &[u8],
+        hash: HashFunction,
+    ) -> Result<Self, SigmaError> {
+        Ok(Self {
+            fiat_shamir: FiatShamir::new(
+                S::new(instance),
+                &SessionContext::from_raw_context(ctx),
+                DynTranscript::new(hash)?,
+                &[DOMSEP, hash.name().as_bytes()].concat(),
+                FsMode::Strong,
+            )?,
+        })
+    }
+
+    /// Verify an enveloped batchable proof. Returns [`SigmaError::EnvelopeMismatch`] without verifying if the
+    /// version, hash function or protocol of the envelope are not those of this verifier
+    pub fn verify_enveloped(
+        &self,
+        envelope: &ProofEnvelope<BatchableProof<S>>,
+        message: Option<&[u8]>,
+    ) -> Result<(), SigmaError> {
+        self.fiat_shamir.check_envelope(envelope)?;
+        self.batchable_verify(&envelope.proof, message)
+    }
+}
+
+impl<S: MultiRoundSigmaProtocol, D: Transcript, const L: usize> NizkVerifier<S, D, L> {
+    /// Initialise the verifier for a given Sigma protocol instance and context. Panics if
+    /// [`SigmaProtocol::validate_instance`] rejects the instance, which [`NizkVerifier::try_new`] reports instead
+    pub fn new(instance: &S::Instance, ctx: &[u8]) -> Self
+    where
+        D: SufficientOutput + Default,
+    {
+        Self::try_new(instance, ctx).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Initialise the verifier over a transcript whose output length is only known at runtime. Returns
+    /// [`SigmaError::DigestTooShort`] if it outputs fewer than `CHALLENGE_LENGTH` bytes, and
+    /// [`SigmaError::InvalidInstance`] if [`SigmaProtocol::validate_instance`] rejects the instance
+    pub fn try_new(instance: &S::Instance, ctx: &[u8]) -> Result<Self, SigmaError>
+    where
+        D: Default,
+    {
+        Self::from_protocol(S::new(instance), ctx)
+    }
+
+    /// Initialise the verifier for an instance shared with the caller, which the protocols overriding
+    /// [`SigmaProtocol::from_shared`] hold rather than copy. See [`NizkVerifier::try_new`] for the errors
+    pub fn from_shared(instance: Arc<S::Instance>, ctx: &[u8]) -> Result<Self, SigmaError>
+    where
+        D: Default,
+    {
+        Self::from_protocol(S::from_shared(instance), ctx)
+    }
+
+    /// Initialise the verifier for an already built protocol, such as one carrying precomputed tables, see
+    /// [`NizkVerifier::try_new`]
+    pub fn from_protocol(protocol: S, ctx: &[u8]) -> Result<Self, SigmaError>
+    where
+        D: Default,
+    {
+        Self::from_protocol_with_session(protocol, &SessionContext::from_raw_context(ctx))
+    }
+
+    /// Initialise the verifier for the proofs of a session, see [`SessionContext`] and [`NizkVerifier::try_new`] for the
+    /// errors
+    pub fn with_session(
+        instance: &S::Instance,
+        session: &SessionContext,
+    ) -> Result<Self, SigmaError>
+    where
+        D: Default,
+    {
+        Self::from_protocol_with_session(S::new(instance), session)
+    }
+
+    /// Initialise the verifier for an already built protocol and the proofs of a session, see
+    /// [`NizkVerifier::with_session`]
+    pub fn from_protocol_with_session(
+        protocol: S,
+        session: &SessionContext,
+    ) -> Result<Self, SigmaError>
+    where
+        D: Default,
+    {
+        Ok(Self {
+            fiat_shamir: FiatShamir::new(protocol, session, D::default(), DOMSEP, FsMode::Strong)?,
+        })
+    }
+
+    /// Start hashing a message over the transcript of this verifier, for the `_hashed` variants
+    pub fn message_hasher(&self) -> MessageHasher<D> {
+        self.fiat_shamir.message_hasher()
+    }
+
+    fn hash_data(&self, data: Option<&TranscriptData>) -> Option<HashedMessage<D>> {
+        self.fiat_shamir.hash_data(data)
+    }
+
+    /// Verify a multi-round proof, recomputing the challenge of every round as
+    /// [`NizkProver::multi_round_proof`] derives it
+    pub fn multi_round_verify(
+        &self,
+        proof: &MultiRoundProof<S>,
+        message: Option<&[u8]>,
+    ) -> Result<(), SigmaError> {
+        let result = self.check_multi_round(proof, message);
+        self.traced("multi-round", result).map_err(rejected)
+    }
+
+    fn traced(
+        &self,
+        proof: &'static str,
+        result: Result<(), SigmaError>,
+    ) -> Result<(), SigmaError> {
+        trace::verified(&self.fiat_shamir.ha, proof, &result);
+        result
+    }
+
+    fn check_multi_round(
+        &self,
+        proof: &MultiRoundProof<S>,
+        message: Option<&[u8]>,
+    ) -> Result<(), SigmaError> {
+        let expansion = self.fiat_shamir.expansion();
+        let fiat_shamir = &self.fiat_shamir;
+        let mut transcript =
+            fiat_shamir.round_transcript(fiat_shamir.hash_message(message).as_ref());
+        let challenges = proof
+            .messages
+            .iter()
+            .map(|message| {
+                S::challenge_from_bytes(
+                    &fiat_shamir.round_challenge(&mut transcript, message)?,
+                    expansion,
+                )
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        fiat_shamir
+            .interactive_protocol
+            .verifier(&proof.messages, &challenges, &proof.response)
+    }
+}
+
+impl<S: SigmaProtocol, D: Transcript, const L: usize> NizkVerifier<S, D, L> {
+    fn challenge(
+        &self,
+        message: Option<&[u8]>,
+        commitment: &S::Commitment,
+    ) -> Result<Challenge, SigmaError> {
+        self.fiat_shamir.challenge(message, commitment)
+    }
+
+    /// The Fiat-Shamir challenge of a batchable proof, for verifiers that check many proofs at once
+    pub fn batchable_challenge(
+        &self,
+        proof: &BatchableProof<S>,
+        message: Option<&[u8]>,
+    ) -> Result<Challenge, SigmaError> {
+        self.challenge(message, &proof.commitment)
+    }
+
+    /// The challenge of a batchable proof converted into the challenge space of the protocol with the expansion
+    /// of the transcript, as the verifier reads it
+    pub(crate) fn batchable_protocol_challenge(
+        &self,
+        proof: &BatchableProof<S>,
+        message: Option<&[u8]>,
+    ) -> Result<S::Challenge, SigmaError> {
+        S::challenge_from_bytes(
+            &self.batchable_challenge(proof, message)?,
+            self.fiat_shamir.expansion(),
+        )
+    }
+
+    /// Verify a batchable proof. Every rejection is reported as [`SigmaError::VerificationFailed`], see
+    /// [`NizkVerifier::batchable_verify_detailed`] for the cause
+    pub fn batchable_verify(
+        &self,
+        proof: &BatchableProof<S>,
+        message: Option<&[u8]>,
+    ) -> Result<(), SigmaError> {
+        self.batchable_verify_hashed(proof, self.fiat_shamir.hash_message(message).as_ref())
+    }
+
+    /// Verify a batchable proof as [`NizkVerifier::batchable_verify`] does, but report why it is rejected: a
+    /// challenge that cannot be converted, a response of the wrong shape or [`SigmaError::VerificationFailed`]
+    /// for a proof that does not verify. Meant for debugging, as the cause tells a malformed proof from an
+    /// invalid one
+    pub fn batchable_verify_detailed(
+        &self,
+        proof: &BatchableProof<S>,
+        message: Option<&[u8]>,
+    ) -> Result<(), SigmaError> {
+        self.traced(
+            "batchable",
+            self.check_batchable(proof, self.fiat_shamir.hash_message(message).as_ref()),
+        )
+    }
+
+    /// Verify a batchable proof bound to labeled associated data
+    pub fn batchable_verify_with_data(
+        &self,
+        proof: &BatchableProof<S>,
+        data: Option<&TranscriptData>,
+    ) -> Result<(), SigmaError> {
+        self.batchable_verify_hashed(proof, self.hash_data(data).as_ref())
+    }
+
+    /// Verify a batchable proof bound to a message hashed with a [`MessageHasher`]
+    pub fn batchable_verify_hashed(
+        &self,
+        proof: &BatchableProof<S>,
+        message: Option<&HashedMessage<D>>,
+    ) -> Result<(), SigmaError> {
+        self.traced("batchable", self.check_batchable(proof, message))
+            .map_err(rejected)
+    }
+
+    /// Records the outcome of a verification of proofs of the kind `proof`, which is passed through
+    fn check_batchable(
+        &self,
+        proof: &BatchableProof<S>,
+        message: Option<&HashedMessage<D>>,
+    ) -> Result<(), SigmaError> {
+        let expansion = self.fiat_shamir.expansion();
+        let challenge = self
+            .fiat_shamir
+            .hashed_challenge(message, &proof.commitment)?;
+        self.fiat_shamir.interactive_protocol.verifier(
+            &proof.commitment,
+            &S::challenge_from_bytes(&challenge, expansion)?,
+            &proof.response,
+        )
+    }
+
+    /// Verify a short proof. The recomputed challenge is compared to the challenge of the proof in constant time,
+    /// and every rejection is reported as [`SigmaError::VerificationFailed`], see
+    /// [`NizkVerifier::short_verify_detailed`] for the cause
+    pub fn short_verify(
+        &self,
+        proof: &ShortProof<S, L>,
+        message: Option<&[u8]>,
+    ) -> Result<(), SigmaError>
+    where
+        S: CommitmentRecoverable,
+    {
+        self.short_verify_hashed(proof, self.fiat_shamir.hash_message(message).as_ref())
+    }
+
+    /// Verify a short proof as [`NizkVerifier::short_verify`] does, but report why it is rejected: a challenge
+    /// that cannot be converted into the protocol's challenge space is reported as
+    /// [`SigmaError::ChallengeConversionFailure`], a commitment that cannot be recomputed as
+    /// [`SigmaError::CommitmentNotRecoverable`], and a wrong challenge as [`SigmaError::VerificationFailed`].
+    /// Meant for debugging, as the cause tells a malformed proof from an invalid one
+    pub fn short_verify_detailed(
+        &self,
+        proof: &ShortProof<S, L>,
+        message: Option<&[u8]>,
+    ) -> Result<(), SigmaError>
+    where
+        S: CommitmentRecoverable,
+    {
+        self.traced(
+            "short",
+            self.check_short(proof, self.fiat_shamir.hash_message(message).as_ref()),
+        )
+    }
+
+    /// Verify a short proof bound to labeled associated data
+    pub fn short_verify_with_data(
+        &self,
+        proof: &ShortProof<S, L>,
+        data: Option<&TranscriptData>,
+    ) -> Result<(), SigmaError>
+    where
+        S: CommitmentRecoverable,
+    {
+        self.short_verify_hashed(proof, self.hash_data(data).as_ref())
+    }
+
+    /// Verify a short proof bound to a message hashed with a [`MessageHasher`]
+    pub fn short_verify_hashed(
+        &self,
+        proof: &ShortProof<S, L>,
+        message: Option<&HashedMessage<D>>,
+    ) -> Result<(), SigmaError>
+    where
+        S: CommitmentRecoverable,
+    {
+        self.traced("short", self.check_short(proof, message))
+            .map_err(rejected)
+    }
+
+    fn check_short(
+        &self,
+        proof: &ShortProof<S, L>,
+        message: Option<&HashedMessage<D>>,
+    ) -> Result<(), SigmaError>
+    where
+        S: CommitmentRecoverable,
+    {
+        let expansion = self.fiat_shamir.expansion();
+        let commitment = self.fiat_shamir.interactive_protocol.simulate_commitment(
+            &S::challenge_from_bytes(&padded(&proof.challenge), expansion)?,
+            &proof.response,
+        )?;
+        let challenge = self.fiat_shamir.hashed_challenge(message, &commitment)?;
+
+        if bool::from(challenge[..L].ct_eq(&proof.challenge[..])) {
+            Ok(())
+        } else {
+            Err(SigmaError::VerificationFailed)
+        }
+    }
+
+    /// Verify many batchable proofs, each with its own message, at once with
+    /// [`BatchVerifiable::batch_verifier`]. The batch is rejected with [`SigmaError::VerificationFailed`] if any of
+    /// the proofs is invalid. Verifiers combining the proofs with random weights, as Schnorr does with
+    /// 128-bit [`BatchRandomizers`](crate::BatchRandomizers), accept an invalid batch with probability at most
+    /// `2^-bits`
+    pub fn batch_verify<R: Rng>(
+        &self,
+        proofs: &[(BatchableProof<S>, Option<&[u8]>)],
+        rng: &mut R,
+    ) -> Result<(), SigmaError>
+    where
+        S: BatchVerifiable,
+    {
+        self.traced("batch", self.check_batch(proofs, rng))
+            .map_err(rejected)
+    }
+
+    fn check_batch<R: Rng>(
+        &self,
+        proofs: &[(BatchableProof<S>, Option<&[u8]>)],
+        rng: &mut R,
+    ) -> Result<(), SigmaError>
+    where
+        S: BatchVerifiable,
+    {
+        let expansion = self.fiat_shamir.expansion();
+        let transcripts = proofs
+            .iter()
+            .map(|(proof, message)| {
+                let challenge = self.batchable_challenge(proof, *message)?;
+                Ok((
+                    &proof.commitment,
+                    S::challenge_from_bytes(&challenge, expansion)?,
+                    &proof.response,
+                ))
+            })
+            .collect::<Result<Vec<_>, SigmaError>>()?;
+
+        self.fiat_shamir
+            .interactive_protocol
+            .batch_verifier(&transcripts, rng)
+    }
+
+    /// Verify many batchable proofs, each with its own message, and report the result of every proof. The whole
+    /// batch is checked with [`NizkVerifier::batch_verify`], and a failing batch is split in halves until the
+    /// invalid proofs are found
+    pub fn verify_many<R: Rng>(
+        &self,
+        proofs: &[(BatchableProof<S>, Option<&[u8]>)],
+        rng: &mut R,
+    ) -> Vec<Result<(), SigmaError>>
+    where
+        S: BatchVerifiable,
+    {
+        localize_failures(proofs.len(), |range| self.batch_verify(&proofs[range], rng))
+    }
+
+    /// Verify many short proofs, each with its own message, at once. The commitments are recomputed together with
+    /// [`BatchVerifiable::batch_simulate_commitments`], then every challenge is recomputed and compared in constant
+    /// time. The batch is rejected with [`SigmaError::VerificationFailed`] if any of the proofs is invalid
+    pub fn batch_verify_short(
+        &self,
+        proofs: &[(ShortProof<S, L>, Option<&[u8]>)],
+    ) -> Result<(), SigmaError>
+    where
+        S: BatchVerifiable + CommitmentRecoverable,
+    {
+        self.traced("short batch", self.check_batch_short(proofs))
+            .map_err(rejected)
+    }
+
+    fn check_batch_short(
+        &self,
+        proofs: &[(ShortProof<S, L>, Option<&[u8]>)],
+    ) -> Result<(), SigmaError>
+    where
+        S: BatchVerifiable + CommitmentRecoverable,
+    {
+        let expansion = self.fiat_shamir.expansion();
+        let transcripts = proofs
+            .iter()
+            .map(|(proof, _)| {
+                let challenge = S::challenge_from_bytes(&padded(&proof.challenge), expansion)?;
+                Ok((challenge, &proof.response))
+            })
+            .collect::<Result<Vec<_>, SigmaError>>()?;
+        let protocol = &self.fiat_shamir.interactive_protocol;
+        let mut commitments = protocol.batch_simulate_commitments(&transcripts)?;
+        protocol.normalize_commitments(&mut commitments);
+
+        let mut valid = Choice::from(1);
+        for ((proof, message), commitment) in proofs.iter().zip(&commitments) {
+            let challenge = self.challenge(*message, commitment)?;
+            valid &= challenge[..L].ct_eq(&proof.challenge[..]);
+        }
+
+        if bool::from(valid) {
+            Ok(())
+        } else {
+            Err(SigmaError::VerificationFailed)
+        }
+    }
+
+    /// Convert a batchable proof into the short proof with the same challenge and response, for relays short on
+    /// bandwidth. The proof is verified first, and an invalid proof is rejected with
+    /// [`SigmaError::VerificationFailed`]
+    pub fn shorten(
+        &self,
+        proof: &BatchableProof<S>,
+        message: Option<&[u8]>,
+    ) -> Result<ShortProof<S, L>, SigmaError>
+    where
+        S: CommitmentRecoverable,
+        S::Response: Clone,
+    {
+        let expansion = self.fiat_shamir.expansion();
+        let challenge = self
+            .challenge(message, &proof.commitment)
+            .map_err(rejected)?;
+        S::challenge_from_bytes(&challenge, expansion)
+            .and_then(|converted| {
+                self.fiat_shamir.interactive_protocol.verifier(
+                    &proof.commitment,
+                    &converted,
+                    &proof.response,
+                )
+            })
+            .map_err(rejected)?;
+
+        Ok(ShortProof {
+            challenge: truncated(&challenge),
+            response: proof.response.clone(),
+        })
+    }
+
+    /// Convert a short proof into the batchable proof with the same response, recomputing the commitment with
+    /// [`CommitmentRecoverable::simulate_commitment`]. The proof is verified first, and an invalid proof is rejected with
+    /// [`SigmaError::VerificationFailed`]. Expanding a shortened proof gives back the encoding of the original proof
+    pub fn expand(
+        &self,
+        proof: &ShortProof<S, L>,
+        message: Option<&[u8]>,
+    ) -> Result<BatchableProof<S>, SigmaError>
+    where
+        S: CommitmentRecoverable,
+        S::Response: Clone,
+    {
+        let expansion = self.fiat_shamir.expansion();
+        let commitment = S::challenge_from_bytes(&padded(&proof.challenge), expansion)
+            .and_then(|challenge| {
+                self.fiat_shamir
+                    .interactive_protocol
+                    .simulate_commitment(&challenge, &proof.response)
+            })
+            .map_err(rejected)?;
+        let challenge = self.challenge(message, &commitment).map_err(rejected)?;
+
+        if bool::from(challenge[..L].ct_eq(&proof.challenge[..])) {
+            Ok(BatchableProof {
+                commitment,
+                response: proof.response.clone(),
+            })
+        } else {
+            Err(SigmaError::VerificationFailed)
+        }
+    }
+}
+
+impl<S: SigmaProtocol> NIZK<S, DynTranscript> {
+    /// Initialise the NIZK over a hash function chosen at runtime, see [`NizkProver::with_hash_function`]
+    pub fn with_hash_function(
+        instance: &S::Instance,
+        ctx: &[u8],
+        hash: HashFunction,
+    ) -> Result<Self, SigmaError> {
+        Ok(Self {
+            prover: NizkProver::with_hash_function(instance, ctx, hash)?,
+            verifier: NizkVerifier::with_hash_function(instance, ctx, hash)?,
+        })
+    }
+
+    /// Produce an enveloped batchable proof, see [`NizkProver::prove_enveloped`]
+    pub fn prove_enveloped<R: Rng>(
+        &self,
+        witness: &S::Witness,
+        message: Option<&[u8]>,
+        rng: &mut R,
+    ) -> Result<ProofEnvelope<BatchableProof<S>>, SigmaError> {
+        self.prover.prove_enveloped(witness, message, rng)
+    }
+
+    /// Verify an enveloped batchable proof, see [`NizkVerifier::verify_enveloped`]
+    pub fn verify_enveloped(
+        &self,
+        envelope: &ProofEnvelope<BatchableProof<S>>,
+        message: Option<&[u8]>,
+    ) -> Result<(), SigmaError> {
+        self.verifier.verify_enveloped(envelope, message)
+    }
+}
+
+impl<S: MultiRoundSigmaProtocol, D: Transcript, const L: usize> NIZK<S, D, L> {
+    /// Start building the NIZK for a given Sigma protocol instance, to choose its context or its [`FsMode`]
+    pub fn builder(instance: &S::Instance) -> NizkBuilder<S, D, L> {
+        NizkBuilder::new(instance)
+    }
+
+    /// initialise the NIZK for a given Sigma protocol. Panics if [`SigmaProtocol::validate_instance`] rejects the
+    /// instance, see [`NIZK::try_new`]
+    pub fn new(instance: &S::Instance, ctx: &[u8]) -> Self
+    where
+        D: SufficientOutput + Default,
+    {
+        Self {
+            prover: NizkProver::new(instance, ctx),
+            verifier: NizkVerifier::new(instance, ctx),
+        }
+    }
+
+    /// Initialise the NIZK over a transcript whose output length is only known at runtime, or for an instance
+    /// that may be invalid, see [`NizkProver::try_new`]
+    pub fn try_new(instance: &S::Instance, ctx: &[u8]) -> Result<Self, SigmaError>
+    where
+        D: Default,
+    {
+        Ok(Self {
+            prover: NizkProver::try_new(instance, ctx)?,
+            verifier: NizkVerifier::try_new(instance, ctx)?,
+        })
+    }
+
+    /// Initialise the NIZK for an instance shared with the caller. Protocols whose instances grow with the
+    /// statement, as [`Representation`](crate::protocols::Representation), hold the instance rather than copying
+    /// it, so that the prover, the verifier and every proof of the NIZK use the allocation of the caller. See
+    /// [`NizkProver::try_new`] for the errors
+    pub fn from_shared(instance: Arc<S::Instance>, ctx: &[u8]) -> Result<Self, SigmaError>
+    where
+        D: Default,
+    {
+        Ok(Self {
+            prover: NizkProver::from_shared(instance.clone(), ctx)?,
+            verifier: NizkVerifier::from_shared(instance, ctx)?,
+        })
+    }
+
+    /// Initialise the NIZK for an already built protocol, such as a [`SchnorrDLOG`](crate::protocols::SchnorrDLOG)
+    /// with precomputed tables, which the prover and the verifier share. See [`NizkProver::try_new`] for the errors
+    pub fn from_protocol(protocol: S, ctx: &[u8]) -> Result<Self, SigmaError>
+    where
+        S: Clone,
+        D: Default,
+    {
+        Ok(Self {
+            prover: NizkProver::from_protocol(protocol.clone(), ctx)?,
+            verifier: NizkVerifier::from_protocol(protocol, ctx)?,
+        })
+    }
+
+    /// Initialise the NIZK for the proofs of a session, see [`SessionContext`] and [`NizkProver::try_new`] for the
+    /// errors
+    pub fn with_session(
+        instance: &S::Instance,
+        session: &SessionContext,
+    ) -> Result<Self, SigmaError>
+    where
+        D: Default,
+    {
+        Ok(Self {
+            prover: NizkProver::with_session(instance, session)?,
+            verifier: NizkVerifier::with_session(instance, session)?,
+        })
+    }
+
+    /// Initialise the NIZK for an already built protocol and the proofs of a session, see [`NIZK::from_protocol`]
+    /// and [`NIZK::with_session`]
+    pub fn from_protocol_with_session(
+        protocol: S,
+        session: &SessionContext,
+    ) -> Result<Self, SigmaError>
+    where
+        S: Clone,
+        D: Default,
+    {
+        Ok(Self {
+            prover: NizkProver::from_protocol_with_session(protocol.clone(), session)?,
+            verifier: NizkVerifier::from_protocol_with_session(protocol, session)?,
+        })
+    }
+
+    /// Start hashing a message for the `_hashed` variants of proving and verifying
+    pub fn message_hasher(&self) -> MessageHasher<D> {
+        self.prover.message_hasher()
+    }
+
+    /// Produce a proof of a multi-round protocol, see [`NizkProver::multi_round_proof`]
+    pub fn multi_round_proof<R: Rng>(
+        &self,
+        witness: &S::Witness,
+        message: Option<&[u8]>,
+        rng: &mut R,
+    ) -> Result<MultiRoundProof<S>, SigmaError> {
+        self.prover.multi_round_proof(witness, message, rng)
+    }
+
+    /// Verify a multi-round proof, see [`NizkVerifier::multi_round_verify`]
+    pub fn multi_round_verify(
+        &self,
+        proof: &MultiRoundProof<S>,
+        message: Option<&[u8]>,
+    ) -> Result<(), SigmaError> {
+        self.verifier.multi_round_verify(proof, message)
+    }
+}
+
+impl<S: SigmaProtocol, D: Transcript, const L: usize> NIZK<S, D, L> {
+    /// The Fiat-Shamir challenge of a batchable proof, for verifiers that check many proofs at once
+    pub fn batchable_challenge(
+        &self,
+        proof: &BatchableProof<S>,
+        message: Option<&[u8]>,
+    ) -> Result<Challenge, SigmaError> {
+        self.verifier.batchable_challenge(proof, message)
+    }
+
+    /// See [`NizkVerifier::batchable_protocol_challenge`]
+    pub(crate) fn batchable_protocol_challenge(
+        &self,
+        proof: &BatchableProof<S>,
+        message: Option<&[u8]>,
+    ) -> Result<S::Challenge, SigmaError> {
+        self.verifier.batchable_protocol_challenge(proof, message)
+    }
+
+    /// Produce a batchable proof for the instance using the provided witness
+    pub fn batchable_proof<R: Rng>(
+        &self,
+        witness: &S::Witness,
+        message: Option<&[u8]>,
+        rng: &mut R,
+    ) -> Result<BatchableProof<S>, SigmaError> {
+        self.prover.batchable_proof(witness, message, rng)
+    }
+
+    /// Produce a batchable proof bound to labeled associated data, see [`NizkProver::batchable_proof_with_data`]
+    pub fn batchable_proof_with_data<R: Rng>(
+        &self,
+        witness: &S::Witness,
+        data: Option<&TranscriptData>,
+        rng: &mut R,
+    ) -> Result<BatchableProof<S>, SigmaError> {
+        self.prover.batchable_proof_with_data(witness, data, rng)
+    }
+
+    /// Produce a batchable proof bound to a hashed message, see [`NizkProver::batchable_proof_hashed`]
+    pub fn batchable_proof_hashed<R: Rng>(
+        &self,
+        witness: &S::Witness,
+        message: Option<&HashedMessage<D>>,
+        rng: &mut R,
+    ) -> Result<BatchableProof<S>, SigmaError> {
+        self.prover.batchable_proof_hashed(witness, message, rng)
+    }
+
+    /// Verify a batchable proof
+    pub fn batchable_verify(
+        &self,
+        proof: &BatchableProof<S>,
+        message: Option<&[u8]>,
+    ) -> Result<(), SigmaError> {
+        self.verifier.batchable_verify(proof, message)
+    }
+
+    /// Verify a batchable proof and report why it is rejected, see [`NizkVerifier::batchable_verify_detailed`]
+    pub fn batchable_verify_detailed(
+        &self,
+        proof: &BatchableProof<S>,
+        message: Option<&[u8]>,
+    ) -> Result<(), SigmaError> {
+        self.verifier.batchable_verify_detailed(proof, message)
+    }
+
+    /// Verify a batchable proof bound to labeled associated data, see [`NizkVerifier::batchable_verify_with_data`]
+    pub fn batchable_verify_with_data(
+        &self,
+        proof: &BatchableProof<S>,
+        data: Option<&TranscriptData>,
+    ) -> Result<(), SigmaError> {
+        self.verifier.batchable_verify_with_data(proof, data)
+    }
+
+    /// Verify a batchable proof bound to a hashed message, see [`NizkVerifier::batchable_verify_hashed`]
+    pub fn batchable_verify_hashed(
+        &self,
+        proof: &BatchableProof<S>,
+        message: Option<&HashedMessage<D>>,
+    ) -> Result<(), SigmaError> {
+        self.verifier.batchable_verify_hashed(proof, message)
+    }
+
+    /// The commitment and encoded prover state of a split proof, see [`NizkProver::commit_phase`]
+    pub fn commit_phase<R: Rng>(
+        &self,
+        witness: &S::Witness,
+        message: Option<&[u8]>,
+        rng: &mut R,
+    ) -> Result<(S::Commitment, ProverStateBytes), SigmaError>
+    where
+        S::ProverState: CanonicalSerialize,
+    {
+        self.prover.commit_phase(witness, message, rng)
+    }
+
+    /// The batchable proof completing a split proof, see [`NizkProver::response_phase`]
+    pub fn response_phase(
+        &self,
+        commitment: S::Commitment,
+        prover_state: ProverStateBytes,
+        message: Option<&[u8]>,
+    ) -> Result<BatchableProof<S>, SigmaError>
+    where
+        S::ProverState: CanonicalDeserialize,
+    {
+        self.prover
+            .response_phase(commitment, prover_state, message)
+    }
+
+    /// Produce a batchable proof for each pair of a witness and a message, see [`NizkProver::batchable_proofs`]
+    pub fn batchable_proofs<R: Rng>(
+        &self,
+        jobs: &[(S::Witness, Option<&[u8]>)],
+        rng: &mut R,
+    ) -> Result<Vec<BatchableProof<S>>, SigmaError> {
+        self.prover.batchable_proofs(jobs, rng)
+    }
+
+    /// Produce a short proof for the instance using the provided witness
+    pub fn short_proof<R: Rng>(
+        &self,
+        witness: &S::Witness,
+        message: Option<&[u8]>,
+        rng: &mut R,
+    ) -> Result<ShortProof<S, L>, SigmaError>
+    where
+        S: CommitmentRecoverable,
+    {
+        self.prover.short_proof(witness, message, rng)
+    }
+
+    /// Produce a short proof bound to labeled associated data, see [`NizkProver::short_proof_with_data`]
+    pub fn short_proof_with_data<R: Rng>(
+        &self,
+        witness: &S::Witness,
+        data: Option<&TranscriptData>,
+        rng: &mut R,
+    ) -> Result<ShortProof<S, L>, SigmaError>
+    where
+        S: CommitmentRecoverable,
+    {
+        self.prover.short_proof_with_data(witness, data, rng)
+    }
+
+    /// Produce a short proof bound to a hashed message, see [`NizkProver::short_proof_hashed`]
+    pub fn short_proof_hashed<R: Rng>(
+        &self,
+        witness: &S::Witness,
+        message: Option<&HashedMessage<D>>,
+        rng: &mut R,
+    ) -> Result<ShortProof<S, L>, SigmaError>
+    where
+        S: CommitmentRecoverable,
+    {
+        self.prover.short_proof_hashed(witness, message, rng)
+    }
+
+    /// Verify a short proof, see [`NizkVerifier::short_verify`]
+    pub fn short_verify(
+        &self,
+        proof: &ShortProof<S, L>,
+        message: Option<&[u8]>,
+    ) -> Result<(), SigmaError>
+    where
+        S: CommitmentRecoverable,
+    {
+        self.verifier.short_verify(proof, message)
+    }
+
+    /// Verify a short proof and report why it is rejected, see [`NizkVerifier::short_verify_detailed`]
+    pub fn short_verify_detailed(
+        &self,
+        proof: &ShortProof<S, L>,
+        message: Option<&[u8]>,
+    ) -> Result<(), SigmaError>
+    where
+        S: CommitmentRecoverable,
+    {
+        self.verifier.short_verify_detailed(proof, message)
+    }
+
+    /// Verify a short proof bound to labeled associated data, see [`NizkVerifier::short_verify_with_data`]
+    pub fn short_verify_with_data(
+        &self,
+        proof: &ShortProof<S, L>,
+        data: Option<&TranscriptData>,
+    ) -> Result<(), SigmaError>
+    where
+        S: CommitmentRecoverable,
+    {
+        self.verifier.short_verify_with_data(proof, data)
+    }
+
+    /// Verify a short proof bound to a hashed message, see [`NizkVerifier::short_verify_hashed`]
+    pub fn short_verify_hashed(
+        &self,
+        proof: &ShortProof<S, L>,
+        message: Option<&HashedMessage<D>>,
+    ) -> Result<(), SigmaError>
+    where
+        S: CommitmentRecoverable,
+    {
+        self.verifier.short_verify_hashed(proof, message)
+    }
+
+    /// Verify many batchable proofs at once, see [`NizkVerifier::batch_verify`]
+    pub fn batch_verify<R: Rng>(
+        &self,
+        proofs: &[(BatchableProof<S>, Option<&[u8]>)],
+        rng: &mut R,
+    ) -> Result<(), SigmaError>
+    where
+        S: BatchVerifiable,
+    {
+        self.verifier.batch_verify(proofs, rng)
+    }
+
+    /// Verify many batchable proofs and report the result of every proof, see [`NizkVerifier::verify_many`]
+    pub fn verify_many<R: Rng>(
+        &self,
+        proofs: &[(BatchableProof<S>, Option<&[u8]>)],
+        rng: &mut R,
+    ) -> Vec<Result<(), SigmaError>>
+    where
+        S: BatchVerifiable,
+    {
+        self.verifier.verify_many(proofs, rng)
+    }
+
+    /// Verify many short proofs at once, see [`NizkVerifier::batch_verify_short`]
+    pub fn batch_verify_short(
+        &self,
+        proofs: &[(ShortProof<S, L>, Option<&[u8]>)],
+    ) -> Result<(), SigmaError>
+    where
+        S: BatchVerifiable + CommitmentRecoverable,
+    {
+        self.verifier.batch_verify_short(proofs)
+    }
+
+    /// Convert a valid batchable proof into a short proof, see [`NizkVerifier::shorten`]
+    pub fn shorten(
+        &self,
+        proof: &BatchableProof<S>,
+        message: Option<&[u8]>,
+    ) -> Result<ShortProof<S, L>, SigmaError>
+    where
+        S: CommitmentRecoverable,
+        S::Response: Clone,
+    {
+        self.verifier.shorten(proof, message)
+    }
+
+    /// Convert a valid short proof into a batchable proof, see [`NizkVerifier::expand`]
+    pub fn expand(
+        &self,
+        proof: &ShortProof<S, L>,
+        message: Option<&[u8]>,
+    ) -> Result<BatchableProof<S>, SigmaError>
+    where
+        S: CommitmentRecoverable,
+        S::Response: Clone,
+    {
+        self.verifier.expand(proof, message)
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use ark_ec::ProjectiveCurve;
+    use ark_ff::{Field, One, PrimeField, UniformRand};
+    use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, SerializationError};
+    use digest::{
+        consts::{U20, U32},
+        Digest, FixedOutput, Output, Reset, Update,
+    };
+    use rand::{rngs::StdRng, thread_rng, Rng, SeedableRng};
+
+    use crate::{
+        protocols::{MultiDlog, MultiDlogInstance, SchnorrDLOG, SchnorrInstance},
+        Challenge, ChallengeExpansion, CommitmentRecoverable, HashFunction, NizkProver,
+        NizkVerifier, SigmaError, SigmaProtocol, SufficientOutput, CHALLENGE_LENGTH, LABEL_LENGTH,
+        NIZK,
+    };
+
+    use std::{
+        collections::HashSet,
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+    };
+
+    use super::{
+        encode_hex, envelope_version, localize_failures, BatchableProof, FsMode, HashedMessage,
+        MessageHasher, ProofEnvelope, SessionContext, ShortProof, TranscriptData,
+        DIGEST_SUFFIX_REVISION,
+    };
+
+    type G = ark_bls12_377::G1Projective;
+    type F = ark_bls12_377::Fr;
+
+    /// A digest always outputting bytes `0xFF`, whose challenges exceed the BLS12-377 scalar modulus as integers
+    #[derive(Clone, Default)]
+    pub(crate) struct AllOnesDigest;
+
+    impl Update for AllOnesDigest {
+        fn update(&mut self, _data: impl AsRef<[u8]>) {}
+    }
+
+    impl FixedOutput for AllOnesDigest {
+        type OutputSize = U32;
+
+        fn finalize_into(self, out: &mut Output<Self>) {
+            out.iter_mut().for_each(|byte| *byte = 0xFF);
+        }
+
+        fn finalize_into_reset(&mut self, out: &mut Output<Self>) {
+            self.clone().finalize_into(out);
+        }
+    }
+
+    impl Reset for AllOnesDigest {
+        fn reset(&mut self) {}
+    }
+
+    /// A digest with the 20-byte output of SHA-1, too short for a challenge
+    #[derive(Clone, Default)]
+    struct ShortDigest;
+
+    impl Update for ShortDigest {
+        fn update(&mut self, _data: impl AsRef<[u8]>) {}
+    }
+
+    impl FixedOutput for ShortDigest {
+        type OutputSize = U20;
+
+        fn finalize_into(self, out: &mut Output<Self>) {
+            out.iter_mut().for_each(|byte| *byte = 0);
+        }
+
+        fn finalize_into_reset(&mut self, out: &mut Output<Self>) {
+            self.clone().finalize_into(out);
+        }
+    }
+
+    impl Reset for ShortDigest {
+        fn reset(&mut self) {}
+    }
+
+    /// Generates a batched proof using the provided witness and instance and returns the verifier output
+    pub(crate) fn run_nizk_batched<D: SufficientOutput + Default, S: SigmaProtocol, R: Rng>(
+        instance: &S::Instance,
+        witness: &S::Witness,
+        rng: &mut R,
+    ) -> Result<(), SigmaError> {
+        run_nizk_batched_at::<D, S, R, CHALLENGE_LENGTH>(instance, witness, rng)
+    }
+
+    /// [`run_nizk_batched`] with challenges of `L` bytes
+    pub(crate) fn run_nizk_batched_at<
+        D: SufficientOutput + Default,
+        S: SigmaProtocol,
+        R: Rng,
+        const L: usize,
+    >(
+        instance: &S::Instance,
+        witness: &S::Witness,
+        rng: &mut R,
+    ) -> Result<(), SigmaError> {
+        let ctx = b"this is a test";
+        let message = b"this is a message";
+
+        let prover = NizkProver::<S, D, L>::new(instance, ctx);
+        let verifier = NizkVerifier::<S, D, L>::new(instance, ctx);
+
+        let proof = prover.batchable_proof(witness, Some(message), rng)?;
+
+        verifier.batchable_verify(&proof, Some(message))
+    }
+
+    /// Generates a batched proof using the provided witness and instance and returns the verifier output
+    pub(crate) fn run_nizk_short<
+        D: SufficientOutput + Default,
+        S: CommitmentRecoverable,
+        R: Rng,
+    >(
+        instance: &S::Instance,
+        witness: &S::Witness,
+        rng: &mut R,
+    ) -> Result<(), SigmaError> {
+        run_nizk_short_at::<D, S, R, CHALLENGE_LENGTH>(instance, witness, rng)
+    }
+
+    /// [`run_nizk_short`] with challenges of `L` bytes
+    pub(crate) fn run_nizk_short_at<
+        D: SufficientOutput + Default,
+        S: CommitmentRecoverable,
+        R: Rng,
+        const L: usize,
+    >(
+        instance: &S::Instance,
+        witness: &S::Witness,
+        rng: &mut R,
+    ) -> Result<(), SigmaError> {
+        let ctx = b"this is a test";
+        let message = b"this is a message";
+
+        let prover = NizkProver::<S, D, L>::new(instance, ctx);
+        let verifier = NizkVerifier::<S, D, L>::new(instance, ctx);
+
+        let proof = prover.short_proof(witness, Some(message), rng)?;
+
+        verifier.short_verify(&proof, Some(message))
+    }
+
+    /// A protocol running `S`, which counts the prover states it wipes with [`SigmaProtocol::zeroize_state`]
+    pub(crate) struct Tracked<S> {
+        protocol: S,
+        wiped: Arc<AtomicUsize>,
+    }
+
+    impl<S> Tracked<S> {
+        /// The number of prover states wiped so far
+        pub(crate) fn wiped(&self) -> usize {
+            self.wiped.load(Ordering::SeqCst)
+        }
+    }
+
+    impl<S: SigmaProtocol> SigmaProtocol for Tracked<S> {
+        type Instance = S::Instance;
+        type Commitment = S::Commitment;
+        type ProverState = S::ProverState;
+        type Witness = S::Witness;
+        type Response = S::Response;
+        type Challenge = S::Challenge;
+
+        const PROTOCOL_ID: &'static [u8] = S::PROTOCOL_ID;
+
+        fn label(&self) -> [u8; LABEL_LENGTH] {
+            self.protocol.label()
+        }
+
+        fn new(instance: &Self::Instance) -> Self {
+            Self {
+                protocol: S::new(instance),
+                wiped: Arc::new(AtomicUsize::new(0)),
+            }
+        }
+
+        fn challenge_from_bytes(
+            challenge: &Challenge,
+            expansion: ChallengeExpansion,
+        ) -> Result<Self::Challenge, SigmaError> {
+            S::challenge_from_bytes(challenge, expansion)
+        }
+
+        fn instance_bytes(&self) -> Vec<u8> {
+            self.protocol.instance_bytes()
+        }
+
+        fn witness_bytes(&self, witness: &Self::Witness) -> Vec<u8> {
+            self.protocol.witness_bytes(witness)
+        }
+
+        fn prover_commit<R: Rng>(
+            &self,
+            witness: &Self::Witness,
+            expansion: ChallengeExpansion,
+            rng: &mut R,
+        ) -> Result<(Self::Commitment, Self::ProverState), SigmaError> {
+            self.protocol.prover_commit(witness, expansion, rng)
+        }
+
+        fn prover_response(
+            &self,
+            prover_state: &Self::ProverState,
+            challenge: &Self::Challenge,
+        ) -> Result<Self::Response, SigmaError> {
+            self.protocol.prover_response(prover_state, challenge)
+        }
+
+        fn verifier(
+            &self,
+            commitment: &Self::Commitment,
+            challenge: &Self::Challenge,
+            response: &Self::Response,
+        ) -> Result<(), SigmaError> {
+            self.protocol.verifier(commitment, challenge, response)
+        }
+
+        fn simulate_response<R: Rng>(&self, rng: &mut R) -> Self::Response {
+            self.protocol.simulate_response(rng)
+        }
+
+        fn zeroize_state(&self, prover_state: &mut Self::ProverState) {
+            self.wiped.fetch_add(1, Ordering::SeqCst);
+            self.protocol.zeroize_state(prover_state)
+        }
+    }
+
+    impl<S: CommitmentRecoverable> CommitmentRecoverable for Tracked<S> {
+        fn simulate_commitment(
+            &self,
+            challenge: &Self::Challenge,
+            response: &Self::Response,
+        ) -> Result<Self::Commitment, SigmaError> {
+            self.protocol.simulate_commitment(challenge, response)
+        }
+    }
+
+    /// Checks that every proof of the NIZK wipes the prover states it commits with before returning, and that the
+    /// proofs still verify
+    pub(crate) fn check_prover_states_wiped<
+        D: SufficientOutput + Default,
+        S: CommitmentRecoverable,
+        R: Rng,
+    >(
+        instance: &S::Instance,
+        witness: &S::Witness,
+        rng: &mut R,
+    ) where
+        S::Witness: Clone,
+    {
+        let prover =
+            NizkProver::<Tracked<S>, D>::from_protocol(Tracked::new(instance), b"ctx").unwrap();
+        let verifier = NizkVerifier::<Tracked<S>, D>::new(instance, b"ctx");
+        let wiped = || prover.fiat_shamir.interactive_protocol.wiped();
+
+        let proof = prover.batchable_proof(witness, None, rng).unwrap();
+        assert_eq!(wiped(), 1);
+        let short_proof = prover.short_proof(witness, None, rng).unwrap();
+        assert_eq!(wiped(), 2);
+        let jobs = [
+            (witness.clone(), None),
+            (witness.clone(), Some(&b"message"[..])),
+        ];
+        let proofs = prover.batchable_proofs(&jobs, rng).unwrap();
+        assert_eq!(wiped(), 4);
+        let multi_round_proof = prover.multi_round_proof(witness, None, rng).unwrap();
+        assert_eq!(wiped(), 5);
+        // The default witness check runs the protocol once
+        assert!(prover
+            .fiat_shamir
+            .interactive_protocol
+            .is_valid_witness(witness));
+        assert_eq!(wiped(), 6);
+
+        assert!(verifier.batchable_verify(&proof, None).is_ok());
+        assert!(verifier.short_verify(&short_proof, None).is_ok());
+        assert!(verifier
+            .batchable_verify(&proofs[1], Some(b"message"))
+            .is_ok());
+        assert!(verifier
+            .multi_round_verify(&multi_round_proof, None)
+            .is_ok());
+    }
+
+    #[test]
+    fn test_proofs_accept_challenge_above_modulus() {
+        let rng = &mut thread_rng();
+        let (instance, witness) = SchnorrInstance::<G>::generate(rng);
+        let prover = NizkProver::<SchnorrDLOG<G>, AllOnesDigest>::new(&instance, b"ctx");
+        let verifier = NizkVerifier::<SchnorrDLOG<G>, AllOnesDigest>::new(&instance, b"ctx");
+
+        let batchable_proof = prover.batchable_proof(&witness, None, rng).unwrap();
+        let short_proof = prover.short_proof(&witness, Some(b"message"), rng).unwrap();
+
+        assert!(verifier.batchable_verify(&batchable_proof, None).is_ok());
+        assert!(verifier
+            .short_verify(&short_proof, Some(b"message"))
+            .is_ok());
+    }
+
+    #[test]
+    fn test_short_verify_reject_forged_challenge_above_modulus() {
+        let rng = &mut thread_rng();
+        let (instance, _) = SchnorrInstance::<G>::generate(rng);
+        let verifier = NizkVerifier::<SchnorrDLOG<G>, blake2::Blake2s>::new(&instance, b"ctx");
+
+        // The challenge is reduced like any other, so the forgery is merely invalid
+        let forged = ShortProof::<SchnorrDLOG<G>> {
+            challenge: [0xFF; 32],
+            response: F::rand(rng),
+        };
+
+        assert_eq!(
+            verifier.short_verify(&forged, None),
+            Err(SigmaError::VerificationFailed)
+        );
+    }
+
+    #[test]
+    fn test_challenge_conversion_never_fails() {
+        let rng = &mut thread_rng();
+        let generator = G::prime_subgroup_generator();
+        let witness = F::rand(rng);
+        let protocol = SchnorrDLOG::new(&SchnorrInstance::new(generator, generator));
+        let (_, state) = protocol
+            .prover_commit(&witness, ChallengeExpansion::default(), rng)
+            .unwrap();
+
+        for _ in 0..10_000 {
+            let challenge = rng.gen();
+            assert!(protocol.prover_response(&state, &challenge).is_ok());
+            assert!(protocol
+                .simulate_commitment(&challenge, &F::rand(rng))
+                .is_ok());
+        }
+    }
+
+    #[test]
+    fn test_proof_does_not_transfer_to_another_instance() {
+        let rng = &mut thread_rng();
+        let generator = G::prime_subgroup_generator();
+        let (instance, witness) = SchnorrInstance::<G>::generate(rng);
+        let prover = NizkProver::<SchnorrDLOG<G>, blake2::Blake2s>::new(&instance, b"ctx");
+        let verifier = NizkVerifier::<SchnorrDLOG<G>, blake2::Blake2s>::new(&instance, b"ctx");
+        let proof = prover.batchable_proof(&witness, None, rng).unwrap();
+
+        // A proof for an unrelated statement fails
+        let other = SchnorrInstance::new(generator, G::rand(rng));
+        let other_verifier = NizkVerifier::<SchnorrDLOG<G>, blake2::Blake2s>::new(&other, b"ctx");
+        assert_eq!(
+            other_verifier.batchable_verify(&proof, None),
+            Err(SigmaError::VerificationFailed)
+        );
+
+        // Without the instance in the transcript the challenge of a commitment `T` would be the same for every
+        // claim, so that `(T - s * G) / c` could be claimed after the challenge with any response `s`
+        let commitment = G::rand(rng);
+        let response = F::rand(rng);
+        let challenge = verifier.challenge(None, &commitment).unwrap();
+        let c: F = ChallengeExpansion::default().scalar(&challenge);
+        let forged_claim = (commitment - generator.mul(response.into_repr()))
+            .mul(c.inverse().unwrap().into_repr());
+        let forged = SchnorrInstance::new(generator, forged_claim);
+        assert!(SchnorrDLOG::new(&forged)
+            .verifier(&commitment, &c, &response)
+            .is_ok());
+
+        let forged_verifier = NizkVerifier::<SchnorrDLOG<G>, blake2::Blake2s>::new(&forged, b"ctx");
+        assert_eq!(
+            forged_verifier.batchable_verify(
+                &BatchableProof {
+                    commitment,
+                    response
+                },
+                None
+            ),
+            Err(SigmaError::VerificationFailed)
+        );
+    }
+
+    fn assert_send_sync<T: Send + Sync>() {}
+
+    #[test]
+    fn test_nizk_is_send_and_sync() {
+        assert_send_sync::<NIZK<SchnorrDLOG<G>, blake2::Blake2s>>();
+        assert_send_sync::<NizkProver<SchnorrDLOG<G>, blake2::Blake2s>>();
+        assert_send_sync::<NizkVerifier<SchnorrDLOG<G>, blake2::Blake2s>>();
+        assert_send_sync::<BatchableProof<SchnorrDLOG<G>>>();
+        assert_send_sync::<ShortProof<SchnorrDLOG<G>>>();
+    }
+
+    #[test]
+    fn test_challenge_known_answer() {
+        let generator = G::prime_subgroup_generator();
+        let instance = SchnorrInstance::new(generator, generator.double());
+        let verifier = NizkVerifier::<SchnorrDLOG<G>, blake2::Blake2s>::new(&instance, b"ctx");
+
+        // Computed when the hasher was still stored in the NIZK and reset after each use, and recomputed when the
+        // label became the last half of its digest
+        let expected_with_message =
+            "96387b25f40576ce87d149b3bacb013d604a887fbd9c6cd25bc908d6a56fff7e";
+        let expected_without_message =
+            "f55592087a4fac32eaaf2155fbf95f288812ca0e76ed04fd9deebb184bdb4717";
+        let hex = |challenge: Challenge| -> String {
+            challenge
+                .iter()
+                .map(|byte| format!("{:02x}", byte))
+                .collect()
+        };
+
+        // Repeated calls do not depend on each other
+        for _ in 0..2 {
+            let with_message = verifier.challenge(Some(b"message"), &generator).unwrap();
+            let without_message = verifier.challenge(None, &generator).unwrap();
+            assert_eq!(hex(with_message), expected_with_message);
+            assert_eq!(hex(without_message), expected_without_message);
+        }
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_fiat_shamir_modes() {
+        let rng = &mut thread_rng();
+        let generator = G::prime_subgroup_generator();
+        let instance = SchnorrInstance::new(generator, generator.double());
+        let witness = F::from(2u64);
+        let nizk = |mode: FsMode| {
+            NIZK::<SchnorrDLOG<G>, blake2::Blake2s>::builder(&instance)
+                .context(b"ctx")
+                .fiat_shamir(mode)
+                .build()
+                .unwrap()
+        };
+        let (strong, weak) = (nizk(FsMode::Strong), nizk(FsMode::LegacyWeak));
+        let hex = |nizk: &NIZK<SchnorrDLOG<G>, blake2::Blake2s>, message: Option<&[u8]>| {
+            encode_hex(&nizk.verifier.challenge(message, &generator).unwrap())
+        };
+
+        // The strong mode is the default and keeps the challenges of `test_challenge_known_answer`
+        let expected = [
+            (
+                hex(&strong, Some(b"message")),
+                "96387b25f40576ce87d149b3bacb013d604a887fbd9c6cd25bc908d6a56fff7e",
+            ),
+            (
+                hex(&strong, None),
+                "f55592087a4fac32eaaf2155fbf95f288812ca0e76ed04fd9deebb184bdb4717",
+            ),
+            (
+                hex(&weak, Some(b"message")),
+                "e6e8f30555d23a5bbdb778617d8d037650c6459fdd8b04c3c0b6e3ffb20e2fed",
+            ),
+            (
+                hex(&weak, None),
+                "8cb4c45726f229772655a26a9d790dc848a7db9d1c6b9dfef787423e503de0c2",
+            ),
+        ];
+        for (challenge, expected) in expected {
+            assert_eq!(challenge, expected);
+        }
+        let default = NIZK::<SchnorrDLOG<G>, _>::builder(&instance)
+            .context(b"ctx")
+            .build()
+            .unwrap();
+        assert_eq!(hex(&default, None), hex(&strong, None));
+
+        // Proofs of one mode never verify in the other
+        for (prover, verifier) in [(&strong, &weak), (&weak, &strong)] {
+            let batchable = prover.batchable_proof(&witness, None, rng).unwrap();
+            let short = prover.short_proof(&witness, None, rng).unwrap();
+            assert!(prover.batchable_verify(&batchable, None).is_ok());
+            assert!(prover.short_verify(&short, None).is_ok());
+            assert_eq!(
+                verifier.batchable_verify(&batchable, None),
+                Err(SigmaError::VerificationFailed)
+            );
+            assert_eq!(
+                verifier.short_verify(&short, None),
+                Err(SigmaError::VerificationFailed)
+            );
+        }
+
+        // The weak challenges do not depend on the instance
+        let other = SchnorrInstance::new(generator, generator.double().double());
+        let weak_other = NIZK::<SchnorrDLOG<G>, blake2::Blake2s>::builder(&other)
+            .context(b"ctx")
+            .fiat_shamir(FsMode::LegacyWeak)
+            .build()
+            .unwrap();
+        assert_eq!(hex(&weak_other, None), hex(&weak, None));
+    }
+
+    #[test]
+    fn test_blake2b_challenge_known_answer() {
+        let generator = G::prime_subgroup_generator();
+        let instance = SchnorrInstance::new(generator, generator.double());
+        let verifier = NizkVerifier::<SchnorrDLOG<G>, blake2::Blake2b>::new(&instance, b"ctx");
+        let dyn_verifier = NizkVerifier::<SchnorrDLOG<G>, _>::with_hash_function(
+            &instance,
+            b"ctx",
+            HashFunction::Blake2b,
+        )
+        .unwrap();
+
+        // Every hash absorbed, and the challenge, is the last half of a 64-byte digest
+        let expected = [
+            (
+                verifier.challenge(Some(b"message"), &generator),
+                "7b15f2b4a87d4b88672c05844e8cd120e08197e2ed1220f8082a8ab83be31ae3",
+            ),
+            (
+                verifier.challenge(None, &generator),
+                "56c58da667a64a2d8fab249af0e52bea468483c2f3dc805b6179d0c9024e19f9",
+            ),
+            (
+                dyn_verifier.challenge(Some(b"message"), &generator),
+                "b8715011e8d01951543d22da75b84543dbd5854e4994801cf6508f1f7549b2b9",
+            ),
+        ];
+        for (challenge, expected) in expected {
+            assert_eq!(encode_hex(&challenge.unwrap()), expected);
+        }
+    }
+
+    #[test]
+    fn test_label_known_answer() {
+        let generator = G::prime_subgroup_generator();
+        let schnorr = SchnorrDLOG::new(&SchnorrInstance::new(generator, generator.double()));
+        let id = SchnorrDLOG::<G>::PROTOCOL_ID;
+        let digest = blake2::Blake2b::digest(
+            &[
+                &(id.len() as u64).to_le_bytes()[..],
+                id,
+                &schnorr.instance_bytes(),
+            ]
+            .concat(),
+        );
+
+        // The label is the last half of the Blake2b digest of the length-prefixed identifier and the instance, as
+        // the challenges are, and so is the envelope version
+        assert_eq!(schnorr.label()[..], digest[64 - LABEL_LENGTH..]);
+        assert_ne!(schnorr.label()[..], digest[..LABEL_LENGTH]);
+        assert_eq!(
+            encode_hex(&schnorr.label()),
+            "b15406698eb7354fd0741bc71039241fbd4ecea1e22939740659df8cfc8cefa6"
+        );
+        assert_eq!(envelope_version(), 0xce);
+    }
+
+    #[test]
+    fn test_streamed_commitment_known_answer() {
+        let generator = G::prime_subgroup_generator();
+        let claims = vec![generator.double(), generator.double() + generator];
+        let instance = MultiDlogInstance::new(generator, claims.clone());
+        let verifier = NizkVerifier::<MultiDlog<G>, blake2::Blake2s>::new(&instance, b"ctx");
+        let dyn_verifier = NizkVerifier::<MultiDlog<G>, _>::with_hash_function(
+            &instance,
+            b"ctx",
+            HashFunction::SHA256,
+        )
+        .unwrap();
+        let hex = |challenge: Challenge| -> String {
+            challenge
+                .iter()
+                .map(|byte| format!("{:02x}", byte))
+                .collect()
+        };
+
+        // Computed when the commitment was serialized into a buffer before it was hashed, and recomputed when the
+        // label became the last half of its digest. The commitment is a vector, which is written as its length and
+        // then every point
+        let expected = [
+            (
+                verifier.challenge(Some(b"message"), &claims),
+                "d42515275baa97acac41eb5cc6f3275a31c0c52d4a6f04ebec211783a8a53022",
+            ),
+            (
+                verifier.challenge(None, &claims),
+                "be3e4992367195b10d1057eddaaa5cb1c9b9dfa57bd520173cfaeca73ed2be07",
+            ),
+            (
+                dyn_verifier.challenge(Some(b"message"), &claims),
+                "868e49a62dd359298eab6cf7dbb0d1c2b3896c9ee9f688c4e23a84606ced577f",
+            ),
+            (
+                dyn_verifier.challenge(None, &claims),
+                "e633b678db584eac984ab822f5d9309995408283db5352b3a82738190b2d7a98",
+            ),
+        ];
+        for (challenge, expected) in expected {
+            assert_eq!(hex(challenge.unwrap()), expected);
+        }
+    }
+
+    #[test]
+    fn test_streamed_message_matches_one_shot() {
+        let rng = &mut thread_rng();
+        let generator = G::prime_subgroup_generator();
+        let (instance, witness) = SchnorrInstance::<G>::generate(rng);
+        let prover = NizkProver::<SchnorrDLOG<G>, blake2::Blake2s>::new(&instance, b"ctx");
+        let verifier = NizkVerifier::<SchnorrDLOG<G>, blake2::Blake2s>::new(&instance, b"ctx");
+        let message: Vec<u8> = (0..10_000).map(|_| rng.gen()).collect();
+
+        let mut hasher = MessageHasher::new();
+        hasher.update(&message[..1]);
+        std::io::copy(&mut &message[1..4_097], &mut hasher).unwrap();
+        message[4_097..]
+            .chunks(1_000)
+            .for_each(|chunk| hasher.update(chunk));
+        let hashed = hasher.finalize();
+
+        assert_eq!(
+            verifier
+                .fiat_shamir
+                .hashed_challenge(Some(&hashed), &generator)
+                .unwrap(),
+            verifier.challenge(Some(&message), &generator).unwrap()
+        );
+
+        // Proofs bound to the streamed message verify against the whole message, and the other way around
+        let batchable = prover.batchable_proof_hashed(&witness, Some(&hashed), rng);
+        let short = prover.short_proof(&witness, Some(&message), rng);
+        assert!(verifier
+            .batchable_verify(&batchable.unwrap(), Some(&message))
+            .is_ok());
+        assert!(verifier
+            .short_verify_hashed(&short.unwrap(), Some(&hashed))
+            .is_ok());
+
+        let other = HashedMessage::new(&message[1..]);
+        let batchable = prover.batchable_proof(&witness, Some(&message), rng);
+        assert_eq!(
+            verifier.batchable_verify_hashed(&batchable.unwrap(), Some(&other)),
+            Err(SigmaError::VerificationFailed)
+        );
+    }
+
+    #[test]
+    fn test_transcript_data_fields_are_framed() {
+        let rng = &mut thread_rng();
+        let generator = G::prime_subgroup_generator();
+        let (instance, witness) = SchnorrInstance::<G>::generate(rng);
+        let nizk = NIZK::<SchnorrDLOG<G>, blake2::Blake2s>::new(&instance, b"ctx");
+
+        // The same concatenated bytes, split differently between the fields
+        let first = TranscriptData::new()
+            .add_label("sender", b"ab")
+            .add_label("recipient", b"c");
+        let second = TranscriptData::new()
+            .add_label("sender", b"a")
+            .add_label("recipient", b"bc");
+        let challenge = |data: &TranscriptData| {
+            nizk.verifier
+                .fiat_shamir
+                .hashed_challenge(Some(&data.hashed()), &generator)
+                .unwrap()
+        };
+        assert_ne!(challenge(&first), challenge(&second));
+
+        let batchable = nizk.batchable_proof_with_data(&witness, Some(&first), rng);
+        let short = nizk.short_proof_with_data(&witness, Some(&first), rng);
+        let (batchable, short) = (batchable.unwrap(), short.unwrap());
+        assert!(nizk
+            .batchable_verify_with_data(&batchable, Some(&first))
+            .is_ok());
+        assert!(nizk.short_verify_with_data(&short, Some(&first)).is_ok());
+        assert_eq!(
+            nizk.batchable_verify_with_data(&batchable, Some(&second)),
+            Err(SigmaError::VerificationFailed)
+        );
+        assert_eq!(
+            nizk.short_verify_with_data(&short, None),
+            Err(SigmaError::VerificationFailed)
+        );
+    }
+
+    #[test]
+    fn test_transcript_data_is_not_a_raw_message() {
+        let rng = &mut thread_rng();
+        let (instance, witness) = SchnorrInstance::<G>::generate(rng);
+        let nizk = NIZK::<SchnorrDLOG<G>, blake2::Blake2s>::new(&instance, b"ctx");
+        let data = TranscriptData::new().add_label("sender", b"alice");
+        let encoding = data.encoding.clone();
+
+        // A proof bound to the fields is not bound to their encoding as a raw message, nor the other way around
+        let bound_to_data = nizk.batchable_proof_with_data(&witness, Some(&data), rng);
+        let bound_to_bytes = nizk.batchable_proof(&witness, Some(&encoding), rng);
+        let (bound_to_data, bound_to_bytes) = (bound_to_data.unwrap(), bound_to_bytes.unwrap());
+        assert!(nizk
+            .batchable_verify_with_data(&bound_to_data, Some(&data))
+            .is_ok());
+        assert!(nizk
+            .batchable_verify(&bound_to_bytes, Some(&encoding))
+            .is_ok());
+        assert_eq!(
+            nizk.batchable_verify(&bound_to_data, Some(&encoding)),
+            Err(SigmaError::VerificationFailed)
+        );
+        assert_eq!(
+            nizk.batchable_verify_with_data(&bound_to_bytes, Some(&data)),
+            Err(SigmaError::VerificationFailed)
+        );
+
+        let short = nizk
+            .short_proof_with_data(&witness, Some(&data), rng)
+            .unwrap();
+        assert_eq!(
+            nizk.short_verify(&short, Some(&encoding)),
+            Err(SigmaError::VerificationFailed)
+        );
+        assert_eq!(
+            nizk.short_verify_hashed(&short, Some(&HashedMessage::new(&encoding))),
+            Err(SigmaError::VerificationFailed)
+        );
+        assert!(nizk
+            .short_verify_hashed(&short, Some(&data.hashed()))
+            .is_ok());
+    }
+
+    #[test]
+    fn test_session_binding() {
+        let rng = &mut thread_rng();
+        let generator = G::prime_subgroup_generator();
+        let (instance, witness) = SchnorrInstance::<G>::generate(rng);
+        let session =
+            |nonce: &[u8]| SessionContext::new(1, b"app", b"alice", nonce, Some(1700000000));
+        let nizk = |session: &SessionContext| {
+            NIZK::<SchnorrDLOG<G>, blake2::Blake2s>::with_session(&instance, session).unwrap()
+        };
+        let (a, b) = (nizk(&session(b"nonce A")), nizk(&session(b"nonce B")));
+
+        // A proof made in session A only verifies in session A
+        let batchable = a.batchable_proof(&witness, Some(b"message"), rng).unwrap();
+        let short = a.short_proof(&witness, Some(b"message"), rng).unwrap();
+        assert!(a.batchable_verify(&batchable, Some(b"message")).is_ok());
+        assert!(a.short_verify(&short, Some(b"message")).is_ok());
+        assert_eq!(
+            b.batchable_verify(&batchable, Some(b"message")),
+            Err(SigmaError::VerificationFailed)
+        );
+        assert_eq!(
+            b.short_verify(&short, Some(b"message")),
+            Err(SigmaError::VerificationFailed)
+        );
+
+        // Every field changes the challenge, and so does dropping the timestamp
+        let challenge = |session: &SessionContext| {
+            nizk(session)
+                .verifier
+                .fiat_shamir
+                .challenge(Some(b"message"), &generator)
+                .unwrap()
+        };
+        let sessions = [
+            session(b"nonce A"),
+            session(b"nonce B"),
+            SessionContext::new(2, b"app", b"alice", b"nonce A", Some(1700000000)),
+            SessionContext::new(1, b"other app", b"alice", b"nonce A", Some(1700000000)),
+            SessionContext::new(1, b"app", b"bob", b"nonce A", Some(1700000000)),
+            SessionContext::new(1, b"app", b"alice", b"nonce A", Some(1700000001)),
+            SessionContext::new(1, b"app", b"alice", b"nonce A", None),
+            // The same bytes split differently between the identifier and the identity
+            SessionContext::new(1, b"appa", b"lice", b"nonce A", Some(1700000000)),
+            SessionContext::from_raw_context(b"nonce A"),
+        ];
+        let challenges: HashSet<_> = sessions.iter().map(challenge).collect();
+        assert_eq!(challenges.len(), sessions.len());
+
+        // A raw context is the context of the constructors taking bytes
+        let raw = nizk(&SessionContext::from_raw_context(b"ctx"));
+        let bytes = NIZK::<SchnorrDLOG<G>, blake2::Blake2s>::new(&instance, b"ctx");
+        let proof = bytes.batchable_proof(&witness, None, rng).unwrap();
+        assert!(raw.batchable_verify(&proof, None).is_ok());
+        assert_eq!(
+            raw.verifier.fiat_shamir.challenge(None, &generator),
+            bytes.verifier.fiat_shamir.challenge(None, &generator)
+        );
+    }
+
+    #[test]
+    fn test_nizk_under_registry_hashes() {
+        let rng = &mut thread_rng();
+        let (instance, witness) = SchnorrInstance::<G>::generate(rng);
+        let hashes = [
+            HashFunction::Blake2b,
+            HashFunction::Blake2s,
+            HashFunction::SHA256,
+            HashFunction::SHA3_256,
+            HashFunction::SHA3_512,
+            HashFunction::SHAKE256,
+        ];
+        let nizks: Vec<_> = hashes
+            .iter()
+            .map(|hash| {
+                NIZK::<SchnorrDLOG<G>, _>::with_hash_function(&instance, b"ctx", *hash).unwrap()
+            })
+            .collect();
+
+        for (i, nizk) in nizks.iter().enumerate() {
+            let proof = nizk
+                .batchable_proof(&witness, Some(b"message"), rng)
+                .unwrap();
+            let mut hasher = nizk.message_hasher();
+            hasher.update(b"mess");
+            hasher.update(b"age");
+            let short_proof = nizk
+                .short_proof_hashed(&witness, Some(&hasher.finalize()), rng)
+                .unwrap();
+
+            assert!(nizk.batchable_verify(&proof, Some(b"message")).is_ok());
+            assert!(nizk.short_verify(&short_proof, Some(b"message")).is_ok());
+            for other in nizks.iter().skip(i + 1) {
+                assert_eq!(
+                    other.batchable_verify(&proof, Some(b"message")),
+                    Err(SigmaError::VerificationFailed)
+                );
+            }
+        }
+
+        // The name of the hash separates a runtime choice from the same hash chosen at compile time
+        let proof = nizks[1].batchable_proof(&witness, None, rng).unwrap();
+        let static_nizk = NIZK::<SchnorrDLOG<G>, blake2::Blake2s>::new(&instance, b"ctx");
+        assert_eq!(
+            static_nizk.batchable_verify(&proof, None),
+            Err(SigmaError::VerificationFailed)
+        );
+    }
+
+    #[test]
+    fn test_proof_envelope_round_trip() {
+        let rng = &mut thread_rng();
+        let (instance, witness) = SchnorrInstance::<G>::generate(rng);
+        let nizk =
+            NIZK::<SchnorrDLOG<G>, _>::with_hash_function(&instance, b"ctx", HashFunction::Blake2s)
+                .unwrap();
+
+        let envelope = nizk
+            .prove_enveloped(&witness, Some(b"message"), rng)
+            .unwrap();
+        let mut bytes = Vec::new();
+        envelope.serialize(&mut bytes).unwrap();
+        assert_eq!(bytes.len(), envelope.serialized_size());
+
+        let decoded =
+            ProofEnvelope::<BatchableProof<SchnorrDLOG<G>>>::deserialize(&bytes[..]).unwrap();
+        assert_eq!(decoded.hash_id(), HashFunction::Blake2s.id());
+        assert_eq!(decoded.label(), &SchnorrDLOG::<G>::new(&instance).label());
+        assert!(nizk.verify_enveloped(&decoded, Some(b"message")).is_ok());
+        assert_eq!(
+            nizk.verify_enveloped(&decoded, None),
+            Err(SigmaError::VerificationFailed)
+        );
+    }
+
+    #[test]
+    fn test_proof_envelope_rejects_wrong_header() {
+        let rng = &mut thread_rng();
+        let (instance, witness) = SchnorrInstance::<G>::generate(rng);
+        let nizk =
+            |hash| NIZK::<SchnorrDLOG<G>, _>::with_hash_function(&instance, b"ctx", hash).unwrap();
+        let envelope = nizk(HashFunction::Blake2s)
+            .prove_enveloped(&witness, None, rng)
+            .unwrap();
+        let mut bytes = Vec::new();
+        envelope.serialize(&mut bytes).unwrap();
+
+        assert_eq!(
+            nizk(HashFunction::SHA256).verify_enveloped(&envelope, None),
+            Err(SigmaError::EnvelopeMismatch)
+        );
+
+        // Rewriting the hash identifier of the encoding is caught before verification
+        bytes[1] = HashFunction::SHA256.id();
+        let relabeled =
+            ProofEnvelope::<BatchableProof<SchnorrDLOG<G>>>::deserialize(&bytes[..]).unwrap();
+        assert_eq!(
+            nizk(HashFunction::Blake2s).verify_enveloped(&relabeled, None),
+            Err(SigmaError::EnvelopeMismatch)
+        );
+        assert_eq!(
+            nizk(HashFunction::SHA256).verify_enveloped(&relabeled, None),
+            Err(SigmaError::VerificationFailed)
+        );
+
+        // The version bytes of the envelopes made when challenges were the first half of a 64-byte digest, and
+        // when labels and the version byte still were
+        let legacy_versions = [
+            blake2::Blake2b::digest(crate::DOMSEP)[0],
+            blake2::Blake2b::digest(&[crate::DOMSEP, DIGEST_SUFFIX_REVISION].concat())[0],
+        ];
+        for version in legacy_versions {
+            bytes[0] = version;
+            let legacy =
+                ProofEnvelope::<BatchableProof<SchnorrDLOG<G>>>::deserialize(&bytes[..]).unwrap();
+            assert_eq!(
+                nizk(HashFunction::SHA256).verify_enveloped(&legacy, None),
+                Err(SigmaError::EnvelopeMismatch)
+            );
+        }
+    }
+
+    #[test]
+    fn test_short_digest_is_rejected() {
+        let generator = G::prime_subgroup_generator();
+        let instance = SchnorrInstance::new(generator, generator.double());
+
+        let result = NIZK::<SchnorrDLOG<G>, ShortDigest>::try_new(&instance, b"ctx");
+        assert!(matches!(
+            result.err(),
+            Some(SigmaError::DigestTooShort { got: 20, need: 32 })
+        ));
+        assert!(NIZK::<SchnorrDLOG<G>, blake2::Blake2s>::try_new(&instance, b"ctx").is_ok());
+    }
+
+    #[test]
+    fn test_short_proof_round_trip() {
+        let rng = &mut thread_rng();
+        let (instance, witness) = SchnorrInstance::<G>::generate(rng);
+        let nizk = NIZK::<SchnorrDLOG<G>, blake2::Blake2s>::new(&instance, b"ctx");
+        let proof = nizk.short_proof(&witness, None, rng).unwrap();
+
+        let mut bytes = Vec::new();
+        proof.serialize(&mut bytes).unwrap();
+        assert_eq!(bytes.len(), proof.serialized_size());
+
+        let decoded = ShortProof::<SchnorrDLOG<G>>::deserialize(&bytes[..]).unwrap();
+        assert_eq!(decoded, proof);
+        assert!(nizk.short_verify(&decoded, None).is_ok());
+
+        let batchable = nizk.batchable_proof(&witness, None, rng).unwrap();
+        assert_eq!(batchable.clone(), batchable);
+        assert_eq!(proof.clone(), proof);
+    }
+
+    #[test]
+    fn test_short_proof_truncated_input() {
+        let rng = &mut thread_rng();
+        let (instance, witness) = SchnorrInstance::<G>::generate(rng);
+        let nizk = NIZK::<SchnorrDLOG<G>, blake2::Blake2s>::new(&instance, b"ctx");
+        let proof = nizk.short_proof(&witness, None, rng).unwrap();
+
+        let mut bytes = Vec::new();
+        proof.serialize(&mut bytes).unwrap();
+
+        // Cut in the middle of the challenge, then in the middle of the response
+        for length in [16, bytes.len() - 1] {
+            assert!(matches!(
+                ShortProof::<SchnorrDLOG<G>>::deserialize(&bytes[..length]),
+                Err(SerializationError::IoError(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_compressed_and_uncompressed_encodings() {
+        let rng = &mut thread_rng();
+        let (instance, witness) = SchnorrInstance::<G>::generate(rng);
+        let nizk = NIZK::<SchnorrDLOG<G>, blake2::Blake2s>::new(&instance, b"ctx");
+        let proof = nizk.batchable_proof(&witness, None, rng).unwrap();
+        let short_proof = nizk.short_proof(&witness, None, rng).unwrap();
+
+        // A compressed G1 point and a scalar
+        assert_eq!(proof.size_in_bytes(true), 48 + 32);
+        assert_eq!(proof.to_bytes_compressed().len(), 48 + 32);
+        assert!(proof.size_in_bytes(false) > proof.size_in_bytes(true));
+        assert_eq!(short_proof.size_in_bytes(true), 32 + 32);
+
+        let compressed = proof.to_bytes_compressed();
+        let uncompressed = proof.to_bytes_uncompressed();
+        assert_eq!(uncompressed.len(), proof.size_in_bytes(false));
+        for decoded in [
+            BatchableProof::from_bytes_compressed(&compressed),
+            BatchableProof::from_bytes_uncompressed(&uncompressed),
+        ] {
+            let decoded = decoded.unwrap();
+            assert_eq!(decoded, proof);
+            assert!(nizk.batchable_verify(&decoded, None).is_ok());
+        }
+
+        for decoded in [
+            ShortProof::from_bytes_compressed(&short_proof.to_bytes_compressed()),
+            ShortProof::from_bytes_uncompressed(&short_proof.to_bytes_uncompressed()),
+        ] {
+            assert!(nizk.short_verify(&decoded.unwrap(), None).is_ok());
+        }
+
+        let mut trailing = compressed;
+        trailing.push(0);
+        assert_eq!(
+            BatchableProof::<SchnorrDLOG<G>>::from_bytes_compressed(&trailing),
+            Err(SigmaError::SerializationError(
+                SerializationError::InvalidData
+            ))
+        );
+    }
+
+    /// A broken rng whose output is always zero
+    struct StuckRng;
+
+    impl rand::RngCore for StuckRng {
+        fn next_u32(&mut self) -> u32 {
+            0
+        }
+
+        fn next_u64(&mut self) -> u64 {
+            0
+        }
+
+        fn fill_bytes(&mut self, dest: &mut [u8]) {
+            dest.fill(0);
+        }
+
+        fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+            dest.fill(0);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_hedged_nonces_with_stuck_rng() {
+        let (instance, witness) = SchnorrInstance::<G>::generate(&mut thread_rng());
+        let nizk = NIZK::<SchnorrDLOG<G>, blake2::Blake2s>::new(&instance, b"ctx");
+
+        // The interactive prover alone repeats its nonce
+        let protocol = SchnorrDLOG::<G>::new(&instance);
+        let expansion = ChallengeExpansion::default();
+        let (first, _) = protocol
+            .prover_commit(&witness, expansion, &mut StuckRng)
+            .unwrap();
+        let (second, _) = protocol
+            .prover_commit(&witness, expansion, &mut StuckRng)
+            .unwrap();
+        assert_eq!(first, second);
+
+        let proofs: Vec<_> = [&b"first"[..], b"second", b"first"]
+            .iter()
+            .map(|message| {
+                nizk.batchable_proof(&witness, Some(message), &mut StuckRng)
+                    .unwrap()
+            })
+            .collect();
+
+        assert!(nizk.batchable_verify(&proofs[0], Some(b"first")).is_ok());
+        assert!(nizk.batchable_verify(&proofs[1], Some(b"second")).is_ok());
+        assert_ne!(proofs[0].commitment, proofs[1].commitment);
+        assert_ne!(proofs[0].commitment, first);
+        // With the same inputs and a stuck rng the derivation is deterministic, which repeats the whole proof
+        // rather than the nonce alone
+        assert_eq!(proofs[0], proofs[2]);
+
+        let unbound = nizk.batchable_proof(&witness, None, &mut StuckRng).unwrap();
+        assert_ne!(unbound.commitment, proofs[0].commitment);
+    }
+
+    #[test]
+    fn test_shorten_and_expand() {
+        let rng = &mut thread_rng();
+        let (instance, witness) = SchnorrInstance::<G>::generate(rng);
+        let nizk = NIZK::<SchnorrDLOG<G>, blake2::Blake2s>::new(&instance, b"ctx");
+        let proof = nizk
+            .batchable_proof(&witness, Some(b"message"), rng)
+            .unwrap();
+
+        let short_proof = nizk.shorten(&proof, Some(b"message")).unwrap();
+        assert!(nizk.short_verify(&short_proof, Some(b"message")).is_ok());
+        let expanded = nizk.expand(&short_proof, Some(b"message")).unwrap();
+        assert_eq!(expanded.to_bytes_compressed(), proof.to_bytes_compressed());
+
+        let short_proof = nizk.short_proof(&witness, None, rng).unwrap();
+        let expanded = nizk.expand(&short_proof, None).unwrap();
+        assert!(nizk.batchable_verify(&expanded, None).is_ok());
+        assert_eq!(nizk.shorten(&expanded, None).unwrap(), short_proof);
+
+        // Invalid proofs, or proofs bound to another message, are not converted
+        let forged = BatchableProof::<SchnorrDLOG<G>> {
+            commitment: G::rand(rng),
+            response: F::rand(rng),
+        };
+        assert_eq!(
+            nizk.shorten(&forged, None),
+            Err(SigmaError::VerificationFailed)
+        );
+        assert_eq!(
+            nizk.shorten(&proof, None),
+            Err(SigmaError::VerificationFailed)
+        );
+        assert_eq!(
+            nizk.expand(&short_proof, Some(b"message")),
+            Err(SigmaError::VerificationFailed)
+        );
+    }
+
+    #[test]
+    fn test_challenge_does_not_depend_on_projective_coordinates() {
+        let rng = &mut thread_rng();
+        let (instance, witness) = SchnorrInstance::<G>::generate(rng);
+        let nizk = NIZK::<SchnorrDLOG<G>, blake2::Blake2s>::new(&instance, b"ctx");
+        let proof = nizk.batchable_proof(&witness, None, rng).unwrap();
+
+        // The same point with Z scaled by a random factor
+        let commitment = proof.commitment;
+        let factor = ark_bls12_377::Fq::rand(rng);
+        let rescaled = G::new(
+            commitment.x * factor.square(),
+            commitment.y * factor.square() * factor,
+            commitment.z * factor,
+        );
+        assert_eq!(rescaled, commitment);
+        assert_ne!(rescaled.z, commitment.z);
+        let rescaled_proof = BatchableProof::<SchnorrDLOG<G>> {
+            commitment: rescaled,
+            response: proof.response,
+        };
+
+        assert_eq!(
+            nizk.batchable_challenge(&rescaled_proof, None),
+            nizk.batchable_challenge(&proof, None)
+        );
+        assert!(nizk.batchable_verify(&rescaled_proof, None).is_ok());
+        assert_eq!(
+            rescaled_proof.to_bytes_compressed(),
+            proof.to_bytes_compressed()
+        );
+    }
+
+    #[test]
+    fn test_batchable_proofs_match_single_proofs() {
+        let rng = &mut thread_rng();
+        let (instance, witness) = SchnorrInstance::<G>::generate(rng);
+        let nizk = NIZK::<SchnorrDLOG<G>, blake2::Blake2s>::new(&instance, b"ctx");
+        // A wrong witness gives a proof that does not verify, but the same one as the single-proof API
+        let jobs = [
+            (witness, Some(&b"first"[..])),
+            (witness, None),
+            (F::rand(rng), Some(b"third")),
+            (witness, Some(b"")),
+        ];
+
+        let proofs = nizk
+            .batchable_proofs(&jobs, &mut StdRng::seed_from_u64(3))
+            .unwrap();
+        let single_rng = &mut StdRng::seed_from_u64(3);
+        let single: Vec<_> = jobs
+            .iter()
+            .map(|(witness, message)| nizk.batchable_proof(witness, *message, single_rng).unwrap())
+            .collect();
+
+        assert_eq!(proofs, single);
+        let encoding = |proofs: &[BatchableProof<_>]| -> Vec<_> {
+            proofs
+                .iter()
+                .map(|proof| proof.to_bytes_compressed())
+                .collect()
+        };
+        assert_eq!(encoding(&proofs), encoding(&single));
+        // Normalized together, so that encoding them needs no further inversion
+        assert!(proofs.iter().all(|proof| proof.commitment.z.is_one()));
+        let batch: Vec<_> = proofs
+            .into_iter()
+            .zip(jobs)
+            .map(|(proof, (_, message))| (proof, message))
+            .collect();
+        let results = nizk.verify_many(&batch, rng);
+        assert_eq!(results.iter().filter(|result| result.is_ok()).count(), 3);
+        assert!(results[2].is_err());
+        assert!(nizk.batchable_proofs(&[], rng).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_rejections_are_uniform() {
+        let rng = &mut thread_rng();
+        let base = G::rand(rng);
+        let witness: Vec<F> = (0..3).map(|_| F::rand(rng)).collect();
+        let instance = MultiDlogInstance::new(
+            base,
+            witness.iter().map(|x| base.mul(x.into_repr())).collect(),
+        );
+        let truncated_instance = MultiDlogInstance::new(
+            base,
+            witness[1..]
+                .iter()
+                .map(|x| base.mul(x.into_repr()))
+                .collect(),
+        );
+        let nizk = NIZK::<MultiDlog<G>, blake2::Blake2s>::new(&instance, b"ctx");
+        let truncated_nizk =
+            NIZK::<MultiDlog<G>, blake2::Blake2s>::new(&truncated_instance, b"ctx");
+
+        let proof = nizk.batchable_proof(&witness, None, rng).unwrap();
+        let short_proof = nizk.short_proof(&witness, None, rng).unwrap();
+        let mut forged = short_proof.clone();
+        forged.challenge[0] ^= 1;
+
+        // A proof with a wrong challenge and a proof whose responses do not match the instance are rejected alike
+        assert_eq!(
+            nizk.short_verify(&forged, None),
+            Err(SigmaError::VerificationFailed)
+        );
+        assert_eq!(
+            truncated_nizk.short_verify(&short_proof, None),
+            Err(SigmaError::VerificationFailed)
+        );
+        assert_eq!(
+            nizk.batchable_verify(&proof, Some(b"message")),
+            Err(SigmaError::VerificationFailed)
+        );
+        assert_eq!(
+            truncated_nizk.batchable_verify(&proof, None),
+            Err(SigmaError::VerificationFailed)
+        );
+
+        // The detailed verifiers tell them apart
+        assert_eq!(
+            nizk.short_verify_detailed(&forged, None),
+            Err(SigmaError::VerificationFailed)
+        );
+        assert_eq!(
+            truncated_nizk.short_verify_detailed(&short_proof, None),
+            Err(SigmaError::LengthMismatch)
+        );
+        assert_eq!(
+            truncated_nizk.batchable_verify_detailed(&proof, None),
+            Err(SigmaError::LengthMismatch)
+        );
+        assert!(nizk.short_verify_detailed(&short_proof, None).is_ok());
+        assert!(nizk.batchable_verify_detailed(&proof, None).is_ok());
+    }
+
+    #[test]
+    fn test_text_encodings() {
+        let rng = &mut thread_rng();
+        let (instance, witness) = SchnorrInstance::<G>::generate(rng);
+        let nizk = NIZK::<SchnorrDLOG<G>, blake2::Blake2s>::new(&instance, b"ctx");
+        let proof = nizk.batchable_proof(&witness, None, rng).unwrap();
+        let short_proof = nizk.short_proof(&witness, None, rng).unwrap();
+
+        let hex = proof.to_string();
+        assert_eq!(hex, proof.to_hex());
+        assert_eq!(hex.len(), 2 * proof.size_in_bytes(true));
+        assert_eq!(
+            hex.parse::<BatchableProof<SchnorrDLOG<G>>>().unwrap(),
+            proof
+        );
+        assert_eq!(
+            BatchableProof::from_hex(&hex.to_uppercase()).unwrap(),
+            proof
+        );
+        let decoded = BatchableProof::from_base64url(&proof.to_base64url()).unwrap();
+        assert!(nizk.batchable_verify(&decoded, None).is_ok());
+
+        let decoded = short_proof.to_string().parse().unwrap();
+        assert!(nizk.short_verify(&decoded, None).is_ok());
+        let decoded = ShortProof::from_base64url(&short_proof.to_base64url()).unwrap();
+        assert!(nizk.short_verify(&decoded, None).is_ok());
+
+        // 64 bytes are 86 base64url digits without padding
+        let base64 = short_proof.to_base64url();
+        assert_eq!(base64.len(), 86);
+        for invalid in [
+            format!("{}==", base64),
+            format!(" {}", base64),
+            format!("{}0", hex),
+            format!("0x{}", hex),
+        ] {
+            assert!(invalid.parse::<BatchableProof<SchnorrDLOG<G>>>().is_err());
+            assert!(ShortProof::<SchnorrDLOG<G>>::from_base64url(&invalid).is_err());
+        }
+    }
+
+    #[test]
+    fn test_text_encodings_reject_random_strings() {
+        let rng = &mut thread_rng();
+        let alphabet: Vec<char> = "0123456789abcdefABCDEF-_+/= \n\u{e9}\u{1f600}"
+            .chars()
+            .collect();
+        let hex = NIZK::<SchnorrDLOG<G>, blake2::Blake2s>::new(
+            &SchnorrInstance::new(G::prime_subgroup_generator(), G::rand(rng)),
+            b"ctx",
+        )
+        .short_proof(&F::rand(rng), None, rng)
+        .unwrap()
+        .to_hex();
+
+        for _ in 0..2000 {
+            let len = rng.gen_range(0..200);
+            let random: String = (0..len)
+                .map(|_| alphabet[rng.gen_range(0..alphabet.len())])
+                .collect();
+            // A valid encoding with a digit changed reaches the decoders of the scalar and the challenge
+            let mut flipped = hex.clone().into_bytes();
+            let position = rng.gen_range(0..flipped.len());
+            flipped[position] = b"0123456789abcdef"[rng.gen_range(0..16)];
+            let flipped = String::from_utf8(flipped).unwrap();
+
+            for string in [random, flipped] {
+                let _ = string.parse::<BatchableProof<SchnorrDLOG<G>>>();
+                let _ = string.parse::<ShortProof<SchnorrDLOG<G>>>();
+                let _ = BatchableProof::<SchnorrDLOG<G>>::from_base64url(&string);
+                let _ = ShortProof::<SchnorrDLOG<G>>::from_base64url(&string);
+            }
+        }
+    }
+
+    #[test]
+    fn test_verifier_shared_between_threads() {
+        let rng = &mut thread_rng();
+        let (instance, witness) = SchnorrInstance::<G>::generate(rng);
+        let prover = NizkProver::<SchnorrDLOG<G>, blake2::Blake2s>::new(&instance, b"ctx");
+        let verifier = NizkVerifier::<SchnorrDLOG<G>, blake2::Blake2s>::new(&instance, b"ctx");
+        let valid = prover.batchable_proof(&witness, None, rng).unwrap();
+        let invalid = prover.batchable_proof(&F::rand(rng), None, rng).unwrap();
+
+        std::thread::scope(|scope| {
+            for _ in 0..4 {
+                scope.spawn(|| {
+                    assert!(verifier.batchable_verify(&valid, None).is_ok());
+                    assert_eq!(
+                        verifier.batchable_verify(&invalid, None),
+                        Err(SigmaError::VerificationFailed)
+                    );
+                });
+            }
+        });
+    }
+
+    #[test]
+    fn test_nizk_interoperates_with_split_types() {
+        let rng = &mut thread_rng();
+        let (instance, witness) = SchnorrInstance::<G>::generate(rng);
+        let nizk = NIZK::<SchnorrDLOG<G>, blake2::Blake2s>::new(&instance, b"ctx");
+        let prover = NizkProver::<SchnorrDLOG<G>, blake2::Blake2s>::new(&instance, b"ctx");
+        let verifier = NizkVerifier::<SchnorrDLOG<G>, blake2::Blake2s>::new(&instance, b"ctx");
+
+        let batchable_proof = nizk.batchable_proof(&witness, None, rng).unwrap();
+        let short_proof = prover.short_proof(&witness, Some(b"message"), rng).unwrap();
+
+        assert!(verifier.batchable_verify(&batchable_proof, None).is_ok());
+        assert!(nizk.short_verify(&short_proof, Some(b"message")).is_ok());
+    }
+
+    /// Localizes an injected failing set in a batch of 64, returning the results and the number of checks
+    fn localize_injected(failing: &[usize]) -> (Vec<Result<(), SigmaError>>, usize) {
+        let failing: HashSet<_> = failing.iter().copied().collect();
+        let mut checks = 0;
+        let results = localize_failures(64, |range| {
+            checks += 1;
+            if range.clone().any(|i| failing.contains(&i)) {
+                Err(SigmaError::VerificationFailed)
+            } else {
+                Ok(())
+            }
+        });
+
+        (results, checks)
+    }
+
+    fn failures(results: &[Result<(), SigmaError>]) -> Vec<usize> {
+        (0..results.len())
+            .filter(|i| results[*i].is_err())
+            .collect()
+    }
+
+    #[test]
+    fn test_localize_failures() {
+        let (results, checks) = localize_injected(&[]);
+        assert_eq!(results.len(), 64);
+        assert!(failures(&results).is_empty());
+        assert_eq!(checks, 1);
 
-        let mut nizk = NIZK::<S, D>::new(instance, ctx);
+        // The failing half is split down to the single proof, the other halves pass at once
+        let (results, checks) = localize_injected(&[37]);
+        assert_eq!(failures(&results), vec![37]);
+        assert_eq!(checks, 1 + 2 * 6);
 
-        let proof = nizk.short_proof(witness, Some(message), rng)?;
+        let scattered = [0, 9, 10, 31, 32, 63];
+        let (results, checks) = localize_injected(&scattered);
+        assert_eq!(failures(&results), scattered.to_vec());
+        assert!(checks < 64);
 
-        nizk.short_verify(&proof, Some(message))
+        assert!(localize_failures(0, |_| unreachable!()).is_empty());
     }
 }
@@ -0,0 +1,139 @@
+use digest::Digest;
+use rand::{rngs::StdRng, SeedableRng};
+use rayon::prelude::*;
+
+use crate::{BatchableProof, SigmaError, SigmaProtocol, Transcript, NIZK};
+
+/// Domain separator of the RNG seeds derived for every proof of [`NIZK::prove_all`]
+const SEED_DOMSEP: &[u8] = b"zkpstd/sigma/prove_all";
+
+/// The RNG of the proof at `index`, seeded with `Blake2s(SEED_DOMSEP || rng_seed || index)` for the index as a
+/// little-endian `u64`, so that every proof has the same randomness whichever thread produces it
+pub(crate) fn proof_rng(rng_seed: &[u8; 32], index: usize) -> StdRng {
+    let mut hasher = blake2::Blake2s::new();
+    hasher.update(SEED_DOMSEP);
+    hasher.update(rng_seed);
+    hasher.update((index as u64).to_le_bytes());
+    StdRng::from_seed(hasher.finalize().into())
+}
+
+impl<S: SigmaProtocol, D: Transcript> NIZK<S, D>
+where
+    S: Sync,
+    D: Sync,
+{
+    /// Produce a batchable proof for every witness and message, in parallel on the current rayon thread pool. The
+    /// proof at index `i` draws its randomness from a [`StdRng`] seeded with `Blake2s(SEED_DOMSEP || rng_seed || i)`,
+    /// so the proofs are the same whatever the number of threads, and the same as proving them one after another.
+    /// The first error, in the order of the witnesses, is returned
+    pub fn prove_all(
+        &self,
+        witnesses_and_messages: &[(S::Witness, Option<&[u8]>)],
+        rng_seed: [u8; 32],
+    ) -> Result<Vec<BatchableProof<S>>, SigmaError>
+    where
+        S::Witness: Sync,
+        BatchableProof<S>: Send,
+    {
+        witnesses_and_messages
+            .par_iter()
+            .enumerate()
+            .map(|(i, (witness, message))| {
+                self.batchable_proof(witness, *message, &mut proof_rng(&rng_seed, i))
+            })
+            .collect::<Vec<_>>()
+            .into_iter()
+            .collect()
+    }
+
+    /// Verify every batchable proof against its message, in parallel on the current rayon thread pool, and report
+    /// the result of every proof in order. Unlike [`NIZK::verify_many`], every proof is checked on its own
+    pub fn verify_all(
+        &self,
+        proofs: &[(BatchableProof<S>, Option<&[u8]>)],
+    ) -> Vec<Result<(), SigmaError>>
+    where
+        BatchableProof<S>: Sync,
+    {
+        proofs
+            .par_iter()
+            .map(|(proof, message)| self.batchable_verify(proof, *message))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::thread_rng;
+    use rayon::ThreadPoolBuilder;
+
+    use crate::{
+        protocols::{SchnorrDLOG, SchnorrInstance},
+        BatchableProof, SigmaError, NIZK,
+    };
+
+    use super::proof_rng;
+
+    type G = ark_bls12_377::G1Projective;
+
+    #[test]
+    fn test_prove_all_matches_sequential_proofs() {
+        let rng = &mut thread_rng();
+        let (instance, witness) = SchnorrInstance::<G>::generate(rng);
+        let nizk = NIZK::<SchnorrDLOG<G>, blake2::Blake2s>::new(&instance, b"ctx");
+        let messages: Vec<Vec<u8>> = (0..9u8).map(|i| vec![i; usize::from(i)]).collect();
+        let witnesses_and_messages: Vec<_> = messages
+            .iter()
+            .map(|message| (witness, Some(&message[..])))
+            .collect();
+
+        let proofs = nizk.prove_all(&witnesses_and_messages, [7; 32]).unwrap();
+        let sequential: Vec<_> = witnesses_and_messages
+            .iter()
+            .enumerate()
+            .map(|(i, (witness, message))| {
+                nizk.batchable_proof(witness, *message, &mut proof_rng(&[7; 32], i))
+                    .unwrap()
+            })
+            .collect();
+        assert_eq!(proofs, sequential);
+        for threads in [1, 3, 4] {
+            let pool = ThreadPoolBuilder::new()
+                .num_threads(threads)
+                .build()
+                .unwrap();
+            assert_eq!(
+                pool.install(|| nizk.prove_all(&witnesses_and_messages, [7; 32]))
+                    .unwrap(),
+                proofs
+            );
+        }
+        assert_eq!(
+            nizk.prove_all(&witnesses_and_messages, [7; 32]).unwrap(),
+            proofs
+        );
+        assert_ne!(
+            nizk.prove_all(&witnesses_and_messages, [8; 32]).unwrap(),
+            proofs
+        );
+
+        let mut proofs_and_messages: Vec<(BatchableProof<_>, _)> = proofs
+            .into_iter()
+            .zip(messages.iter().map(|message| Some(&message[..])))
+            .collect();
+        assert!(nizk
+            .verify_all(&proofs_and_messages)
+            .iter()
+            .all(Result::is_ok));
+
+        proofs_and_messages[4].1 = None;
+        let results = nizk.verify_all(&proofs_and_messages);
+        for (i, result) in results.into_iter().enumerate() {
+            if i == 4 {
+                assert_eq!(result, Err(SigmaError::VerificationFailed));
+            } else {
+                assert!(result.is_ok());
+            }
+        }
+    }
+}
@@ -0,0 +1,233 @@
+use std::{
+    io::{self, Read, Write},
+    marker::PhantomData,
+};
+
+use ark_serialize::SerializationError;
+
+use crate::{BatchableProof, SigmaError, SigmaProtocol};
+
+/// A stream of batchable proofs over a file, socket or any other I/O object. Each proof is written as the length of
+/// its compressed encoding, a `u64` in little endian, followed by the encoding, so that many proofs can be appended
+/// to one file. Over a reader, the stream is an iterator decoding the proofs lazily, one at a time.
+///
+/// Iteration ends at the end of the input, and after the first error: a truncated length or proof, or a proof that
+/// does not decode in exactly its length. Wrap unbuffered readers in [`std::io::BufReader`], as every proof takes
+/// two reads.
+pub struct ProofStream<S: SigmaProtocol, T> {
+    inner: T,
+    failed: bool,
+    _protocol: PhantomData<S>,
+}
+
+impl<S: SigmaProtocol, T> ProofStream<S, T> {
+    /// A stream over `inner`, which writes proofs where `inner` is and reads them from there
+    pub fn new(inner: T) -> Self {
+        Self {
+            inner,
+            failed: false,
+            _protocol: PhantomData,
+        }
+    }
+
+    /// The underlying I/O object
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+}
+
+impl<S: SigmaProtocol, W: Write> ProofStream<S, W> {
+    /// Appends `proof` to the stream
+    pub fn push(&mut self, proof: &BatchableProof<S>) -> Result<(), SigmaError> {
+        let len = proof.size_in_bytes(true) as u64;
+        self.inner
+            .write_all(&len.to_le_bytes())
+            .map_err(SerializationError::from)?;
+        proof.write_into(&mut self.inner)
+    }
+
+    /// Flushes the underlying writer
+    pub fn flush(&mut self) -> Result<(), SigmaError> {
+        Ok(self.inner.flush().map_err(SerializationError::from)?)
+    }
+}
+
+impl<S: SigmaProtocol, R: Read> ProofStream<S, R> {
+    /// The length prefix of the next proof, or `None` at the end of the input
+    fn read_len(&mut self) -> io::Result<Option<u64>> {
+        let mut len = [0u8; 8];
+        let mut filled = 0;
+        while filled < len.len() {
+            match self.inner.read(&mut len[filled..]) {
+                Ok(0) if filled == 0 => return Ok(None),
+                Ok(0) => return Err(io::ErrorKind::UnexpectedEof.into()),
+                Ok(read) => filled += read,
+                Err(error) if error.kind() == io::ErrorKind::Interrupted => {}
+                Err(error) => return Err(error),
+            }
+        }
+        Ok(Some(u64::from_le_bytes(len)))
+    }
+
+    fn read_proof(&mut self) -> Result<Option<BatchableProof<S>>, SigmaError> {
+        let len = match self.read_len().map_err(SerializationError::from)? {
+            Some(len) => len,
+            None => return Ok(None),
+        };
+
+        // Reading through `take` bounds the memory to the bytes actually present, whatever the prefix claims
+        let mut bytes = Vec::new();
+        (&mut self.inner)
+            .take(len)
+            .read_to_end(&mut bytes)
+            .map_err(SerializationError::from)?;
+        if bytes.len() as u64 != len {
+            return Err(
+                SerializationError::from(io::Error::from(io::ErrorKind::UnexpectedEof)).into(),
+            );
+        }
+
+        BatchableProof::from_bytes_compressed(&bytes).map(Some)
+    }
+}
+
+impl<S: SigmaProtocol, R: Read> Iterator for ProofStream<S, R> {
+    type Item = Result<BatchableProof<S>, SigmaError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+
+        let proof = self.read_proof().transpose();
+        self.failed = matches!(proof, Some(Err(_)));
+        proof
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        env, fs,
+        io::{BufReader, BufWriter},
+        path::PathBuf,
+        process,
+    };
+
+    use ark_serialize::SerializationError;
+    use rand::thread_rng;
+
+    use crate::{
+        protocols::{SchnorrDLOG, SchnorrInstance},
+        BatchableProof, SigmaError, NIZK,
+    };
+
+    use super::ProofStream;
+
+    type G = ark_bls12_377::G1Projective;
+    type F = ark_bls12_377::Fr;
+    type Schnorr = SchnorrDLOG<G>;
+
+    /// A file in the temporary directory, removed when dropped
+    struct TempFile(PathBuf);
+
+    impl TempFile {
+        fn new(name: &str) -> Self {
+            Self(env::temp_dir().join(format!("sigma-{}-{}", process::id(), name)))
+        }
+    }
+
+    impl Drop for TempFile {
+        fn drop(&mut self) {
+            let _ = fs::remove_file(&self.0);
+        }
+    }
+
+    fn setup() -> (NIZK<Schnorr, blake2::Blake2s>, F) {
+        let rng = &mut thread_rng();
+        let (instance, witness) = SchnorrInstance::<G>::generate(rng);
+        (NIZK::new(&instance, b"ctx"), witness)
+    }
+
+    #[test]
+    fn test_write_into_and_read_from() {
+        let rng = &mut thread_rng();
+        let (nizk, witness) = setup();
+        let proof = nizk.batchable_proof(&witness, None, rng).unwrap();
+
+        let mut bytes = Vec::new();
+        proof.write_into(&mut bytes).unwrap();
+        assert_eq!(bytes, proof.to_bytes_compressed());
+
+        // The reader is left after the proof
+        bytes.push(7);
+        let reader = &mut &bytes[..];
+        assert_eq!(
+            BatchableProof::<Schnorr>::read_from(&mut *reader).unwrap(),
+            proof
+        );
+        assert_eq!(reader, &[7]);
+    }
+
+    #[test]
+    fn test_proof_stream_through_file() {
+        let rng = &mut thread_rng();
+        let (nizk, witness) = setup();
+        let file = TempFile::new("proof-stream");
+
+        let proofs = nizk
+            .batchable_proofs(&vec![(witness, None); 1000], rng)
+            .unwrap();
+        let mut stream = ProofStream::new(BufWriter::new(fs::File::create(&file.0).unwrap()));
+        for proof in &proofs {
+            stream.push(proof).unwrap();
+        }
+        stream.flush().unwrap();
+        drop(stream);
+
+        let read = ProofStream::<Schnorr, _>::new(BufReader::new(fs::File::open(&file.0).unwrap()))
+            .map(|proof| proof.map(|proof| (proof, None)))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(read.len(), 1000);
+        assert!(read
+            .iter()
+            .zip(&proofs)
+            .all(|((read, _), proof)| read == proof));
+        assert!(nizk.batch_verify(&read, rng).is_ok());
+    }
+
+    #[test]
+    fn test_proof_stream_stops_at_first_error() {
+        let rng = &mut thread_rng();
+        let (nizk, witness) = setup();
+        let proof = nizk.batchable_proof(&witness, None, rng).unwrap();
+        let mut stream = ProofStream::new(Vec::new());
+        stream.push(&proof).unwrap();
+        stream.push(&proof).unwrap();
+        let bytes = stream.into_inner();
+        let eof = || {
+            SigmaError::SerializationError(SerializationError::IoError(
+                std::io::ErrorKind::UnexpectedEof.into(),
+            ))
+        };
+
+        // Truncated in the length prefix and in the proof
+        for len in [bytes.len() / 2 + 3, bytes.len() - 1] {
+            let mut stream = ProofStream::<Schnorr, _>::new(&bytes[..len]);
+            assert_eq!(stream.next(), Some(Ok(proof.clone())));
+            assert_eq!(stream.next(), Some(Err(eof())));
+            assert_eq!(stream.next(), None);
+        }
+
+        // A length prefix one byte too long takes a byte of the next proof
+        let mut longer = bytes.clone();
+        longer[0] += 1;
+        let mut stream = ProofStream::<Schnorr, _>::new(&longer[..]);
+        assert!(matches!(stream.next(), Some(Err(_))));
+        assert_eq!(stream.next(), None);
+
+        let empty: &[u8] = &[];
+        assert_eq!(ProofStream::<Schnorr, _>::new(empty).next(), None);
+    }
+}
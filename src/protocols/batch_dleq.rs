@@ -0,0 +1,284 @@
+use std::{fmt, sync::Arc};
+
+use ark_ec::{msm::VariableBaseMSM, ProjectiveCurve};
+use ark_ff::{Field, PrimeField};
+use ark_serialize::{CanonicalSerialize, SerializationError, Write};
+use ark_std::UniformRand;
+use rand::Rng;
+
+use super::challenge_scalar;
+use crate::{
+    group::wipe, interactive_proofs::canonical_bytes, secret::Redacted, Challenge,
+    ChallengeExpansion, CommitmentRecoverable, SigmaError, SigmaProtocol,
+};
+
+/// Proof that a single exponent links every `(base, claim)` pair of a list, i.e. `claim_i = x * base_i` for all `i`.
+///
+/// This generalises [`DleqProof`](super::DleqProof) to any number of pairs: the commitment has one element per pair
+/// and the response is a single scalar. Besides the pair-by-pair [`SigmaProtocol::verifier`], a batched verifier
+/// checks all pairs at once with a random linear combination, see [`BatchDleq::verifier_randomized`].
+#[derive(Clone)]
+pub struct BatchDleq<G: ProjectiveCurve> {
+    instance: Arc<BatchDleqInstance<G>>,
+}
+
+/// The instance for the batched DLEQ proof: the `(base, claim)` pairs sharing the same exponent
+#[derive(Debug, Clone, CanonicalSerialize)]
+pub struct BatchDleqInstance<G: ProjectiveCurve> {
+    pairs: Vec<(G, G)>,
+}
+
+impl<G: ProjectiveCurve> BatchDleqInstance<G> {
+    /// Create a new batched DLEQ instance from the provided `(base, claim)` pairs
+    pub fn new(pairs: Vec<(G, G)>) -> Self {
+        Self { pairs }
+    }
+}
+
+/// A wrapper type for a batched DLEQ witness
+pub type BatchDleqWitness<G> = <G as ProjectiveCurve>::ScalarField;
+
+/// A wrapper type for a batched DLEQ commitment, one group element per pair
+pub type BatchDleqCommitment<G> = Vec<G>;
+
+/// A wrapper type for a batched DLEQ response
+pub type BatchDleqResponse<G> = <G as ProjectiveCurve>::ScalarField;
+
+pub struct ProverState<F: Field> {
+    witness: F,
+    random_value: F,
+}
+
+impl<F: Field> fmt::Debug for ProverState<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ProverState")
+            .field("witness", &Redacted)
+            .field("random_value", &Redacted)
+            .finish()
+    }
+}
+
+impl<G: ProjectiveCurve> BatchDleq<G> {
+    /// Verify all pairs with a single multi-scalar multiplication.
+    ///
+    /// Each check `commitment_i = s * base_i + c * claim_i` is weighted by a fresh random scalar and the
+    /// weighted checks are summed, so a proof that fails for any pair is accepted with negligible probability.
+    pub fn verifier_randomized<R: Rng>(
+        &self,
+        commitment: &BatchDleqCommitment<G>,
+        challenge: &G::ScalarField,
+        response: &BatchDleqResponse<G>,
+        rng: &mut R,
+    ) -> Result<(), SigmaError> {
+        if commitment.len() != self.instance.pairs.len() {
+            return Err(SigmaError::LengthMismatch);
+        }
+
+        let mut bases = Vec::with_capacity(3 * commitment.len());
+        let mut scalars = Vec::with_capacity(3 * commitment.len());
+        for ((base, claim), commitment) in self.instance.pairs.iter().zip(commitment) {
+            let weight = G::ScalarField::rand(rng);
+            bases.extend([*base, *claim, *commitment]);
+            scalars.extend([
+                (weight * response).into_repr(),
+                (weight * *challenge).into_repr(),
+                (-weight).into_repr(),
+            ]);
+        }
+
+        let bases = G::batch_normalization_into_affine(&bases);
+        if VariableBaseMSM::multi_scalar_mul(&bases, &scalars).is_zero() {
+            Ok(())
+        } else {
+            Err(SigmaError::VerificationFailed)
+        }
+    }
+}
+
+impl<G: ProjectiveCurve> SigmaProtocol for BatchDleq<G> {
+    type Instance = BatchDleqInstance<G>;
+    type Commitment = BatchDleqCommitment<G>;
+    type ProverState = ProverState<G::ScalarField>;
+    type Witness = BatchDleqWitness<G>;
+    type Response = BatchDleqResponse<G>;
+    type Challenge = G::ScalarField;
+
+    const PROTOCOL_ID: &'static [u8] = b"zkpstd/sigma/batch-dleq";
+
+    fn new(instance: &BatchDleqInstance<G>) -> Self {
+        Self::from_shared(Arc::new(instance.clone()))
+    }
+
+    fn from_shared(instance: Arc<BatchDleqInstance<G>>) -> Self {
+        Self { instance }
+    }
+
+    fn challenge_from_bytes(
+        challenge: &Challenge,
+        expansion: ChallengeExpansion,
+    ) -> Result<G::ScalarField, SigmaError> {
+        challenge_scalar(challenge, expansion)
+    }
+
+    fn instance_bytes(&self) -> Vec<u8> {
+        canonical_bytes(&*self.instance)
+    }
+
+    fn witness_bytes(&self, witness: &Self::Witness) -> Vec<u8> {
+        canonical_bytes(witness)
+    }
+
+    fn prover_commit<R: Rng>(
+        &self,
+        witness: &Self::Witness,
+        _expansion: ChallengeExpansion,
+        rng: &mut R,
+    ) -> Result<(Self::Commitment, Self::ProverState), SigmaError> {
+        let random_value = G::ScalarField::rand(rng);
+
+        let commitment = self
+            .instance
+            .pairs
+            .iter()
+            .map(|(base, _)| base.mul(random_value.into_repr()))
+            .collect();
+
+        let state = ProverState {
+            witness: *witness,
+            random_value,
+        };
+
+        Ok((commitment, state))
+    }
+
+    fn prover_response(
+        &self,
+        prover_state: &Self::ProverState,
+        challenge: &Self::Challenge,
+    ) -> Result<Self::Response, SigmaError> {
+        Ok(prover_state.random_value - *challenge * prover_state.witness)
+    }
+
+    fn verifier(
+        &self,
+        commitment: &Self::Commitment,
+        challenge: &Self::Challenge,
+        response: &Self::Response,
+    ) -> Result<(), SigmaError> {
+        if &self.simulate_commitment(challenge, response)? == commitment {
+            Ok(())
+        } else {
+            Err(SigmaError::VerificationFailed)
+        }
+    }
+
+    fn simulate_response<R: Rng>(&self, rng: &mut R) -> Self::Response {
+        G::ScalarField::rand(rng)
+    }
+
+    fn zeroize_state(&self, prover_state: &mut Self::ProverState) {
+        wipe(&mut prover_state.witness);
+        wipe(&mut prover_state.random_value);
+    }
+}
+
+impl<G: ProjectiveCurve> CommitmentRecoverable for BatchDleq<G> {
+    fn simulate_commitment(
+        &self,
+        challenge: &Self::Challenge,
+        response: &Self::Response,
+    ) -> Result<Self::Commitment, SigmaError> {
+        let challenge = challenge.into_repr();
+        let response = response.into_repr();
+
+        Ok(self
+            .instance
+            .pairs
+            .iter()
+            .map(|(base, claim)| base.mul(response) + claim.mul(challenge))
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_ec::ProjectiveCurve;
+    use ark_ff::{PrimeField, UniformRand};
+    use rand::{thread_rng, Rng};
+
+    use crate::{
+        nizk_proofs::tests::{run_nizk_batched, run_nizk_short},
+        ChallengeExpansion, SigmaError, SigmaProtocol,
+    };
+
+    use super::{BatchDleq, BatchDleqInstance};
+
+    type Hash = blake2::Blake2s;
+    type G = ark_bls12_377::G1Projective;
+    type F = ark_bls12_377::Fr;
+
+    fn batch_dleq_setup<R: Rng>(pairs: usize, rng: &mut R) -> (BatchDleqInstance<G>, F) {
+        let witness = F::rand(rng);
+        let pairs = (0..pairs)
+            .map(|_| {
+                let base = G::rand(rng);
+                (base, base.mul(witness.into_repr()))
+            })
+            .collect();
+
+        (BatchDleqInstance::new(pairs), witness)
+    }
+
+    /// Runs the interactive protocol and checks it with the randomized verifier
+    fn run_randomized<R: Rng>(
+        instance: &BatchDleqInstance<G>,
+        witness: &F,
+        rng: &mut R,
+    ) -> Result<(), SigmaError> {
+        let protocol = BatchDleq::new(instance);
+        let (commitment, state) =
+            protocol.prover_commit(witness, ChallengeExpansion::default(), rng)?;
+        let challenge = rng.gen();
+        let response = protocol.prover_response(&state, &challenge)?;
+
+        protocol.verifier_randomized(&commitment, &challenge, &response, rng)
+    }
+
+    #[test]
+    fn test_batch_dleq_accept_valid() {
+        let rng = &mut thread_rng();
+
+        for pairs in [1, 2, 50] {
+            let (instance, witness) = batch_dleq_setup(pairs, rng);
+
+            let batched_result =
+                run_nizk_batched::<Hash, BatchDleq<_>, _>(&instance, &witness, rng);
+            let short_result = run_nizk_short::<Hash, BatchDleq<_>, _>(&instance, &witness, rng);
+            let randomized_result = run_randomized(&instance, &witness, rng);
+
+            assert!(batched_result.is_ok());
+            assert!(short_result.is_ok());
+            assert!(randomized_result.is_ok());
+        }
+    }
+
+    #[test]
+    fn test_batch_dleq_reject_different_exponent() {
+        let rng = &mut thread_rng();
+
+        for pairs in [1, 2, 50] {
+            let (mut instance, witness) = batch_dleq_setup(pairs, rng);
+            let (base, _) = instance.pairs[pairs - 1];
+            instance.pairs[pairs - 1] = (base, base.mul(F::rand(rng).into_repr()));
+
+            let batched_result =
+                run_nizk_batched::<Hash, BatchDleq<_>, _>(&instance, &witness, rng);
+            let short_result = run_nizk_short::<Hash, BatchDleq<_>, _>(&instance, &witness, rng);
+            let randomized_result = run_randomized(&instance, &witness, rng);
+
+            assert_eq!(batched_result, Err(SigmaError::VerificationFailed));
+            assert_eq!(short_result, Err(SigmaError::VerificationFailed));
+            assert_eq!(randomized_result, Err(SigmaError::VerificationFailed));
+        }
+    }
+}
@@ -0,0 +1,262 @@
+use std::fmt;
+
+use ark_ec::ProjectiveCurve;
+use ark_ff::{Field, One, PrimeField};
+use ark_serialize::{CanonicalSerialize, SerializationError, Write};
+use ark_std::UniformRand;
+use rand::Rng;
+
+use super::{challenge_scalar, PedersenOpening, PedersenParams};
+use crate::{
+    group::wipe, interactive_proofs::canonical_bytes, secret::Redacted, Challenge,
+    ChallengeExpansion, CommitmentRecoverable, SigmaError, SigmaProtocol,
+};
+
+/// Proof that a Pedersen commitment opens to either 0 or 1.
+///
+/// This is an OR proof of the two statements `C = r * H` and `C - G = r * H`: the branch that does not match the
+/// committed bit is simulated and the challenges of both branches sum to the Fiat-Shamir challenge.
+pub struct BitProof<G: ProjectiveCurve> {
+    instance: BitInstance<G>,
+}
+
+/// The instance for the bit proof: the Pedersen parameters and the commitment to the bit
+#[derive(Debug, Clone, Copy, CanonicalSerialize)]
+pub struct BitInstance<G: ProjectiveCurve> {
+    params: PedersenParams<G>,
+    commitment: G,
+}
+
+impl<G: ProjectiveCurve> BitInstance<G> {
+    /// Create a new bit instance from the provided commitment
+    pub fn new(params: PedersenParams<G>, commitment: G) -> Self {
+        Self { params, commitment }
+    }
+
+    /// The points whose discrete logarithm with respect to `H` is claimed for the 0 and 1 branch respectively
+    fn claims(&self) -> [G; 2] {
+        [self.commitment, self.commitment - self.params.generator()]
+    }
+}
+
+/// A wrapper type for a bit witness: the opening of the commitment, whose message must be 0 or 1
+pub type BitWitness<G> = PedersenOpening<<G as ProjectiveCurve>::ScalarField>;
+
+/// A wrapper type for a bit commitment, one group element per branch
+pub type BitCommitment<G> = (G, G);
+
+/// A wrapper type for a bit response: the challenge of the 0 branch and the responses of both branches
+pub type BitResponse<G> = (
+    <G as ProjectiveCurve>::ScalarField,
+    <G as ProjectiveCurve>::ScalarField,
+    <G as ProjectiveCurve>::ScalarField,
+);
+
+pub struct ProverState<F: Field> {
+    bit: bool,
+    blinding: F,
+    random_value: F,
+    simulated_challenge: F,
+    simulated_response: F,
+}
+
+impl<F: Field> fmt::Debug for ProverState<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ProverState")
+            .field("bit", &Redacted)
+            .field("blinding", &Redacted)
+            .field("random_value", &Redacted)
+            .field("simulated_challenge", &Redacted)
+            .field("simulated_response", &Redacted)
+            .finish()
+    }
+}
+
+impl<G: ProjectiveCurve> SigmaProtocol for BitProof<G> {
+    type Instance = BitInstance<G>;
+    type Commitment = BitCommitment<G>;
+    type ProverState = ProverState<G::ScalarField>;
+    type Witness = BitWitness<G>;
+    type Response = BitResponse<G>;
+    type Challenge = G::ScalarField;
+
+    const PROTOCOL_ID: &'static [u8] = b"zkpstd/sigma/bit";
+
+    fn new(instance: &BitInstance<G>) -> Self {
+        Self {
+            instance: *instance,
+        }
+    }
+
+    fn challenge_from_bytes(
+        challenge: &Challenge,
+        expansion: ChallengeExpansion,
+    ) -> Result<G::ScalarField, SigmaError> {
+        challenge_scalar(challenge, expansion)
+    }
+
+    fn instance_bytes(&self) -> Vec<u8> {
+        canonical_bytes(&self.instance)
+    }
+
+    fn witness_bytes(&self, witness: &Self::Witness) -> Vec<u8> {
+        canonical_bytes(witness)
+    }
+
+    fn prover_commit<R: Rng>(
+        &self,
+        witness: &Self::Witness,
+        _expansion: ChallengeExpansion,
+        rng: &mut R,
+    ) -> Result<(Self::Commitment, Self::ProverState), SigmaError> {
+        let bit = witness.message() == G::ScalarField::one();
+        let blinding_base = self.instance.params.blinding_base();
+        let random_value = G::ScalarField::rand(rng);
+        let simulated_challenge = G::ScalarField::rand(rng);
+        let simulated_response = G::ScalarField::rand(rng);
+
+        let real = blinding_base.mul(random_value.into_repr());
+        let simulated = blinding_base.mul(simulated_response.into_repr())
+            + self.instance.claims()[!bit as usize].mul(simulated_challenge.into_repr());
+        let commitment = if bit {
+            (simulated, real)
+        } else {
+            (real, simulated)
+        };
+
+        let state = ProverState {
+            bit,
+            blinding: witness.blinding(),
+            random_value,
+            simulated_challenge,
+            simulated_response,
+        };
+
+        Ok((commitment, state))
+    }
+
+    fn prover_response(
+        &self,
+        prover_state: &Self::ProverState,
+        challenge: &Self::Challenge,
+    ) -> Result<Self::Response, SigmaError> {
+        let real_challenge = *challenge - prover_state.simulated_challenge;
+        let real_response = prover_state.random_value - real_challenge * prover_state.blinding;
+
+        if prover_state.bit {
+            Ok((
+                prover_state.simulated_challenge,
+                prover_state.simulated_response,
+                real_response,
+            ))
+        } else {
+            Ok((
+                real_challenge,
+                real_response,
+                prover_state.simulated_response,
+            ))
+        }
+    }
+
+    fn verifier(
+        &self,
+        commitment: &Self::Commitment,
+        challenge: &Self::Challenge,
+        response: &Self::Response,
+    ) -> Result<(), SigmaError> {
+        if &self.simulate_commitment(challenge, response)? == commitment {
+            Ok(())
+        } else {
+            Err(SigmaError::VerificationFailed)
+        }
+    }
+
+    fn simulate_response<R: Rng>(&self, rng: &mut R) -> Self::Response {
+        (
+            G::ScalarField::rand(rng),
+            G::ScalarField::rand(rng),
+            G::ScalarField::rand(rng),
+        )
+    }
+
+    fn zeroize_state(&self, prover_state: &mut Self::ProverState) {
+        wipe(&mut prover_state.bit);
+        wipe(&mut prover_state.blinding);
+        wipe(&mut prover_state.random_value);
+        wipe(&mut prover_state.simulated_challenge);
+        wipe(&mut prover_state.simulated_response);
+    }
+}
+
+impl<G: ProjectiveCurve> CommitmentRecoverable for BitProof<G> {
+    fn simulate_commitment(
+        &self,
+        challenge: &Self::Challenge,
+        response: &Self::Response,
+    ) -> Result<Self::Commitment, SigmaError> {
+        let (challenge0, response0, response1) = response;
+        let challenge1 = *challenge - challenge0;
+        let blinding_base = self.instance.params.blinding_base();
+        let [claim0, claim1] = self.instance.claims();
+
+        Ok((
+            blinding_base.mul(response0.into_repr()) + claim0.mul(challenge0.into_repr()),
+            blinding_base.mul(response1.into_repr()) + claim1.mul(challenge1.into_repr()),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_ff::UniformRand;
+    use rand::{thread_rng, Rng};
+
+    use crate::{
+        nizk_proofs::tests::{run_nizk_batched, run_nizk_short},
+        protocols::{PedersenOpening, PedersenParams},
+        SigmaError,
+    };
+
+    use super::{BitInstance, BitProof, BitWitness};
+
+    type Hash = blake2::Blake2s;
+    type G = ark_bls12_377::G1Projective;
+    type F = ark_bls12_377::Fr;
+
+    fn bit_setup<R: Rng>(value: u64, rng: &mut R) -> (BitInstance<G>, BitWitness<G>) {
+        let params = PedersenParams::new(G::rand(rng), G::rand(rng));
+        let opening = PedersenOpening::new(F::from(value), F::rand(rng));
+
+        (
+            BitInstance::new(params, params.commit_opening(&opening)),
+            opening,
+        )
+    }
+
+    #[test]
+    fn test_bit_accept_zero_and_one() {
+        let rng = &mut thread_rng();
+
+        for bit in [0, 1] {
+            let (instance, witness) = bit_setup(bit, rng);
+
+            let batched_result = run_nizk_batched::<Hash, BitProof<_>, _>(&instance, &witness, rng);
+            let short_result = run_nizk_short::<Hash, BitProof<_>, _>(&instance, &witness, rng);
+
+            assert!(batched_result.is_ok());
+            assert!(short_result.is_ok());
+        }
+    }
+
+    #[test]
+    fn test_bit_reject_non_bit() {
+        let rng = &mut thread_rng();
+        let (instance, witness) = bit_setup(2, rng);
+
+        let batched_result = run_nizk_batched::<Hash, BitProof<_>, _>(&instance, &witness, rng);
+        let short_result = run_nizk_short::<Hash, BitProof<_>, _>(&instance, &witness, rng);
+
+        assert_eq!(batched_result, Err(SigmaError::VerificationFailed));
+        assert_eq!(short_result, Err(SigmaError::VerificationFailed));
+    }
+}
@@ -0,0 +1,235 @@
+use std::marker::PhantomData;
+
+use ark_ec::{PairingEngine, ProjectiveCurve};
+use ark_ff::PrimeField;
+use ark_serialize::CanonicalSerialize;
+use rand::Rng;
+
+use super::{SchnorrDLOG, SchnorrInstance};
+use crate::{
+    BatchableProof, Challenge, ChallengeExpansion, CommitmentRecoverable, SigmaError,
+    SigmaProtocol, SufficientOutput, Transcript, NIZK,
+};
+
+/// Selects the source group of a pairing in which BLS public keys live.
+pub trait BlsKeyGroup<E: PairingEngine> {
+    /// The group of the public keys
+    type Group: ProjectiveCurve<ScalarField = E::Fr>;
+
+    /// The protocol identifier, distinct for each group
+    const PROTOCOL_ID: &'static [u8];
+}
+
+/// BLS public keys in `G1`, signatures in `G2`
+#[derive(Debug, Clone, Copy)]
+pub struct PublicKeyInG1;
+
+/// BLS public keys in `G2`, signatures in `G1`
+#[derive(Debug, Clone, Copy)]
+pub struct PublicKeyInG2;
+
+impl<E: PairingEngine> BlsKeyGroup<E> for PublicKeyInG1 {
+    type Group = E::G1Projective;
+
+    const PROTOCOL_ID: &'static [u8] = b"zkpstd/sigma/bls-pop-g1";
+}
+
+impl<E: PairingEngine> BlsKeyGroup<E> for PublicKeyInG2 {
+    type Group = E::G2Projective;
+
+    const PROTOCOL_ID: &'static [u8] = b"zkpstd/sigma/bls-pop-g2";
+}
+
+/// Proof of knowledge of the secret key `sk` behind a BLS public key `PK = sk * G`.
+///
+/// `G` is the standard generator of the key group selected by `K`. This is a Schnorr proof in that group, with its
+/// own label per key group.
+pub struct BlsKeyPossession<E: PairingEngine, K: BlsKeyGroup<E>> {
+    schnorr: SchnorrDLOG<K::Group>,
+}
+
+/// The instance for the BLS proof of possession: the public key
+#[derive(Debug, Clone, Copy)]
+pub struct BlsKeyPossessionInstance<E: PairingEngine, K: BlsKeyGroup<E>> {
+    public_key: K::Group,
+    _engine: PhantomData<E>,
+}
+
+impl<E: PairingEngine, K: BlsKeyGroup<E>> BlsKeyPossessionInstance<E, K> {
+    /// Create a new proof of possession instance for the provided public key
+    pub fn new(public_key: K::Group) -> Self {
+        Self {
+            public_key,
+            _engine: PhantomData,
+        }
+    }
+}
+
+impl<E: PairingEngine, K: BlsKeyGroup<E>> From<&BlsKeyPossessionInstance<E, K>>
+    for SchnorrInstance<K::Group>
+{
+    fn from(instance: &BlsKeyPossessionInstance<E, K>) -> Self {
+        SchnorrInstance::new(K::Group::prime_subgroup_generator(), instance.public_key)
+    }
+}
+
+/// A wrapper type for a BLS proof of possession witness: the secret key
+pub type BlsKeyPossessionWitness<E> = <E as PairingEngine>::Fr;
+
+impl<E: PairingEngine, K: BlsKeyGroup<E>> SigmaProtocol for BlsKeyPossession<E, K> {
+    type Instance = BlsKeyPossessionInstance<E, K>;
+    type Commitment = <SchnorrDLOG<K::Group> as SigmaProtocol>::Commitment;
+    type ProverState = <SchnorrDLOG<K::Group> as SigmaProtocol>::ProverState;
+    type Witness = BlsKeyPossessionWitness<E>;
+    type Response = <SchnorrDLOG<K::Group> as SigmaProtocol>::Response;
+    type Challenge = <SchnorrDLOG<K::Group> as SigmaProtocol>::Challenge;
+
+    const PROTOCOL_ID: &'static [u8] = K::PROTOCOL_ID;
+
+    fn new(instance: &BlsKeyPossessionInstance<E, K>) -> Self {
+        Self {
+            schnorr: SchnorrDLOG::new(&instance.into()),
+        }
+    }
+
+    fn challenge_from_bytes(
+        challenge: &Challenge,
+        expansion: ChallengeExpansion,
+    ) -> Result<Self::Challenge, SigmaError> {
+        SchnorrDLOG::<K::Group>::challenge_from_bytes(challenge, expansion)
+    }
+
+    fn instance_bytes(&self) -> Vec<u8> {
+        self.schnorr.instance_bytes()
+    }
+
+    fn witness_bytes(&self, witness: &Self::Witness) -> Vec<u8> {
+        self.schnorr.witness_bytes(witness)
+    }
+
+    fn prover_commit<R: Rng>(
+        &self,
+        witness: &Self::Witness,
+        expansion: ChallengeExpansion,
+        rng: &mut R,
+    ) -> Result<(Self::Commitment, Self::ProverState), SigmaError> {
+        self.schnorr.prover_commit(witness, expansion, rng)
+    }
+
+    fn prover_response(
+        &self,
+        prover_state: &Self::ProverState,
+        challenge: &Self::Challenge,
+    ) -> Result<Self::Response, SigmaError> {
+        self.schnorr.prover_response(prover_state, challenge)
+    }
+
+    fn verifier(
+        &self,
+        commitment: &Self::Commitment,
+        challenge: &Self::Challenge,
+        response: &Self::Response,
+    ) -> Result<(), SigmaError> {
+        self.schnorr.verifier(commitment, challenge, response)
+    }
+
+    fn simulate_response<R: Rng>(&self, rng: &mut R) -> Self::Response {
+        self.schnorr.simulate_response(rng)
+    }
+
+    fn zeroize_state(&self, prover_state: &mut Self::ProverState) {
+        self.schnorr.zeroize_state(prover_state);
+    }
+}
+
+impl<E: PairingEngine, K: BlsKeyGroup<E>> CommitmentRecoverable for BlsKeyPossession<E, K> {
+    fn simulate_commitment(
+        &self,
+        challenge: &Self::Challenge,
+        response: &Self::Response,
+    ) -> Result<Self::Commitment, SigmaError> {
+        self.schnorr.simulate_commitment(challenge, response)
+    }
+}
+
+/// A non-interactive BLS proof of possession
+pub type BlsPossessionProof<E, K> = BatchableProof<BlsKeyPossession<E, K>>;
+
+/// Non-interactive BLS proofs of possession, with the serialized public key as the NIZK context.
+pub struct ProofOfPossession<E: PairingEngine, K: BlsKeyGroup<E>, D: Transcript> {
+    _marker: PhantomData<(E, K, D)>,
+}
+
+impl<E: PairingEngine, K: BlsKeyGroup<E>, D: SufficientOutput + Default>
+    ProofOfPossession<E, K, D>
+{
+    fn nizk(public_key: &K::Group) -> NIZK<BlsKeyPossession<E, K>, D> {
+        let mut ctx = Vec::new();
+        public_key.serialize(&mut ctx).unwrap();
+
+        NIZK::new(&BlsKeyPossessionInstance::new(*public_key), &ctx)
+    }
+
+    /// Prove possession of `secret_key`, returning the public key and the proof
+    pub fn prove<R: Rng>(
+        secret_key: &E::Fr,
+        rng: &mut R,
+    ) -> Result<(K::Group, BlsPossessionProof<E, K>), SigmaError> {
+        let public_key = K::Group::prime_subgroup_generator().mul(secret_key.into_repr());
+        let proof = Self::nizk(&public_key).batchable_proof(secret_key, None, rng)?;
+
+        Ok((public_key, proof))
+    }
+
+    /// Verify a proof of possession for `public_key`
+    pub fn verify(
+        public_key: &K::Group,
+        proof: &BlsPossessionProof<E, K>,
+    ) -> Result<(), SigmaError> {
+        Self::nizk(public_key).batchable_verify(proof, None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_bls12_377::Bls12_377;
+    use ark_ec::PairingEngine;
+    use ark_ff::UniformRand;
+    use rand::thread_rng;
+
+    use crate::SigmaError;
+
+    use super::{BlsKeyGroup, BlsPossessionProof, ProofOfPossession, PublicKeyInG1, PublicKeyInG2};
+
+    type Hash = blake2::Blake2s;
+    type F = <Bls12_377 as PairingEngine>::Fr;
+
+    fn prove<K: BlsKeyGroup<Bls12_377>>(
+        secret_key: &F,
+    ) -> (K::Group, BlsPossessionProof<Bls12_377, K>) {
+        let rng = &mut thread_rng();
+        ProofOfPossession::<Bls12_377, K, Hash>::prove(secret_key, rng).unwrap()
+    }
+
+    fn check_accept_and_reject<K: BlsKeyGroup<Bls12_377>>() {
+        let rng = &mut thread_rng();
+        let (public_key, proof) = prove::<K>(&F::rand(rng));
+        let (other_public_key, _) = prove::<K>(&F::rand(rng));
+
+        assert!(ProofOfPossession::<Bls12_377, K, Hash>::verify(&public_key, &proof).is_ok());
+        assert_eq!(
+            ProofOfPossession::<Bls12_377, K, Hash>::verify(&other_public_key, &proof),
+            Err(SigmaError::VerificationFailed)
+        );
+    }
+
+    #[test]
+    fn test_proof_of_possession_g1() {
+        check_accept_and_reject::<PublicKeyInG1>();
+    }
+
+    #[test]
+    fn test_proof_of_possession_g2() {
+        check_accept_and_reject::<PublicKeyInG2>();
+    }
+}
@@ -0,0 +1,311 @@
+use ark_ec::{msm::VariableBaseMSM, ProjectiveCurve};
+use ark_ff::PrimeField;
+use ark_std::UniformRand;
+use rand::Rng;
+
+use super::{DleqInstance, DleqProof, ElGamalCiphertext};
+use crate::{
+    BatchableProof, Challenge, ChallengeExpansion, CommitmentRecoverable, SigmaError,
+    SigmaProtocol, SufficientOutput, NIZK,
+};
+
+/// Proof that a threshold ElGamal decryption share `d_i = sk_i * c1` matches the server's verification key
+/// `VK_i = sk_i * G`.
+///
+/// This is a DLEQ proof with its own label, so that its transcripts cannot be confused with those of a generic
+/// [`DleqProof`]. Shares from many servers can be checked together with [`DecryptionShareProof::batch_verify`].
+pub struct DecryptionShareProof<G: ProjectiveCurve> {
+    dleq: DleqProof<G>,
+}
+
+/// The instance for the decryption share proof: the generator, the server's verification key, the ciphertext
+/// and the published share
+#[derive(Debug, Clone, Copy)]
+pub struct DecryptionShareInstance<G: ProjectiveCurve> {
+    generator: G,
+    verification_key: G,
+    ciphertext: ElGamalCiphertext<G>,
+    share: G,
+}
+
+impl<G: ProjectiveCurve> DecryptionShareInstance<G> {
+    /// Create a new decryption share instance from the provided group elements
+    pub fn new(
+        generator: G,
+        verification_key: G,
+        ciphertext: ElGamalCiphertext<G>,
+        share: G,
+    ) -> Self {
+        Self {
+            generator,
+            verification_key,
+            ciphertext,
+            share,
+        }
+    }
+}
+
+impl<G: ProjectiveCurve> From<&DecryptionShareInstance<G>> for DleqInstance<G> {
+    fn from(instance: &DecryptionShareInstance<G>) -> Self {
+        DleqInstance::new(
+            instance.generator,
+            instance.verification_key,
+            instance.ciphertext.c1(),
+            instance.share,
+        )
+    }
+}
+
+/// A wrapper type for a decryption share witness: the server's secret key share
+pub type DecryptionShareWitness<G> = <G as ProjectiveCurve>::ScalarField;
+
+impl<G: ProjectiveCurve> DecryptionShareProof<G> {
+    /// Verify the batchable proofs of many decryption shares with a single multi-scalar multiplication.
+    ///
+    /// Both verification equations of every proof are weighted by fresh random scalars and summed, so the batch
+    /// is accepted only if, with overwhelming probability, every proof is valid. All proofs must have been produced
+    /// with the same context and message.
+    pub fn batch_verify<D: SufficientOutput + Default, R: Rng>(
+        shares: &[(DecryptionShareInstance<G>, BatchableProof<Self>)],
+        ctx: &[u8],
+        message: Option<&[u8]>,
+        rng: &mut R,
+    ) -> Result<(), SigmaError> {
+        let mut bases = Vec::with_capacity(6 * shares.len());
+        let mut scalars = Vec::with_capacity(6 * shares.len());
+
+        for (instance, proof) in shares {
+            let nizk = NIZK::<Self, D>::new(instance, ctx);
+            let challenge = nizk.batchable_protocol_challenge(proof, message)?;
+            let response = *proof.response();
+            let (commitment1, commitment2) = *proof.commitment();
+            let weight1 = G::ScalarField::rand(rng);
+            let weight2 = G::ScalarField::rand(rng);
+
+            bases.extend([
+                instance.generator,
+                instance.verification_key,
+                commitment1,
+                instance.ciphertext.c1(),
+                instance.share,
+                commitment2,
+            ]);
+            scalars.extend([
+                (weight1 * response).into_repr(),
+                (weight1 * challenge).into_repr(),
+                (-weight1).into_repr(),
+                (weight2 * response).into_repr(),
+                (weight2 * challenge).into_repr(),
+                (-weight2).into_repr(),
+            ]);
+        }
+
+        let bases = G::batch_normalization_into_affine(&bases);
+        if VariableBaseMSM::multi_scalar_mul(&bases, &scalars).is_zero() {
+            Ok(())
+        } else {
+            Err(SigmaError::VerificationFailed)
+        }
+    }
+}
+
+impl<G: ProjectiveCurve> SigmaProtocol for DecryptionShareProof<G> {
+    type Instance = DecryptionShareInstance<G>;
+    type Commitment = <DleqProof<G> as SigmaProtocol>::Commitment;
+    type ProverState = <DleqProof<G> as SigmaProtocol>::ProverState;
+    type Witness = DecryptionShareWitness<G>;
+    type Response = <DleqProof<G> as SigmaProtocol>::Response;
+    type Challenge = <DleqProof<G> as SigmaProtocol>::Challenge;
+
+    const PROTOCOL_ID: &'static [u8] = b"zkpstd/sigma/decryption-share";
+
+    fn new(instance: &DecryptionShareInstance<G>) -> Self {
+        Self {
+            dleq: DleqProof::new(&instance.into()),
+        }
+    }
+
+    fn challenge_from_bytes(
+        challenge: &Challenge,
+        expansion: ChallengeExpansion,
+    ) -> Result<Self::Challenge, SigmaError> {
+        DleqProof::<G>::challenge_from_bytes(challenge, expansion)
+    }
+
+    fn instance_bytes(&self) -> Vec<u8> {
+        self.dleq.instance_bytes()
+    }
+
+    fn witness_bytes(&self, witness: &Self::Witness) -> Vec<u8> {
+        self.dleq.witness_bytes(witness)
+    }
+
+    fn prover_commit<R: Rng>(
+        &self,
+        witness: &Self::Witness,
+        expansion: ChallengeExpansion,
+        rng: &mut R,
+    ) -> Result<(Self::Commitment, Self::ProverState), SigmaError> {
+        self.dleq.prover_commit(witness, expansion, rng)
+    }
+
+    fn prover_response(
+        &self,
+        prover_state: &Self::ProverState,
+        challenge: &Self::Challenge,
+    ) -> Result<Self::Response, SigmaError> {
+        self.dleq.prover_response(prover_state, challenge)
+    }
+
+    fn verifier(
+        &self,
+        commitment: &Self::Commitment,
+        challenge: &Self::Challenge,
+        response: &Self::Response,
+    ) -> Result<(), SigmaError> {
+        self.dleq.verifier(commitment, challenge, response)
+    }
+
+    fn simulate_response<R: Rng>(&self, rng: &mut R) -> Self::Response {
+        self.dleq.simulate_response(rng)
+    }
+
+    fn zeroize_state(&self, prover_state: &mut Self::ProverState) {
+        self.dleq.zeroize_state(prover_state);
+    }
+}
+
+impl<G: ProjectiveCurve> CommitmentRecoverable for DecryptionShareProof<G> {
+    fn simulate_commitment(
+        &self,
+        challenge: &Self::Challenge,
+        response: &Self::Response,
+    ) -> Result<Self::Commitment, SigmaError> {
+        self.dleq.simulate_commitment(challenge, response)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_ec::ProjectiveCurve;
+    use ark_ff::{PrimeField, UniformRand};
+    use rand::{thread_rng, Rng};
+
+    use crate::{
+        protocols::{DleqProof, ElGamalCiphertext},
+        BatchableProof, SigmaError, SigmaProtocol, SufficientOutput, XofTranscript, NIZK,
+    };
+
+    use super::{DecryptionShareInstance, DecryptionShareProof};
+
+    type Hash = blake2::Blake2s;
+    type Shake = XofTranscript<sha3::Shake128>;
+    type G = ark_bls12_377::G1Projective;
+    type F = ark_bls12_377::Fr;
+
+    const CTX: &[u8] = b"threshold decryption";
+    const SERVERS: usize = 5;
+
+    type Share = (
+        DecryptionShareInstance<G>,
+        BatchableProof<DecryptionShareProof<G>>,
+    );
+
+    /// Every server proves its share of the same ciphertext. The share of `corrupted`, if any, is replaced after
+    /// the proof is made
+    fn shares_setup<D: SufficientOutput + Default, R: Rng>(
+        corrupted: Option<usize>,
+        rng: &mut R,
+    ) -> Vec<Share> {
+        let generator = G::prime_subgroup_generator();
+        let ciphertext = ElGamalCiphertext::new(G::rand(rng), G::rand(rng));
+
+        (0..SERVERS)
+            .map(|server| {
+                let secret_key = F::rand(rng);
+                let mut instance = DecryptionShareInstance::new(
+                    generator,
+                    generator.mul(secret_key.into_repr()),
+                    ciphertext,
+                    ciphertext.c1().mul(secret_key.into_repr()),
+                );
+                let nizk = NIZK::<DecryptionShareProof<_>, D>::new(&instance, CTX);
+                let proof = nizk.batchable_proof(&secret_key, None, rng).unwrap();
+
+                if corrupted == Some(server) {
+                    instance.share += generator;
+                }
+
+                (instance, proof)
+            })
+            .collect()
+    }
+
+    fn verify_individually<D: SufficientOutput + Default>(
+        shares: &[Share],
+    ) -> Vec<Result<(), SigmaError>> {
+        shares
+            .iter()
+            .map(|(instance, proof)| {
+                NIZK::<DecryptionShareProof<_>, D>::new(instance, CTX).batchable_verify(proof, None)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_decryption_share_accept_valid() {
+        let rng = &mut thread_rng();
+        let shares = shares_setup::<Hash, _>(None, rng);
+
+        assert!(verify_individually::<Hash>(&shares)
+            .iter()
+            .all(Result::is_ok));
+        assert!(DecryptionShareProof::batch_verify::<Hash, _>(&shares, CTX, None, rng).is_ok());
+    }
+
+    #[test]
+    fn test_decryption_share_batch_verify_expands_with_the_xof() {
+        let rng = &mut thread_rng();
+        let shares = shares_setup::<Shake, _>(None, rng);
+
+        // The batch must convert the challenges as the individual verifier does, with SHAKE128
+        assert!(verify_individually::<Shake>(&shares)
+            .iter()
+            .all(Result::is_ok));
+        assert!(DecryptionShareProof::batch_verify::<Shake, _>(&shares, CTX, None, rng).is_ok());
+
+        let corrupted = shares_setup::<Shake, _>(Some(1), rng);
+        assert_eq!(
+            DecryptionShareProof::batch_verify::<Shake, _>(&corrupted, CTX, None, rng),
+            Err(SigmaError::VerificationFailed)
+        );
+    }
+
+    #[test]
+    fn test_decryption_share_reject_corrupted() {
+        let rng = &mut thread_rng();
+        let shares = shares_setup::<Hash, _>(Some(2), rng);
+
+        let individual_results = verify_individually::<Hash>(&shares);
+        assert_eq!(individual_results[2], Err(SigmaError::VerificationFailed));
+        assert!(individual_results
+            .iter()
+            .enumerate()
+            .all(|(server, result)| server == 2 || result.is_ok()));
+        assert_eq!(
+            DecryptionShareProof::batch_verify::<Hash, _>(&shares, CTX, None, rng),
+            Err(SigmaError::VerificationFailed)
+        );
+    }
+
+    #[test]
+    fn test_decryption_share_label_differs_from_dleq() {
+        let rng = &mut thread_rng();
+        let (instance, _) = shares_setup::<Hash, _>(None, rng).remove(0);
+
+        assert_ne!(
+            DecryptionShareProof::new(&instance).label(),
+            DleqProof::new(&(&instance).into()).label()
+        );
+    }
+}
@@ -0,0 +1,350 @@
+use std::fmt;
+
+use ark_ec::ProjectiveCurve;
+use ark_ff::{Field, PrimeField};
+use ark_serialize::{CanonicalSerialize, SerializationError, Write};
+use ark_std::UniformRand;
+use rand::Rng;
+
+use super::challenge_scalar;
+use crate::{
+    group::wipe, interactive_proofs::canonical_bytes, secret::Redacted, Challenge,
+    ChallengeExpansion, CommitmentRecoverable, SigmaError, SigmaGroup, SigmaProtocol,
+};
+
+/// Chaum-Pedersen proof that two claims share the same discrete logarithm with respect to two bases.
+pub struct DleqProof<G: ProjectiveCurve> {
+    instance: DleqInstance<G>,
+}
+
+/// The instance for the DLEQ proof. The prover claims to know `x` such that
+/// `claim1 = x * base1` and `claim2 = x * base2`
+#[derive(Debug, Clone, Copy, CanonicalSerialize)]
+pub struct DleqInstance<G: ProjectiveCurve> {
+    base1: G,
+    claim1: G,
+    base2: G,
+    claim2: G,
+}
+
+impl<G: ProjectiveCurve> DleqInstance<G> {
+    /// Create a new DLEQ instance from the provided group elements
+    pub fn new(base1: G, claim1: G, base2: G, claim2: G) -> Self {
+        Self {
+            base1,
+            claim1,
+            base2,
+            claim2,
+        }
+    }
+
+    /// Create the DLEQ instance of `witness` over `base1` and `base2`, whose claims are the bases multiplied by the
+    /// witness
+    pub fn from_witness(base1: G, base2: G, witness: &G::ScalarField) -> Self {
+        let witness = witness.into_repr();
+        Self::new(base1, base1.mul(witness), base2, base2.mul(witness))
+    }
+}
+
+/// A wrapper type for a DLEQ witness
+pub type DleqWitness<G> = <G as ProjectiveCurve>::ScalarField;
+
+/// A wrapper type for a DLEQ commitment, one group element per base
+pub type DleqCommitment<G> = (G, G);
+
+/// A wrapper type for a DLEQ response
+pub type DleqResponse<G> = <G as ProjectiveCurve>::ScalarField;
+
+pub struct ProverState<F: Field> {
+    witness: F,
+    random_value: F,
+}
+
+impl<F: Field> fmt::Debug for ProverState<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ProverState")
+            .field("witness", &Redacted)
+            .field("random_value", &Redacted)
+            .finish()
+    }
+}
+
+impl<G: ProjectiveCurve> SigmaProtocol for DleqProof<G> {
+    type Instance = DleqInstance<G>;
+    type Commitment = DleqCommitment<G>;
+    type ProverState = ProverState<G::ScalarField>;
+    type Witness = DleqWitness<G>;
+    type Response = DleqResponse<G>;
+    type Challenge = G::ScalarField;
+
+    const PROTOCOL_ID: &'static [u8] = b"zkpstd/sigma/dleq";
+
+    fn new(instance: &DleqInstance<G>) -> Self {
+        Self {
+            instance: *instance,
+        }
+    }
+
+    fn challenge_from_bytes(
+        challenge: &Challenge,
+        expansion: ChallengeExpansion,
+    ) -> Result<G::ScalarField, SigmaError> {
+        challenge_scalar(challenge, expansion)
+    }
+
+    fn instance_bytes(&self) -> Vec<u8> {
+        canonical_bytes(&self.instance)
+    }
+
+    fn witness_bytes(&self, witness: &Self::Witness) -> Vec<u8> {
+        canonical_bytes(witness)
+    }
+
+    fn is_valid_witness(&self, witness: &Self::Witness) -> bool {
+        self.instance.base1.mul(witness.into_repr()) == self.instance.claim1
+            && self.instance.base2.mul(witness.into_repr()) == self.instance.claim2
+    }
+
+    fn prover_commit<R: Rng>(
+        &self,
+        witness: &Self::Witness,
+        _expansion: ChallengeExpansion,
+        rng: &mut R,
+    ) -> Result<(Self::Commitment, Self::ProverState), SigmaError> {
+        let random_value = G::ScalarField::rand(rng);
+
+        let commitment = (
+            self.instance.base1.mul(random_value.into_repr()),
+            self.instance.base2.mul(random_value.into_repr()),
+        );
+
+        let state = ProverState {
+            witness: *witness,
+            random_value,
+        };
+
+        Ok((commitment, state))
+    }
+
+    fn prover_response(
+        &self,
+        prover_state: &Self::ProverState,
+        challenge: &Self::Challenge,
+    ) -> Result<Self::Response, SigmaError> {
+        Ok(prover_state.random_value - *challenge * prover_state.witness)
+    }
+
+    fn verifier(
+        &self,
+        commitment: &Self::Commitment,
+        challenge: &Self::Challenge,
+        response: &Self::Response,
+    ) -> Result<(), SigmaError> {
+        if &self.simulate_commitment(challenge, response)? == commitment {
+            Ok(())
+        } else {
+            Err(SigmaError::VerificationFailed)
+        }
+    }
+
+    fn simulate_response<R: Rng>(&self, rng: &mut R) -> Self::Response {
+        G::ScalarField::rand(rng)
+    }
+
+    fn zeroize_state(&self, prover_state: &mut Self::ProverState) {
+        wipe(&mut prover_state.witness);
+        wipe(&mut prover_state.random_value);
+    }
+}
+
+impl<G: ProjectiveCurve> CommitmentRecoverable for DleqProof<G> {
+    fn simulate_commitment(
+        &self,
+        challenge: &Self::Challenge,
+        response: &Self::Response,
+    ) -> Result<Self::Commitment, SigmaError> {
+        let scalars = [*response, *challenge];
+
+        Ok((
+            G::multi_scalar_mul(&[self.instance.base1, self.instance.claim1], &scalars),
+            G::multi_scalar_mul(&[self.instance.base2, self.instance.claim2], &scalars),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_ec::ProjectiveCurve;
+    use ark_ff::{PrimeField, UniformRand};
+    use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+    use rand::{thread_rng, Rng};
+
+    use crate::{
+        nizk_proofs::tests::{check_prover_states_wiped, run_nizk_batched, run_nizk_short},
+        protocols::{SchnorrDLOG, SchnorrInstance},
+        testing::interactive::run_interactive,
+        BatchableProof, ChallengeExpansion, SigmaError, SigmaProtocol, NIZK,
+    };
+
+    use super::{DleqInstance, DleqProof};
+
+    type Hash = blake2::Blake2s;
+    type G = ark_bls12_377::G1Projective;
+    type F = ark_bls12_377::Fr;
+
+    fn dleq_setup<R: Rng>(rng: &mut R) -> (DleqInstance<G>, F, F) {
+        let base1 = G::prime_subgroup_generator();
+        let base2 = G::rand(rng);
+        let witness = F::rand(rng);
+        let instance = DleqInstance::from_witness(base1, base2, &witness);
+        let wrong_witness = F::rand(rng);
+
+        (instance, witness, wrong_witness)
+    }
+
+    #[test]
+    fn test_dleq_accept_valid_batchable() {
+        let rng = &mut thread_rng();
+        let (instance, witness, _) = dleq_setup(rng);
+
+        let test_result = run_nizk_batched::<Hash, DleqProof<_>, _>(&instance, &witness, rng);
+
+        assert!(test_result.is_ok())
+    }
+
+    #[test]
+    fn test_dleq_interactive_completeness() {
+        let rng = &mut thread_rng();
+        let (instance, witness, _) = dleq_setup(rng);
+
+        assert!(run_interactive::<DleqProof<_>, _>(&instance, &witness, rng).is_ok());
+    }
+
+    #[test]
+    fn test_dleq_is_valid_witness() {
+        let rng = &mut thread_rng();
+        let (instance, witness, wrong_witness) = dleq_setup(rng);
+        let protocol = DleqProof::new(&instance);
+
+        assert!(protocol.is_valid_witness(&witness));
+        assert!(!protocol.is_valid_witness(&wrong_witness));
+        // The witness must be the discrete logarithm of both claims
+        let unequal = DleqInstance::new(
+            instance.base1,
+            instance.claim1,
+            instance.base2,
+            G::rand(rng),
+        );
+        assert!(!DleqProof::new(&unequal).is_valid_witness(&witness));
+        assert!(DleqProof::new(&DleqInstance::from_witness(
+            G::rand(rng),
+            G::rand(rng),
+            &witness
+        ))
+        .is_valid_witness(&witness));
+    }
+
+    #[test]
+    fn test_dleq_reject_wrong_batchable() {
+        let rng = &mut thread_rng();
+        let (instance, _, wrong_witness) = dleq_setup(rng);
+
+        let test_result = run_nizk_batched::<Hash, DleqProof<_>, _>(&instance, &wrong_witness, rng);
+
+        assert_eq!(test_result, Err(SigmaError::VerificationFailed))
+    }
+
+    #[test]
+    fn test_dleq_accept_valid_short() {
+        let rng = &mut thread_rng();
+        let (instance, witness, _) = dleq_setup(rng);
+
+        let test_result = run_nizk_short::<Hash, DleqProof<_>, _>(&instance, &witness, rng);
+
+        assert!(test_result.is_ok())
+    }
+
+    #[test]
+    fn test_dleq_reject_wrong_short() {
+        let rng = &mut thread_rng();
+        let (instance, _, wrong_witness) = dleq_setup(rng);
+
+        let test_result = run_nizk_short::<Hash, DleqProof<_>, _>(&instance, &wrong_witness, rng);
+
+        assert_eq!(test_result, Err(SigmaError::VerificationFailed))
+    }
+
+    #[test]
+    fn test_dleq_reject_unequal_logs() {
+        let rng = &mut thread_rng();
+        let (instance, witness, wrong_witness) = dleq_setup(rng);
+        // The second claim uses a different exponent than the first
+        let instance = DleqInstance::new(
+            instance.base1,
+            instance.claim1,
+            instance.base2,
+            instance.base2.mul(wrong_witness.into_repr()),
+        );
+
+        let test_result = run_nizk_batched::<Hash, DleqProof<_>, _>(&instance, &witness, rng);
+
+        assert_eq!(test_result, Err(SigmaError::VerificationFailed))
+    }
+
+    #[test]
+    fn test_dleq_proof_does_not_verify_as_schnorr() {
+        let rng = &mut thread_rng();
+        let (instance, witness, _) = dleq_setup(rng);
+        let nizk = NIZK::<DleqProof<G>, Hash>::new(&instance, b"ctx");
+        let proof = nizk.batchable_proof(&witness, None, rng).unwrap();
+        let challenge = nizk.batchable_challenge(&proof, None).unwrap();
+
+        // The first half of the transcript is a valid Schnorr transcript for `claim1 = x * base1`
+        let schnorr_instance = SchnorrInstance::new(instance.base1, instance.claim1);
+        let (commitment1, _) = proof.commitment();
+        assert!(SchnorrDLOG::new(&schnorr_instance)
+            .verifier(
+                commitment1,
+                &SchnorrDLOG::<G>::challenge_from_bytes(&challenge, ChallengeExpansion::default())
+                    .unwrap(),
+                proof.response()
+            )
+            .is_ok());
+
+        // but the Schnorr challenge differs, as it hashes another label and instance
+        let mut bytes = Vec::new();
+        commitment1.serialize(&mut bytes).unwrap();
+        proof.response().serialize(&mut bytes).unwrap();
+        let schnorr_proof = BatchableProof::<SchnorrDLOG<G>>::deserialize(&bytes[..]).unwrap();
+        let schnorr_nizk = NIZK::<SchnorrDLOG<G>, Hash>::new(&schnorr_instance, b"ctx");
+
+        assert_eq!(
+            schnorr_nizk.batchable_verify(&schnorr_proof, None),
+            Err(SigmaError::VerificationFailed)
+        );
+    }
+
+    #[test]
+    fn test_nizk_wipes_prover_states() {
+        let rng = &mut thread_rng();
+        let (instance, witness, _) = dleq_setup(rng);
+        check_prover_states_wiped::<Hash, DleqProof<G>, _>(&instance, &witness, rng);
+    }
+
+    #[cfg(feature = "zeroize")]
+    #[test]
+    fn test_prover_state_is_zeroized() {
+        use ark_ff::Zero;
+
+        let rng = &mut thread_rng();
+        let (instance, witness, _) = dleq_setup(rng);
+        let protocol = DleqProof::new(&instance);
+        let (_, mut state) = protocol
+            .prover_commit(&witness, ChallengeExpansion::default(), rng)
+            .unwrap();
+
+        protocol.zeroize_state(&mut state);
+        assert!(state.witness.is_zero());
+        assert!(state.random_value.is_zero());
+    }
+}
@@ -0,0 +1,262 @@
+use std::fmt;
+
+use ark_ec::ProjectiveCurve;
+use ark_ff::{Field, PrimeField};
+use ark_serialize::{CanonicalSerialize, SerializationError, Write};
+use ark_std::UniformRand;
+use rand::Rng;
+
+use super::challenge_scalar;
+use crate::{
+    group::wipe, interactive_proofs::canonical_bytes, secret::Redacted, Challenge,
+    ChallengeExpansion, SigmaError, SigmaProtocol,
+};
+
+/// Camenisch-Shoup proof that two claims have different discrete logarithms with respect to two bases.
+///
+/// For `claim1 = x * base1` and `claim2 = y * base2`, the prover knows `x` and picks a random `r`. It publishes the
+/// auxiliary element `C = r * (x * base2 - claim2) = r * (x - y) * base2`, which is the identity exactly when
+/// `x = y`, and proves knowledge of `(a, b) = (r * x, -r)` such that
+///
+/// - `C = a * base2 + b * claim2`
+/// - `0 = a * base1 + b * claim1`
+///
+/// The verifier additionally checks that `C` is not the identity. Since `C` cannot be recomputed from a challenge
+/// and response, only batchable proofs are supported.
+pub struct DlogInequality<G: ProjectiveCurve> {
+    instance: DlogInequalityInstance<G>,
+}
+
+/// The instance for the inequality of discrete logarithms proof. The prover claims to know `x` such that
+/// `claim1 = x * base1` and `claim2 != x * base2`
+#[derive(Debug, Clone, Copy, CanonicalSerialize)]
+pub struct DlogInequalityInstance<G: ProjectiveCurve> {
+    base1: G,
+    claim1: G,
+    base2: G,
+    claim2: G,
+}
+
+impl<G: ProjectiveCurve> DlogInequalityInstance<G> {
+    /// Create a new inequality of discrete logarithms instance from the provided group elements
+    pub fn new(base1: G, claim1: G, base2: G, claim2: G) -> Self {
+        Self {
+            base1,
+            claim1,
+            base2,
+            claim2,
+        }
+    }
+}
+
+/// A wrapper type for an inequality of discrete logarithms witness: the logarithm of `claim1`
+pub type DlogInequalityWitness<G> = <G as ProjectiveCurve>::ScalarField;
+
+/// A wrapper type for an inequality of discrete logarithms commitment: the auxiliary element followed by one group
+/// element per relation
+pub type DlogInequalityCommitment<G> = (G, G, G);
+
+/// A wrapper type for an inequality of discrete logarithms response, one scalar per exponent of the auxiliary
+/// element
+pub type DlogInequalityResponse<G> = (
+    <G as ProjectiveCurve>::ScalarField,
+    <G as ProjectiveCurve>::ScalarField,
+);
+
+pub struct ProverState<F: Field> {
+    witness: (F, F),
+    random_values: (F, F),
+}
+
+impl<F: Field> fmt::Debug for ProverState<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ProverState")
+            .field("witness", &Redacted)
+            .field("random_values", &Redacted)
+            .finish()
+    }
+}
+
+impl<G: ProjectiveCurve> DlogInequality<G> {
+    /// Recomputes the commitments to both relations for the given auxiliary element
+    fn relation_commitments(
+        &self,
+        auxiliary: G,
+        challenge: &G::ScalarField,
+        response: &DlogInequalityResponse<G>,
+    ) -> Result<(G, G), SigmaError> {
+        let challenge = challenge.into_repr();
+        let (response1, response2) = (response.0.into_repr(), response.1.into_repr());
+
+        Ok((
+            self.instance.base2.mul(response1)
+                + self.instance.claim2.mul(response2)
+                + auxiliary.mul(challenge),
+            self.instance.base1.mul(response1) + self.instance.claim1.mul(response2),
+        ))
+    }
+}
+
+impl<G: ProjectiveCurve> SigmaProtocol for DlogInequality<G> {
+    type Instance = DlogInequalityInstance<G>;
+    type Commitment = DlogInequalityCommitment<G>;
+    type ProverState = ProverState<G::ScalarField>;
+    type Witness = DlogInequalityWitness<G>;
+    type Response = DlogInequalityResponse<G>;
+    type Challenge = G::ScalarField;
+
+    const PROTOCOL_ID: &'static [u8] = b"zkpstd/sigma/dlog-inequality";
+
+    fn new(instance: &DlogInequalityInstance<G>) -> Self {
+        Self {
+            instance: *instance,
+        }
+    }
+
+    fn challenge_from_bytes(
+        challenge: &Challenge,
+        expansion: ChallengeExpansion,
+    ) -> Result<G::ScalarField, SigmaError> {
+        challenge_scalar(challenge, expansion)
+    }
+
+    fn instance_bytes(&self) -> Vec<u8> {
+        canonical_bytes(&self.instance)
+    }
+
+    fn witness_bytes(&self, witness: &Self::Witness) -> Vec<u8> {
+        canonical_bytes(witness)
+    }
+
+    fn prover_commit<R: Rng>(
+        &self,
+        witness: &Self::Witness,
+        _expansion: ChallengeExpansion,
+        rng: &mut R,
+    ) -> Result<(Self::Commitment, Self::ProverState), SigmaError> {
+        let blinding = G::ScalarField::rand(rng);
+        let auxiliary = (self.instance.base2.mul(witness.into_repr()) - self.instance.claim2)
+            .mul(blinding.into_repr());
+
+        let random_values = (G::ScalarField::rand(rng), G::ScalarField::rand(rng));
+        let (random1, random2) = (random_values.0.into_repr(), random_values.1.into_repr());
+
+        let commitment = (
+            auxiliary,
+            self.instance.base2.mul(random1) + self.instance.claim2.mul(random2),
+            self.instance.base1.mul(random1) + self.instance.claim1.mul(random2),
+        );
+
+        let state = ProverState {
+            witness: (blinding * witness, -blinding),
+            random_values,
+        };
+
+        Ok((commitment, state))
+    }
+
+    fn prover_response(
+        &self,
+        prover_state: &Self::ProverState,
+        challenge: &Self::Challenge,
+    ) -> Result<Self::Response, SigmaError> {
+        Ok((
+            prover_state.random_values.0 - *challenge * prover_state.witness.0,
+            prover_state.random_values.1 - *challenge * prover_state.witness.1,
+        ))
+    }
+
+    fn verifier(
+        &self,
+        commitment: &Self::Commitment,
+        challenge: &Self::Challenge,
+        response: &Self::Response,
+    ) -> Result<(), SigmaError> {
+        let (auxiliary, commitment1, commitment2) = *commitment;
+        if auxiliary.is_zero() {
+            return Err(SigmaError::VerificationFailed);
+        }
+
+        if self.relation_commitments(auxiliary, challenge, response)? == (commitment1, commitment2)
+        {
+            Ok(())
+        } else {
+            Err(SigmaError::VerificationFailed)
+        }
+    }
+
+    fn simulate_response<R: Rng>(&self, rng: &mut R) -> Self::Response {
+        (G::ScalarField::rand(rng), G::ScalarField::rand(rng))
+    }
+
+    fn zeroize_state(&self, prover_state: &mut Self::ProverState) {
+        wipe(&mut prover_state.witness);
+        wipe(&mut prover_state.random_values);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_ec::ProjectiveCurve;
+    use ark_ff::{PrimeField, UniformRand};
+    use rand::{thread_rng, Rng};
+
+    use crate::{nizk_proofs::tests::run_nizk_batched, SigmaError};
+
+    use super::{DlogInequality, DlogInequalityInstance};
+
+    type Hash = blake2::Blake2s;
+    type G = ark_bls12_377::G1Projective;
+    type F = ark_bls12_377::Fr;
+
+    /// Builds an instance and the logarithm of `claim1`. The logarithm of `claim2` is the same one if `equal_logs`
+    fn dlog_inequality_setup<R: Rng>(
+        equal_logs: bool,
+        rng: &mut R,
+    ) -> (DlogInequalityInstance<G>, F) {
+        let (base1, base2) = (G::rand(rng), G::rand(rng));
+        let witness = F::rand(rng);
+        let log2 = if equal_logs { witness } else { F::rand(rng) };
+
+        let instance = DlogInequalityInstance::new(
+            base1,
+            base1.mul(witness.into_repr()),
+            base2,
+            base2.mul(log2.into_repr()),
+        );
+
+        (instance, witness)
+    }
+
+    #[test]
+    fn test_dlog_inequality_accept_different_logs() {
+        let rng = &mut thread_rng();
+        let (instance, witness) = dlog_inequality_setup(false, rng);
+
+        let test_result = run_nizk_batched::<Hash, DlogInequality<_>, _>(&instance, &witness, rng);
+
+        assert!(test_result.is_ok());
+    }
+
+    #[test]
+    fn test_dlog_inequality_reject_equal_logs() {
+        let rng = &mut thread_rng();
+        let (instance, witness) = dlog_inequality_setup(true, rng);
+
+        // The honest prover's auxiliary element is the identity
+        let test_result = run_nizk_batched::<Hash, DlogInequality<_>, _>(&instance, &witness, rng);
+
+        assert_eq!(test_result, Err(SigmaError::VerificationFailed));
+    }
+
+    #[test]
+    fn test_dlog_inequality_reject_wrong_witness() {
+        let rng = &mut thread_rng();
+        let (instance, _) = dlog_inequality_setup(false, rng);
+
+        let test_result =
+            run_nizk_batched::<Hash, DlogInequality<_>, _>(&instance, &F::rand(rng), rng);
+
+        assert_eq!(test_result, Err(SigmaError::VerificationFailed));
+    }
+}
@@ -0,0 +1,43 @@
+use ark_ec::ProjectiveCurve;
+use ark_ff::PrimeField;
+use ark_serialize::{CanonicalSerialize, SerializationError, Write};
+
+/// An ElGamal ciphertext `(c1, c2) = (r * G, M + r * PK)` of the message point `M`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, CanonicalSerialize)]
+pub struct ElGamalCiphertext<G: ProjectiveCurve> {
+    c1: G,
+    c2: G,
+}
+
+impl<G: ProjectiveCurve> ElGamalCiphertext<G> {
+    /// Create a ciphertext from its two components
+    pub fn new(c1: G, c2: G) -> Self {
+        Self { c1, c2 }
+    }
+
+    /// Encrypt the message point `message` under `public_key` with the provided randomness
+    pub fn encrypt(generator: G, public_key: G, message: G, randomness: &G::ScalarField) -> Self {
+        Self {
+            c1: generator.mul(randomness.into_repr()),
+            c2: message + public_key.mul(randomness.into_repr()),
+        }
+    }
+
+    /// Re-encrypt the ciphertext under the same public key by adding a fresh encryption of the identity
+    pub fn rerandomize(&self, generator: G, public_key: G, randomness: &G::ScalarField) -> Self {
+        Self {
+            c1: self.c1 + generator.mul(randomness.into_repr()),
+            c2: self.c2 + public_key.mul(randomness.into_repr()),
+        }
+    }
+
+    /// The randomness component `r * G`
+    pub fn c1(&self) -> G {
+        self.c1
+    }
+
+    /// The masked message component `M + r * PK`
+    pub fn c2(&self) -> G {
+        self.c2
+    }
+}
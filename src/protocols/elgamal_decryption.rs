@@ -0,0 +1,203 @@
+use ark_ec::ProjectiveCurve;
+use rand::Rng;
+
+use super::{DleqInstance, DleqProof, ElGamalCiphertext};
+use crate::{Challenge, ChallengeExpansion, CommitmentRecoverable, SigmaError, SigmaProtocol};
+
+/// Proof that a plaintext point is the correct decryption of an ElGamal ciphertext.
+///
+/// This is a DLEQ proof of `PK = sk * G` and `c2 - M = sk * c1`, but with its own label so that its transcripts
+/// cannot be confused with those of a generic [`DleqProof`].
+pub struct CorrectDecryption<G: ProjectiveCurve> {
+    dleq: DleqProof<G>,
+}
+
+/// The instance for the correct decryption proof: the generator, the public key, the ciphertext and the
+/// claimed plaintext point
+#[derive(Debug, Clone, Copy)]
+pub struct CorrectDecryptionInstance<G: ProjectiveCurve> {
+    generator: G,
+    public_key: G,
+    ciphertext: ElGamalCiphertext<G>,
+    plaintext: G,
+}
+
+impl<G: ProjectiveCurve> CorrectDecryptionInstance<G> {
+    /// Create a new correct decryption instance from the provided group elements
+    pub fn new(
+        generator: G,
+        public_key: G,
+        ciphertext: ElGamalCiphertext<G>,
+        plaintext: G,
+    ) -> Self {
+        Self {
+            generator,
+            public_key,
+            ciphertext,
+            plaintext,
+        }
+    }
+}
+
+impl<G: ProjectiveCurve> From<&CorrectDecryptionInstance<G>> for DleqInstance<G> {
+    fn from(instance: &CorrectDecryptionInstance<G>) -> Self {
+        DleqInstance::new(
+            instance.generator,
+            instance.public_key,
+            instance.ciphertext.c1(),
+            instance.ciphertext.c2() - instance.plaintext,
+        )
+    }
+}
+
+/// A wrapper type for a correct decryption witness: the secret key
+pub type CorrectDecryptionWitness<G> = <G as ProjectiveCurve>::ScalarField;
+
+impl<G: ProjectiveCurve> SigmaProtocol for CorrectDecryption<G> {
+    type Instance = CorrectDecryptionInstance<G>;
+    type Commitment = <DleqProof<G> as SigmaProtocol>::Commitment;
+    type ProverState = <DleqProof<G> as SigmaProtocol>::ProverState;
+    type Witness = CorrectDecryptionWitness<G>;
+    type Response = <DleqProof<G> as SigmaProtocol>::Response;
+    type Challenge = <DleqProof<G> as SigmaProtocol>::Challenge;
+
+    const PROTOCOL_ID: &'static [u8] = b"zkpstd/sigma/elgamal-decryption";
+
+    fn new(instance: &CorrectDecryptionInstance<G>) -> Self {
+        Self {
+            dleq: DleqProof::new(&instance.into()),
+        }
+    }
+
+    fn challenge_from_bytes(
+        challenge: &Challenge,
+        expansion: ChallengeExpansion,
+    ) -> Result<Self::Challenge, SigmaError> {
+        DleqProof::<G>::challenge_from_bytes(challenge, expansion)
+    }
+
+    fn instance_bytes(&self) -> Vec<u8> {
+        self.dleq.instance_bytes()
+    }
+
+    fn witness_bytes(&self, witness: &Self::Witness) -> Vec<u8> {
+        self.dleq.witness_bytes(witness)
+    }
+
+    fn prover_commit<R: Rng>(
+        &self,
+        witness: &Self::Witness,
+        expansion: ChallengeExpansion,
+        rng: &mut R,
+    ) -> Result<(Self::Commitment, Self::ProverState), SigmaError> {
+        self.dleq.prover_commit(witness, expansion, rng)
+    }
+
+    fn prover_response(
+        &self,
+        prover_state: &Self::ProverState,
+        challenge: &Self::Challenge,
+    ) -> Result<Self::Response, SigmaError> {
+        self.dleq.prover_response(prover_state, challenge)
+    }
+
+    fn verifier(
+        &self,
+        commitment: &Self::Commitment,
+        challenge: &Self::Challenge,
+        response: &Self::Response,
+    ) -> Result<(), SigmaError> {
+        self.dleq.verifier(commitment, challenge, response)
+    }
+
+    fn simulate_response<R: Rng>(&self, rng: &mut R) -> Self::Response {
+        self.dleq.simulate_response(rng)
+    }
+
+    fn zeroize_state(&self, prover_state: &mut Self::ProverState) {
+        self.dleq.zeroize_state(prover_state);
+    }
+}
+
+impl<G: ProjectiveCurve> CommitmentRecoverable for CorrectDecryption<G> {
+    fn simulate_commitment(
+        &self,
+        challenge: &Self::Challenge,
+        response: &Self::Response,
+    ) -> Result<Self::Commitment, SigmaError> {
+        self.dleq.simulate_commitment(challenge, response)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_ec::ProjectiveCurve;
+    use ark_ff::{PrimeField, UniformRand};
+    use rand::{thread_rng, Rng};
+
+    use crate::{
+        nizk_proofs::tests::{run_nizk_batched, run_nizk_short},
+        protocols::{DleqProof, ElGamalCiphertext},
+        SigmaError, SigmaProtocol,
+    };
+
+    use super::{CorrectDecryption, CorrectDecryptionInstance};
+
+    type Hash = blake2::Blake2s;
+    type G = ark_bls12_377::G1Projective;
+    type F = ark_bls12_377::Fr;
+
+    fn decryption_setup<R: Rng>(rng: &mut R) -> (G, G, ElGamalCiphertext<G>, G, F) {
+        let generator = G::prime_subgroup_generator();
+        let secret_key = F::rand(rng);
+        let public_key = generator.mul(secret_key.into_repr());
+        let plaintext = G::rand(rng);
+        let ciphertext =
+            ElGamalCiphertext::encrypt(generator, public_key, plaintext, &F::rand(rng));
+
+        (generator, public_key, ciphertext, plaintext, secret_key)
+    }
+
+    #[test]
+    fn test_correct_decryption_accept_valid() {
+        let rng = &mut thread_rng();
+        let (generator, public_key, ciphertext, plaintext, secret_key) = decryption_setup(rng);
+        let instance = CorrectDecryptionInstance::new(generator, public_key, ciphertext, plaintext);
+
+        let batched_result =
+            run_nizk_batched::<Hash, CorrectDecryption<_>, _>(&instance, &secret_key, rng);
+        let short_result =
+            run_nizk_short::<Hash, CorrectDecryption<_>, _>(&instance, &secret_key, rng);
+
+        assert!(batched_result.is_ok());
+        assert!(short_result.is_ok());
+    }
+
+    #[test]
+    fn test_correct_decryption_reject_wrong_plaintext() {
+        let rng = &mut thread_rng();
+        let (generator, public_key, ciphertext, _, secret_key) = decryption_setup(rng);
+        let instance =
+            CorrectDecryptionInstance::new(generator, public_key, ciphertext, G::rand(rng));
+
+        let batched_result =
+            run_nizk_batched::<Hash, CorrectDecryption<_>, _>(&instance, &secret_key, rng);
+        let short_result =
+            run_nizk_short::<Hash, CorrectDecryption<_>, _>(&instance, &secret_key, rng);
+
+        assert_eq!(batched_result, Err(SigmaError::VerificationFailed));
+        assert_eq!(short_result, Err(SigmaError::VerificationFailed));
+    }
+
+    #[test]
+    fn test_correct_decryption_label_differs_from_dleq() {
+        let rng = &mut thread_rng();
+        let (generator, public_key, ciphertext, plaintext, _) = decryption_setup(rng);
+        let instance = CorrectDecryptionInstance::new(generator, public_key, ciphertext, plaintext);
+
+        assert_ne!(
+            CorrectDecryption::new(&instance).label(),
+            DleqProof::new(&(&instance).into()).label()
+        );
+    }
+}
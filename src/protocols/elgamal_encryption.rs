@@ -0,0 +1,256 @@
+use std::fmt;
+
+use ark_ec::ProjectiveCurve;
+use ark_ff::{Field, PrimeField};
+use ark_serialize::{CanonicalSerialize, SerializationError, Write};
+use ark_std::UniformRand;
+use rand::Rng;
+
+use super::{challenge_scalar, ElGamalCiphertext};
+use crate::{
+    group::wipe, interactive_proofs::canonical_bytes, secret::Redacted, Challenge,
+    ChallengeExpansion, CommitmentRecoverable, SigmaError, SigmaProtocol,
+};
+
+/// Proof that an ElGamal ciphertext `(r * G, m * G + r * PK)` was produced with a message `m` and randomness `r`
+/// known to the prover.
+pub struct ElGamalEncryption<G: ProjectiveCurve> {
+    instance: ElGamalEncryptionInstance<G>,
+}
+
+/// The instance for the ElGamal encryption proof: the generator, the public key and the ciphertext
+#[derive(Debug, Clone, Copy, CanonicalSerialize)]
+pub struct ElGamalEncryptionInstance<G: ProjectiveCurve> {
+    generator: G,
+    public_key: G,
+    ciphertext: ElGamalCiphertext<G>,
+}
+
+impl<G: ProjectiveCurve> ElGamalEncryptionInstance<G> {
+    /// Create a new ElGamal encryption instance from the provided group elements
+    pub fn new(generator: G, public_key: G, ciphertext: ElGamalCiphertext<G>) -> Self {
+        Self {
+            generator,
+            public_key,
+            ciphertext,
+        }
+    }
+}
+
+/// A wrapper type for an ElGamal encryption witness: the message and the encryption randomness
+pub type ElGamalEncryptionWitness<G> = (
+    <G as ProjectiveCurve>::ScalarField,
+    <G as ProjectiveCurve>::ScalarField,
+);
+
+/// A wrapper type for an ElGamal encryption commitment, one group element per ciphertext component
+pub type ElGamalEncryptionCommitment<G> = (G, G);
+
+/// A wrapper type for an ElGamal encryption response, in the same order as the witness
+pub type ElGamalEncryptionResponse<G> = ElGamalEncryptionWitness<G>;
+
+pub struct ProverState<F: Field> {
+    witness: (F, F),
+    random_values: (F, F),
+}
+
+impl<F: Field> fmt::Debug for ProverState<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ProverState")
+            .field("witness", &Redacted)
+            .field("random_values", &Redacted)
+            .finish()
+    }
+}
+
+impl<G: ProjectiveCurve> SigmaProtocol for ElGamalEncryption<G> {
+    type Instance = ElGamalEncryptionInstance<G>;
+    type Commitment = ElGamalEncryptionCommitment<G>;
+    type ProverState = ProverState<G::ScalarField>;
+    type Witness = ElGamalEncryptionWitness<G>;
+    type Response = ElGamalEncryptionResponse<G>;
+    type Challenge = G::ScalarField;
+
+    const PROTOCOL_ID: &'static [u8] = b"zkpstd/sigma/elgamal-encryption";
+
+    fn new(instance: &ElGamalEncryptionInstance<G>) -> Self {
+        Self {
+            instance: *instance,
+        }
+    }
+
+    fn challenge_from_bytes(
+        challenge: &Challenge,
+        expansion: ChallengeExpansion,
+    ) -> Result<G::ScalarField, SigmaError> {
+        challenge_scalar(challenge, expansion)
+    }
+
+    fn instance_bytes(&self) -> Vec<u8> {
+        canonical_bytes(&self.instance)
+    }
+
+    fn witness_bytes(&self, witness: &Self::Witness) -> Vec<u8> {
+        canonical_bytes(witness)
+    }
+
+    fn prover_commit<R: Rng>(
+        &self,
+        witness: &Self::Witness,
+        _expansion: ChallengeExpansion,
+        rng: &mut R,
+    ) -> Result<(Self::Commitment, Self::ProverState), SigmaError> {
+        let random_values = (G::ScalarField::rand(rng), G::ScalarField::rand(rng));
+
+        let commitment = ElGamalCiphertext::encrypt(
+            self.instance.generator,
+            self.instance.public_key,
+            self.instance.generator.mul(random_values.0.into_repr()),
+            &random_values.1,
+        );
+
+        let state = ProverState {
+            witness: *witness,
+            random_values,
+        };
+
+        Ok(((commitment.c1(), commitment.c2()), state))
+    }
+
+    fn prover_response(
+        &self,
+        prover_state: &Self::ProverState,
+        challenge: &Self::Challenge,
+    ) -> Result<Self::Response, SigmaError> {
+        Ok((
+            prover_state.random_values.0 - *challenge * prover_state.witness.0,
+            prover_state.random_values.1 - *challenge * prover_state.witness.1,
+        ))
+    }
+
+    fn verifier(
+        &self,
+        commitment: &Self::Commitment,
+        challenge: &Self::Challenge,
+        response: &Self::Response,
+    ) -> Result<(), SigmaError> {
+        if &self.simulate_commitment(challenge, response)? == commitment {
+            Ok(())
+        } else {
+            Err(SigmaError::VerificationFailed)
+        }
+    }
+
+    fn simulate_response<R: Rng>(&self, rng: &mut R) -> Self::Response {
+        (G::ScalarField::rand(rng), G::ScalarField::rand(rng))
+    }
+
+    fn zeroize_state(&self, prover_state: &mut Self::ProverState) {
+        wipe(&mut prover_state.witness);
+        wipe(&mut prover_state.random_values);
+    }
+}
+
+impl<G: ProjectiveCurve> CommitmentRecoverable for ElGamalEncryption<G> {
+    fn simulate_commitment(
+        &self,
+        challenge: &Self::Challenge,
+        response: &Self::Response,
+    ) -> Result<Self::Commitment, SigmaError> {
+        let challenge = challenge.into_repr();
+        let (s_m, s_r) = response;
+        let generator = self.instance.generator;
+        let ciphertext = self.instance.ciphertext;
+
+        Ok((
+            generator.mul(s_r.into_repr()) + ciphertext.c1().mul(challenge),
+            generator.mul(s_m.into_repr())
+                + self.instance.public_key.mul(s_r.into_repr())
+                + ciphertext.c2().mul(challenge),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_ec::ProjectiveCurve;
+    use ark_ff::{PrimeField, UniformRand};
+    use rand::{thread_rng, Rng};
+
+    use crate::{
+        nizk_proofs::tests::{run_nizk_batched, run_nizk_short},
+        protocols::ElGamalCiphertext,
+        SigmaError,
+    };
+
+    use super::{ElGamalEncryption, ElGamalEncryptionInstance};
+
+    type Hash = blake2::Blake2s;
+    type G = ark_bls12_377::G1Projective;
+    type F = ark_bls12_377::Fr;
+
+    fn elgamal_setup<R: Rng>(
+        message: F,
+        randomness: F,
+        rng: &mut R,
+    ) -> ElGamalEncryptionInstance<G> {
+        let generator = G::prime_subgroup_generator();
+        let public_key = generator.mul(F::rand(rng).into_repr());
+        let ciphertext = ElGamalCiphertext::encrypt(
+            generator,
+            public_key,
+            generator.mul(message.into_repr()),
+            &randomness,
+        );
+
+        ElGamalEncryptionInstance::new(generator, public_key, ciphertext)
+    }
+
+    fn run_both(
+        instance: &ElGamalEncryptionInstance<G>,
+        witness: &(F, F),
+    ) -> (Result<(), SigmaError>, Result<(), SigmaError>) {
+        let rng = &mut thread_rng();
+
+        (
+            run_nizk_batched::<Hash, ElGamalEncryption<_>, _>(instance, witness, rng),
+            run_nizk_short::<Hash, ElGamalEncryption<_>, _>(instance, witness, rng),
+        )
+    }
+
+    #[test]
+    fn test_elgamal_encryption_accept_valid() {
+        let rng = &mut thread_rng();
+        let witness = (F::rand(rng), F::rand(rng));
+        let instance = elgamal_setup(witness.0, witness.1, rng);
+
+        let (batched_result, short_result) = run_both(&instance, &witness);
+
+        assert!(batched_result.is_ok());
+        assert!(short_result.is_ok());
+    }
+
+    #[test]
+    fn test_elgamal_encryption_reject_different_randomness() {
+        let rng = &mut thread_rng();
+        let witness = (F::rand(rng), F::rand(rng));
+        let instance = elgamal_setup(witness.0, F::rand(rng), rng);
+
+        let (batched_result, short_result) = run_both(&instance, &witness);
+
+        assert_eq!(batched_result, Err(SigmaError::VerificationFailed));
+        assert_eq!(short_result, Err(SigmaError::VerificationFailed));
+    }
+
+    #[test]
+    fn test_elgamal_encryption_reject_different_message() {
+        let rng = &mut thread_rng();
+        let witness = (F::rand(rng), F::rand(rng));
+        let instance = elgamal_setup(F::rand(rng), witness.1, rng);
+
+        let (batched_result, short_result) = run_both(&instance, &witness);
+
+        assert_eq!(batched_result, Err(SigmaError::VerificationFailed));
+        assert_eq!(short_result, Err(SigmaError::VerificationFailed));
+    }
+}
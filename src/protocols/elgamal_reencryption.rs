@@ -0,0 +1,188 @@
+use ark_ec::ProjectiveCurve;
+use rand::Rng;
+
+use super::{DleqInstance, DleqProof, ElGamalCiphertext};
+use crate::{Challenge, ChallengeExpansion, CommitmentRecoverable, SigmaError, SigmaProtocol};
+
+/// Proof that an ElGamal ciphertext `(d1, d2)` is a re-encryption of `(c1, c2)` under the same public key.
+///
+/// This is a DLEQ proof of `d1 - c1 = s * G` and `d2 - c2 = s * PK` for the re-encryption randomness `s`.
+pub struct ReEncryption<G: ProjectiveCurve> {
+    dleq: DleqProof<G>,
+}
+
+/// The instance for the re-encryption proof: the generator, the public key, the original ciphertext and
+/// its claimed re-encryption
+#[derive(Debug, Clone, Copy)]
+pub struct ReEncryptionInstance<G: ProjectiveCurve> {
+    generator: G,
+    public_key: G,
+    original: ElGamalCiphertext<G>,
+    reencrypted: ElGamalCiphertext<G>,
+}
+
+impl<G: ProjectiveCurve> ReEncryptionInstance<G> {
+    /// Create a new re-encryption instance from the provided group elements
+    pub fn new(
+        generator: G,
+        public_key: G,
+        original: ElGamalCiphertext<G>,
+        reencrypted: ElGamalCiphertext<G>,
+    ) -> Self {
+        Self {
+            generator,
+            public_key,
+            original,
+            reencrypted,
+        }
+    }
+}
+
+impl<G: ProjectiveCurve> From<&ReEncryptionInstance<G>> for DleqInstance<G> {
+    fn from(instance: &ReEncryptionInstance<G>) -> Self {
+        DleqInstance::new(
+            instance.generator,
+            instance.reencrypted.c1() - instance.original.c1(),
+            instance.public_key,
+            instance.reencrypted.c2() - instance.original.c2(),
+        )
+    }
+}
+
+/// A wrapper type for a re-encryption witness: the re-encryption randomness
+pub type ReEncryptionWitness<G> = <G as ProjectiveCurve>::ScalarField;
+
+impl<G: ProjectiveCurve> SigmaProtocol for ReEncryption<G> {
+    type Instance = ReEncryptionInstance<G>;
+    type Commitment = <DleqProof<G> as SigmaProtocol>::Commitment;
+    type ProverState = <DleqProof<G> as SigmaProtocol>::ProverState;
+    type Witness = ReEncryptionWitness<G>;
+    type Response = <DleqProof<G> as SigmaProtocol>::Response;
+    type Challenge = <DleqProof<G> as SigmaProtocol>::Challenge;
+
+    const PROTOCOL_ID: &'static [u8] = b"zkpstd/sigma/elgamal-reencrypt";
+
+    fn new(instance: &ReEncryptionInstance<G>) -> Self {
+        Self {
+            dleq: DleqProof::new(&instance.into()),
+        }
+    }
+
+    fn challenge_from_bytes(
+        challenge: &Challenge,
+        expansion: ChallengeExpansion,
+    ) -> Result<Self::Challenge, SigmaError> {
+        DleqProof::<G>::challenge_from_bytes(challenge, expansion)
+    }
+
+    fn instance_bytes(&self) -> Vec<u8> {
+        self.dleq.instance_bytes()
+    }
+
+    fn witness_bytes(&self, witness: &Self::Witness) -> Vec<u8> {
+        self.dleq.witness_bytes(witness)
+    }
+
+    fn prover_commit<R: Rng>(
+        &self,
+        witness: &Self::Witness,
+        expansion: ChallengeExpansion,
+        rng: &mut R,
+    ) -> Result<(Self::Commitment, Self::ProverState), SigmaError> {
+        self.dleq.prover_commit(witness, expansion, rng)
+    }
+
+    fn prover_response(
+        &self,
+        prover_state: &Self::ProverState,
+        challenge: &Self::Challenge,
+    ) -> Result<Self::Response, SigmaError> {
+        self.dleq.prover_response(prover_state, challenge)
+    }
+
+    fn verifier(
+        &self,
+        commitment: &Self::Commitment,
+        challenge: &Self::Challenge,
+        response: &Self::Response,
+    ) -> Result<(), SigmaError> {
+        self.dleq.verifier(commitment, challenge, response)
+    }
+
+    fn simulate_response<R: Rng>(&self, rng: &mut R) -> Self::Response {
+        self.dleq.simulate_response(rng)
+    }
+
+    fn zeroize_state(&self, prover_state: &mut Self::ProverState) {
+        self.dleq.zeroize_state(prover_state);
+    }
+}
+
+impl<G: ProjectiveCurve> CommitmentRecoverable for ReEncryption<G> {
+    fn simulate_commitment(
+        &self,
+        challenge: &Self::Challenge,
+        response: &Self::Response,
+    ) -> Result<Self::Commitment, SigmaError> {
+        self.dleq.simulate_commitment(challenge, response)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_ec::ProjectiveCurve;
+    use ark_ff::{PrimeField, UniformRand};
+    use rand::thread_rng;
+
+    use crate::{
+        nizk_proofs::tests::{run_nizk_batched, run_nizk_short},
+        protocols::ElGamalCiphertext,
+        SigmaError,
+    };
+
+    use super::{ReEncryption, ReEncryptionInstance};
+
+    type Hash = blake2::Blake2s;
+    type G = ark_bls12_377::G1Projective;
+    type F = ark_bls12_377::Fr;
+
+    #[test]
+    fn test_reencryption_accept_valid() {
+        let rng = &mut thread_rng();
+        let generator = G::prime_subgroup_generator();
+        let public_key = generator.mul(F::rand(rng).into_repr());
+        let original =
+            ElGamalCiphertext::encrypt(generator, public_key, G::rand(rng), &F::rand(rng));
+        let randomness = F::rand(rng);
+        let reencrypted = original.rerandomize(generator, public_key, &randomness);
+        let instance = ReEncryptionInstance::new(generator, public_key, original, reencrypted);
+
+        let batched_result =
+            run_nizk_batched::<Hash, ReEncryption<_>, _>(&instance, &randomness, rng);
+        let short_result = run_nizk_short::<Hash, ReEncryption<_>, _>(&instance, &randomness, rng);
+
+        assert!(batched_result.is_ok());
+        assert!(short_result.is_ok());
+    }
+
+    #[test]
+    fn test_reencryption_reject_different_plaintext() {
+        let rng = &mut thread_rng();
+        let generator = G::prime_subgroup_generator();
+        let public_key = generator.mul(F::rand(rng).into_repr());
+        let original =
+            ElGamalCiphertext::encrypt(generator, public_key, G::rand(rng), &F::rand(rng));
+        let randomness = F::rand(rng);
+        // Same randomness offset, but the second ciphertext hides a different plaintext
+        let shifted = original.rerandomize(generator, public_key, &randomness);
+        let reencrypted = ElGamalCiphertext::new(shifted.c1(), shifted.c2() + G::rand(rng));
+        let instance = ReEncryptionInstance::new(generator, public_key, original, reencrypted);
+
+        let batched_result =
+            run_nizk_batched::<Hash, ReEncryption<_>, _>(&instance, &randomness, rng);
+        let short_result = run_nizk_short::<Hash, ReEncryption<_>, _>(&instance, &randomness, rng);
+
+        assert_eq!(batched_result, Err(SigmaError::VerificationFailed));
+        assert_eq!(short_result, Err(SigmaError::VerificationFailed));
+    }
+}
@@ -0,0 +1,205 @@
+use ark_ec::ProjectiveCurve;
+use rand::Rng;
+
+use super::{DleqInstance, DleqProof};
+use crate::{Challenge, ChallengeExpansion, CommitmentRecoverable, SigmaError, SigmaProtocol};
+
+/// The Katz-Wang variant of the Schnorr proof of knowledge of a discrete logarithm.
+///
+/// The instance publishes the witness `x` with respect to two independent generators, `X1 = x * G1` and
+/// `X2 = x * G2`, which gives a tight security reduction to DDH in the random oracle model. The proof is
+/// structurally a DLEQ proof, but it carries its own label so that its transcripts are domain-separated from
+/// those of [`DleqProof`].
+pub struct KatzWang<G: ProjectiveCurve> {
+    dleq: DleqProof<G>,
+}
+
+/// The instance for the Katz-Wang proof: two independent generators and the two claims sharing the witness
+#[derive(Debug, Clone, Copy)]
+pub struct KatzWangInstance<G: ProjectiveCurve> {
+    generator1: G,
+    claim1: G,
+    generator2: G,
+    claim2: G,
+}
+
+impl<G: ProjectiveCurve> KatzWangInstance<G> {
+    /// Create a new Katz-Wang instance from the provided group elements
+    pub fn new(generator1: G, claim1: G, generator2: G, claim2: G) -> Self {
+        Self {
+            generator1,
+            claim1,
+            generator2,
+            claim2,
+        }
+    }
+}
+
+impl<G: ProjectiveCurve> From<&KatzWangInstance<G>> for DleqInstance<G> {
+    fn from(instance: &KatzWangInstance<G>) -> Self {
+        DleqInstance::new(
+            instance.generator1,
+            instance.claim1,
+            instance.generator2,
+            instance.claim2,
+        )
+    }
+}
+
+/// A wrapper type for a Katz-Wang witness: the shared discrete logarithm
+pub type KatzWangWitness<G> = <G as ProjectiveCurve>::ScalarField;
+
+impl<G: ProjectiveCurve> SigmaProtocol for KatzWang<G> {
+    type Instance = KatzWangInstance<G>;
+    type Commitment = <DleqProof<G> as SigmaProtocol>::Commitment;
+    type ProverState = <DleqProof<G> as SigmaProtocol>::ProverState;
+    type Witness = KatzWangWitness<G>;
+    type Response = <DleqProof<G> as SigmaProtocol>::Response;
+    type Challenge = <DleqProof<G> as SigmaProtocol>::Challenge;
+
+    const PROTOCOL_ID: &'static [u8] = b"zkpstd/sigma/katz-wang";
+
+    fn new(instance: &KatzWangInstance<G>) -> Self {
+        Self {
+            dleq: DleqProof::new(&instance.into()),
+        }
+    }
+
+    fn challenge_from_bytes(
+        challenge: &Challenge,
+        expansion: ChallengeExpansion,
+    ) -> Result<Self::Challenge, SigmaError> {
+        DleqProof::<G>::challenge_from_bytes(challenge, expansion)
+    }
+
+    fn instance_bytes(&self) -> Vec<u8> {
+        self.dleq.instance_bytes()
+    }
+
+    fn witness_bytes(&self, witness: &Self::Witness) -> Vec<u8> {
+        self.dleq.witness_bytes(witness)
+    }
+
+    fn prover_commit<R: Rng>(
+        &self,
+        witness: &Self::Witness,
+        expansion: ChallengeExpansion,
+        rng: &mut R,
+    ) -> Result<(Self::Commitment, Self::ProverState), SigmaError> {
+        self.dleq.prover_commit(witness, expansion, rng)
+    }
+
+    fn prover_response(
+        &self,
+        prover_state: &Self::ProverState,
+        challenge: &Self::Challenge,
+    ) -> Result<Self::Response, SigmaError> {
+        self.dleq.prover_response(prover_state, challenge)
+    }
+
+    fn verifier(
+        &self,
+        commitment: &Self::Commitment,
+        challenge: &Self::Challenge,
+        response: &Self::Response,
+    ) -> Result<(), SigmaError> {
+        self.dleq.verifier(commitment, challenge, response)
+    }
+
+    fn simulate_response<R: Rng>(&self, rng: &mut R) -> Self::Response {
+        self.dleq.simulate_response(rng)
+    }
+
+    fn zeroize_state(&self, prover_state: &mut Self::ProverState) {
+        self.dleq.zeroize_state(prover_state);
+    }
+}
+
+impl<G: ProjectiveCurve> CommitmentRecoverable for KatzWang<G> {
+    fn simulate_commitment(
+        &self,
+        challenge: &Self::Challenge,
+        response: &Self::Response,
+    ) -> Result<Self::Commitment, SigmaError> {
+        self.dleq.simulate_commitment(challenge, response)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_ec::ProjectiveCurve;
+    use ark_ff::{PrimeField, UniformRand};
+    use rand::{thread_rng, Rng};
+
+    use crate::{
+        nizk_proofs::tests::{run_nizk_batched, run_nizk_short},
+        protocols::DleqProof,
+        testing::interactive::run_interactive,
+        SigmaError, SigmaProtocol,
+    };
+
+    use super::{KatzWang, KatzWangInstance};
+
+    type Hash = blake2::Blake2s;
+    type G = ark_bls12_377::G1Projective;
+    type F = ark_bls12_377::Fr;
+
+    fn katz_wang_setup<R: Rng>(rng: &mut R) -> (KatzWangInstance<G>, F) {
+        let generator1 = G::rand(rng);
+        let generator2 = G::rand(rng);
+        let witness = F::rand(rng);
+        let instance = KatzWangInstance::new(
+            generator1,
+            generator1.mul(witness.into_repr()),
+            generator2,
+            generator2.mul(witness.into_repr()),
+        );
+
+        (instance, witness)
+    }
+
+    #[test]
+    fn test_katz_wang_accept_valid() {
+        let rng = &mut thread_rng();
+        let (instance, witness) = katz_wang_setup(rng);
+
+        let batched_result = run_nizk_batched::<Hash, KatzWang<_>, _>(&instance, &witness, rng);
+        let short_result = run_nizk_short::<Hash, KatzWang<_>, _>(&instance, &witness, rng);
+
+        assert!(batched_result.is_ok());
+        assert!(short_result.is_ok());
+    }
+
+    #[test]
+    fn test_katz_wang_interactive_completeness() {
+        let rng = &mut thread_rng();
+        let (instance, witness) = katz_wang_setup(rng);
+
+        assert!(run_interactive::<KatzWang<_>, _>(&instance, &witness, rng).is_ok());
+    }
+
+    #[test]
+    fn test_katz_wang_reject_wrong_witness() {
+        let rng = &mut thread_rng();
+        let (instance, _) = katz_wang_setup(rng);
+        let wrong_witness = F::rand(rng);
+
+        let batched_result =
+            run_nizk_batched::<Hash, KatzWang<_>, _>(&instance, &wrong_witness, rng);
+        let short_result = run_nizk_short::<Hash, KatzWang<_>, _>(&instance, &wrong_witness, rng);
+
+        assert_eq!(batched_result, Err(SigmaError::VerificationFailed));
+        assert_eq!(short_result, Err(SigmaError::VerificationFailed));
+    }
+
+    #[test]
+    fn test_katz_wang_label_differs_from_dleq() {
+        let rng = &mut thread_rng();
+        let (instance, _) = katz_wang_setup(rng);
+
+        assert_ne!(
+            KatzWang::new(&instance).label(),
+            DleqProof::new(&(&instance).into()).label()
+        );
+    }
+}
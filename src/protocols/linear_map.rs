@@ -0,0 +1,382 @@
+use std::{fmt, sync::Arc};
+
+use ark_ec::ProjectiveCurve;
+use ark_ff::{Field, PrimeField};
+use ark_serialize::{CanonicalSerialize, SerializationError, Write};
+use ark_std::UniformRand;
+use rand::Rng;
+
+use super::{challenge_scalar, representation::linear_combination, PedersenParams};
+use crate::{
+    group::wipe, interactive_proofs::canonical_bytes, secret::Redacted, Challenge,
+    ChallengeExpansion, CommitmentRecoverable, SigmaError, SigmaProtocol,
+};
+
+/// Maurer's generic proof of knowledge of a preimage under a group homomorphism.
+///
+/// The homomorphism `phi: F^n -> G^m` is given by an `m x n` matrix of group elements, with
+/// `phi(x)_i = sum_j matrix[i][j] * x_j`. Schnorr, DLEQ, Okamoto and Pedersen openings are all special cases, see
+/// the constructors of [`LinearMapInstance`].
+#[derive(Clone)]
+pub struct LinearMapProof<G: ProjectiveCurve> {
+    instance: Arc<LinearMapInstance<G>>,
+}
+
+/// The instance for the linear map proof: the matrix of the homomorphism and the claimed image. The prover claims
+/// to know `x` such that `phi(x) = image`
+#[derive(Debug, Clone, CanonicalSerialize)]
+pub struct LinearMapInstance<G: ProjectiveCurve> {
+    matrix: Vec<Vec<G>>,
+    image: Vec<G>,
+}
+
+impl<G: ProjectiveCurve> LinearMapInstance<G> {
+    /// Create a new linear map instance, one matrix row per image element
+    pub fn new(matrix: Vec<Vec<G>>, image: Vec<G>) -> Self {
+        Self { matrix, image }
+    }
+
+    /// The statement of [`SchnorrDLOG`](super::SchnorrDLOG): `claim = x * base`
+    pub fn schnorr(base: G, claim: G) -> Self {
+        Self::new(vec![vec![base]], vec![claim])
+    }
+
+    /// The statement of [`DleqProof`](super::DleqProof): `claim1 = x * base1` and `claim2 = x * base2`
+    pub fn dleq(base1: G, claim1: G, base2: G, claim2: G) -> Self {
+        Self::new(vec![vec![base1], vec![base2]], vec![claim1, claim2])
+    }
+
+    /// The statement of [`Okamoto`](super::Okamoto): `target = x * base1 + y * base2`
+    pub fn okamoto(base1: G, base2: G, target: G) -> Self {
+        Self::new(vec![vec![base1, base2]], vec![target])
+    }
+
+    /// Knowledge of an opening `(m, r)` of a Pedersen commitment `m * G + r * H`
+    pub fn pedersen_opening(params: PedersenParams<G>, commitment: G) -> Self {
+        Self::okamoto(params.generator(), params.blinding_base(), commitment)
+    }
+
+    /// The number of scalars in a preimage, or `None` if the rows of the matrix do not all have the same length
+    fn preimage_length(&self) -> Option<usize> {
+        let length = self.matrix.first().map_or(0, Vec::len);
+        self.matrix
+            .iter()
+            .all(|row| row.len() == length)
+            .then_some(length)
+    }
+
+    /// The rows of the matrix, each with the image element it maps to
+    pub(crate) fn rows(&self) -> impl Iterator<Item = (&[G], &G)> {
+        self.matrix.iter().map(Vec::as_slice).zip(&self.image)
+    }
+
+    /// Evaluates the homomorphism, the caller is responsible for checking the length of `scalars`
+    fn apply(&self, scalars: &[G::ScalarField]) -> Vec<G> {
+        self.matrix
+            .iter()
+            .map(|row| linear_combination(row, scalars))
+            .collect()
+    }
+
+    /// Checks that the matrix has one row per image element and `scalars` columns
+    pub(crate) fn check_lengths(&self, scalars: usize) -> Result<(), SigmaError> {
+        if self.matrix.len() == self.image.len() && self.preimage_length() == Some(scalars) {
+            Ok(())
+        } else {
+            Err(SigmaError::LengthMismatch)
+        }
+    }
+}
+
+/// A wrapper type for a linear map witness, one scalar per matrix column
+pub type LinearMapWitness<G> = Vec<<G as ProjectiveCurve>::ScalarField>;
+
+/// A wrapper type for a linear map commitment, one group element per matrix row
+pub type LinearMapCommitment<G> = Vec<G>;
+
+/// A wrapper type for a linear map response, one scalar per matrix column
+pub type LinearMapResponse<G> = Vec<<G as ProjectiveCurve>::ScalarField>;
+
+pub struct ProverState<F: Field> {
+    witness: Vec<F>,
+    random_values: Vec<F>,
+}
+
+impl<F: Field> fmt::Debug for ProverState<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ProverState")
+            .field("witness", &Redacted)
+            .field("random_values", &Redacted)
+            .finish()
+    }
+}
+
+impl<G: ProjectiveCurve> SigmaProtocol for LinearMapProof<G> {
+    type Instance = LinearMapInstance<G>;
+    type Commitment = LinearMapCommitment<G>;
+    type ProverState = ProverState<G::ScalarField>;
+    type Witness = LinearMapWitness<G>;
+    type Response = LinearMapResponse<G>;
+    type Challenge = G::ScalarField;
+
+    const PROTOCOL_ID: &'static [u8] = b"zkpstd/sigma/linear-map";
+
+    fn new(instance: &LinearMapInstance<G>) -> Self {
+        Self::from_shared(Arc::new(instance.clone()))
+    }
+
+    fn from_shared(instance: Arc<LinearMapInstance<G>>) -> Self {
+        Self { instance }
+    }
+
+    fn challenge_from_bytes(
+        challenge: &Challenge,
+        expansion: ChallengeExpansion,
+    ) -> Result<G::ScalarField, SigmaError> {
+        challenge_scalar(challenge, expansion)
+    }
+
+    fn instance_bytes(&self) -> Vec<u8> {
+        canonical_bytes(&*self.instance)
+    }
+
+    fn witness_bytes(&self, witness: &Self::Witness) -> Vec<u8> {
+        canonical_bytes(witness)
+    }
+
+    fn prover_commit<R: Rng>(
+        &self,
+        witness: &Self::Witness,
+        _expansion: ChallengeExpansion,
+        rng: &mut R,
+    ) -> Result<(Self::Commitment, Self::ProverState), SigmaError> {
+        self.instance.check_lengths(witness.len())?;
+
+        let random_values: Vec<_> = (0..witness.len())
+            .map(|_| G::ScalarField::rand(rng))
+            .collect();
+
+        let commitment = self.instance.apply(&random_values);
+
+        let state = ProverState {
+            witness: witness.clone(),
+            random_values,
+        };
+
+        Ok((commitment, state))
+    }
+
+    fn prover_response(
+        &self,
+        prover_state: &Self::ProverState,
+        challenge: &Self::Challenge,
+    ) -> Result<Self::Response, SigmaError> {
+        Ok(prover_state
+            .random_values
+            .iter()
+            .zip(&prover_state.witness)
+            .map(|(random_value, witness)| *random_value - *challenge * witness)
+            .collect())
+    }
+
+    fn verifier(
+        &self,
+        commitment: &Self::Commitment,
+        challenge: &Self::Challenge,
+        response: &Self::Response,
+    ) -> Result<(), SigmaError> {
+        if &self.simulate_commitment(challenge, response)? == commitment {
+            Ok(())
+        } else {
+            Err(SigmaError::VerificationFailed)
+        }
+    }
+
+    fn simulate_response<R: Rng>(&self, rng: &mut R) -> Self::Response {
+        (0..self.instance.preimage_length().unwrap_or(0))
+            .map(|_| G::ScalarField::rand(rng))
+            .collect()
+    }
+
+    fn zeroize_state(&self, prover_state: &mut Self::ProverState) {
+        wipe(&mut prover_state.witness);
+        wipe(&mut prover_state.random_values);
+    }
+}
+
+impl<G: ProjectiveCurve> CommitmentRecoverable for LinearMapProof<G> {
+    fn simulate_commitment(
+        &self,
+        challenge: &Self::Challenge,
+        response: &Self::Response,
+    ) -> Result<Self::Commitment, SigmaError> {
+        self.instance.check_lengths(response.len())?;
+
+        let challenge = challenge.into_repr();
+
+        Ok(self
+            .instance
+            .apply(response)
+            .into_iter()
+            .zip(&self.instance.image)
+            .map(|(evaluation, image)| evaluation + image.mul(challenge))
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_ec::ProjectiveCurve;
+    use ark_ff::{PrimeField, UniformRand};
+    use rand::{rngs::StdRng, thread_rng, SeedableRng};
+
+    use crate::{
+        nizk_proofs::tests::{run_nizk_batched, run_nizk_short},
+        protocols::{
+            DleqInstance, DleqProof, PedersenOpening, PedersenParams, SchnorrDLOG, SchnorrInstance,
+        },
+        ChallengeExpansion, SigmaError, SigmaProtocol,
+    };
+
+    use super::{LinearMapInstance, LinearMapProof};
+
+    type Hash = blake2::Blake2s;
+    type G = ark_bls12_377::G1Projective;
+    type F = ark_bls12_377::Fr;
+
+    // Any challenge will do
+    const CHALLENGE: u64 = 1;
+
+    #[test]
+    fn test_linear_map_agrees_with_schnorr() {
+        let rng = &mut thread_rng();
+        let base = G::rand(rng);
+        let witness = F::rand(rng);
+        let claim = base.mul(witness.into_repr());
+        let linear_map = LinearMapProof::new(&LinearMapInstance::schnorr(base, claim));
+        let schnorr = SchnorrDLOG::new(&SchnorrInstance::new(base, claim));
+
+        let (map_commitment, map_state) = linear_map
+            .prover_commit(
+                &vec![witness],
+                ChallengeExpansion::default(),
+                &mut StdRng::seed_from_u64(0),
+            )
+            .unwrap();
+        let (schnorr_commitment, schnorr_state) = schnorr
+            .prover_commit(
+                &witness,
+                ChallengeExpansion::default(),
+                &mut StdRng::seed_from_u64(0),
+            )
+            .unwrap();
+        let map_response = linear_map
+            .prover_response(&map_state, &F::from(CHALLENGE))
+            .unwrap();
+        let schnorr_response = schnorr
+            .prover_response(&schnorr_state, &F::from(CHALLENGE))
+            .unwrap();
+
+        assert_eq!(map_commitment, vec![schnorr_commitment]);
+        assert_eq!(map_response, vec![schnorr_response]);
+        assert!(schnorr
+            .verifier(&map_commitment[0], &F::from(CHALLENGE), &map_response[0])
+            .is_ok());
+        assert!(linear_map
+            .verifier(
+                &vec![schnorr_commitment],
+                &F::from(CHALLENGE),
+                &vec![schnorr_response]
+            )
+            .is_ok());
+    }
+
+    #[test]
+    fn test_linear_map_agrees_with_dleq() {
+        let rng = &mut thread_rng();
+        let (base1, base2) = (G::rand(rng), G::rand(rng));
+        let witness = F::rand(rng);
+        let (claim1, claim2) = (
+            base1.mul(witness.into_repr()),
+            base2.mul(witness.into_repr()),
+        );
+        let linear_map =
+            LinearMapProof::new(&LinearMapInstance::dleq(base1, claim1, base2, claim2));
+        let dleq = DleqProof::new(&DleqInstance::new(base1, claim1, base2, claim2));
+
+        let (map_commitment, map_state) = linear_map
+            .prover_commit(
+                &vec![witness],
+                ChallengeExpansion::default(),
+                &mut StdRng::seed_from_u64(0),
+            )
+            .unwrap();
+        let (dleq_commitment, dleq_state) = dleq
+            .prover_commit(
+                &witness,
+                ChallengeExpansion::default(),
+                &mut StdRng::seed_from_u64(0),
+            )
+            .unwrap();
+        let map_response = linear_map
+            .prover_response(&map_state, &F::from(CHALLENGE))
+            .unwrap();
+        let dleq_response = dleq
+            .prover_response(&dleq_state, &F::from(CHALLENGE))
+            .unwrap();
+
+        assert_eq!(map_commitment, vec![dleq_commitment.0, dleq_commitment.1]);
+        assert_eq!(map_response, vec![dleq_response]);
+        assert!(dleq
+            .verifier(
+                &(map_commitment[0], map_commitment[1]),
+                &F::from(CHALLENGE),
+                &map_response[0]
+            )
+            .is_ok());
+
+        // Unequal logs are rejected by both
+        let other_claim2 = base2.mul(F::rand(rng).into_repr());
+        let unequal = LinearMapInstance::dleq(base1, claim1, base2, other_claim2);
+        let test_result =
+            run_nizk_batched::<Hash, LinearMapProof<_>, _>(&unequal, &vec![witness], rng);
+        assert_eq!(test_result, Err(SigmaError::VerificationFailed));
+    }
+
+    #[test]
+    fn test_linear_map_pedersen_opening() {
+        let rng = &mut thread_rng();
+        let params = PedersenParams::new(G::rand(rng), G::rand(rng));
+        let opening = PedersenOpening::new(F::rand(rng), F::rand(rng));
+        let instance = LinearMapInstance::pedersen_opening(params, params.commit_opening(&opening));
+        let witness = vec![opening.message(), opening.blinding()];
+        let wrong_witness = vec![opening.message(), F::rand(rng)];
+
+        let batched_result =
+            run_nizk_batched::<Hash, LinearMapProof<_>, _>(&instance, &witness, rng);
+        let short_result = run_nizk_short::<Hash, LinearMapProof<_>, _>(&instance, &witness, rng);
+        let wrong_result =
+            run_nizk_batched::<Hash, LinearMapProof<_>, _>(&instance, &wrong_witness, rng);
+
+        assert!(batched_result.is_ok());
+        assert!(short_result.is_ok());
+        assert_eq!(wrong_result, Err(SigmaError::VerificationFailed));
+    }
+
+    #[test]
+    fn test_linear_map_reject_ragged_matrix() {
+        let rng = &mut thread_rng();
+        let instance = LinearMapInstance::new(
+            vec![vec![G::rand(rng), G::rand(rng)], vec![G::rand(rng)]],
+            vec![G::rand(rng), G::rand(rng)],
+        );
+
+        let test_result = run_nizk_batched::<Hash, LinearMapProof<_>, _>(
+            &instance,
+            &vec![F::rand(rng), F::rand(rng)],
+            rng,
+        );
+
+        assert_eq!(test_result, Err(SigmaError::LengthMismatch));
+    }
+}
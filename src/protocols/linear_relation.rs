@@ -0,0 +1,258 @@
+use std::fmt;
+
+use ark_ec::ProjectiveCurve;
+use ark_ff::{Field, PrimeField};
+use ark_serialize::{CanonicalSerialize, SerializationError, Write};
+use ark_std::UniformRand;
+use rand::Rng;
+
+use super::{challenge_scalar, PedersenOpening, PedersenParams};
+use crate::{
+    group::wipe, interactive_proofs::canonical_bytes, secret::Redacted, Challenge,
+    ChallengeExpansion, CommitmentRecoverable, SigmaError, SigmaProtocol,
+};
+
+/// Proof that three Pedersen commitments hide values satisfying `z = a * x + b * y` for public `a` and `b`.
+pub struct LinearRelation<G: ProjectiveCurve> {
+    instance: LinearRelationInstance<G>,
+}
+
+/// The instance for the linear relation proof: the Pedersen parameters, the commitments
+/// `(C_x, C_y, C_z)` and the public coefficients `(a, b)`
+#[derive(Debug, Clone, Copy, CanonicalSerialize)]
+pub struct LinearRelationInstance<G: ProjectiveCurve> {
+    params: PedersenParams<G>,
+    commitments: (G, G, G),
+    coefficients: (G::ScalarField, G::ScalarField),
+}
+
+impl<G: ProjectiveCurve> LinearRelationInstance<G> {
+    /// Create a new linear relation instance from the commitments `(C_x, C_y, C_z)` and the coefficients `(a, b)`
+    pub fn new(
+        params: PedersenParams<G>,
+        commitments: (G, G, G),
+        coefficients: (G::ScalarField, G::ScalarField),
+    ) -> Self {
+        Self {
+            params,
+            commitments,
+            coefficients,
+        }
+    }
+
+    /// `a * C_x + b * C_y - C_z`, which commits to zero under the blinding base iff the relation holds
+    fn combined_commitment(&self) -> G {
+        let (c_x, c_y, c_z) = self.commitments;
+        let (a, b) = self.coefficients;
+
+        c_x.mul(a.into_repr()) + c_y.mul(b.into_repr()) - c_z
+    }
+}
+
+/// A wrapper type for a linear relation witness: the openings of `(C_x, C_y, C_z)`
+pub type LinearRelationWitness<G> = (
+    PedersenOpening<<G as ProjectiveCurve>::ScalarField>,
+    PedersenOpening<<G as ProjectiveCurve>::ScalarField>,
+    PedersenOpening<<G as ProjectiveCurve>::ScalarField>,
+);
+
+/// A wrapper type for a linear relation response
+pub type LinearRelationResponse<G> = <G as ProjectiveCurve>::ScalarField;
+
+pub struct ProverState<F: Field> {
+    combined_blinding: F,
+    random_value: F,
+}
+
+impl<F: Field> fmt::Debug for ProverState<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ProverState")
+            .field("combined_blinding", &Redacted)
+            .field("random_value", &Redacted)
+            .finish()
+    }
+}
+
+impl<G: ProjectiveCurve> SigmaProtocol for LinearRelation<G> {
+    type Instance = LinearRelationInstance<G>;
+    type Commitment = G;
+    type ProverState = ProverState<G::ScalarField>;
+    type Witness = LinearRelationWitness<G>;
+    type Response = LinearRelationResponse<G>;
+    type Challenge = G::ScalarField;
+
+    const PROTOCOL_ID: &'static [u8] = b"zkpstd/sigma/linear-relation";
+
+    fn new(instance: &LinearRelationInstance<G>) -> Self {
+        Self {
+            instance: *instance,
+        }
+    }
+
+    fn challenge_from_bytes(
+        challenge: &Challenge,
+        expansion: ChallengeExpansion,
+    ) -> Result<G::ScalarField, SigmaError> {
+        challenge_scalar(challenge, expansion)
+    }
+
+    fn instance_bytes(&self) -> Vec<u8> {
+        canonical_bytes(&self.instance)
+    }
+
+    fn witness_bytes(&self, witness: &Self::Witness) -> Vec<u8> {
+        canonical_bytes(witness)
+    }
+
+    fn prover_commit<R: Rng>(
+        &self,
+        witness: &Self::Witness,
+        _expansion: ChallengeExpansion,
+        rng: &mut R,
+    ) -> Result<(Self::Commitment, Self::ProverState), SigmaError> {
+        let (x, y, z) = witness;
+        let (a, b) = self.instance.coefficients;
+        let random_value = G::ScalarField::rand(rng);
+
+        let commitment = self
+            .instance
+            .params
+            .blinding_base()
+            .mul(random_value.into_repr());
+
+        let state = ProverState {
+            combined_blinding: a * x.blinding() + b * y.blinding() - z.blinding(),
+            random_value,
+        };
+
+        Ok((commitment, state))
+    }
+
+    fn prover_response(
+        &self,
+        prover_state: &Self::ProverState,
+        challenge: &Self::Challenge,
+    ) -> Result<Self::Response, SigmaError> {
+        Ok(prover_state.random_value - *challenge * prover_state.combined_blinding)
+    }
+
+    fn verifier(
+        &self,
+        commitment: &Self::Commitment,
+        challenge: &Self::Challenge,
+        response: &Self::Response,
+    ) -> Result<(), SigmaError> {
+        if &self.simulate_commitment(challenge, response)? == commitment {
+            Ok(())
+        } else {
+            Err(SigmaError::VerificationFailed)
+        }
+    }
+
+    fn simulate_response<R: Rng>(&self, rng: &mut R) -> Self::Response {
+        G::ScalarField::rand(rng)
+    }
+
+    fn zeroize_state(&self, prover_state: &mut Self::ProverState) {
+        wipe(&mut prover_state.combined_blinding);
+        wipe(&mut prover_state.random_value);
+    }
+}
+
+impl<G: ProjectiveCurve> CommitmentRecoverable for LinearRelation<G> {
+    fn simulate_commitment(
+        &self,
+        challenge: &Self::Challenge,
+        response: &Self::Response,
+    ) -> Result<Self::Commitment, SigmaError> {
+        Ok(self
+            .instance
+            .params
+            .blinding_base()
+            .mul(response.into_repr())
+            + self
+                .instance
+                .combined_commitment()
+                .mul(challenge.into_repr()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_ff::{One, UniformRand};
+    use rand::{thread_rng, Rng};
+
+    use crate::{
+        nizk_proofs::tests::{run_nizk_batched, run_nizk_short},
+        protocols::{PedersenOpening, PedersenParams},
+        SigmaError,
+    };
+
+    use super::{LinearRelation, LinearRelationInstance, LinearRelationWitness};
+
+    type Hash = blake2::Blake2s;
+    type G = ark_bls12_377::G1Projective;
+    type F = ark_bls12_377::Fr;
+
+    fn linear_relation_setup<R: Rng>(
+        a: F,
+        b: F,
+        z_offset: F,
+        rng: &mut R,
+    ) -> (LinearRelationInstance<G>, LinearRelationWitness<G>) {
+        let params = PedersenParams::new(G::rand(rng), G::rand(rng));
+        let x = PedersenOpening::new(F::rand(rng), F::rand(rng));
+        let y = PedersenOpening::new(F::rand(rng), F::rand(rng));
+        let z = PedersenOpening::new(a * x.message() + b * y.message() + z_offset, F::rand(rng));
+        let commitments = (
+            params.commit_opening(&x),
+            params.commit_opening(&y),
+            params.commit_opening(&z),
+        );
+
+        (
+            LinearRelationInstance::new(params, commitments, (a, b)),
+            (x, y, z),
+        )
+    }
+
+    fn run_both(instance: &LinearRelationInstance<G>, witness: &LinearRelationWitness<G>) {
+        let rng = &mut thread_rng();
+
+        let batched_result = run_nizk_batched::<Hash, LinearRelation<_>, _>(instance, witness, rng);
+        let short_result = run_nizk_short::<Hash, LinearRelation<_>, _>(instance, witness, rng);
+
+        assert!(batched_result.is_ok());
+        assert!(short_result.is_ok());
+    }
+
+    #[test]
+    fn test_linear_relation_accept_sum() {
+        let (instance, witness) =
+            linear_relation_setup(F::one(), F::one(), F::from(0u64), &mut thread_rng());
+
+        run_both(&instance, &witness);
+    }
+
+    #[test]
+    fn test_linear_relation_accept_negative_coefficients() {
+        let rng = &mut thread_rng();
+        let (instance, witness) =
+            linear_relation_setup(-F::one(), -F::rand(rng), F::from(0u64), rng);
+
+        run_both(&instance, &witness);
+    }
+
+    #[test]
+    fn test_linear_relation_reject_wrong_relation() {
+        let rng = &mut thread_rng();
+        let (instance, witness) = linear_relation_setup(F::one(), F::one(), F::one(), rng);
+
+        let batched_result =
+            run_nizk_batched::<Hash, LinearRelation<_>, _>(&instance, &witness, rng);
+        let short_result = run_nizk_short::<Hash, LinearRelation<_>, _>(&instance, &witness, rng);
+
+        assert_eq!(batched_result, Err(SigmaError::VerificationFailed));
+        assert_eq!(short_result, Err(SigmaError::VerificationFailed));
+    }
+}
@@ -1,2 +1,133 @@
+use ark_ff::PrimeField;
+
+use crate::{Challenge, ChallengeExpansion, SigmaError};
+
 mod schnorr_dlog;
 pub use schnorr_dlog::{SchnorrDLOG, SchnorrInstance, SchnorrResponse, SchnorrWitness};
+
+mod dleq;
+pub use dleq::{DleqCommitment, DleqInstance, DleqProof, DleqResponse, DleqWitness};
+
+mod okamoto;
+pub use okamoto::{Okamoto, OkamotoInstance, OkamotoResponse, OkamotoWitness};
+
+mod representation;
+pub use representation::{
+    Representation, RepresentationInstance, RepresentationResponse, RepresentationWitness,
+};
+
+mod pedersen;
+pub use pedersen::{PedersenOpening, PedersenParams};
+
+mod pedersen_equality;
+pub use pedersen_equality::{
+    PedersenEquality, PedersenEqualityCommitment, PedersenEqualityInstance,
+    PedersenEqualityResponse, PedersenEqualityWitness,
+};
+
+mod linear_relation;
+pub use linear_relation::{
+    LinearRelation, LinearRelationInstance, LinearRelationResponse, LinearRelationWitness,
+};
+
+mod multiplication;
+pub use multiplication::{
+    Multiplication, MultiplicationCommitment, MultiplicationInstance, MultiplicationResponse,
+    MultiplicationWitness,
+};
+
+mod elgamal;
+pub use elgamal::ElGamalCiphertext;
+
+mod elgamal_encryption;
+pub use elgamal_encryption::{
+    ElGamalEncryption, ElGamalEncryptionCommitment, ElGamalEncryptionInstance,
+    ElGamalEncryptionResponse, ElGamalEncryptionWitness,
+};
+
+mod elgamal_decryption;
+pub use elgamal_decryption::{
+    CorrectDecryption, CorrectDecryptionInstance, CorrectDecryptionWitness,
+};
+
+mod elgamal_reencryption;
+pub use elgamal_reencryption::{ReEncryption, ReEncryptionInstance, ReEncryptionWitness};
+
+mod plaintext_equivalence;
+pub use plaintext_equivalence::{
+    PlaintextEquivalence, PlaintextEquivalenceCommitment, PlaintextEquivalenceInstance,
+    PlaintextEquivalenceResponse, PlaintextEquivalenceWitness,
+};
+
+mod bit;
+pub use bit::{BitCommitment, BitInstance, BitProof, BitResponse, BitWitness};
+
+mod range;
+pub use range::{RangeCommitment, RangeInstance, RangeProof, RangeResponse, RangeWitness};
+
+mod set_membership;
+pub use set_membership::{
+    SetMembership, SetMembershipCommitment, SetMembershipInstance, SetMembershipResponse,
+    SetMembershipWitness,
+};
+
+mod ring;
+pub use ring::{RingOfKeys, RingOfKeysInstance, RingOfKeysWitness, RingSignature};
+
+mod square_root;
+pub use square_root::{
+    Residue, SquareRootCommitment, SquareRootIdentification, SquareRootInstance,
+    SquareRootResponse, SquareRootWitness,
+};
+
+mod katz_wang;
+pub use katz_wang::{KatzWang, KatzWangInstance, KatzWangWitness};
+
+mod multi_dlog;
+pub use multi_dlog::{
+    MultiDlog, MultiDlogCommitment, MultiDlogInstance, MultiDlogResponse, MultiDlogWitness,
+};
+
+mod batch_dleq;
+pub use batch_dleq::{
+    BatchDleq, BatchDleqCommitment, BatchDleqInstance, BatchDleqResponse, BatchDleqWitness,
+};
+
+mod bls_pop;
+pub use bls_pop::{
+    BlsKeyGroup, BlsKeyPossession, BlsKeyPossessionInstance, BlsKeyPossessionWitness,
+    BlsPossessionProof, ProofOfPossession, PublicKeyInG1, PublicKeyInG2,
+};
+
+mod decryption_share;
+pub use decryption_share::{DecryptionShareInstance, DecryptionShareProof, DecryptionShareWitness};
+
+mod linear_map;
+pub use linear_map::{
+    LinearMapCommitment, LinearMapInstance, LinearMapProof, LinearMapResponse, LinearMapWitness,
+};
+
+mod dlog_inequality;
+pub use dlog_inequality::{
+    DlogInequality, DlogInequalityCommitment, DlogInequalityInstance, DlogInequalityResponse,
+    DlogInequalityWitness,
+};
+
+mod relation_macro;
+
+/// Maps the challenge to a scalar with [`ChallengeExpansion::scalar`], which never fails
+pub(crate) fn challenge_scalar<F: PrimeField>(
+    challenge: &Challenge,
+    expansion: ChallengeExpansion,
+) -> Result<F, SigmaError> {
+    Ok(expansion.scalar(challenge))
+}
+
+/// The first `count` bits of the challenge, least significant bit of each byte first, for protocols whose
+/// repetitions each consume a single challenge bit. Callers must check that `count <= 8 * CHALLENGE_LENGTH`
+pub(crate) fn challenge_bits(
+    challenge: &Challenge,
+    count: usize,
+) -> impl Iterator<Item = bool> + '_ {
+    (0..count).map(move |i| (challenge[i / 8] >> (i % 8)) & 1 == 1)
+}
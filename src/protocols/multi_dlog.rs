@@ -0,0 +1,271 @@
+use std::{fmt, sync::Arc};
+
+use ark_ec::ProjectiveCurve;
+use ark_ff::{Field, PrimeField};
+use ark_serialize::{CanonicalSerialize, SerializationError, Write};
+use ark_std::UniformRand;
+use rand::Rng;
+
+use super::challenge_scalar;
+use crate::{
+    group::wipe, interactive_proofs::canonical_bytes, secret::Redacted, Challenge,
+    ChallengeExpansion, CommitmentRecoverable, SigmaError, SigmaProtocol,
+};
+
+/// Proof of knowledge of the discrete logarithms of many claims with respect to the same base.
+///
+/// This runs one Schnorr proof per claim under a single shared challenge, so that the whole statement
+/// goes through the Fiat-Shamir transformation once and yields a single proof.
+#[derive(Clone)]
+pub struct MultiDlog<G: ProjectiveCurve> {
+    instance: Arc<MultiDlogInstance<G>>,
+}
+
+/// The instance for the multi-DLOG proof. The prover claims to know `x_i` such that `claims[i] = x_i * base`
+#[derive(Debug, Clone, CanonicalSerialize)]
+pub struct MultiDlogInstance<G: ProjectiveCurve> {
+    base: G,
+    claims: Vec<G>,
+}
+
+impl<G: ProjectiveCurve> MultiDlogInstance<G> {
+    /// Create a new multi-DLOG instance from the provided group elements
+    pub fn new(base: G, claims: Vec<G>) -> Self {
+        Self { base, claims }
+    }
+}
+
+/// A wrapper type for a multi-DLOG witness, one scalar per claim
+pub type MultiDlogWitness<G> = Vec<<G as ProjectiveCurve>::ScalarField>;
+
+/// A wrapper type for a multi-DLOG commitment, one group element per claim
+pub type MultiDlogCommitment<G> = Vec<G>;
+
+/// A wrapper type for a multi-DLOG response, one scalar per claim
+pub type MultiDlogResponse<G> = Vec<<G as ProjectiveCurve>::ScalarField>;
+
+pub struct ProverState<F: Field> {
+    witness: Vec<F>,
+    random_values: Vec<F>,
+}
+
+impl<F: Field> fmt::Debug for ProverState<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ProverState")
+            .field("witness", &Redacted)
+            .field("random_values", &Redacted)
+            .finish()
+    }
+}
+
+impl<G: ProjectiveCurve> SigmaProtocol for MultiDlog<G> {
+    type Instance = MultiDlogInstance<G>;
+    type Commitment = MultiDlogCommitment<G>;
+    type ProverState = ProverState<G::ScalarField>;
+    type Witness = MultiDlogWitness<G>;
+    type Response = MultiDlogResponse<G>;
+    type Challenge = G::ScalarField;
+
+    const PROTOCOL_ID: &'static [u8] = b"zkpstd/sigma/multi-dlog";
+
+    fn new(instance: &MultiDlogInstance<G>) -> Self {
+        Self::from_shared(Arc::new(instance.clone()))
+    }
+
+    fn from_shared(instance: Arc<MultiDlogInstance<G>>) -> Self {
+        Self { instance }
+    }
+
+    fn challenge_from_bytes(
+        challenge: &Challenge,
+        expansion: ChallengeExpansion,
+    ) -> Result<G::ScalarField, SigmaError> {
+        challenge_scalar(challenge, expansion)
+    }
+
+    fn instance_bytes(&self) -> Vec<u8> {
+        canonical_bytes(&*self.instance)
+    }
+
+    fn witness_bytes(&self, witness: &Self::Witness) -> Vec<u8> {
+        canonical_bytes(witness)
+    }
+
+    fn prover_commit<R: Rng>(
+        &self,
+        witness: &Self::Witness,
+        _expansion: ChallengeExpansion,
+        rng: &mut R,
+    ) -> Result<(Self::Commitment, Self::ProverState), SigmaError> {
+        if witness.len() != self.instance.claims.len() {
+            return Err(SigmaError::LengthMismatch);
+        }
+
+        let random_values: Vec<_> = (0..witness.len())
+            .map(|_| G::ScalarField::rand(rng))
+            .collect();
+
+        let commitment = random_values
+            .iter()
+            .map(|random_value| self.instance.base.mul(random_value.into_repr()))
+            .collect();
+
+        let state = ProverState {
+            witness: witness.clone(),
+            random_values,
+        };
+
+        Ok((commitment, state))
+    }
+
+    fn prover_response(
+        &self,
+        prover_state: &Self::ProverState,
+        challenge: &Self::Challenge,
+    ) -> Result<Self::Response, SigmaError> {
+        Ok(prover_state
+            .random_values
+            .iter()
+            .zip(&prover_state.witness)
+            .map(|(random_value, witness)| *random_value - *challenge * witness)
+            .collect())
+    }
+
+    fn verifier(
+        &self,
+        commitment: &Self::Commitment,
+        challenge: &Self::Challenge,
+        response: &Self::Response,
+    ) -> Result<(), SigmaError> {
+        if &self.simulate_commitment(challenge, response)? == commitment {
+            Ok(())
+        } else {
+            Err(SigmaError::VerificationFailed)
+        }
+    }
+
+    fn simulate_response<R: Rng>(&self, rng: &mut R) -> Self::Response {
+        (0..self.instance.claims.len())
+            .map(|_| G::ScalarField::rand(rng))
+            .collect()
+    }
+
+    fn zeroize_state(&self, prover_state: &mut Self::ProverState) {
+        wipe(&mut prover_state.witness);
+        wipe(&mut prover_state.random_values);
+    }
+}
+
+impl<G: ProjectiveCurve> CommitmentRecoverable for MultiDlog<G> {
+    fn simulate_commitment(
+        &self,
+        challenge: &Self::Challenge,
+        response: &Self::Response,
+    ) -> Result<Self::Commitment, SigmaError> {
+        if response.len() != self.instance.claims.len() {
+            return Err(SigmaError::LengthMismatch);
+        }
+
+        let challenge = challenge.into_repr();
+
+        Ok(self
+            .instance
+            .claims
+            .iter()
+            .zip(response)
+            .map(|(claim, response)| {
+                self.instance.base.mul(response.into_repr()) + claim.mul(challenge)
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_ec::ProjectiveCurve;
+    use ark_ff::{PrimeField, UniformRand};
+    use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+    use rand::{thread_rng, Rng};
+
+    use crate::{
+        nizk_proofs::tests::{run_nizk_batched, run_nizk_short},
+        testing::interactive::run_interactive,
+        BatchableProof, SigmaError, NIZK,
+    };
+
+    use super::{MultiDlog, MultiDlogInstance};
+
+    type Hash = blake2::Blake2s;
+    type G = ark_bls12_377::G1Projective;
+    type F = ark_bls12_377::Fr;
+
+    const CLAIMS: usize = 10;
+
+    fn multi_dlog_setup<R: Rng>(rng: &mut R) -> (MultiDlogInstance<G>, Vec<F>) {
+        let base = G::rand(rng);
+        let witness: Vec<F> = (0..CLAIMS).map(|_| F::rand(rng)).collect();
+        let claims = witness.iter().map(|x| base.mul(x.into_repr())).collect();
+
+        (MultiDlogInstance::new(base, claims), witness)
+    }
+
+    #[test]
+    fn test_multi_dlog_accept_valid() {
+        let rng = &mut thread_rng();
+        let (instance, witness) = multi_dlog_setup(rng);
+
+        let batched_result = run_nizk_batched::<Hash, MultiDlog<_>, _>(&instance, &witness, rng);
+        let short_result = run_nizk_short::<Hash, MultiDlog<_>, _>(&instance, &witness, rng);
+
+        assert!(batched_result.is_ok());
+        assert!(short_result.is_ok());
+    }
+
+    #[test]
+    fn test_multi_dlog_interactive_completeness() {
+        let rng = &mut thread_rng();
+        let (instance, witness) = multi_dlog_setup(rng);
+
+        assert!(run_interactive::<MultiDlog<_>, _>(&instance, &witness, rng).is_ok());
+    }
+
+    #[test]
+    fn test_multi_dlog_reject_single_wrong_witness() {
+        let rng = &mut thread_rng();
+        let (instance, mut witness) = multi_dlog_setup(rng);
+        witness[CLAIMS / 2] = F::rand(rng);
+
+        let batched_result = run_nizk_batched::<Hash, MultiDlog<_>, _>(&instance, &witness, rng);
+        let short_result = run_nizk_short::<Hash, MultiDlog<_>, _>(&instance, &witness, rng);
+
+        assert_eq!(batched_result, Err(SigmaError::VerificationFailed));
+        assert_eq!(short_result, Err(SigmaError::VerificationFailed));
+    }
+
+    #[test]
+    fn test_multi_dlog_batchable_proof_round_trip() {
+        let rng = &mut thread_rng();
+        let (instance, witness) = multi_dlog_setup(rng);
+        let nizk = NIZK::<MultiDlog<_>, Hash>::new(&instance, b"ctx");
+
+        let proof = nizk.batchable_proof(&witness, None, rng).unwrap();
+        let mut bytes = Vec::new();
+        proof.serialize(&mut bytes).unwrap();
+        let decoded = BatchableProof::<MultiDlog<G>>::deserialize(&bytes[..]).unwrap();
+
+        assert!(nizk.batchable_verify(&decoded, None).is_ok());
+
+        // The decoded vectors keep their length, which no longer matches an instance with one claim less
+        let mut truncated_instance = instance.clone();
+        truncated_instance.claims.pop();
+        let truncated_nizk = NIZK::<MultiDlog<_>, Hash>::new(&truncated_instance, b"ctx");
+        assert_eq!(
+            truncated_nizk.batchable_verify(&decoded, None),
+            Err(SigmaError::VerificationFailed)
+        );
+        assert_eq!(
+            truncated_nizk.batchable_verify_detailed(&decoded, None),
+            Err(SigmaError::LengthMismatch)
+        );
+    }
+}
@@ -0,0 +1,285 @@
+use std::fmt;
+
+use ark_ec::ProjectiveCurve;
+use ark_ff::{Field, PrimeField};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Read, SerializationError, Write};
+use ark_std::UniformRand;
+use rand::Rng;
+
+use super::{challenge_scalar, PedersenOpening, PedersenParams};
+use crate::{
+    group::wipe, interactive_proofs::canonical_bytes, secret::Redacted, Challenge,
+    ChallengeExpansion, CommitmentRecoverable, SigmaError, SigmaProtocol,
+};
+
+/// Proof that a Pedersen commitment `C_z` hides the product of the values hidden in `C_x` and `C_y`.
+///
+/// With `z = x * y` we have `C_z = x * C_y + (r_z - x * r_y) * H`, so it suffices to prove knowledge of
+/// openings of `C_x` and `C_y` together with a representation of `C_z` in the bases `(C_y, H)` that reuses `x`.
+pub struct Multiplication<G: ProjectiveCurve> {
+    instance: MultiplicationInstance<G>,
+}
+
+/// The instance for the multiplication proof: the Pedersen parameters and the commitments `(C_x, C_y, C_z)`
+#[derive(Debug, Clone, Copy, CanonicalSerialize)]
+pub struct MultiplicationInstance<G: ProjectiveCurve> {
+    params: PedersenParams<G>,
+    commitments: (G, G, G),
+}
+
+impl<G: ProjectiveCurve> MultiplicationInstance<G> {
+    /// Create a new multiplication instance from the commitments `(C_x, C_y, C_z)`
+    pub fn new(params: PedersenParams<G>, commitments: (G, G, G)) -> Self {
+        Self {
+            params,
+            commitments,
+        }
+    }
+}
+
+/// A wrapper type for a multiplication witness: the openings of `(C_x, C_y, C_z)` with `z = x * y`
+pub type MultiplicationWitness<G> = (
+    PedersenOpening<<G as ProjectiveCurve>::ScalarField>,
+    PedersenOpening<<G as ProjectiveCurve>::ScalarField>,
+    PedersenOpening<<G as ProjectiveCurve>::ScalarField>,
+);
+
+/// A wrapper type for a multiplication commitment, one group element per relation
+pub type MultiplicationCommitment<G> = (G, G, G);
+
+/// The response of the multiplication proof
+#[derive(Debug, Clone, Copy, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct MultiplicationResponse<F: PrimeField> {
+    x: F,
+    x_blinding: F,
+    y: F,
+    y_blinding: F,
+    /// Response for the blinding of `C_z` with respect to the bases `(C_y, H)`
+    z_blinding: F,
+}
+
+pub struct ProverState<F: Field> {
+    witness: [F; 5],
+    random_values: [F; 5],
+}
+
+impl<F: Field> fmt::Debug for ProverState<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ProverState")
+            .field("witness", &Redacted)
+            .field("random_values", &Redacted)
+            .finish()
+    }
+}
+
+impl<G: ProjectiveCurve> SigmaProtocol for Multiplication<G> {
+    type Instance = MultiplicationInstance<G>;
+    type Commitment = MultiplicationCommitment<G>;
+    type ProverState = ProverState<G::ScalarField>;
+    type Witness = MultiplicationWitness<G>;
+    type Response = MultiplicationResponse<G::ScalarField>;
+    type Challenge = G::ScalarField;
+
+    const PROTOCOL_ID: &'static [u8] = b"zkpstd/sigma/multiplication";
+
+    fn new(instance: &MultiplicationInstance<G>) -> Self {
+        Self {
+            instance: *instance,
+        }
+    }
+
+    fn challenge_from_bytes(
+        challenge: &Challenge,
+        expansion: ChallengeExpansion,
+    ) -> Result<G::ScalarField, SigmaError> {
+        challenge_scalar(challenge, expansion)
+    }
+
+    fn instance_bytes(&self) -> Vec<u8> {
+        canonical_bytes(&self.instance)
+    }
+
+    fn witness_bytes(&self, witness: &Self::Witness) -> Vec<u8> {
+        canonical_bytes(witness)
+    }
+
+    fn prover_commit<R: Rng>(
+        &self,
+        witness: &Self::Witness,
+        _expansion: ChallengeExpansion,
+        rng: &mut R,
+    ) -> Result<(Self::Commitment, Self::ProverState), SigmaError> {
+        let (x, y, z) = witness;
+        let params = &self.instance.params;
+        let random_values = [(); 5].map(|_| G::ScalarField::rand(rng));
+        let [a, b_x, d, b_y, e] = random_values;
+
+        let commitment = (
+            params.commit(&a, &b_x),
+            params.commit(&d, &b_y),
+            self.instance.commitments.1.mul(a.into_repr())
+                + params.blinding_base().mul(e.into_repr()),
+        );
+
+        let state = ProverState {
+            witness: [
+                x.message(),
+                x.blinding(),
+                y.message(),
+                y.blinding(),
+                z.blinding() - x.message() * y.blinding(),
+            ],
+            random_values,
+        };
+
+        Ok((commitment, state))
+    }
+
+    fn prover_response(
+        &self,
+        prover_state: &Self::ProverState,
+        challenge: &Self::Challenge,
+    ) -> Result<Self::Response, SigmaError> {
+        let mut responses = prover_state.random_values;
+        for (response, witness) in responses.iter_mut().zip(&prover_state.witness) {
+            *response -= *challenge * witness;
+        }
+        let [x, x_blinding, y, y_blinding, z_blinding] = responses;
+
+        Ok(MultiplicationResponse {
+            x,
+            x_blinding,
+            y,
+            y_blinding,
+            z_blinding,
+        })
+    }
+
+    fn verifier(
+        &self,
+        commitment: &Self::Commitment,
+        challenge: &Self::Challenge,
+        response: &Self::Response,
+    ) -> Result<(), SigmaError> {
+        if &self.simulate_commitment(challenge, response)? == commitment {
+            Ok(())
+        } else {
+            Err(SigmaError::VerificationFailed)
+        }
+    }
+
+    fn simulate_response<R: Rng>(&self, rng: &mut R) -> Self::Response {
+        MultiplicationResponse {
+            x: G::ScalarField::rand(rng),
+            x_blinding: G::ScalarField::rand(rng),
+            y: G::ScalarField::rand(rng),
+            y_blinding: G::ScalarField::rand(rng),
+            z_blinding: G::ScalarField::rand(rng),
+        }
+    }
+
+    fn zeroize_state(&self, prover_state: &mut Self::ProverState) {
+        wipe(&mut prover_state.witness);
+        wipe(&mut prover_state.random_values);
+    }
+}
+
+impl<G: ProjectiveCurve> CommitmentRecoverable for Multiplication<G> {
+    fn simulate_commitment(
+        &self,
+        challenge: &Self::Challenge,
+        response: &Self::Response,
+    ) -> Result<Self::Commitment, SigmaError> {
+        let challenge = challenge.into_repr();
+        let params = &self.instance.params;
+        let (c_x, c_y, c_z) = self.instance.commitments;
+
+        Ok((
+            params.commit(&response.x, &response.x_blinding) + c_x.mul(challenge),
+            params.commit(&response.y, &response.y_blinding) + c_y.mul(challenge),
+            c_y.mul(response.x.into_repr())
+                + params.blinding_base().mul(response.z_blinding.into_repr())
+                + c_z.mul(challenge),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_ff::UniformRand;
+    use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+    use rand::{thread_rng, Rng};
+
+    use crate::{
+        nizk_proofs::tests::{run_nizk_batched, run_nizk_short},
+        protocols::{PedersenOpening, PedersenParams},
+        BatchableProof, SigmaError, NIZK,
+    };
+
+    use super::{Multiplication, MultiplicationInstance, MultiplicationWitness};
+
+    type Hash = blake2::Blake2s;
+    type G = ark_bls12_377::G1Projective;
+    type F = ark_bls12_377::Fr;
+
+    fn multiplication_setup<R: Rng>(
+        z_offset: F,
+        rng: &mut R,
+    ) -> (MultiplicationInstance<G>, MultiplicationWitness<G>) {
+        let params = PedersenParams::new(G::rand(rng), G::rand(rng));
+        let x = PedersenOpening::new(F::rand(rng), F::rand(rng));
+        let y = PedersenOpening::new(F::rand(rng), F::rand(rng));
+        let z = PedersenOpening::new(x.message() * y.message() + z_offset, F::rand(rng));
+        let commitments = (
+            params.commit_opening(&x),
+            params.commit_opening(&y),
+            params.commit_opening(&z),
+        );
+
+        (MultiplicationInstance::new(params, commitments), (x, y, z))
+    }
+
+    #[test]
+    fn test_multiplication_accept_valid() {
+        let rng = &mut thread_rng();
+        let (instance, witness) = multiplication_setup(F::from(0u64), rng);
+
+        let batched_result =
+            run_nizk_batched::<Hash, Multiplication<_>, _>(&instance, &witness, rng);
+        let short_result = run_nizk_short::<Hash, Multiplication<_>, _>(&instance, &witness, rng);
+
+        assert!(batched_result.is_ok());
+        assert!(short_result.is_ok());
+    }
+
+    #[test]
+    fn test_multiplication_reject_wrong_product() {
+        let rng = &mut thread_rng();
+        let (instance, witness) = multiplication_setup(F::from(1u64), rng);
+
+        let batched_result =
+            run_nizk_batched::<Hash, Multiplication<_>, _>(&instance, &witness, rng);
+        let short_result = run_nizk_short::<Hash, Multiplication<_>, _>(&instance, &witness, rng);
+
+        assert_eq!(batched_result, Err(SigmaError::VerificationFailed));
+        assert_eq!(short_result, Err(SigmaError::VerificationFailed));
+    }
+
+    #[test]
+    fn test_multiplication_batchable_proof_round_trip() {
+        let rng = &mut thread_rng();
+        let (instance, witness) = multiplication_setup(F::from(0u64), rng);
+        let nizk = NIZK::<Multiplication<_>, Hash>::new(&instance, b"ctx");
+
+        let proof = nizk.batchable_proof(&witness, None, rng).unwrap();
+        let mut bytes = Vec::new();
+        proof.serialize(&mut bytes).unwrap();
+        let decoded = BatchableProof::<Multiplication<G>>::deserialize(&bytes[..]).unwrap();
+
+        let mut reencoded = Vec::new();
+        decoded.serialize(&mut reencoded).unwrap();
+
+        assert_eq!(bytes, reencoded);
+        assert!(nizk.batchable_verify(&decoded, None).is_ok());
+    }
+}
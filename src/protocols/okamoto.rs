@@ -0,0 +1,239 @@
+use std::fmt;
+
+use ark_ec::ProjectiveCurve;
+use ark_ff::{Field, PrimeField};
+use ark_serialize::{CanonicalSerialize, SerializationError, Write};
+use ark_std::UniformRand;
+use rand::Rng;
+
+use super::challenge_scalar;
+use crate::{
+    group::wipe, interactive_proofs::canonical_bytes, secret::Redacted, Challenge,
+    ChallengeExpansion, CommitmentRecoverable, SigmaError, SigmaProtocol,
+};
+
+/// Okamoto proof of knowledge of a representation of a point with respect to two generators.
+pub struct Okamoto<G: ProjectiveCurve> {
+    instance: OkamotoInstance<G>,
+}
+
+/// The instance for the Okamoto proof. The prover claims to know `(x, y)` such that
+/// `target = x * base1 + y * base2`
+#[derive(Debug, Clone, Copy, CanonicalSerialize)]
+pub struct OkamotoInstance<G: ProjectiveCurve> {
+    base1: G,
+    base2: G,
+    target: G,
+}
+
+impl<G: ProjectiveCurve> OkamotoInstance<G> {
+    /// Create a new Okamoto instance from the provided group elements
+    pub fn new(base1: G, base2: G, target: G) -> Self {
+        Self {
+            base1,
+            base2,
+            target,
+        }
+    }
+}
+
+/// A wrapper type for an Okamoto witness, one scalar per base
+pub type OkamotoWitness<G> = (
+    <G as ProjectiveCurve>::ScalarField,
+    <G as ProjectiveCurve>::ScalarField,
+);
+
+/// A wrapper type for an Okamoto response, one scalar per base
+pub type OkamotoResponse<G> = (
+    <G as ProjectiveCurve>::ScalarField,
+    <G as ProjectiveCurve>::ScalarField,
+);
+
+pub struct ProverState<F: Field> {
+    witness: (F, F),
+    random_values: (F, F),
+}
+
+impl<F: Field> fmt::Debug for ProverState<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ProverState")
+            .field("witness", &Redacted)
+            .field("random_values", &Redacted)
+            .finish()
+    }
+}
+
+impl<G: ProjectiveCurve> SigmaProtocol for Okamoto<G> {
+    type Instance = OkamotoInstance<G>;
+    type Commitment = G;
+    type ProverState = ProverState<G::ScalarField>;
+    type Witness = OkamotoWitness<G>;
+    type Response = OkamotoResponse<G>;
+    type Challenge = G::ScalarField;
+
+    const PROTOCOL_ID: &'static [u8] = b"zkpstd/sigma/okamoto";
+
+    fn new(instance: &OkamotoInstance<G>) -> Self {
+        Self {
+            instance: *instance,
+        }
+    }
+
+    fn challenge_from_bytes(
+        challenge: &Challenge,
+        expansion: ChallengeExpansion,
+    ) -> Result<G::ScalarField, SigmaError> {
+        challenge_scalar(challenge, expansion)
+    }
+
+    fn instance_bytes(&self) -> Vec<u8> {
+        canonical_bytes(&self.instance)
+    }
+
+    fn witness_bytes(&self, witness: &Self::Witness) -> Vec<u8> {
+        canonical_bytes(witness)
+    }
+
+    fn prover_commit<R: Rng>(
+        &self,
+        witness: &Self::Witness,
+        _expansion: ChallengeExpansion,
+        rng: &mut R,
+    ) -> Result<(Self::Commitment, Self::ProverState), SigmaError> {
+        let random_values = (G::ScalarField::rand(rng), G::ScalarField::rand(rng));
+
+        let commitment = self.instance.base1.mul(random_values.0.into_repr())
+            + self.instance.base2.mul(random_values.1.into_repr());
+
+        let state = ProverState {
+            witness: *witness,
+            random_values,
+        };
+
+        Ok((commitment, state))
+    }
+
+    fn prover_response(
+        &self,
+        prover_state: &Self::ProverState,
+        challenge: &Self::Challenge,
+    ) -> Result<Self::Response, SigmaError> {
+        Ok((
+            prover_state.random_values.0 - *challenge * prover_state.witness.0,
+            prover_state.random_values.1 - *challenge * prover_state.witness.1,
+        ))
+    }
+
+    fn verifier(
+        &self,
+        commitment: &Self::Commitment,
+        challenge: &Self::Challenge,
+        response: &Self::Response,
+    ) -> Result<(), SigmaError> {
+        if &self.simulate_commitment(challenge, response)? == commitment {
+            Ok(())
+        } else {
+            Err(SigmaError::VerificationFailed)
+        }
+    }
+
+    fn simulate_response<R: Rng>(&self, rng: &mut R) -> Self::Response {
+        (G::ScalarField::rand(rng), G::ScalarField::rand(rng))
+    }
+
+    fn zeroize_state(&self, prover_state: &mut Self::ProverState) {
+        wipe(&mut prover_state.witness);
+        wipe(&mut prover_state.random_values);
+    }
+}
+
+impl<G: ProjectiveCurve> CommitmentRecoverable for Okamoto<G> {
+    fn simulate_commitment(
+        &self,
+        challenge: &Self::Challenge,
+        response: &Self::Response,
+    ) -> Result<Self::Commitment, SigmaError> {
+        Ok(self.instance.base1.mul(response.0.into_repr())
+            + self.instance.base2.mul(response.1.into_repr())
+            + self.instance.target.mul(challenge.into_repr()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_ec::ProjectiveCurve;
+    use ark_ff::{PrimeField, UniformRand};
+    use rand::{thread_rng, Rng};
+
+    use crate::{
+        nizk_proofs::tests::{run_nizk_batched, run_nizk_short},
+        testing::interactive::run_interactive,
+        SigmaError,
+    };
+
+    use super::{Okamoto, OkamotoInstance};
+
+    type Hash = blake2::Blake2s;
+    type G = ark_bls12_377::G1Projective;
+    type F = ark_bls12_377::Fr;
+
+    fn okamoto_setup<R: Rng>(rng: &mut R) -> (OkamotoInstance<G>, (F, F), (F, F)) {
+        let base1 = G::prime_subgroup_generator();
+        let base2 = G::rand(rng);
+        let witness = (F::rand(rng), F::rand(rng));
+        let target = base1.mul(witness.0.into_repr()) + base2.mul(witness.1.into_repr());
+        let instance = OkamotoInstance::new(base1, base2, target);
+        // Only the second component is wrong so a check on the first alone would not catch it
+        let wrong_witness = (witness.0, F::rand(rng));
+
+        (instance, witness, wrong_witness)
+    }
+
+    #[test]
+    fn test_okamoto_accept_valid_batchable() {
+        let rng = &mut thread_rng();
+        let (instance, witness, _) = okamoto_setup(rng);
+
+        let test_result = run_nizk_batched::<Hash, Okamoto<_>, _>(&instance, &witness, rng);
+
+        assert!(test_result.is_ok())
+    }
+
+    #[test]
+    fn test_okamoto_interactive_completeness() {
+        let rng = &mut thread_rng();
+        let (instance, witness, _) = okamoto_setup(rng);
+
+        assert!(run_interactive::<Okamoto<_>, _>(&instance, &witness, rng).is_ok());
+    }
+
+    #[test]
+    fn test_okamoto_reject_wrong_batchable() {
+        let rng = &mut thread_rng();
+        let (instance, _, wrong_witness) = okamoto_setup(rng);
+
+        let test_result = run_nizk_batched::<Hash, Okamoto<_>, _>(&instance, &wrong_witness, rng);
+
+        assert_eq!(test_result, Err(SigmaError::VerificationFailed))
+    }
+
+    #[test]
+    fn test_okamoto_accept_valid_short() {
+        let rng = &mut thread_rng();
+        let (instance, witness, _) = okamoto_setup(rng);
+
+        let test_result = run_nizk_short::<Hash, Okamoto<_>, _>(&instance, &witness, rng);
+
+        assert!(test_result.is_ok())
+    }
+
+    #[test]
+    fn test_okamoto_reject_wrong_short() {
+        let rng = &mut thread_rng();
+        let (instance, _, wrong_witness) = okamoto_setup(rng);
+
+        let test_result = run_nizk_short::<Hash, Okamoto<_>, _>(&instance, &wrong_witness, rng);
+
+        assert_eq!(test_result, Err(SigmaError::VerificationFailed))
+    }
+}
@@ -0,0 +1,79 @@
+use std::fmt;
+
+use ark_ec::ProjectiveCurve;
+use ark_ff::PrimeField;
+use ark_serialize::{CanonicalSerialize, SerializationError, Write};
+
+use crate::secret::Redacted;
+
+/// Parameters for Pedersen commitments `message * generator + blinding * blinding_base`.
+/// The discrete logarithm between the two bases must be unknown to the committer.
+#[derive(Debug, Clone, Copy, CanonicalSerialize)]
+pub struct PedersenParams<G: ProjectiveCurve> {
+    generator: G,
+    blinding_base: G,
+}
+
+impl<G: ProjectiveCurve> PedersenParams<G> {
+    /// Create new Pedersen parameters from the provided group elements
+    pub fn new(generator: G, blinding_base: G) -> Self {
+        Self {
+            generator,
+            blinding_base,
+        }
+    }
+
+    /// The base the message is committed under
+    pub fn generator(&self) -> G {
+        self.generator
+    }
+
+    /// The base the blinding factor is committed under
+    pub fn blinding_base(&self) -> G {
+        self.blinding_base
+    }
+
+    /// Commit to `message` using the blinding factor `blinding`
+    pub fn commit(&self, message: &G::ScalarField, blinding: &G::ScalarField) -> G {
+        self.generator.mul(message.into_repr()) + self.blinding_base.mul(blinding.into_repr())
+    }
+
+    /// Commit to the message of `opening` using its blinding factor
+    pub fn commit_opening(&self, opening: &PedersenOpening<G::ScalarField>) -> G {
+        self.commit(&opening.message, &opening.blinding)
+    }
+}
+
+/// An opening of a Pedersen commitment: the committed message and its blinding factor, encoded in that order for the
+/// nonces of the NIZK. Its debug output omits both
+#[derive(Clone, Copy, PartialEq, Eq, CanonicalSerialize)]
+pub struct PedersenOpening<F: PrimeField> {
+    message: F,
+    blinding: F,
+}
+
+impl<F: PrimeField> fmt::Debug for PedersenOpening<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PedersenOpening")
+            .field("message", &Redacted)
+            .field("blinding", &Redacted)
+            .finish()
+    }
+}
+
+impl<F: PrimeField> PedersenOpening<F> {
+    /// Create a new opening from the provided message and blinding factor
+    pub fn new(message: F, blinding: F) -> Self {
+        Self { message, blinding }
+    }
+
+    /// The committed message
+    pub fn message(&self) -> F {
+        self.message
+    }
+
+    /// The blinding factor
+    pub fn blinding(&self) -> F {
+        self.blinding
+    }
+}
@@ -0,0 +1,250 @@
+use std::fmt;
+
+use ark_ec::ProjectiveCurve;
+use ark_ff::{Field, PrimeField};
+use ark_serialize::{CanonicalSerialize, SerializationError, Write};
+use ark_std::UniformRand;
+use rand::Rng;
+
+use super::{challenge_scalar, PedersenParams};
+use crate::{
+    group::wipe, interactive_proofs::canonical_bytes, secret::Redacted, Challenge,
+    ChallengeExpansion, CommitmentRecoverable, SigmaError, SigmaProtocol,
+};
+
+/// Proof that two Pedersen commitments, possibly under different parameters, open to the same message.
+pub struct PedersenEquality<G: ProjectiveCurve> {
+    instance: PedersenEqualityInstance<G>,
+}
+
+/// The instance for the Pedersen equality proof. The prover claims to know `(m, r1, r2)` such that
+/// `commitment1 = params1.commit(m, r1)` and `commitment2 = params2.commit(m, r2)`
+#[derive(Debug, Clone, Copy, CanonicalSerialize)]
+pub struct PedersenEqualityInstance<G: ProjectiveCurve> {
+    params1: PedersenParams<G>,
+    commitment1: G,
+    params2: PedersenParams<G>,
+    commitment2: G,
+}
+
+impl<G: ProjectiveCurve> PedersenEqualityInstance<G> {
+    /// Create a new Pedersen equality instance from the two parameter sets and commitments
+    pub fn new(
+        params1: PedersenParams<G>,
+        commitment1: G,
+        params2: PedersenParams<G>,
+        commitment2: G,
+    ) -> Self {
+        Self {
+            params1,
+            commitment1,
+            params2,
+            commitment2,
+        }
+    }
+}
+
+/// A wrapper type for a Pedersen equality witness: the shared message and both blinding factors
+pub type PedersenEqualityWitness<G> = (
+    <G as ProjectiveCurve>::ScalarField,
+    <G as ProjectiveCurve>::ScalarField,
+    <G as ProjectiveCurve>::ScalarField,
+);
+
+/// A wrapper type for a Pedersen equality commitment, one group element per parameter set
+pub type PedersenEqualityCommitment<G> = (G, G);
+
+/// A wrapper type for a Pedersen equality response, in the same order as the witness
+pub type PedersenEqualityResponse<G> = PedersenEqualityWitness<G>;
+
+pub struct ProverState<F: Field> {
+    witness: (F, F, F),
+    random_values: (F, F, F),
+}
+
+impl<F: Field> fmt::Debug for ProverState<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ProverState")
+            .field("witness", &Redacted)
+            .field("random_values", &Redacted)
+            .finish()
+    }
+}
+
+impl<G: ProjectiveCurve> SigmaProtocol for PedersenEquality<G> {
+    type Instance = PedersenEqualityInstance<G>;
+    type Commitment = PedersenEqualityCommitment<G>;
+    type ProverState = ProverState<G::ScalarField>;
+    type Witness = PedersenEqualityWitness<G>;
+    type Response = PedersenEqualityResponse<G>;
+    type Challenge = G::ScalarField;
+
+    const PROTOCOL_ID: &'static [u8] = b"zkpstd/sigma/pedersen-equality";
+
+    fn new(instance: &PedersenEqualityInstance<G>) -> Self {
+        Self {
+            instance: *instance,
+        }
+    }
+
+    fn challenge_from_bytes(
+        challenge: &Challenge,
+        expansion: ChallengeExpansion,
+    ) -> Result<G::ScalarField, SigmaError> {
+        challenge_scalar(challenge, expansion)
+    }
+
+    fn instance_bytes(&self) -> Vec<u8> {
+        canonical_bytes(&self.instance)
+    }
+
+    fn witness_bytes(&self, witness: &Self::Witness) -> Vec<u8> {
+        canonical_bytes(witness)
+    }
+
+    fn prover_commit<R: Rng>(
+        &self,
+        witness: &Self::Witness,
+        _expansion: ChallengeExpansion,
+        rng: &mut R,
+    ) -> Result<(Self::Commitment, Self::ProverState), SigmaError> {
+        let random_values = (
+            G::ScalarField::rand(rng),
+            G::ScalarField::rand(rng),
+            G::ScalarField::rand(rng),
+        );
+
+        // The message randomness is shared so that a single response binds both openings
+        let commitment = (
+            self.instance
+                .params1
+                .commit(&random_values.0, &random_values.1),
+            self.instance
+                .params2
+                .commit(&random_values.0, &random_values.2),
+        );
+
+        let state = ProverState {
+            witness: *witness,
+            random_values,
+        };
+
+        Ok((commitment, state))
+    }
+
+    fn prover_response(
+        &self,
+        prover_state: &Self::ProverState,
+        challenge: &Self::Challenge,
+    ) -> Result<Self::Response, SigmaError> {
+        let (m, r1, r2) = prover_state.witness;
+        let (a, b1, b2) = prover_state.random_values;
+
+        Ok((
+            a - *challenge * m,
+            b1 - *challenge * r1,
+            b2 - *challenge * r2,
+        ))
+    }
+
+    fn verifier(
+        &self,
+        commitment: &Self::Commitment,
+        challenge: &Self::Challenge,
+        response: &Self::Response,
+    ) -> Result<(), SigmaError> {
+        if &self.simulate_commitment(challenge, response)? == commitment {
+            Ok(())
+        } else {
+            Err(SigmaError::VerificationFailed)
+        }
+    }
+
+    fn simulate_response<R: Rng>(&self, rng: &mut R) -> Self::Response {
+        (
+            G::ScalarField::rand(rng),
+            G::ScalarField::rand(rng),
+            G::ScalarField::rand(rng),
+        )
+    }
+
+    fn zeroize_state(&self, prover_state: &mut Self::ProverState) {
+        wipe(&mut prover_state.witness);
+        wipe(&mut prover_state.random_values);
+    }
+}
+
+impl<G: ProjectiveCurve> CommitmentRecoverable for PedersenEquality<G> {
+    fn simulate_commitment(
+        &self,
+        challenge: &Self::Challenge,
+        response: &Self::Response,
+    ) -> Result<Self::Commitment, SigmaError> {
+        let (s_m, s_r1, s_r2) = response;
+
+        Ok((
+            self.instance.params1.commit(s_m, s_r1)
+                + self.instance.commitment1.mul(challenge.into_repr()),
+            self.instance.params2.commit(s_m, s_r2)
+                + self.instance.commitment2.mul(challenge.into_repr()),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_ff::UniformRand;
+    use rand::{thread_rng, Rng};
+
+    use crate::{
+        nizk_proofs::tests::{run_nizk_batched, run_nizk_short},
+        protocols::PedersenParams,
+        SigmaError,
+    };
+
+    use super::{PedersenEquality, PedersenEqualityInstance};
+
+    type Hash = blake2::Blake2s;
+    type G = ark_bls12_377::G1Projective;
+    type F = ark_bls12_377::Fr;
+
+    fn pedersen_equality_setup<R: Rng>(
+        message2: Option<F>,
+        rng: &mut R,
+    ) -> (PedersenEqualityInstance<G>, (F, F, F)) {
+        let params1 = PedersenParams::new(G::rand(rng), G::rand(rng));
+        let params2 = PedersenParams::new(G::rand(rng), G::rand(rng));
+        let witness = (F::rand(rng), F::rand(rng), F::rand(rng));
+        let commitment1 = params1.commit(&witness.0, &witness.1);
+        let commitment2 = params2.commit(&message2.unwrap_or(witness.0), &witness.2);
+        let instance = PedersenEqualityInstance::new(params1, commitment1, params2, commitment2);
+
+        (instance, witness)
+    }
+
+    #[test]
+    fn test_pedersen_equality_accept_valid() {
+        let rng = &mut thread_rng();
+        let (instance, witness) = pedersen_equality_setup(None, rng);
+
+        let batched_result =
+            run_nizk_batched::<Hash, PedersenEquality<_>, _>(&instance, &witness, rng);
+        let short_result = run_nizk_short::<Hash, PedersenEquality<_>, _>(&instance, &witness, rng);
+
+        assert!(batched_result.is_ok());
+        assert!(short_result.is_ok());
+    }
+
+    #[test]
+    fn test_pedersen_equality_reject_different_messages() {
+        let rng = &mut thread_rng();
+        let (instance, witness) = pedersen_equality_setup(Some(F::rand(rng)), rng);
+
+        let batched_result =
+            run_nizk_batched::<Hash, PedersenEquality<_>, _>(&instance, &witness, rng);
+        let short_result = run_nizk_short::<Hash, PedersenEquality<_>, _>(&instance, &witness, rng);
+
+        assert_eq!(batched_result, Err(SigmaError::VerificationFailed));
+        assert_eq!(short_result, Err(SigmaError::VerificationFailed));
+    }
+}
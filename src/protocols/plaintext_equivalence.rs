@@ -0,0 +1,290 @@
+use std::fmt;
+
+use ark_ec::ProjectiveCurve;
+use ark_ff::{Field, PrimeField};
+use ark_serialize::{CanonicalSerialize, SerializationError, Write};
+use ark_std::UniformRand;
+use rand::Rng;
+
+use super::{challenge_scalar, ElGamalCiphertext};
+use crate::{
+    group::wipe, interactive_proofs::canonical_bytes, secret::Redacted, Challenge,
+    ChallengeExpansion, CommitmentRecoverable, SigmaError, SigmaProtocol,
+};
+
+/// Proof that two ElGamal ciphertexts under two different public keys encrypt the same message `m * G`.
+///
+/// The prover shows knowledge of `(m, r1, r2)` such that both ciphertexts are `(r_i * G, m * G + r_i * PK_i)`.
+/// Each of the four ciphertext components gets its own commitment element so that the single response for `m`
+/// binds both encryptions to the same message.
+pub struct PlaintextEquivalence<G: ProjectiveCurve> {
+    instance: PlaintextEquivalenceInstance<G>,
+}
+
+/// The instance for the plaintext equivalence proof: the generator and both (public key, ciphertext) pairs
+#[derive(Debug, Clone, Copy, CanonicalSerialize)]
+pub struct PlaintextEquivalenceInstance<G: ProjectiveCurve> {
+    generator: G,
+    public_key1: G,
+    ciphertext1: ElGamalCiphertext<G>,
+    public_key2: G,
+    ciphertext2: ElGamalCiphertext<G>,
+}
+
+impl<G: ProjectiveCurve> PlaintextEquivalenceInstance<G> {
+    /// Create a new plaintext equivalence instance from the provided group elements
+    pub fn new(
+        generator: G,
+        public_key1: G,
+        ciphertext1: ElGamalCiphertext<G>,
+        public_key2: G,
+        ciphertext2: ElGamalCiphertext<G>,
+    ) -> Self {
+        Self {
+            generator,
+            public_key1,
+            ciphertext1,
+            public_key2,
+            ciphertext2,
+        }
+    }
+}
+
+/// A wrapper type for a plaintext equivalence witness: the shared message and both encryption randomnesses
+pub type PlaintextEquivalenceWitness<G> = (
+    <G as ProjectiveCurve>::ScalarField,
+    <G as ProjectiveCurve>::ScalarField,
+    <G as ProjectiveCurve>::ScalarField,
+);
+
+/// A wrapper type for a plaintext equivalence commitment: `(c1, c2)` of the first ciphertext then of the second
+pub type PlaintextEquivalenceCommitment<G> = (G, G, G, G);
+
+/// A wrapper type for a plaintext equivalence response, in the same order as the witness
+pub type PlaintextEquivalenceResponse<G> = PlaintextEquivalenceWitness<G>;
+
+pub struct ProverState<F: Field> {
+    witness: (F, F, F),
+    random_values: (F, F, F),
+}
+
+impl<F: Field> fmt::Debug for ProverState<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ProverState")
+            .field("witness", &Redacted)
+            .field("random_values", &Redacted)
+            .finish()
+    }
+}
+
+impl<G: ProjectiveCurve> PlaintextEquivalence<G> {
+    /// Evaluates both encryptions of `m * G` under `(r1, r2)`, flattened in commitment order
+    fn encrypt_both(
+        &self,
+        m: &G::ScalarField,
+        r1: &G::ScalarField,
+        r2: &G::ScalarField,
+    ) -> PlaintextEquivalenceCommitment<G> {
+        let instance = &self.instance;
+        let message = instance.generator.mul(m.into_repr());
+        let first =
+            ElGamalCiphertext::encrypt(instance.generator, instance.public_key1, message, r1);
+        let second =
+            ElGamalCiphertext::encrypt(instance.generator, instance.public_key2, message, r2);
+
+        (first.c1(), first.c2(), second.c1(), second.c2())
+    }
+}
+
+impl<G: ProjectiveCurve> SigmaProtocol for PlaintextEquivalence<G> {
+    type Instance = PlaintextEquivalenceInstance<G>;
+    type Commitment = PlaintextEquivalenceCommitment<G>;
+    type ProverState = ProverState<G::ScalarField>;
+    type Witness = PlaintextEquivalenceWitness<G>;
+    type Response = PlaintextEquivalenceResponse<G>;
+    type Challenge = G::ScalarField;
+
+    const PROTOCOL_ID: &'static [u8] = b"zkpstd/sigma/plaintext-equality";
+
+    fn new(instance: &PlaintextEquivalenceInstance<G>) -> Self {
+        Self {
+            instance: *instance,
+        }
+    }
+
+    fn challenge_from_bytes(
+        challenge: &Challenge,
+        expansion: ChallengeExpansion,
+    ) -> Result<G::ScalarField, SigmaError> {
+        challenge_scalar(challenge, expansion)
+    }
+
+    fn instance_bytes(&self) -> Vec<u8> {
+        canonical_bytes(&self.instance)
+    }
+
+    fn witness_bytes(&self, witness: &Self::Witness) -> Vec<u8> {
+        canonical_bytes(witness)
+    }
+
+    fn prover_commit<R: Rng>(
+        &self,
+        witness: &Self::Witness,
+        _expansion: ChallengeExpansion,
+        rng: &mut R,
+    ) -> Result<(Self::Commitment, Self::ProverState), SigmaError> {
+        let random_values = (
+            G::ScalarField::rand(rng),
+            G::ScalarField::rand(rng),
+            G::ScalarField::rand(rng),
+        );
+
+        let commitment = self.encrypt_both(&random_values.0, &random_values.1, &random_values.2);
+
+        let state = ProverState {
+            witness: *witness,
+            random_values,
+        };
+
+        Ok((commitment, state))
+    }
+
+    fn prover_response(
+        &self,
+        prover_state: &Self::ProverState,
+        challenge: &Self::Challenge,
+    ) -> Result<Self::Response, SigmaError> {
+        let (m, r1, r2) = prover_state.witness;
+        let (a, b1, b2) = prover_state.random_values;
+
+        Ok((
+            a - *challenge * m,
+            b1 - *challenge * r1,
+            b2 - *challenge * r2,
+        ))
+    }
+
+    fn verifier(
+        &self,
+        commitment: &Self::Commitment,
+        challenge: &Self::Challenge,
+        response: &Self::Response,
+    ) -> Result<(), SigmaError> {
+        if &self.simulate_commitment(challenge, response)? == commitment {
+            Ok(())
+        } else {
+            Err(SigmaError::VerificationFailed)
+        }
+    }
+
+    fn simulate_response<R: Rng>(&self, rng: &mut R) -> Self::Response {
+        (
+            G::ScalarField::rand(rng),
+            G::ScalarField::rand(rng),
+            G::ScalarField::rand(rng),
+        )
+    }
+
+    fn zeroize_state(&self, prover_state: &mut Self::ProverState) {
+        wipe(&mut prover_state.witness);
+        wipe(&mut prover_state.random_values);
+    }
+}
+
+impl<G: ProjectiveCurve> CommitmentRecoverable for PlaintextEquivalence<G> {
+    fn simulate_commitment(
+        &self,
+        challenge: &Self::Challenge,
+        response: &Self::Response,
+    ) -> Result<Self::Commitment, SigmaError> {
+        let challenge = challenge.into_repr();
+        let (s_m, s_r1, s_r2) = response;
+        let (e1, e2, e3, e4) = self.encrypt_both(s_m, s_r1, s_r2);
+        let (ciphertext1, ciphertext2) = (self.instance.ciphertext1, self.instance.ciphertext2);
+
+        Ok((
+            e1 + ciphertext1.c1().mul(challenge),
+            e2 + ciphertext1.c2().mul(challenge),
+            e3 + ciphertext2.c1().mul(challenge),
+            e4 + ciphertext2.c2().mul(challenge),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_ec::ProjectiveCurve;
+    use ark_ff::{PrimeField, UniformRand};
+    use rand::{thread_rng, Rng};
+
+    use crate::{
+        nizk_proofs::tests::{run_nizk_batched, run_nizk_short},
+        protocols::ElGamalCiphertext,
+        SigmaError,
+    };
+
+    use super::{PlaintextEquivalence, PlaintextEquivalenceInstance};
+
+    type Hash = blake2::Blake2s;
+    type G = ark_bls12_377::G1Projective;
+    type F = ark_bls12_377::Fr;
+
+    fn equivalence_setup<R: Rng>(
+        second_message: Option<F>,
+        rng: &mut R,
+    ) -> (PlaintextEquivalenceInstance<G>, (F, F, F)) {
+        let generator = G::prime_subgroup_generator();
+        let public_key1 = generator.mul(F::rand(rng).into_repr());
+        let public_key2 = generator.mul(F::rand(rng).into_repr());
+        let witness = (F::rand(rng), F::rand(rng), F::rand(rng));
+        let encrypt = |public_key: G, message: F, randomness: &F| {
+            ElGamalCiphertext::encrypt(
+                generator,
+                public_key,
+                generator.mul(message.into_repr()),
+                randomness,
+            )
+        };
+        let ciphertext1 = encrypt(public_key1, witness.0, &witness.1);
+        let ciphertext2 = encrypt(public_key2, second_message.unwrap_or(witness.0), &witness.2);
+
+        (
+            PlaintextEquivalenceInstance::new(
+                generator,
+                public_key1,
+                ciphertext1,
+                public_key2,
+                ciphertext2,
+            ),
+            witness,
+        )
+    }
+
+    #[test]
+    fn test_plaintext_equivalence_accept_valid() {
+        let rng = &mut thread_rng();
+        let (instance, witness) = equivalence_setup(None, rng);
+
+        let batched_result =
+            run_nizk_batched::<Hash, PlaintextEquivalence<_>, _>(&instance, &witness, rng);
+        let short_result =
+            run_nizk_short::<Hash, PlaintextEquivalence<_>, _>(&instance, &witness, rng);
+
+        assert!(batched_result.is_ok());
+        assert!(short_result.is_ok());
+    }
+
+    #[test]
+    fn test_plaintext_equivalence_reject_different_plaintexts() {
+        let rng = &mut thread_rng();
+        let (instance, witness) = equivalence_setup(Some(F::rand(rng)), rng);
+
+        let batched_result =
+            run_nizk_batched::<Hash, PlaintextEquivalence<_>, _>(&instance, &witness, rng);
+        let short_result =
+            run_nizk_short::<Hash, PlaintextEquivalence<_>, _>(&instance, &witness, rng);
+
+        assert_eq!(batched_result, Err(SigmaError::VerificationFailed));
+        assert_eq!(short_result, Err(SigmaError::VerificationFailed));
+    }
+}
@@ -0,0 +1,356 @@
+use std::fmt;
+
+use ark_ec::ProjectiveCurve;
+use ark_ff::{BigInteger, Field, PrimeField, UniformRand};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Read, SerializationError, Write};
+use rand::Rng;
+
+use super::{
+    challenge_scalar, BitCommitment, BitInstance, BitProof, BitResponse, PedersenOpening,
+    PedersenParams, SchnorrDLOG, SchnorrInstance,
+};
+use crate::{
+    interactive_proofs::canonical_bytes, secret::Redacted, Challenge, ChallengeExpansion,
+    SigmaError, SigmaProtocol,
+};
+
+/// Proof that a Pedersen commitment hides a value in `[0, 2^n)`.
+///
+/// The prover commits to each bit of the value, proves with a [`BitProof`] that every bit commitment opens to 0 or 1,
+/// and proves with a Schnorr proof on the blinding base that `C - sum(2^i * C_i)` commits to zero. All sub-proofs share
+/// the same challenge. Proof size is linear in `n`.
+///
+/// The bit commitments are part of the first message, so they cannot be recomputed from the challenge and response:
+/// this protocol only supports batchable proofs.
+pub struct RangeProof<G: ProjectiveCurve> {
+    instance: RangeInstance<G>,
+}
+
+/// The instance for the range proof: the Pedersen parameters, the commitment and the bit length `n`
+#[derive(Debug, Clone, Copy, CanonicalSerialize)]
+pub struct RangeInstance<G: ProjectiveCurve> {
+    params: PedersenParams<G>,
+    commitment: G,
+    bits: usize,
+}
+
+impl<G: ProjectiveCurve> RangeInstance<G> {
+    /// Create a new range instance claiming that `commitment` hides a value in `[0, 2^bits)`
+    pub fn new(params: PedersenParams<G>, commitment: G, bits: usize) -> Self {
+        Self {
+            params,
+            commitment,
+            bits,
+        }
+    }
+}
+
+/// A wrapper type for a range witness: the opening of the commitment
+pub type RangeWitness<G> = PedersenOpening<<G as ProjectiveCurve>::ScalarField>;
+
+/// The commitment of the range proof
+#[derive(Debug, Clone, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct RangeCommitment<G: ProjectiveCurve> {
+    /// Pedersen commitments to each bit, least significant first
+    bit_commitments: Vec<G>,
+    bit_proofs: Vec<BitCommitment<G>>,
+    sum_proof: G,
+}
+
+/// The response of the range proof
+#[derive(Debug, Clone, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct RangeResponse<G: ProjectiveCurve> {
+    bit_proofs: Vec<BitResponse<G>>,
+    sum_proof: G::ScalarField,
+}
+
+pub struct ProverState<G: ProjectiveCurve> {
+    bit_states: Vec<(BitProof<G>, <BitProof<G> as SigmaProtocol>::ProverState)>,
+    sum_state: (
+        SchnorrDLOG<G>,
+        <SchnorrDLOG<G> as SigmaProtocol>::ProverState,
+    ),
+}
+
+impl<G: ProjectiveCurve> fmt::Debug for ProverState<G> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ProverState")
+            .field("bit_states", &Redacted)
+            .field("sum_state", &Redacted)
+            .finish()
+    }
+}
+
+impl<G: ProjectiveCurve> RangeProof<G> {
+    /// The Schnorr statement `C - sum(2^i * C_i) = rho * H` tying the bit commitments to the commitment
+    fn sum_protocol(&self, bit_commitments: &[G]) -> SchnorrDLOG<G> {
+        let weighted_bits: G = bit_commitments
+            .iter()
+            .enumerate()
+            .map(|(i, bit_commitment)| {
+                bit_commitment.mul(G::ScalarField::from(2u64).pow([i as u64]).into_repr())
+            })
+            .sum();
+
+        SchnorrDLOG::new(&SchnorrInstance::new(
+            self.instance.params.blinding_base(),
+            self.instance.commitment - weighted_bits,
+        ))
+    }
+
+    fn bit_protocol(&self, bit_commitment: G) -> BitProof<G> {
+        BitProof::new(&BitInstance::new(self.instance.params, bit_commitment))
+    }
+}
+
+impl<G: ProjectiveCurve> SigmaProtocol for RangeProof<G> {
+    type Instance = RangeInstance<G>;
+    type Commitment = RangeCommitment<G>;
+    type ProverState = ProverState<G>;
+    type Witness = RangeWitness<G>;
+    type Response = RangeResponse<G>;
+    /// The scalar every bit proof and the sum proof read
+    type Challenge = G::ScalarField;
+
+    const PROTOCOL_ID: &'static [u8] = b"zkpstd/sigma/range";
+
+    fn new(instance: &RangeInstance<G>) -> Self {
+        Self {
+            instance: *instance,
+        }
+    }
+
+    fn challenge_from_bytes(
+        challenge: &Challenge,
+        expansion: ChallengeExpansion,
+    ) -> Result<G::ScalarField, SigmaError> {
+        challenge_scalar(challenge, expansion)
+    }
+
+    /// Rejects a bit length of at least the scalar field's, for which `[0, 2^n)` covers every scalar and the proof
+    /// proves nothing
+    fn validate_instance(&self) -> Result<(), SigmaError> {
+        if self.instance.bits >= G::ScalarField::size_in_bits() {
+            Err(SigmaError::InvalidInstance)
+        } else {
+            Ok(())
+        }
+    }
+
+    fn instance_bytes(&self) -> Vec<u8> {
+        canonical_bytes(&self.instance)
+    }
+
+    fn witness_bytes(&self, witness: &Self::Witness) -> Vec<u8> {
+        canonical_bytes(witness)
+    }
+
+    fn prover_commit<R: Rng>(
+        &self,
+        witness: &Self::Witness,
+        expansion: ChallengeExpansion,
+        rng: &mut R,
+    ) -> Result<(Self::Commitment, Self::ProverState), SigmaError> {
+        // Values outside the range are truncated to their low bits, the sum proof then fails to verify
+        let value_bits = witness.message().into_repr().to_bits_le();
+        let bit_openings: Vec<_> = value_bits
+            .into_iter()
+            .take(self.instance.bits)
+            .map(|bit| PedersenOpening::new(G::ScalarField::from(bit), G::ScalarField::rand(rng)))
+            .collect();
+        let bit_commitments: Vec<_> = bit_openings
+            .iter()
+            .map(|opening| self.instance.params.commit_opening(opening))
+            .collect();
+
+        let mut bit_proofs = Vec::with_capacity(self.instance.bits);
+        let mut bit_states = Vec::with_capacity(self.instance.bits);
+        for (opening, bit_commitment) in bit_openings.iter().zip(&bit_commitments) {
+            let protocol = self.bit_protocol(*bit_commitment);
+            let (commitment, state) = protocol.prover_commit(opening, expansion, rng)?;
+            bit_proofs.push(commitment);
+            bit_states.push((protocol, state));
+        }
+
+        let combined_blinding =
+            bit_openings
+                .iter()
+                .enumerate()
+                .fold(witness.blinding(), |acc, (i, opening)| {
+                    acc - G::ScalarField::from(2u64).pow([i as u64]) * opening.blinding()
+                });
+        let sum_protocol = self.sum_protocol(&bit_commitments);
+        let (sum_proof, sum_state) =
+            sum_protocol.prover_commit(&combined_blinding, expansion, rng)?;
+
+        let commitment = RangeCommitment {
+            bit_commitments,
+            bit_proofs,
+            sum_proof,
+        };
+        let state = ProverState {
+            bit_states,
+            sum_state: (sum_protocol, sum_state),
+        };
+
+        Ok((commitment, state))
+    }
+
+    fn prover_response(
+        &self,
+        prover_state: &Self::ProverState,
+        challenge: &Self::Challenge,
+    ) -> Result<Self::Response, SigmaError> {
+        let bit_proofs = prover_state
+            .bit_states
+            .iter()
+            .map(|(protocol, state)| protocol.prover_response(state, challenge))
+            .collect::<Result<_, _>>()?;
+        let (sum_protocol, sum_state) = &prover_state.sum_state;
+
+        Ok(RangeResponse {
+            bit_proofs,
+            sum_proof: sum_protocol.prover_response(sum_state, challenge)?,
+        })
+    }
+
+    fn verifier(
+        &self,
+        commitment: &Self::Commitment,
+        challenge: &Self::Challenge,
+        response: &Self::Response,
+    ) -> Result<(), SigmaError> {
+        let bits = self.instance.bits;
+        if commitment.bit_commitments.len() != bits
+            || commitment.bit_proofs.len() != bits
+            || response.bit_proofs.len() != bits
+        {
+            return Err(SigmaError::LengthMismatch);
+        }
+
+        for ((bit_commitment, bit_proof), bit_response) in commitment
+            .bit_commitments
+            .iter()
+            .zip(&commitment.bit_proofs)
+            .zip(&response.bit_proofs)
+        {
+            self.bit_protocol(*bit_commitment)
+                .verifier(bit_proof, challenge, bit_response)?;
+        }
+
+        self.sum_protocol(&commitment.bit_commitments).verifier(
+            &commitment.sum_proof,
+            challenge,
+            &response.sum_proof,
+        )
+    }
+
+    fn simulate_response<R: Rng>(&self, rng: &mut R) -> Self::Response {
+        RangeResponse {
+            bit_proofs: (0..self.instance.bits)
+                .map(|_| {
+                    (
+                        G::ScalarField::rand(rng),
+                        G::ScalarField::rand(rng),
+                        G::ScalarField::rand(rng),
+                    )
+                })
+                .collect(),
+            sum_proof: G::ScalarField::rand(rng),
+        }
+    }
+
+    fn zeroize_state(&self, prover_state: &mut Self::ProverState) {
+        for (protocol, state) in &mut prover_state.bit_states {
+            protocol.zeroize_state(state);
+        }
+        let (protocol, state) = &mut prover_state.sum_state;
+        protocol.zeroize_state(state);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_ff::{PrimeField, UniformRand};
+    use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+    use rand::{thread_rng, Rng};
+
+    use crate::{
+        nizk_proofs::tests::run_nizk_batched,
+        protocols::{PedersenOpening, PedersenParams},
+        BatchableProof, SigmaError, SigmaProtocol, NIZK,
+    };
+
+    use super::{RangeInstance, RangeProof, RangeWitness};
+
+    type Hash = blake2::Blake2s;
+    type G = ark_bls12_377::G1Projective;
+    type F = ark_bls12_377::Fr;
+
+    const BITS: usize = 8;
+
+    fn range_setup<R: Rng>(value: u64, rng: &mut R) -> (RangeInstance<G>, RangeWitness<G>) {
+        let params = PedersenParams::new(G::rand(rng), G::rand(rng));
+        let opening = PedersenOpening::new(F::from(value), F::rand(rng));
+
+        (
+            RangeInstance::new(params, params.commit_opening(&opening), BITS),
+            opening,
+        )
+    }
+
+    #[test]
+    fn test_range_accept_bounds() {
+        let rng = &mut thread_rng();
+
+        for value in [0, (1 << BITS) - 1] {
+            let (instance, witness) = range_setup(value, rng);
+
+            let test_result = run_nizk_batched::<Hash, RangeProof<_>, _>(&instance, &witness, rng);
+
+            assert!(test_result.is_ok());
+        }
+    }
+
+    #[test]
+    fn test_range_reject_out_of_range() {
+        let rng = &mut thread_rng();
+        let (instance, witness) = range_setup(1 << BITS, rng);
+
+        let test_result = run_nizk_batched::<Hash, RangeProof<_>, _>(&instance, &witness, rng);
+
+        assert_eq!(test_result, Err(SigmaError::VerificationFailed));
+    }
+
+    #[test]
+    fn test_range_reject_field_wide_bit_length() {
+        let rng = &mut thread_rng();
+        let (instance, _) = range_setup(42, rng);
+        let modulus_bits = F::size_in_bits();
+
+        for bits in [modulus_bits, modulus_bits + 1] {
+            let wide = RangeInstance::new(instance.params, instance.commitment, bits);
+            assert_eq!(
+                RangeProof::new(&wide).validate_instance(),
+                Err(SigmaError::InvalidInstance)
+            );
+            assert!(NIZK::<RangeProof<_>, Hash>::try_new(&wide, b"ctx").is_err());
+        }
+        let widest = RangeInstance::new(instance.params, instance.commitment, modulus_bits - 1);
+        assert!(RangeProof::new(&widest).validate_instance().is_ok());
+    }
+
+    #[test]
+    fn test_range_batchable_proof_round_trip() {
+        let rng = &mut thread_rng();
+        let (instance, witness) = range_setup(42, rng);
+        let nizk = NIZK::<RangeProof<_>, Hash>::new(&instance, b"ctx");
+
+        let proof = nizk.batchable_proof(&witness, None, rng).unwrap();
+        let mut bytes = Vec::new();
+        proof.serialize(&mut bytes).unwrap();
+        let decoded = BatchableProof::<RangeProof<G>>::deserialize(&bytes[..]).unwrap();
+
+        assert!(nizk.batchable_verify(&decoded, None).is_ok());
+    }
+}
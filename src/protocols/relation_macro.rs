@@ -0,0 +1,545 @@
+/// Defines a Sigma protocol for a linear statement over a prime-order group, such as
+/// `A = x * G, B = x * H`, by delegating to [`LinearMapProof`](crate::protocols::LinearMapProof).
+///
+/// The relation names its witness scalars, the public bases and the statements, each one the sum of products of a
+/// witness scalar with a base or with the left-hand side of an earlier statement. The macro defines a module holding
+///
+/// * the protocol, named by the relation, generic over a [`ProjectiveCurve`](ark_ec::ProjectiveCurve) `G`
+/// * `Instance<G>`, with one group element field per base and per statement, in the order of the definition, and
+///   `Instance::from_witness` computing the statements from the bases
+/// * `Witness<G>`, with one scalar field per witness scalar
+/// * `Commitment<G>`, with one group element field per statement, and `Response<G>`, with one scalar field per
+///   witness scalar
+///
+/// The fields are serialized in the order of the definition, so a relation defined like a hand-written protocol
+/// commits and responds with the same bytes. The protocol id is `zkpstd/sigma/relation/` followed by the textual
+/// form of the relation, so relations differing in a name or a statement never share labels.
+///
+/// Bases are listed explicitly: a `macro_rules!` macro cannot collect the distinct names of the statements by
+/// itself. A scalar outside the witness list, or a base that is neither listed nor defined by an earlier statement,
+/// fails to compile.
+///
+/// ```
+/// use ark_ec::ProjectiveCurve;
+/// use sigma_protocol_standard::{define_relation, NIZK};
+///
+/// define_relation! {
+///     /// Knowledge of `x` such that `A = x * G` and `B = x * H`
+///     pub mod equal_logs: Dleq {
+///         witness: x;
+///         bases: G, H;
+///         statements: A = x * G, B = x * H;
+///     }
+/// }
+///
+/// type G1 = ark_bls12_377::G1Projective;
+///
+/// let rng = &mut rand::thread_rng();
+/// let witness = equal_logs::Witness { x: rand::Rng::gen(rng) };
+/// let (g, h) = (G1::prime_subgroup_generator(), rand::Rng::gen(rng));
+/// let instance = equal_logs::Instance::from_witness(g, h, &witness);
+///
+/// let nizk = NIZK::<equal_logs::Dleq<G1>, blake2::Blake2s>::new(&instance, b"context");
+/// let proof = nizk.batchable_proof(&witness, None, rng).unwrap();
+/// assert!(nizk.batchable_verify(&proof, None).is_ok());
+/// ```
+///
+/// ```compile_fail
+/// sigma_protocol_standard::define_relation! {
+///     // `y` is not a witness scalar
+///     pub mod unknown_scalar: Unknown {
+///         witness: x;
+///         bases: G;
+///         statements: A = y * G;
+///     }
+/// }
+/// ```
+#[macro_export]
+macro_rules! define_relation {
+    (
+        $(#[$meta:meta])*
+        $vis:vis mod $module:ident : $name:ident {
+            witness: $witness0:ident $(, $witness:ident)* ;
+            bases: $base0:ident $(, $base:ident)* ;
+            statements:
+                $lhs0:ident = $scalar0:ident * $point0:ident $(+ $scalars0:ident * $points0:ident)*
+                $(, $lhs:ident = $scalar:ident * $point:ident $(+ $scalars:ident * $points:ident)*)* ;
+        }
+    ) => {
+        $crate::define_relation! {
+            @module
+            [$(#[$meta])*] $vis $module $name
+            concat!(
+                "zkpstd/sigma/relation/", stringify!($name),
+                "{witness:", stringify!($witness0), $(",", stringify!($witness),)*
+                ";bases:", stringify!($base0), $(",", stringify!($base),)*
+                ";statements:",
+                stringify!($lhs0), "=", stringify!($scalar0), "*", stringify!($point0),
+                $("+", stringify!($scalars0), "*", stringify!($points0),)*
+                $(
+                    ",", stringify!($lhs), "=", stringify!($scalar), "*", stringify!($point),
+                    $("+", stringify!($scalars), "*", stringify!($points),)*
+                )*
+                "}"
+            );
+            [$witness0 $($witness)*]
+            [$base0 $($base)*]
+            [
+                ($lhs0 = ($scalar0 $point0) $(($scalars0 $points0))*)
+                $(($lhs = ($scalar $point) $(($scalars $points))*))*
+            ]
+        }
+    };
+
+    (
+        @module
+        [$(#[$meta:meta])*] $vis:vis $module:ident $name:ident $id:expr;
+        [$($witness:ident)+]
+        [$($base:ident)+]
+        [$(($lhs:ident = $(($scalar:ident $point:ident))+))+]
+    ) => {
+        $(#[$meta])*
+        $vis mod $module {
+            use $crate::__private::{
+                CanonicalSerialize, Challenge, ChallengeExpansion, CommitmentRecoverable,
+                LinearMapInstance, LinearMapProof, PrimeField, ProjectiveCurve, Rng, SigmaError,
+                SigmaProtocol, Zero,
+            };
+
+            $crate::define_relation! {
+                @struct
+                #[doc = concat!("The instance of [`", stringify!($name), "`]: the bases and the left-hand sides of the statements")]
+                Instance: G [public] { $($base)+ $($lhs)+ }
+            }
+
+            $crate::define_relation! {
+                @struct
+                #[doc = concat!(
+                    "The witness of [`", stringify!($name), "`], one scalar per witness name. Its debug output \
+                    omits the scalars"
+                )]
+                Witness: <G as ProjectiveCurve>::ScalarField [redacted] { $($witness)+ }
+            }
+
+            $crate::define_relation! {
+                @struct
+                #[doc = concat!("The commitment of [`", stringify!($name), "`], one group element per statement")]
+                Commitment: G [public] { $($lhs)+ }
+            }
+
+            $crate::define_relation! {
+                @struct
+                #[doc = concat!("The response of [`", stringify!($name), "`], one scalar per witness name")]
+                Response: <G as ProjectiveCurve>::ScalarField [public] { $($witness)+ }
+            }
+
+            impl<G: ProjectiveCurve> Instance<G> {
+                /// Create the instance of `witness` over the bases, whose statements are evaluated in order
+                #[allow(non_snake_case, clippy::too_many_arguments)]
+                pub fn from_witness($($base: G,)+ witness: &Witness<G>) -> Self {
+                    #[allow(unused_variables)]
+                    let Witness { $($witness),+ } = *witness;
+                    $(
+                        let $lhs = <G as Zero>::zero()
+                            $(+ ProjectiveCurve::mul($point, PrimeField::into_repr(&$scalar)))+;
+                    )+
+                    Self { $($base,)+ $($lhs,)+ }
+                }
+
+                /// The statements as a linear map, with one column per witness scalar
+                #[allow(non_snake_case, unused_variables)]
+                fn linear_map(&self) -> LinearMapInstance<G> {
+                    let Self { $($base,)+ $($lhs,)+ } = *self;
+                    let row = |terms: &[(&str, G)]| -> Vec<G> {
+                        [$(stringify!($witness)),+]
+                            .iter()
+                            .map(|name| {
+                                terms
+                                    .iter()
+                                    .filter(|(scalar, _)| scalar == name)
+                                    .fold(<G as Zero>::zero(), |sum, (_, point)| sum + point)
+                            })
+                            .collect()
+                    };
+                    LinearMapInstance::new(
+                        vec![$(row(&[$((stringify!($scalar), $point)),+])),+],
+                        vec![$($lhs),+],
+                    )
+                }
+            }
+
+            #[doc = concat!(
+                "The proof of knowledge of the relation `", stringify!($name), "`, see [`define_relation!`]"
+            )]
+            pub struct $name<G: ProjectiveCurve> {
+                instance: Instance<G>,
+                linear_map: LinearMapProof<G>,
+            }
+
+            impl<G: ProjectiveCurve> SigmaProtocol for $name<G> {
+                type Instance = Instance<G>;
+                type Commitment = Commitment<G>;
+                type ProverState = <LinearMapProof<G> as SigmaProtocol>::ProverState;
+                type Witness = Witness<G>;
+                type Response = Response<G>;
+                type Challenge = <LinearMapProof<G> as SigmaProtocol>::Challenge;
+
+                const PROTOCOL_ID: &'static [u8] = $id.as_bytes();
+
+                fn new(instance: &Instance<G>) -> Self {
+                    Self {
+                        instance: *instance,
+                        linear_map: LinearMapProof::new(&instance.linear_map()),
+                    }
+                }
+
+                fn challenge_from_bytes(
+                    challenge: &Challenge,
+                    expansion: ChallengeExpansion,
+                ) -> Result<Self::Challenge, SigmaError> {
+                    LinearMapProof::<G>::challenge_from_bytes(challenge, expansion)
+                }
+
+                fn instance_bytes(&self) -> Vec<u8> {
+                    let mut bytes = Vec::new();
+                    self.instance
+                        .serialize(&mut bytes)
+                        .expect("serializing into a vector does not fail");
+                    bytes
+                }
+
+                fn witness_bytes(&self, witness: &Self::Witness) -> Vec<u8> {
+                    let mut bytes = Vec::new();
+                    witness
+                        .serialize(&mut bytes)
+                        .expect("serializing into a vector does not fail");
+                    bytes
+                }
+
+                fn is_valid_witness(&self, witness: &Self::Witness) -> bool {
+                    let Instance { $($base,)+ .. } = self.instance;
+                    Instance::from_witness($($base,)+ witness) == self.instance
+                }
+
+                fn prover_commit<R: Rng>(
+                    &self,
+                    witness: &Self::Witness,
+                    expansion: ChallengeExpansion,
+                    rng: &mut R,
+                ) -> Result<(Self::Commitment, Self::ProverState), SigmaError> {
+                    let (commitment, state) =
+                        self.linear_map.prover_commit(&witness.values(), expansion, rng)?;
+                    Ok((Commitment::from_values(commitment), state))
+                }
+
+                fn prover_response(
+                    &self,
+                    prover_state: &Self::ProverState,
+                    challenge: &Self::Challenge,
+                ) -> Result<Self::Response, SigmaError> {
+                    self.linear_map
+                        .prover_response(prover_state, challenge)
+                        .map(Response::from_values)
+                }
+
+                fn verifier(
+                    &self,
+                    commitment: &Self::Commitment,
+                    challenge: &Self::Challenge,
+                    response: &Self::Response,
+                ) -> Result<(), SigmaError> {
+                    self.linear_map
+                        .verifier(&commitment.values(), challenge, &response.values())
+                }
+
+                fn simulate_response<R: Rng>(&self, rng: &mut R) -> Self::Response {
+                    Response::from_values(self.linear_map.simulate_response(rng))
+                }
+
+                fn zeroize_state(&self, prover_state: &mut Self::ProverState) {
+                    self.linear_map.zeroize_state(prover_state)
+                }
+            }
+
+            impl<G: ProjectiveCurve> CommitmentRecoverable for $name<G> {
+                fn simulate_commitment(
+                    &self,
+                    challenge: &Self::Challenge,
+                    response: &Self::Response,
+                ) -> Result<Self::Commitment, SigmaError> {
+                    self.linear_map
+                        .simulate_commitment(challenge, &response.values())
+                        .map(Commitment::from_values)
+                }
+            }
+        }
+    };
+
+    (
+        @struct
+        #[doc = $doc:expr]
+        $struct:ident: $ty:ty [$debug:ident] { $($field:ident)+ }
+    ) => {
+        #[doc = $doc]
+        #[allow(non_snake_case)]
+        #[derive(Clone, Copy, PartialEq, Eq)]
+        pub struct $struct<G: ProjectiveCurve> {
+            $(
+                #[doc = concat!("`", stringify!($field), "`")]
+                pub $field: $ty,
+            )+
+        }
+
+        impl<G: ProjectiveCurve> ::core::fmt::Debug for $struct<G> {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                f.debug_struct(stringify!($struct))
+                    $(.field(stringify!($field), $crate::define_relation!(@debug $debug self.$field)))+
+                    .finish()
+            }
+        }
+
+        #[allow(dead_code)]
+        impl<G: ProjectiveCurve> $struct<G> {
+            fn values(&self) -> Vec<$ty> {
+                vec![$(self.$field),+]
+            }
+
+            /// The caller is responsible for passing one value per field
+            fn from_values(values: Vec<$ty>) -> Self {
+                let mut values = values.into_iter();
+                Self {
+                    $($field: values.next().expect("one value per field"),)+
+                }
+            }
+        }
+
+        impl<G: ProjectiveCurve> $crate::__private::CanonicalSerialize for $struct<G> {
+            fn serialize<W: $crate::__private::Write>(
+                &self,
+                mut writer: W,
+            ) -> Result<(), $crate::__private::SerializationError> {
+                $(self.$field.serialize(&mut writer)?;)+
+                Ok(())
+            }
+
+            fn serialized_size(&self) -> usize {
+                0 $(+ self.$field.serialized_size())+
+            }
+
+            fn serialize_uncompressed<W: $crate::__private::Write>(
+                &self,
+                mut writer: W,
+            ) -> Result<(), $crate::__private::SerializationError> {
+                $(self.$field.serialize_uncompressed(&mut writer)?;)+
+                Ok(())
+            }
+
+            fn serialize_unchecked<W: $crate::__private::Write>(
+                &self,
+                mut writer: W,
+            ) -> Result<(), $crate::__private::SerializationError> {
+                $(self.$field.serialize_unchecked(&mut writer)?;)+
+                Ok(())
+            }
+
+            fn uncompressed_size(&self) -> usize {
+                0 $(+ self.$field.uncompressed_size())+
+            }
+        }
+
+        impl<G: ProjectiveCurve> $crate::__private::CanonicalDeserialize for $struct<G> {
+            fn deserialize<R: $crate::__private::Read>(
+                mut reader: R,
+            ) -> Result<Self, $crate::__private::SerializationError> {
+                Ok(Self {
+                    $($field: <$ty as $crate::__private::CanonicalDeserialize>::deserialize(&mut reader)?,)+
+                })
+            }
+
+            fn deserialize_uncompressed<R: $crate::__private::Read>(
+                mut reader: R,
+            ) -> Result<Self, $crate::__private::SerializationError> {
+                Ok(Self {
+                    $($field: <$ty as $crate::__private::CanonicalDeserialize>::deserialize_uncompressed(
+                        &mut reader,
+                    )?,)+
+                })
+            }
+
+            fn deserialize_unchecked<R: $crate::__private::Read>(
+                mut reader: R,
+            ) -> Result<Self, $crate::__private::SerializationError> {
+                Ok(Self {
+                    $($field: <$ty as $crate::__private::CanonicalDeserialize>::deserialize_unchecked(
+                        &mut reader,
+                    )?,)+
+                })
+            }
+        }
+    };
+
+    (@debug public $field:expr) => {
+        &$field
+    };
+
+    (@debug redacted $field:expr) => {
+        &$crate::__private::Redacted
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_ec::ProjectiveCurve;
+    use ark_ff::UniformRand;
+    use rand::{rngs::StdRng, thread_rng, SeedableRng};
+
+    use crate::{
+        interactive_proofs::canonical_bytes,
+        nizk_proofs::tests::{run_nizk_batched, run_nizk_short},
+        protocols::{DleqInstance, DleqProof},
+        secret::assert_redacted,
+        ChallengeExpansion, SigmaError, SigmaProtocol,
+    };
+
+    type Hash = blake2::Blake2s;
+    type G = ark_bls12_377::G1Projective;
+    type F = ark_bls12_377::Fr;
+
+    // Any challenge that parses as a scalar will do
+    const CHALLENGE: u64 = 1;
+
+    crate::define_relation! {
+        #[allow(unreachable_pub)]
+        mod equal_logs: Dleq {
+            witness: x;
+            bases: G, H;
+            statements: A = x * G, B = x * H;
+        }
+    }
+
+    // An ElGamal encryption of `m * G` under `Y`, whose second statement reuses the first
+    crate::define_relation! {
+        #[allow(unreachable_pub)]
+        mod elgamal: Encryption {
+            witness: k, m;
+            bases: G, Y;
+            statements: C1 = k * G, C2 = k * Y + m * G, D = k * C1;
+        }
+    }
+
+    #[test]
+    fn test_relation_agrees_with_dleq() {
+        let rng = &mut thread_rng();
+        let (base1, base2) = (G::rand(rng), G::rand(rng));
+        let witness = F::rand(rng);
+        let instance =
+            equal_logs::Instance::from_witness(base1, base2, &equal_logs::Witness { x: witness });
+        let relation = equal_logs::Dleq::new(&instance);
+        let dleq = DleqProof::new(&DleqInstance::from_witness(base1, base2, &witness));
+
+        let (relation_commitment, relation_state) = relation
+            .prover_commit(
+                &equal_logs::Witness { x: witness },
+                ChallengeExpansion::default(),
+                &mut StdRng::seed_from_u64(0),
+            )
+            .unwrap();
+        let (dleq_commitment, dleq_state) = dleq
+            .prover_commit(
+                &witness,
+                ChallengeExpansion::default(),
+                &mut StdRng::seed_from_u64(0),
+            )
+            .unwrap();
+        let relation_response = relation
+            .prover_response(&relation_state, &F::from(CHALLENGE))
+            .unwrap();
+        let dleq_response = dleq
+            .prover_response(&dleq_state, &F::from(CHALLENGE))
+            .unwrap();
+
+        assert_eq!(
+            canonical_bytes(&relation_commitment),
+            canonical_bytes(&dleq_commitment)
+        );
+        assert_eq!(
+            canonical_bytes(&relation_response),
+            canonical_bytes(&dleq_response)
+        );
+        assert!(dleq
+            .verifier(
+                &(relation_commitment.A, relation_commitment.B),
+                &F::from(CHALLENGE),
+                &relation_response.x
+            )
+            .is_ok());
+    }
+
+    #[test]
+    fn test_relation_protocol_id_is_its_textual_form() {
+        assert_eq!(
+            equal_logs::Dleq::<G>::PROTOCOL_ID,
+            b"zkpstd/sigma/relation/Dleq{witness:x;bases:G,H;statements:A=x*G,B=x*H}"
+        );
+        assert_eq!(
+            elgamal::Encryption::<G>::PROTOCOL_ID,
+            &b"zkpstd/sigma/relation/Encryption{witness:k,m;bases:G,Y;statements:C1=k*G,C2=k*Y+m*G,D=k*C1}"[..]
+        );
+    }
+
+    #[test]
+    fn test_relation_witness_debug_is_redacted() {
+        let rng = &mut thread_rng();
+        let witness = elgamal::Witness::<G> {
+            k: F::rand(rng),
+            m: F::rand(rng),
+        };
+
+        let expected = "Witness { k: <redacted>, m: <redacted> }";
+        assert_redacted(&witness, &witness.k, expected);
+        assert_redacted(&witness, &witness.m, expected);
+
+        let response = elgamal::Response::<G> {
+            k: witness.k,
+            m: F::from(2u64),
+        };
+        assert!(format!("{:?}", response).starts_with("Response { k: "));
+    }
+
+    #[test]
+    fn test_relation_nizk() {
+        let rng = &mut thread_rng();
+        let witness = elgamal::Witness {
+            k: F::rand(rng),
+            m: F::rand(rng),
+        };
+        let instance = elgamal::Instance::from_witness(G::rand(rng), G::rand(rng), &witness);
+        assert_eq!(
+            instance.D,
+            instance.C1.mul(ark_ff::PrimeField::into_repr(&witness.k))
+        );
+
+        assert!(
+            run_nizk_batched::<Hash, elgamal::Encryption<G>, _>(&instance, &witness, rng).is_ok()
+        );
+        assert!(
+            run_nizk_short::<Hash, elgamal::Encryption<G>, _>(&instance, &witness, rng).is_ok()
+        );
+
+        let wrong = elgamal::Witness {
+            m: F::rand(rng),
+            ..witness
+        };
+        assert!(!elgamal::Encryption::new(&instance).is_valid_witness(&wrong));
+        assert!(
+            !elgamal::Encryption::new(&instance).is_valid_witness(&elgamal::Witness {
+                k: F::rand(rng),
+                ..witness
+            })
+        );
+        assert_eq!(
+            run_nizk_batched::<Hash, elgamal::Encryption<G>, _>(&instance, &wrong, rng),
+            Err(SigmaError::VerificationFailed)
+        );
+    }
+}
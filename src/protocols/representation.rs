@@ -0,0 +1,394 @@
+use std::{fmt, sync::Arc};
+
+use ark_ec::ProjectiveCurve;
+use ark_ff::{Field, PrimeField};
+use ark_serialize::{CanonicalSerialize, SerializationError, Write};
+use ark_std::UniformRand;
+use rand::Rng;
+
+use super::challenge_scalar;
+use crate::{
+    group::wipe, interactive_proofs::canonical_bytes, secret::Redacted, Challenge,
+    ChallengeExpansion, CommitmentRecoverable, SigmaError, SigmaGroup, SigmaProtocol,
+};
+
+/// Proof of knowledge of a representation of a point with respect to an arbitrary number of bases.
+#[derive(Clone)]
+pub struct Representation<G: ProjectiveCurve> {
+    instance: Arc<RepresentationInstance<G>>,
+}
+
+/// The instance for the representation proof. The prover claims to know scalars `x_i` such that
+/// `target = sum(x_i * bases[i])`
+#[derive(Debug, Clone, CanonicalSerialize)]
+pub struct RepresentationInstance<G: ProjectiveCurve> {
+    bases: Vec<G>,
+    target: G,
+}
+
+impl<G: ProjectiveCurve> RepresentationInstance<G> {
+    /// Create a new representation instance from the provided group elements
+    pub fn new(bases: Vec<G>, target: G) -> Self {
+        Self { bases, target }
+    }
+}
+
+/// A wrapper type for a representation witness, one scalar per base
+pub type RepresentationWitness<G> = Vec<<G as ProjectiveCurve>::ScalarField>;
+
+/// A wrapper type for a representation response, one scalar per base
+pub type RepresentationResponse<G> = Vec<<G as ProjectiveCurve>::ScalarField>;
+
+pub struct ProverState<F: Field> {
+    witness: Vec<F>,
+    random_values: Vec<F>,
+}
+
+impl<F: Field> fmt::Debug for ProverState<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ProverState")
+            .field("witness", &Redacted)
+            .field("random_values", &Redacted)
+            .finish()
+    }
+}
+
+/// Computes `sum(scalars[i] * bases[i])`, the caller is responsible for matching lengths
+pub(crate) fn linear_combination<G: ProjectiveCurve>(bases: &[G], scalars: &[G::ScalarField]) -> G {
+    bases
+        .iter()
+        .zip(scalars)
+        .map(|(base, scalar)| base.mul(scalar.into_repr()))
+        .sum()
+}
+
+impl<G: ProjectiveCurve> SigmaProtocol for Representation<G> {
+    type Instance = RepresentationInstance<G>;
+    type Commitment = G;
+    type ProverState = ProverState<G::ScalarField>;
+    type Witness = RepresentationWitness<G>;
+    type Response = RepresentationResponse<G>;
+    type Challenge = G::ScalarField;
+
+    const PROTOCOL_ID: &'static [u8] = b"zkpstd/sigma/representation";
+
+    fn new(instance: &RepresentationInstance<G>) -> Self {
+        Self::from_shared(Arc::new(instance.clone()))
+    }
+
+    fn from_shared(instance: Arc<RepresentationInstance<G>>) -> Self {
+        Self { instance }
+    }
+
+    fn challenge_from_bytes(
+        challenge: &Challenge,
+        expansion: ChallengeExpansion,
+    ) -> Result<G::ScalarField, SigmaError> {
+        challenge_scalar(challenge, expansion)
+    }
+
+    fn instance_bytes(&self) -> Vec<u8> {
+        canonical_bytes(&*self.instance)
+    }
+
+    fn witness_bytes(&self, witness: &Self::Witness) -> Vec<u8> {
+        canonical_bytes(witness)
+    }
+
+    fn prover_commit<R: Rng>(
+        &self,
+        witness: &Self::Witness,
+        _expansion: ChallengeExpansion,
+        rng: &mut R,
+    ) -> Result<(Self::Commitment, Self::ProverState), SigmaError> {
+        if witness.len() != self.instance.bases.len() {
+            return Err(SigmaError::LengthMismatch);
+        }
+
+        let random_values: Vec<_> = (0..witness.len())
+            .map(|_| G::ScalarField::rand(rng))
+            .collect();
+
+        let commitment = linear_combination(&self.instance.bases, &random_values);
+
+        let state = ProverState {
+            witness: witness.clone(),
+            random_values,
+        };
+
+        Ok((commitment, state))
+    }
+
+    fn prover_response(
+        &self,
+        prover_state: &Self::ProverState,
+        challenge: &Self::Challenge,
+    ) -> Result<Self::Response, SigmaError> {
+        Ok(prover_state
+            .random_values
+            .iter()
+            .zip(&prover_state.witness)
+            .map(|(random_value, witness)| *random_value - *challenge * witness)
+            .collect())
+    }
+
+    fn verifier(
+        &self,
+        commitment: &Self::Commitment,
+        challenge: &Self::Challenge,
+        response: &Self::Response,
+    ) -> Result<(), SigmaError> {
+        if &self.simulate_commitment(challenge, response)? == commitment {
+            Ok(())
+        } else {
+            Err(SigmaError::VerificationFailed)
+        }
+    }
+
+    fn simulate_response<R: Rng>(&self, rng: &mut R) -> Self::Response {
+        (0..self.instance.bases.len())
+            .map(|_| G::ScalarField::rand(rng))
+            .collect()
+    }
+
+    fn zeroize_state(&self, prover_state: &mut Self::ProverState) {
+        wipe(&mut prover_state.witness);
+        wipe(&mut prover_state.random_values);
+    }
+}
+
+impl<G: ProjectiveCurve> CommitmentRecoverable for Representation<G> {
+    fn simulate_commitment(
+        &self,
+        challenge: &Self::Challenge,
+        response: &Self::Response,
+    ) -> Result<Self::Commitment, SigmaError> {
+        if response.len() != self.instance.bases.len() {
+            return Err(SigmaError::LengthMismatch);
+        }
+
+        let bases: Vec<_> = self
+            .instance
+            .bases
+            .iter()
+            .chain([&self.instance.target])
+            .copied()
+            .collect();
+        let scalars: Vec<_> = response.iter().chain([challenge]).copied().collect();
+
+        Ok(G::multi_scalar_mul(&bases, &scalars))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{iter, sync::Arc};
+
+    use ark_ec::ProjectiveCurve;
+    use ark_ff::UniformRand;
+    use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+    use rand::{
+        rngs::{mock::StepRng, StdRng},
+        thread_rng, Rng, SeedableRng,
+    };
+
+    use crate::{
+        nizk_proofs::tests::{run_nizk_batched, run_nizk_short},
+        protocols::{SchnorrDLOG, SchnorrInstance},
+        testing::interactive::run_interactive,
+        BatchableProof, ChallengeExpansion, SigmaError, SigmaProtocol, NIZK,
+    };
+
+    use super::{linear_combination, Representation, RepresentationInstance};
+
+    type Hash = blake2::Blake2s;
+    type G = ark_bls12_377::G1Projective;
+    type F = ark_bls12_377::Fr;
+
+    fn representation_setup<R: Rng>(
+        n: usize,
+        rng: &mut R,
+    ) -> (RepresentationInstance<G>, Vec<F>, Vec<F>) {
+        let bases: Vec<G> = (0..n).map(|_| G::rand(rng)).collect();
+        let witness: Vec<F> = (0..n).map(|_| F::rand(rng)).collect();
+        let target = linear_combination(&bases, &witness);
+        let instance = RepresentationInstance::new(bases, target);
+        let mut wrong_witness = witness.clone();
+        wrong_witness[n - 1] = F::rand(rng);
+
+        (instance, witness, wrong_witness)
+    }
+
+    #[test]
+    fn test_representation_single_base_matches_schnorr() {
+        let (instance, witness, _) = representation_setup(1, &mut thread_rng());
+        let schnorr_instance = SchnorrInstance::new(instance.bases[0], instance.target);
+        let representation = Representation::new(&instance);
+        let schnorr = SchnorrDLOG::new(&schnorr_instance);
+        // Any challenge will do
+        let challenge = F::from(1u64);
+
+        let (rep_commitment, rep_state) = representation
+            .prover_commit(
+                &witness,
+                ChallengeExpansion::default(),
+                &mut StdRng::seed_from_u64(0),
+            )
+            .unwrap();
+        let (schnorr_commitment, schnorr_state) = schnorr
+            .prover_commit(
+                &witness[0],
+                ChallengeExpansion::default(),
+                &mut StdRng::seed_from_u64(0),
+            )
+            .unwrap();
+        let rep_response = representation
+            .prover_response(&rep_state, &challenge)
+            .unwrap();
+        let schnorr_response = schnorr.prover_response(&schnorr_state, &challenge).unwrap();
+
+        assert_eq!(rep_commitment, schnorr_commitment);
+        assert_eq!(rep_response, vec![schnorr_response]);
+        assert!(schnorr
+            .verifier(&rep_commitment, &challenge, &rep_response[0])
+            .is_ok());
+    }
+
+    #[test]
+    fn test_representation_accept_valid() {
+        let rng = &mut thread_rng();
+
+        for n in [1, 3] {
+            let (instance, witness, _) = representation_setup(n, rng);
+
+            let batched_result =
+                run_nizk_batched::<Hash, Representation<_>, _>(&instance, &witness, rng);
+            let short_result =
+                run_nizk_short::<Hash, Representation<_>, _>(&instance, &witness, rng);
+
+            assert!(batched_result.is_ok());
+            assert!(short_result.is_ok());
+        }
+    }
+
+    #[test]
+    fn test_representation_nonces_bind_the_whole_witness() {
+        let rng = &mut thread_rng();
+        // With the second base a known multiple of the first, the statement has many witnesses
+        let (s, h) = (F::rand(rng), F::rand(rng));
+        let base = G::rand(rng);
+        let bases = vec![base, linear_combination(&[base], &[s])];
+        let witness = vec![F::rand(rng), F::rand(rng)];
+        let other = vec![witness[0] + s * h, witness[1] - h];
+        let instance =
+            RepresentationInstance::new(bases.clone(), linear_combination(&bases, &witness));
+        assert_eq!(instance.target, linear_combination(&bases, &other));
+
+        let protocol = Representation::new(&instance);
+        assert_ne!(
+            protocol.witness_bytes(&witness),
+            protocol.witness_bytes(&other)
+        );
+
+        let nizk = NIZK::<Representation<G>, Hash>::new(&instance, b"ctx");
+        let first = nizk
+            .batchable_proof(&witness, Some(b"message"), &mut StepRng::new(0, 0))
+            .unwrap();
+        let second = nizk
+            .batchable_proof(&other, Some(b"message"), &mut StepRng::new(0, 0))
+            .unwrap();
+        assert!(nizk.batchable_verify(&first, Some(b"message")).is_ok());
+        assert!(nizk.batchable_verify(&second, Some(b"message")).is_ok());
+        assert_ne!(first.commitment(), second.commitment());
+    }
+
+    #[test]
+    fn test_representation_interactive_completeness() {
+        let rng = &mut thread_rng();
+        let (instance, witness, _) = representation_setup(4, rng);
+
+        assert!(run_interactive::<Representation<_>, _>(&instance, &witness, rng).is_ok());
+    }
+
+    #[test]
+    fn test_representation_reject_wrong() {
+        let rng = &mut thread_rng();
+
+        for n in [1, 3] {
+            let (instance, _, wrong_witness) = representation_setup(n, rng);
+
+            let batched_result =
+                run_nizk_batched::<Hash, Representation<_>, _>(&instance, &wrong_witness, rng);
+            let short_result =
+                run_nizk_short::<Hash, Representation<_>, _>(&instance, &wrong_witness, rng);
+
+            assert_eq!(batched_result, Err(SigmaError::VerificationFailed));
+            assert_eq!(short_result, Err(SigmaError::VerificationFailed));
+        }
+    }
+
+    #[test]
+    fn test_representation_reject_length_mismatch() {
+        let rng = &mut thread_rng();
+        let (instance, mut witness, _) = representation_setup(3, rng);
+        witness.pop();
+
+        let test_result = run_nizk_batched::<Hash, Representation<_>, _>(&instance, &witness, rng);
+
+        assert_eq!(test_result, Err(SigmaError::LengthMismatch))
+    }
+
+    #[test]
+    fn test_representation_batchable_proof_round_trip() {
+        let rng = &mut thread_rng();
+        let (instance, witness, _) = representation_setup(3, rng);
+        let nizk = NIZK::<Representation<_>, Hash>::new(&instance, b"ctx");
+
+        let proof = nizk.batchable_proof(&witness, None, rng).unwrap();
+        let mut bytes = Vec::new();
+        proof.serialize(&mut bytes).unwrap();
+        let decoded = BatchableProof::<Representation<G>>::deserialize(&bytes[..]).unwrap();
+
+        assert!(nizk.batchable_verify(&decoded, None).is_ok());
+    }
+
+    #[test]
+    fn test_representation_shares_large_instances() {
+        // Multiples of the generator, as sampling 10k random bases takes a scalar multiplication each
+        let generator = G::prime_subgroup_generator();
+        let bases: Vec<G> = iter::successors(Some(generator), |base| Some(*base + generator))
+            .take(10_000)
+            .collect();
+        let instance = Arc::new(RepresentationInstance::new(bases, generator));
+
+        let protocol = Representation::from_shared(instance.clone());
+        assert!(Arc::ptr_eq(&protocol.instance, &instance));
+        let clone = protocol.clone();
+        assert_eq!(Arc::strong_count(&instance), 3);
+        drop((protocol, clone));
+
+        // The prover and the verifier each hold the instance of the caller
+        let nizk = NIZK::<Representation<G>, Hash>::from_shared(instance.clone(), b"ctx").unwrap();
+        assert_eq!(Arc::strong_count(&instance), 3);
+        drop(nizk);
+        assert_eq!(Arc::strong_count(&instance), 1);
+    }
+
+    #[test]
+    fn test_representation_batched_proofs_share_the_instance() {
+        let rng = &mut thread_rng();
+        let (instance, witness, _) = representation_setup(3, rng);
+        let instance = Arc::new(instance);
+        let nizk = NIZK::<Representation<G>, Hash>::from_shared(instance.clone(), b"ctx").unwrap();
+
+        let jobs: Vec<_> = [&b"first"[..], b"second", b"third"]
+            .into_iter()
+            .map(|message| (witness.clone(), Some(message)))
+            .collect();
+        let proofs = nizk.batchable_proofs(&jobs, rng).unwrap();
+        for (proof, (_, message)) in proofs.iter().zip(&jobs) {
+            assert!(nizk.batchable_verify(proof, *message).is_ok());
+        }
+        assert_eq!(Arc::strong_count(&instance), 3);
+    }
+}
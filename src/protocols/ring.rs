@@ -0,0 +1,231 @@
+use ark_ec::ProjectiveCurve;
+use rand::Rng;
+
+use super::{SetMembership, SetMembershipInstance, SetMembershipWitness};
+use crate::{
+    Challenge, ChallengeExpansion, CommitmentRecoverable, ShortProof, SigmaError, SigmaProtocol,
+    SufficientOutput, Transcript, NIZK,
+};
+
+/// Proof of knowledge of the secret key behind one of a ring of public keys.
+///
+/// This is a [`SetMembership`] proof over the public keys with respect to the generator: the challenge of each branch
+/// is part of the response, so the verifier recomputes every branch and checks that the challenges sum to the
+/// Fiat-Shamir challenge.
+pub struct RingOfKeys<G: ProjectiveCurve> {
+    membership: SetMembership<G>,
+}
+
+/// The instance for the ring proof: the generator and the public keys of the ring members
+#[derive(Debug, Clone)]
+pub struct RingOfKeysInstance<G: ProjectiveCurve> {
+    generator: G,
+    public_keys: Vec<G>,
+}
+
+impl<G: ProjectiveCurve> RingOfKeysInstance<G> {
+    /// Create a new ring instance from the generator and the public keys of the ring
+    pub fn new(generator: G, public_keys: Vec<G>) -> Self {
+        Self {
+            generator,
+            public_keys,
+        }
+    }
+}
+
+impl<G: ProjectiveCurve> From<&RingOfKeysInstance<G>> for SetMembershipInstance<G> {
+    fn from(instance: &RingOfKeysInstance<G>) -> Self {
+        SetMembershipInstance::new(instance.generator, instance.public_keys.clone())
+    }
+}
+
+/// A wrapper type for a ring witness: the position of the signer in the ring and their secret key
+pub type RingOfKeysWitness<G> = SetMembershipWitness<G>;
+
+impl<G: ProjectiveCurve> SigmaProtocol for RingOfKeys<G> {
+    type Instance = RingOfKeysInstance<G>;
+    type Commitment = <SetMembership<G> as SigmaProtocol>::Commitment;
+    type ProverState = <SetMembership<G> as SigmaProtocol>::ProverState;
+    type Witness = RingOfKeysWitness<G>;
+    type Response = <SetMembership<G> as SigmaProtocol>::Response;
+    type Challenge = <SetMembership<G> as SigmaProtocol>::Challenge;
+
+    const PROTOCOL_ID: &'static [u8] = b"zkpstd/sigma/ring-of-keys";
+
+    fn new(instance: &RingOfKeysInstance<G>) -> Self {
+        Self {
+            membership: SetMembership::new(&instance.into()),
+        }
+    }
+
+    fn challenge_from_bytes(
+        challenge: &Challenge,
+        expansion: ChallengeExpansion,
+    ) -> Result<Self::Challenge, SigmaError> {
+        SetMembership::<G>::challenge_from_bytes(challenge, expansion)
+    }
+
+    fn instance_bytes(&self) -> Vec<u8> {
+        self.membership.instance_bytes()
+    }
+
+    fn witness_bytes(&self, witness: &Self::Witness) -> Vec<u8> {
+        self.membership.witness_bytes(witness)
+    }
+
+    fn prover_commit<R: Rng>(
+        &self,
+        witness: &Self::Witness,
+        expansion: ChallengeExpansion,
+        rng: &mut R,
+    ) -> Result<(Self::Commitment, Self::ProverState), SigmaError> {
+        self.membership.prover_commit(witness, expansion, rng)
+    }
+
+    fn prover_response(
+        &self,
+        prover_state: &Self::ProverState,
+        challenge: &Self::Challenge,
+    ) -> Result<Self::Response, SigmaError> {
+        self.membership.prover_response(prover_state, challenge)
+    }
+
+    fn verifier(
+        &self,
+        commitment: &Self::Commitment,
+        challenge: &Self::Challenge,
+        response: &Self::Response,
+    ) -> Result<(), SigmaError> {
+        self.membership.verifier(commitment, challenge, response)
+    }
+
+    fn simulate_response<R: Rng>(&self, rng: &mut R) -> Self::Response {
+        self.membership.simulate_response(rng)
+    }
+
+    fn zeroize_state(&self, prover_state: &mut Self::ProverState) {
+        self.membership.zeroize_state(prover_state);
+    }
+}
+
+impl<G: ProjectiveCurve> CommitmentRecoverable for RingOfKeys<G> {
+    fn simulate_commitment(
+        &self,
+        challenge: &Self::Challenge,
+        response: &Self::Response,
+    ) -> Result<Self::Commitment, SigmaError> {
+        self.membership.simulate_commitment(challenge, response)
+    }
+}
+
+/// A ring signature: a short [`RingOfKeys`] proof that always binds a message.
+pub struct RingSignature<G: ProjectiveCurve, D: Transcript> {
+    nizk: NIZK<RingOfKeys<G>, D>,
+}
+
+impl<G: ProjectiveCurve, D: SufficientOutput + Default> RingSignature<G, D> {
+    /// Initialise the signature scheme for the provided ring and context
+    pub fn new(ring: &RingOfKeysInstance<G>, ctx: &[u8]) -> Self {
+        Self {
+            nizk: NIZK::new(ring, ctx),
+        }
+    }
+
+    /// Sign `message` on behalf of the ring using the signer's position and secret key
+    pub fn sign<R: Rng>(
+        &self,
+        signer: &RingOfKeysWitness<G>,
+        message: &[u8],
+        rng: &mut R,
+    ) -> Result<ShortProof<RingOfKeys<G>>, SigmaError> {
+        self.nizk.short_proof(signer, Some(message), rng)
+    }
+
+    /// Verify that `signature` was produced on `message` by a member of the ring
+    pub fn verify(
+        &self,
+        signature: &ShortProof<RingOfKeys<G>>,
+        message: &[u8],
+    ) -> Result<(), SigmaError> {
+        self.nizk.short_verify(signature, Some(message))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_ec::ProjectiveCurve;
+    use ark_ff::{PrimeField, UniformRand};
+    use rand::{thread_rng, Rng};
+
+    use crate::{ShortProof, SigmaError};
+
+    use super::{RingOfKeys, RingOfKeysInstance, RingSignature};
+
+    type Hash = blake2::Blake2s;
+    type G = ark_bls12_377::G1Projective;
+    type F = ark_bls12_377::Fr;
+
+    const CTX: &[u8] = b"ring signature test";
+
+    fn ring_setup<R: Rng>(size: usize, rng: &mut R) -> (RingOfKeysInstance<G>, Vec<F>) {
+        let generator = G::prime_subgroup_generator();
+        let secret_keys: Vec<_> = (0..size).map(|_| F::rand(rng)).collect();
+        let public_keys = secret_keys
+            .iter()
+            .map(|sk| generator.mul(sk.into_repr()))
+            .collect();
+
+        (RingOfKeysInstance::new(generator, public_keys), secret_keys)
+    }
+
+    fn sign<R: Rng>(
+        scheme: &mut RingSignature<G, Hash>,
+        signer: (usize, F),
+        message: &[u8],
+        rng: &mut R,
+    ) -> ShortProof<RingOfKeys<G>> {
+        scheme.sign(&signer, message, rng).unwrap()
+    }
+
+    #[test]
+    fn test_ring_signature_ring_sizes() {
+        let rng = &mut thread_rng();
+
+        for size in [1, 2, 16] {
+            let (ring, secret_keys) = ring_setup(size, rng);
+            let mut scheme = RingSignature::<G, Hash>::new(&ring, CTX);
+            let signer = size - 1;
+
+            let signature = sign(&mut scheme, (signer, secret_keys[signer]), b"message", rng);
+
+            assert!(scheme.verify(&signature, b"message").is_ok());
+        }
+    }
+
+    #[test]
+    fn test_ring_signature_different_signers() {
+        let rng = &mut thread_rng();
+        let (ring, secret_keys) = ring_setup(16, rng);
+        let mut scheme = RingSignature::<G, Hash>::new(&ring, CTX);
+
+        let first = sign(&mut scheme, (2, secret_keys[2]), b"message", rng);
+        let second = sign(&mut scheme, (11, secret_keys[11]), b"message", rng);
+
+        assert!(scheme.verify(&first, b"message").is_ok());
+        assert!(scheme.verify(&second, b"message").is_ok());
+    }
+
+    #[test]
+    fn test_ring_signature_reject_other_message() {
+        let rng = &mut thread_rng();
+        let (ring, secret_keys) = ring_setup(4, rng);
+        let mut scheme = RingSignature::<G, Hash>::new(&ring, CTX);
+
+        let signature = sign(&mut scheme, (0, secret_keys[0]), b"message", rng);
+
+        assert_eq!(
+            scheme.verify(&signature, b"another message"),
+            Err(SigmaError::VerificationFailed)
+        );
+    }
+}
@@ -1,134 +1,486 @@
-use ark_ec::ProjectiveCurve;
-use ark_ff::{Field, PrimeField};
-use ark_std::UniformRand;
+use std::{fmt, sync::Arc};
+
+use ark_ec::{msm::VariableBaseMSM, ProjectiveCurve};
+use ark_ff::{FpParameters, PrimeField, Zero};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Read, SerializationError, Write};
 use rand::Rng;
 
-use crate::{Challenge, SigmaError, SigmaProtocol, CHALLENGE_LENGTH};
+use crate::{
+    group::wipe,
+    group::{short_multi_scalar_mul, FixedBaseMul},
+    interactive_proofs::{canonical_bytes, canonical_ct_eq},
+    secret::Redacted,
+    BatchRandomizers, BatchVerifiable, Challenge, ChallengeExpansion, CommitmentRecoverable,
+    FixedBaseTable, SigmaError, SigmaGroup, SigmaProtocol, Zeroizable,
+};
 
 /// Schnorr proof of knowledge of the discrete logarithm.
-pub struct SchnorrDLOG<G: ProjectiveCurve> {
+///
+/// Over an arkworks curve, [`SchnorrDLOG::with_precomputation`] caches fixed-base tables for the instance, which
+/// the prover, the verifier and the simulator then multiply with. A NIZK built with
+/// [`NIZK::from_protocol`](crate::NIZK::from_protocol) proves and verifies with them
+#[derive(Clone)]
+pub struct SchnorrDLOG<G: SigmaGroup> {
     instance: SchnorrInstance<G>,
+    tables: Option<SchnorrTables<G>>,
+    randomizers: BatchRandomizers,
+}
+
+/// The tables of the base and, if it has one, of the claim. They are shared by the clones of a protocol and, for
+/// the base, between instances over the same base
+#[derive(Clone)]
+struct SchnorrTables<G: SigmaGroup> {
+    base: Arc<dyn FixedBaseMul<G>>,
+    claim: Option<Arc<dyn FixedBaseMul<G>>>,
 }
 
 /// The instance for the DLOG proof. It is composed of two group elements:
 /// the prover claims to know the discrete log between the `base` point and the `claim` point
-#[derive(Debug, Clone, Copy)]
-pub struct SchnorrInstance<G: ProjectiveCurve> {
+#[derive(Debug, Clone, Copy, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct SchnorrInstance<G: SigmaGroup> {
     base: G,
     claim: G,
 }
 
-impl<G: ProjectiveCurve> SchnorrInstance<G> {
+impl<G: SigmaGroup> SchnorrInstance<G> {
     /// Create a new DLOG instance from the provided group elements
     pub fn new(base: G, claim: G) -> Self {
         Self { base, claim }
     }
+
+    /// Create the DLOG instance of `witness` over `base`, whose claim is `base` multiplied by the witness
+    pub fn from_witness(base: G, witness: &G::Scalar) -> Self {
+        Self::new(base, base.scalar_mul(witness))
+    }
+
+    /// Sample a witness, and create its DLOG instance over the standard generator of the group
+    pub fn generate<R: Rng>(rng: &mut R) -> (Self, G::Scalar) {
+        let witness = G::random_scalar(rng);
+        (Self::from_witness(G::generator(), &witness), witness)
+    }
+
+    /// Create a new DLOG instance, checking it as [`SigmaProtocol::validate_instance`] does
+    pub fn try_new(base: G, claim: G) -> Result<Self, SigmaError> {
+        let instance = Self::new(base, claim);
+        instance.validate()?;
+        Ok(instance)
+    }
+
+    /// The base, of which the claim is a multiple
+    pub fn base(&self) -> G {
+        self.base
+    }
+
+    /// The claim, the base multiplied by the witness
+    pub fn claim(&self) -> G {
+        self.claim
+    }
+
+    /// Rejects an identity base, for which every claim but the identity has no discrete logarithm and the
+    /// identity has all of them, and points outside the prime-order subgroup
+    fn validate(&self) -> Result<(), SigmaError> {
+        if self.base.is_identity()
+            || !self.base.is_in_prime_subgroup()
+            || !self.claim.is_in_prime_subgroup()
+        {
+            Err(SigmaError::InvalidInstance)
+        } else {
+            Ok(())
+        }
+    }
 }
 
 /// A wrapper type for a Schnorr witness
-pub type SchnorrWitness<G> = <G as ProjectiveCurve>::ScalarField;
+pub type SchnorrWitness<G> = <G as SigmaGroup>::Scalar;
 
 /// A wrapper type for a schnorr instance
-pub type SchnorrResponse<G> = <G as ProjectiveCurve>::ScalarField;
+pub type SchnorrResponse<G> = <G as SigmaGroup>::Scalar;
 
-pub struct ProverState<F: Field> {
+/// The witness and the nonce, which are redacted from the debug output and wiped on drop with the `zeroize` feature
+pub struct ProverState<F: Zeroizable> {
     witness: F,
     random_value: F,
 }
 
-impl<G: ProjectiveCurve> SigmaProtocol for SchnorrDLOG<G> {
+impl<F: Zeroizable> fmt::Debug for ProverState<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ProverState")
+            .field("witness", &Redacted)
+            .field("random_value", &Redacted)
+            .finish()
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl<F: Zeroizable> zeroize::Zeroize for ProverState<F> {
+    fn zeroize(&mut self) {
+        self.witness.zeroize();
+        self.random_value.zeroize();
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl<F: Zeroizable> Drop for ProverState<F> {
+    fn drop(&mut self) {
+        zeroize::Zeroize::zeroize(self);
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl<F: Zeroizable> zeroize::ZeroizeOnDrop for ProverState<F> {}
+
+/// Encoded as the witness followed by the nonce, for [`NIZK::commit_phase`](crate::NIZK::commit_phase)
+impl<F: Zeroizable + CanonicalSerialize> CanonicalSerialize for ProverState<F> {
+    fn serialize<W: Write>(&self, mut writer: W) -> Result<(), SerializationError> {
+        self.witness.serialize(&mut writer)?;
+        self.random_value.serialize(writer)
+    }
+
+    fn serialized_size(&self) -> usize {
+        self.witness.serialized_size() + self.random_value.serialized_size()
+    }
+}
+
+impl<F: Zeroizable + CanonicalDeserialize> CanonicalDeserialize for ProverState<F> {
+    fn deserialize<R: Read>(mut reader: R) -> Result<Self, SerializationError> {
+        Ok(Self {
+            witness: F::deserialize(&mut reader)?,
+            random_value: F::deserialize(reader)?,
+        })
+    }
+}
+
+impl<G: ProjectiveCurve> SchnorrDLOG<G> {
+    /// Precompute tables for both the base and the claim, for an instance proven or verified many times
+    pub fn with_precomputation(self) -> Self {
+        let base = Arc::new(FixedBaseTable::new(self.instance.base));
+        let claim = Arc::new(FixedBaseTable::new(self.instance.claim));
+
+        Self {
+            tables: Some(SchnorrTables {
+                base,
+                claim: Some(claim),
+            }),
+            ..self
+        }
+    }
+
+    /// Multiply the base with `table`, which can be shared between instances over the same base with different
+    /// claims. The claim keeps being multiplied from scratch. Panics if the table is for another point
+    pub fn with_base_table(self, table: Arc<FixedBaseTable<G>>) -> Self {
+        assert_eq!(
+            table.base(),
+            self.instance.base,
+            "the table is for another point than the base"
+        );
+
+        Self {
+            tables: Some(SchnorrTables {
+                base: table,
+                claim: None,
+            }),
+            ..self
+        }
+    }
+}
+
+impl<G: SigmaGroup> SchnorrDLOG<G> {
+    /// Weight the transcripts of a batch with `randomizers` instead of the default 128-bit weights, see
+    /// [`BatchRandomizers::with_bits`] for the soundness of a width
+    pub fn with_batch_randomizers(self, randomizers: BatchRandomizers) -> Self {
+        Self {
+            randomizers,
+            ..self
+        }
+    }
+
+    fn base_mul(&self, scalar: &G::Scalar) -> G {
+        match &self.tables {
+            Some(tables) => tables.base.mul(scalar),
+            None => self.instance.base.scalar_mul(scalar),
+        }
+    }
+
+    /// `response * base + challenge * claim`, the commitment the verifier and the simulator recompute
+    fn recompute_commitment(&self, response: &G::Scalar, challenge: &G::Scalar) -> G {
+        match &self.tables {
+            Some(tables) => {
+                let claim = match &tables.claim {
+                    Some(claim) => claim.mul(challenge),
+                    None => self.instance.claim.scalar_mul(challenge),
+                };
+                tables.base.mul(response) + claim
+            }
+            None => G::multi_scalar_mul(
+                &[self.instance.base, self.instance.claim],
+                &[*response, *challenge],
+            ),
+        }
+    }
+
+    /// Commit with an explicitly provided nonce instead of sampling one.
+    ///
+    /// This is meant for reproducing test vectors: reusing a nonce across two challenges reveals the witness.
+    pub fn prover_commit_with_nonce(
+        &self,
+        witness: &SchnorrWitness<G>,
+        nonce: G::Scalar,
+    ) -> (G, ProverState<G::Scalar>) {
+        let commitment = self.base_mul(&nonce);
+
+        let state = ProverState {
+            witness: *witness,
+            random_value: nonce,
+        };
+
+        (commitment, state)
+    }
+}
+
+impl<G: SigmaGroup> SigmaProtocol for SchnorrDLOG<G> {
     type Instance = SchnorrInstance<G>;
     type Commitment = G;
-    type ProverState = ProverState<G::ScalarField>;
+    type ProverState = ProverState<G::Scalar>;
     type Witness = SchnorrWitness<G>;
     type Response = SchnorrResponse<G>;
+    type Challenge = G::Scalar;
 
-    // TODO: Fix this according to the spec. Need to decide whether hashing is decided at the interactive stage or later at NIZK
-    // Same hash function as for challenge? Domain separation?
-    fn label(&self) -> [u8; CHALLENGE_LENGTH] {
-        [0; CHALLENGE_LENGTH]
-    }
+    const PROTOCOL_ID: &'static [u8] = b"zkpstd/sigma/dlog";
+
+    /// The challenge is expanded into a scalar, so 16 bytes give the 128-bit soundness of 128-bit profiles
+    const MIN_CHALLENGE_LENGTH: usize = 16;
 
     fn new(instance: &SchnorrInstance<G>) -> Self {
         Self {
             instance: *instance,
+            tables: None,
+            randomizers: BatchRandomizers::default(),
         }
     }
 
+    fn challenge_from_bytes(
+        challenge: &Challenge,
+        expansion: ChallengeExpansion,
+    ) -> Result<G::Scalar, SigmaError> {
+        G::challenge_scalar(challenge, expansion)
+    }
+
+    fn validate_instance(&self) -> Result<(), SigmaError> {
+        self.instance.validate()
+    }
+
+    fn instance_bytes(&self) -> Vec<u8> {
+        canonical_bytes(&self.instance)
+    }
+
+    fn witness_bytes(&self, witness: &Self::Witness) -> Vec<u8> {
+        canonical_bytes(witness)
+    }
+
+    fn is_valid_witness(&self, witness: &Self::Witness) -> bool {
+        self.base_mul(witness) == self.instance.claim
+    }
+
+    /// Building with `RUSTFLAGS="--cfg sigma_check_witnesses"` asserts in debug builds that the witness is valid,
+    /// to catch configuration bugs. It is opt-in, as proving with a wrong witness is otherwise a rejected proof
     fn prover_commit<R: Rng>(
         &self,
         witness: &Self::Witness,
+        _expansion: ChallengeExpansion,
         rng: &mut R,
-    ) -> (Self::Commitment, Self::ProverState) {
-        // TODO change this with the seeding from standard. Same hash function as for challenge? Domain separation?
-        let random_value = G::ScalarField::rand(rng);
-
-        let commitment = self.instance.base.mul(random_value.into_repr());
+    ) -> Result<(Self::Commitment, Self::ProverState), SigmaError> {
+        #[cfg(sigma_check_witnesses)]
+        debug_assert!(
+            self.is_valid_witness(witness),
+            "the witness does not match the instance"
+        );
+        let random_value = G::random_scalar(rng);
 
-        let state = ProverState {
-            witness: *witness,
-            random_value,
-        };
-
-        (commitment, state)
+        Ok(self.prover_commit_with_nonce(witness, random_value))
     }
 
     fn prover_response(
         &self,
         prover_state: &Self::ProverState,
-        challenge: &Challenge,
+        challenge: &Self::Challenge,
     ) -> Result<Self::Response, SigmaError> {
-        let challenge_scalar = G::ScalarField::from_random_bytes(challenge)
-            .ok_or(SigmaError::ChallengeConversionFailure)?;
-
-        Ok(prover_state.random_value - challenge_scalar * prover_state.witness)
+        Ok(prover_state.random_value - *challenge * prover_state.witness)
     }
 
     fn verifier(
         &self,
         commitment: &Self::Commitment,
-        challenge: &Challenge,
+        challenge: &Self::Challenge,
         response: &Self::Response,
     ) -> Result<(), crate::SigmaError> {
-        let challenge_scalar = G::ScalarField::from_random_bytes(challenge)
-            .ok_or(SigmaError::ChallengeConversionFailure)?;
+        let expected = self.recompute_commitment(response, challenge);
 
-        if &(self.instance.base.mul(response.into_repr())
-            + self.instance.claim.mul(challenge_scalar.into_repr()))
-            == commitment
-        {
-            return Ok(());
+        if bool::from(canonical_ct_eq(&expected, commitment)) {
+            Ok(())
         } else {
-            return Err(SigmaError::VerificationFailed);
+            Err(SigmaError::VerificationFailed)
         }
     }
 
     fn simulate_response<R: Rng>(&self, rng: &mut R) -> Self::Response {
-        G::ScalarField::rand(rng)
+        G::random_scalar(rng)
+    }
+
+    fn normalize_commitments(&self, commitments: &mut [G]) {
+        G::batch_normalize(commitments);
     }
 
+    fn zeroize_state(&self, prover_state: &mut Self::ProverState) {
+        wipe(prover_state);
+    }
+}
+
+impl<G: SigmaGroup> CommitmentRecoverable for SchnorrDLOG<G> {
     fn simulate_commitment(
         &self,
-        challenge: &Challenge,
+        challenge: &Self::Challenge,
         response: &Self::Response,
     ) -> Result<Self::Commitment, SigmaError> {
-        let challenge_scalar = G::ScalarField::from_random_bytes(challenge)
-            .ok_or(SigmaError::ChallengeConversionFailure)?;
+        Ok(self.recompute_commitment(response, challenge))
+    }
+}
+
+impl<G: ProjectiveCurve> BatchVerifiable for SchnorrDLOG<G> {
+    /// Checks `sum(w_i * T_i) = sum(w_i * s_i) * base + sum(w_i * c_i) * claim` for random weights `w_i`, 128-bit
+    /// unless set with [`SchnorrDLOG::with_batch_randomizers`], instead of two scalar multiplications per
+    /// transcript. The commitments are weighted in a multi-scalar multiplication over the width of the weights,
+    /// which 128-bit weights make about half as long as over full-width scalars
+    fn batch_verifier<R: Rng>(
+        &self,
+        transcripts: &[(&Self::Commitment, Self::Challenge, &Self::Response)],
+        rng: &mut R,
+    ) -> Result<(), SigmaError> {
+        let mut bases = Vec::with_capacity(transcripts.len() + 2);
+        let mut scalars = Vec::with_capacity(transcripts.len() + 2);
+        let (mut response_sum, mut challenge_sum) =
+            (G::ScalarField::zero(), G::ScalarField::zero());
+
+        for (commitment, challenge, response) in transcripts {
+            let weight: G::ScalarField = self.randomizers.sample(rng);
+            response_sum += weight * **response;
+            challenge_sum += weight * challenge;
+
+            bases.push(**commitment);
+            scalars.push(weight.into_repr());
+        }
+
+        bases.extend([self.instance.base, self.instance.claim]);
+        scalars.extend([(-response_sum).into_repr(), (-challenge_sum).into_repr()]);
+
+        let bases = G::batch_normalization_into_affine(&bases);
+        let bits = self.randomizers.bits();
+        let sum = if bits < <G::ScalarField as PrimeField>::Params::MODULUS_BITS {
+            // The weights are short and the two sums full-width
+            let (commitments, instance) = bases.split_at(transcripts.len());
+            let (weights, sums) = scalars.split_at(transcripts.len());
+            short_multi_scalar_mul::<G>(commitments, weights, bits)
+                + VariableBaseMSM::multi_scalar_mul(instance, sums)
+        } else {
+            VariableBaseMSM::multi_scalar_mul(&bases, &scalars)
+        };
+        if sum.is_zero() {
+            Ok(())
+        } else {
+            Err(SigmaError::VerificationFailed)
+        }
+    }
+}
+
+#[cfg(any(feature = "ristretto", feature = "secp256k1"))]
+impl<G: SigmaGroup> SchnorrDLOG<G>
+where
+    G::Scalar: From<u128>,
+{
+    /// Checks `sum(w_i * T_i) = sum(w_i * s_i) * base + sum(w_i * c_i) * claim` for random weights `w_i` with two
+    /// multi-scalar multiplications, for the groups other than arkworks curves
+    fn weighted_batch_verifier<R: Rng>(
+        &self,
+        transcripts: &[(&G, G::Scalar, &SchnorrResponse<G>)],
+        rng: &mut R,
+    ) -> Result<(), SigmaError> {
+        let mut commitments = Vec::with_capacity(transcripts.len());
+        let mut weights = Vec::with_capacity(transcripts.len());
+        let mut sums: Option<(G::Scalar, G::Scalar)> = None;
 
-        Ok(self.instance.base.mul(response.into_repr())
-            + self.instance.claim.mul(challenge_scalar.into_repr()))
+        for (commitment, challenge, response) in transcripts {
+            let weight = self.randomizers.sample_scalar::<G, _>(rng);
+            let terms = (weight * **response, weight * *challenge);
+            sums = Some(match sums {
+                Some((response_sum, challenge_sum)) => {
+                    (response_sum + terms.0, challenge_sum + terms.1)
+                }
+                None => terms,
+            });
+
+            commitments.push(**commitment);
+            weights.push(weight);
+        }
+
+        let Some((response_sum, challenge_sum)) = sums else {
+            return Ok(());
+        };
+        if G::multi_scalar_mul(&commitments, &weights)
+            == G::multi_scalar_mul(
+                &[self.instance.base, self.instance.claim],
+                &[response_sum, challenge_sum],
+            )
+        {
+            Ok(())
+        } else {
+            Err(SigmaError::VerificationFailed)
+        }
+    }
+}
+
+#[cfg(feature = "ristretto")]
+impl BatchVerifiable for SchnorrDLOG<crate::ristretto::RistrettoElement> {
+    /// The check of the arkworks curves, with 128-bit weights unless set with
+    /// [`SchnorrDLOG::with_batch_randomizers`], over the multi-scalar multiplication of `curve25519-dalek`
+    fn batch_verifier<R: Rng>(
+        &self,
+        transcripts: &[(&Self::Commitment, Self::Challenge, &Self::Response)],
+        rng: &mut R,
+    ) -> Result<(), SigmaError> {
+        self.weighted_batch_verifier(transcripts, rng)
+    }
+}
+
+#[cfg(feature = "secp256k1")]
+impl BatchVerifiable for SchnorrDLOG<crate::secp256k1::Secp256k1Element> {
+    /// The check of the arkworks curves, with 128-bit weights unless set with
+    /// [`SchnorrDLOG::with_batch_randomizers`]
+    fn batch_verifier<R: Rng>(
+        &self,
+        transcripts: &[(&Self::Commitment, Self::Challenge, &Self::Response)],
+        rng: &mut R,
+    ) -> Result<(), SigmaError> {
+        self.weighted_batch_verifier(transcripts, rng)
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use ark_ec::ProjectiveCurve;
-    use ark_ff::{PrimeField, UniformRand};
-    use rand::{thread_rng, Rng};
+    use std::sync::Arc;
+
+    use ark_ec::{AffineCurve, ProjectiveCurve};
+    use ark_ff::{BigInteger, PrimeField, UniformRand, Zero};
+    use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+    use rand::{rngs::StdRng, thread_rng, Rng, SeedableRng};
 
     use crate::{
-        nizk_proofs::tests::{run_nizk_batched, run_nizk_short},
-        SigmaError,
+        nizk_proofs::tests::{
+            check_prover_states_wiped, run_nizk_batched_at, run_nizk_short_at, Tracked,
+        },
+        secret::assert_redacted,
+        testing::interactive::run_interactive,
+        BatchRandomizers, BatchableProof, Challenge, ChallengeExpansion, CommitmentRecoverable,
+        FixedBaseTable, NizkProver, NizkVerifier, ProverStateBytes, ShortProof, SigmaError,
+        SigmaGroup, SigmaProtocol, CHALLENGE_LENGTH, NIZK,
     };
 
     use super::{SchnorrDLOG, SchnorrInstance};
@@ -137,12 +489,23 @@ mod tests {
     type G = ark_bls12_377::G1Projective;
     type F = ark_bls12_377::Fr;
 
+    /// The challenge lengths the NIZK tests run at, the 128-bit and the default security profiles
+    const PROFILES: [usize; 2] = [16, CHALLENGE_LENGTH];
+
+    /// Runs the generic test `$check` once for every length of [`PROFILES`]
+    macro_rules! for_each_profile {
+        ($check:ident) => {{
+            $check::<{ PROFILES[0] }>();
+            $check::<{ PROFILES[1] }>();
+        }};
+    }
+
+    /// Proofs with the messages they bind
+    type Batch = Vec<(BatchableProof<SchnorrDLOG<G>>, Option<&'static [u8]>)>;
+
     fn schnorr_setup<R: Rng>(rng: &mut R) -> (SchnorrInstance<G>, F, F) {
         // Produce witness and instance
-        let generator = G::prime_subgroup_generator();
-        let witness = F::rand(rng);
-        let claim = generator.mul(witness.into_repr());
-        let instance = SchnorrInstance::new(generator, claim);
+        let (instance, witness) = SchnorrInstance::generate(rng);
         let wrong_witness = F::rand(rng);
 
         (instance, witness, wrong_witness)
@@ -150,78 +513,598 @@ mod tests {
 
     #[test]
     fn test_schnorr_accept_valid_batchable() {
-        let rng = &mut thread_rng();
-        let mut challenge_failures = 0;
+        fn check<const L: usize>() {
+            let rng = &mut thread_rng();
+            let (instance, witness, _) = schnorr_setup(rng);
+
+            let test_result =
+                run_nizk_batched_at::<Hash, SchnorrDLOG<_>, _, L>(&instance, &witness, rng);
 
+            assert!(test_result.is_ok())
+        }
+        for_each_profile!(check);
+    }
+
+    #[test]
+    fn test_schnorr_prover_state_debug_is_redacted() {
+        let rng = &mut thread_rng();
         let (instance, witness, _) = schnorr_setup(rng);
+        let (_, state) = SchnorrDLOG::new(&instance)
+            .prover_commit(&witness, ChallengeExpansion::default(), rng)
+            .unwrap();
+
+        let expected = "ProverState { witness: <redacted>, random_value: <redacted> }";
+        assert_redacted(&state, &witness, expected);
+        assert_redacted(&state, &state.random_value, expected);
+    }
+
+    #[test]
+    fn test_schnorr_interactive_completeness() {
+        let rng = &mut thread_rng();
+        let (instance, witness, wrong_witness) = schnorr_setup(rng);
+
+        assert!(run_interactive::<SchnorrDLOG<_>, _>(&instance, &witness, rng).is_ok());
+        assert_eq!(
+            run_interactive::<SchnorrDLOG<_>, _>(&instance, &wrong_witness, rng),
+            Err(SigmaError::VerificationFailed)
+        );
+    }
 
-        let mut test_result = run_nizk_batched::<Hash, SchnorrDLOG<_>, _>(&instance, &witness, rng);
+    #[test]
+    fn test_schnorr_answers_scalar_challenges() {
+        let rng = &mut thread_rng();
+        let (instance, witness, _) = schnorr_setup(rng);
+        let protocol = SchnorrDLOG::new(&instance);
 
-        while test_result == Err(SigmaError::ChallengeConversionFailure) {
-            challenge_failures += 1;
-            test_result = run_nizk_batched::<Hash, SchnorrDLOG<_>, _>(&instance, &witness, rng)
+        // The protocol answers scalars directly, with no byte challenge to convert
+        for challenge in [F::zero(), -F::from(1u64), F::rand(rng)] {
+            let (commitment, state) = protocol
+                .prover_commit(&witness, ChallengeExpansion::default(), rng)
+                .unwrap();
+            let response = protocol.prover_response(&state, &challenge).unwrap();
+            assert!(protocol
+                .verifier(&commitment, &challenge, &response)
+                .is_ok());
+            assert_eq!(
+                protocol.verifier(&commitment, &(challenge + F::from(1u64)), &response),
+                Err(SigmaError::VerificationFailed)
+            );
         }
 
-        println!("Parsing the challenge failed {} times", challenge_failures);
-        assert!(test_result.is_ok())
+        let bytes: Challenge = rng.gen();
+        assert_eq!(
+            SchnorrDLOG::<G>::challenge_from_bytes(&bytes, ChallengeExpansion::default()),
+            G::challenge_scalar(&bytes, ChallengeExpansion::default())
+        );
     }
 
     #[test]
     fn test_schnorr_reject_wrong_batchable() {
+        fn check<const L: usize>() {
+            let rng = &mut thread_rng();
+            let (instance, _, wrong_witness) = schnorr_setup(rng);
+
+            let test_result =
+                run_nizk_batched_at::<Hash, SchnorrDLOG<_>, _, L>(&instance, &wrong_witness, rng);
+
+            assert_eq!(test_result, Err(SigmaError::VerificationFailed))
+        }
+        for_each_profile!(check);
+    }
+
+    #[test]
+    fn test_schnorr_accept_valid_short() {
+        fn check<const L: usize>() {
+            let rng = &mut thread_rng();
+            let (instance, witness, _) = schnorr_setup(rng);
+
+            let test_result =
+                run_nizk_short_at::<Hash, SchnorrDLOG<_>, _, L>(&instance, &witness, rng);
+
+            assert!(test_result.is_ok())
+        }
+        for_each_profile!(check);
+    }
+
+    #[test]
+    fn test_schnorr_reject_wrong_short() {
+        fn check<const L: usize>() {
+            let rng = &mut thread_rng();
+            let (instance, _, wrong_witness) = schnorr_setup(rng);
+
+            let test_result =
+                run_nizk_short_at::<Hash, SchnorrDLOG<_>, _, L>(&instance, &wrong_witness, rng);
+
+            assert_eq!(test_result, Err(SigmaError::VerificationFailed))
+        }
+        for_each_profile!(check);
+    }
+
+    #[test]
+    fn test_short_challenges_shorten_short_proofs() {
         let rng = &mut thread_rng();
-        let mut challenge_failures = 0;
+        let (instance, witness, _) = schnorr_setup(rng);
+        let nizk = NIZK::<SchnorrDLOG<G>, Hash>::new(&instance, b"ctx");
+        let short_nizk = NIZK::<SchnorrDLOG<G>, Hash, 16>::new(&instance, b"ctx");
 
-        let (instance, _, wrong_witness) = schnorr_setup(rng);
+        let proof = nizk.short_proof(&witness, None, rng).unwrap();
+        let short = short_nizk.short_proof(&witness, None, rng).unwrap();
+        assert_eq!(
+            proof.serialized_size() - short.serialized_size(),
+            CHALLENGE_LENGTH - 16
+        );
+
+        // The profiles derive different challenges, so neither verifies the proofs of the other
+        let batchable = short_nizk.batchable_proof(&witness, None, rng).unwrap();
+        assert_eq!(
+            nizk.batchable_verify(&batchable, None),
+            Err(SigmaError::VerificationFailed)
+        );
+        let batchable = nizk.batchable_proof(&witness, None, rng).unwrap();
+        assert_eq!(
+            short_nizk.batchable_verify(&batchable, None),
+            Err(SigmaError::VerificationFailed)
+        );
+        let truncated = ShortProof::<SchnorrDLOG<G>, 16>::deserialize(
+            &[
+                &proof.to_bytes_compressed()[..16],
+                &proof.to_bytes_compressed()[CHALLENGE_LENGTH..],
+            ]
+            .concat()[..],
+        )
+        .unwrap();
+        assert_eq!(
+            short_nizk.short_verify(&truncated, None),
+            Err(SigmaError::VerificationFailed)
+        );
+    }
+
+    #[test]
+    fn test_challenges_shorter_than_the_protocol_needs_are_rejected() {
+        let rng = &mut thread_rng();
+        let (instance, _, _) = schnorr_setup(rng);
+        assert!(matches!(
+            NizkVerifier::<SchnorrDLOG<G>, Hash, 15>::try_new(&instance, b"ctx"),
+            Err(SigmaError::ChallengeTooShort { got: 15, need: 16 })
+        ));
+    }
 
-        let mut test_result =
-            run_nizk_batched::<Hash, SchnorrDLOG<_>, _>(&instance, &wrong_witness, rng);
+    fn batch_setup<const L: usize, R: Rng>(
+        instance: &SchnorrInstance<G>,
+        witness: &F,
+        count: usize,
+        rng: &mut R,
+    ) -> Batch {
+        let prover = NizkProver::<SchnorrDLOG<G>, Hash, L>::new(instance, b"ctx");
+        let messages: [Option<&'static [u8]>; 3] = [None, Some(b"first"), Some(b"second")];
+
+        (0..count)
+            .map(|i| {
+                let message = messages[i % messages.len()];
+                (
+                    prover.batchable_proof(witness, message, rng).unwrap(),
+                    message,
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_schnorr_batch_verify_accept_valid() {
+        fn check<const L: usize>() {
+            let rng = &mut thread_rng();
+            let (instance, witness, _) = schnorr_setup(rng);
+            let proofs = batch_setup::<L, _>(&instance, &witness, 100, rng);
+            let verifier = NizkVerifier::<SchnorrDLOG<G>, Hash, L>::new(&instance, b"ctx");
 
-        while test_result == Err(SigmaError::ChallengeConversionFailure) {
-            challenge_failures += 1;
-            test_result =
-                run_nizk_batched::<Hash, SchnorrDLOG<_>, _>(&instance, &wrong_witness, rng)
+            assert!(verifier.batch_verify(&proofs, rng).is_ok());
+            assert!(verifier.batch_verify(&proofs[..1], rng).is_ok());
+            assert!(verifier.batch_verify(&[], rng).is_ok());
         }
+        for_each_profile!(check);
+    }
+
+    #[test]
+    fn test_schnorr_batch_verify_reject_corrupted() {
+        fn check<const L: usize>() {
+            let rng = &mut thread_rng();
+            let (instance, witness, wrong_witness) = schnorr_setup(rng);
+            let verifier = NizkVerifier::<SchnorrDLOG<G>, Hash, L>::new(&instance, b"ctx");
 
-        println!("Parsing the challenge failed {} times", challenge_failures);
+            // One proof with the wrong witness
+            let mut proofs = batch_setup::<L, _>(&instance, &witness, 100, rng);
+            proofs[42] = batch_setup::<L, _>(&instance, &wrong_witness, 1, rng).remove(0);
+            assert_eq!(
+                verifier.batch_verify(&proofs, rng),
+                Err(SigmaError::VerificationFailed)
+            );
 
-        assert_eq!(test_result, Err(SigmaError::VerificationFailed))
+            // One valid proof verified against another message
+            let mut proofs = batch_setup::<L, _>(&instance, &witness, 100, rng);
+            proofs[7].1 = Some(b"another message");
+            assert_eq!(
+                verifier.batch_verify(&proofs, rng),
+                Err(SigmaError::VerificationFailed)
+            );
+        }
+        for_each_profile!(check);
     }
 
     #[test]
-    fn test_schnorr_accept_valid_short() {
+    fn test_batch_randomizers_have_their_width() {
         let rng = &mut thread_rng();
-        let mut challenge_failures = 0;
+        assert_eq!(
+            BatchRandomizers::default().bits(),
+            BatchRandomizers::DEFAULT_BITS
+        );
 
-        let (instance, witness, _) = schnorr_setup(rng);
+        for bits in [1, 8, 63, 64, 65, 128, 200] {
+            let randomizers = BatchRandomizers::with_bits(bits);
+            let widths: Vec<u32> = (0..200)
+                .map(|_| randomizers.sample::<F, _>(rng).into_repr().num_bits())
+                .collect();
+            assert!(widths.iter().all(|width| *width <= bits));
+            assert!(widths.contains(&bits));
+        }
+    }
 
-        let mut test_result = run_nizk_short::<Hash, SchnorrDLOG<_>, _>(&instance, &witness, rng);
+    #[test]
+    fn test_batch_verify_rejects_one_bad_proof_in_every_trial() {
+        let rng = &mut thread_rng();
+        let (instance, witness, wrong_witness) = schnorr_setup(rng);
+        let verifier = NizkVerifier::<SchnorrDLOG<G>, Hash>::new(&instance, b"ctx");
+        let mut proofs = batch_setup::<CHALLENGE_LENGTH, _>(&instance, &witness, 4, rng);
+        proofs[0] = batch_setup::<CHALLENGE_LENGTH, _>(&instance, &wrong_witness, 1, rng).remove(0);
 
-        while test_result == Err(SigmaError::ChallengeConversionFailure) {
-            challenge_failures += 1;
-            test_result = run_nizk_short::<Hash, SchnorrDLOG<_>, _>(&instance, &witness, rng)
+        // Fresh 128-bit weights in every trial, with the bad proof at every position in turn
+        for _ in 0..1000 {
+            assert_eq!(
+                verifier.batch_verify(&proofs, rng),
+                Err(SigmaError::VerificationFailed)
+            );
+            proofs.rotate_left(1);
         }
+    }
+
+    #[test]
+    fn test_one_bit_randomizers_accept_bad_batches() {
+        let rng = &mut thread_rng();
+        let (instance, witness, wrong_witness) = schnorr_setup(rng);
+        let protocol =
+            SchnorrDLOG::new(&instance).with_batch_randomizers(BatchRandomizers::with_bits(1));
+        let verifier =
+            NizkVerifier::<SchnorrDLOG<G>, Hash>::from_protocol(protocol, b"ctx").unwrap();
+        let mut proofs = batch_setup::<CHALLENGE_LENGTH, _>(&instance, &witness, 4, rng);
+        proofs[2] = batch_setup::<CHALLENGE_LENGTH, _>(&instance, &wrong_witness, 1, rng).remove(0);
 
-        println!("Parsing the challenge failed {} times", challenge_failures);
-        assert!(test_result.is_ok())
+        // The bad proof escapes whenever its weight is zero, half of the time
+        let accepted = (0..64)
+            .filter(|_| verifier.batch_verify(&proofs, rng).is_ok())
+            .count();
+        assert!(0 < accepted && accepted < 64);
     }
 
     #[test]
-    fn test_schnorr_reject_wrong_short() {
+    fn test_schnorr_batch_verify_short_detects_invalid() {
+        fn check<const L: usize>() {
+            let rng = &mut thread_rng();
+            let (instance, witness, wrong_witness) = schnorr_setup(rng);
+            let prover = NizkProver::<SchnorrDLOG<G>, Hash, L>::new(&instance, b"ctx");
+            let verifier = NizkVerifier::<SchnorrDLOG<G>, Hash, L>::new(&instance, b"ctx");
+
+            let mut proofs: Vec<_> = (0..20)
+                .map(|i| {
+                    let message: Option<&[u8]> = if i % 2 == 0 { None } else { Some(b"message") };
+                    (prover.short_proof(&witness, message, rng).unwrap(), message)
+                })
+                .collect();
+            assert!(verifier.batch_verify_short(&proofs).is_ok());
+            assert!(verifier.batch_verify_short(&[]).is_ok());
+
+            proofs[13].0 = prover
+                .short_proof(&wrong_witness, Some(b"message"), rng)
+                .unwrap();
+            assert_eq!(
+                verifier.batch_verify_short(&proofs),
+                Err(SigmaError::VerificationFailed)
+            );
+        }
+        for_each_profile!(check);
+    }
+
+    #[test]
+    fn test_schnorr_verify_many_reports_invalid_proofs() {
+        fn check<const L: usize>() {
+            let rng = &mut thread_rng();
+            let (instance, witness, wrong_witness) = schnorr_setup(rng);
+            let verifier = NizkVerifier::<SchnorrDLOG<G>, Hash, L>::new(&instance, b"ctx");
+            let mut proofs = batch_setup::<L, _>(&instance, &witness, 64, rng);
+            assert!(verifier.verify_many(&proofs, rng).iter().all(Result::is_ok));
+
+            let invalid = [3, 4, 50];
+            for i in invalid {
+                proofs[i] = batch_setup::<L, _>(&instance, &wrong_witness, 1, rng).remove(0);
+            }
+            let results = verifier.verify_many(&proofs, rng);
+
+            assert_eq!(results.len(), 64);
+            for (i, result) in results.into_iter().enumerate() {
+                if invalid.contains(&i) {
+                    assert_eq!(result, Err(SigmaError::VerificationFailed));
+                } else {
+                    assert!(result.is_ok());
+                }
+            }
+        }
+        for_each_profile!(check);
+    }
+
+    #[test]
+    fn test_nizk_wipes_prover_states() {
+        let rng = &mut thread_rng();
+        let (instance, witness, _) = schnorr_setup(rng);
+        check_prover_states_wiped::<Hash, SchnorrDLOG<G>, _>(&instance, &witness, rng);
+    }
+
+    #[test]
+    fn test_schnorr_is_valid_witness() {
         let rng = &mut thread_rng();
-        let mut challenge_failures = 0;
+        let (instance, witness, wrong_witness) = schnorr_setup(rng);
+        let protocol = SchnorrDLOG::new(&instance);
+        // Without an override, the witness is checked by running the protocol
+        let tracked = Tracked::<SchnorrDLOG<G>>::new(&instance);
 
+        assert!(protocol.is_valid_witness(&witness));
+        assert!(!protocol.is_valid_witness(&wrong_witness));
+        assert!(tracked.is_valid_witness(&witness));
+        assert!(!tracked.is_valid_witness(&wrong_witness));
+    }
+
+    #[test]
+    fn test_schnorr_instance_helpers() {
+        let rng = &mut thread_rng();
+        let (instance, witness) = SchnorrInstance::<G>::generate(rng);
+        let generator = G::prime_subgroup_generator();
+
+        assert_eq!(instance.base(), generator);
+        assert_eq!(instance.claim(), generator.mul(witness.into_repr()));
+        assert!(SchnorrDLOG::new(&instance).is_valid_witness(&witness));
+
+        let base = G::rand(rng);
+        let other = SchnorrInstance::from_witness(base, &witness);
+        assert_eq!(
+            other,
+            SchnorrInstance::new(base, base.mul(witness.into_repr()))
+        );
+        assert!(SchnorrDLOG::new(&other).is_valid_witness(&witness));
+    }
+
+    #[cfg(all(sigma_check_witnesses, debug_assertions))]
+    #[test]
+    #[should_panic(expected = "the witness does not match the instance")]
+    fn test_prover_commit_asserts_valid_witness() {
+        let rng = &mut thread_rng();
         let (instance, _, wrong_witness) = schnorr_setup(rng);
 
-        let mut test_result =
-            run_nizk_short::<Hash, SchnorrDLOG<_>, _>(&instance, &wrong_witness, rng);
+        let _ = SchnorrDLOG::new(&instance).prover_commit(&wrong_witness, rng);
+    }
+
+    /// A point of the curve outside the prime-order subgroup, lifted from a small x-coordinate
+    fn point_outside_subgroup() -> G {
+        (1u64..)
+            .filter_map(|x| ark_bls12_377::G1Affine::get_point_from_x(x.into(), false))
+            .find(|point| !point.is_in_correct_subgroup_assuming_on_curve())
+            .unwrap()
+            .into_projective()
+    }
+
+    #[test]
+    fn test_reject_identity_base() {
+        let rng = &mut thread_rng();
+        let identity = G::zero();
+        let claim = G::rand(rng);
+
+        assert_eq!(
+            SchnorrInstance::try_new(identity, claim),
+            Err(SigmaError::InvalidInstance)
+        );
+        assert_eq!(
+            SchnorrInstance::try_new(identity, identity),
+            Err(SigmaError::InvalidInstance)
+        );
+
+        // Any witness would prove the identity claim, so the NIZK refuses the instance
+        let instance = SchnorrInstance::new(identity, identity);
+        assert!(matches!(
+            NIZK::<SchnorrDLOG<G>, Hash>::try_new(&instance, b"ctx"),
+            Err(SigmaError::InvalidInstance)
+        ));
+    }
+
+    #[test]
+    #[should_panic(expected = "the instance is not valid")]
+    fn test_nizk_new_panics_on_identity_base() {
+        let instance = SchnorrInstance::new(G::zero(), G::zero());
+        NIZK::<SchnorrDLOG<G>, Hash>::new(&instance, b"ctx");
+    }
+
+    #[test]
+    fn test_reject_point_outside_subgroup() {
+        let rng = &mut thread_rng();
+        let (instance, _, _) = schnorr_setup(rng);
+        let outside = point_outside_subgroup();
+
+        assert!(SchnorrInstance::try_new(instance.base, instance.claim).is_ok());
+        assert_eq!(
+            SchnorrInstance::try_new(outside, instance.claim),
+            Err(SigmaError::InvalidInstance)
+        );
+        assert_eq!(
+            SchnorrInstance::try_new(instance.base, outside),
+            Err(SigmaError::InvalidInstance)
+        );
+        assert!(matches!(
+            NizkVerifier::<SchnorrDLOG<G>, Hash>::try_new(
+                &SchnorrInstance::new(instance.base, outside),
+                b"ctx"
+            ),
+            Err(SigmaError::InvalidInstance)
+        ));
+    }
+
+    #[test]
+    fn test_precomputed_proofs_are_identical() {
+        let rng = &mut thread_rng();
+        let (instance, witness, wrong_witness) = schnorr_setup(rng);
+        let nizk = NIZK::<SchnorrDLOG<G>, Hash>::new(&instance, b"ctx");
+        let precomputed = NIZK::<SchnorrDLOG<G>, Hash>::from_protocol(
+            SchnorrDLOG::new(&instance).with_precomputation(),
+            b"ctx",
+        )
+        .unwrap();
+
+        let prove = |nizk: &NIZK<SchnorrDLOG<G>, Hash>| {
+            let rng = &mut StdRng::seed_from_u64(7);
+            (
+                nizk.batchable_proof(&witness, Some(b"message"), rng)
+                    .unwrap(),
+                nizk.short_proof(&witness, Some(b"message"), rng).unwrap(),
+            )
+        };
+        let (batchable, short) = prove(&precomputed);
+
+        assert_eq!((batchable.clone(), short.clone()), prove(&nizk));
+        assert!(nizk.batchable_verify(&batchable, Some(b"message")).is_ok());
+        assert!(precomputed.short_verify(&short, Some(b"message")).is_ok());
+        assert_eq!(
+            precomputed.batchable_verify(&batchable, None),
+            Err(SigmaError::VerificationFailed)
+        );
+
+        let wrong = precomputed
+            .batchable_proof(&wrong_witness, None, rng)
+            .unwrap();
+        assert_eq!(
+            precomputed.batchable_verify(&wrong, None),
+            Err(SigmaError::VerificationFailed)
+        );
+    }
+
+    #[test]
+    fn test_base_table_shared_between_claims() {
+        let rng = &mut thread_rng();
+        let generator = G::prime_subgroup_generator();
+        let table = Arc::new(FixedBaseTable::new(generator));
+
+        for _ in 0..3 {
+            let witness = F::rand(rng);
+            let instance = SchnorrInstance::new(generator, generator.mul(witness.into_repr()));
+            let protocol = SchnorrDLOG::new(&instance).with_base_table(table.clone());
+            let challenge = rng.gen();
+            let response = F::rand(rng);
+
+            assert!(protocol.is_valid_witness(&witness));
+            assert_eq!(
+                protocol.simulate_commitment(&challenge, &response),
+                SchnorrDLOG::new(&instance).simulate_commitment(&challenge, &response)
+            );
 
-        while test_result == Err(SigmaError::ChallengeConversionFailure) {
-            challenge_failures += 1;
-            test_result = run_nizk_short::<Hash, SchnorrDLOG<_>, _>(&instance, &wrong_witness, rng)
+            let nizk = NIZK::<SchnorrDLOG<G>, Hash>::from_protocol(protocol, b"ctx").unwrap();
+            let proof = nizk.batchable_proof(&witness, None, rng).unwrap();
+            assert!(NIZK::<SchnorrDLOG<G>, Hash>::new(&instance, b"ctx")
+                .batchable_verify(&proof, None)
+                .is_ok());
         }
+    }
 
-        println!("Parsing the challenge failed {} times", challenge_failures);
+    #[test]
+    #[should_panic(expected = "the table is for another point than the base")]
+    fn test_base_table_for_another_point_panics() {
+        let rng = &mut thread_rng();
+        let (instance, _, _) = schnorr_setup(rng);
+
+        let _ = SchnorrDLOG::new(&instance)
+            .with_base_table(Arc::new(FixedBaseTable::new(G::rand(rng))));
+    }
+
+    #[test]
+    fn test_split_phase_proof_verifies() {
+        let rng = &mut thread_rng();
+        let (instance, witness, _) = schnorr_setup(rng);
+        let nizk = NIZK::<SchnorrDLOG<G>, Hash>::new(&instance, b"ctx");
+
+        let (commitment, state) = nizk.commit_phase(&witness, Some(b"message"), rng).unwrap();
+        // The state crosses the process boundary as bytes
+        let state = ProverStateBytes::from_bytes(state.as_bytes().to_vec());
+        let proof = nizk
+            .response_phase(commitment, state, Some(b"message"))
+            .unwrap();
+
+        assert!(nizk.batchable_verify(&proof, Some(b"message")).is_ok());
+        assert_eq!(
+            nizk.batchable_verify(&proof, None),
+            Err(SigmaError::VerificationFailed)
+        );
+    }
+
+    #[test]
+    fn test_split_phase_rejects_malformed_state() {
+        let rng = &mut thread_rng();
+        let (instance, witness, _) = schnorr_setup(rng);
+        let nizk = NIZK::<SchnorrDLOG<G>, Hash>::new(&instance, b"ctx");
+        let (commitment, state) = nizk.commit_phase(&witness, None, rng).unwrap();
+        let bytes = state.as_bytes().to_vec();
+
+        for malformed in [
+            &bytes[..bytes.len() - 1],
+            &[bytes.clone(), vec![0]].concat(),
+        ] {
+            assert!(matches!(
+                nizk.response_phase(
+                    commitment,
+                    ProverStateBytes::from_bytes(malformed.to_vec()),
+                    None
+                ),
+                Err(SigmaError::SerializationError(_))
+            ));
+        }
+
+        // A state for another commitment gives a proof that does not verify
+        let (_, other_state) = nizk.commit_phase(&witness, None, rng).unwrap();
+        let proof = nizk.response_phase(commitment, other_state, None).unwrap();
+        assert_eq!(
+            nizk.batchable_verify(&proof, None),
+            Err(SigmaError::VerificationFailed)
+        );
+    }
+
+    #[cfg(feature = "zeroize")]
+    #[test]
+    fn test_prover_state_bytes_are_zeroized() {
+        use zeroize::Zeroize;
+
+        let rng = &mut thread_rng();
+        let (instance, witness, _) = schnorr_setup(rng);
+        let nizk = NIZK::<SchnorrDLOG<G>, Hash>::new(&instance, b"ctx");
+        let (_, mut state) = nizk.commit_phase(&witness, None, rng).unwrap();
+
+        assert!(!state.as_bytes().is_empty());
+        state.zeroize();
+        assert!(state.as_bytes().is_empty());
+    }
+
+    #[cfg(feature = "zeroize")]
+    #[test]
+    fn test_prover_state_is_zeroized() {
+        use zeroize::Zeroize;
+
+        let rng = &mut thread_rng();
+        let (instance, witness, _) = schnorr_setup(rng);
+        let (_, mut state) =
+            SchnorrDLOG::new(&instance).prover_commit_with_nonce(&witness, F::rand(rng));
 
-        assert_eq!(test_result, Err(SigmaError::VerificationFailed))
+        state.zeroize();
+        assert!(state.witness.is_zero());
+        assert!(state.random_value.is_zero());
     }
 }
@@ -0,0 +1,306 @@
+use std::{fmt, sync::Arc};
+
+use ark_ec::ProjectiveCurve;
+use ark_ff::{Field, PrimeField, Zero};
+use ark_serialize::{CanonicalSerialize, SerializationError, Write};
+use ark_std::UniformRand;
+use rand::Rng;
+
+use super::challenge_scalar;
+use crate::{
+    group::wipe, interactive_proofs::canonical_bytes, secret::Redacted, Challenge,
+    ChallengeExpansion, CommitmentRecoverable, SigmaError, SigmaProtocol,
+};
+
+/// Proof of knowledge of the discrete logarithm of one element of a public list, without revealing which one.
+///
+/// This is an n-ary OR of Schnorr statements: every branch but the real one is simulated, and the branch challenges
+/// sum to the Fiat-Shamir challenge.
+#[derive(Clone)]
+pub struct SetMembership<G: ProjectiveCurve> {
+    instance: Arc<SetMembershipInstance<G>>,
+}
+
+/// The instance for the set membership proof: the prover claims to know the discrete log between the `base` point
+/// and one of the `claims`
+#[derive(Debug, Clone, CanonicalSerialize)]
+pub struct SetMembershipInstance<G: ProjectiveCurve> {
+    base: G,
+    claims: Vec<G>,
+}
+
+impl<G: ProjectiveCurve> SetMembershipInstance<G> {
+    /// Create a new set membership instance from the provided group elements
+    pub fn new(base: G, claims: Vec<G>) -> Self {
+        Self { base, claims }
+    }
+}
+
+/// A wrapper type for a set membership witness: the index of the known claim and its discrete log
+pub type SetMembershipWitness<G> = (usize, <G as ProjectiveCurve>::ScalarField);
+
+/// A wrapper type for a set membership commitment, one group element per claim
+pub type SetMembershipCommitment<G> = Vec<G>;
+
+/// A wrapper type for a set membership response, one `(challenge, response)` pair per claim
+pub type SetMembershipResponse<G> = Vec<(
+    <G as ProjectiveCurve>::ScalarField,
+    <G as ProjectiveCurve>::ScalarField,
+)>;
+
+pub struct ProverState<F: Field> {
+    index: usize,
+    witness: F,
+    random_value: F,
+    simulated: Vec<(F, F)>,
+}
+
+impl<F: Field> fmt::Debug for ProverState<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ProverState")
+            .field("index", &Redacted)
+            .field("witness", &Redacted)
+            .field("random_value", &Redacted)
+            .field("simulated", &Redacted)
+            .finish()
+    }
+}
+
+impl<G: ProjectiveCurve> SetMembership<G> {
+    /// The commitment `s * base + c * claim` for a single branch
+    fn branch_commitment(
+        &self,
+        claim: &G,
+        challenge: &G::ScalarField,
+        response: &G::ScalarField,
+    ) -> G {
+        self.instance.base.mul(response.into_repr()) + claim.mul(challenge.into_repr())
+    }
+}
+
+impl<G: ProjectiveCurve> SigmaProtocol for SetMembership<G> {
+    type Instance = SetMembershipInstance<G>;
+    type Commitment = SetMembershipCommitment<G>;
+    type ProverState = ProverState<G::ScalarField>;
+    type Witness = SetMembershipWitness<G>;
+    type Response = SetMembershipResponse<G>;
+    type Challenge = G::ScalarField;
+
+    const PROTOCOL_ID: &'static [u8] = b"zkpstd/sigma/set-membership";
+
+    fn new(instance: &SetMembershipInstance<G>) -> Self {
+        Self::from_shared(Arc::new(instance.clone()))
+    }
+
+    fn from_shared(instance: Arc<SetMembershipInstance<G>>) -> Self {
+        Self { instance }
+    }
+
+    fn challenge_from_bytes(
+        challenge: &Challenge,
+        expansion: ChallengeExpansion,
+    ) -> Result<G::ScalarField, SigmaError> {
+        challenge_scalar(challenge, expansion)
+    }
+
+    fn instance_bytes(&self) -> Vec<u8> {
+        canonical_bytes(&*self.instance)
+    }
+
+    fn witness_bytes(&self, witness: &Self::Witness) -> Vec<u8> {
+        canonical_bytes(witness)
+    }
+
+    fn prover_commit<R: Rng>(
+        &self,
+        witness: &Self::Witness,
+        _expansion: ChallengeExpansion,
+        rng: &mut R,
+    ) -> Result<(Self::Commitment, Self::ProverState), SigmaError> {
+        let (index, scalar) = *witness;
+        if index >= self.instance.claims.len() {
+            return Err(SigmaError::LengthMismatch);
+        }
+
+        let random_value = G::ScalarField::rand(rng);
+        let real = self.instance.base.mul(random_value.into_repr());
+
+        // Every branch, including the real one, is simulated so that the work does not depend on the index
+        let simulated: Vec<_> = self
+            .instance
+            .claims
+            .iter()
+            .map(|_| (G::ScalarField::rand(rng), G::ScalarField::rand(rng)))
+            .collect();
+        let commitment = self
+            .instance
+            .claims
+            .iter()
+            .zip(&simulated)
+            .enumerate()
+            .map(|(i, (claim, (challenge, response)))| {
+                let simulated = self.branch_commitment(claim, challenge, response);
+                if i == index {
+                    real
+                } else {
+                    simulated
+                }
+            })
+            .collect();
+
+        let state = ProverState {
+            index,
+            witness: scalar,
+            random_value,
+            simulated,
+        };
+
+        Ok((commitment, state))
+    }
+
+    fn prover_response(
+        &self,
+        prover_state: &Self::ProverState,
+        challenge: &Self::Challenge,
+    ) -> Result<Self::Response, SigmaError> {
+        let index = prover_state.index;
+
+        let simulated_sum = prover_state
+            .simulated
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| *i != index)
+            .fold(G::ScalarField::zero(), |acc, (_, (c, _))| acc + c);
+        let real_challenge = *challenge - simulated_sum;
+        let real_response = prover_state.random_value - real_challenge * prover_state.witness;
+
+        let mut response = prover_state.simulated.clone();
+        response[index] = (real_challenge, real_response);
+
+        Ok(response)
+    }
+
+    fn verifier(
+        &self,
+        commitment: &Self::Commitment,
+        challenge: &Self::Challenge,
+        response: &Self::Response,
+    ) -> Result<(), SigmaError> {
+        if &self.simulate_commitment(challenge, response)? == commitment {
+            Ok(())
+        } else {
+            Err(SigmaError::VerificationFailed)
+        }
+    }
+
+    fn simulate_response<R: Rng>(&self, rng: &mut R) -> Self::Response {
+        (0..self.instance.claims.len())
+            .map(|_| (G::ScalarField::rand(rng), G::ScalarField::rand(rng)))
+            .collect()
+    }
+
+    fn zeroize_state(&self, prover_state: &mut Self::ProverState) {
+        wipe(&mut prover_state.index);
+        wipe(&mut prover_state.witness);
+        wipe(&mut prover_state.random_value);
+        wipe(&mut prover_state.simulated);
+    }
+}
+
+impl<G: ProjectiveCurve> CommitmentRecoverable for SetMembership<G> {
+    fn simulate_commitment(
+        &self,
+        challenge: &Self::Challenge,
+        response: &Self::Response,
+    ) -> Result<Self::Commitment, SigmaError> {
+        if response.len() != self.instance.claims.len() {
+            return Err(SigmaError::LengthMismatch);
+        }
+
+        let challenge_sum = response
+            .iter()
+            .fold(G::ScalarField::zero(), |acc, (c, _)| acc + c);
+        if challenge_sum != *challenge {
+            return Err(SigmaError::VerificationFailed);
+        }
+
+        Ok(self
+            .instance
+            .claims
+            .iter()
+            .zip(response)
+            .map(|(claim, (c, s))| self.branch_commitment(claim, c, s))
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_ec::ProjectiveCurve;
+    use ark_ff::{PrimeField, UniformRand};
+    use rand::{thread_rng, Rng};
+
+    use crate::{
+        nizk_proofs::tests::{run_nizk_batched, run_nizk_short},
+        SigmaError,
+    };
+
+    use super::{SetMembership, SetMembershipInstance};
+
+    type Hash = blake2::Blake2s;
+    type G = ark_bls12_377::G1Projective;
+    type F = ark_bls12_377::Fr;
+
+    const SET_SIZE: usize = 8;
+
+    fn set_membership_setup<R: Rng>(rng: &mut R) -> (SetMembershipInstance<G>, Vec<F>) {
+        let base = G::rand(rng);
+        let scalars: Vec<_> = (0..SET_SIZE).map(|_| F::rand(rng)).collect();
+        let claims = scalars.iter().map(|x| base.mul(x.into_repr())).collect();
+
+        (SetMembershipInstance::new(base, claims), scalars)
+    }
+
+    #[test]
+    fn test_set_membership_accept_every_index() {
+        let rng = &mut thread_rng();
+        let (instance, scalars) = set_membership_setup(rng);
+
+        for (index, scalar) in scalars.into_iter().enumerate() {
+            let witness = (index, scalar);
+
+            let batched_result =
+                run_nizk_batched::<Hash, SetMembership<_>, _>(&instance, &witness, rng);
+            let short_result =
+                run_nizk_short::<Hash, SetMembership<_>, _>(&instance, &witness, rng);
+
+            assert!(batched_result.is_ok());
+            assert!(short_result.is_ok());
+        }
+    }
+
+    #[test]
+    fn test_set_membership_reject_non_member() {
+        let rng = &mut thread_rng();
+        let (instance, _) = set_membership_setup(rng);
+        let witness = (3, F::rand(rng));
+
+        let batched_result =
+            run_nizk_batched::<Hash, SetMembership<_>, _>(&instance, &witness, rng);
+        let short_result = run_nizk_short::<Hash, SetMembership<_>, _>(&instance, &witness, rng);
+
+        assert_eq!(batched_result, Err(SigmaError::VerificationFailed));
+        assert_eq!(short_result, Err(SigmaError::VerificationFailed));
+    }
+
+    #[test]
+    fn test_set_membership_index_out_of_bounds() {
+        let rng = &mut thread_rng();
+        let (instance, scalars) = set_membership_setup(rng);
+        let witness = (SET_SIZE, scalars[0]);
+
+        let test_result = run_nizk_batched::<Hash, SetMembership<_>, _>(&instance, &witness, rng);
+
+        assert_eq!(test_result, Err(SigmaError::LengthMismatch));
+    }
+}
@@ -0,0 +1,318 @@
+use std::fmt;
+
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Read, SerializationError, Write};
+use num_bigint::{BigUint, RandBigInt};
+use rand::Rng;
+
+use super::challenge_bits;
+use crate::{
+    interactive_proofs::canonical_bytes, secret::Redacted, Challenge, ChallengeExpansion,
+    CommitmentRecoverable, SigmaError, SigmaProtocol, CHALLENGE_LENGTH,
+};
+
+/// The Fiat-Shamir identification protocol: proof of knowledge of a square root modulo a Blum integer.
+///
+/// A single round has a binary challenge, so the protocol is repeated `t` times in parallel and round `i` is driven by
+/// bit `i` of the challenge. In each round the prover commits to `x = r^2` and answers `y = r * s^e`, which the
+/// verifier checks as `y^2 = x * v^e mod N`. Soundness error is `2^-t`.
+pub struct SquareRootIdentification {
+    instance: SquareRootInstance,
+}
+
+/// The instance for the square root proof: the prover claims to know a square root of `square` modulo `modulus`
+#[derive(Debug, Clone)]
+pub struct SquareRootInstance {
+    modulus: BigUint,
+    square: BigUint,
+    repetitions: usize,
+}
+
+impl SquareRootInstance {
+    /// Create a new square root instance, run with `repetitions` parallel rounds. At most `8 * CHALLENGE_LENGTH`
+    /// rounds are supported
+    pub fn new(modulus: BigUint, square: BigUint, repetitions: usize) -> Self {
+        Self {
+            modulus,
+            square,
+            repetitions,
+        }
+    }
+}
+
+/// An integer modulo the instance's modulus, serialized as its little-endian bytes
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Residue(BigUint);
+
+impl Residue {
+    /// The integer value of the residue
+    pub fn value(&self) -> &BigUint {
+        &self.0
+    }
+}
+
+impl CanonicalSerialize for Residue {
+    fn serialize<W: Write>(&self, writer: W) -> Result<(), SerializationError> {
+        self.0.to_bytes_le().serialize(writer)
+    }
+
+    fn serialized_size(&self) -> usize {
+        self.0.to_bytes_le().serialized_size()
+    }
+}
+
+impl CanonicalDeserialize for Residue {
+    fn deserialize<R: Read>(reader: R) -> Result<Self, SerializationError> {
+        let bytes = Vec::<u8>::deserialize(reader)?;
+        Ok(Self(BigUint::from_bytes_le(&bytes)))
+    }
+}
+
+/// A wrapper type for a square root witness
+pub type SquareRootWitness = BigUint;
+
+/// A wrapper type for a square root commitment, one square per round
+pub type SquareRootCommitment = Vec<Residue>;
+
+/// A wrapper type for a square root response, one residue per round
+pub type SquareRootResponse = Vec<Residue>;
+
+pub struct ProverState {
+    witness: BigUint,
+    random_values: Vec<BigUint>,
+}
+
+impl fmt::Debug for ProverState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ProverState")
+            .field("witness", &Redacted)
+            .field("random_values", &Redacted)
+            .finish()
+    }
+}
+
+/// Overwrites the digits `value` holds with zeros, with the `zeroize` feature. `BigUint` has no `Zeroize`, so the
+/// buffers it freed while the value was computed are not wiped
+fn wipe_biguint(value: &mut BigUint) {
+    #[cfg(feature = "zeroize")]
+    value.assign_from_slice(&vec![0; value.iter_u32_digits().len()]);
+    #[cfg(not(feature = "zeroize"))]
+    let _ = value;
+}
+
+impl SquareRootIdentification {
+    fn check_repetitions(&self) -> Result<(), SigmaError> {
+        if self.instance.repetitions > 8 * CHALLENGE_LENGTH {
+            Err(SigmaError::LengthMismatch)
+        } else {
+            Ok(())
+        }
+    }
+
+    fn random_residue<R: Rng>(&self, rng: &mut R) -> BigUint {
+        rng.gen_biguint_below(&self.instance.modulus)
+    }
+}
+
+impl SigmaProtocol for SquareRootIdentification {
+    type Instance = SquareRootInstance;
+    type Commitment = SquareRootCommitment;
+    type ProverState = ProverState;
+    type Witness = SquareRootWitness;
+    type Response = SquareRootResponse;
+    /// The bytes, of which each repetition reads one bit
+    type Challenge = Challenge;
+
+    const PROTOCOL_ID: &'static [u8] = b"zkpstd/sigma/fiat-shamir-sqrt";
+
+    fn new(instance: &SquareRootInstance) -> Self {
+        Self {
+            instance: instance.clone(),
+        }
+    }
+
+    fn challenge_from_bytes(
+        challenge: &Challenge,
+        _expansion: ChallengeExpansion,
+    ) -> Result<Challenge, SigmaError> {
+        Ok(*challenge)
+    }
+
+    fn instance_bytes(&self) -> Vec<u8> {
+        canonical_bytes(&(
+            self.instance.modulus.to_bytes_le(),
+            self.instance.square.to_bytes_le(),
+            self.instance.repetitions,
+        ))
+    }
+
+    fn witness_bytes(&self, witness: &Self::Witness) -> Vec<u8> {
+        canonical_bytes(&witness.to_bytes_le())
+    }
+
+    fn prover_commit<R: Rng>(
+        &self,
+        witness: &Self::Witness,
+        _expansion: ChallengeExpansion,
+        rng: &mut R,
+    ) -> Result<(Self::Commitment, Self::ProverState), SigmaError> {
+        self.check_repetitions()?;
+        let modulus = &self.instance.modulus;
+
+        let random_values: Vec<_> = (0..self.instance.repetitions)
+            .map(|_| self.random_residue(rng))
+            .collect();
+
+        let commitment = random_values
+            .iter()
+            .map(|r| Residue(r * r % modulus))
+            .collect();
+
+        let state = ProverState {
+            witness: witness.clone(),
+            random_values,
+        };
+
+        Ok((commitment, state))
+    }
+
+    fn prover_response(
+        &self,
+        prover_state: &Self::ProverState,
+        challenge: &Challenge,
+    ) -> Result<Self::Response, SigmaError> {
+        let modulus = &self.instance.modulus;
+
+        Ok(prover_state
+            .random_values
+            .iter()
+            .zip(challenge_bits(challenge, self.instance.repetitions))
+            .map(|(r, bit)| {
+                if bit {
+                    Residue(r * &prover_state.witness % modulus)
+                } else {
+                    Residue(r.clone())
+                }
+            })
+            .collect())
+    }
+
+    fn verifier(
+        &self,
+        commitment: &Self::Commitment,
+        challenge: &Challenge,
+        response: &Self::Response,
+    ) -> Result<(), SigmaError> {
+        if &self.simulate_commitment(challenge, response)? == commitment {
+            Ok(())
+        } else {
+            Err(SigmaError::VerificationFailed)
+        }
+    }
+
+    fn simulate_response<R: Rng>(&self, rng: &mut R) -> Self::Response {
+        (0..self.instance.repetitions)
+            .map(|_| Residue(self.random_residue(rng)))
+            .collect()
+    }
+
+    fn zeroize_state(&self, prover_state: &mut Self::ProverState) {
+        wipe_biguint(&mut prover_state.witness);
+        prover_state.random_values.iter_mut().for_each(wipe_biguint);
+    }
+}
+
+impl CommitmentRecoverable for SquareRootIdentification {
+    fn simulate_commitment(
+        &self,
+        challenge: &Challenge,
+        response: &Self::Response,
+    ) -> Result<Self::Commitment, SigmaError> {
+        self.check_repetitions()?;
+        if response.len() != self.instance.repetitions {
+            return Err(SigmaError::LengthMismatch);
+        }
+
+        let modulus = &self.instance.modulus;
+        let square_inverse = self
+            .instance
+            .square
+            .modinv(modulus)
+            .ok_or(SigmaError::VerificationFailed)?;
+
+        Ok(response
+            .iter()
+            .zip(challenge_bits(challenge, self.instance.repetitions))
+            .map(|(Residue(y), bit)| {
+                let y_squared = y * y % modulus;
+                if bit {
+                    Residue(y_squared * &square_inverse % modulus)
+                } else {
+                    Residue(y_squared)
+                }
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use num_bigint::{BigUint, RandBigInt};
+    use rand::{thread_rng, Rng};
+
+    use crate::{
+        nizk_proofs::tests::{run_nizk_batched, run_nizk_short},
+        SigmaError,
+    };
+
+    use super::{SquareRootIdentification, SquareRootInstance};
+
+    type Hash = blake2::Blake2s;
+
+    const REPETITIONS: usize = 32;
+
+    /// The product of the Mersenne primes `2^127 - 1` and `2^89 - 1`, both congruent to 3 modulo 4
+    fn blum_integer() -> BigUint {
+        let one = BigUint::from(1u8);
+        ((&one << 127) - &one) * ((&one << 89) - &one)
+    }
+
+    fn square_root_setup<R: Rng>(rng: &mut R) -> (SquareRootInstance, BigUint) {
+        let modulus = blum_integer();
+        let root = rng.gen_biguint_below(&modulus);
+        let square = &root * &root % &modulus;
+
+        (SquareRootInstance::new(modulus, square, REPETITIONS), root)
+    }
+
+    #[test]
+    fn test_square_root_accept_valid() {
+        let rng = &mut thread_rng();
+        let (instance, witness) = square_root_setup(rng);
+
+        let batched_result =
+            run_nizk_batched::<Hash, SquareRootIdentification, _>(&instance, &witness, rng);
+        let short_result =
+            run_nizk_short::<Hash, SquareRootIdentification, _>(&instance, &witness, rng);
+
+        assert!(batched_result.is_ok());
+        assert!(short_result.is_ok());
+    }
+
+    #[test]
+    fn test_square_root_reject_non_residue() {
+        let rng = &mut thread_rng();
+        let modulus = blum_integer();
+        // -1 has Jacobi symbol 1 modulo a Blum integer but is not a square
+        let non_residue = &modulus - BigUint::from(1u8);
+        let instance = SquareRootInstance::new(modulus.clone(), non_residue, REPETITIONS);
+        let witness = rng.gen_biguint_below(&modulus);
+
+        let batched_result =
+            run_nizk_batched::<Hash, SquareRootIdentification, _>(&instance, &witness, rng);
+        let short_result =
+            run_nizk_short::<Hash, SquareRootIdentification, _>(&instance, &witness, rng);
+
+        assert_eq!(batched_result, Err(SigmaError::VerificationFailed));
+        assert_eq!(short_result, Err(SigmaError::VerificationFailed));
+    }
+}
@@ -0,0 +1,177 @@
+use pyo3::{
+    exceptions::{PyException, PyValueError},
+    prelude::*,
+    types::PyBytes,
+};
+use rand::{rngs::StdRng, SeedableRng};
+
+use crate::{
+    nizk_proofs::{proof_bytes, proof_from_bytes},
+    protocols::{SchnorrDLOG, SchnorrInstance},
+    ristretto::RistrettoElement,
+    BatchableProof, SigmaError, SigmaGroup, NIZK,
+};
+
+type Nizk = NIZK<SchnorrDLOG<RistrettoElement>, blake2::Blake2s>;
+
+/// The length of the seeds of `SchnorrInstance.generate`
+const SEED_LENGTH: usize = 32;
+
+/// The exception of Python, named as the error of the crate
+mod exception {
+    use pyo3::{create_exception, exceptions::PyException};
+
+    create_exception!(
+        sigma_protocol_standard,
+        SigmaError,
+        PyException,
+        "A failure reported by the library, with the name of its `SigmaError` variant in `name`"
+    );
+}
+
+/// The name of the variant of `error`, in the style of Python constants
+fn error_name(error: &SigmaError) -> &'static str {
+    match error {
+        SigmaError::VerificationFailed => "VERIFICATION_FAILED",
+        SigmaError::ChallengeConversionFailure => "CHALLENGE_CONVERSION_FAILURE",
+        SigmaError::SerializationError(_) => "SERIALIZATION_ERROR",
+        SigmaError::InvalidInstance => "INVALID_INSTANCE",
+        SigmaError::LengthMismatch => "LENGTH_MISMATCH",
+        SigmaError::CommitmentNotRecoverable => "COMMITMENT_NOT_RECOVERABLE",
+        SigmaError::UnsupportedHashFunction => "UNSUPPORTED_HASH_FUNCTION",
+        SigmaError::DigestTooShort { .. } => "DIGEST_TOO_SHORT",
+        SigmaError::EnvelopeMismatch => "ENVELOPE_MISMATCH",
+        SigmaError::ChallengeTooShort { .. } => "CHALLENGE_TOO_SHORT",
+    }
+}
+
+/// Raises `error` as a `SigmaError` of Python, whose message is the display of the error
+fn raise(error: SigmaError) -> PyErr {
+    Python::attach(|py| {
+        let exception = exception::SigmaError::new_err(error.to_string());
+        match exception.value(py).setattr("name", error_name(&error)) {
+            Ok(()) => exception,
+            Err(error) => error,
+        }
+    })
+}
+
+/// 32 bytes of the OS rng, for the seeds the caller does not supply
+fn os_seed() -> PyResult<[u8; SEED_LENGTH]> {
+    let mut seed = [0; SEED_LENGTH];
+    getrandom::getrandom(&mut seed).map_err(|error| PyException::new_err(error.to_string()))?;
+    Ok(seed)
+}
+
+/// A Schnorr instance over ristretto255, decoded from its canonical encoding: the compressed base followed by the
+/// compressed claim
+#[pyclass(name = "SchnorrInstance", module = "sigma_protocol_standard", frozen)]
+struct PySchnorrInstance(SchnorrInstance<RistrettoElement>);
+
+#[pymethods]
+impl PySchnorrInstance {
+    #[new]
+    fn new(encoding: &[u8]) -> PyResult<Self> {
+        proof_from_bytes(encoding, true).map(Self).map_err(raise)
+    }
+
+    /// A fresh instance over the standard generator with the canonical encoding of its witness, sampled from the
+    /// 32 bytes of `seed` or from the OS rng
+    #[staticmethod]
+    #[pyo3(signature = (seed = None))]
+    fn generate<'py>(
+        py: Python<'py>,
+        seed: Option<&[u8]>,
+    ) -> PyResult<(Self, Bound<'py, PyBytes>)> {
+        let seed = match seed {
+            Some(seed) => seed
+                .try_into()
+                .map_err(|_| PyValueError::new_err("the seed must be 32 bytes"))?,
+            None => os_seed()?,
+        };
+        let witness = RistrettoElement::random_scalar(&mut StdRng::from_seed(seed));
+        let instance = SchnorrInstance::from_witness(RistrettoElement::generator(), &witness);
+        Ok((
+            Self(instance),
+            PyBytes::new(py, &proof_bytes(&witness, true)),
+        ))
+    }
+
+    /// The canonical encoding of the instance
+    fn to_bytes<'py>(&self, py: Python<'py>) -> Bound<'py, PyBytes> {
+        PyBytes::new(py, &proof_bytes(&self.0, true))
+    }
+}
+
+/// The Schnorr NIZK over ristretto255 with Blake2s for an instance and a context, proving and verifying the
+/// compressed encodings of batchable proofs. A message of `None` is not the same as an empty message
+#[pyclass(name = "NIZK", module = "sigma_protocol_standard", frozen)]
+struct PyNizk(Nizk);
+
+#[pymethods]
+impl PyNizk {
+    #[new]
+    fn new(instance: &PySchnorrInstance, ctx: &[u8]) -> PyResult<Self> {
+        NIZK::try_new(&instance.0, ctx).map(Self).map_err(raise)
+    }
+
+    /// The compressed encoding of a batchable proof for the canonical encoding of `witness`, bound to `message`
+    #[pyo3(signature = (witness, message = None))]
+    fn prove<'py>(
+        &self,
+        py: Python<'py>,
+        witness: &[u8],
+        message: Option<&[u8]>,
+    ) -> PyResult<Bound<'py, PyBytes>> {
+        let witness = proof_from_bytes(witness, true).map_err(raise)?;
+        let proof = self
+            .0
+            .batchable_proof(&witness, message, &mut StdRng::from_seed(os_seed()?))
+            .map_err(raise)?;
+        Ok(PyBytes::new(py, &proof.to_bytes_compressed()))
+    }
+
+    /// Whether `proof` is valid for `message`. Bytes that are not a proof raise a `SigmaError`
+    #[pyo3(signature = (proof, message = None))]
+    fn verify(&self, proof: &[u8], message: Option<&[u8]>) -> PyResult<bool> {
+        let proof = BatchableProof::from_bytes_compressed(proof).map_err(raise)?;
+        match self.0.batchable_verify(&proof, message) {
+            Ok(()) => Ok(true),
+            Err(SigmaError::VerificationFailed) => Ok(false),
+            Err(error) => Err(raise(error)),
+        }
+    }
+
+    /// The Fiat-Shamir challenge of `proof` and `message`, whether the proof is valid or not
+    #[pyo3(signature = (proof, message = None))]
+    fn challenge<'py>(
+        &self,
+        py: Python<'py>,
+        proof: &[u8],
+        message: Option<&[u8]>,
+    ) -> PyResult<Bound<'py, PyBytes>> {
+        let proof = BatchableProof::from_bytes_compressed(proof).map_err(raise)?;
+        let challenge = self.0.batchable_challenge(&proof, message).map_err(raise)?;
+        Ok(PyBytes::new(py, &challenge))
+    }
+}
+
+/// The Schnorr NIZK of sigma_protocol_standard over ristretto255 with Blake2s. Instances, witnesses, proofs and
+/// challenges are the canonical byte encodings of the Rust types, as `bytes`, and every error of the library is
+/// raised as a `SigmaError`
+#[pymodule(name = "sigma_protocol_standard")]
+mod module {
+    #[pymodule_export]
+    use super::{exception::SigmaError, PyNizk, PySchnorrInstance};
+
+    #[pymodule_export]
+    const SCALAR_LENGTH: usize = 32;
+    #[pymodule_export]
+    const INSTANCE_LENGTH: usize = 64;
+    #[pymodule_export]
+    const PROOF_LENGTH: usize = 64;
+    #[pymodule_export]
+    const CHALLENGE_LENGTH: usize = crate::CHALLENGE_LENGTH;
+    #[pymodule_export]
+    const SEED_LENGTH: usize = super::SEED_LENGTH;
+}
@@ -0,0 +1,260 @@
+use std::marker::PhantomData;
+
+use ark_ec::ProjectiveCurve;
+use ark_ff::PrimeField;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Read, SerializationError, Write};
+use digest::Digest;
+use rand::Rng;
+
+use crate::{
+    interactive_proofs::canonical_bytes, protocols::SchnorrInstance, SigmaError, SigmaGroup,
+};
+
+/// A group over which RFC 8235 proofs are defined: how its elements are encoded into the challenge, and how the
+/// digest becomes a scalar
+pub trait Rfc8235Group: SigmaGroup {
+    /// The encoding of the element hashed into the challenge. Defaults to its canonical encoding; elliptic curves
+    /// with a SEC1 encoding use the uncompressed one, as implementations of the RFC such as EC J-PAKE do
+    fn rfc8235_encoding(&self) -> Vec<u8> {
+        canonical_bytes(self)
+    }
+
+    /// The scalar of the big-endian integer `bytes` reduced modulo the group order
+    fn scalar_from_be_bytes_mod_order(bytes: &[u8]) -> Self::Scalar;
+}
+
+impl<G: ProjectiveCurve> Rfc8235Group for G {
+    fn scalar_from_be_bytes_mod_order(bytes: &[u8]) -> Self::Scalar {
+        G::ScalarField::from_be_bytes_mod_order(bytes)
+    }
+}
+
+/// A Schnorr NIZK proof of RFC 8235: the commitment `V = g * v` and the response `r = v - a * h`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct Rfc8235Proof<G: SigmaGroup> {
+    commitment: G,
+    response: G::Scalar,
+}
+
+impl<G: SigmaGroup> Rfc8235Proof<G> {
+    /// A proof from its commitment and response, as received from another implementation of the RFC
+    pub fn new(commitment: G, response: G::Scalar) -> Self {
+        Self {
+            commitment,
+            response,
+        }
+    }
+
+    /// The commitment `V`
+    pub fn commitment(&self) -> G {
+        self.commitment
+    }
+
+    /// The response `r`
+    pub fn response(&self) -> G::Scalar {
+        self.response
+    }
+}
+
+/// The Schnorr NIZK of RFC 8235, as used by J-PAKE, for the knowledge of the discrete logarithm `a` of the public
+/// key `A = g * a`.
+///
+/// Unlike [`NIZK`](crate::NIZK), which derives its challenge from the domain separator, the protocol label and the
+/// context, this mode reproduces the transcript of the RFC so that its proofs interoperate with other
+/// implementations: the challenge is `h = H(g || V || A || UserID || OtherInfo)` read as a big-endian integer
+/// modulo the group order, where every item is prefixed with its length as a 4-byte big-endian integer, and the
+/// response is `r = v - a * h`. Group elements are encoded as [`Rfc8235Group::rfc8235_encoding`] and `H` is any
+/// [`Digest`]
+#[derive(Debug, Clone)]
+pub struct Rfc8235Schnorr<G: Rfc8235Group, D: Digest> {
+    base: G,
+    public_key: G,
+    user_id: Vec<u8>,
+    other_info: Vec<Vec<u8>>,
+    _hash: PhantomData<D>,
+}
+
+impl<G: Rfc8235Group, D: Digest> Rfc8235Schnorr<G, D> {
+    /// The NIZK for the base and the public key of `instance` and the identifier of the prover. Returns
+    /// [`SigmaError::InvalidInstance`] for an identity base or public key, or one outside the prime-order subgroup,
+    /// as the RFC requires verifiers to check the public key
+    pub fn new(instance: &SchnorrInstance<G>, user_id: &[u8]) -> Result<Self, SigmaError> {
+        let (base, public_key) = (instance.base(), instance.claim());
+        if public_key.is_identity() {
+            return Err(SigmaError::InvalidInstance);
+        }
+        SchnorrInstance::try_new(base, public_key)?;
+
+        Ok(Self {
+            base,
+            public_key,
+            user_id: user_id.to_vec(),
+            other_info: Vec::new(),
+            _hash: PhantomData,
+        })
+    }
+
+    /// Binds the proofs to `other_info`, whose subitems are each hashed after the UserID with their own length
+    /// prefix
+    pub fn with_other_info(mut self, other_info: &[&[u8]]) -> Self {
+        self.other_info = other_info.iter().map(|info| info.to_vec()).collect();
+        self
+    }
+
+    /// The challenge `h` for the commitment `V`
+    pub fn challenge(&self, commitment: &G) -> G::Scalar {
+        let mut hasher = D::new();
+        let mut item = |bytes: &[u8]| {
+            // Items longer than 4 GiB cannot be encoded by the RFC, and have no reason to exist
+            let len = u32::try_from(bytes.len()).expect("an RFC 8235 item is shorter than 4 GiB");
+            hasher.update(len.to_be_bytes());
+            hasher.update(bytes);
+        };
+
+        item(&self.base.rfc8235_encoding());
+        item(&commitment.rfc8235_encoding());
+        item(&self.public_key.rfc8235_encoding());
+        item(&self.user_id);
+        for info in &self.other_info {
+            item(info);
+        }
+
+        G::scalar_from_be_bytes_mod_order(&hasher.finalize())
+    }
+
+    /// Prove the knowledge of `witness`, the discrete logarithm of the public key, with a nonce sampled from `rng`
+    pub fn prove<R: Rng>(&self, witness: &G::Scalar, rng: &mut R) -> Rfc8235Proof<G> {
+        self.prove_with_nonce(witness, G::random_scalar(rng))
+    }
+
+    /// Prove with a given nonce `v`, as for reproducing test vectors. Reusing a nonce, or using one that is not
+    /// uniformly random and secret, reveals the witness
+    pub fn prove_with_nonce(&self, witness: &G::Scalar, nonce: G::Scalar) -> Rfc8235Proof<G> {
+        let commitment = self.base.scalar_mul(&nonce);
+        let response = nonce - *witness * self.challenge(&commitment);
+
+        Rfc8235Proof {
+            commitment,
+            response,
+        }
+    }
+
+    /// Verify that `V = g * r + A * h`, for a commitment in the prime-order subgroup
+    pub fn verify(&self, proof: &Rfc8235Proof<G>) -> Result<(), SigmaError> {
+        if !proof.commitment.is_in_prime_subgroup() {
+            return Err(SigmaError::VerificationFailed);
+        }
+
+        let challenge = self.challenge(&proof.commitment);
+        let expected =
+            G::multi_scalar_mul(&[self.base, self.public_key], &[proof.response, challenge]);
+        if expected == proof.commitment {
+            Ok(())
+        } else {
+            Err(SigmaError::VerificationFailed)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_ec::ProjectiveCurve;
+    use ark_ff::Zero;
+    use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+    use rand::thread_rng;
+
+    use crate::{protocols::SchnorrInstance, SigmaError};
+
+    use super::{Rfc8235Proof, Rfc8235Schnorr};
+
+    type G = ark_bls12_377::G1Projective;
+    type F = ark_bls12_377::Fr;
+    type Schnorr = Rfc8235Schnorr<G, sha2::Sha256>;
+
+    fn random_instance() -> (SchnorrInstance<G>, F) {
+        SchnorrInstance::generate(&mut thread_rng())
+    }
+
+    #[test]
+    fn test_rfc8235_prove_and_verify() {
+        let (instance, witness) = random_instance();
+        let nizk = Schnorr::new(&instance, b"alice").unwrap();
+        let proof = nizk.prove(&witness, &mut thread_rng());
+        assert!(nizk.verify(&proof).is_ok());
+
+        let mut bytes = Vec::new();
+        proof.serialize(&mut bytes).unwrap();
+        let decoded = Rfc8235Proof::<G>::deserialize(&bytes[..]).unwrap();
+        assert_eq!(decoded, proof);
+        assert!(Schnorr::new(&instance, b"alice")
+            .unwrap()
+            .verify(&decoded)
+            .is_ok());
+
+        let (other_instance, _) = random_instance();
+        assert_eq!(
+            Schnorr::new(&other_instance, b"alice")
+                .unwrap()
+                .verify(&proof),
+            Err(SigmaError::VerificationFailed)
+        );
+    }
+
+    #[test]
+    fn test_rfc8235_rejects_other_user_id() {
+        let (instance, witness) = random_instance();
+        let proof = Schnorr::new(&instance, b"alice")
+            .unwrap()
+            .prove(&witness, &mut thread_rng());
+
+        for user_id in [&b"bob"[..], b"", b"alice\0", b"Alice"] {
+            assert_eq!(
+                Schnorr::new(&instance, user_id).unwrap().verify(&proof),
+                Err(SigmaError::VerificationFailed)
+            );
+        }
+
+        // The OtherInfo is bound as well
+        let with_info = Schnorr::new(&instance, b"alice")
+            .unwrap()
+            .with_other_info(&[b"session"]);
+        assert_eq!(
+            with_info.verify(&proof),
+            Err(SigmaError::VerificationFailed)
+        );
+        assert!(with_info
+            .verify(&with_info.prove(&witness, &mut thread_rng()))
+            .is_ok());
+    }
+
+    #[test]
+    fn test_rfc8235_items_are_length_prefixed() {
+        let (instance, _) = random_instance();
+        let commitment = G::prime_subgroup_generator();
+        let challenge = |user_id: &[u8], other_info: &[&[u8]]| {
+            Schnorr::new(&instance, user_id)
+                .unwrap()
+                .with_other_info(other_info)
+                .challenge(&commitment)
+        };
+
+        // Moving bytes between the UserID and the OtherInfo, or between subitems, changes the challenge
+        assert_ne!(challenge(b"ab", &[]), challenge(b"a", &[b"b"]));
+        assert_ne!(challenge(b"a", &[b"bc"]), challenge(b"a", &[b"b", b"c"]));
+        assert_ne!(challenge(b"a", &[]), challenge(b"a", &[b""]));
+    }
+
+    #[test]
+    fn test_rfc8235_rejects_invalid_instances() {
+        let generator = G::prime_subgroup_generator();
+        for instance in [
+            SchnorrInstance::new(generator, G::zero()),
+            SchnorrInstance::new(G::zero(), generator),
+        ] {
+            assert_eq!(
+                Schnorr::new(&instance, b"alice").err(),
+                Some(SigmaError::InvalidInstance)
+            );
+        }
+    }
+}
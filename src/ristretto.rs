@@ -0,0 +1,269 @@
+use std::ops::{Add, Mul, Sub};
+
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Read, SerializationError, Write};
+use curve25519_dalek::{
+    constants::RISTRETTO_BASEPOINT_POINT,
+    ristretto::{CompressedRistretto, RistrettoPoint},
+    scalar::Scalar,
+    traits::{Identity, VartimeMultiscalarMul},
+};
+use rand::Rng;
+
+use crate::{rfc8235::Rfc8235Group, Challenge, ChallengeExpansion, SigmaError, SigmaGroup};
+
+/// Length in bytes of a compressed ristretto255 element and of a scalar
+const ENCODING_LENGTH: usize = 32;
+
+/// A ristretto255 group element, usable with every protocol generic over [`SigmaGroup`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RistrettoElement(pub RistrettoPoint);
+
+/// A scalar of the ristretto255 group
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RistrettoScalar(pub Scalar);
+
+#[cfg(feature = "zeroize")]
+impl zeroize::Zeroize for RistrettoScalar {
+    fn zeroize(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+impl RistrettoElement {
+    /// The standard ristretto255 base point
+    pub fn generator() -> Self {
+        Self(RISTRETTO_BASEPOINT_POINT)
+    }
+}
+
+impl Add for RistrettoElement {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self(self.0 + other.0)
+    }
+}
+
+impl Sub for RistrettoElement {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Self(self.0 - other.0)
+    }
+}
+
+impl Add for RistrettoScalar {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self(self.0 + other.0)
+    }
+}
+
+impl Sub for RistrettoScalar {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Self(self.0 - other.0)
+    }
+}
+
+impl Mul for RistrettoScalar {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self {
+        Self(self.0 * other.0)
+    }
+}
+
+/// The scalar of a small integer, as the weights of batch verification
+impl From<u128> for RistrettoScalar {
+    fn from(integer: u128) -> Self {
+        Self(Scalar::from(integer))
+    }
+}
+
+impl CanonicalSerialize for RistrettoElement {
+    fn serialize<W: Write>(&self, mut writer: W) -> Result<(), SerializationError> {
+        Ok(writer.write_all(self.0.compress().as_bytes())?)
+    }
+
+    fn serialized_size(&self) -> usize {
+        ENCODING_LENGTH
+    }
+}
+
+impl CanonicalDeserialize for RistrettoElement {
+    fn deserialize<R: Read>(mut reader: R) -> Result<Self, SerializationError> {
+        let mut bytes = [0u8; ENCODING_LENGTH];
+        reader.read_exact(&mut bytes)?;
+        CompressedRistretto(bytes)
+            .decompress()
+            .map(Self)
+            .ok_or(SerializationError::InvalidData)
+    }
+}
+
+impl CanonicalSerialize for RistrettoScalar {
+    fn serialize<W: Write>(&self, mut writer: W) -> Result<(), SerializationError> {
+        Ok(writer.write_all(self.0.as_bytes())?)
+    }
+
+    fn serialized_size(&self) -> usize {
+        ENCODING_LENGTH
+    }
+}
+
+impl CanonicalDeserialize for RistrettoScalar {
+    fn deserialize<R: Read>(mut reader: R) -> Result<Self, SerializationError> {
+        let mut bytes = [0u8; ENCODING_LENGTH];
+        reader.read_exact(&mut bytes)?;
+        Option::from(Scalar::from_canonical_bytes(bytes))
+            .map(Self)
+            .ok_or(SerializationError::InvalidData)
+    }
+}
+
+impl SigmaGroup for RistrettoElement {
+    type Scalar = RistrettoScalar;
+
+    fn generator() -> Self {
+        Self(RISTRETTO_BASEPOINT_POINT)
+    }
+
+    fn is_identity(&self) -> bool {
+        self.0 == RistrettoPoint::identity()
+    }
+
+    fn scalar_mul(&self, scalar: &Self::Scalar) -> Self {
+        Self(self.0 * scalar.0)
+    }
+
+    fn multi_scalar_mul(bases: &[Self], scalars: &[Self::Scalar]) -> Self {
+        assert_eq!(bases.len(), scalars.len(), "one scalar per base");
+
+        Self(RistrettoPoint::vartime_multiscalar_mul(
+            scalars.iter().map(|scalar| scalar.0),
+            bases.iter().map(|base| base.0),
+        ))
+    }
+
+    fn random_scalar<R: Rng>(rng: &mut R) -> Self::Scalar {
+        let mut bytes = [0u8; 2 * ENCODING_LENGTH];
+        rng.fill(&mut bytes[..]);
+        RistrettoScalar(Scalar::from_bytes_mod_order_wide(&bytes))
+    }
+
+    /// The challenge is expanded to 512 bits, which are reduced modulo the group order
+    fn challenge_scalar(
+        challenge: &Challenge,
+        expansion: ChallengeExpansion,
+    ) -> Result<Self::Scalar, SigmaError> {
+        let mut bytes = [0u8; 2 * ENCODING_LENGTH];
+        bytes.copy_from_slice(&expansion.expand(challenge, 2 * ENCODING_LENGTH));
+        Ok(RistrettoScalar(Scalar::from_bytes_mod_order_wide(&bytes)))
+    }
+}
+
+impl Rfc8235Group for RistrettoElement {
+    fn scalar_from_be_bytes_mod_order(bytes: &[u8]) -> Self::Scalar {
+        let byte_base = Scalar::from(256u64);
+        RistrettoScalar(bytes.iter().fold(Scalar::ZERO, |scalar, byte| {
+            scalar * byte_base + Scalar::from(u64::from(*byte))
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+    use rand::thread_rng;
+
+    use crate::{
+        nizk_proofs::tests::{run_nizk_batched, run_nizk_short},
+        protocols::{SchnorrDLOG, SchnorrInstance},
+        BatchRandomizers, SigmaError, SigmaGroup, SigmaProtocol, NIZK,
+    };
+
+    use super::RistrettoElement;
+
+    type Hash = blake2::Blake2s;
+
+    #[test]
+    fn test_schnorr_ristretto() {
+        let rng = &mut thread_rng();
+        let (instance, witness) = SchnorrInstance::<RistrettoElement>::generate(rng);
+        let wrong_witness = RistrettoElement::random_scalar(rng);
+
+        let batched_result = run_nizk_batched::<Hash, SchnorrDLOG<_>, _>(&instance, &witness, rng);
+        let short_result = run_nizk_short::<Hash, SchnorrDLOG<_>, _>(&instance, &witness, rng);
+        let wrong_result =
+            run_nizk_batched::<Hash, SchnorrDLOG<_>, _>(&instance, &wrong_witness, rng);
+
+        assert!(batched_result.is_ok());
+        assert!(short_result.is_ok());
+        assert_eq!(wrong_result, Err(SigmaError::VerificationFailed));
+    }
+
+    #[test]
+    fn test_ristretto_serialization_round_trip() {
+        let rng = &mut thread_rng();
+        let scalar = RistrettoElement::random_scalar(rng);
+        let element = RistrettoElement::generator().scalar_mul(&scalar);
+
+        let mut bytes = Vec::new();
+        element.serialize(&mut bytes).unwrap();
+        scalar.serialize(&mut bytes).unwrap();
+        let mut reader = &bytes[..];
+
+        assert_eq!(RistrettoElement::deserialize(&mut reader).unwrap(), element);
+        assert_eq!(
+            <RistrettoElement as SigmaGroup>::Scalar::deserialize(&mut reader).unwrap(),
+            scalar
+        );
+    }
+
+    #[test]
+    fn test_multi_scalar_mul_matches_scalar_muls() {
+        let rng = &mut thread_rng();
+        let generator = RistrettoElement::generator();
+        let bases: Vec<_> = (0..3)
+            .map(|_| generator.scalar_mul(&RistrettoElement::random_scalar(rng)))
+            .collect();
+        let scalars: Vec<_> = (0..3)
+            .map(|_| RistrettoElement::random_scalar(rng))
+            .collect();
+
+        assert_eq!(
+            RistrettoElement::multi_scalar_mul(&bases, &scalars),
+            bases[0].scalar_mul(&scalars[0])
+                + bases[1].scalar_mul(&scalars[1])
+                + bases[2].scalar_mul(&scalars[2])
+        );
+        assert!(RistrettoElement::multi_scalar_mul(&[], &[]).is_identity());
+    }
+
+    #[test]
+    fn test_ristretto_batch_verify() {
+        let rng = &mut thread_rng();
+        let (instance, witness) = SchnorrInstance::<RistrettoElement>::generate(rng);
+        let nizk = NIZK::<SchnorrDLOG<_>, Hash>::new(&instance, b"ctx");
+        let mut proofs: Vec<_> = (0..3)
+            .map(|_| (nizk.batchable_proof(&witness, None, rng).unwrap(), None))
+            .collect();
+
+        assert!(nizk.batch_verify(&proofs, rng).is_ok());
+        assert!(nizk.batch_verify(&[], rng).is_ok());
+
+        // A proof of another message is rejected by weights of every width, with probability one half for 1-bit
+        // weights, which draw a zero weight for it otherwise
+        proofs[1].1 = Some(b"other".as_slice());
+        for bits in [1, 64, 128, 256] {
+            let protocol = SchnorrDLOG::new(&instance)
+                .with_batch_randomizers(BatchRandomizers::with_bits(bits));
+            let nizk = NIZK::<_, Hash>::from_protocol(protocol, b"ctx").unwrap();
+            assert!((0..32).any(|_| nizk.batch_verify(&proofs, rng).is_err()));
+        }
+        assert!(nizk.batch_verify(&proofs, rng).is_err());
+    }
+}
@@ -0,0 +1,281 @@
+use std::ops::{Add, Mul, Sub};
+
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Read, SerializationError, Write};
+use k256::{
+    elliptic_curve::{
+        bigint::U512, group::GroupEncoding, ops::Reduce, sec1::ToEncodedPoint, PrimeField,
+    },
+    CompressedPoint, FieldBytes, ProjectivePoint, Scalar,
+};
+use rand::Rng;
+
+use crate::{rfc8235::Rfc8235Group, Challenge, ChallengeExpansion, SigmaError, SigmaGroup};
+
+/// Length in bytes of a compressed SEC1 point, the identity being encoded as all zeros
+const POINT_LENGTH: usize = 33;
+
+/// Length in bytes of a big-endian scalar
+const SCALAR_LENGTH: usize = 32;
+
+/// A secp256k1 group element, usable with every protocol generic over [`SigmaGroup`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Secp256k1Element(pub ProjectivePoint);
+
+/// A scalar of the secp256k1 group
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Secp256k1Scalar(pub Scalar);
+
+#[cfg(feature = "zeroize")]
+impl zeroize::Zeroize for Secp256k1Scalar {
+    fn zeroize(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+impl Secp256k1Element {
+    /// The standard secp256k1 generator
+    pub fn generator() -> Self {
+        Self(ProjectivePoint::GENERATOR)
+    }
+}
+
+impl Secp256k1Scalar {
+    /// Decode a canonical big-endian scalar, the encoding used by SEC1 and by the test vectors
+    pub fn from_bytes(bytes: [u8; SCALAR_LENGTH]) -> Option<Self> {
+        Option::from(Scalar::from_repr(FieldBytes::from(bytes))).map(Self)
+    }
+}
+
+impl Add for Secp256k1Element {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self(self.0 + other.0)
+    }
+}
+
+impl Sub for Secp256k1Element {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Self(self.0 - other.0)
+    }
+}
+
+impl Add for Secp256k1Scalar {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self(self.0 + other.0)
+    }
+}
+
+impl Sub for Secp256k1Scalar {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Self(self.0 - other.0)
+    }
+}
+
+impl Mul for Secp256k1Scalar {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self {
+        Self(self.0 * other.0)
+    }
+}
+
+/// The scalar of a small integer, as the weights of batch verification
+impl From<u128> for Secp256k1Scalar {
+    fn from(integer: u128) -> Self {
+        Self(Scalar::from(integer))
+    }
+}
+
+impl CanonicalSerialize for Secp256k1Element {
+    fn serialize<W: Write>(&self, mut writer: W) -> Result<(), SerializationError> {
+        Ok(writer.write_all(&self.0.to_bytes())?)
+    }
+
+    fn serialized_size(&self) -> usize {
+        POINT_LENGTH
+    }
+}
+
+impl CanonicalDeserialize for Secp256k1Element {
+    fn deserialize<R: Read>(mut reader: R) -> Result<Self, SerializationError> {
+        let mut bytes = CompressedPoint::default();
+        reader.read_exact(&mut bytes)?;
+        Option::from(ProjectivePoint::from_bytes(&bytes))
+            .map(Self)
+            .ok_or(SerializationError::InvalidData)
+    }
+}
+
+impl CanonicalSerialize for Secp256k1Scalar {
+    fn serialize<W: Write>(&self, mut writer: W) -> Result<(), SerializationError> {
+        Ok(writer.write_all(&self.0.to_bytes())?)
+    }
+
+    fn serialized_size(&self) -> usize {
+        SCALAR_LENGTH
+    }
+}
+
+impl CanonicalDeserialize for Secp256k1Scalar {
+    fn deserialize<R: Read>(mut reader: R) -> Result<Self, SerializationError> {
+        let mut bytes = [0u8; SCALAR_LENGTH];
+        reader.read_exact(&mut bytes)?;
+        Self::from_bytes(bytes).ok_or(SerializationError::InvalidData)
+    }
+}
+
+impl SigmaGroup for Secp256k1Element {
+    type Scalar = Secp256k1Scalar;
+
+    fn generator() -> Self {
+        Self(ProjectivePoint::GENERATOR)
+    }
+
+    fn is_identity(&self) -> bool {
+        self.0 == ProjectivePoint::IDENTITY
+    }
+
+    fn scalar_mul(&self, scalar: &Self::Scalar) -> Self {
+        Self(self.0 * scalar.0)
+    }
+
+    fn random_scalar<R: Rng>(rng: &mut R) -> Self::Scalar {
+        loop {
+            if let Some(scalar) = Secp256k1Scalar::from_bytes(rng.gen()) {
+                return scalar;
+            }
+        }
+    }
+
+    /// The challenge is expanded to 512 bits, which are read as a big-endian integer and reduced modulo the group
+    /// order
+    fn challenge_scalar(
+        challenge: &Challenge,
+        expansion: ChallengeExpansion,
+    ) -> Result<Self::Scalar, SigmaError> {
+        let wide = U512::from_be_slice(&expansion.expand(challenge, 2 * SCALAR_LENGTH));
+        Ok(Secp256k1Scalar(<Scalar as Reduce<U512>>::reduce(wide)))
+    }
+}
+
+impl Rfc8235Group for Secp256k1Element {
+    /// The uncompressed SEC1 encoding, as hashed by EC J-PAKE
+    fn rfc8235_encoding(&self) -> Vec<u8> {
+        self.0
+            .to_affine()
+            .to_encoded_point(false)
+            .as_bytes()
+            .to_vec()
+    }
+
+    fn scalar_from_be_bytes_mod_order(bytes: &[u8]) -> Self::Scalar {
+        let byte_base = Scalar::from(256u64);
+        Secp256k1Scalar(bytes.iter().fold(Scalar::ZERO, |scalar, byte| {
+            scalar * byte_base + Scalar::from(u64::from(*byte))
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+    use rand::thread_rng;
+
+    use crate::{
+        nizk_proofs::tests::{run_nizk_batched, run_nizk_short},
+        protocols::{SchnorrDLOG, SchnorrInstance},
+        BatchRandomizers, SigmaError, SigmaGroup, SigmaProtocol, NIZK,
+    };
+
+    use super::{Secp256k1Element, Secp256k1Scalar};
+
+    type Hash = blake2::Blake2s;
+
+    #[test]
+    fn test_schnorr_secp256k1() {
+        let rng = &mut thread_rng();
+        let (instance, witness) = SchnorrInstance::<Secp256k1Element>::generate(rng);
+        let wrong_witness = Secp256k1Element::random_scalar(rng);
+
+        let batched_result = run_nizk_batched::<Hash, SchnorrDLOG<_>, _>(&instance, &witness, rng);
+        let short_result = run_nizk_short::<Hash, SchnorrDLOG<_>, _>(&instance, &witness, rng);
+        let wrong_result =
+            run_nizk_batched::<Hash, SchnorrDLOG<_>, _>(&instance, &wrong_witness, rng);
+
+        assert!(batched_result.is_ok());
+        assert!(short_result.is_ok());
+        assert_eq!(wrong_result, Err(SigmaError::VerificationFailed));
+    }
+
+    #[test]
+    fn test_secp256k1_serialization_round_trip() {
+        let rng = &mut thread_rng();
+        let scalar = Secp256k1Element::random_scalar(rng);
+        let element = Secp256k1Element::generator().scalar_mul(&scalar);
+        let identity = element - element;
+
+        let mut bytes = Vec::new();
+        element.serialize(&mut bytes).unwrap();
+        identity.serialize(&mut bytes).unwrap();
+        scalar.serialize(&mut bytes).unwrap();
+        let mut reader = &bytes[..];
+
+        assert_eq!(Secp256k1Element::deserialize(&mut reader).unwrap(), element);
+        assert_eq!(
+            Secp256k1Element::deserialize(&mut reader).unwrap(),
+            identity
+        );
+        assert_eq!(Secp256k1Scalar::deserialize(&mut reader).unwrap(), scalar);
+    }
+
+    #[test]
+    fn test_multi_scalar_mul_matches_scalar_muls() {
+        let rng = &mut thread_rng();
+        let generator = Secp256k1Element::generator();
+        let bases: Vec<_> = (0..3)
+            .map(|_| generator.scalar_mul(&Secp256k1Element::random_scalar(rng)))
+            .collect();
+        let scalars: Vec<_> = (0..3)
+            .map(|_| Secp256k1Element::random_scalar(rng))
+            .collect();
+
+        assert_eq!(
+            Secp256k1Element::multi_scalar_mul(&bases, &scalars),
+            bases[0].scalar_mul(&scalars[0])
+                + bases[1].scalar_mul(&scalars[1])
+                + bases[2].scalar_mul(&scalars[2])
+        );
+        assert!(Secp256k1Element::multi_scalar_mul(&[], &[]).is_identity());
+    }
+
+    #[test]
+    fn test_secp256k1_batch_verify() {
+        let rng = &mut thread_rng();
+        let (instance, witness) = SchnorrInstance::<Secp256k1Element>::generate(rng);
+        let nizk = NIZK::<SchnorrDLOG<_>, Hash>::new(&instance, b"ctx");
+        let mut proofs: Vec<_> = (0..3)
+            .map(|_| (nizk.batchable_proof(&witness, None, rng).unwrap(), None))
+            .collect();
+
+        assert!(nizk.batch_verify(&proofs, rng).is_ok());
+        assert!(nizk.batch_verify(&[], rng).is_ok());
+
+        // A proof of another message is rejected by weights of every width, with probability one half for 1-bit
+        // weights, which draw a zero weight for it otherwise
+        proofs[1].1 = Some(b"other".as_slice());
+        for bits in [1, 64, 128, 256] {
+            let protocol = SchnorrDLOG::new(&instance)
+                .with_batch_randomizers(BatchRandomizers::with_bits(bits));
+            let nizk = NIZK::<_, Hash>::from_protocol(protocol, b"ctx").unwrap();
+            assert!((0..32).any(|_| nizk.batch_verify(&proofs, rng).is_err()));
+        }
+        assert!(nizk.batch_verify(&proofs, rng).is_err());
+    }
+}
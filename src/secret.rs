@@ -0,0 +1,150 @@
+use std::fmt;
+
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Read, SerializationError, Write};
+
+use crate::Zeroizable;
+
+/// The placeholder that the debug output of prover states, witnesses and keys prints instead of a secret field
+#[doc(hidden)]
+pub struct Redacted;
+
+impl fmt::Debug for Redacted {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("<redacted>")
+    }
+}
+
+/// A secret scalar, such as a witness, whose debug output is redacted and which is wiped on drop with the
+/// `zeroize` feature.
+///
+/// The scalar is only read through [`SecretScalar::expose`], so that every use of the secret stands out in review.
+/// A protocol can declare `type Witness = SecretScalar<F>`: the encoding is that of the scalar, so
+/// [`SigmaProtocol::witness_bytes`](crate::SigmaProtocol::witness_bytes) can serialize it as usual
+#[derive(Clone)]
+pub struct SecretScalar<F: Zeroizable>(F);
+
+impl<F: Zeroizable> SecretScalar<F> {
+    /// Wrap a secret scalar
+    pub fn new(scalar: F) -> Self {
+        Self(scalar)
+    }
+
+    /// The secret scalar
+    pub fn expose(&self) -> &F {
+        &self.0
+    }
+}
+
+impl<F: Zeroizable> From<F> for SecretScalar<F> {
+    fn from(scalar: F) -> Self {
+        Self::new(scalar)
+    }
+}
+
+impl<F: Zeroizable> fmt::Debug for SecretScalar<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("SecretScalar").field(&Redacted).finish()
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl<F: Zeroizable> zeroize::Zeroize for SecretScalar<F> {
+    fn zeroize(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl<F: Zeroizable> Drop for SecretScalar<F> {
+    fn drop(&mut self) {
+        zeroize::Zeroize::zeroize(self);
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl<F: Zeroizable> zeroize::ZeroizeOnDrop for SecretScalar<F> {}
+
+impl<F: Zeroizable + CanonicalSerialize> CanonicalSerialize for SecretScalar<F> {
+    fn serialize<W: Write>(&self, writer: W) -> Result<(), SerializationError> {
+        self.0.serialize(writer)
+    }
+
+    fn serialized_size(&self) -> usize {
+        self.0.serialized_size()
+    }
+}
+
+impl<F: Zeroizable + CanonicalDeserialize> CanonicalDeserialize for SecretScalar<F> {
+    fn deserialize<R: Read>(reader: R) -> Result<Self, SerializationError> {
+        F::deserialize(reader).map(Self)
+    }
+}
+
+/// Asserts that the debug output of `value` is `expected` and holds no hex digits of the encoding of `secret`,
+/// nor its own debug output
+#[cfg(test)]
+pub(crate) fn assert_redacted<T: fmt::Debug, F: CanonicalSerialize + fmt::Debug>(
+    value: &T,
+    secret: &F,
+    expected: &str,
+) {
+    let output = format!("{:?}", value);
+    assert_eq!(output, expected);
+
+    let mut bytes = Vec::new();
+    secret.serialize(&mut bytes).unwrap();
+    let hex = crate::encoding::encode_hex(&bytes);
+    // Any four bytes of the encoding would already identify the secret
+    for window in hex.as_bytes().windows(8) {
+        assert!(!output.contains(std::str::from_utf8(window).unwrap()));
+    }
+    assert!(!output.contains(&format!("{:?}", secret)));
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_ff::UniformRand;
+    use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+    use rand::thread_rng;
+
+    use crate::protocols::PedersenOpening;
+
+    use super::{assert_redacted, SecretScalar};
+
+    type F = ark_bls12_377::Fr;
+
+    #[test]
+    fn test_secret_scalar_debug_is_redacted() {
+        let scalar = F::rand(&mut thread_rng());
+        let secret = SecretScalar::new(scalar);
+
+        assert_redacted(&secret, &scalar, "SecretScalar(<redacted>)");
+        assert_eq!(secret.expose(), &scalar);
+    }
+
+    #[test]
+    fn test_pedersen_opening_debug_is_redacted() {
+        let rng = &mut thread_rng();
+        let (message, blinding) = (F::rand(rng), F::rand(rng));
+        let opening = PedersenOpening::new(message, blinding);
+
+        let expected = "PedersenOpening { message: <redacted>, blinding: <redacted> }";
+        assert_redacted(&opening, &message, expected);
+        assert_redacted(&opening, &blinding, expected);
+    }
+
+    #[test]
+    fn test_secret_scalar_encodes_as_its_scalar() {
+        let scalar = F::rand(&mut thread_rng());
+        let mut bytes = Vec::new();
+        SecretScalar::from(scalar).serialize(&mut bytes).unwrap();
+
+        let mut expected = Vec::new();
+        scalar.serialize(&mut expected).unwrap();
+        assert_eq!(bytes, expected);
+        assert_eq!(
+            SecretScalar::<F>::deserialize(&bytes[..]).unwrap().expose(),
+            &scalar
+        );
+    }
+}
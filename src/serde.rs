@@ -0,0 +1,238 @@
+use std::fmt;
+
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{
+    encoding::{decode_hex, encode_hex},
+    nizk_proofs::{proof_bytes, proof_from_bytes},
+    protocols::SchnorrInstance,
+    BatchableProof, ShortProof, SigmaGroup, SigmaProtocol,
+};
+
+/// Serializes `bytes` as a lowercase hex string in human-readable formats, and as a byte string otherwise
+fn serialize_bytes<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+    if serializer.is_human_readable() {
+        serializer.serialize_str(&encode_hex(bytes))
+    } else {
+        serializer.serialize_bytes(bytes)
+    }
+}
+
+/// Deserializes the bytes written by [`serialize_bytes`], and decodes them with `decode`
+fn deserialize_bytes<'de, D, T, E>(
+    deserializer: D,
+    decode: impl FnOnce(&[u8]) -> Result<T, E>,
+) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    E: fmt::Display,
+{
+    let bytes = if deserializer.is_human_readable() {
+        deserializer.deserialize_str(BytesVisitor)?
+    } else {
+        deserializer.deserialize_byte_buf(BytesVisitor)?
+    };
+    decode(&bytes).map_err(de::Error::custom)
+}
+
+/// Accepts the hex of human-readable formats, and byte strings or sequences of bytes otherwise
+struct BytesVisitor;
+
+impl<'de> de::Visitor<'de> for BytesVisitor {
+    type Value = Vec<u8>;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a canonical encoding, as hex or bytes")
+    }
+
+    fn visit_str<E: de::Error>(self, hex: &str) -> Result<Self::Value, E> {
+        decode_hex(hex).map_err(E::custom)
+    }
+
+    fn visit_bytes<E: de::Error>(self, bytes: &[u8]) -> Result<Self::Value, E> {
+        Ok(bytes.to_vec())
+    }
+
+    fn visit_byte_buf<E: de::Error>(self, bytes: Vec<u8>) -> Result<Self::Value, E> {
+        Ok(bytes)
+    }
+
+    fn visit_seq<A: de::SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut bytes = Vec::with_capacity(seq.size_hint().unwrap_or(0).min(4096));
+        while let Some(byte) = seq.next_element()? {
+            bytes.push(byte);
+        }
+        Ok(bytes)
+    }
+}
+
+impl<S: SigmaProtocol> Serialize for BatchableProof<S> {
+    fn serialize<Z: Serializer>(&self, serializer: Z) -> Result<Z::Ok, Z::Error> {
+        serialize_bytes(&self.to_bytes_compressed(), serializer)
+    }
+}
+
+impl<'de, S: SigmaProtocol> Deserialize<'de> for BatchableProof<S> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_bytes(deserializer, Self::from_bytes_compressed)
+    }
+}
+
+impl<S: SigmaProtocol, const L: usize> Serialize for ShortProof<S, L> {
+    fn serialize<Z: Serializer>(&self, serializer: Z) -> Result<Z::Ok, Z::Error> {
+        serialize_bytes(&self.to_bytes_compressed(), serializer)
+    }
+}
+
+impl<'de, S: SigmaProtocol, const L: usize> Deserialize<'de> for ShortProof<S, L> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_bytes(deserializer, Self::from_bytes_compressed)
+    }
+}
+
+/// Implements `Serialize` and `Deserialize` for an instance type through its compressed canonical encoding,
+/// rejecting trailing bytes. Instance types with a `CanonicalDeserialize` implementation are added here
+macro_rules! canonical_serde {
+    ($instance:ident<$group:ident: $bound:path>) => {
+        impl<$group: $bound> Serialize for $instance<$group> {
+            fn serialize<Z: Serializer>(&self, serializer: Z) -> Result<Z::Ok, Z::Error> {
+                serialize_bytes(&proof_bytes(self, true), serializer)
+            }
+        }
+
+        impl<'de, $group: $bound> Deserialize<'de> for $instance<$group> {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                deserialize_bytes(deserializer, |bytes| proof_from_bytes(bytes, true))
+            }
+        }
+    };
+}
+
+canonical_serde!(SchnorrInstance<G: SigmaGroup>);
+
+#[cfg(test)]
+mod tests {
+    use rand::thread_rng;
+    use serde_json::Value;
+
+    use crate::{
+        protocols::{SchnorrDLOG, SchnorrInstance},
+        BatchableProof, ShortProof, NIZK,
+    };
+
+    type G = ark_bls12_377::G1Projective;
+    type Proof = BatchableProof<SchnorrDLOG<G>>;
+    type Short = ShortProof<SchnorrDLOG<G>>;
+    type Nizk = NIZK<SchnorrDLOG<G>, blake2::Blake2s>;
+
+    fn setup() -> (SchnorrInstance<G>, Proof, Short) {
+        let rng = &mut thread_rng();
+        let (instance, witness) = SchnorrInstance::<G>::generate(rng);
+        let nizk = Nizk::new(&instance, b"ctx");
+        let proof = nizk
+            .batchable_proof(&witness, Some(b"message"), rng)
+            .unwrap();
+        let short_proof = nizk.short_proof(&witness, Some(b"message"), rng).unwrap();
+        (instance, proof, short_proof)
+    }
+
+    #[test]
+    fn test_json_round_trip() {
+        let (instance, proof, short_proof) = setup();
+
+        let json = serde_json::to_string(&(&instance, &proof, &short_proof)).unwrap();
+        let expected = format!(
+            "[\"{}\",\"{}\",\"{}\"]",
+            crate::encoding::encode_hex(&crate::nizk_proofs::proof_bytes(&instance, true)),
+            proof.to_hex(),
+            short_proof.to_hex()
+        );
+        assert_eq!(json, expected);
+
+        let (decoded_instance, decoded, decoded_short): (SchnorrInstance<G>, Proof, Short) =
+            serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded_instance, instance);
+        assert_eq!(decoded, proof);
+        assert_eq!(decoded_short, short_proof);
+
+        let nizk = Nizk::new(&decoded_instance, b"ctx");
+        assert!(nizk.batchable_verify(&decoded, Some(b"message")).is_ok());
+        assert!(nizk.short_verify(&decoded_short, Some(b"message")).is_ok());
+    }
+
+    #[test]
+    fn test_bincode_round_trip() {
+        let (instance, proof, short_proof) = setup();
+
+        let bytes = bincode::serialize(&(&instance, &proof, &short_proof)).unwrap();
+        // Each encoding is a byte string, prefixed with its length
+        assert_eq!(
+            bytes.len(),
+            3 * 8
+                + crate::nizk_proofs::proof_bytes(&instance, true).len()
+                + proof.to_bytes_compressed().len()
+                + short_proof.to_bytes_compressed().len()
+        );
+
+        let (decoded_instance, decoded, decoded_short): (SchnorrInstance<G>, Proof, Short) =
+            bincode::deserialize(&bytes).unwrap();
+        assert_eq!(decoded_instance, instance);
+        assert_eq!(decoded, proof);
+        assert_eq!(decoded_short, short_proof);
+    }
+
+    #[test]
+    fn test_tampered_json_is_rejected() {
+        let (instance, proof, _) = setup();
+        let nizk = Nizk::new(&instance, b"ctx");
+        let json = serde_json::to_value(&proof).unwrap();
+        let hex = json.as_str().unwrap();
+
+        for position in 0..hex.len() {
+            let mut tampered = hex.as_bytes().to_vec();
+            tampered[position] = if tampered[position] == b'0' {
+                b'1'
+            } else {
+                b'0'
+            };
+            let tampered = Value::String(String::from_utf8(tampered).unwrap());
+
+            // Either the encoding no longer decodes, or the decoded proof fails verification
+            if let Ok(decoded) = serde_json::from_value::<Proof>(tampered) {
+                assert!(nizk.batchable_verify(&decoded, Some(b"message")).is_err());
+            }
+        }
+
+        for tampered in [
+            Value::String(hex[1..].into()),
+            Value::String(format!("{}00", hex)),
+            Value::String(format!("0x{}", hex)),
+            Value::from(7),
+            Value::Null,
+        ] {
+            assert!(serde_json::from_value::<Proof>(tampered).is_err());
+        }
+    }
+
+    #[test]
+    fn test_tampered_instance_is_rejected() {
+        let (instance, proof, _) = setup();
+        let json = serde_json::to_value(instance).unwrap();
+        let hex = json.as_str().unwrap();
+
+        for position in 0..hex.len() {
+            let mut tampered = hex.as_bytes().to_vec();
+            tampered[position] = if tampered[position] == b'0' {
+                b'1'
+            } else {
+                b'0'
+            };
+            let tampered = Value::String(String::from_utf8(tampered).unwrap());
+
+            if let Ok(decoded) = serde_json::from_value::<SchnorrInstance<G>>(tampered) {
+                let nizk = Nizk::new(&decoded, b"ctx");
+                assert!(nizk.batchable_verify(&proof, Some(b"message")).is_err());
+            }
+        }
+    }
+}
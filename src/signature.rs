@@ -0,0 +1,227 @@
+use std::fmt;
+
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Read, SerializationError, Write};
+use rand::Rng;
+
+use crate::{
+    protocols::{SchnorrDLOG, SchnorrInstance},
+    secret::Redacted,
+    ShortProof, SigmaError, SigmaGroup, NIZK,
+};
+
+/// The hash of the signatures
+type Hash = blake2::Blake2s;
+
+/// The context of the NIZK behind the signatures, which separates them from other Schnorr proofs
+const CTX: &[u8] = b"zkpstd/sigma/schnorr-signature";
+
+/// A Schnorr signing key: a secret scalar for the standard generator of the group.
+///
+/// Signing builds a [`NIZK`] for [`SchnorrDLOG`] over the verifying key and always binds the message, so that a
+/// signature cannot be produced as a proof bound to nothing. Signatures use Blake2s. The key is encoded as the
+/// secret scalar alone, and the verifying key is recomputed when it is decoded. Its debug output redacts the secret,
+/// which is wiped on drop with the `zeroize` feature
+#[derive(Clone)]
+pub struct SigningKey<G: SigmaGroup> {
+    secret: G::Scalar,
+    verifying_key: VerifyingKey<G>,
+}
+
+/// A Schnorr verifying key: the public key `secret * G` for the standard generator `G` of the group
+#[derive(Debug, Clone, Copy, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct VerifyingKey<G: SigmaGroup> {
+    public_key: G,
+}
+
+/// A Schnorr signature, the short proof of knowledge of the signing key bound to the message
+#[derive(Debug, Clone, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct Signature<G: SigmaGroup> {
+    proof: ShortProof<SchnorrDLOG<G>>,
+}
+
+impl<G: SigmaGroup> SigningKey<G> {
+    /// Sample a fresh signing key
+    pub fn generate<R: Rng>(rng: &mut R) -> Self {
+        Self::from_secret(G::random_scalar(rng))
+    }
+
+    fn from_secret(secret: G::Scalar) -> Self {
+        Self {
+            secret,
+            verifying_key: VerifyingKey {
+                public_key: G::generator().scalar_mul(&secret),
+            },
+        }
+    }
+
+    /// The verifying key of this signing key
+    pub fn verifying_key(&self) -> &VerifyingKey<G> {
+        &self.verifying_key
+    }
+
+    /// Sign `message`
+    pub fn sign<R: Rng>(&self, message: &[u8], rng: &mut R) -> Result<Signature<G>, SigmaError> {
+        let proof = self
+            .verifying_key
+            .nizk()?
+            .short_proof(&self.secret, Some(message), rng)?;
+
+        Ok(Signature { proof })
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl<G: SigmaGroup> zeroize::Zeroize for SigningKey<G> {
+    fn zeroize(&mut self) {
+        self.secret.zeroize();
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl<G: SigmaGroup> Drop for SigningKey<G> {
+    fn drop(&mut self) {
+        zeroize::Zeroize::zeroize(self);
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl<G: SigmaGroup> zeroize::ZeroizeOnDrop for SigningKey<G> {}
+
+impl<G: SigmaGroup> fmt::Debug for SigningKey<G> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SigningKey")
+            .field("secret", &Redacted)
+            .field("verifying_key", &self.verifying_key)
+            .finish()
+    }
+}
+
+impl<G: SigmaGroup> CanonicalSerialize for SigningKey<G> {
+    fn serialize<W: Write>(&self, writer: W) -> Result<(), SerializationError> {
+        self.secret.serialize(writer)
+    }
+
+    fn serialized_size(&self) -> usize {
+        self.secret.serialized_size()
+    }
+}
+
+impl<G: SigmaGroup> CanonicalDeserialize for SigningKey<G> {
+    fn deserialize<R: Read>(reader: R) -> Result<Self, SerializationError> {
+        G::Scalar::deserialize(reader).map(Self::from_secret)
+    }
+}
+
+impl<G: SigmaGroup> VerifyingKey<G> {
+    /// The public key, as a multiple of the standard generator of the group
+    pub fn public_key(&self) -> G {
+        self.public_key
+    }
+
+    /// Returns [`SigmaError::InvalidInstance`] for a public key outside the prime-order subgroup
+    fn nizk(&self) -> Result<NIZK<SchnorrDLOG<G>, Hash>, SigmaError> {
+        NIZK::try_new(
+            &SchnorrInstance::try_new(G::generator(), self.public_key)?,
+            CTX,
+        )
+    }
+
+    /// Verify that `signature` was produced on `message` by the signing key of this verifying key
+    pub fn verify(&self, message: &[u8], signature: &Signature<G>) -> Result<(), SigmaError> {
+        self.nizk()?.short_verify(&signature.proof, Some(message))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+    use rand::thread_rng;
+
+    use crate::{secret::assert_redacted, SigmaError};
+
+    use super::{Signature, SigningKey, VerifyingKey};
+
+    type G = ark_bls12_377::G1Projective;
+
+    fn serialize<T: CanonicalSerialize>(value: &T) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        value.serialize(&mut bytes).unwrap();
+        bytes
+    }
+
+    #[test]
+    fn test_sign_and_verify() {
+        let rng = &mut thread_rng();
+        let signing_key = SigningKey::<G>::generate(rng);
+        let signature = signing_key.sign(b"message", rng).unwrap();
+
+        assert!(signing_key
+            .verifying_key()
+            .verify(b"message", &signature)
+            .is_ok());
+
+        // Empty messages are bound as well
+        let empty = signing_key.sign(b"", rng).unwrap();
+        assert!(signing_key.verifying_key().verify(b"", &empty).is_ok());
+    }
+
+    #[test]
+    fn test_reject_wrong_message() {
+        let rng = &mut thread_rng();
+        let signing_key = SigningKey::<G>::generate(rng);
+        let signature = signing_key.sign(b"message", rng).unwrap();
+
+        for message in [&b"another message"[..], b""] {
+            assert_eq!(
+                signing_key.verifying_key().verify(message, &signature),
+                Err(SigmaError::VerificationFailed)
+            );
+        }
+    }
+
+    #[test]
+    fn test_reject_wrong_key() {
+        let rng = &mut thread_rng();
+        let signing_key = SigningKey::<G>::generate(rng);
+        let other_key = SigningKey::<G>::generate(rng);
+        let signature = signing_key.sign(b"message", rng).unwrap();
+
+        assert_eq!(
+            other_key.verifying_key().verify(b"message", &signature),
+            Err(SigmaError::VerificationFailed)
+        );
+    }
+
+    #[test]
+    fn test_signing_key_debug_is_redacted() {
+        let signing_key = SigningKey::<G>::generate(&mut thread_rng());
+
+        assert_redacted(
+            &signing_key,
+            &signing_key.secret,
+            &format!(
+                "SigningKey {{ secret: <redacted>, verifying_key: {:?} }}",
+                signing_key.verifying_key()
+            ),
+        );
+    }
+
+    #[test]
+    fn test_keys_and_signatures_round_trip() {
+        let rng = &mut thread_rng();
+        let signing_key = SigningKey::<G>::generate(rng);
+        let signature = signing_key.sign(b"message", rng).unwrap();
+
+        let key_bytes = serialize(&signing_key);
+        let verifying_bytes = serialize(signing_key.verifying_key());
+        let signature_bytes = serialize(&signature);
+
+        let decoded_key = SigningKey::<G>::deserialize(&key_bytes[..]).unwrap();
+        let verifying_key = VerifyingKey::<G>::deserialize(&verifying_bytes[..]).unwrap();
+        let decoded_signature = Signature::<G>::deserialize(&signature_bytes[..]).unwrap();
+
+        assert_eq!(decoded_key.verifying_key(), &verifying_key);
+        assert_eq!(decoded_signature, signature);
+        assert!(verifying_key.verify(b"message", &decoded_signature).is_ok());
+    }
+}
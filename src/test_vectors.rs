@@ -0,0 +1,840 @@
+use std::fmt;
+
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use rand::{rngs::StdRng, SeedableRng};
+
+use crate::{
+    encoding::{decode_hex, encode_hex},
+    interactive_proofs::canonical_bytes,
+    BatchableProof, Challenge, SigmaError, SigmaProtocol, Transcript, NIZK,
+};
+
+/// The version of the schema written by [`TestVectors::to_json`], the only one read by [`TestVectors::from_json`]
+pub const SCHEMA_VERSION: u64 = 1;
+
+/// The deepest nesting of arrays and objects the parser accepts, far above the three levels of the schema
+const MAX_DEPTH: usize = 32;
+
+/// A failure to load or replay test vectors
+#[derive(Debug, PartialEq)]
+pub enum VectorError {
+    /// The input is not JSON, as at this byte offset
+    Json(usize),
+    /// A field is missing, of the wrong type, not lowercase or uppercase hex, or of the wrong length. A version
+    /// other than [`SCHEMA_VERSION`] is reported as such a field
+    Field(&'static str),
+    /// The vectors are for another protocol than the one they are replayed against
+    Protocol,
+    /// The vector at `index` does not decode or does not verify
+    Sigma {
+        /// The position of the vector in the file
+        index: usize,
+        /// The error of decoding or verification
+        error: SigmaError,
+    },
+    /// A field of the vector at `index` differs from the value recomputed from the other fields
+    Mismatch {
+        /// The position of the vector in the file
+        index: usize,
+        /// The name of the field in the schema
+        field: &'static str,
+    },
+}
+
+impl fmt::Display for VectorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Json(offset) => write!(f, "invalid JSON at byte {}", offset),
+            Self::Field(field) => write!(f, "missing or invalid field `{}`", field),
+            Self::Protocol => f.write_str("test vectors for another protocol"),
+            Self::Sigma { index, error } => write!(f, "vector {}: {}", index, error),
+            Self::Mismatch { index, field } => {
+                write!(f, "vector {}: `{}` does not match", index, field)
+            }
+        }
+    }
+}
+
+/// A file of NIZK test vectors for one protocol, group and hash, in the JSON schema of the working group:
+///
+/// ```text
+/// {
+///   "version": 1,
+///   "protocol": the protocol identifier, such as "zkpstd/sigma/dlog",
+///   "group": the name of the group, such as "bls12-377",
+///   "hash": the name of the hash of the transcript, such as "blake2s",
+///   "vectors": [
+///     {
+///       "instance": the compressed canonical encoding of the instance,
+///       "ctx": the context of the NIZK,
+///       "message": the message the proof is bound to, or null for no message,
+///       "witness": the compressed canonical encoding of the witness, or null,
+///       "nonce_seed": the 32-byte seed of the rng the prover drew from, or null,
+///       "commitment": the compressed canonical encoding of the commitment,
+///       "challenge": the 32-byte Fiat-Shamir challenge,
+///       "response": the compressed canonical encoding of the response,
+///       "proof": the compressed encoding of the batchable proof, the commitment followed by the response
+///     }
+///   ]
+/// }
+/// ```
+///
+/// Byte strings are lowercase hex. Unknown fields are ignored, so that other implementations may annotate their
+/// vectors. The nonce seed seeds the [`StdRng`] this crate hedges its nonces with, so only this crate can replay
+/// the proving of a vector, while any implementation can check its verification
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TestVectors {
+    /// The protocol identifier, [`SigmaProtocol::PROTOCOL_ID`] in this crate
+    pub protocol: String,
+    /// The name of the group
+    pub group: String,
+    /// The name of the hash of the transcript
+    pub hash: String,
+    /// The vectors
+    pub vectors: Vec<TestVector>,
+}
+
+/// A full transcript of a batchable NIZK proof, see [`TestVectors`] for the meaning of the fields
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TestVector {
+    /// The compressed canonical encoding of the instance
+    pub instance: Vec<u8>,
+    /// The context of the NIZK
+    pub ctx: Vec<u8>,
+    /// The message the proof is bound to
+    pub message: Option<Vec<u8>>,
+    /// The compressed canonical encoding of the witness, for replaying the proving
+    pub witness: Option<Vec<u8>>,
+    /// The seed of the rng of the prover, for replaying the proving
+    pub nonce_seed: Option<[u8; 32]>,
+    /// The compressed canonical encoding of the commitment
+    pub commitment: Vec<u8>,
+    /// The Fiat-Shamir challenge
+    pub challenge: Challenge,
+    /// The compressed canonical encoding of the response
+    pub response: Vec<u8>,
+    /// The compressed encoding of the batchable proof
+    pub proof: Vec<u8>,
+}
+
+impl TestVectors {
+    /// An empty file of vectors for `S` over the named group and hash
+    pub fn new<S: SigmaProtocol>(group: &str, hash: &str) -> Self {
+        Self {
+            protocol: String::from_utf8_lossy(S::PROTOCOL_ID).into_owned(),
+            group: group.into(),
+            hash: hash.into(),
+            vectors: Vec::new(),
+        }
+    }
+
+    /// Parses vectors in the schema of [`TestVectors`]
+    pub fn from_json(json: &str) -> Result<Self, VectorError> {
+        let root = Parser::parse(json)?;
+        let root = root.as_object().ok_or(VectorError::Field("version"))?;
+        match field(root, "version")? {
+            Json::Number(version) if *version == SCHEMA_VERSION.to_string() => {}
+            _ => return Err(VectorError::Field("version")),
+        }
+
+        let vectors = match field(root, "vectors")? {
+            Json::Array(vectors) => vectors
+                .iter()
+                .map(TestVector::from_json)
+                .collect::<Result<_, _>>()?,
+            _ => return Err(VectorError::Field("vectors")),
+        };
+
+        Ok(Self {
+            protocol: string_field(root, "protocol")?.into(),
+            group: string_field(root, "group")?.into(),
+            hash: string_field(root, "hash")?.into(),
+            vectors,
+        })
+    }
+
+    /// The vectors in the schema of [`TestVectors`], indented by two spaces and ending with a newline
+    pub fn to_json(&self) -> String {
+        let root = Json::Object(vec![
+            ("version".into(), Json::Number(SCHEMA_VERSION.to_string())),
+            ("protocol".into(), Json::String(self.protocol.clone())),
+            ("group".into(), Json::String(self.group.clone())),
+            ("hash".into(), Json::String(self.hash.clone())),
+            (
+                "vectors".into(),
+                Json::Array(self.vectors.iter().map(TestVector::to_json).collect()),
+            ),
+        ]);
+
+        let mut json = String::new();
+        root.write(&mut json, 0);
+        json.push('\n');
+        json
+    }
+
+    /// Replays every vector against a [`NIZK`] for `S` over the transcript `D`, and reports the first that fails.
+    /// The instance, commitment, response and proof must decode to exactly their encodings, the proof must be the
+    /// commitment followed by the response, the challenge must be the one of the proof, and the proof must
+    /// verify. With a witness and a nonce seed, proving again must give the same proof
+    pub fn replay<S, D>(&self) -> Result<(), VectorError>
+    where
+        S: SigmaProtocol,
+        S::Instance: CanonicalDeserialize,
+        S::Witness: CanonicalDeserialize,
+        D: Transcript + Default,
+    {
+        if self.protocol.as_bytes() != S::PROTOCOL_ID {
+            return Err(VectorError::Protocol);
+        }
+
+        self.vectors
+            .iter()
+            .enumerate()
+            .try_for_each(|(index, vector)| vector.replay::<S, D>(index))
+    }
+}
+
+impl TestVector {
+    /// Proves `witness` for `instance` with a [`NIZK`] over the transcript `D`, drawing the randomness of the
+    /// prover from a [`StdRng`] seeded with `nonce_seed`, and records the whole transcript
+    pub fn generate<S, D>(
+        instance: &S::Instance,
+        witness: &S::Witness,
+        ctx: &[u8],
+        message: Option<&[u8]>,
+        nonce_seed: [u8; 32],
+    ) -> Result<Self, SigmaError>
+    where
+        S: SigmaProtocol,
+        S::Instance: CanonicalSerialize,
+        S::Witness: CanonicalSerialize,
+        D: Transcript + Default,
+    {
+        let nizk = NIZK::<S, D>::try_new(instance, ctx)?;
+        let proof = nizk.batchable_proof(witness, message, &mut StdRng::from_seed(nonce_seed))?;
+
+        Ok(Self {
+            instance: canonical_bytes(instance),
+            ctx: ctx.to_vec(),
+            message: message.map(<[u8]>::to_vec),
+            witness: Some(canonical_bytes(witness)),
+            nonce_seed: Some(nonce_seed),
+            commitment: canonical_bytes(proof.commitment()),
+            challenge: nizk.batchable_challenge(&proof, message)?,
+            response: canonical_bytes(proof.response()),
+            proof: proof.to_bytes_compressed(),
+        })
+    }
+
+    /// The vector without its witness and nonce seed, which only checks verification
+    pub fn without_witness(self) -> Self {
+        Self {
+            witness: None,
+            nonce_seed: None,
+            ..self
+        }
+    }
+
+    /// Checks that the fields of the vector at `index` agree, see [`TestVectors::replay`]
+    fn replay<S, D>(&self, index: usize) -> Result<(), VectorError>
+    where
+        S: SigmaProtocol,
+        S::Instance: CanonicalDeserialize,
+        S::Witness: CanonicalDeserialize,
+        D: Transcript + Default,
+    {
+        let sigma = |error: SigmaError| VectorError::Sigma { index, error };
+        let check = |matches: bool, field| match matches {
+            true => Ok(()),
+            false => Err(VectorError::Mismatch { index, field }),
+        };
+        let message = self.message.as_deref();
+
+        let instance =
+            S::Instance::deserialize(&self.instance[..]).map_err(|error| sigma(error.into()))?;
+        let nizk = NIZK::<S, D>::try_new(&instance, &self.ctx).map_err(sigma)?;
+        let proof = BatchableProof::<S>::from_bytes_compressed(&self.proof).map_err(sigma)?;
+
+        check(
+            canonical_bytes(proof.commitment()) == self.commitment,
+            "commitment",
+        )?;
+        check(
+            canonical_bytes(proof.response()) == self.response,
+            "response",
+        )?;
+        check(proof.to_bytes_compressed() == self.proof, "proof")?;
+        check(
+            nizk.batchable_challenge(&proof, message).map_err(sigma)? == self.challenge,
+            "challenge",
+        )?;
+        nizk.batchable_verify(&proof, message).map_err(sigma)?;
+
+        if let (Some(witness), Some(nonce_seed)) = (&self.witness, self.nonce_seed) {
+            let witness =
+                S::Witness::deserialize(&witness[..]).map_err(|error| sigma(error.into()))?;
+            let proved = nizk
+                .batchable_proof(&witness, message, &mut StdRng::from_seed(nonce_seed))
+                .map_err(sigma)?;
+            check(proved.to_bytes_compressed() == self.proof, "proof")?;
+        }
+        Ok(())
+    }
+
+    fn from_json(json: &Json) -> Result<Self, VectorError> {
+        let vector = json.as_object().ok_or(VectorError::Field("vectors"))?;
+
+        Ok(Self {
+            instance: bytes_field(vector, "instance")?,
+            ctx: bytes_field(vector, "ctx")?,
+            message: optional_bytes_field(vector, "message")?,
+            witness: optional_bytes_field(vector, "witness")?,
+            nonce_seed: optional_bytes_field(vector, "nonce_seed")?
+                .map(|seed| {
+                    seed.try_into()
+                        .map_err(|_| VectorError::Field("nonce_seed"))
+                })
+                .transpose()?,
+            commitment: bytes_field(vector, "commitment")?,
+            challenge: bytes_field(vector, "challenge")?
+                .try_into()
+                .map_err(|_| VectorError::Field("challenge"))?,
+            response: bytes_field(vector, "response")?,
+            proof: bytes_field(vector, "proof")?,
+        })
+    }
+
+    fn to_json(&self) -> Json {
+        let hex = |bytes: &[u8]| Json::String(encode_hex(bytes));
+        let optional = |bytes: Option<&[u8]>| bytes.map_or(Json::Null, hex);
+
+        Json::Object(vec![
+            ("instance".into(), hex(&self.instance)),
+            ("ctx".into(), hex(&self.ctx)),
+            ("message".into(), optional(self.message.as_deref())),
+            ("witness".into(), optional(self.witness.as_deref())),
+            (
+                "nonce_seed".into(),
+                optional(self.nonce_seed.as_ref().map(|seed| &seed[..])),
+            ),
+            ("commitment".into(), hex(&self.commitment)),
+            ("challenge".into(), hex(&self.challenge)),
+            ("response".into(), hex(&self.response)),
+            ("proof".into(), hex(&self.proof)),
+        ])
+    }
+}
+
+fn field<'a>(object: &'a [(String, Json)], name: &'static str) -> Result<&'a Json, VectorError> {
+    object
+        .iter()
+        .find(|(key, _)| key == name)
+        .map(|(_, value)| value)
+        .ok_or(VectorError::Field(name))
+}
+
+fn string_field<'a>(
+    object: &'a [(String, Json)],
+    name: &'static str,
+) -> Result<&'a str, VectorError> {
+    match field(object, name)? {
+        Json::String(string) => Ok(string),
+        _ => Err(VectorError::Field(name)),
+    }
+}
+
+fn bytes_field(object: &[(String, Json)], name: &'static str) -> Result<Vec<u8>, VectorError> {
+    decode_hex(string_field(object, name)?).map_err(|_| VectorError::Field(name))
+}
+
+/// A byte string that may be null or absent
+fn optional_bytes_field(
+    object: &[(String, Json)],
+    name: &'static str,
+) -> Result<Option<Vec<u8>>, VectorError> {
+    match field(object, name) {
+        Err(_) | Ok(Json::Null) => Ok(None),
+        Ok(_) => bytes_field(object, name).map(Some),
+    }
+}
+
+/// A JSON value. Numbers keep their text, as the schema only has the version, and objects keep the order of their
+/// keys, so that a file writes back as it was read
+#[derive(Debug, Clone, PartialEq)]
+enum Json {
+    Null,
+    Bool(bool),
+    Number(String),
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>),
+}
+
+impl Json {
+    fn as_object(&self) -> Option<&[(String, Json)]> {
+        match self {
+            Self::Object(fields) => Some(fields),
+            _ => None,
+        }
+    }
+
+    /// Writes the value with each element of arrays and objects on its own line, indented by two spaces per level
+    fn write(&self, out: &mut String, depth: usize) {
+        let indent = |out: &mut String, depth: usize| out.extend((0..2 * depth).map(|_| ' '));
+        match self {
+            Self::Null => out.push_str("null"),
+            Self::Bool(value) => out.push_str(if *value { "true" } else { "false" }),
+            Self::Number(number) => out.push_str(number),
+            Self::String(string) => write_string(string, out),
+            Self::Array(values) if values.is_empty() => out.push_str("[]"),
+            Self::Object(fields) if fields.is_empty() => out.push_str("{}"),
+            Self::Array(values) => {
+                out.push_str("[\n");
+                for (i, value) in values.iter().enumerate() {
+                    indent(out, depth + 1);
+                    value.write(out, depth + 1);
+                    out.push_str(if i + 1 < values.len() { ",\n" } else { "\n" });
+                }
+                indent(out, depth);
+                out.push(']');
+            }
+            Self::Object(fields) => {
+                out.push_str("{\n");
+                for (i, (key, value)) in fields.iter().enumerate() {
+                    indent(out, depth + 1);
+                    write_string(key, out);
+                    out.push_str(": ");
+                    value.write(out, depth + 1);
+                    out.push_str(if i + 1 < fields.len() { ",\n" } else { "\n" });
+                }
+                indent(out, depth);
+                out.push('}');
+            }
+        }
+    }
+}
+
+fn write_string(string: &str, out: &mut String) {
+    out.push('"');
+    for c in string.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if u32::from(c) < 0x20 => out.push_str(&format!("\\u{:04x}", u32::from(c))),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+/// A parser of RFC 8259 JSON, rejecting duplicate keys and anything after the value
+struct Parser<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn parse(json: &'a str) -> Result<Json, VectorError> {
+        let mut parser = Self {
+            bytes: json.as_bytes(),
+            position: 0,
+        };
+        let value = parser.value(0)?;
+        parser.skip_whitespace();
+        match parser.peek() {
+            None => Ok(value),
+            Some(_) => Err(parser.error()),
+        }
+    }
+
+    fn error(&self) -> VectorError {
+        VectorError::Json(self.position)
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.bytes.get(self.position).copied()
+    }
+
+    fn next(&mut self) -> Result<u8, VectorError> {
+        let byte = self.peek().ok_or_else(|| self.error())?;
+        self.position += 1;
+        Ok(byte)
+    }
+
+    fn expect(&mut self, byte: u8) -> Result<(), VectorError> {
+        match self.peek() {
+            Some(next) if next == byte => {
+                self.position += 1;
+                Ok(())
+            }
+            _ => Err(self.error()),
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while matches!(self.peek(), Some(b' ' | b'\t' | b'\n' | b'\r')) {
+            self.position += 1;
+        }
+    }
+
+    fn value(&mut self, depth: usize) -> Result<Json, VectorError> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some(b'{' | b'[') if depth == MAX_DEPTH => Err(self.error()),
+            Some(b'{') => self.object(depth),
+            Some(b'[') => self.array(depth),
+            Some(b'"') => self.string().map(Json::String),
+            Some(b'-' | b'0'..=b'9') => self.number(),
+            Some(b't') => self.literal("true", Json::Bool(true)),
+            Some(b'f') => self.literal("false", Json::Bool(false)),
+            Some(b'n') => self.literal("null", Json::Null),
+            _ => Err(self.error()),
+        }
+    }
+
+    fn literal(&mut self, literal: &str, value: Json) -> Result<Json, VectorError> {
+        if !self.bytes[self.position..].starts_with(literal.as_bytes()) {
+            return Err(self.error());
+        }
+        self.position += literal.len();
+        Ok(value)
+    }
+
+    fn object(&mut self, depth: usize) -> Result<Json, VectorError> {
+        self.expect(b'{')?;
+        let mut fields: Vec<(String, Json)> = Vec::new();
+        self.skip_whitespace();
+        if self.peek() == Some(b'}') {
+            self.position += 1;
+            return Ok(Json::Object(fields));
+        }
+
+        loop {
+            self.skip_whitespace();
+            let start = self.position;
+            let key = self.string()?;
+            if fields.iter().any(|(other, _)| *other == key) {
+                return Err(VectorError::Json(start));
+            }
+            self.skip_whitespace();
+            self.expect(b':')?;
+            fields.push((key, self.value(depth + 1)?));
+
+            self.skip_whitespace();
+            match self.next()? {
+                b',' => {}
+                b'}' => return Ok(Json::Object(fields)),
+                _ => return Err(VectorError::Json(self.position - 1)),
+            }
+        }
+    }
+
+    fn array(&mut self, depth: usize) -> Result<Json, VectorError> {
+        self.expect(b'[')?;
+        let mut values = Vec::new();
+        self.skip_whitespace();
+        if self.peek() == Some(b']') {
+            self.position += 1;
+            return Ok(Json::Array(values));
+        }
+
+        loop {
+            values.push(self.value(depth + 1)?);
+            self.skip_whitespace();
+            match self.next()? {
+                b',' => {}
+                b']' => return Ok(Json::Array(values)),
+                _ => return Err(VectorError::Json(self.position - 1)),
+            }
+        }
+    }
+
+    fn string(&mut self) -> Result<String, VectorError> {
+        self.expect(b'"')?;
+        let mut bytes = Vec::new();
+        loop {
+            match self.next()? {
+                b'"' => break,
+                b'\\' => {
+                    let escaped = match self.next()? {
+                        b'"' => '"',
+                        b'\\' => '\\',
+                        b'/' => '/',
+                        b'b' => '\u{8}',
+                        b'f' => '\u{c}',
+                        b'n' => '\n',
+                        b'r' => '\r',
+                        b't' => '\t',
+                        b'u' => self.unicode_escape()?,
+                        _ => return Err(VectorError::Json(self.position - 1)),
+                    };
+                    bytes.extend(escaped.encode_utf8(&mut [0; 4]).as_bytes());
+                }
+                byte if byte < 0x20 => return Err(VectorError::Json(self.position - 1)),
+                byte => bytes.push(byte),
+            }
+        }
+
+        // The input is a `str` and escapes are whole characters, so the bytes are UTF-8
+        String::from_utf8(bytes).map_err(|_| self.error())
+    }
+
+    /// The character of a `\u` escape, whose `\u` is already read, combining surrogate pairs
+    fn unicode_escape(&mut self) -> Result<char, VectorError> {
+        let start = self.position;
+        let high = self.hex4()?;
+        let code = match high {
+            0xd800..=0xdbff => {
+                self.expect(b'\\')?;
+                self.expect(b'u')?;
+                match self.hex4()? {
+                    low @ 0xdc00..=0xdfff => 0x10000 + ((high - 0xd800) << 10) + (low - 0xdc00),
+                    _ => return Err(VectorError::Json(start)),
+                }
+            }
+            code => code,
+        };
+        char::from_u32(code).ok_or(VectorError::Json(start))
+    }
+
+    fn hex4(&mut self) -> Result<u32, VectorError> {
+        (0..4).try_fold(0, |code, _| {
+            let digit = char::from(self.next()?)
+                .to_digit(16)
+                .ok_or(VectorError::Json(self.position - 1))?;
+            Ok(code << 4 | digit)
+        })
+    }
+
+    fn digits(&mut self) -> usize {
+        let start = self.position;
+        while matches!(self.peek(), Some(b'0'..=b'9')) {
+            self.position += 1;
+        }
+        self.position - start
+    }
+
+    fn number(&mut self) -> Result<Json, VectorError> {
+        let start = self.position;
+        if self.peek() == Some(b'-') {
+            self.position += 1;
+        }
+        match self.peek() {
+            Some(b'0') => self.position += 1,
+            Some(b'1'..=b'9') => {
+                self.digits();
+            }
+            _ => return Err(self.error()),
+        }
+        if self.peek() == Some(b'.') {
+            self.position += 1;
+            if self.digits() == 0 {
+                return Err(self.error());
+            }
+        }
+        if matches!(self.peek(), Some(b'e' | b'E')) {
+            self.position += 1;
+            if matches!(self.peek(), Some(b'+' | b'-')) {
+                self.position += 1;
+            }
+            if self.digits() == 0 {
+                return Err(self.error());
+            }
+        }
+
+        let number = &self.bytes[start..self.position];
+        Ok(Json::Number(String::from_utf8_lossy(number).into_owned()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_ec::ProjectiveCurve;
+    use ark_ff::PrimeField;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    use crate::{
+        protocols::{SchnorrDLOG, SchnorrInstance},
+        SigmaError,
+    };
+
+    use super::{Json, Parser, TestVector, TestVectors, VectorError};
+
+    type G = ark_bls12_377::G1Projective;
+    type F = ark_bls12_377::Fr;
+    type Schnorr = SchnorrDLOG<G>;
+
+    /// The Schnorr DLOG vectors over BLS12-377 G1 with Blake2s
+    const SCHNORR_BLS12_377: &str = include_str!("../tests/vectors/schnorr_bls12_377.json");
+
+    /// Vectors with and without a message, with an empty message and without a witness, from a seeded rng
+    fn generate_vectors(seed: u64) -> TestVectors {
+        let rng = &mut StdRng::seed_from_u64(seed);
+        let generator = G::prime_subgroup_generator();
+        let mut vectors = TestVectors::new::<Schnorr>("bls12-377", "blake2s");
+
+        let messages: [Option<&[u8]>; 4] = [Some(b"message"), None, Some(b""), Some(b"message")];
+        for (i, message) in messages.into_iter().enumerate() {
+            let witness: F = rng.gen();
+            let base = generator.mul(F::from(i as u64 + 1).into_repr());
+            let instance = SchnorrInstance::new(base, base.mul(witness.into_repr()));
+            let vector = TestVector::generate::<Schnorr, blake2::Blake2s>(
+                &instance,
+                &witness,
+                b"test vectors",
+                message,
+                rng.gen(),
+            )
+            .unwrap();
+            vectors.vectors.push(match i {
+                3 => vector.without_witness(),
+                _ => vector,
+            });
+        }
+        vectors
+    }
+
+    #[test]
+    fn test_schnorr_bls12_377_vectors() {
+        let vectors = TestVectors::from_json(SCHNORR_BLS12_377).unwrap();
+        assert_eq!(vectors.vectors.len(), 4);
+        assert_eq!(vectors.group, "bls12-377");
+        assert_eq!(vectors.hash, "blake2s");
+        assert_eq!(vectors.replay::<Schnorr, blake2::Blake2s>(), Ok(()));
+
+        // The file is exactly what the generator and the writer produce
+        assert_eq!(vectors, generate_vectors(0x5eed));
+        assert_eq!(vectors.to_json(), SCHNORR_BLS12_377);
+    }
+
+    #[test]
+    fn test_every_field_is_checked() {
+        let vectors = TestVectors::from_json(SCHNORR_BLS12_377).unwrap();
+        let replay = |change: &dyn Fn(&mut TestVector)| {
+            let mut changed = vectors.clone();
+            change(&mut changed.vectors[0]);
+            changed.replay::<Schnorr, blake2::Blake2s>()
+        };
+        let mismatch = |field| Err(VectorError::Mismatch { index: 0, field });
+        let failed = Err(VectorError::Sigma {
+            index: 0,
+            error: SigmaError::VerificationFailed,
+        });
+
+        assert_eq!(
+            replay(&|vector| vector.challenge[0] ^= 1),
+            mismatch("challenge")
+        );
+        assert_eq!(
+            replay(&|vector| vector.commitment[0] ^= 1),
+            mismatch("commitment")
+        );
+        assert_eq!(
+            replay(&|vector| vector.response[31] ^= 1),
+            mismatch("response")
+        );
+        assert_eq!(replay(&|vector| vector.ctx.push(0)), mismatch("challenge"));
+        assert_eq!(
+            replay(&|vector| vector.message = None),
+            mismatch("challenge")
+        );
+        assert_eq!(
+            replay(&|vector| vector.nonce_seed.as_mut().unwrap()[0] ^= 1),
+            mismatch("proof")
+        );
+        assert_eq!(
+            replay(&|vector| vector.witness.as_mut().unwrap()[0] ^= 1),
+            mismatch("proof")
+        );
+        assert!(replay(&|vector| vector.instance[0] ^= 1).is_err());
+        assert!(replay(&|vector| vector.proof[0] ^= 1).is_err());
+
+        // A consistent transcript that does not verify, with the response of another proof
+        assert_eq!(
+            replay(&|vector| {
+                let other = &vectors.vectors[1];
+                vector.response = other.response.clone();
+                vector.proof = [&vector.commitment[..], &other.response].concat();
+            }),
+            failed
+        );
+
+        let mut other_protocol = vectors.clone();
+        other_protocol.protocol = "zkpstd/sigma/dleq".into();
+        assert_eq!(
+            other_protocol.replay::<Schnorr, blake2::Blake2s>(),
+            Err(VectorError::Protocol)
+        );
+    }
+
+    #[test]
+    fn test_loader_rejects_invalid_files() {
+        let valid = SCHNORR_BLS12_377;
+        for (from, to, field) in [
+            ("\"version\": 1", "\"version\": 2", "version"),
+            ("\"version\": 1", "\"version\": \"1\"", "version"),
+            ("\"hash\"", "\"hash function\"", "hash"),
+            ("\"challenge\": \"", "\"challenge\": \"00", "challenge"),
+            ("\"instance\": \"", "\"instance\": \"0", "instance"),
+            ("\"nonce_seed\": \"", "\"nonce_seed\": \"zz", "nonce_seed"),
+        ] {
+            let changed = valid.replacen(from, to, 1);
+            assert_eq!(
+                TestVectors::from_json(&changed),
+                Err(VectorError::Field(field))
+            );
+        }
+
+        for json in [
+            "",
+            "{",
+            "{\"a\": 1,}",
+            "[1 2]",
+            "{\"a\": 1, \"a\": 2}",
+            "01",
+            "1.",
+            "tru",
+        ] {
+            assert!(matches!(Parser::parse(json), Err(VectorError::Json(_))));
+        }
+        assert!(matches!(
+            TestVectors::from_json(&(valid.to_owned() + "{}")),
+            Err(VectorError::Json(_))
+        ));
+        assert!(Parser::parse(&"[".repeat(100)).is_err());
+    }
+
+    #[test]
+    fn test_json_strings_and_numbers() {
+        assert_eq!(
+            Parser::parse(r#"["a\"\\\/\né😀", -1.5e+3, true, null, {}]"#).unwrap(),
+            Json::Array(vec![
+                Json::String("a\"\\/\né😀".into()),
+                Json::Number("-1.5e+3".into()),
+                Json::Bool(true),
+                Json::Null,
+                Json::Object(vec![]),
+            ])
+        );
+        assert!(Parser::parse(r#""\ud83d""#).is_err());
+        assert!(Parser::parse("\"\u{1}\"").is_err());
+
+        let mut json = String::new();
+        Json::String("\"\\\n\u{1}é".into()).write(&mut json, 0);
+        assert_eq!(json, r#""\"\\\n\u0001é""#);
+        assert_eq!(
+            Parser::parse(&json).unwrap(),
+            Json::String("\"\\\n\u{1}é".into())
+        );
+    }
+
+    /// Prints the vector file, run with `cargo test test_vectors -- --ignored --nocapture`
+    #[test]
+    #[ignore]
+    fn generate_schnorr_bls12_377_vectors() {
+        print!("{}", generate_vectors(0x5eed).to_json());
+    }
+}
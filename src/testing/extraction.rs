@@ -0,0 +1,279 @@
+use ark_ec::ProjectiveCurve;
+use ark_ff::PrimeField;
+
+use crate::{
+    composition::AndComposition,
+    protocols::{DleqProof, SchnorrDLOG},
+    SigmaProtocol,
+};
+
+/// A Sigma protocol with an extractor for its special soundness: a witness can be computed from two accepting
+/// transcripts that share their commitment but not their challenge
+pub trait SpecialSoundness: SigmaProtocol {
+    /// The witness behind the accepting transcripts `(commitment, c1, r1)` and `(commitment, c2, r2)`. Returns
+    /// `None` if either transcript is rejected or if the challenges map to the same point of the challenge space
+    fn extract_witness(
+        &self,
+        commitment: &Self::Commitment,
+        first: (&Self::Challenge, &Self::Response),
+        second: (&Self::Challenge, &Self::Response),
+    ) -> Option<Self::Witness>;
+}
+
+/// Whether both transcripts are accepted for the same commitment
+fn accepted<S: SigmaProtocol>(
+    protocol: &S,
+    commitment: &S::Commitment,
+    first: (&S::Challenge, &S::Response),
+    second: (&S::Challenge, &S::Response),
+) -> bool {
+    protocol.verifier(commitment, first.0, first.1).is_ok()
+        && protocol.verifier(commitment, second.0, second.1).is_ok()
+}
+
+/// Solves `r1 - r2 = (c2 - c1) * w` for the responses `r = k - c * w` of the discrete logarithm protocols
+fn extract_scalar<F: PrimeField>(first: (&F, &F), second: (&F, &F)) -> Option<F> {
+    Some((*first.1 - *second.1) * (*second.0 - first.0).inverse()?)
+}
+
+impl<G: ProjectiveCurve> SpecialSoundness for SchnorrDLOG<G> {
+    fn extract_witness(
+        &self,
+        commitment: &G,
+        first: (&G::ScalarField, &G::ScalarField),
+        second: (&G::ScalarField, &G::ScalarField),
+    ) -> Option<G::ScalarField> {
+        if !accepted(self, commitment, first, second) {
+            return None;
+        }
+        extract_scalar(first, second)
+    }
+}
+
+impl<G: ProjectiveCurve> SpecialSoundness for DleqProof<G> {
+    fn extract_witness(
+        &self,
+        commitment: &Self::Commitment,
+        first: (&G::ScalarField, &G::ScalarField),
+        second: (&G::ScalarField, &G::ScalarField),
+    ) -> Option<G::ScalarField> {
+        if !accepted(self, commitment, first, second) {
+            return None;
+        }
+        extract_scalar(first, second)
+    }
+}
+
+/// The witnesses of both statements, extracted from the projections of the transcripts
+impl<S1: SpecialSoundness, S2: SpecialSoundness> SpecialSoundness for AndComposition<S1, S2> {
+    fn extract_witness(
+        &self,
+        commitment: &Self::Commitment,
+        first: (&Self::Challenge, &Self::Response),
+        second: (&Self::Challenge, &Self::Response),
+    ) -> Option<Self::Witness> {
+        let (first_protocol, second_protocol) = self.components();
+        Some((
+            first_protocol.extract_witness(
+                &commitment.0,
+                (&first.0 .0, &first.1 .0),
+                (&second.0 .0, &second.1 .0),
+            )?,
+            second_protocol.extract_witness(
+                &commitment.1,
+                (&first.0 .1, &first.1 .1),
+                (&second.0 .1, &second.1 .1),
+            )?,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_ec::ProjectiveCurve;
+    use ark_ff::{PrimeField, UniformRand};
+    use rand::{thread_rng, Rng};
+
+    use crate::{
+        composition::{
+            AndComposition, Either, OrComposition, OrMany, Threshold, ThresholdInstance,
+        },
+        protocols::{DleqInstance, DleqProof, SchnorrDLOG, SchnorrInstance},
+        ChallengeExpansion, CommitmentRecoverable, SigmaProtocol,
+    };
+
+    use super::SpecialSoundness;
+
+    type G = ark_bls12_377::G1Projective;
+    type F = ark_bls12_377::Fr;
+
+    type SchnorrAnd = AndComposition<SchnorrDLOG<G>, SchnorrDLOG<G>>;
+    type SchnorrOr = OrComposition<SchnorrDLOG<G>, SchnorrDLOG<G>>;
+
+    const TRIALS: usize = 16;
+
+    fn schnorr_setup<R: Rng>(rng: &mut R) -> (SchnorrInstance<G>, F) {
+        let base = G::rand(rng);
+        let witness = F::rand(rng);
+
+        (
+            SchnorrInstance::new(base, base.mul(witness.into_repr())),
+            witness,
+        )
+    }
+
+    fn dleq_setup<R: Rng>(rng: &mut R) -> (DleqInstance<G>, F) {
+        let (base1, base2) = (G::rand(rng), G::rand(rng));
+        let witness = F::rand(rng);
+        let instance = DleqInstance::new(
+            base1,
+            base1.mul(witness.into_repr()),
+            base2,
+            base2.mul(witness.into_repr()),
+        );
+
+        (instance, witness)
+    }
+
+    fn assert_simulations_verify<S: CommitmentRecoverable, R: Rng>(protocol: &S, rng: &mut R) {
+        let expansion = ChallengeExpansion::default();
+        for _ in 0..TRIALS {
+            let (commitment, challenge, response) =
+                protocol.simulate_transcript(expansion, rng).unwrap();
+            let challenge = S::challenge_from_bytes(&challenge, expansion).unwrap();
+            assert!(protocol
+                .verifier(&commitment, &challenge, &response)
+                .is_ok());
+        }
+    }
+
+    /// Runs the honest prover up to its commitment, then answers two distinct random challenges from the same
+    /// prover state and extracts the witness from both transcripts
+    fn rewind_and_extract<S: SpecialSoundness, R: Rng>(
+        protocol: &S,
+        witness: &S::Witness,
+        rng: &mut R,
+    ) -> Option<S::Witness> {
+        let expansion = ChallengeExpansion::default();
+        let (commitment, state) = protocol.prover_commit(witness, expansion, rng).unwrap();
+        let c1 = S::challenge_from_bytes(&rng.gen(), expansion).unwrap();
+        let c2 = S::challenge_from_bytes(&rng.gen(), expansion).unwrap();
+        let r1 = protocol.prover_response(&state, &c1).unwrap();
+        let r2 = protocol.prover_response(&state, &c2).unwrap();
+
+        protocol.extract_witness(&commitment, (&c1, &r1), (&c2, &r2))
+    }
+
+    #[test]
+    fn test_simulated_transcripts_verify() {
+        let rng = &mut thread_rng();
+        let (schnorr, _) = schnorr_setup(rng);
+        let (other, _) = schnorr_setup(rng);
+        let (dleq, _) = dleq_setup(rng);
+
+        assert_simulations_verify(&SchnorrDLOG::new(&schnorr), rng);
+        assert_simulations_verify(&DleqProof::new(&dleq), rng);
+        assert_simulations_verify(&SchnorrAnd::new(&(schnorr, other)), rng);
+        assert_simulations_verify(&SchnorrOr::new(&(schnorr, other)), rng);
+        assert_simulations_verify(
+            &OrComposition::<SchnorrOr, SchnorrDLOG<G>>::new(&((schnorr, other), schnorr)),
+            rng,
+        );
+        assert_simulations_verify(
+            &OrMany::<SchnorrDLOG<G>>::new(&vec![schnorr, other, schnorr]),
+            rng,
+        );
+        let instances = (0..5).map(|_| schnorr_setup(rng).0).collect();
+        assert_simulations_verify(
+            &Threshold::<SchnorrDLOG<G>, F>::new(&ThresholdInstance::new(3, instances)),
+            rng,
+        );
+    }
+
+    #[test]
+    fn test_simulated_transcripts_do_not_need_a_witness() {
+        let rng = &mut thread_rng();
+        // Nobody knows the discrete logarithm of a random claim
+        let instance = SchnorrInstance::new(G::rand(rng), G::rand(rng));
+
+        assert_simulations_verify(&SchnorrDLOG::new(&instance), rng);
+    }
+
+    #[test]
+    fn test_extractor_recovers_witness() {
+        let rng = &mut thread_rng();
+        for _ in 0..TRIALS {
+            let (schnorr, schnorr_witness) = schnorr_setup(rng);
+            let (other, other_witness) = schnorr_setup(rng);
+            let (dleq, dleq_witness) = dleq_setup(rng);
+
+            assert_eq!(
+                rewind_and_extract(&SchnorrDLOG::new(&schnorr), &schnorr_witness, rng),
+                Some(schnorr_witness)
+            );
+            assert_eq!(
+                rewind_and_extract(&DleqProof::new(&dleq), &dleq_witness, rng),
+                Some(dleq_witness)
+            );
+            assert_eq!(
+                rewind_and_extract(
+                    &SchnorrAnd::new(&(schnorr, other)),
+                    &(schnorr_witness, other_witness),
+                    rng
+                ),
+                Some((schnorr_witness, other_witness))
+            );
+        }
+    }
+
+    #[test]
+    fn test_extractor_rejects_unusable_transcripts() {
+        let rng = &mut thread_rng();
+        let (instance, witness) = schnorr_setup(rng);
+        let protocol = SchnorrDLOG::new(&instance);
+        let (commitment, state) = protocol
+            .prover_commit(&witness, ChallengeExpansion::default(), rng)
+            .unwrap();
+        let (c1, c2) = (F::rand(rng), F::rand(rng));
+        let r1 = protocol.prover_response(&state, &c1).unwrap();
+        let r2 = protocol.prover_response(&state, &c2).unwrap();
+
+        // The same challenge twice
+        assert_eq!(
+            protocol.extract_witness(&commitment, (&c1, &r1), (&c1, &r1)),
+            None
+        );
+        // A rejected transcript
+        assert_eq!(
+            protocol.extract_witness(&commitment, (&c1, &r1), (&c2, &F::rand(rng))),
+            None
+        );
+        // Transcripts with different commitments
+        let (other_commitment, _) = protocol
+            .prover_commit(&witness, ChallengeExpansion::default(), rng)
+            .unwrap();
+        assert_eq!(
+            protocol.extract_witness(&other_commitment, (&c1, &r1), (&c2, &r2)),
+            None
+        );
+    }
+
+    #[test]
+    fn test_or_simulation_hides_the_known_side() {
+        let rng = &mut thread_rng();
+        let (first, first_witness) = schnorr_setup(rng);
+        let (second, _) = schnorr_setup(rng);
+        let protocol = SchnorrOr::new(&(first, second));
+
+        // The prover only knows the first witness, and still answers any challenge
+        let expansion = ChallengeExpansion::default();
+        let (commitment, state) = protocol
+            .prover_commit(&Either::Left(first_witness), expansion, rng)
+            .unwrap();
+        let challenge = SchnorrOr::challenge_from_bytes(&rng.gen(), expansion).unwrap();
+        let response = protocol.prover_response(&state, &challenge).unwrap();
+        assert!(protocol
+            .verifier(&commitment, &challenge, &response)
+            .is_ok());
+    }
+}
@@ -0,0 +1,128 @@
+use ark_serialize::CanonicalDeserialize;
+use rand::{rngs::StdRng, SeedableRng};
+
+use crate::{
+    protocols::{SchnorrDLOG, SchnorrInstance},
+    BatchableProof, ShortProof, SigmaError, SigmaGroup, SufficientOutput, NIZK,
+};
+
+/// The context of the NIZK the decoded proofs are verified with
+const CONTEXT: &[u8] = b"zkpstd/sigma/fuzz";
+
+/// A Schnorr NIZK over the instance of a fixed seed, so that every run verifies against the same statement
+fn nizk<G: SigmaGroup, D: SufficientOutput + Default>() -> NIZK<SchnorrDLOG<G>, D> {
+    let (instance, _) = SchnorrInstance::generate(&mut StdRng::seed_from_u64(0));
+    NIZK::new(&instance, CONTEXT)
+}
+
+/// Decodes `data` as a batchable Schnorr proof, compressed and then uncompressed, and verifies every proof that
+/// decodes with `data` as its message. The fuzz target checks that this returns, whatever the bytes
+pub fn batchable_proof<G: SigmaGroup, D: SufficientOutput + Default>(
+    data: &[u8],
+) -> Result<(), SigmaError> {
+    let nizk = nizk::<G, D>();
+    let uncompressed = BatchableProof::<SchnorrDLOG<G>>::deserialize_uncompressed(data)
+        .map_err(SigmaError::from)
+        .and_then(|proof| nizk.batchable_verify(&proof, Some(data)));
+    let proof = BatchableProof::<SchnorrDLOG<G>>::deserialize(data)?;
+    nizk.batchable_verify(&proof, Some(data))?;
+    uncompressed
+}
+
+/// Decodes `data` as a short Schnorr proof, compressed and then uncompressed, and verifies every proof that
+/// decodes with `data` as its message
+pub fn short_proof<G: SigmaGroup, D: SufficientOutput + Default>(
+    data: &[u8],
+) -> Result<(), SigmaError> {
+    let nizk = nizk::<G, D>();
+    let uncompressed = ShortProof::<SchnorrDLOG<G>>::deserialize_uncompressed(data)
+        .map_err(SigmaError::from)
+        .and_then(|proof| nizk.short_verify(&proof, Some(data)));
+    let proof = ShortProof::<SchnorrDLOG<G>>::deserialize(data)?;
+    nizk.short_verify(&proof, Some(data))?;
+    uncompressed
+}
+
+/// Decodes a Schnorr instance from the start of `data` and a batchable proof from the rest, and verifies the
+/// proof under a NIZK built with [`NIZK::try_new`], which rejects the invalid instances that decode
+pub fn schnorr_instance<G: SigmaGroup, D: SufficientOutput + Default>(
+    data: &[u8],
+) -> Result<(), SigmaError> {
+    let mut reader = data;
+    let instance = SchnorrInstance::<G>::deserialize(&mut reader)?;
+    let nizk = NIZK::<SchnorrDLOG<G>, D>::try_new(&instance, CONTEXT)?;
+    let proof = BatchableProof::deserialize(reader)?;
+    nizk.batchable_verify(&proof, None)
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_ff::Zero;
+    use ark_serialize::CanonicalSerialize;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    use crate::{protocols::SchnorrInstance, SigmaError};
+
+    use super::{batchable_proof, nizk, schnorr_instance, short_proof};
+
+    type Hash = blake2::Blake2s;
+    type G = ark_bls12_377::G1Projective;
+
+    fn serialize<T: CanonicalSerialize>(value: &T) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        value.serialize(&mut bytes).unwrap();
+        bytes
+    }
+
+    /// Random and all-ones strings around the length of `valid`, a single-bit flip of every byte of `valid`,
+    /// cycling through the bit positions, and every truncation of `valid`. Decoding points is slow in debug
+    /// builds, so the inputs are a sample of what the fuzzer explores
+    fn adversarial_inputs(valid: &[u8]) -> Vec<Vec<u8>> {
+        let rng = &mut StdRng::seed_from_u64(1);
+        let lengths = (0..2 * valid.len())
+            .step_by(7)
+            .chain([valid.len() - 1, valid.len() + 1]);
+        let mut inputs: Vec<Vec<u8>> = lengths
+            .flat_map(|length| [(0..length).map(|_| rng.gen()).collect(), vec![0xff; length]])
+            .collect();
+        for byte in 0..valid.len() {
+            let mut flipped = valid.to_vec();
+            flipped[byte] ^= 1 << (byte % 8);
+            inputs.push(flipped);
+        }
+        inputs.extend((0..valid.len()).map(|length| valid[..length].to_vec()));
+        inputs
+    }
+
+    #[test]
+    fn test_fuzz_targets_reject_adversarial_proofs() {
+        let (instance, witness) = SchnorrInstance::<G>::generate(&mut StdRng::seed_from_u64(0));
+        let nizk = nizk::<G, Hash>();
+        let rng = &mut StdRng::seed_from_u64(2);
+
+        // A proof bound to its own encoding cannot be produced, so the honest proofs are bound to none, which
+        // the targets reject as bound to the wrong message
+        let batchable = serialize(&nizk.batchable_proof(&witness, None, rng).unwrap());
+        let short = serialize(&nizk.short_proof(&witness, None, rng).unwrap());
+
+        for input in adversarial_inputs(&batchable) {
+            assert!(batchable_proof::<G, Hash>(&input).is_err());
+        }
+        for input in adversarial_inputs(&short) {
+            assert!(short_proof::<G, Hash>(&input).is_err());
+        }
+
+        let valid = [serialize(&instance), batchable].concat();
+        assert!(schnorr_instance::<G, Hash>(&valid).is_ok());
+        for input in adversarial_inputs(&valid) {
+            assert!(schnorr_instance::<G, Hash>(&input).is_err());
+        }
+
+        // An identity base decodes but is rejected when the NIZK is built
+        let identity = serialize(&SchnorrInstance::new(G::zero(), G::zero()));
+        assert!(matches!(
+            schnorr_instance::<G, Hash>(&identity),
+            Err(SigmaError::InvalidInstance)
+        ));
+    }
+}
@@ -0,0 +1,182 @@
+use rand::Rng;
+
+use crate::{Challenge, ChallengeExpansion, SigmaError, SigmaProtocol};
+
+/// A uniformly random challenge, as drawn by an honest verifier
+pub fn random_challenge<R: Rng>(rng: &mut R) -> Challenge {
+    rng.gen()
+}
+
+/// The prover of an interactive session, before it commits
+pub struct ProverSession<'a, S: SigmaProtocol> {
+    protocol: &'a S,
+    witness: &'a S::Witness,
+    expansion: ChallengeExpansion,
+}
+
+/// The prover of an interactive session once it has committed, waiting for the challenge. Responding consumes it,
+/// so that the commitment randomness answers a single challenge
+pub struct CommittedProver<'a, S: SigmaProtocol> {
+    protocol: &'a S,
+    state: S::ProverState,
+    expansion: ChallengeExpansion,
+}
+
+/// The verifier of an interactive session, waiting for the commitment
+pub struct VerifierSession<'a, S: SigmaProtocol> {
+    protocol: &'a S,
+    expansion: ChallengeExpansion,
+}
+
+/// The verifier of an interactive session once it has sent its challenge, waiting for the response
+pub struct ChallengedVerifier<'a, S: SigmaProtocol> {
+    protocol: &'a S,
+    commitment: &'a S::Commitment,
+    challenge: Challenge,
+    expansion: ChallengeExpansion,
+}
+
+impl<'a, S: SigmaProtocol> ProverSession<'a, S> {
+    /// A session converting its challenge with the default [`ChallengeExpansion`]
+    pub fn new(protocol: &'a S, witness: &'a S::Witness) -> Self {
+        Self {
+            protocol,
+            witness,
+            expansion: ChallengeExpansion::default(),
+        }
+    }
+
+    /// Converts the challenge with `expansion` instead, which must be the expansion of the verifier
+    pub fn with_expansion(self, expansion: ChallengeExpansion) -> Self {
+        Self { expansion, ..self }
+    }
+
+    /// The first message of the session
+    pub fn commit<R: Rng>(
+        self,
+        rng: &mut R,
+    ) -> Result<(S::Commitment, CommittedProver<'a, S>), SigmaError> {
+        let (commitment, state) = self
+            .protocol
+            .prover_commit(self.witness, self.expansion, rng)?;
+
+        Ok((
+            commitment,
+            CommittedProver {
+                protocol: self.protocol,
+                state,
+                expansion: self.expansion,
+            },
+        ))
+    }
+}
+
+impl<S: SigmaProtocol> CommittedProver<'_, S> {
+    /// The last message of the session, answering `challenge`
+    pub fn respond(self, challenge: &Challenge) -> Result<S::Response, SigmaError> {
+        self.protocol.prover_response(
+            &self.state,
+            &S::challenge_from_bytes(challenge, self.expansion)?,
+        )
+    }
+}
+
+impl<'a, S: SigmaProtocol> VerifierSession<'a, S> {
+    /// A session converting its challenge with the default [`ChallengeExpansion`]
+    pub fn new(protocol: &'a S) -> Self {
+        Self {
+            protocol,
+            expansion: ChallengeExpansion::default(),
+        }
+    }
+
+    /// Converts the challenge with `expansion` instead, which must be the expansion of the prover
+    pub fn with_expansion(self, expansion: ChallengeExpansion) -> Self {
+        Self { expansion, ..self }
+    }
+
+    /// Receives the commitment and answers it with a random challenge
+    pub fn challenge<R: Rng>(
+        self,
+        commitment: &'a S::Commitment,
+        rng: &mut R,
+    ) -> (Challenge, ChallengedVerifier<'a, S>) {
+        let challenge = random_challenge(rng);
+
+        (
+            challenge,
+            ChallengedVerifier {
+                protocol: self.protocol,
+                commitment,
+                challenge,
+                expansion: self.expansion,
+            },
+        )
+    }
+}
+
+impl<S: SigmaProtocol> ChallengedVerifier<'_, S> {
+    /// Receives the response and accepts or rejects the transcript
+    pub fn verify(self, response: &S::Response) -> Result<(), SigmaError> {
+        self.protocol.verifier(
+            self.commitment,
+            &S::challenge_from_bytes(&self.challenge, self.expansion)?,
+            response,
+        )
+    }
+}
+
+/// Runs a session of the protocol for `instance` between an honest prover holding `witness` and an honest
+/// verifier, returning the accepted transcript or the error of the party that failed
+pub fn run_interactive<S: SigmaProtocol, R: Rng>(
+    instance: &S::Instance,
+    witness: &S::Witness,
+    rng: &mut R,
+) -> Result<(S::Commitment, Challenge, S::Response), SigmaError> {
+    let protocol = S::new(instance);
+    let (commitment, prover) = ProverSession::new(&protocol, witness).commit(rng)?;
+    let (challenge, verifier) = VerifierSession::new(&protocol).challenge(&commitment, rng);
+    let response = prover.respond(&challenge)?;
+    verifier.verify(&response)?;
+
+    Ok((commitment, challenge, response))
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_ec::ProjectiveCurve;
+    use ark_ff::{PrimeField, UniformRand};
+    use rand::thread_rng;
+
+    use crate::{
+        protocols::{SchnorrDLOG, SchnorrInstance},
+        SigmaError, SigmaProtocol,
+    };
+
+    use super::{ProverSession, VerifierSession};
+
+    type G = ark_bls12_377::G1Projective;
+    type F = ark_bls12_377::Fr;
+
+    #[test]
+    fn test_sessions_reject_response_to_another_challenge() {
+        let rng = &mut thread_rng();
+        let generator = G::prime_subgroup_generator();
+        let witness = F::rand(rng);
+        let protocol = SchnorrDLOG::new(&SchnorrInstance::new(
+            generator,
+            generator.mul(witness.into_repr()),
+        ));
+
+        let (commitment, prover) = ProverSession::new(&protocol, &witness).commit(rng).unwrap();
+        let (challenge, verifier) = VerifierSession::new(&protocol).challenge(&commitment, rng);
+        let mut other_challenge = challenge;
+        other_challenge[0] ^= 1;
+        let response = prover.respond(&other_challenge).unwrap();
+
+        assert_eq!(
+            verifier.verify(&response),
+            Err(SigmaError::VerificationFailed)
+        );
+    }
+}
@@ -0,0 +1,17 @@
+mod extraction;
+pub use extraction::SpecialSoundness;
+
+pub mod interactive;
+pub use interactive::random_challenge;
+
+mod simulation;
+pub use simulation::{check_simulator, honest_transcripts, simulated_transcripts, Divergence};
+
+mod properties;
+pub use properties::{
+    check_batchable_properties, check_case, check_properties, ArbitraryStatement, BitFlips,
+    Property, PropertyViolation,
+};
+
+/// The entry points of the fuzz targets of `fuzz/`, which decode attacker-supplied bytes and verify them
+pub mod fuzz;
@@ -0,0 +1,1005 @@
+use std::{fmt, rc::Rc};
+
+use ark_ec::{PairingEngine, ProjectiveCurve};
+use ark_ff::{PrimeField, UniformRand};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use num_bigint::{BigUint, RandBigInt};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use crate::{
+    composition::{
+        AndComposition, AndMany, DynProtocol, Either, OrComposition, OrMany, Repeated,
+        SharedWitnessAnd, SharedWitnessInstance, Threshold, ThresholdInstance,
+    },
+    interactive_proofs::canonical_bytes,
+    protocols::{
+        BatchDleq, BatchDleqInstance, BitInstance, BitProof, BlsKeyGroup, BlsKeyPossession,
+        BlsKeyPossessionInstance, CorrectDecryption, CorrectDecryptionInstance,
+        DecryptionShareInstance, DecryptionShareProof, DleqInstance, DleqProof, DlogInequality,
+        DlogInequalityInstance, ElGamalCiphertext, ElGamalEncryption, ElGamalEncryptionInstance,
+        KatzWang, KatzWangInstance, LinearMapInstance, LinearMapProof, LinearRelation,
+        LinearRelationInstance, MultiDlog, MultiDlogInstance, Multiplication,
+        MultiplicationInstance, Okamoto, OkamotoInstance, PedersenEquality,
+        PedersenEqualityInstance, PedersenOpening, PedersenParams, PlaintextEquivalence,
+        PlaintextEquivalenceInstance, RangeInstance, RangeProof, ReEncryption,
+        ReEncryptionInstance, Representation, RepresentationInstance, RingOfKeys,
+        RingOfKeysInstance, SchnorrDLOG, SchnorrInstance, SetMembership, SetMembershipInstance,
+        SquareRootIdentification, SquareRootInstance,
+    },
+    CommitmentRecoverable, SigmaError, SigmaGroup, SigmaProtocol, SufficientOutput, NIZK,
+};
+
+/// The number of bytes of a proof flipped in each case with [`BitFlips::Sampled`]. Shorter proofs have every byte
+/// flipped, longer ones a random sample of their bytes
+const MAX_FLIPS: usize = 64;
+
+/// The longest context and message of a case
+const MAX_INPUT_LENGTH: usize = 64;
+
+/// A property of the NIZK proofs of a protocol
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Property {
+    /// Honest proofs verify
+    Completeness,
+    /// Decoding and encoding a proof again gives the same bytes
+    CanonicalEncoding,
+    /// A proof whose bit `bit` of the byte `byte` is flipped is rejected
+    BitFlip {
+        /// The index of the flipped byte in the encoding of the proof
+        byte: usize,
+        /// The flipped bit of that byte
+        bit: u8,
+    },
+    /// A proof is rejected for another message
+    MessageBinding,
+    /// A proof is rejected under another context
+    ContextBinding,
+}
+
+impl fmt::Display for Property {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Completeness => write!(f, "completeness"),
+            Self::CanonicalEncoding => write!(f, "canonical encoding"),
+            Self::BitFlip { byte, bit } => write!(f, "rejection of bit {} of byte {}", bit, byte),
+            Self::MessageBinding => write!(f, "message binding"),
+            Self::ContextBinding => write!(f, "context binding"),
+        }
+    }
+}
+
+/// The bits of a proof that the [`Property::BitFlip`] checks flip, one at a time
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BitFlips {
+    /// A random bit of every byte of a proof of at most `MAX_FLIPS` bytes, or of as many random bytes of a longer
+    /// proof, which is fast enough for every test run
+    Sampled,
+    /// Every bit of every byte of the proof, so that no single bit flip of a long proof goes unchecked
+    Every,
+}
+
+/// A property that the proofs of a protocol broke, with the seed that reproduces it through [`check_case`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PropertyViolation {
+    /// The broken property
+    pub property: Property,
+    /// The kind of the proof, `batchable` or `short`
+    pub proof: &'static str,
+    /// The seed of the case
+    pub seed: u64,
+}
+
+impl fmt::Display for PropertyViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} proofs break {} in the case of seed {}",
+            self.proof, self.property, self.seed
+        )
+    }
+}
+
+/// A Sigma protocol with random statements, which is all [`check_properties`] needs to check the properties of
+/// its proofs. The statements should be small, since every case proves and verifies dozens of times
+pub trait ArbitraryStatement: SigmaProtocol {
+    /// A random instance and a witness for it
+    fn arbitrary_statement<R: Rng>(rng: &mut R) -> (Self::Instance, Self::Witness);
+}
+
+/// Checks every [`Property`] of the batchable and short proofs of `S` over the transcript `D`, on the cases of
+/// seeds `0..cases`, flipping the bits `flips`
+pub fn check_properties<S, D>(cases: u64, flips: BitFlips) -> Result<(), PropertyViolation>
+where
+    S: ArbitraryStatement + CommitmentRecoverable,
+    D: SufficientOutput + Default,
+{
+    (0..cases).try_for_each(|seed| check_case::<S, D>(seed, flips))
+}
+
+/// Checks every [`Property`] of the batchable proofs of `S` over the transcript `D`, on the cases of seeds
+/// `0..cases`, for the protocols whose commitments cannot be recomputed and which have no short proofs
+pub fn check_batchable_properties<S: ArbitraryStatement, D: SufficientOutput + Default>(
+    cases: u64,
+    flips: BitFlips,
+) -> Result<(), PropertyViolation> {
+    (0..cases).try_for_each(|seed| {
+        check_batchable_case::<S, D, _>(seed, flips, &mut StdRng::seed_from_u64(seed)).map(|_| ())
+    })
+}
+
+/// Checks the properties of [`check_properties`] on a single case: a random statement, context and message,
+/// drawn from a `StdRng` seeded with `seed`
+pub fn check_case<S, D>(seed: u64, flips: BitFlips) -> Result<(), PropertyViolation>
+where
+    S: ArbitraryStatement + CommitmentRecoverable,
+    D: SufficientOutput + Default,
+{
+    let rng = &mut StdRng::seed_from_u64(seed);
+    let Case {
+        witness,
+        message,
+        nizks: [nizk, other_nizk],
+    } = check_batchable_case::<S, D, _>(seed, flips, rng)?;
+
+    let proof = nizk
+        .short_proof(&witness, Some(&message), rng)
+        .map_err(|_| violation(seed, "short")(Property::Completeness))?;
+    check_proof(
+        [&nizk, &other_nizk],
+        &proof,
+        &message,
+        |nizk, proof, message| nizk.short_verify(proof, message),
+        flips,
+        rng,
+    )
+    .map_err(violation(seed, "short"))
+}
+
+/// A case whose batchable proofs were checked, kept for checking its short proofs
+struct Case<S: SigmaProtocol, D: SufficientOutput> {
+    witness: S::Witness,
+    message: Vec<u8>,
+    /// The NIZK of the case and one under another context
+    nizks: [NIZK<S, D>; 2],
+}
+
+/// Checks the properties of the batchable proofs on the case of `seed`, drawn from `rng`
+fn check_batchable_case<S, D, R>(
+    seed: u64,
+    flips: BitFlips,
+    rng: &mut R,
+) -> Result<Case<S, D>, PropertyViolation>
+where
+    S: ArbitraryStatement,
+    D: SufficientOutput + Default,
+    R: Rng,
+{
+    let (instance, witness) = S::arbitrary_statement(rng);
+    let context = random_bytes(rng);
+    let message = random_bytes(rng);
+    let nizk = NIZK::<S, D>::new(&instance, &context);
+    let other_nizk = NIZK::<S, D>::new(&instance, &flip_random_bit(&context, rng));
+
+    let proof = nizk
+        .batchable_proof(&witness, Some(&message), rng)
+        .map_err(|_| violation(seed, "batchable")(Property::Completeness))?;
+    check_proof(
+        [&nizk, &other_nizk],
+        &proof,
+        &message,
+        |nizk, proof, message| nizk.batchable_verify(proof, message),
+        flips,
+        rng,
+    )
+    .map_err(violation(seed, "batchable"))?;
+
+    Ok(Case {
+        witness,
+        message,
+        nizks: [nizk, other_nizk],
+    })
+}
+
+/// The violation of a property by the proofs of kind `proof` in the case of `seed`
+fn violation(seed: u64, proof: &'static str) -> impl Fn(Property) -> PropertyViolation {
+    move |property| PropertyViolation {
+        property,
+        proof,
+        seed,
+    }
+}
+
+/// Checks the properties of an honest `proof` of `message` under the first NIZK, the second one having another
+/// context
+fn check_proof<S, D, P, R>(
+    [nizk, other_nizk]: [&NIZK<S, D>; 2],
+    proof: &P,
+    message: &[u8],
+    verify: impl Fn(&NIZK<S, D>, &P, Option<&[u8]>) -> Result<(), SigmaError>,
+    flips: BitFlips,
+    rng: &mut R,
+) -> Result<(), Property>
+where
+    S: SigmaProtocol,
+    D: SufficientOutput,
+    P: CanonicalSerialize + CanonicalDeserialize,
+    R: Rng,
+{
+    verify(nizk, proof, Some(message)).map_err(|_| Property::Completeness)?;
+
+    let bytes = canonical_bytes(proof);
+    let decoded = P::deserialize(&bytes[..]).map_err(|_| Property::CanonicalEncoding)?;
+    if canonical_bytes(&decoded) != bytes {
+        return Err(Property::CanonicalEncoding);
+    }
+
+    if verify(nizk, proof, Some(&flip_random_bit(message, rng))).is_ok() {
+        return Err(Property::MessageBinding);
+    }
+    if verify(other_nizk, proof, Some(message)).is_ok() {
+        return Err(Property::ContextBinding);
+    }
+
+    let bits_to_flip: Vec<(usize, u8)> = match flips {
+        BitFlips::Sampled => {
+            let bytes_to_flip: Vec<usize> = if bytes.len() <= MAX_FLIPS {
+                (0..bytes.len()).collect()
+            } else {
+                (0..MAX_FLIPS)
+                    .map(|_| rng.gen_range(0..bytes.len()))
+                    .collect()
+            };
+            bytes_to_flip
+                .into_iter()
+                .map(|byte| (byte, rng.gen_range(0..8)))
+                .collect()
+        }
+        BitFlips::Every => (0..bytes.len())
+            .flat_map(|byte| (0..8).map(move |bit| (byte, bit)))
+            .collect(),
+    };
+    for (byte, bit) in bits_to_flip {
+        let mut flipped = bytes.clone();
+        flipped[byte] ^= 1 << bit;
+        let decoded = P::deserialize(&flipped[..]);
+        if decoded.is_ok_and(|proof| verify(nizk, &proof, Some(message)).is_ok()) {
+            return Err(Property::BitFlip { byte, bit });
+        }
+    }
+    Ok(())
+}
+
+/// Between one and [`MAX_INPUT_LENGTH`] random bytes
+fn random_bytes<R: Rng>(rng: &mut R) -> Vec<u8> {
+    let length = rng.gen_range(1..=MAX_INPUT_LENGTH);
+    (0..length).map(|_| rng.gen()).collect()
+}
+
+/// `bytes` with one random bit flipped
+fn flip_random_bit<R: Rng>(bytes: &[u8], rng: &mut R) -> Vec<u8> {
+    let mut flipped = bytes.to_vec();
+    flipped[rng.gen_range(0..bytes.len())] ^= 1 << rng.gen_range(0..8);
+    flipped
+}
+
+/// A pair of random Pedersen parameters
+fn pedersen_params<G: ProjectiveCurve, R: Rng>(rng: &mut R) -> PedersenParams<G> {
+    PedersenParams::new(G::rand(rng), G::rand(rng))
+}
+
+/// A Pedersen opening of `message` with a random blinding
+fn pedersen_opening<F: PrimeField, R: Rng>(message: F, rng: &mut R) -> PedersenOpening<F> {
+    PedersenOpening::new(message, F::rand(rng))
+}
+
+/// The multiple of `base` by `scalar`
+fn mul<G: ProjectiveCurve>(base: G, scalar: &G::ScalarField) -> G {
+    base.mul(scalar.into_repr())
+}
+
+/// A random public key of the generator of `G` with its secret key
+fn key_pair<G: ProjectiveCurve, R: Rng>(rng: &mut R) -> (G, G::ScalarField) {
+    let secret_key = G::ScalarField::rand(rng);
+    (mul(G::prime_subgroup_generator(), &secret_key), secret_key)
+}
+
+/// An ElGamal encryption of a random plaintext under `public_key`, with the randomness
+fn encryption<G: ProjectiveCurve, R: Rng>(
+    public_key: G,
+    rng: &mut R,
+) -> (ElGamalCiphertext<G>, G::ScalarField) {
+    let randomness = G::ScalarField::rand(rng);
+    let generator = G::prime_subgroup_generator();
+    let ciphertext = ElGamalCiphertext::encrypt(generator, public_key, G::rand(rng), &randomness);
+    (ciphertext, randomness)
+}
+
+impl<G: SigmaGroup> ArbitraryStatement for SchnorrDLOG<G> {
+    fn arbitrary_statement<R: Rng>(rng: &mut R) -> (Self::Instance, Self::Witness) {
+        SchnorrInstance::generate(rng)
+    }
+}
+
+impl<G: ProjectiveCurve> ArbitraryStatement for DleqProof<G> {
+    fn arbitrary_statement<R: Rng>(rng: &mut R) -> (Self::Instance, Self::Witness) {
+        let witness = G::ScalarField::rand(rng);
+        (
+            DleqInstance::from_witness(G::rand(rng), G::rand(rng), &witness),
+            witness,
+        )
+    }
+}
+
+impl<G: ProjectiveCurve> ArbitraryStatement for Okamoto<G> {
+    fn arbitrary_statement<R: Rng>(rng: &mut R) -> (Self::Instance, Self::Witness) {
+        let (base1, base2) = (G::rand(rng), G::rand(rng));
+        let witness = (G::ScalarField::rand(rng), G::ScalarField::rand(rng));
+        let target = mul(base1, &witness.0) + mul(base2, &witness.1);
+        (OkamotoInstance::new(base1, base2, target), witness)
+    }
+}
+
+impl<G: ProjectiveCurve> ArbitraryStatement for Representation<G> {
+    fn arbitrary_statement<R: Rng>(rng: &mut R) -> (Self::Instance, Self::Witness) {
+        let bases: Vec<G> = (0..3).map(|_| G::rand(rng)).collect();
+        let witness: Vec<_> = (0..3).map(|_| G::ScalarField::rand(rng)).collect();
+        let target = bases
+            .iter()
+            .zip(&witness)
+            .map(|(base, x)| mul(*base, x))
+            .sum();
+        (RepresentationInstance::new(bases, target), witness)
+    }
+}
+
+impl<G: ProjectiveCurve> ArbitraryStatement for PedersenEquality<G> {
+    fn arbitrary_statement<R: Rng>(rng: &mut R) -> (Self::Instance, Self::Witness) {
+        let (params1, params2) = (pedersen_params::<G, _>(rng), pedersen_params::<G, _>(rng));
+        let witness = (
+            G::ScalarField::rand(rng),
+            G::ScalarField::rand(rng),
+            G::ScalarField::rand(rng),
+        );
+        let instance = PedersenEqualityInstance::new(
+            params1,
+            params1.commit(&witness.0, &witness.1),
+            params2,
+            params2.commit(&witness.0, &witness.2),
+        );
+        (instance, witness)
+    }
+}
+
+impl<G: ProjectiveCurve> ArbitraryStatement for LinearRelation<G> {
+    fn arbitrary_statement<R: Rng>(rng: &mut R) -> (Self::Instance, Self::Witness) {
+        let params = pedersen_params::<G, _>(rng);
+        let (a, b) = (G::ScalarField::rand(rng), G::ScalarField::rand(rng));
+        let x = pedersen_opening(G::ScalarField::rand(rng), rng);
+        let y = pedersen_opening(G::ScalarField::rand(rng), rng);
+        let z = pedersen_opening(a * x.message() + b * y.message(), rng);
+        let commitments = (
+            params.commit_opening(&x),
+            params.commit_opening(&y),
+            params.commit_opening(&z),
+        );
+        (
+            LinearRelationInstance::new(params, commitments, (a, b)),
+            (x, y, z),
+        )
+    }
+}
+
+impl<G: ProjectiveCurve> ArbitraryStatement for Multiplication<G> {
+    fn arbitrary_statement<R: Rng>(rng: &mut R) -> (Self::Instance, Self::Witness) {
+        let params = pedersen_params::<G, _>(rng);
+        let x = pedersen_opening(G::ScalarField::rand(rng), rng);
+        let y = pedersen_opening(G::ScalarField::rand(rng), rng);
+        let z = pedersen_opening(x.message() * y.message(), rng);
+        let commitments = (
+            params.commit_opening(&x),
+            params.commit_opening(&y),
+            params.commit_opening(&z),
+        );
+        (MultiplicationInstance::new(params, commitments), (x, y, z))
+    }
+}
+
+impl<G: ProjectiveCurve> ArbitraryStatement for ElGamalEncryption<G> {
+    fn arbitrary_statement<R: Rng>(rng: &mut R) -> (Self::Instance, Self::Witness) {
+        let generator = G::prime_subgroup_generator();
+        let (public_key, _) = key_pair::<G, _>(rng);
+        let witness = (G::ScalarField::rand(rng), G::ScalarField::rand(rng));
+        let ciphertext = ElGamalCiphertext::encrypt(
+            generator,
+            public_key,
+            mul(generator, &witness.0),
+            &witness.1,
+        );
+        (
+            ElGamalEncryptionInstance::new(generator, public_key, ciphertext),
+            witness,
+        )
+    }
+}
+
+impl<G: ProjectiveCurve> ArbitraryStatement for CorrectDecryption<G> {
+    fn arbitrary_statement<R: Rng>(rng: &mut R) -> (Self::Instance, Self::Witness) {
+        let (public_key, secret_key) = key_pair::<G, _>(rng);
+        let (ciphertext, _) = encryption(public_key, rng);
+        let plaintext = ciphertext.c2() - mul(ciphertext.c1(), &secret_key);
+        let instance = CorrectDecryptionInstance::new(
+            G::prime_subgroup_generator(),
+            public_key,
+            ciphertext,
+            plaintext,
+        );
+        (instance, secret_key)
+    }
+}
+
+impl<G: ProjectiveCurve> ArbitraryStatement for ReEncryption<G> {
+    fn arbitrary_statement<R: Rng>(rng: &mut R) -> (Self::Instance, Self::Witness) {
+        let generator = G::prime_subgroup_generator();
+        let (public_key, _) = key_pair::<G, _>(rng);
+        let (original, _) = encryption(public_key, rng);
+        let randomness = G::ScalarField::rand(rng);
+        let reencrypted = original.rerandomize(generator, public_key, &randomness);
+        (
+            ReEncryptionInstance::new(generator, public_key, original, reencrypted),
+            randomness,
+        )
+    }
+}
+
+impl<G: ProjectiveCurve> ArbitraryStatement for PlaintextEquivalence<G> {
+    fn arbitrary_statement<R: Rng>(rng: &mut R) -> (Self::Instance, Self::Witness) {
+        let generator = G::prime_subgroup_generator();
+        let (public_key1, _) = key_pair::<G, _>(rng);
+        let (public_key2, _) = key_pair::<G, _>(rng);
+        let witness = (
+            G::ScalarField::rand(rng),
+            G::ScalarField::rand(rng),
+            G::ScalarField::rand(rng),
+        );
+        let plaintext = mul(generator, &witness.0);
+        let instance = PlaintextEquivalenceInstance::new(
+            generator,
+            public_key1,
+            ElGamalCiphertext::encrypt(generator, public_key1, plaintext, &witness.1),
+            public_key2,
+            ElGamalCiphertext::encrypt(generator, public_key2, plaintext, &witness.2),
+        );
+        (instance, witness)
+    }
+}
+
+impl<G: ProjectiveCurve> ArbitraryStatement for BitProof<G> {
+    fn arbitrary_statement<R: Rng>(rng: &mut R) -> (Self::Instance, Self::Witness) {
+        let params = pedersen_params::<G, _>(rng);
+        let opening = pedersen_opening(G::ScalarField::from(rng.gen::<bool>()), rng);
+        (
+            BitInstance::new(params, params.commit_opening(&opening)),
+            opening,
+        )
+    }
+}
+
+impl<G: ProjectiveCurve> ArbitraryStatement for RangeProof<G> {
+    fn arbitrary_statement<R: Rng>(rng: &mut R) -> (Self::Instance, Self::Witness) {
+        const BITS: usize = 4;
+        let params = pedersen_params::<G, _>(rng);
+        let opening = pedersen_opening(G::ScalarField::from(rng.gen_range(0..1u64 << BITS)), rng);
+        (
+            RangeInstance::new(params, params.commit_opening(&opening), BITS),
+            opening,
+        )
+    }
+}
+
+impl<G: ProjectiveCurve> ArbitraryStatement for SetMembership<G> {
+    fn arbitrary_statement<R: Rng>(rng: &mut R) -> (Self::Instance, Self::Witness) {
+        let base = G::rand(rng);
+        let scalars: Vec<_> = (0..3).map(|_| G::ScalarField::rand(rng)).collect();
+        let claims = scalars.iter().map(|x| mul(base, x)).collect();
+        let index = rng.gen_range(0..scalars.len());
+        (
+            SetMembershipInstance::new(base, claims),
+            (index, scalars[index]),
+        )
+    }
+}
+
+impl<G: ProjectiveCurve> ArbitraryStatement for RingOfKeys<G> {
+    fn arbitrary_statement<R: Rng>(rng: &mut R) -> (Self::Instance, Self::Witness) {
+        let (public_keys, secret_keys): (Vec<_>, Vec<_>) =
+            (0..3).map(|_| key_pair::<G, _>(rng)).unzip();
+        let signer = rng.gen_range(0..secret_keys.len());
+        (
+            RingOfKeysInstance::new(G::prime_subgroup_generator(), public_keys),
+            (signer, secret_keys[signer]),
+        )
+    }
+}
+
+impl ArbitraryStatement for SquareRootIdentification {
+    fn arbitrary_statement<R: Rng>(rng: &mut R) -> (Self::Instance, Self::Witness) {
+        // The product of the Mersenne primes `2^127 - 1` and `2^89 - 1`, both congruent to 3 modulo 4
+        let one = BigUint::from(1u8);
+        let modulus = ((&one << 127) - &one) * ((&one << 89) - &one);
+        let root = rng.gen_biguint_below(&modulus);
+        let square = &root * &root % &modulus;
+        (SquareRootInstance::new(modulus, square, 32), root)
+    }
+}
+
+impl<G: ProjectiveCurve> ArbitraryStatement for KatzWang<G> {
+    fn arbitrary_statement<R: Rng>(rng: &mut R) -> (Self::Instance, Self::Witness) {
+        let (generator1, generator2) = (G::rand(rng), G::rand(rng));
+        let witness = G::ScalarField::rand(rng);
+        let instance = KatzWangInstance::new(
+            generator1,
+            mul(generator1, &witness),
+            generator2,
+            mul(generator2, &witness),
+        );
+        (instance, witness)
+    }
+}
+
+impl<G: ProjectiveCurve> ArbitraryStatement for MultiDlog<G> {
+    fn arbitrary_statement<R: Rng>(rng: &mut R) -> (Self::Instance, Self::Witness) {
+        let base = G::rand(rng);
+        let witness: Vec<_> = (0..3).map(|_| G::ScalarField::rand(rng)).collect();
+        let claims = witness.iter().map(|x| mul(base, x)).collect();
+        (MultiDlogInstance::new(base, claims), witness)
+    }
+}
+
+impl<G: ProjectiveCurve> ArbitraryStatement for BatchDleq<G> {
+    fn arbitrary_statement<R: Rng>(rng: &mut R) -> (Self::Instance, Self::Witness) {
+        let witness = G::ScalarField::rand(rng);
+        let pairs = (0..3)
+            .map(|_| {
+                let base = G::rand(rng);
+                (base, mul(base, &witness))
+            })
+            .collect();
+        (BatchDleqInstance::new(pairs), witness)
+    }
+}
+
+impl<E: PairingEngine, K: BlsKeyGroup<E>> ArbitraryStatement for BlsKeyPossession<E, K> {
+    fn arbitrary_statement<R: Rng>(rng: &mut R) -> (Self::Instance, Self::Witness) {
+        let (public_key, secret_key) = key_pair::<K::Group, _>(rng);
+        (BlsKeyPossessionInstance::new(public_key), secret_key)
+    }
+}
+
+impl<G: ProjectiveCurve> ArbitraryStatement for DecryptionShareProof<G> {
+    fn arbitrary_statement<R: Rng>(rng: &mut R) -> (Self::Instance, Self::Witness) {
+        let (verification_key, secret_key) = key_pair::<G, _>(rng);
+        let ciphertext = ElGamalCiphertext::new(G::rand(rng), G::rand(rng));
+        let instance = DecryptionShareInstance::new(
+            G::prime_subgroup_generator(),
+            verification_key,
+            ciphertext,
+            mul(ciphertext.c1(), &secret_key),
+        );
+        (instance, secret_key)
+    }
+}
+
+impl<G: ProjectiveCurve> ArbitraryStatement for LinearMapProof<G> {
+    fn arbitrary_statement<R: Rng>(rng: &mut R) -> (Self::Instance, Self::Witness) {
+        let witness: Vec<_> = (0..2).map(|_| G::ScalarField::rand(rng)).collect();
+        let matrix: Vec<Vec<G>> = (0..3)
+            .map(|_| (0..2).map(|_| G::rand(rng)).collect())
+            .collect();
+        let image = matrix
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .zip(&witness)
+                    .map(|(base, x)| mul(*base, x))
+                    .sum()
+            })
+            .collect();
+        (LinearMapInstance::new(matrix, image), witness)
+    }
+}
+
+impl<G: ProjectiveCurve> ArbitraryStatement for DlogInequality<G> {
+    fn arbitrary_statement<R: Rng>(rng: &mut R) -> (Self::Instance, Self::Witness) {
+        let (base1, base2) = (G::rand(rng), G::rand(rng));
+        let (witness, log2) = (G::ScalarField::rand(rng), G::ScalarField::rand(rng));
+        let instance =
+            DlogInequalityInstance::new(base1, mul(base1, &witness), base2, mul(base2, &log2));
+        (instance, witness)
+    }
+}
+
+impl<S1: ArbitraryStatement, S2: ArbitraryStatement> ArbitraryStatement for AndComposition<S1, S2> {
+    fn arbitrary_statement<R: Rng>(rng: &mut R) -> (Self::Instance, Self::Witness) {
+        let (instance1, witness1) = S1::arbitrary_statement(rng);
+        let (instance2, witness2) = S2::arbitrary_statement(rng);
+        ((instance1, instance2), (witness1, witness2))
+    }
+}
+
+/// A witness for a random branch
+impl<S1, S2> ArbitraryStatement for OrComposition<S1, S2>
+where
+    S1: ArbitraryStatement + CommitmentRecoverable,
+    S2: ArbitraryStatement + CommitmentRecoverable,
+    S1::Response: Clone,
+    S2::Response: Clone,
+{
+    fn arbitrary_statement<R: Rng>(rng: &mut R) -> (Self::Instance, Self::Witness) {
+        let (instance1, witness1) = S1::arbitrary_statement(rng);
+        let (instance2, witness2) = S2::arbitrary_statement(rng);
+        let witness = if rng.gen() {
+            Either::Left(witness1)
+        } else {
+            Either::Right(witness2)
+        };
+        ((instance1, instance2), witness)
+    }
+}
+
+impl<S: ArbitraryStatement> ArbitraryStatement for AndMany<S> {
+    fn arbitrary_statement<R: Rng>(rng: &mut R) -> (Self::Instance, Self::Witness) {
+        (0..3).map(|_| S::arbitrary_statement(rng)).unzip()
+    }
+}
+
+/// A witness for a random one of three statements
+impl<S: ArbitraryStatement + CommitmentRecoverable> ArbitraryStatement for OrMany<S>
+where
+    S::Response: Clone,
+{
+    fn arbitrary_statement<R: Rng>(rng: &mut R) -> (Self::Instance, Self::Witness) {
+        let (instances, mut witnesses): (Vec<_>, Vec<_>) =
+            (0..3).map(|_| S::arbitrary_statement(rng)).unzip();
+        let index = rng.gen_range(0..witnesses.len());
+        (instances, (index, witnesses.swap_remove(index)))
+    }
+}
+
+/// Witnesses for two random ones of three statements
+impl<S, F: PrimeField> ArbitraryStatement for Threshold<S, F>
+where
+    S: ArbitraryStatement + CommitmentRecoverable<Challenge = F>,
+    S::Response: Clone,
+{
+    fn arbitrary_statement<R: Rng>(rng: &mut R) -> (Self::Instance, Self::Witness) {
+        let (instances, witnesses): (Vec<_>, Vec<_>) =
+            (0..3).map(|_| S::arbitrary_statement(rng)).unzip();
+        let unknown = rng.gen_range(0..witnesses.len());
+        let known = witnesses
+            .into_iter()
+            .enumerate()
+            .filter(|(index, _)| *index != unknown)
+            .collect();
+        (ThresholdInstance::new(2, instances), known)
+    }
+}
+
+impl<S: ArbitraryStatement, const T: usize> ArbitraryStatement for Repeated<S, T> {
+    fn arbitrary_statement<R: Rng>(rng: &mut R) -> (Self::Instance, Self::Witness) {
+        S::arbitrary_statement(rng)
+    }
+}
+
+/// A Schnorr statement and a DLEQ statement over the same witness, beside a second witness of its own
+impl<G: ProjectiveCurve> ArbitraryStatement for SharedWitnessAnd<G> {
+    fn arbitrary_statement<R: Rng>(rng: &mut R) -> (Self::Instance, Self::Witness) {
+        let witness = vec![G::ScalarField::rand(rng), G::ScalarField::rand(rng)];
+        let (base1, base2, base3) = (G::rand(rng), G::rand(rng), G::rand(rng));
+        let instance = SharedWitnessInstance::new(2)
+            .statement(
+                LinearMapInstance::schnorr(base1, mul(base1, &witness[0])),
+                vec![0],
+            )
+            .and_then(|instance| {
+                instance.statement(
+                    LinearMapInstance::okamoto(
+                        base2,
+                        base3,
+                        mul(base2, &witness[0]) + mul(base3, &witness[1]),
+                    ),
+                    vec![0, 1],
+                )
+            })
+            .expect("every statement has one slot per witness");
+        (instance, witness)
+    }
+}
+
+/// A square root statement behind a dynamic protocol
+impl ArbitraryStatement for DynProtocol {
+    fn arbitrary_statement<R: Rng>(rng: &mut R) -> (Self::Instance, Self::Witness) {
+        let (instance, witness) = SquareRootIdentification::arbitrary_statement(rng);
+        (
+            Rc::new(SquareRootIdentification::new(&instance)),
+            Box::new(witness),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_bls12_377::Bls12_377;
+    use ark_ec::ProjectiveCurve;
+    use ark_ff::{UniformRand, Zero};
+    use ark_serialize::CanonicalSerialize;
+    use rand::Rng;
+
+    use crate::{
+        composition::{
+            AndComposition, AndDyn, AndMany, OrComposition, OrDyn, OrMany, Repeated,
+            SharedWitnessAnd, Threshold,
+        },
+        protocols::{
+            BatchDleq, BitProof, BlsKeyPossession, CorrectDecryption, DecryptionShareProof,
+            DleqProof, DlogInequality, ElGamalEncryption, KatzWang, LinearMapProof, LinearRelation,
+            MultiDlog, Multiplication, Okamoto, PedersenEquality, PlaintextEquivalence,
+            PublicKeyInG1, PublicKeyInG2, RangeProof, ReEncryption, Representation, RingOfKeys,
+            SchnorrDLOG, SetMembership, SquareRootIdentification,
+        },
+        Challenge, ChallengeExpansion, CommitmentRecoverable, SigmaError, SigmaProtocol,
+    };
+
+    use super::{
+        check_batchable_properties, check_properties, ArbitraryStatement, BitFlips, Property,
+        PropertyViolation,
+    };
+
+    type Hash = blake2::Blake2s;
+    type G = ark_bls12_377::G1Projective;
+    type F = ark_bls12_377::Fr;
+
+    /// The cases of the default run: every protocol once, which is already slow in debug builds
+    const CASES: u64 = 1;
+
+    /// The cases of the run with `cargo test -- --ignored`, which also flips every bit of every proof of its first
+    /// case
+    const HEAVY_CASES: u64 = 16;
+
+    crate::define_relation! {
+        #[allow(unreachable_pub)]
+        mod commitment_key: CommitmentKey {
+            witness: x, r;
+            bases: G, H;
+            statements: X = x * G, C = x * H + r * G;
+        }
+    }
+
+    impl ArbitraryStatement for commitment_key::CommitmentKey<G> {
+        fn arbitrary_statement<R: Rng>(rng: &mut R) -> (Self::Instance, Self::Witness) {
+            let witness = commitment_key::Witness {
+                x: F::rand(rng),
+                r: F::rand(rng),
+            };
+            let instance =
+                commitment_key::Instance::from_witness(G::rand(rng), G::rand(rng), &witness);
+            (instance, witness)
+        }
+    }
+
+    fn check<S: ArbitraryStatement + CommitmentRecoverable>(cases: u64, flips: BitFlips) {
+        if let Err(violation) = check_properties::<S, Hash>(cases, flips) {
+            panic!("{}: {}", std::any::type_name::<S>(), violation);
+        }
+    }
+
+    /// Checks a protocol without short proofs
+    fn check_batchable<S: ArbitraryStatement>(cases: u64, flips: BitFlips) {
+        if let Err(violation) = check_batchable_properties::<S, Hash>(cases, flips) {
+            panic!("{}: {}", std::any::type_name::<S>(), violation);
+        }
+    }
+
+    fn check_discrete_log_protocols(cases: u64, flips: BitFlips) {
+        check::<SchnorrDLOG<G>>(cases, flips);
+        check::<DleqProof<G>>(cases, flips);
+        check::<Okamoto<G>>(cases, flips);
+        check::<Representation<G>>(cases, flips);
+        check::<MultiDlog<G>>(cases, flips);
+        check::<BatchDleq<G>>(cases, flips);
+        check::<KatzWang<G>>(cases, flips);
+        check_batchable::<DlogInequality<G>>(cases, flips);
+        check::<LinearMapProof<G>>(cases, flips);
+        check::<DecryptionShareProof<G>>(cases, flips);
+        check::<BlsKeyPossession<Bls12_377, PublicKeyInG1>>(cases, flips);
+        check::<BlsKeyPossession<Bls12_377, PublicKeyInG2>>(cases, flips);
+        check::<commitment_key::CommitmentKey<G>>(cases, flips);
+    }
+
+    fn check_commitment_and_encryption_protocols(cases: u64, flips: BitFlips) {
+        check::<PedersenEquality<G>>(cases, flips);
+        check::<LinearRelation<G>>(cases, flips);
+        check::<Multiplication<G>>(cases, flips);
+        check::<ElGamalEncryption<G>>(cases, flips);
+        check::<CorrectDecryption<G>>(cases, flips);
+        check::<ReEncryption<G>>(cases, flips);
+        check::<PlaintextEquivalence<G>>(cases, flips);
+    }
+
+    fn check_membership_protocols(cases: u64, flips: BitFlips) {
+        check::<BitProof<G>>(cases, flips);
+        check_batchable::<RangeProof<G>>(cases, flips);
+        check::<SetMembership<G>>(cases, flips);
+        check::<RingOfKeys<G>>(cases, flips);
+        check::<SquareRootIdentification>(cases, flips);
+    }
+
+    fn check_compositions(cases: u64, flips: BitFlips) {
+        check::<AndComposition<SchnorrDLOG<G>, DleqProof<G>>>(cases, flips);
+        check::<OrComposition<SchnorrDLOG<G>, DleqProof<G>>>(cases, flips);
+        check::<AndMany<SchnorrDLOG<G>>>(cases, flips);
+        check::<OrMany<SchnorrDLOG<G>>>(cases, flips);
+        check::<Threshold<SchnorrDLOG<G>, F>>(cases, flips);
+        check::<Repeated<SchnorrDLOG<G>, 2>>(cases, flips);
+        check::<SharedWitnessAnd<G>>(cases, flips);
+        check::<AndDyn>(cases, flips);
+        check::<OrDyn>(cases, flips);
+    }
+
+    #[test]
+    fn test_discrete_log_protocol_properties() {
+        check_discrete_log_protocols(CASES, BitFlips::Sampled);
+    }
+
+    #[test]
+    fn test_commitment_and_encryption_protocol_properties() {
+        check_commitment_and_encryption_protocols(CASES, BitFlips::Sampled);
+    }
+
+    #[test]
+    fn test_membership_protocol_properties() {
+        check_membership_protocols(CASES, BitFlips::Sampled);
+    }
+
+    #[test]
+    fn test_composition_properties() {
+        check_compositions(CASES, BitFlips::Sampled);
+    }
+
+    #[test]
+    #[ignore]
+    fn test_protocol_properties_many_cases() {
+        check_discrete_log_protocols(HEAVY_CASES, BitFlips::Sampled);
+        check_commitment_and_encryption_protocols(HEAVY_CASES, BitFlips::Sampled);
+        check_membership_protocols(HEAVY_CASES, BitFlips::Sampled);
+        check_compositions(HEAVY_CASES, BitFlips::Sampled);
+    }
+
+    #[test]
+    #[ignore]
+    fn test_protocol_properties_every_bit_flip() {
+        check_discrete_log_protocols(1, BitFlips::Every);
+        check_commitment_and_encryption_protocols(1, BitFlips::Every);
+        check_membership_protocols(1, BitFlips::Every);
+        check_compositions(1, BitFlips::Every);
+    }
+
+    #[cfg(feature = "ristretto")]
+    #[test]
+    fn test_ristretto_schnorr_properties() {
+        check::<SchnorrDLOG<crate::ristretto::RistrettoElement>>(CASES, BitFlips::Sampled);
+    }
+
+    #[cfg(feature = "secp256k1")]
+    #[test]
+    fn test_secp256k1_schnorr_properties() {
+        check::<SchnorrDLOG<crate::secp256k1::Secp256k1Element>>(CASES, BitFlips::Sampled);
+    }
+
+    /// A Schnorr proof whose response carries eight bytes that the verifier ignores
+    struct Padded(SchnorrDLOG<G>);
+
+    impl SigmaProtocol for Padded {
+        type Instance = <SchnorrDLOG<G> as SigmaProtocol>::Instance;
+        type Witness = F;
+        type Commitment = G;
+        type ProverState = <SchnorrDLOG<G> as SigmaProtocol>::ProverState;
+        type Response = (F, u64);
+        type Challenge = F;
+
+        const PROTOCOL_ID: &'static [u8] = b"zkpstd/sigma/test/padded";
+
+        fn new(instance: &Self::Instance) -> Self {
+            Self(SchnorrDLOG::new(instance))
+        }
+
+        fn challenge_from_bytes(
+            challenge: &Challenge,
+            expansion: ChallengeExpansion,
+        ) -> Result<F, SigmaError> {
+            SchnorrDLOG::<G>::challenge_from_bytes(challenge, expansion)
+        }
+
+        fn instance_bytes(&self) -> Vec<u8> {
+            self.0.instance_bytes()
+        }
+
+        fn witness_bytes(&self, witness: &F) -> Vec<u8> {
+            self.0.witness_bytes(witness)
+        }
+
+        fn prover_commit<R: Rng>(
+            &self,
+            witness: &F,
+            expansion: ChallengeExpansion,
+            rng: &mut R,
+        ) -> Result<(G, Self::ProverState), SigmaError> {
+            self.0.prover_commit(witness, expansion, rng)
+        }
+
+        fn prover_response(
+            &self,
+            state: &Self::ProverState,
+            challenge: &F,
+        ) -> Result<(F, u64), SigmaError> {
+            Ok((self.0.prover_response(state, challenge)?, 0))
+        }
+
+        fn verifier(
+            &self,
+            commitment: &G,
+            challenge: &F,
+            response: &(F, u64),
+        ) -> Result<(), SigmaError> {
+            self.0.verifier(commitment, challenge, &response.0)
+        }
+
+        fn simulate_response<R: Rng>(&self, rng: &mut R) -> (F, u64) {
+            (self.0.simulate_response(rng), 0)
+        }
+    }
+
+    impl CommitmentRecoverable for Padded {
+        fn simulate_commitment(&self, challenge: &F, response: &(F, u64)) -> Result<G, SigmaError> {
+            self.0.simulate_commitment(challenge, &response.0)
+        }
+    }
+
+    impl ArbitraryStatement for Padded {
+        fn arbitrary_statement<R: Rng>(rng: &mut R) -> (Self::Instance, F) {
+            SchnorrDLOG::<G>::arbitrary_statement(rng)
+        }
+    }
+
+    #[test]
+    fn test_check_properties_finds_ignored_bytes() {
+        // The padding ends the batchable proof
+        let length = G::prime_subgroup_generator().serialized_size() + F::zero().serialized_size();
+        let violation = check_properties::<Padded, Hash>(CASES, BitFlips::Sampled);
+
+        assert!(matches!(
+            violation,
+            Err(PropertyViolation {
+                property: Property::BitFlip { byte, .. },
+                proof: "batchable",
+                seed: 0,
+            }) if byte >= length
+        ));
+        assert!(violation
+            .unwrap_err()
+            .to_string()
+            .starts_with("batchable proofs break rejection of bit"));
+
+        // Flipping every bit finds the first ignored one
+        assert_eq!(
+            check_properties::<Padded, Hash>(CASES, BitFlips::Every),
+            Err(PropertyViolation {
+                property: Property::BitFlip {
+                    byte: length,
+                    bit: 0
+                },
+                proof: "batchable",
+                seed: 0,
+            })
+        );
+    }
+}
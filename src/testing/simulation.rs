@@ -0,0 +1,335 @@
+use std::{collections::HashSet, fmt};
+
+use ark_ff::{FpParameters, PrimeField};
+use ark_serialize::CanonicalSerialize;
+use num_bigint::BigUint;
+use rand::Rng;
+
+use super::random_challenge;
+use crate::{
+    interactive_proofs::canonical_bytes, Challenge, ChallengeExpansion, CommitmentRecoverable,
+    SigmaProtocol,
+};
+
+/// The number of buckets the responses are counted in, by their position in the field
+const RESPONSE_BUCKETS: usize = 16;
+
+/// A statistic for which the honest and simulated transcripts diverged
+#[derive(Debug, Clone, PartialEq)]
+pub struct Divergence {
+    /// The statistic that diverged
+    pub statistic: &'static str,
+    /// Its value
+    pub value: f64,
+    /// The largest value it may take
+    pub bound: f64,
+}
+
+impl fmt::Display for Divergence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} diverged: {:.1} exceeds the bound {:.1}",
+            self.statistic, self.value, self.bound
+        )
+    }
+}
+
+/// `samples` honest transcripts for `witness`, each answering a random challenge through the interactive prover
+pub fn honest_transcripts<S: SigmaProtocol, R: Rng>(
+    protocol: &S,
+    witness: &S::Witness,
+    samples: usize,
+    rng: &mut R,
+) -> Vec<(S::Commitment, Challenge, S::Response)> {
+    let expansion = ChallengeExpansion::default();
+    (0..samples)
+        .map(|_| {
+            let (commitment, state) = protocol
+                .prover_commit(witness, expansion, rng)
+                .expect("the honest prover commits");
+            let challenge = random_challenge(rng);
+            let response = protocol
+                .prover_response(
+                    &state,
+                    &S::challenge_from_bytes(&challenge, expansion)
+                        .expect("the challenge converts"),
+                )
+                .expect("the honest prover responds");
+            (commitment, challenge, response)
+        })
+        .collect()
+}
+
+/// `samples` transcripts of [`CommitmentRecoverable::simulate_transcript`]
+pub fn simulated_transcripts<S: CommitmentRecoverable, R: Rng>(
+    protocol: &S,
+    samples: usize,
+    rng: &mut R,
+) -> Vec<(S::Commitment, Challenge, S::Response)> {
+    (0..samples)
+        .map(|_| {
+            protocol
+                .simulate_transcript(ChallengeExpansion::default(), rng)
+                .expect("the simulator outputs transcripts")
+        })
+        .collect()
+}
+
+/// A bound a chi-square statistic with `freedom` degrees of freedom exceeds with probability about `10^-9`, six
+/// standard deviations above its mean in the normal approximation
+fn chi_square_bound(freedom: usize) -> f64 {
+    freedom as f64 + 6.0 * (2.0 * freedom as f64).sqrt()
+}
+
+/// The chi-square statistic of the hypothesis that two samples of the same size share their distribution over
+/// the categories of `first` and `second`, and its degrees of freedom
+pub(crate) fn chi_square_homogeneity(first: &[u64], second: &[u64]) -> (f64, usize) {
+    let (statistic, categories) = first
+        .iter()
+        .zip(second)
+        .filter(|(a, b)| **a + **b > 0)
+        .fold((0.0, 0), |(statistic, categories), (a, b)| {
+            let difference = *a as f64 - *b as f64;
+            (
+                statistic + difference * difference / (*a + *b) as f64,
+                categories + 1,
+            )
+        });
+    (statistic, categories.max(1) - 1)
+}
+
+/// The chi-square statistic of the hypothesis that `counts` are uniform over their categories, and its degrees of
+/// freedom
+pub(crate) fn chi_square_uniform(counts: &[u64]) -> (f64, usize) {
+    let expected = counts.iter().sum::<u64>() as f64 / counts.len() as f64;
+    let statistic = counts
+        .iter()
+        .map(|count| (*count as f64 - expected).powi(2) / expected)
+        .sum();
+    (statistic, counts.len() - 1)
+}
+
+fn check(statistic: &'static str, (value, freedom): (f64, usize)) -> Result<(), Divergence> {
+    let bound = chi_square_bound(freedom);
+    if value <= bound {
+        Ok(())
+    } else {
+        Err(Divergence {
+            statistic,
+            value,
+            bound,
+        })
+    }
+}
+
+/// The frequency of every byte value in the encodings of the commitments and responses
+fn byte_frequencies<C: CanonicalSerialize, Z: CanonicalSerialize>(
+    transcripts: &[(C, Challenge, Z)],
+) -> Vec<u64> {
+    let mut counts = vec![0u64; 256];
+    for (commitment, _, response) in transcripts {
+        for byte in canonical_bytes(commitment)
+            .into_iter()
+            .chain(canonical_bytes(response))
+        {
+            counts[usize::from(byte)] += 1;
+        }
+    }
+    counts
+}
+
+/// The number of responses in each of `RESPONSE_BUCKETS` equal slices of the field
+fn response_buckets<C, F: PrimeField>(transcripts: &[(C, Challenge, F)]) -> Vec<u64> {
+    let modulus = BigUint::from_bytes_le(&canonical_bytes(&F::Params::MODULUS));
+    let mut counts = vec![0u64; RESPONSE_BUCKETS];
+    for (_, _, response) in transcripts {
+        let value = BigUint::from_bytes_le(&canonical_bytes(&response.into_repr()));
+        let bucket = usize::try_from(value * RESPONSE_BUCKETS / &modulus)
+            .expect("field elements are below the modulus");
+        counts[bucket] += 1;
+    }
+    counts
+}
+
+/// The number of commitments that repeat an earlier one
+fn repeated_commitments<C: CanonicalSerialize, Z>(transcripts: &[(C, Challenge, Z)]) -> usize {
+    let mut seen = HashSet::new();
+    transcripts
+        .iter()
+        .filter(|(commitment, _, _)| !seen.insert(canonical_bytes(commitment)))
+        .count()
+}
+
+/// Compares `samples` honest transcripts for `witness` with `samples` simulated ones, for protocols whose responses
+/// are field elements. Checks, in this order, that:
+///
+/// - no commitment, and thus no nonce, repeats in either set, reported as `"repeated honest commitments"` or
+///   `"repeated simulated commitments"`,
+/// - the byte frequencies of the encoded commitments and responses agree between both sets, with a chi-square
+///   homogeneity test reported as `"byte frequencies"`,
+/// - the responses of each set are uniform over the field, with a chi-square test over `RESPONSE_BUCKETS` slices
+///   of the field reported as `"honest response buckets"` or `"simulated response buckets"`.
+///
+/// The chi-square bounds are exceeded by chance with probability about `10^-9`, so a divergence points to a
+/// simulator that does not match the prover. A few thousand samples detect gross biases only
+pub fn check_simulator<S, R>(
+    protocol: &S,
+    witness: &S::Witness,
+    samples: usize,
+    rng: &mut R,
+) -> Result<(), Divergence>
+where
+    S: CommitmentRecoverable,
+    S::Response: PrimeField,
+    R: Rng,
+{
+    let honest = honest_transcripts(protocol, witness, samples, rng);
+    let simulated = simulated_transcripts(protocol, samples, rng);
+
+    for (statistic, transcripts) in [
+        ("repeated honest commitments", &honest),
+        ("repeated simulated commitments", &simulated),
+    ] {
+        let repeated = repeated_commitments(transcripts);
+        if repeated > 0 {
+            return Err(Divergence {
+                statistic,
+                value: repeated as f64,
+                bound: 0.0,
+            });
+        }
+    }
+
+    check(
+        "byte frequencies",
+        chi_square_homogeneity(&byte_frequencies(&honest), &byte_frequencies(&simulated)),
+    )?;
+    check(
+        "honest response buckets",
+        chi_square_uniform(&response_buckets(&honest)),
+    )?;
+    check(
+        "simulated response buckets",
+        chi_square_uniform(&response_buckets(&simulated)),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_ec::ProjectiveCurve;
+    use ark_ff::{PrimeField, UniformRand};
+    use rand::{thread_rng, Rng};
+
+    use crate::{
+        protocols::{DleqInstance, DleqProof, SchnorrDLOG, SchnorrInstance},
+        SigmaProtocol,
+    };
+
+    use super::{
+        check_simulator, chi_square_bound, chi_square_homogeneity, chi_square_uniform,
+        repeated_commitments, response_buckets, RESPONSE_BUCKETS,
+    };
+
+    type G = ark_bls12_377::G1Projective;
+    type F = ark_bls12_377::Fr;
+
+    /// Enough samples for the heavy tests to detect a bias of a few percent in any statistic
+    const HEAVY_SAMPLES: usize = 4096;
+
+    fn schnorr_setup<R: Rng>(rng: &mut R) -> (SchnorrDLOG<G>, F) {
+        let base = G::rand(rng);
+        let witness = F::rand(rng);
+
+        (
+            SchnorrDLOG::new(&SchnorrInstance::new(base, base.mul(witness.into_repr()))),
+            witness,
+        )
+    }
+
+    fn dleq_setup<R: Rng>(rng: &mut R) -> (DleqProof<G>, F) {
+        let (base1, base2) = (G::rand(rng), G::rand(rng));
+        let witness = F::rand(rng);
+
+        (
+            DleqProof::new(&DleqInstance::new(
+                base1,
+                base1.mul(witness.into_repr()),
+                base2,
+                base2.mul(witness.into_repr()),
+            )),
+            witness,
+        )
+    }
+
+    #[test]
+    fn test_chi_square_statistics() {
+        let uniform = vec![100; 16];
+        let skewed: Vec<u64> = (0..16).map(|i| if i == 0 { 400 } else { 80 }).collect();
+
+        assert_eq!(chi_square_uniform(&uniform), (0.0, 15));
+        assert!(chi_square_uniform(&skewed).0 > chi_square_bound(15));
+        assert_eq!(chi_square_homogeneity(&uniform, &uniform), (0.0, 15));
+        assert!(chi_square_homogeneity(&uniform, &skewed).0 > chi_square_bound(15));
+        // Categories empty in both samples carry no degree of freedom
+        assert_eq!(chi_square_homogeneity(&[5, 0, 5], &[5, 0, 5]).1, 1);
+    }
+
+    #[test]
+    fn test_statistics_detect_flawed_transcripts() {
+        let rng = &mut thread_rng();
+        let generator = G::prime_subgroup_generator();
+        let uniform: Vec<_> = (0..256)
+            .map(|_| (generator, rng.gen(), F::rand(rng)))
+            .collect();
+        // Responses below 2^64, all in the first slice of the field
+        let small: Vec<_> = (0..256)
+            .map(|_| (generator, rng.gen(), F::from(rng.gen::<u64>())))
+            .collect();
+
+        let (statistic, freedom) = chi_square_uniform(&response_buckets(&uniform));
+        assert_eq!(freedom, RESPONSE_BUCKETS - 1);
+        assert!(statistic <= chi_square_bound(freedom));
+        assert_eq!(response_buckets(&small)[0], 256);
+
+        // Every transcript repeats the commitment of the first one
+        assert_eq!(repeated_commitments(&uniform), 255);
+        let distinct: Vec<_> = uniform
+            .iter()
+            .map(|(_, challenge, response)| (G::rand(rng), *challenge, *response))
+            .collect();
+        assert_eq!(repeated_commitments(&distinct), 0);
+    }
+
+    #[test]
+    fn test_schnorr_simulator_sanity() {
+        let rng = &mut thread_rng();
+        let (protocol, witness) = schnorr_setup(rng);
+
+        if let Err(divergence) = check_simulator(&protocol, &witness, 64, rng) {
+            panic!("{}", divergence);
+        }
+    }
+
+    #[test]
+    #[ignore]
+    fn test_schnorr_simulator_is_indistinguishable() {
+        let rng = &mut thread_rng();
+        let (protocol, witness) = schnorr_setup(rng);
+
+        if let Err(divergence) = check_simulator(&protocol, &witness, HEAVY_SAMPLES, rng) {
+            panic!("{}", divergence);
+        }
+    }
+
+    #[test]
+    #[ignore]
+    fn test_dleq_simulator_is_indistinguishable() {
+        let rng = &mut thread_rng();
+        let (protocol, witness) = dleq_setup(rng);
+
+        if let Err(divergence) = check_simulator(&protocol, &witness, HEAVY_SAMPLES, rng) {
+            panic!("{}", divergence);
+        }
+    }
+}
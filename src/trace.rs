@@ -0,0 +1,260 @@
+#[cfg(feature = "tracing")]
+use crate::{encoding::encode_hex, Transcript};
+use crate::{Challenge, SigmaError, LABEL_LENGTH};
+
+/// The number of bytes of the digest so far recorded by every absorption event
+#[cfg(feature = "tracing")]
+const DIGEST_PREFIX_LENGTH: usize = 8;
+
+/// The guard of the span of a challenge computation, or nothing without the `tracing` feature
+#[cfg(feature = "tracing")]
+pub(crate) type Span = tracing::span::EnteredSpan;
+
+#[cfg(not(feature = "tracing"))]
+pub(crate) struct Span;
+
+/// Enters the span of the computation of a Fiat-Shamir challenge, which carries the protocol label and the hash of
+/// the context in hex, so that two parties can tell their transcripts apart before comparing them field by field
+#[cfg(feature = "tracing")]
+pub(crate) fn challenge_span(label: &[u8; LABEL_LENGTH], context: &Challenge) -> Span {
+    tracing::trace_span!(
+        "challenge",
+        label = %encode_hex(label),
+        context = %encode_hex(context)
+    )
+    .entered()
+}
+
+#[cfg(not(feature = "tracing"))]
+pub(crate) fn challenge_span(_label: &[u8; LABEL_LENGTH], _context: &Challenge) -> Span {
+    Span
+}
+
+/// Records that `length` bytes were absorbed into the field `field` of `transcript`, with the first bytes of the
+/// challenge the transcript would output at this point. Only the hashes of the public fields absorbed by the
+/// challenge are recorded, never the witness or the nonces
+#[cfg(feature = "tracing")]
+pub(crate) fn absorbed<D: Transcript>(transcript: &D, field: &'static [u8], length: usize) {
+    tracing::trace!(
+        field = %String::from_utf8_lossy(field),
+        length,
+        digest = %encode_hex(&transcript.clone().challenge(field)[..DIGEST_PREFIX_LENGTH]),
+        "absorbed"
+    );
+}
+
+#[cfg(not(feature = "tracing"))]
+pub(crate) fn absorbed<D>(_transcript: &D, _field: &'static [u8], _length: usize) {}
+
+/// Records that a proof of the kind `proof`, `batchable` or `short`, was produced under `label`
+#[cfg(feature = "tracing")]
+pub(crate) fn proved(label: &[u8; LABEL_LENGTH], proof: &'static str) {
+    tracing::debug!(label = %encode_hex(label), proof, "proof created");
+}
+
+#[cfg(not(feature = "tracing"))]
+pub(crate) fn proved(_label: &[u8; LABEL_LENGTH], _proof: &'static str) {}
+
+/// Records the outcome of verifying proofs of the kind `proof` under `label`, with the cause of a rejection
+#[cfg(feature = "tracing")]
+pub(crate) fn verified(
+    label: &[u8; LABEL_LENGTH],
+    proof: &'static str,
+    result: &Result<(), SigmaError>,
+) {
+    match result {
+        Ok(()) => {
+            tracing::debug!(label = %encode_hex(label), proof, valid = true, "proof verified")
+        }
+        Err(error) => tracing::debug!(
+            label = %encode_hex(label),
+            proof,
+            valid = false,
+            error = ?error,
+            "proof verified"
+        ),
+    }
+}
+
+#[cfg(not(feature = "tracing"))]
+pub(crate) fn verified(
+    _label: &[u8; LABEL_LENGTH],
+    _proof: &'static str,
+    _result: &Result<(), SigmaError>,
+) {
+}
+
+#[cfg(all(test, feature = "tracing"))]
+mod tests {
+    use std::{
+        fmt,
+        sync::{Arc, Mutex},
+    };
+
+    use ark_ff::UniformRand;
+    use ark_serialize::CanonicalSerialize;
+    use rand::thread_rng;
+    use tracing::{
+        field::{Field, Visit},
+        span, Event, Metadata, Subscriber,
+    };
+
+    use crate::{
+        encoding::encode_hex,
+        protocols::{SchnorrDLOG, SchnorrInstance},
+        transcript::Transcript,
+        SigmaGroup, SigmaProtocol, NIZK,
+    };
+
+    type Hash = blake2::Blake2s;
+    type G = ark_bls12_377::G1Projective;
+    type F = ark_bls12_377::Fr;
+
+    /// The fields of an event or span, in the order they were recorded
+    #[derive(Debug, Default, Clone)]
+    struct Fields(Vec<(String, String)>);
+
+    impl Fields {
+        fn get(&self, name: &str) -> &str {
+            self.0
+                .iter()
+                .find(|(field, _)| field == name)
+                .map(|(_, value)| value.as_str())
+                .unwrap_or_else(|| panic!("no field `{}` in {:?}", name, self.0))
+        }
+    }
+
+    impl Visit for Fields {
+        fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+            self.0
+                .push((field.name().to_string(), format!("{:?}", value)));
+        }
+
+        fn record_str(&mut self, field: &Field, value: &str) {
+            self.0.push((field.name().to_string(), value.to_string()));
+        }
+    }
+
+    /// A subscriber recording every event and the fields of every span
+    #[derive(Clone, Default)]
+    struct Recorder {
+        events: Arc<Mutex<Vec<Fields>>>,
+        spans: Arc<Mutex<Vec<Fields>>>,
+    }
+
+    impl Subscriber for Recorder {
+        fn enabled(&self, _metadata: &Metadata<'_>) -> bool {
+            true
+        }
+
+        fn new_span(&self, span: &span::Attributes<'_>) -> span::Id {
+            let mut fields = Fields::default();
+            span.record(&mut fields);
+            let mut spans = self.spans.lock().unwrap();
+            spans.push(fields);
+            span::Id::from_u64(spans.len() as u64)
+        }
+
+        fn record(&self, _span: &span::Id, _values: &span::Record<'_>) {}
+
+        fn record_follows_from(&self, _span: &span::Id, _follows: &span::Id) {}
+
+        fn event(&self, event: &Event<'_>) {
+            let mut fields = Fields::default();
+            event.record(&mut fields);
+            self.events.lock().unwrap().push(fields);
+        }
+
+        fn enter(&self, _span: &span::Id) {}
+
+        fn exit(&self, _span: &span::Id) {}
+    }
+
+    /// The `(field, length, digest)` of every absorption event, and the message of every other event
+    fn summary(events: &[Fields]) -> Vec<String> {
+        events
+            .iter()
+            .map(|event| match event.get("message") {
+                "absorbed" => format!("{}:{}", event.get("field"), event.get("length")),
+                message => message.to_string(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_schnorr_proof_absorption_events() {
+        let rng = &mut thread_rng();
+        let (instance, witness) = SchnorrInstance::<G>::generate(rng);
+        let nizk = NIZK::<SchnorrDLOG<G>, Hash>::new(&instance, b"context");
+
+        let recorder = Recorder::default();
+        let proof = tracing::subscriber::with_default(recorder.clone(), || {
+            let proof = nizk
+                .batchable_proof(&witness, Some(b"message"), rng)
+                .unwrap();
+            assert!(nizk.batchable_verify(&proof, Some(b"message")).is_ok());
+            let wrong_proof = nizk
+                .batchable_proof(&F::rand(rng), Some(b"message"), rng)
+                .unwrap();
+            assert!(nizk
+                .batchable_verify(&wrong_proof, Some(b"message"))
+                .is_err());
+            proof
+        });
+        let events = recorder.events.lock().unwrap().clone();
+
+        let commitment_length = G::generator().serialized_size();
+        let absorptions = [
+            "domain:32".to_string(),
+            "context:32".to_string(),
+            "label:32".to_string(),
+            "instance:32".to_string(),
+            "message:32".to_string(),
+            format!("commitment:{}", commitment_length),
+        ];
+        let proof_events = [
+            &absorptions[..],
+            &["proof created".to_string()],
+            &absorptions,
+            &["proof verified".to_string()],
+        ]
+        .concat();
+        let expected = [&proof_events[..], &proof_events].concat();
+        assert_eq!(summary(&events), expected);
+
+        let label = encode_hex(&SchnorrDLOG::new(&instance).label());
+        let (created, verified, rejected) = (&events[6], &events[13], &events[27]);
+        assert_eq!(created.get("label"), label);
+        assert_eq!(created.get("proof"), "batchable");
+        assert_eq!(verified.get("valid"), "true");
+        assert_eq!(rejected.get("valid"), "false");
+        assert_eq!(rejected.get("error"), "VerificationFailed");
+
+        // The prover and the verifier absorb the same bytes, and the last digest is the start of the challenge
+        for i in 0..6 {
+            assert_eq!(events[i].get("digest"), events[7 + i].get("digest"));
+        }
+        assert_ne!(events[5].get("digest"), events[19].get("digest"));
+        let challenge = nizk.batchable_challenge(&proof, Some(b"message")).unwrap();
+        assert_eq!(events[5].get("digest"), encode_hex(&challenge[..8]));
+
+        // Every challenge span carries the label and the hash of the context
+        let context = encode_hex(&Hash::default().hash(b"context", b"context"));
+        let spans = recorder.spans.lock().unwrap();
+        assert_eq!(spans.len(), 4);
+        for span in spans.iter() {
+            assert_eq!(span.get("label"), label);
+            assert_eq!(span.get("context"), context);
+        }
+
+        // Nothing recorded holds the witness
+        let mut witness_bytes = Vec::new();
+        witness.serialize(&mut witness_bytes).unwrap();
+        let witness_hex = encode_hex(&witness_bytes);
+        for fields in events.iter().chain(spans.iter()) {
+            for (_, value) in &fields.0 {
+                assert!(!value.contains(&witness_hex[..16]));
+            }
+        }
+    }
+}
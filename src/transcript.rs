@@ -0,0 +1,386 @@
+use std::fmt;
+
+use ark_ff::PrimeField;
+use ark_serialize::{CanonicalSerialize, SerializationError, Write};
+use blake2::Blake2b;
+use digest::{
+    consts::U32,
+    generic_array::typenum::{IsGreaterOrEqual, True},
+    Digest, DynDigest, ExtendableOutput, Update, XofReader,
+};
+
+use crate::{Challenge, HashFunction, SigmaError, CHALLENGE_LENGTH, DOMSEP};
+
+/// The number of bits by which an expanded challenge exceeds the modulus, which bounds the statistical distance
+/// of the reduced scalar from uniform by `2^-128`
+const CHALLENGE_SECURITY_BITS: usize = 128;
+
+/// How a challenge is expanded into the longer strings that protocols reduce modulo the order of their group. It is
+/// chosen by the transcript, see [`Transcript::challenge_expansion`], and passed to every conversion of a challenge,
+/// as [`SigmaProtocol::challenge_from_bytes`](crate::SigmaProtocol::challenge_from_bytes), so that a prover and a
+/// verifier running a protocol outside of the NIZK must agree on it as well. The default is the expansion of the
+/// standard, the concatenation of the blocks `Blake2b(DOMSEP || i || challenge)` for a one-byte counter `i`
+#[derive(Clone, Copy)]
+pub struct ChallengeExpansion {
+    expand: fn(&Challenge, usize) -> Vec<u8>,
+}
+
+impl ChallengeExpansion {
+    /// The expansion computing `length` bytes out of a challenge with `expand`
+    pub fn new(expand: fn(&Challenge, usize) -> Vec<u8>) -> Self {
+        Self { expand }
+    }
+
+    /// The challenge expanded into `length` pseudorandom bytes
+    pub fn expand(&self, challenge: &Challenge, length: usize) -> Vec<u8> {
+        (self.expand)(challenge, length)
+    }
+
+    /// Maps the challenge to a scalar: the challenge is expanded to 128 more bits than the modulus, read as a
+    /// little-endian integer and reduced modulo the field order. The scalar is thus statistically close to uniform,
+    /// and the conversion never fails
+    pub fn scalar<F: PrimeField>(&self, challenge: &Challenge) -> F {
+        let length = (F::size_in_bits() + CHALLENGE_SECURITY_BITS).div_ceil(8);
+        F::from_le_bytes_mod_order(&self.expand(challenge, length))
+    }
+}
+
+impl Default for ChallengeExpansion {
+    fn default() -> Self {
+        Self::new(blake2b_expansion)
+    }
+}
+
+impl fmt::Debug for ChallengeExpansion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ChallengeExpansion").finish_non_exhaustive()
+    }
+}
+
+/// The blocks `Blake2b(DOMSEP || i || challenge)` for a one-byte counter `i`, truncated to `length`
+fn blake2b_expansion(challenge: &Challenge, length: usize) -> Vec<u8> {
+    let mut bytes: Vec<u8> = (0..=u8::MAX)
+        .map(|counter| Blake2b::digest(&[DOMSEP, &[counter], challenge].concat()))
+        .take(length.div_ceil(64))
+        .flatten()
+        .collect();
+    bytes.truncate(length);
+    bytes
+}
+
+/// The challenge of a digest output, its last `CHALLENGE_LENGTH` bytes as the standard mandates, so that a 64-byte
+/// digest gives the same challenge in every implementation. Every hash of the NIZK is compressed here: the domain
+/// separator, the context, the instance, the message and the challenge. Panics on outputs shorter than a challenge,
+/// which the NIZK rejects when it is built
+pub(crate) fn compress_digest(output: &[u8]) -> Challenge {
+    let mut challenge = [0u8; CHALLENGE_LENGTH];
+    challenge.copy_from_slice(&output[output.len() - CHALLENGE_LENGTH..]);
+    challenge
+}
+
+/// The state absorbing the fields of a Fiat-Shamir transcript, from which the challenge is squeezed.
+///
+/// Appending the bytes of a field in several calls with the same label must be the same as appending them at once,
+/// so that large messages can be streamed. Every [`Digest`] is a transcript that ignores the labels, as the fields
+/// absorbed by the NIZK have fixed lengths and a fixed order; the challenge is the last `CHALLENGE_LENGTH` bytes of
+/// the digest.
+///
+/// The NIZK keeps an empty transcript, which it clones for every hash it computes. [`NIZK::new`](crate::NIZK::new)
+/// starts from the default transcript, so that only backends chosen at runtime, as [`DynTranscript`], need to be
+/// built by hand.
+pub trait Transcript: Clone {
+    /// Absorb `bytes` into the field `label`
+    fn append(&mut self, label: &'static [u8], bytes: &[u8]);
+
+    /// Squeeze the challenge out of everything absorbed, under `label`. May panic if the transcript cannot output
+    /// `CHALLENGE_LENGTH` bytes, which the NIZK rules out when it is built
+    fn challenge(self, label: &'static [u8]) -> Challenge;
+
+    /// The number of bytes the transcript outputs, which must be at least `CHALLENGE_LENGTH`
+    fn output_len(&self) -> usize;
+
+    /// How the challenges of the NIZK are expanded into the longer strings that protocols reduce modulo the order
+    /// of their group. Defaults to the expansion over Blake2b of the standard
+    fn challenge_expansion(&self) -> ChallengeExpansion {
+        ChallengeExpansion::default()
+    }
+
+    /// The challenge of a single field appended to a copy of this transcript
+    fn hash(&self, label: &'static [u8], bytes: &[u8]) -> Challenge {
+        let mut transcript = self.clone();
+        transcript.append(label, bytes);
+        transcript.challenge(label)
+    }
+}
+
+/// A transcript statically known to output at least `CHALLENGE_LENGTH` bytes, which the infallible constructors of
+/// the NIZK require. Other transcripts, as digests shorter than 32 bytes, are only accepted by the `try_new`
+/// constructors, which check the output length
+pub trait SufficientOutput: Transcript {}
+
+impl<D: Digest + Clone> SufficientOutput for D where
+    D::OutputSize: IsGreaterOrEqual<U32, Output = True>
+{
+}
+
+impl<X: Update + ExtendableOutput + Clone + Default> SufficientOutput for XofTranscript<X> {}
+
+impl<D: Digest + Clone> Transcript for D {
+    fn append(&mut self, _label: &'static [u8], bytes: &[u8]) {
+        self.update(bytes);
+    }
+
+    fn challenge(self, _label: &'static [u8]) -> Challenge {
+        compress_digest(&self.finalize())
+    }
+
+    fn output_len(&self) -> usize {
+        D::output_size()
+    }
+}
+
+/// A transcript over an extendable-output function such as [`sha3::Shake128`] or [`sha3::Shake256`], which ignores
+/// the labels as a [`Digest`] does. The challenge is the first `CHALLENGE_LENGTH` bytes squeezed out of the XOF, so
+/// that no output is truncated away, and the challenges are expanded for the reduction modulo the group order by
+/// squeezing `XOF(DOMSEP || challenge)`
+#[derive(Clone, Default)]
+pub struct XofTranscript<X> {
+    xof: X,
+}
+
+/// The first `length` bytes squeezed out of `XOF(DOMSEP || challenge)`
+fn xof_expansion<X: Update + ExtendableOutput + Default>(
+    challenge: &Challenge,
+    length: usize,
+) -> Vec<u8> {
+    let mut xof = X::default();
+    xof.update(DOMSEP);
+    xof.update(challenge);
+    let mut bytes = vec![0u8; length];
+    xof.finalize_xof().read(&mut bytes);
+    bytes
+}
+
+impl<X: Update + ExtendableOutput + Clone + Default> Transcript for XofTranscript<X> {
+    fn append(&mut self, _label: &'static [u8], bytes: &[u8]) {
+        self.xof.update(bytes);
+    }
+
+    fn challenge(self, _label: &'static [u8]) -> Challenge {
+        let mut challenge = [0u8; CHALLENGE_LENGTH];
+        self.xof.finalize_xof().read(&mut challenge);
+        challenge
+    }
+
+    fn output_len(&self) -> usize {
+        CHALLENGE_LENGTH
+    }
+
+    fn challenge_expansion(&self) -> ChallengeExpansion {
+        ChallengeExpansion::new(xof_expansion::<X>)
+    }
+}
+
+/// A transcript over a hash function of the [`HashFunction`] registry, for hashes chosen at runtime. It ignores the
+/// labels as a [`Digest`] does
+#[derive(Clone)]
+pub struct DynTranscript {
+    hash: HashFunction,
+    hasher: Box<dyn DynDigest>,
+}
+
+impl DynTranscript {
+    /// An empty transcript over `hash`, or [`SigmaError::UnsupportedHashFunction`] if this build has no
+    /// implementation of it
+    pub fn new(hash: HashFunction) -> Result<Self, SigmaError> {
+        Ok(Self {
+            hash,
+            hasher: hash.hasher()?,
+        })
+    }
+
+    /// The hash function of the transcript
+    pub fn hash_function(&self) -> HashFunction {
+        self.hash
+    }
+}
+
+impl Transcript for DynTranscript {
+    fn append(&mut self, _label: &'static [u8], bytes: &[u8]) {
+        self.hasher.update(bytes);
+    }
+
+    fn challenge(self, _label: &'static [u8]) -> Challenge {
+        compress_digest(&self.hasher.finalize())
+    }
+
+    fn output_len(&self) -> usize {
+        self.hasher.output_size()
+    }
+}
+
+/// The encoding in which a commitment is absorbed into the Fiat-Shamir challenge, which every implementation of the
+/// standard must agree on: the compressed encoding of [`CanonicalSerialize::serialize`], with every point in its
+/// canonical affine form. A point thus has the same challenge whatever its projective coordinates, and whether its
+/// proof was decoded from the compressed or the uncompressed encoding.
+///
+/// The arkworks curves convert projective points to affine before compressing them, and the ristretto255 and
+/// secp256k1 adapters encode the canonical compressed point, so every commitment built from them absorbs
+/// canonically. A commitment type implementing [`CanonicalSerialize`] by hand must do the same
+pub trait CanonicalAbsorb {
+    /// Writes the canonical encoding of the commitment, as absorbed into the challenge
+    fn absorb_canonical<W: Write>(&self, writer: W) -> Result<(), SerializationError>;
+
+    /// The length of the canonical encoding in bytes
+    fn absorbed_size(&self) -> usize;
+}
+
+impl<T: CanonicalSerialize> CanonicalAbsorb for T {
+    fn absorb_canonical<W: Write>(&self, writer: W) -> Result<(), SerializationError> {
+        self.serialize(writer)
+    }
+
+    fn absorbed_size(&self) -> usize {
+        self.serialized_size()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use ark_bls12_377::Fr;
+    use ark_ec::ProjectiveCurve;
+    use ark_ff::PrimeField;
+    use rand::{rngs::StdRng, thread_rng, SeedableRng};
+    use sha3::{Shake128, Shake256};
+
+    use crate::{
+        encoding::encode_hex,
+        protocols::{SchnorrDLOG, SchnorrInstance},
+        DynTranscript, HashFunction, SigmaError, CHALLENGE_LENGTH, DOMSEP, NIZK,
+    };
+
+    use super::{compress_digest, Blake2b, ChallengeExpansion, Digest, Transcript, XofTranscript};
+
+    type G = ark_bls12_377::G1Projective;
+
+    #[test]
+    fn test_xof_challenge_is_first_squeezed_bytes() {
+        // SHAKE128("abc") and SHAKE256("abc") from FIPS 202, absorbed as two fields
+        fn challenge<X: digest::Update + digest::ExtendableOutput + Clone + Default>() -> String {
+            let mut transcript = XofTranscript::<X>::default();
+            transcript.append(b"first", b"ab");
+            transcript.append(b"second", b"c");
+            encode_hex(&transcript.challenge(b"challenge"))
+        }
+        assert_eq!(
+            challenge::<Shake128>(),
+            "5881092dd818bf5cf8a3ddb793fbcba74097d5c526a6d35f97b83351940f2cc8"
+        );
+        assert_eq!(
+            challenge::<Shake256>(),
+            "483366601360a8771c6863080cc4114d8db44530f8f1e1ee4f94ea37e78b5739"
+        );
+    }
+
+    #[test]
+    fn test_xof_expansion_known_answer() {
+        // The first 48 bytes of SHAKE128 and SHAKE256 of `DOMSEP` and an all-zero challenge
+        let challenge = [0u8; CHALLENGE_LENGTH];
+        let expansion = |transcript: &dyn Fn() -> ChallengeExpansion| {
+            encode_hex(&transcript().expand(&challenge, 48))
+        };
+        assert_eq!(
+            expansion(&|| XofTranscript::<Shake128>::default().challenge_expansion()),
+            "ffb0481709c344adcddd58305d0ac9e3bc84e9be388b2d6d9e0eb587705f2c2d\
+             cfe03472f2cb37e04fcd440d8e339be7"
+        );
+        assert_eq!(
+            expansion(&|| XofTranscript::<Shake256>::default().challenge_expansion()),
+            "37aee7eba9a24d90946d02f25353f44c35b1e7618800eb2b2f877d19343d807c\
+             0ad1b763b3055aef1c2bad4a4c95f1ab"
+        );
+        // The other transcripts expand with Blake2b, whose first block is `Blake2b(DOMSEP || 0 || challenge)`
+        assert_eq!(
+            expansion(&|| blake2::Blake2b::default().challenge_expansion()),
+            encode_hex(&Blake2b::digest(&[DOMSEP, &[0], &challenge].concat())[..48])
+        );
+    }
+
+    #[test]
+    fn test_shake_challenge_known_answer() {
+        // Batchable Schnorr proofs over BLS12-377 from a fixed seed, pinning the challenge of each XOF
+        fn challenge<X: digest::Update + digest::ExtendableOutput + Clone + Default>() -> String {
+            let rng = &mut StdRng::seed_from_u64(53);
+            let (instance, witness) = SchnorrInstance::<G>::generate(rng);
+            let nizk = NIZK::<SchnorrDLOG<G>, XofTranscript<X>>::new(&instance, b"ctx");
+            let proof = nizk
+                .batchable_proof(&witness, Some(b"message"), rng)
+                .unwrap();
+            let challenge = nizk.batchable_challenge(&proof, Some(b"message")).unwrap();
+            encode_hex(&challenge)
+        }
+        assert_eq!(
+            challenge::<Shake128>(),
+            "ffaf157133f90a229b89754c97dcb4dc00115ac359608666d938ea6ac6449775"
+        );
+        assert_eq!(
+            challenge::<Shake256>(),
+            "88c61414bfb0f13f1dc3a07b698492dd409c7dab90a82b505172c9526e56c569"
+        );
+    }
+
+    #[test]
+    fn test_nizk_expands_challenges_with_its_xof() {
+        let rng = &mut thread_rng();
+        let (instance, witness) = SchnorrInstance::<G>::generate(rng);
+        let nizk = NIZK::<SchnorrDLOG<G>, XofTranscript<Shake256>>::new(&instance, b"ctx");
+
+        let proof = nizk.batchable_proof(&witness, None, rng).unwrap();
+        let short_proof = nizk.short_proof(&witness, Some(b"message"), rng).unwrap();
+        assert!(nizk.batchable_verify(&proof, None).is_ok());
+        assert!(nizk.short_verify(&short_proof, Some(b"message")).is_ok());
+        assert_eq!(
+            nizk.short_verify(&short_proof, None),
+            Err(SigmaError::VerificationFailed)
+        );
+
+        // The response answers the challenge expanded with SHAKE256, not with the Blake2b expansion
+        let challenge = nizk.batchable_challenge(&proof, None).unwrap();
+        let expansion = XofTranscript::<Shake256>::default().challenge_expansion();
+        let length = (Fr::size_in_bits() + 128).div_ceil(8);
+        let expanded = Fr::from_le_bytes_mod_order(&expansion.expand(&challenge, length));
+        // Schnorr responses are `k - c * x`, so the commitment is `z * base + c * claim`
+        let check = |c: Fr| {
+            *proof.commitment()
+                == instance.base().mul(proof.response().into_repr())
+                    + instance.claim().mul(c.into_repr())
+        };
+        assert!(check(expanded));
+        assert_eq!(expansion.scalar::<Fr>(&challenge), expanded);
+        assert!(!check(ChallengeExpansion::default().scalar(&challenge)));
+    }
+
+    #[test]
+    fn test_blake2b_challenges_are_the_last_half_of_the_digest() {
+        // BLAKE2b-512("abc") from RFC 7693, whose last 32 bytes are the challenge
+        let digest = "ba80a53f981c4d0d6a2797b69f12f6e94c212f14685ac4b74b12bb6fdbffa2d1\
+                      7d87c5392aab792dc252d5de4533cc9518d38aa8dbf1925ab92386edd4009923";
+        let last_half = "7d87c5392aab792dc252d5de4533cc9518d38aa8dbf1925ab92386edd4009923";
+        assert_eq!(
+            encode_hex(&<blake2::Blake2b as digest::Digest>::digest(b"abc")),
+            digest
+        );
+        assert_eq!(
+            encode_hex(&blake2::Blake2b::default().hash(b"context", b"abc")),
+            last_half
+        );
+        let dyn_transcript = DynTranscript::new(HashFunction::Blake2b).unwrap();
+        assert_eq!(
+            encode_hex(&dyn_transcript.hash(b"context", b"abc")),
+            last_half
+        );
+        // A 32-byte digest is its own challenge
+        assert_eq!(compress_digest(&[7; 32]), [7; 32]);
+    }
+}
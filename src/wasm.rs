@@ -0,0 +1,172 @@
+use rand::{rngs::StdRng, SeedableRng};
+use wasm_bindgen::prelude::*;
+
+use crate::{
+    nizk_proofs::proof_from_bytes,
+    protocols::{SchnorrDLOG, SchnorrInstance},
+    ristretto::RistrettoElement,
+    BatchableProof, SigmaError, NIZK,
+};
+
+type Nizk = NIZK<SchnorrDLOG<RistrettoElement>, blake2::Blake2s>;
+
+/// The NIZK of the instance of canonical encoding `instance_bytes` under the context `ctx`
+fn nizk(instance_bytes: &[u8], ctx: &[u8]) -> Result<Nizk, SigmaError> {
+    let instance: SchnorrInstance<RistrettoElement> = proof_from_bytes(instance_bytes, true)?;
+    NIZK::try_new(&instance, ctx)
+}
+
+/// [`schnorr_prove`], with the commitment randomness seeded by `seed` together with the witness and the message
+pub(crate) fn prove(
+    instance_bytes: &[u8],
+    witness_bytes: &[u8],
+    ctx: &[u8],
+    message: Option<&[u8]>,
+    seed: [u8; 32],
+) -> Result<Vec<u8>, SigmaError> {
+    let witness = proof_from_bytes(witness_bytes, true)?;
+    let proof = nizk(instance_bytes, ctx)?.batchable_proof(
+        &witness,
+        message,
+        &mut StdRng::from_seed(seed),
+    )?;
+    Ok(proof.to_bytes_compressed())
+}
+
+/// [`schnorr_verify`], with the error of a rejected proof
+pub(crate) fn verify(
+    instance_bytes: &[u8],
+    proof_bytes: &[u8],
+    ctx: &[u8],
+    message: Option<&[u8]>,
+) -> Result<(), SigmaError> {
+    let proof = BatchableProof::from_bytes_compressed(proof_bytes)?;
+    nizk(instance_bytes, ctx)?.batchable_verify(&proof, message)
+}
+
+/// Proves knowledge of the witness of canonical encoding `witness_bytes`, a ristretto255 scalar, for the Schnorr
+/// instance of canonical encoding `instance_bytes` under the context `ctx`, bound to `message` unless it is
+/// `undefined`. Returns the compressed batchable proof, which the NIZK of the crate over ristretto255 with Blake2s
+/// verifies, and throws the [`SigmaError`] of inputs that do not decode, of an invalid instance or witness, or of a
+/// browser without `crypto.getRandomValues`
+#[wasm_bindgen]
+pub fn schnorr_prove(
+    instance_bytes: &[u8],
+    witness_bytes: &[u8],
+    ctx: &[u8],
+    message: Option<Vec<u8>>,
+) -> Result<Vec<u8>, JsError> {
+    let mut seed = [0; 32];
+    getrandom::getrandom(&mut seed).map_err(|error| JsError::new(&error.to_string()))?;
+    prove(instance_bytes, witness_bytes, ctx, message.as_deref(), seed)
+        .map_err(|error| JsError::new(&error.to_string()))
+}
+
+/// Whether `proof_bytes` is a valid compressed batchable proof for the Schnorr instance of canonical encoding
+/// `instance_bytes` under the context `ctx` and `message`, as in [`schnorr_prove`]. Inputs that do not decode and
+/// invalid instances are rejected like invalid proofs
+#[wasm_bindgen]
+pub fn schnorr_verify(
+    instance_bytes: &[u8],
+    proof_bytes: &[u8],
+    ctx: &[u8],
+    message: Option<Vec<u8>>,
+) -> bool {
+    verify(instance_bytes, proof_bytes, ctx, message.as_deref()).is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_serialize::SerializationError;
+    use rand::{thread_rng, Rng};
+
+    use crate::{
+        nizk_proofs::proof_bytes,
+        protocols::{SchnorrDLOG, SchnorrInstance},
+        ristretto::RistrettoElement,
+        BatchableProof, SigmaError, NIZK,
+    };
+
+    use super::{prove, schnorr_verify, verify};
+
+    #[test]
+    fn test_prove_and_verify_bytes() {
+        let rng = &mut thread_rng();
+        let (instance, witness) = SchnorrInstance::<RistrettoElement>::generate(rng);
+        let (instance_bytes, witness_bytes) =
+            (proof_bytes(&instance, true), proof_bytes(&witness, true));
+
+        for message in [None, Some(&b""[..]), Some(b"message")] {
+            let proof = prove(&instance_bytes, &witness_bytes, b"ctx", message, rng.gen()).unwrap();
+            assert!(schnorr_verify(
+                &instance_bytes,
+                &proof,
+                b"ctx",
+                message.map(Vec::from)
+            ));
+
+            // The proofs are those of the NIZK of the crate
+            let nizk =
+                NIZK::<SchnorrDLOG<RistrettoElement>, blake2::Blake2s>::new(&instance, b"ctx");
+            let decoded = BatchableProof::from_bytes_compressed(&proof).unwrap();
+            assert!(nizk.batchable_verify(&decoded, message).is_ok());
+
+            assert!(!schnorr_verify(
+                &instance_bytes,
+                &proof,
+                b"other",
+                message.map(Vec::from)
+            ));
+            assert!(!schnorr_verify(
+                &instance_bytes,
+                &proof,
+                b"ctx",
+                Some(b"other".to_vec())
+            ));
+            assert!(!schnorr_verify(
+                &instance_bytes,
+                &proof[1..],
+                b"ctx",
+                message.map(Vec::from)
+            ));
+        }
+    }
+
+    #[test]
+    fn test_reject_malformed_bytes() {
+        let rng = &mut thread_rng();
+        let (instance, witness) = SchnorrInstance::<RistrettoElement>::generate(rng);
+        let (instance_bytes, witness_bytes) =
+            (proof_bytes(&instance, true), proof_bytes(&witness, true));
+        let invalid = Err(SigmaError::SerializationError(
+            SerializationError::InvalidData,
+        ));
+
+        assert_eq!(
+            prove(
+                &instance_bytes[1..],
+                &witness_bytes,
+                b"ctx",
+                None,
+                rng.gen()
+            ),
+            invalid
+        );
+        assert_eq!(
+            prove(&instance_bytes, &[0xff; 32], b"ctx", None, rng.gen()),
+            invalid
+        );
+
+        let generator = instance_bytes[..32].to_vec();
+        let identity = [0; 32];
+        assert_eq!(
+            verify(
+                &[&identity[..], &generator].concat(),
+                &[0; 64],
+                b"ctx",
+                None
+            ),
+            Err(SigmaError::InvalidInstance)
+        );
+    }
+}
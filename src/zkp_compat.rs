@@ -0,0 +1,363 @@
+use ark_serialize::{CanonicalDeserialize, SerializationError};
+use curve25519_dalek::scalar::Scalar;
+use rand::Rng;
+
+use crate::{
+    interactive_proofs::canonical_bytes,
+    protocols::SchnorrInstance,
+    ristretto::{RistrettoElement, RistrettoScalar},
+    SigmaError, SigmaGroup,
+};
+
+pub use crate::merlin::MerlinTranscript;
+
+/// The domain separator the toolbox of `zkp` 0.8 appends before the label of every proof
+const ZKP_DOMSEP: &[u8] = b"schnorrzkp/1.0/ristretto255";
+
+/// Length in bytes of a scalar, and of a compressed ristretto255 element
+const ENCODING_LENGTH: usize = 32;
+
+/// Length in bytes of the length prefix of the responses
+const LENGTH_PREFIX: usize = 8;
+
+/// A `CompactProof` of the dalek `zkp` crate: the challenge and a response for every secret variable.
+///
+/// Its bytes are those of the `serde` encoding of `CompactProof` under `bincode`, the usual way of shipping these
+/// proofs: the 32-byte challenge, the number of responses as a little-endian `u64`, then the 32-byte responses.
+/// Scalars are little endian and must be canonical
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ZkpCompactProof {
+    challenge: RistrettoScalar,
+    responses: Vec<RistrettoScalar>,
+}
+
+impl ZkpCompactProof {
+    /// The challenge
+    pub fn challenge(&self) -> RistrettoScalar {
+        self.challenge
+    }
+
+    /// The responses, in the order the secret variables are declared
+    pub fn responses(&self) -> &[RistrettoScalar] {
+        &self.responses
+    }
+
+    /// The `bincode` encoding of the proof
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = canonical_bytes(&self.challenge);
+        bytes.extend((self.responses.len() as u64).to_le_bytes());
+        for response in &self.responses {
+            bytes.extend(canonical_bytes(response));
+        }
+        bytes
+    }
+
+    /// Decodes the `bincode` encoding of a proof, rejecting non-canonical scalars, a count of responses that
+    /// disagrees with the length and trailing bytes with [`SerializationError::InvalidData`]
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, SigmaError> {
+        let invalid = || SigmaError::from(SerializationError::InvalidData);
+        if bytes.len() < ENCODING_LENGTH + LENGTH_PREFIX {
+            return Err(invalid());
+        }
+        let (challenge, rest) = bytes.split_at(ENCODING_LENGTH);
+        let (count, responses) = rest.split_at(LENGTH_PREFIX);
+
+        let count = u64::from_le_bytes(count.try_into().unwrap());
+        if count.checked_mul(ENCODING_LENGTH as u64) != Some(responses.len() as u64) {
+            return Err(invalid());
+        }
+
+        Ok(Self {
+            challenge: RistrettoScalar::deserialize(challenge)?,
+            responses: responses
+                .chunks_exact(ENCODING_LENGTH)
+                .map(RistrettoScalar::deserialize)
+                .collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+/// A Schnorr DLOG statement declared with the `define_proof!` macro of the dalek `zkp` crate, whose proofs verify
+/// under the other crate and the other way around. The default names of the variables match
+///
+/// ```text
+/// define_proof! {dlog, "<proof label>", (x), (A), (G) : A = (x * G)}
+/// ```
+///
+/// where `x` is the witness, `A` the claim of the instance and `G` its base.
+///
+/// The transcript is the one of `zkp` 0.8 over a Merlin transcript that the caller creates, with the label it
+/// gives `merlin::Transcript::new`, and possibly binds to other data first:
+/// 1. `dom-sep`: `schnorrzkp/1.0/ristretto255`, then `dom-sep`: the proof label
+/// 2. `scvar`: the name of the witness
+/// 3. `ptvar`: the name of the claim, then `val`: the compressed claim, and the same for the base, as the
+///    instance variables come before the common ones
+/// 4. `blindcom`: the name of the claim, then `val`: the compressed commitment `k * G`
+/// 5. the challenge `c`: 64 bytes of `challenge_bytes` under `chal`, reduced modulo the group order
+///
+/// The response is `r = x * c + k`, and the verifier recomputes the commitment as `r * G - c * A`. As in `zkp`,
+/// an identity claim or base is rejected
+#[derive(Debug, Clone)]
+pub struct ZkpSchnorr {
+    base: RistrettoElement,
+    claim: RistrettoElement,
+    proof_label: Vec<u8>,
+    secret_name: Vec<u8>,
+    claim_name: Vec<u8>,
+    base_name: Vec<u8>,
+}
+
+impl ZkpSchnorr {
+    /// The statement for `instance` with the proof label of `define_proof!`. Returns
+    /// [`SigmaError::InvalidInstance`] for an identity base or claim
+    pub fn new(
+        instance: &SchnorrInstance<RistrettoElement>,
+        proof_label: &str,
+    ) -> Result<Self, SigmaError> {
+        let (base, claim) = (instance.base(), instance.claim());
+        if base.is_identity() || claim.is_identity() {
+            return Err(SigmaError::InvalidInstance);
+        }
+
+        Ok(Self {
+            base,
+            claim,
+            proof_label: proof_label.into(),
+            secret_name: b"x".to_vec(),
+            claim_name: b"A".to_vec(),
+            base_name: b"G".to_vec(),
+        })
+    }
+
+    /// Names the witness, the claim and the base as the variables of another `define_proof!` statement
+    pub fn with_names(mut self, secret: &str, claim: &str, base: &str) -> Self {
+        self.secret_name = secret.into();
+        self.claim_name = claim.into();
+        self.base_name = base.into();
+        self
+    }
+
+    /// Appends the statement and `commitment` to `transcript` and derives the challenge
+    fn challenge(
+        &self,
+        transcript: &mut MerlinTranscript,
+        commitment: &RistrettoElement,
+    ) -> RistrettoScalar {
+        transcript.append_message(b"dom-sep", ZKP_DOMSEP);
+        transcript.append_message(b"dom-sep", &self.proof_label);
+        transcript.append_message(b"scvar", &self.secret_name);
+        for (name, point) in [
+            (&self.claim_name, &self.claim),
+            (&self.base_name, &self.base),
+        ] {
+            transcript.append_message(b"ptvar", name);
+            transcript.append_message(b"val", &canonical_bytes(point));
+        }
+        transcript.append_message(b"blindcom", &self.claim_name);
+        transcript.append_message(b"val", &canonical_bytes(commitment));
+
+        let mut challenge = [0u8; 2 * ENCODING_LENGTH];
+        transcript.challenge_bytes(b"chal", &mut challenge);
+        RistrettoScalar(Scalar::from_bytes_mod_order_wide(&challenge))
+    }
+
+    /// Prove the knowledge of `witness` over `transcript`, as `dlog::prove_compact` does
+    pub fn prove<R: Rng>(
+        &self,
+        transcript: &mut MerlinTranscript,
+        witness: &RistrettoScalar,
+        rng: &mut R,
+    ) -> ZkpCompactProof {
+        let nonce = RistrettoElement::random_scalar(rng);
+        let challenge = self.challenge(transcript, &self.base.scalar_mul(&nonce));
+
+        ZkpCompactProof {
+            challenge,
+            responses: vec![*witness * challenge + nonce],
+        }
+    }
+
+    /// Verify `proof` over `transcript`, as `dlog::verify_compact` does. A proof with other than one response is
+    /// rejected with [`SigmaError::LengthMismatch`]
+    pub fn verify(
+        &self,
+        transcript: &mut MerlinTranscript,
+        proof: &ZkpCompactProof,
+    ) -> Result<(), SigmaError> {
+        let response = match proof.responses[..] {
+            [response] => response,
+            _ => return Err(SigmaError::LengthMismatch),
+        };
+
+        let minus_challenge = RistrettoScalar(-proof.challenge.0);
+        let commitment = RistrettoElement::multi_scalar_mul(
+            &[self.base, self.claim],
+            &[response, minus_challenge],
+        );
+        if self.challenge(transcript, &commitment) == proof.challenge {
+            Ok(())
+        } else {
+            Err(SigmaError::VerificationFailed)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_serialize::{CanonicalDeserialize, SerializationError};
+    use rand::{rngs::StdRng, thread_rng, SeedableRng};
+
+    use crate::{
+        encoding::decode_hex,
+        protocols::SchnorrInstance,
+        ristretto::{RistrettoElement, RistrettoScalar},
+        SigmaError, SigmaGroup,
+    };
+
+    use super::{MerlinTranscript, ZkpCompactProof, ZkpSchnorr};
+
+    /// Proofs made by `zkp` 0.8 itself for its `dlog` example, see `tests/vectors/zkp_dlog/main.rs`
+    const ZKP_DLOG: &str = include_str!("../tests/vectors/zkp_dlog.txt");
+
+    /// The witness, generator, claim and proof bytes of every vector of [`ZKP_DLOG`]
+    fn zkp_vectors() -> Vec<[Vec<u8>; 4]> {
+        let lines: Vec<_> = ZKP_DLOG
+            .lines()
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(|line| {
+                let (_, value) = line.split_once(" = ").unwrap();
+                decode_hex(value).unwrap()
+            })
+            .collect();
+        lines
+            .chunks(4)
+            .map(|vector| vector.to_vec().try_into().unwrap())
+            .collect()
+    }
+
+    /// The statement of `zkp`'s `dlog` example, for a witness drawn from a seeded rng
+    fn statement(rng: &mut StdRng) -> (ZkpSchnorr, RistrettoScalar) {
+        let witness = RistrettoElement::random_scalar(rng);
+        let generator = RistrettoElement::generator();
+        let instance = SchnorrInstance::new(generator, generator.scalar_mul(&witness));
+        (ZkpSchnorr::new(&instance, "DLog").unwrap(), witness)
+    }
+
+    #[test]
+    fn test_zkp_prove_and_verify() {
+        let rng = &mut StdRng::from_rng(thread_rng()).unwrap();
+        let (statement, witness) = statement(rng);
+        let proof = statement.prove(&mut MerlinTranscript::new(b"DLogTest"), &witness, rng);
+        assert!(statement
+            .verify(&mut MerlinTranscript::new(b"DLogTest"), &proof)
+            .is_ok());
+
+        let decoded = ZkpCompactProof::from_bytes(&proof.to_bytes()).unwrap();
+        assert_eq!(decoded, proof);
+        assert!(statement
+            .verify(&mut MerlinTranscript::new(b"DLogTest"), &decoded)
+            .is_ok());
+
+        // The transcript label, the data bound before the proof, the proof label and the names are all bound
+        let mut bound = MerlinTranscript::new(b"DLogTest");
+        bound.append_message(b"session", b"1");
+        for (transcript, statement) in [
+            (MerlinTranscript::new(b"Other"), statement.clone()),
+            (bound, statement.clone()),
+            (
+                MerlinTranscript::new(b"DLogTest"),
+                statement.clone().with_names("y", "A", "G"),
+            ),
+            (
+                MerlinTranscript::new(b"DLogTest"),
+                statement.clone().with_names("x", "G", "A"),
+            ),
+        ] {
+            assert_eq!(
+                statement.verify(&mut transcript.clone(), &proof),
+                Err(SigmaError::VerificationFailed)
+            );
+        }
+    }
+
+    #[test]
+    fn test_zkp_vectors() {
+        let vectors = zkp_vectors();
+        assert_eq!(vectors.len(), 3);
+
+        for [witness, generator, claim, proof] in vectors {
+            let witness = RistrettoScalar::deserialize(&witness[..]).unwrap();
+            let generator = RistrettoElement::deserialize(&generator[..]).unwrap();
+            let claim = RistrettoElement::deserialize(&claim[..]).unwrap();
+            assert_eq!(generator, RistrettoElement::generator());
+            assert_eq!(claim, generator.scalar_mul(&witness));
+
+            let statement =
+                ZkpSchnorr::new(&SchnorrInstance::new(generator, claim), "DLog").unwrap();
+            let decoded = ZkpCompactProof::from_bytes(&proof).unwrap();
+            assert_eq!(decoded.to_bytes(), proof);
+            assert!(statement
+                .verify(&mut MerlinTranscript::new(b"DLogTest"), &decoded)
+                .is_ok());
+            assert_eq!(
+                statement.verify(&mut MerlinTranscript::new(b"DLEQTest"), &decoded),
+                Err(SigmaError::VerificationFailed)
+            );
+        }
+    }
+
+    #[test]
+    fn test_zkp_compact_proof_encoding() {
+        let invalid = Err(SigmaError::SerializationError(
+            SerializationError::InvalidData,
+        ));
+        let [.., bytes] = zkp_vectors().remove(0);
+        assert_eq!(bytes.len(), 72);
+
+        // A missing or trailing byte, a count that disagrees with the length and a non-canonical scalar
+        assert_eq!(ZkpCompactProof::from_bytes(&bytes[..71]), invalid);
+        assert_eq!(
+            ZkpCompactProof::from_bytes(&[&bytes[..], &[0]].concat()),
+            invalid
+        );
+        let mut count = bytes.clone();
+        count[32] = 2;
+        assert_eq!(ZkpCompactProof::from_bytes(&count), invalid);
+        let mut count = bytes.clone();
+        count[39] = 0x80;
+        assert_eq!(ZkpCompactProof::from_bytes(&count), invalid);
+        let mut scalar = bytes.clone();
+        scalar[40..].copy_from_slice(&[0xff; 32]);
+        assert_eq!(ZkpCompactProof::from_bytes(&scalar), invalid);
+
+        // Proofs of other statements decode, but do not verify as a Schnorr proof
+        let rng = &mut StdRng::seed_from_u64(0x5eed);
+        let (statement, _) = statement(rng);
+        for responses in [0, 2] {
+            let mut other = bytes[..32].to_vec();
+            other.extend((responses as u64).to_le_bytes());
+            other.extend(bytes[40..].repeat(responses));
+            let proof = ZkpCompactProof::from_bytes(&other).unwrap();
+            assert_eq!(proof.responses().len(), responses);
+            assert_eq!(
+                statement.verify(&mut MerlinTranscript::new(b"DLogTest"), &proof),
+                Err(SigmaError::LengthMismatch)
+            );
+        }
+    }
+
+    #[test]
+    fn test_zkp_rejects_identity_points() {
+        let generator = RistrettoElement::generator();
+        let identity = generator - generator;
+        for instance in [
+            SchnorrInstance::new(generator, identity),
+            SchnorrInstance::new(identity, generator),
+        ] {
+            assert_eq!(
+                ZkpSchnorr::new(&instance, "DLog").err(),
+                Some(SigmaError::InvalidInstance)
+            );
+        }
+    }
+}
@@ -0,0 +1,112 @@
+#![cfg(feature = "cbor")]
+
+use ark_serialize::CanonicalSerialize;
+use rand::{rngs::StdRng, SeedableRng};
+use sigma_protocol_standard::{
+    cbor::{instance_from_cbor, instance_to_cbor},
+    protocols::{SchnorrDLOG, SchnorrInstance},
+    BatchableProof, HashFunction, ProofEnvelope, ShortProof, NIZK,
+};
+
+type G = ark_bls12_377::G1Projective;
+type Schnorr = SchnorrDLOG<G>;
+
+/// The CBOR encodings of a Schnorr DLOG instance and its proofs over BLS12-377
+const SCHNORR_BLS12_377_CBOR: &str = include_str!("vectors/schnorr_bls12_377_cbor.txt");
+
+const CTX: &[u8] = b"cbor vectors";
+
+const SEED: u64 = 0x5eed;
+
+const HEADER: &str = "\
+# CBOR encodings of a Schnorr DLOG instance over BLS12-377 G1 with the prime subgroup generator as base, and of
+# proofs for it with the context \"cbor vectors\".
+# Byte strings hold the compressed arkworks encodings of the values.
+# instance = {1: label, 6: instance}
+# batchable_proof = {3: commitment, 5: response}, with Blake2s
+# short_proof = {4: challenge, 5: response}, with Blake2s
+# envelope = {0: version, 1: label, 2: hash id, 3: commitment, 5: response}, with SHA-256
+";
+
+fn decode_hex(hex: &str) -> Vec<u8> {
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).expect("invalid hex in test vector"))
+        .collect()
+}
+
+fn encode_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+fn field(name: &str) -> Vec<u8> {
+    SCHNORR_BLS12_377_CBOR
+        .lines()
+        .filter(|line| !line.starts_with('#'))
+        .find_map(|line| line.strip_prefix(name)?.strip_prefix(" = "))
+        .map(decode_hex)
+        .unwrap_or_else(|| panic!("missing `{}` in test vector", name))
+}
+
+/// The vectors from a seeded RNG, in the `key = hex` format of the fixture
+fn generate_vectors() -> String {
+    let rng = &mut StdRng::seed_from_u64(SEED);
+    let (instance, witness) = SchnorrInstance::<G>::generate(rng);
+    let nizk = NIZK::<Schnorr, blake2::Blake2s>::new(&instance, CTX);
+    let dyn_nizk =
+        NIZK::<Schnorr, _>::with_hash_function(&instance, CTX, HashFunction::SHA256).unwrap();
+
+    let batchable_proof = nizk.batchable_proof(&witness, None, rng).unwrap();
+    let short_proof = nizk.short_proof(&witness, None, rng).unwrap();
+    let envelope = dyn_nizk.prove_enveloped(&witness, None, rng).unwrap();
+
+    let mut witness_bytes = Vec::new();
+    witness.serialize(&mut witness_bytes).unwrap();
+
+    format!(
+        "{}\nwitness = {}\ninstance = {}\nbatchable_proof = {}\nshort_proof = {}\nenvelope = {}\n",
+        HEADER,
+        encode_hex(&witness_bytes),
+        encode_hex(&instance_to_cbor::<Schnorr>(&instance)),
+        encode_hex(&batchable_proof.to_cbor()),
+        encode_hex(&short_proof.to_cbor()),
+        encode_hex(&envelope.to_cbor()),
+    )
+}
+
+#[test]
+fn test_schnorr_cbor_vectors_decode_and_verify() {
+    let instance_bytes = field("instance");
+    let instance = instance_from_cbor::<Schnorr>(&instance_bytes).unwrap();
+    assert_eq!(instance_to_cbor::<Schnorr>(&instance), instance_bytes);
+    let nizk = NIZK::<Schnorr, blake2::Blake2s>::new(&instance, CTX);
+    let dyn_nizk =
+        NIZK::<Schnorr, _>::with_hash_function(&instance, CTX, HashFunction::SHA256).unwrap();
+
+    let batchable_bytes = field("batchable_proof");
+    let batchable_proof = BatchableProof::<Schnorr>::from_cbor(&batchable_bytes).unwrap();
+    assert_eq!(batchable_proof.to_cbor(), batchable_bytes);
+    assert!(nizk.batchable_verify(&batchable_proof, None).is_ok());
+
+    let short_bytes = field("short_proof");
+    let short_proof = ShortProof::<Schnorr>::from_cbor(&short_bytes).unwrap();
+    assert_eq!(short_proof.to_cbor(), short_bytes);
+    assert!(nizk.short_verify(&short_proof, None).is_ok());
+
+    let envelope_bytes = field("envelope");
+    let envelope = ProofEnvelope::<BatchableProof<Schnorr>>::from_cbor(&envelope_bytes).unwrap();
+    assert_eq!(envelope.to_cbor(), envelope_bytes);
+    assert!(dyn_nizk.verify_enveloped(&envelope, None).is_ok());
+}
+
+#[test]
+fn test_schnorr_cbor_vectors_are_deterministic() {
+    assert_eq!(generate_vectors(), SCHNORR_BLS12_377_CBOR);
+}
+
+/// Prints the vectors, run with `cargo test --features cbor -- --ignored --nocapture`
+#[test]
+#[ignore]
+fn generate_schnorr_cbor_vectors() {
+    print!("{}", generate_vectors());
+}
@@ -0,0 +1,198 @@
+use ark_bls12_377::{Fr, G1Projective};
+use ark_ec::ProjectiveCurve;
+use ark_ff::{PrimeField, UniformRand, Zero};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use rand::{thread_rng, Rng};
+use sigma_protocol_standard::{
+    protocols::{SchnorrDLOG, SchnorrInstance},
+    BatchableProof, Challenge, ChallengeExpansion, MultiRoundProof, MultiRoundSigmaProtocol,
+    SigmaError, NIZK,
+};
+
+type Hash = blake2::Blake2s;
+type G = G1Projective;
+type F = Fr;
+
+const CTX: &[u8] = b"multi-round";
+
+/// A toy five-move proof of knowledge of `x` with `X = x * base`: the prover sends `A = a * base`, receives `c1`,
+/// sends `B = b * base`, receives `c2` and answers `z = a + c1 * b + c2 * x`, which the verifier checks as
+/// `z * base = A + c1 * B + c2 * X`. Two accepting transcripts sharing `A`, `c1` and `B` give `x`
+struct TwoChallengeDlog {
+    base: G,
+    public: G,
+}
+
+struct TwoChallengeState {
+    witness: F,
+    a: F,
+    b: F,
+    c1: F,
+}
+
+impl MultiRoundSigmaProtocol for TwoChallengeDlog {
+    type Instance = (G, G);
+    type Witness = F;
+    type Message = G;
+    type ProverState = TwoChallengeState;
+    type Response = F;
+    type Challenge = F;
+
+    const PROTOCOL_ID: &'static [u8] = b"zkpstd/sigma/test/two-challenge-dlog";
+    const ROUNDS: usize = 2;
+
+    fn new(&(base, public): &(G, G)) -> Self {
+        Self { base, public }
+    }
+
+    fn challenge_from_bytes(
+        challenge: &Challenge,
+        expansion: ChallengeExpansion,
+    ) -> Result<F, SigmaError> {
+        Ok(expansion.scalar(challenge))
+    }
+
+    fn instance_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        (self.base, self.public).serialize(&mut bytes).unwrap();
+        bytes
+    }
+
+    fn witness_bytes(&self, witness: &F) -> Vec<u8> {
+        encode(witness)
+    }
+
+    fn prover_commit<R: Rng>(
+        &self,
+        witness: &F,
+        _expansion: ChallengeExpansion,
+        rng: &mut R,
+    ) -> Result<(G, TwoChallengeState), SigmaError> {
+        let state = TwoChallengeState {
+            witness: *witness,
+            a: F::rand(rng),
+            b: F::zero(),
+            c1: F::zero(),
+        };
+        Ok((self.base.mul(state.a.into_repr()), state))
+    }
+
+    fn prover_message<R: Rng>(
+        &self,
+        state: &mut TwoChallengeState,
+        round: usize,
+        challenge: &F,
+        rng: &mut R,
+    ) -> Result<G, SigmaError> {
+        assert_eq!(
+            round, 0,
+            "only the first challenge is answered by a message"
+        );
+        state.b = F::rand(rng);
+        state.c1 = *challenge;
+        Ok(self.base.mul(state.b.into_repr()))
+    }
+
+    fn prover_response(&self, state: &TwoChallengeState, c2: &F) -> Result<F, SigmaError> {
+        Ok(state.a + state.c1 * state.b + *c2 * state.witness)
+    }
+
+    fn verifier(&self, messages: &[G], challenges: &[F], z: &F) -> Result<(), SigmaError> {
+        let ([a, b], [c1, c2]) = (messages, challenges) else {
+            return Err(SigmaError::LengthMismatch);
+        };
+        if self.base.mul(z.into_repr())
+            == *a + b.mul(c1.into_repr()) + self.public.mul(c2.into_repr())
+        {
+            Ok(())
+        } else {
+            Err(SigmaError::VerificationFailed)
+        }
+    }
+}
+
+fn two_challenge_statement<R: Rng>(rng: &mut R) -> ((G, G), F) {
+    let base = G::prime_subgroup_generator();
+    let witness = F::rand(rng);
+    ((base, base.mul(witness.into_repr())), witness)
+}
+
+fn encode<X: CanonicalSerialize>(value: &X) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    value.serialize(&mut bytes).unwrap();
+    bytes
+}
+
+#[test]
+fn test_two_challenge_proof_verifies() {
+    let rng = &mut thread_rng();
+    let (instance, witness) = two_challenge_statement(rng);
+    let nizk = NIZK::<TwoChallengeDlog, Hash>::new(&instance, CTX);
+
+    let proof = nizk
+        .multi_round_proof(&witness, Some(b"message"), rng)
+        .unwrap();
+    assert!(nizk.multi_round_verify(&proof, Some(b"message")).is_ok());
+    assert!(nizk.multi_round_verify(&proof, Some(b"other")).is_err());
+    assert!(
+        NIZK::<TwoChallengeDlog, Hash>::new(&instance, b"other context")
+            .multi_round_verify(&proof, Some(b"message"))
+            .is_err()
+    );
+
+    // Two group elements and a scalar, with no length prefix
+    let bytes = encode(&proof);
+    assert_eq!(bytes.len(), 2 * 48 + 32);
+    let decoded = MultiRoundProof::<TwoChallengeDlog>::deserialize(&bytes[..]).unwrap();
+    assert!(nizk.multi_round_verify(&decoded, Some(b"message")).is_ok());
+
+    let (_, other_witness) = two_challenge_statement(rng);
+    let forged = nizk.multi_round_proof(&other_witness, None, rng).unwrap();
+    assert!(nizk.multi_round_verify(&forged, None).is_err());
+}
+
+#[test]
+fn test_every_challenge_depends_on_the_transcript_so_far() {
+    let rng = &mut thread_rng();
+    let (instance, witness) = two_challenge_statement(rng);
+    let nizk = NIZK::<TwoChallengeDlog, Hash>::new(&instance, CTX);
+    let proof = nizk.multi_round_proof(&witness, None, rng).unwrap();
+    let bytes = encode(&proof);
+
+    // Replacing the message of either round with another point changes the challenges that follow it, and the
+    // response no longer answers them
+    let other = encode(&G::prime_subgroup_generator());
+    for round in 0..2 {
+        let mut tampered = bytes.clone();
+        tampered[48 * round..48 * (round + 1)].copy_from_slice(&other);
+        let tampered = MultiRoundProof::<TwoChallengeDlog>::deserialize(&tampered[..]).unwrap();
+        assert!(nizk.multi_round_verify(&tampered, None).is_err());
+    }
+}
+
+#[test]
+fn test_three_move_protocols_prove_in_a_single_round() {
+    let rng = &mut thread_rng();
+    let (instance, witness) = SchnorrInstance::<G>::generate(rng);
+    let nizk = NIZK::<SchnorrDLOG<G>, Hash>::new(&instance, CTX);
+
+    let proof = nizk
+        .multi_round_proof(&witness, Some(b"message"), rng)
+        .unwrap();
+    assert!(nizk.multi_round_verify(&proof, Some(b"message")).is_ok());
+
+    // A single round absorbs the commitment as the batchable proofs do, so the proof is a batchable proof
+    let batchable = BatchableProof::<SchnorrDLOG<G>>::deserialize(&encode(&proof)[..]).unwrap();
+    assert!(nizk.batchable_verify(&batchable, Some(b"message")).is_ok());
+    let batchable = nizk
+        .batchable_proof(&witness, Some(b"message"), rng)
+        .unwrap();
+    let proof = MultiRoundProof::<SchnorrDLOG<G>>::deserialize(&encode(&batchable)[..]).unwrap();
+    assert!(nizk.multi_round_verify(&proof, Some(b"message")).is_ok());
+
+    let schnorr: SchnorrDLOG<G> = MultiRoundSigmaProtocol::new(&instance);
+    assert!(matches!(
+        MultiRoundSigmaProtocol::verifier(&schnorr, &[], &[], &F::zero()),
+        Err(SigmaError::LengthMismatch)
+    ));
+}
@@ -0,0 +1,149 @@
+use ark_bls12_377::{Fr, G1Projective};
+use ark_ec::ProjectiveCurve;
+use ark_ff::{PrimeField, UniformRand};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use rand::{thread_rng, Rng};
+use sigma_protocol_standard::{
+    composition::{AndComposition, Either, OrComposition},
+    protocols::{
+        Okamoto, OkamotoInstance, PedersenOpening, PedersenParams, SchnorrDLOG, SchnorrInstance,
+    },
+    BatchableProof, CommitmentRecoverable, SigmaError, SigmaProtocol, NIZK,
+};
+
+type Hash = blake2::Blake2s;
+type G = G1Projective;
+type F = Fr;
+
+/// (knows sk_A OR knows sk_B) AND knows the opening of C
+type AndOfOrs = AndComposition<OrComposition<SchnorrDLOG<G>, SchnorrDLOG<G>>, Okamoto<G>>;
+
+/// (knows sk_A AND knows the opening of C) OR knows sk_B
+type OrOfAnds = OrComposition<AndComposition<SchnorrDLOG<G>, Okamoto<G>>, SchnorrDLOG<G>>;
+
+const CTX: &[u8] = b"nested composition";
+
+struct Statements {
+    key_a: SchnorrInstance<G>,
+    key_b: SchnorrInstance<G>,
+    opening: OkamotoInstance<G>,
+    secret_a: F,
+    secret_b: F,
+    opening_witness: (F, F),
+}
+
+fn statements_setup<R: Rng>(rng: &mut R) -> Statements {
+    let generator = G::prime_subgroup_generator();
+    let (secret_a, secret_b) = (F::rand(rng), F::rand(rng));
+    let params = PedersenParams::new(G::rand(rng), G::rand(rng));
+    let opening = PedersenOpening::new(F::rand(rng), F::rand(rng));
+
+    Statements {
+        key_a: SchnorrInstance::new(generator, generator.mul(secret_a.into_repr())),
+        key_b: SchnorrInstance::new(generator, generator.mul(secret_b.into_repr())),
+        opening: OkamotoInstance::new(
+            params.generator(),
+            params.blinding_base(),
+            params.commit_opening(&opening),
+        ),
+        secret_a,
+        secret_b,
+        opening_witness: (opening.message(), opening.blinding()),
+    }
+}
+
+/// Proves through the NIZK, then checks the batchable proof after a serialization round trip and the short proof
+fn prove_and_verify<S: CommitmentRecoverable, R: Rng>(
+    instance: &S::Instance,
+    witness: &S::Witness,
+    rng: &mut R,
+) -> Result<(), SigmaError> {
+    let nizk = NIZK::<S, Hash>::new(instance, CTX);
+
+    let proof = nizk.batchable_proof(witness, None, rng)?;
+    let mut bytes = Vec::new();
+    proof.serialize(&mut bytes).unwrap();
+    let decoded = BatchableProof::<S>::deserialize(&bytes[..]).unwrap();
+    nizk.batchable_verify(&decoded, None)?;
+
+    let short_proof = nizk.short_proof(witness, None, rng)?;
+    nizk.short_verify(&short_proof, None)
+}
+
+#[test]
+fn test_and_of_ors() {
+    let rng = &mut thread_rng();
+    let statements = statements_setup(rng);
+    let instance = ((statements.key_a, statements.key_b), statements.opening);
+
+    for key in [
+        Either::Left(statements.secret_a),
+        Either::Right(statements.secret_b),
+    ] {
+        let witness = (key, statements.opening_witness);
+        assert!(prove_and_verify::<AndOfOrs, _>(&instance, &witness, rng).is_ok());
+    }
+
+    let no_key = (Either::Left(F::rand(rng)), statements.opening_witness);
+    let no_opening = (
+        Either::Right(statements.secret_b),
+        (statements.opening_witness.0, F::rand(rng)),
+    );
+    for witness in [no_key, no_opening] {
+        assert_eq!(
+            prove_and_verify::<AndOfOrs, _>(&instance, &witness, rng),
+            Err(SigmaError::VerificationFailed)
+        );
+    }
+}
+
+#[test]
+fn test_or_of_ands() {
+    let rng = &mut thread_rng();
+    let statements = statements_setup(rng);
+    let instance = ((statements.key_a, statements.opening), statements.key_b);
+
+    let first_branch = Either::Left((statements.secret_a, statements.opening_witness));
+    let second_branch = Either::Right(statements.secret_b);
+    for witness in [first_branch, second_branch] {
+        assert!(prove_and_verify::<OrOfAnds, _>(&instance, &witness, rng).is_ok());
+    }
+
+    // Half of the first branch is not enough
+    let half_branch = Either::Left((statements.secret_a, (F::rand(rng), F::rand(rng))));
+    assert_eq!(
+        prove_and_verify::<OrOfAnds, _>(&instance, &half_branch, rng),
+        Err(SigmaError::VerificationFailed)
+    );
+}
+
+#[test]
+fn test_policies_have_distinct_labels() {
+    let rng = &mut thread_rng();
+    let statements = statements_setup(rng);
+    let (key_a, key_b, opening) = (statements.key_a, statements.key_b, statements.opening);
+
+    let labels = [
+        AndOfOrs::new(&((key_a, key_b), opening)).label(),
+        OrOfAnds::new(&((key_a, opening), key_b)).label(),
+        AndComposition::<Okamoto<G>, OrComposition<SchnorrDLOG<G>, SchnorrDLOG<G>>>::new(&(
+            opening,
+            (key_a, key_b),
+        ))
+        .label(),
+        OrComposition::<AndComposition<SchnorrDLOG<G>, Okamoto<G>>, Okamoto<G>>::new(&(
+            (key_a, opening),
+            opening,
+        ))
+        .label(),
+        AndComposition::<OrComposition<SchnorrDLOG<G>, SchnorrDLOG<G>>, SchnorrDLOG<G>>::new(&(
+            (key_a, key_b),
+            key_a,
+        ))
+        .label(),
+    ];
+
+    for (i, label) in labels.iter().enumerate() {
+        assert!(labels[i + 1..].iter().all(|other| other != label));
+    }
+}
@@ -0,0 +1,72 @@
+#![cfg(feature = "python")]
+
+use std::{env, fs, path::PathBuf, process::Command};
+
+/// Builds the extension module with the `python` feature, and returns the directory holding it under the name
+/// Python imports it by. Integration tests do not rebuild the cdylib, so it is built by a nested cargo, in a target
+/// directory of its own to stay off the lock of the running build. `PYO3_BUILD_EXTENSION_MODULE` leaves libpython
+/// to the interpreter loading the module
+fn build_module() -> PathBuf {
+    let manifest_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    let target_dir = manifest_dir.join("target").join("python");
+    let status = Command::new(env!("CARGO"))
+        .args(["build", "--lib", "--features", "python", "--target-dir"])
+        .arg(&target_dir)
+        .current_dir(&manifest_dir)
+        .env("PYO3_BUILD_EXTENSION_MODULE", "1")
+        .status()
+        .unwrap();
+    assert!(status.success(), "building the library failed");
+
+    let library = target_dir.join("debug").join(format!(
+        "{}sigma_protocol_standard{}",
+        env::consts::DLL_PREFIX,
+        env::consts::DLL_SUFFIX
+    ));
+    let module_dir = target_dir.join("module");
+    fs::create_dir_all(&module_dir).unwrap();
+    let extension = if cfg!(windows) { "pyd" } else { "so" };
+    fs::copy(
+        library,
+        module_dir.join(format!("sigma_protocol_standard.{extension}")),
+    )
+    .unwrap();
+    module_dir
+}
+
+/// Runs the tests of the Python bindings against a fresh build of the extension module, with pytest when it is
+/// installed and as a script otherwise, and skips without `python3`
+#[test]
+fn test_python_bindings() {
+    let python = env::var("PYTHON").unwrap_or_else(|_| "python3".into());
+    if Command::new(&python).arg("--version").output().is_err() {
+        eprintln!("skipped: {python} cannot run");
+        return;
+    }
+
+    let module_dir = build_module();
+    let tests = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("python")
+        .join("test_sigma_protocol_standard.py");
+    let pytest = Command::new(&python)
+        .args(["-c", "import pytest"])
+        .output()
+        .is_ok_and(|output| output.status.success());
+    let mut command = Command::new(&python);
+    if pytest {
+        command.args(["-m", "pytest", "-p", "no:cacheprovider"]);
+    }
+    let output = command
+        .arg(tests)
+        .current_dir(&module_dir)
+        .env("PYTHONPATH", &module_dir)
+        .output()
+        .unwrap();
+
+    assert!(
+        output.status.success(),
+        "{}{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
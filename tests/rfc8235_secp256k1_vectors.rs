@@ -0,0 +1,86 @@
+#![cfg(feature = "secp256k1")]
+
+mod vectors;
+
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use sigma_protocol_standard::{
+    protocols::SchnorrInstance,
+    rfc8235::{Rfc8235Proof, Rfc8235Schnorr},
+    secp256k1::{Secp256k1Element, Secp256k1Scalar},
+    SigmaError, SigmaGroup,
+};
+
+use vectors::{load_rfc8235_vectors, Rfc8235Vector, RFC8235_SECP256K1};
+
+type Schnorr = Rfc8235Schnorr<Secp256k1Element, sha2::Sha256>;
+
+fn serialize<T: CanonicalSerialize>(value: &T) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    value.serialize(&mut bytes).unwrap();
+    bytes
+}
+
+fn nizk(vector: &Rfc8235Vector, user_id: &[u8]) -> Schnorr {
+    let public_key = Secp256k1Element::deserialize(&vector.public_key[..]).unwrap();
+    let other_info: Vec<_> = vector.other_info.iter().map(Vec::as_slice).collect();
+    Schnorr::new(
+        &SchnorrInstance::new(Secp256k1Element::generator(), public_key),
+        user_id,
+    )
+    .unwrap()
+    .with_other_info(&other_info)
+}
+
+#[test]
+fn test_rfc8235_secp256k1_vectors() {
+    let vectors = load_rfc8235_vectors(RFC8235_SECP256K1);
+    assert_eq!(vectors.len(), 4);
+
+    for vector in vectors {
+        let witness = Secp256k1Scalar::deserialize(&vector.witness[..]).unwrap();
+        let nonce = Secp256k1Scalar::deserialize(&vector.nonce[..]).unwrap();
+        assert_eq!(
+            serialize(&Secp256k1Element::generator().scalar_mul(&witness)),
+            vector.public_key
+        );
+
+        // Proving reproduces the transcript of the independent implementation
+        let nizk = nizk(&vector, &vector.user_id);
+        let proof = nizk.prove_with_nonce(&witness, nonce);
+        assert_eq!(serialize(&proof.commitment()), vector.commitment);
+        assert_eq!(
+            serialize(&nizk.challenge(&proof.commitment())),
+            vector.challenge
+        );
+        assert_eq!(serialize(&proof.response()), vector.response);
+
+        // And its proof verifies
+        let proof = Rfc8235Proof::new(
+            Secp256k1Element::deserialize(&vector.commitment[..]).unwrap(),
+            Secp256k1Scalar::deserialize(&vector.response[..]).unwrap(),
+        );
+        assert!(nizk.verify(&proof).is_ok());
+    }
+}
+
+#[test]
+fn test_rfc8235_secp256k1_vectors_reject_other_user_id() {
+    for vector in load_rfc8235_vectors(RFC8235_SECP256K1) {
+        let proof = Rfc8235Proof::new(
+            Secp256k1Element::deserialize(&vector.commitment[..]).unwrap(),
+            Secp256k1Scalar::deserialize(&vector.response[..]).unwrap(),
+        );
+
+        let mut longer = vector.user_id.clone();
+        longer.push(0);
+        let empty: &[u8] = &[];
+        for user_id in [&longer[..], b"mallory", empty] {
+            if user_id != &vector.user_id[..] {
+                assert_eq!(
+                    nizk(&vector, user_id).verify(&proof),
+                    Err(SigmaError::VerificationFailed)
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,50 @@
+#![cfg(feature = "secp256k1")]
+
+mod vectors;
+
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use sigma_protocol_standard::{
+    protocols::{SchnorrDLOG, SchnorrInstance},
+    secp256k1::{Secp256k1Element, Secp256k1Scalar},
+    Challenge, ChallengeExpansion, SigmaGroup, SigmaProtocol,
+};
+
+use vectors::{load_schnorr_vectors, SCHNORR_SECP256K1};
+
+fn serialize<T: CanonicalSerialize>(value: &T) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    value.serialize(&mut bytes).unwrap();
+    bytes
+}
+
+#[test]
+fn test_schnorr_secp256k1_vectors() {
+    let generator = Secp256k1Element::generator();
+    let vectors = load_schnorr_vectors(SCHNORR_SECP256K1);
+    assert!(!vectors.is_empty());
+
+    for vector in vectors {
+        let witness = Secp256k1Scalar::deserialize(&vector.witness[..]).unwrap();
+        let nonce = Secp256k1Scalar::deserialize(&vector.nonce[..]).unwrap();
+        let claim = Secp256k1Element::deserialize(&vector.claim[..]).unwrap();
+        let mut challenge: Challenge = Default::default();
+        challenge.copy_from_slice(&vector.challenge);
+        let scalar = SchnorrDLOG::<Secp256k1Element>::challenge_from_bytes(
+            &challenge,
+            ChallengeExpansion::default(),
+        )
+        .unwrap();
+        let protocol = SchnorrDLOG::new(&SchnorrInstance::new(generator, claim));
+
+        assert_eq!(serialize(&generator.scalar_mul(&witness)), vector.claim);
+
+        let (commitment, state) = protocol.prover_commit_with_nonce(&witness, nonce);
+        let response = protocol.prover_response(&state, &scalar).unwrap();
+        assert_eq!(serialize(&commitment), vector.commitment);
+        assert_eq!(serialize(&response), vector.response);
+
+        let commitment = Secp256k1Element::deserialize(&vector.commitment[..]).unwrap();
+        let response = Secp256k1Scalar::deserialize(&vector.response[..]).unwrap();
+        assert!(protocol.verifier(&commitment, &scalar, &response).is_ok());
+    }
+}
@@ -0,0 +1,151 @@
+#![cfg(feature = "cli")]
+
+//! Drives the `sigma-cli` binary over files in a temporary directory, checking its outputs and exit codes.
+
+use std::fs;
+
+use assert_cmd::{assert::Assert, Command};
+use tempfile::{tempdir, TempDir};
+
+fn sigma_cli(args: &[&str]) -> Assert {
+    Command::cargo_bin("sigma-cli").unwrap().args(args).assert()
+}
+
+/// The path of `file` in `dir`, as passed to the binary
+fn path(dir: &TempDir, file: &str) -> String {
+    dir.path().join(file).to_str().unwrap().to_string()
+}
+
+/// Generates a key pair in `dir` and proves `message` with `options`
+fn keygen_and_prove(dir: &TempDir, message: &[u8], options: &[&str]) {
+    let (witness, instance) = (path(dir, "witness"), path(dir, "instance"));
+    sigma_cli(&["keygen", "--witness", &witness, "--instance", &instance]).code(0);
+    assert_eq!(fs::read(&witness).unwrap().len(), 32);
+    assert_eq!(fs::read(&instance).unwrap().len(), 64);
+
+    prove(dir, message, options);
+}
+
+/// Proves `message` with `options` under the key pair already in `dir`
+fn prove(dir: &TempDir, message: &[u8], options: &[&str]) {
+    fs::write(path(dir, "message"), message).unwrap();
+    let (instance, witness) = (path(dir, "instance"), path(dir, "witness"));
+    let (message, proof) = (path(dir, "message"), path(dir, "proof"));
+    let args = [
+        &["prove", "--instance", &instance, "--witness", &witness][..],
+        &["--message", &message, "--out", &proof],
+        options,
+    ]
+    .concat();
+    sigma_cli(&args).code(0);
+    assert_eq!(fs::read(proof).unwrap().len(), 64);
+}
+
+fn verify(dir: &TempDir, proof: &str, options: &[&str]) -> Assert {
+    let instance = path(dir, "instance");
+    let message = path(dir, "message");
+    sigma_cli(
+        &[
+            &["verify", "--instance", &instance][..],
+            &["--proof", proof, "--message", &message],
+            options,
+        ]
+        .concat(),
+    )
+}
+
+#[test]
+fn test_prove_and_verify() {
+    let dir = tempdir().unwrap();
+    keygen_and_prove(&dir, b"message", &[]);
+    let proof = path(&dir, "proof");
+
+    verify(&dir, &proof, &[]).code(0).stdout("valid\n");
+
+    // The hash function and the context must be those of the prover
+    verify(&dir, &proof, &["--hash", "sha256"]).code(1);
+    verify(&dir, &proof, &["--context", "other"]).code(1);
+
+    fs::write(path(&dir, "message"), b"another message").unwrap();
+    verify(&dir, &proof, &[]).code(1);
+}
+
+#[test]
+fn test_options_select_the_hash_and_context() {
+    let dir = tempdir().unwrap();
+    let options = ["--hash", "sha256", "--context", "interop"];
+    keygen_and_prove(&dir, b"", &options);
+    let proof = path(&dir, "proof");
+
+    verify(&dir, &proof, &options).code(0);
+    verify(&dir, &proof, &[]).code(1);
+}
+
+#[test]
+fn test_exit_codes_distinguish_failures() {
+    let dir = tempdir().unwrap();
+    keygen_and_prove(&dir, b"message", &[]);
+    let proof = path(&dir, "proof");
+
+    // A proof that does not decode is invalid
+    fs::write(path(&dir, "garbage"), [0xff; 64]).unwrap();
+    verify(&dir, &path(&dir, "garbage"), &[]).code(1);
+
+    // A missing file is an I/O error
+    let missing = verify(&dir, &path(&dir, "missing"), &[]).code(3);
+    assert!(String::from_utf8_lossy(&missing.get_output().stderr).contains("missing"));
+
+    // A proof made with another hash is invalid
+    verify(&dir, &proof, &["--hash", "sha3-256"]).code(1);
+
+    // Unknown and missing options are usage errors
+    for options in [&["--hash", "md5"][..], &["--out", "x"]] {
+        verify(&dir, &proof, options).code(2);
+    }
+    sigma_cli(&["verify", "--instance", &path(&dir, "instance")]).code(2);
+    sigma_cli(&["unknown"]).code(2);
+    sigma_cli(&[]).code(2);
+
+    // An instance that does not decode is rejected input
+    fs::write(path(&dir, "instance"), [0xff; 64]).unwrap();
+    verify(&dir, &proof, &[]).code(4);
+}
+
+#[test]
+fn test_batch_verify_reports_every_proof() {
+    let dir = tempdir().unwrap();
+    keygen_and_prove(&dir, b"first", &[]);
+
+    let proofs = tempdir().unwrap();
+    let copy = |from: &str, to: &str| fs::copy(from, path(&proofs, to)).unwrap();
+    copy(&path(&dir, "proof"), "a.proof");
+    copy(&path(&dir, "message"), "a.msg");
+    fs::write(path(&proofs, "notes.txt"), b"not a proof").unwrap();
+
+    let batch_verify = || {
+        sigma_cli(&[
+            "batch-verify",
+            "--instance",
+            &path(&dir, "instance"),
+            "--dir",
+            proofs.path().to_str().unwrap(),
+        ])
+    };
+    batch_verify()
+        .code(0)
+        .stdout(format!("{}: valid\n", path(&proofs, "a.proof")));
+
+    prove(&dir, b"second", &[]);
+    copy(&path(&dir, "proof"), "b.proof");
+    fs::write(path(&proofs, "b.msg"), b"tampered").unwrap();
+
+    batch_verify().code(1).stdout(format!(
+        "{}: valid\n{}: invalid\n",
+        path(&proofs, "a.proof"),
+        path(&proofs, "b.proof")
+    ));
+
+    // A proof without its message is an I/O error, not an invalid proof
+    fs::remove_file(path(&proofs, "b.msg")).unwrap();
+    batch_verify().code(3);
+}
@@ -0,0 +1,127 @@
+//! Loader for the byte-level test vectors of this directory.
+//!
+//! Every value is hex encoded with the canonical serialization of its type, so the vectors can be checked by any
+//! implementation of the standard without depending on this crate.
+
+/// The Schnorr DLOG vectors over secp256k1, with the generator as base, generated by the independent implementation
+/// of `schnorr_secp256k1.py`
+#[allow(dead_code)]
+pub const SCHNORR_SECP256K1: &str = include_str!("schnorr_secp256k1.txt");
+
+/// A single Schnorr DLOG vector
+#[allow(dead_code)]
+#[derive(Debug)]
+pub struct SchnorrVector {
+    pub witness: Vec<u8>,
+    pub claim: Vec<u8>,
+    pub nonce: Vec<u8>,
+    pub commitment: Vec<u8>,
+    pub challenge: Vec<u8>,
+    pub response: Vec<u8>,
+}
+
+fn decode_hex(hex: &str) -> Vec<u8> {
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).expect("invalid hex in test vector"))
+        .collect()
+}
+
+/// The RFC 8235 Schnorr NIZK vectors over secp256k1 with SHA-256, generated by the independent implementation of
+/// `rfc8235_secp256k1.py`
+#[allow(dead_code)]
+pub const RFC8235_SECP256K1: &str = include_str!("rfc8235_secp256k1.txt");
+
+/// A single RFC 8235 vector
+#[allow(dead_code)]
+#[derive(Debug)]
+pub struct Rfc8235Vector {
+    pub user_id: Vec<u8>,
+    pub other_info: Vec<Vec<u8>>,
+    pub witness: Vec<u8>,
+    pub public_key: Vec<u8>,
+    pub nonce: Vec<u8>,
+    pub commitment: Vec<u8>,
+    pub challenge: Vec<u8>,
+    pub response: Vec<u8>,
+}
+
+/// The `key = hex` pairs of the blocks of `source`, one blank-line-separated block per vector, skipping comments.
+/// An empty value is an empty byte string
+fn blocks(source: &str) -> Vec<Vec<(&str, Vec<u8>)>> {
+    source
+        .split("\n\n")
+        .map(|block| {
+            block
+                .lines()
+                .filter(|line| !line.starts_with('#'))
+                .map(|line| {
+                    let (key, value) = line.split_once('=').expect("a `key = hex` line");
+                    (key.trim(), decode_hex(value.trim()))
+                })
+                .collect::<Vec<_>>()
+        })
+        .filter(|pairs| !pairs.is_empty())
+        .collect()
+}
+
+/// Parses the RFC 8235 vectors, in which `other_info` may repeat, once per subitem
+#[allow(dead_code)]
+pub fn load_rfc8235_vectors(source: &str) -> Vec<Rfc8235Vector> {
+    blocks(source)
+        .into_iter()
+        .map(|pairs| {
+            let field = |name: &str| {
+                pairs
+                    .iter()
+                    .find(|(key, _)| *key == name)
+                    .map(|(_, value)| value.clone())
+                    .unwrap_or_else(|| panic!("missing `{}` in test vector", name))
+            };
+
+            Rfc8235Vector {
+                user_id: field("user_id"),
+                other_info: pairs
+                    .iter()
+                    .filter(|(key, _)| *key == "other_info")
+                    .map(|(_, value)| value.clone())
+                    .collect(),
+                witness: field("witness"),
+                public_key: field("public_key"),
+                nonce: field("nonce"),
+                commitment: field("commitment"),
+                challenge: field("challenge"),
+                response: field("response"),
+            }
+        })
+        .collect()
+}
+
+/// Parses `key = hex` lines into vectors, one blank-line-separated block per vector. Lines starting with `#` are
+/// comments
+#[allow(dead_code)]
+pub fn load_schnorr_vectors(source: &str) -> Vec<SchnorrVector> {
+    source
+        .split("\n\n")
+        .filter(|block| block.lines().any(|line| !line.starts_with('#')))
+        .map(|block| {
+            let field = |name: &str| {
+                block
+                    .lines()
+                    .filter(|line| !line.starts_with('#'))
+                    .find_map(|line| line.strip_prefix(name)?.strip_prefix(" = "))
+                    .map(decode_hex)
+                    .unwrap_or_else(|| panic!("missing `{}` in test vector", name))
+            };
+
+            SchnorrVector {
+                witness: field("witness"),
+                claim: field("claim"),
+                nonce: field("nonce"),
+                commitment: field("commitment"),
+                challenge: field("challenge"),
+                response: field("response"),
+            }
+        })
+        .collect()
+}
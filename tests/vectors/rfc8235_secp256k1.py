@@ -0,0 +1,94 @@
+"""Independent implementation of the RFC 8235 Schnorr NIZK over secp256k1 with SHA-256, generating
+rfc8235_secp256k1.txt.
+
+It shares no code with the Rust crate: the curve arithmetic is textbook affine arithmetic over Python integers,
+following section 3 of RFC 8235. Run it with `python3 tests/vectors/rfc8235_secp256k1.py > tests/vectors/rfc8235_secp256k1.txt`.
+"""
+
+import hashlib
+
+P = 2**256 - 2**32 - 977
+N = 0xFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEBAAEDCE6AF48A03BBFD25E8CD0364141
+G = (
+    0x79BE667EF9DCBBAC55A06295CE870B07029BFCDB2DCE28D959F2815B16F81798,
+    0x483ADA7726A3C4655DA4FBFC0E1108A8FD17B448A68554199C47D08FFB10D4B8,
+)
+
+
+def add(p1, p2):
+    if p1 is None:
+        return p2
+    if p2 is None:
+        return p1
+    (x1, y1), (x2, y2) = p1, p2
+    if x1 == x2 and (y1 + y2) % P == 0:
+        return None
+    if p1 == p2:
+        slope = 3 * x1 * x1 * pow(2 * y1, -1, P) % P
+    else:
+        slope = (y2 - y1) * pow(x2 - x1, -1, P) % P
+    x3 = (slope * slope - x1 - x2) % P
+    return x3, (slope * (x1 - x3) - y1) % P
+
+
+def mul(k, point):
+    result = None
+    while k:
+        if k & 1:
+            result = add(result, point)
+        point = add(point, point)
+        k >>= 1
+    return result
+
+
+def uncompressed(point):
+    """The uncompressed SEC1 encoding, as hashed by RFC 8235 implementations such as EC J-PAKE"""
+    return b"\x04" + point[0].to_bytes(32, "big") + point[1].to_bytes(32, "big")
+
+
+def item(data):
+    """An item of the hash input, prefixed with its length as a 4-byte big-endian integer (RFC 8235, section 2.2)"""
+    return len(data).to_bytes(4, "big") + data
+
+
+def challenge(base, commitment, public_key, user_id, other_info):
+    data = item(uncompressed(base)) + item(uncompressed(commitment)) + item(uncompressed(public_key))
+    data += item(user_id) + b"".join(item(info) for info in other_info)
+    return int.from_bytes(hashlib.sha256(data).digest(), "big") % N
+
+
+def scalar(label, i):
+    return int.from_bytes(hashlib.sha256(b"rfc8235 vectors/" + label + bytes([i])).digest(), "big") % N
+
+
+VECTORS = [
+    (b"alice", []),
+    (b"bob", [b"session 1"]),
+    (b"", []),
+    (b"a user identifier longer than a single hash block, to cover multi-block hashing of the input", [b"", b"x"]),
+]
+
+print("# Schnorr NIZK of RFC 8235 over secp256k1 with SHA-256 and the standard generator as base.")
+print("# Generated by rfc8235_secp256k1.py, an independent Python implementation of the RFC.")
+print("# Points are uncompressed SEC1 in the hash and compressed SEC1 here, scalars are 32-byte big-endian.")
+print("# h = SHA-256(len || G || len || V || len || A || len || UserID [|| len || OtherInfo]...) mod n, r = v - a * h")
+for i, (user_id, other_info) in enumerate(VECTORS):
+    a, v = scalar(b"witness", i), scalar(b"nonce", i)
+    public_key, commitment = mul(a, G), mul(v, G)
+    h = challenge(G, commitment, public_key, user_id, other_info)
+    r = (v - a * h) % N
+    assert add(mul(r, G), mul(h, public_key)) == commitment
+
+    def compressed(point):
+        return bytes([2 + (point[1] & 1)]) + point[0].to_bytes(32, "big")
+
+    print()
+    print(f"user_id = {user_id.hex()}")
+    for info in other_info:
+        print(f"other_info = {info.hex()}")
+    print(f"witness = {a:064x}")
+    print(f"public_key = {compressed(public_key).hex()}")
+    print(f"nonce = {v:064x}")
+    print(f"commitment = {compressed(commitment).hex()}")
+    print(f"challenge = {h:064x}")
+    print(f"response = {r:064x}")
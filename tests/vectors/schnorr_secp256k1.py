@@ -0,0 +1,86 @@
+"""Independent implementation of Schnorr DLOG over secp256k1 as specified by the standard, generating
+schnorr_secp256k1.txt.
+
+It shares no code with the Rust crate: the curve arithmetic is textbook affine arithmetic over Python integers, and
+the challenge scalar is computed from the definition, the blocks Blake2b(DOMSEP || i || challenge) for a one-byte
+counter i, truncated to 64 bytes, read as a big-endian integer and reduced modulo the group order. Run it with
+`python3 tests/vectors/schnorr_secp256k1.py > tests/vectors/schnorr_secp256k1.txt`.
+"""
+
+import hashlib
+
+P = 2**256 - 2**32 - 977
+N = 0xFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEBAAEDCE6AF48A03BBFD25E8CD0364141
+G = (
+    0x79BE667EF9DCBBAC55A06295CE870B07029BFCDB2DCE28D959F2815B16F81798,
+    0x483ADA7726A3C4655DA4FBFC0E1108A8FD17B448A68554199C47D08FFB10D4B8,
+)
+DOMSEP = b"zkpstd/sigma/0.1"
+
+
+def add(p1, p2):
+    if p1 is None:
+        return p2
+    if p2 is None:
+        return p1
+    (x1, y1), (x2, y2) = p1, p2
+    if x1 == x2 and (y1 + y2) % P == 0:
+        return None
+    if p1 == p2:
+        slope = 3 * x1 * x1 * pow(2 * y1, -1, P) % P
+    else:
+        slope = (y2 - y1) * pow(x2 - x1, -1, P) % P
+    x3 = (slope * slope - x1 - x2) % P
+    return x3, (slope * (x1 - x3) - y1) % P
+
+
+def mul(k, point):
+    result = None
+    while k:
+        if k & 1:
+            result = add(result, point)
+        point = add(point, point)
+        k >>= 1
+    return result
+
+
+def compressed(point):
+    """The compressed SEC1 encoding"""
+    return bytes([2 + (point[1] & 1)]) + point[0].to_bytes(32, "big")
+
+
+def expand(challenge, length):
+    """The blocks Blake2b(DOMSEP || i || challenge) for a one-byte counter i, truncated to `length` bytes"""
+    blocks = (hashlib.blake2b(DOMSEP + bytes([i]) + challenge).digest() for i in range((length + 63) // 64))
+    return b"".join(blocks)[:length]
+
+
+def challenge_scalar(challenge):
+    return int.from_bytes(expand(challenge, 64), "big") % N
+
+
+def derive(label, i):
+    return hashlib.sha256(b"schnorr vectors/" + label + bytes([i])).digest()
+
+
+print("# Schnorr DLOG over secp256k1 with the standard generator as base.")
+print("# Generated by schnorr_secp256k1.py, an independent Python implementation of the standard.")
+print("# Scalars are 32-byte big-endian, points are 33-byte compressed SEC1.")
+print("# The challenge scalar is Blake2b(DOMSEP || 0 || challenge), the 64-byte block of the expansion,")
+print("# read as a 512-bit big-endian integer and reduced modulo the group order. response = nonce - c * witness")
+for i in range(4):
+    x = int.from_bytes(derive(b"witness", i), "big") % N
+    k = int.from_bytes(derive(b"nonce", i), "big") % N
+    challenge = derive(b"challenge", i)
+    claim, commitment = mul(x, G), mul(k, G)
+    c = challenge_scalar(challenge)
+    response = (k - c * x) % N
+    assert add(mul(response, G), mul(c, claim)) == commitment
+
+    print()
+    print(f"witness = {x:064x}")
+    print(f"claim = {compressed(claim).hex()}")
+    print(f"nonce = {k:064x}")
+    print(f"commitment = {compressed(commitment).hex()}")
+    print(f"challenge = {challenge.hex()}")
+    print(f"response = {response:064x}")
@@ -0,0 +1,56 @@
+//! Generates zkp_dlog.txt: proofs of the `dlog` example of the `define_proof!` macro of the dalek `zkp` crate,
+//! made by `zkp` 0.8 itself. Run it with
+//! `cargo run --manifest-path tests/vectors/zkp_dlog/Cargo.toml > tests/vectors/zkp_dlog.txt`.
+//!
+//! The nonces of `zkp` are drawn from the OS rng, so every run prints other commitments and responses for the
+//! same statements.
+#![allow(non_snake_case)]
+
+#[macro_use]
+extern crate zkp;
+
+use curve25519_dalek::{
+    constants::{RISTRETTO_BASEPOINT_COMPRESSED, RISTRETTO_BASEPOINT_POINT},
+    scalar::Scalar,
+};
+use zkp::Transcript;
+
+define_proof! {dlog, "DLog", (x), (A), (G) : A = (x * G) }
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+fn main() {
+    println!("# Compact proofs of the `dlog` example of `define_proof!` of the dalek `zkp` crate, made by zkp 0.8.");
+    println!("# Generated by zkp_dlog/main.rs: define_proof! {{dlog, \"DLog\", (x), (A), (G) : A = (x * G) }}.");
+    println!("# The transcript is Transcript::new(b\"DLogTest\"), G is the ristretto255 basepoint, the proof is the");
+    println!("# bincode encoding of dlog::CompactProof and scalars are 32-byte little-endian.");
+    for seed in [89327492234u64, 1, 0x5eed] {
+        let x = Scalar::from(seed).invert();
+        let A = x * RISTRETTO_BASEPOINT_POINT;
+        let (proof, points) = dlog::prove_compact(
+            &mut Transcript::new(b"DLogTest"),
+            dlog::ProveAssignments {
+                x: &x,
+                A: &A,
+                G: &RISTRETTO_BASEPOINT_POINT,
+            },
+        );
+        assert!(dlog::verify_compact(
+            &proof,
+            &mut Transcript::new(b"DLogTest"),
+            dlog::VerifyAssignments {
+                A: &points.A,
+                G: &RISTRETTO_BASEPOINT_COMPRESSED,
+            },
+        )
+        .is_ok());
+
+        println!();
+        println!("witness = {}", hex(x.as_bytes()));
+        println!("generator = {}", hex(RISTRETTO_BASEPOINT_COMPRESSED.as_bytes()));
+        println!("claim = {}", hex(points.A.as_bytes()));
+        println!("proof = {}", hex(&bincode::serialize(&proof).unwrap()));
+    }
+}
@@ -0,0 +1,113 @@
+#![cfg(feature = "wasm")]
+
+use std::{env, path::PathBuf, process::Command};
+
+use ark_serialize::CanonicalSerialize;
+use rand::thread_rng;
+use sigma_protocol_standard::{
+    protocols::{SchnorrDLOG, SchnorrInstance},
+    ristretto::RistrettoElement,
+    BatchableProof, NIZK,
+};
+
+type Nizk = NIZK<SchnorrDLOG<RistrettoElement>, blake2::Blake2s>;
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+fn encode<T: CanonicalSerialize>(value: &T) -> String {
+    let mut bytes = Vec::new();
+    value.serialize(&mut bytes).unwrap();
+    hex(&bytes)
+}
+
+/// Builds the library for wasm32 with the `wasm` feature, and generates its nodejs bindings with `wasm_bindgen`
+/// into the returned directory. As for the Python bindings, the nested cargo has a target directory of its own
+fn build_bindings(wasm_bindgen: &str) -> PathBuf {
+    let manifest_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    let target_dir = manifest_dir.join("target").join("wasm");
+    let status = Command::new(env!("CARGO"))
+        .args([
+            "build",
+            "--lib",
+            "--target",
+            "wasm32-unknown-unknown",
+            "--features",
+            "wasm",
+            "--target-dir",
+        ])
+        .arg(&target_dir)
+        .current_dir(&manifest_dir)
+        .status()
+        .unwrap();
+    assert!(status.success(), "building the library for wasm32 failed");
+
+    let pkg = target_dir.join("pkg");
+    let status = Command::new(wasm_bindgen)
+        .args(["--target", "nodejs", "--out-dir"])
+        .arg(&pkg)
+        .arg(
+            target_dir
+                .join("wasm32-unknown-unknown")
+                .join("debug")
+                .join("sigma_protocol_standard.wasm"),
+        )
+        .status()
+        .unwrap();
+    assert!(status.success(), "generating the bindings failed");
+    pkg
+}
+
+/// Proves in wasm under node and verifies the proof natively, and the other way around. Needs `node`, the
+/// `wasm-bindgen` command of the version of the `wasm-bindgen` crate and the wasm32-unknown-unknown target, and fails
+/// without them, run with `cargo test --features wasm --test wasm -- --ignored`
+#[test]
+#[ignore]
+fn test_prove_in_wasm_and_verify_natively() {
+    let node = env::var("NODE").unwrap_or_else(|_| "node".into());
+    let wasm_bindgen = env::var("WASM_BINDGEN").unwrap_or_else(|_| "wasm-bindgen".into());
+    for command in [&node, &wasm_bindgen] {
+        if let Err(error) = Command::new(command).arg("--version").output() {
+            panic!("{command} cannot run: {error}");
+        }
+    }
+
+    let rng = &mut thread_rng();
+    let (instance, witness) = SchnorrInstance::<RistrettoElement>::generate(rng);
+    let nizk = Nizk::new(&instance, b"ctx");
+    let native_proof = nizk
+        .batchable_proof(&witness, Some(b"message"), rng)
+        .unwrap();
+
+    let pkg = build_bindings(&wasm_bindgen);
+    let script = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("wasm")
+        .join("prove.js");
+    let output = Command::new(&node)
+        .arg(script)
+        .arg(pkg)
+        .args([
+            encode(&instance),
+            encode(&witness),
+            hex(b"ctx"),
+            hex(b"message"),
+            native_proof.to_hex(),
+        ])
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let lines: Vec<_> = stdout.lines().collect();
+    assert_eq!(lines.len(), 2);
+    let proof: BatchableProof<SchnorrDLOG<RistrettoElement>> = lines[0].parse().unwrap();
+    assert!(nizk.batchable_verify(&proof, Some(b"message")).is_ok());
+    assert!(nizk.batchable_verify(&proof, Some(b"other")).is_err());
+    assert_eq!(lines[1], "true");
+}
@@ -0,0 +1,24 @@
+// Proves in wasm for tests/wasm.rs, through the nodejs bindings that wasm-bindgen generates into the directory
+// of the first argument. The remaining arguments are hex: the instance, the witness, the context, the message and
+// a proof made natively. Prints the hex of the proof made in wasm, then whether the native proof verifies.
+const [pkg, instance, witness, ctx, message, nativeProof] = process.argv.slice(2);
+const { schnorr_prove, schnorr_verify } = require(require("path").resolve(pkg, "sigma_protocol_standard.js"));
+
+const bytes = (hex) => Uint8Array.from(Buffer.from(hex, "hex"));
+
+const proof = schnorr_prove(bytes(instance), bytes(witness), bytes(ctx), bytes(message));
+console.log(Buffer.from(proof).toString("hex"));
+console.log(schnorr_verify(bytes(instance), bytes(nativeProof), bytes(ctx), bytes(message)));
+
+// Inputs that do not decode throw from schnorr_prove, and are rejected by schnorr_verify
+try {
+    schnorr_prove(bytes(instance).slice(1), bytes(witness), bytes(ctx), bytes(message));
+    process.exit(1);
+} catch (error) {
+    if (!error.message.includes("serialization")) {
+        throw error;
+    }
+}
+if (schnorr_verify(bytes(instance), proof.slice(1), bytes(ctx), bytes(message))) {
+    process.exit(1);
+}